@@ -258,6 +258,37 @@ EOF
 
 }
 
+create_public_client_with_device_authorization() {
+  CLIENT_NAME="$1"
+  CLIENT_REALM="${2:-$REALM}"
+
+  CLIENT_EXISTS=$(get_client_id "${CLIENT_NAME}" "${CLIENT_REALM}")
+  if [ -z "$CLIENT_EXISTS" ]; then
+    echo "Create public client ${CLIENT_NAME} with device authorization grant enabled in realm ${CLIENT_REALM}."
+    kcadm create clients -r "${CLIENT_REALM}" -f - << EOF
+      {
+        "enabled": true,
+        "protocol": "openid-connect",
+        "clientId": "$CLIENT_NAME",
+        "publicClient": true,
+        "standardFlowEnabled": false,
+        "directAccessGrantsEnabled": false,
+        "fullScopeAllowed": true,
+        "attributes": {
+          "oauth2.device.authorization.grant.enabled": true,
+          "access.token.lifespan": "300"
+        }
+      }
+EOF
+    echo "Public client ${CLIENT_NAME} created. Result code: $?"
+
+  else
+    echo "WARNING: Client ${CLIENT_NAME} already exists in realm ${CLIENT_REALM}."
+    return
+  fi
+
+}
+
 client_delete() {
   CLIENT_NAME="$1"
   CLIENT_REALM="${2:-$REALM}"
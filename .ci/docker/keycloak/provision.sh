@@ -61,6 +61,9 @@ main() {
   # Create keycloak client for opendut CLIs CLEO/EDGAR
   create_secret_client "opendut-cleo-client" "918642e0-4ec4-4ef5-8ae0-ba92de7da3f9" "$REALM_OPENDUT"
   create_secret_client "opendut-edgar-client" "c7d6ace0-b90f-471a-bb62-a4ecac4150f8" "$REALM_OPENDUT"
+  # Create keycloak client for users logging in to CLEO via the device authorization flow
+  create_public_client_with_device_authorization "opendut-cleo-login-client" "$REALM_OPENDUT"
+  add_client_scope_to_client "opendut-cleo-login-client" "$GROUP_SCOPE_NAME" "$REALM_OPENDUT"
 
   # Create keycloak client privileges for openDuT-CARL
   create_realm_role carl-admin "$REALM_OPENDUT"
@@ -4,10 +4,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     std::env::set_var("PROTOC", protobuf_src::protoc());
 
     let protos = [
+        "proto/opendut/carl/services/audit-log.proto",
         "proto/opendut/carl/services/cluster-manager.proto",
         "proto/opendut/carl/services/metadata-provider.proto",
         "proto/opendut/carl/services/peer-manager.proto",
         "proto/opendut/carl/services/peer-messaging-broker.proto",
+        "proto/opendut/carl/services/project-manager.proto",
+        "proto/opendut/carl/services/state-manager.proto",
+        "proto/opendut/carl/services/vpn-manager.proto",
     ];
 
     let includes = [
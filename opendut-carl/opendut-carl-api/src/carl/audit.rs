@@ -0,0 +1,366 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use opendut_types::project::ProjectId;
+
+#[cfg(any(feature = "client", feature = "wasm-client"))]
+pub use client::*;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ListAuditRecordsError {
+    #[error("An internal error occurred computing the list of audit records:\n  {cause}")]
+    Internal {
+        cause: String
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AuditRecordId(pub Uuid);
+
+impl AuditRecordId {
+    pub fn random() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl fmt::Display for AuditRecordId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The identity of the caller, which triggered an audited operation.
+///
+/// CARL does not verify the signature of bearer tokens, so identities claimed by a token are prefixed with [`AuditIdentity::UNVERIFIED_PREFIX`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AuditIdentity(pub String);
+
+impl AuditIdentity {
+    pub const ANONYMOUS: &'static str = "anonymous";
+    pub const UNVERIFIED_PREFIX: &'static str = "unverified:";
+
+    pub fn anonymous() -> Self {
+        Self(String::from(Self::ANONYMOUS))
+    }
+
+    pub fn unverified(claim: impl fmt::Display) -> Self {
+        Self(format!("{}{claim}", Self::UNVERIFIED_PREFIX))
+    }
+
+    /// Whether this is the given identity, with or without the prefix of unverified identities.
+    pub fn matches(&self, identity: &str) -> bool {
+        self.0 == identity
+            || self.0.strip_prefix(Self::UNVERIFIED_PREFIX) == Some(identity)
+    }
+}
+
+impl fmt::Display for AuditIdentity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AuditAction {
+    StorePeerDescriptor,
+    DeletePeerDescriptor,
+    GeneratePeerSetup,
+    CreateClusterConfiguration,
+    DeleteClusterConfiguration,
+    StoreClusterDeployment,
+    DeleteClusterDeployment,
+    CreateProject,
+    DeleteProject,
+    ImportState,
+    UpdatePeerDescriptor,
+}
+
+impl AuditAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AuditAction::StorePeerDescriptor => "StorePeerDescriptor",
+            AuditAction::DeletePeerDescriptor => "DeletePeerDescriptor",
+            AuditAction::GeneratePeerSetup => "GeneratePeerSetup",
+            AuditAction::CreateClusterConfiguration => "CreateClusterConfiguration",
+            AuditAction::DeleteClusterConfiguration => "DeleteClusterConfiguration",
+            AuditAction::StoreClusterDeployment => "StoreClusterDeployment",
+            AuditAction::DeleteClusterDeployment => "DeleteClusterDeployment",
+            AuditAction::CreateProject => "CreateProject",
+            AuditAction::DeleteProject => "DeleteProject",
+            AuditAction::ImportState => "ImportState",
+            AuditAction::UpdatePeerDescriptor => "UpdatePeerDescriptor",
+        }
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditOutcome {
+    Success,
+    Failure { cause: String },
+}
+
+impl fmt::Display for AuditOutcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditOutcome::Success => write!(f, "Success"),
+            AuditOutcome::Failure { cause } => write!(f, "Failure: {cause}"),
+        }
+    }
+}
+
+/// A single entry of the audit log, describing one mutating operation.
+///
+/// The state of the affected resource before and after the operation is kept as JSON,
+/// to be independent of the resource's type.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub id: AuditRecordId,
+    pub timestamp: DateTime<Utc>,
+    pub identity: AuditIdentity,
+    /// The address of the client, which sent the request, if known.
+    #[serde(default)]
+    pub client_address: Option<IpAddr>,
+    pub action: AuditAction,
+    pub resource_id: Uuid,
+    /// The project, in which the operation was performed. Only visible to callers, which select this project.
+    #[serde(default)]
+    pub project: ProjectId,
+    pub outcome: AuditOutcome,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+impl AuditRecord {
+    pub fn new(identity: AuditIdentity, action: AuditAction, resource_id: Uuid) -> Self {
+        Self {
+            id: AuditRecordId::random(),
+            timestamp: Utc::now(),
+            identity,
+            client_address: None,
+            action,
+            resource_id,
+            project: ProjectId::DEFAULT,
+            outcome: AuditOutcome::Success,
+            before: None,
+            after: None,
+        }
+    }
+
+    pub fn with_client_address(mut self, client_address: Option<IpAddr>) -> Self {
+        self.client_address = client_address;
+        self
+    }
+
+    pub fn with_project(mut self, project: ProjectId) -> Self {
+        self.project = project;
+        self
+    }
+
+    pub fn with_before<T: Serialize>(mut self, before: Option<&T>) -> Self {
+        self.before = before.and_then(|value| serde_json::to_value(value).ok());
+        self
+    }
+
+    pub fn with_after<T: Serialize>(mut self, after: Option<&T>) -> Self {
+        self.after = after.and_then(|value| serde_json::to_value(value).ok());
+        self
+    }
+
+    pub fn with_outcome<T, E: fmt::Display>(mut self, result: &Result<T, E>) -> Self {
+        self.outcome = match result {
+            Ok(_) => AuditOutcome::Success,
+            Err(error) => AuditOutcome::Failure { cause: error.to_string() },
+        };
+        self
+    }
+
+    /// Computes the differences between the state before and after the operation.
+    pub fn changes(&self) -> Vec<AuditChange> {
+        let mut changes = Vec::new();
+        collect_changes(String::new(), self.before.as_ref(), self.after.as_ref(), &mut changes);
+        changes
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditChange {
+    pub path: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+impl fmt::Display for AuditChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() { "." } else { &self.path };
+        match (&self.before, &self.after) {
+            (None, Some(after)) => write!(f, "+ {path}: {after}"),
+            (Some(before), None) => write!(f, "- {path}: {before}"),
+            (Some(before), Some(after)) => write!(f, "~ {path}: {before} -> {after}"),
+            (None, None) => write!(f, "  {path}"),
+        }
+    }
+}
+
+fn collect_changes(path: String, before: Option<&serde_json::Value>, after: Option<&serde_json::Value>, changes: &mut Vec<AuditChange>) {
+    use serde_json::Value;
+
+    match (before, after) {
+        (Some(Value::Object(before)), Some(Value::Object(after))) => {
+            let mut keys = before.keys().chain(after.keys()).collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                collect_changes(format!("{path}.{key}"), before.get(key), after.get(key), changes);
+            }
+        }
+        (before, after) if before != after => {
+            changes.push(AuditChange {
+                path,
+                before: before.cloned(),
+                after: after.cloned(),
+            });
+        }
+        _ => {}
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AuditRecordFilter {
+    pub resource_id: Option<Uuid>,
+    pub identity: Option<String>,
+    /// Set by CARL to the project selected by the caller, so that only the records of that project are listed.
+    pub project: Option<ProjectId>,
+    pub limit: Option<u32>,
+}
+
+impl AuditRecordFilter {
+    pub fn matches(&self, record: &AuditRecord) -> bool {
+        self.resource_id.map_or(true, |resource_id| record.resource_id == resource_id)
+            && self.identity.as_ref().map_or(true, |identity| record.identity.matches(identity))
+            && self.project.map_or(true, |project| record.project == project)
+    }
+}
+
+#[cfg(any(feature = "client", feature = "wasm-client"))]
+mod client {
+    use tonic::codegen::{Body, Bytes, http, InterceptedService, StdError};
+
+    use crate::carl::{ClientError, extract};
+    use crate::proto::services::audit_log;
+    use crate::proto::services::audit_log::audit_log_client::AuditLogClient;
+
+    use super::*;
+
+    #[derive(Clone, Debug)]
+    pub struct AuditLog<T> {
+        inner: AuditLogClient<T>,
+    }
+
+    impl<T> AuditLog<T>
+    where T: tonic::client::GrpcService<tonic::body::BoxBody>,
+          T::Error: Into<StdError>,
+          T::ResponseBody: Body<Data=Bytes> + Send + 'static,
+          <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: AuditLogClient<T>) -> AuditLog<T> {
+            AuditLog {
+                inner
+            }
+        }
+
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> AuditLog<InterceptedService<T, F>>
+            where
+                F: tonic::service::Interceptor,
+                T::ResponseBody: Default,
+                T: tonic::codegen::Service<
+                    http::Request<tonic::body::BoxBody>,
+                    Response = http::Response<
+                        <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                    >,
+                >,
+                <T as tonic::codegen::Service<
+                    http::Request<tonic::body::BoxBody>,
+                >>::Error: Into<StdError> + Send + Sync,
+        {
+            let inner_client = AuditLogClient::new(InterceptedService::new(inner, interceptor));
+            AuditLog {
+                inner: inner_client
+            }
+        }
+
+        pub async fn list_audit_records(&mut self, filter: AuditRecordFilter) -> Result<Vec<AuditRecord>, ClientError<ListAuditRecordsError>> {
+
+            let request = tonic::Request::new(audit_log::ListAuditRecordsRequest::from(filter));
+
+            let response = self.inner.list_audit_records(request).await?
+                .into_inner();
+
+            match extract!(response.reply)? {
+                audit_log::list_audit_records_response::Reply::Failure(failure) => {
+                    let error = ListAuditRecordsError::try_from(failure)?;
+                    Err(ClientError::UsageError(error))
+                }
+                audit_log::list_audit_records_response::Reply::Success(success) => {
+                    let records = success.records.into_iter()
+                        .map(AuditRecord::try_from)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok(records)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn should_filter_unverified_identities_with_and_without_prefix() -> Result<()> {
+        let record = AuditRecord::new(AuditIdentity::unverified("alice"), AuditAction::StorePeerDescriptor, Uuid::new_v4());
+        let filter = |identity: &str| AuditRecordFilter { identity: Some(String::from(identity)), ..Default::default() };
+
+        verify_that!(filter("alice").matches(&record), eq(true))?;
+        verify_that!(filter("unverified:alice").matches(&record), eq(true))?;
+        verify_that!(filter("bob").matches(&record), eq(false))
+    }
+
+    #[test]
+    fn should_compute_changes_between_before_and_after() -> Result<()> {
+        let mut record = AuditRecord::new(AuditIdentity::anonymous(), AuditAction::StorePeerDescriptor, Uuid::new_v4());
+        record.before = Some(json!({ "name": "alpha", "location": "Ulm", "topology": { "devices": [] } }));
+        record.after = Some(json!({ "name": "beta", "topology": { "devices": [] }, "executors": [] }));
+
+        let changes = record.changes();
+
+        verify_that!(changes, elements_are![
+            eq(AuditChange { path: String::from(".executors"), before: None, after: Some(json!([])) }),
+            eq(AuditChange { path: String::from(".location"), before: Some(json!("Ulm")), after: None }),
+            eq(AuditChange { path: String::from(".name"), before: Some(json!("alpha")), after: Some(json!("beta")) }),
+        ])
+    }
+
+    #[test]
+    fn should_report_no_changes_without_snapshots() -> Result<()> {
+        let record = AuditRecord::new(AuditIdentity::anonymous(), AuditAction::GeneratePeerSetup, Uuid::new_v4());
+
+        verify_that!(record.changes(), empty())
+    }
+}
@@ -8,7 +8,8 @@ use oauth2::{Scope as OAuthScope, ClientId as OAuthClientId, ClientSecret as OAu
 #[derive(Clone, Debug, Deserialize)]
 pub struct OidcIdentityProviderConfig {
     pub client_id: OAuthClientId,
-    pub client_secret: OAuthClientSecret,
+    /// Secret of a confidential client. Without a secret, tokens can only be obtained by a user logging in.
+    pub client_secret: Option<OAuthClientSecret>,
     pub issuer_url: Url,
     pub scopes: Vec<OAuthScope>,
 }
@@ -42,7 +43,8 @@ impl TryFrom<&Config> for OidcIdentityProviderConfig {
         let client_id = config.get_string(OidcIdentityProviderConfig::CLIENT_ID)
             .map_err(|error| anyhow!("Failed to find configuration for `{}`. {}", OidcIdentityProviderConfig::CLIENT_ID, error))?;
         let client_secret = config.get_string(OidcIdentityProviderConfig::CLIENT_SECRET)
-            .map_err(|error| anyhow!("Failed to find configuration for `{}`. {}", OidcIdentityProviderConfig::CLIENT_SECRET, error))?;
+            .ok()
+            .filter(|client_secret| !client_secret.is_empty());
         let issuer = config.get_string(OidcIdentityProviderConfig::ISSUER_URL)
             .map_err(|error| anyhow!("Failed to find configuration for `{}`. {}", OidcIdentityProviderConfig::ISSUER_URL, error))?;
         let issuer_url = Url::parse(&issuer)
@@ -54,7 +56,7 @@ impl TryFrom<&Config> for OidcIdentityProviderConfig {
 
             Ok(Self {
                 client_id: OAuthClientId::new(client_id),
-                client_secret: OAuthClientSecret::new(client_secret),
+                client_secret: client_secret.map(OAuthClientSecret::new),
                 issuer_url,
                 scopes,
             })
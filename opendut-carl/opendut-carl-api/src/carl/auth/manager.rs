@@ -1,23 +1,55 @@
+use std::ops::Not;
+use std::path::PathBuf;
+
 use chrono::{NaiveDateTime, Utc};
-use oauth2::{AccessToken, AuthUrl, Scope, TokenResponse, TokenUrl};
+use oauth2::{AccessToken, AuthUrl, DeviceAuthorizationUrl, RefreshToken, Scope, StandardDeviceAuthorizationResponse, TokenResponse, TokenUrl};
 use oauth2::basic::{BasicClient, BasicTokenResponse};
 use oauth2::reqwest::async_http_client;
+use opendut_util::fs::write_private_file;
+use serde::{Deserialize, Serialize};
 use tokio::sync::{RwLock, RwLockWriteGuard};
+use tracing::{debug, warn};
 
 use crate::carl::OidcIdentityProviderConfig;
 
 #[derive(Debug)]
 pub struct AuthenticationManager {
     client: BasicClient,
+    /// Identifies the identity provider and client, for which the tokens were issued.
+    issuer: TokenIssuer,
     scopes: Vec<Scope>,
+    /// Tokens are only issued to a user, who logged in via [`AuthenticationManager::login`], see [`AuthenticationManager::with_interactive_login`].
+    interactive: bool,
+    /// Without a client secret, tokens can only be obtained by logging in.
+    confidential: bool,
 
     state: RwLock<Option<TokenStorage>>,
+    /// File in which the token is kept between runs, so that it can be reused until it expires.
+    token_cache: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
 struct TokenStorage {
     pub access_token: AccessToken,
     pub expires_in: NaiveDateTime,
+    pub refresh_token: Option<RefreshToken>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct TokenIssuer {
+    issuer_url: String,
+    client_id: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedToken {
+    /// Tokens cached for another identity provider or client are not reused, e.g. when the cache is shared between contexts.
+    #[serde(default)]
+    issuer: TokenIssuer,
+    access_token: String,
+    expires_in: NaiveDateTime,
+    #[serde(default)]
+    refresh_token: Option<String>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -26,12 +58,38 @@ pub enum AuthError {
     FailedToGetToken { message: String, cause: String },
     #[error("ExpirationFieldMissing: {message}.")]
     ExpirationFieldMissing { message: String },
+    #[error("LoginRequired: {message}.")]
+    LoginRequired { message: String },
+    #[error("ClientSecretMissing: {message}.")]
+    ClientSecretMissing { message: String },
+    #[error("FailedToLogin: {message} cause: {cause}.")]
+    FailedToLogin { message: String, cause: String },
 }
 
 pub struct Token {
     pub value: String,
 }
 
+/// Instructions for the user to confirm a login in the browser.
+pub struct DeviceAuthorization {
+    pub verification_url: String,
+    /// URL, which already contains the user code, if the identity provider offers it.
+    pub verification_url_complete: Option<String>,
+    pub user_code: String,
+    pub expires_in: std::time::Duration,
+}
+
+impl From<&StandardDeviceAuthorizationResponse> for DeviceAuthorization {
+    fn from(response: &StandardDeviceAuthorizationResponse) -> Self {
+        Self {
+            verification_url: response.verification_uri().to_string(),
+            verification_url_complete: response.verification_uri_complete().map(|url| url.secret().to_owned()),
+            user_code: response.user_code().secret().to_owned(),
+            expires_in: response.expires_in(),
+        }
+    }
+}
+
 
 impl TryFrom<OidcIdentityProviderConfig> for AuthenticationManager {
     type Error = anyhow::Error;
@@ -41,23 +99,85 @@ impl TryFrom<OidcIdentityProviderConfig> for AuthenticationManager {
             .map_err(|error| anyhow::anyhow!("Failed to derive auth url from issuer url: {}", error))?;
         let token_endpoint = idp_config.issuer_url.join("protocol/openid-connect/token")
             .map_err(|error| anyhow::anyhow!("Failed to derive token url from issuer url: {}", error))?;
+        let device_authorization_endpoint = idp_config.issuer_url.join("protocol/openid-connect/auth/device")
+            .map_err(|error| anyhow::anyhow!("Failed to derive device authorization url from issuer url: {}", error))?;
 
+        let issuer = TokenIssuer {
+            issuer_url: idp_config.issuer_url.to_string(),
+            client_id: idp_config.client_id.to_string(),
+        };
+        let confidential = idp_config.client_secret.is_some();
         let client = BasicClient::new(
             idp_config.client_id,
-            Some(idp_config.client_secret),
+            idp_config.client_secret,
             AuthUrl::from_url(auth_endpoint),
             Some(TokenUrl::from_url(token_endpoint)),
-        );
+        ).set_device_authorization_url(DeviceAuthorizationUrl::from_url(device_authorization_endpoint));
 
         Ok(Self {
             client,
+            issuer,
             scopes: idp_config.scopes,
+            interactive: false,
+            confidential,
             state: Default::default(),
+            token_cache: None,
         })
     }
 }
 
 impl AuthenticationManager {
+    /// Obtains tokens only by letting the user log in, instead of using the credentials of the client.
+    pub fn with_interactive_login(mut self) -> Self {
+        self.interactive = true;
+        self
+    }
+
+    pub fn with_token_cache(mut self, path: PathBuf) -> Self {
+        self.token_cache = Some(path);
+        self
+    }
+
+    fn load_cached_token(&self) -> Option<TokenStorage> {
+        let path = self.token_cache.as_ref()?;
+        let content = std::fs::read_to_string(path).ok()?;
+        match serde_json::from_str::<CachedToken>(&content) {
+            Ok(cached) if cached.issuer != self.issuer => {
+                debug!("Ignoring authentication token in '{}', which was cached for another identity provider or client.", path.display());
+                None
+            }
+            Ok(cached) => {
+                debug!("Loaded cached authentication token from '{}'.", path.display());
+                Some(TokenStorage {
+                    access_token: AccessToken::new(cached.access_token),
+                    expires_in: cached.expires_in,
+                    refresh_token: cached.refresh_token.map(RefreshToken::new),
+                })
+            }
+            Err(cause) => {
+                warn!("Ignoring unreadable token cache '{}': {cause}", path.display());
+                None
+            }
+        }
+    }
+
+    fn store_cached_token(&self, token: &TokenStorage) {
+        let Some(path) = &self.token_cache else { return };
+
+        let cached = CachedToken {
+            issuer: self.issuer.clone(),
+            access_token: token.access_token.secret().to_owned(),
+            expires_in: token.expires_in,
+            refresh_token: token.refresh_token.as_ref().map(|refresh_token| refresh_token.secret().to_owned()),
+        };
+        let result = serde_json::to_string(&cached)
+            .map_err(|cause| cause.to_string())
+            .and_then(|content| write_private_file(path, &content).map_err(|cause| cause.to_string()));
+        if let Err(cause) = result {
+            warn!("Failed to cache authentication token in '{}': {cause}", path.display());
+        }
+    }
+
     fn update_storage_token(response: &BasicTokenResponse, state: &mut RwLockWriteGuard<Option<TokenStorage>>) -> Result<Token, AuthError> {
         let access_token = response.access_token().clone();
         let expires_in = match response.expires_in() {
@@ -66,14 +186,80 @@ impl AuthenticationManager {
             }
             Some(expiry_duration) => { Utc::now().naive_utc() + expiry_duration }
         };
+        let refresh_token = response.refresh_token().cloned()
+            .or_else(|| state.as_ref().and_then(|token| token.refresh_token.clone()));
         let _token_storage = state.insert(TokenStorage {
             access_token,
             expires_in,
+            refresh_token,
         });
         Ok(Token { value: response.access_token().secret().to_string() })
     }
 
+    async fn store_token(&self, response: &BasicTokenResponse) -> Result<Token, AuthError> {
+        let mut state = self.state.write().await;
+
+        let token = Self::update_storage_token(response, &mut state)?;
+        if let Some(token) = state.as_ref() {
+            self.store_cached_token(token);
+        }
+        Ok(token)
+    }
+
+    /// Logs in the user via the OAuth 2.0 device authorization grant.
+    /// The `prompt` is called with the instructions for the user, before waiting for the user to confirm the login.
+    pub async fn login<P>(&self, prompt: P) -> Result<Token, AuthError>
+    where
+        P: FnOnce(&DeviceAuthorization),
+    {
+        let details: StandardDeviceAuthorizationResponse = self.client.exchange_device_code()
+            .map_err(|error| AuthError::FailedToLogin {
+                message: "Device authorization is not configured!".to_string(),
+                cause: error.to_string(),
+            })?
+            .add_scopes(self.scopes.clone())
+            .request_async(async_http_client)
+            .await
+            .map_err(|error| AuthError::FailedToLogin {
+                message: "Requesting device authorization failed!".to_string(),
+                cause: error.to_string(),
+            })?;
+
+        prompt(&DeviceAuthorization::from(&details));
+
+        let response = self.client.exchange_device_access_token(&details)
+            .request_async(async_http_client, tokio::time::sleep, None)
+            .await
+            .map_err(|error| AuthError::FailedToLogin {
+                message: "Waiting for the login to be confirmed failed!".to_string(),
+                cause: error.to_string(),
+            })?;
+
+        self.store_token(&response).await
+    }
+
+    async fn refresh_token(&self, refresh_token: &RefreshToken) -> Result<Token, AuthError> {
+        let response = self.client.exchange_refresh_token(refresh_token)
+            .request_async(async_http_client)
+            .await
+            .map_err(|error|
+                AuthError::FailedToGetToken {
+                    message: "Refreshing authentication token failed!".to_string(),
+                    cause: error.to_string(),
+                }
+            )?;
+
+        self.store_token(&response).await
+    }
+
     async fn fetch_token(&self) -> Result<Token, AuthError> {
+        if self.interactive {
+            return Err(AuthError::LoginRequired { message: "No valid authentication token available. Please log in".to_string() });
+        }
+        if self.confidential.not() {
+            return Err(AuthError::ClientSecretMissing { message: "No client secret configured to fetch an authentication token with. Configure a client secret or enable the login of users".to_string() });
+        }
+
         let response = self.client.exchange_client_credentials()
             .add_scopes(self.scopes.clone())
             .request_async(async_http_client)
@@ -85,15 +271,14 @@ impl AuthenticationManager {
                 }
             )?;
 
-        let mut state = self.state.write().await;
-
-        Self::update_storage_token(&response, &mut state)?;
-
-        Ok(Token { value: response.access_token().secret().to_string() })
+        self.store_token(&response).await
     }
 
     pub async fn get_token(&self) -> Result<Token, AuthError> {
-        let token_storage = self.state.read().await.clone();
+        let token_storage = match self.state.read().await.clone() {
+            Some(token) => Some(token),
+            None => self.load_cached_token(),
+        };
         let access_token = match token_storage {
             None => {
                 self.fetch_token().await?
@@ -102,7 +287,16 @@ impl AuthenticationManager {
                 if Utc::now().naive_utc().lt(&token.expires_in) {
                     Token { value: token.access_token.secret().to_string() }
                 } else {
-                    self.fetch_token().await?
+                    match &token.refresh_token {
+                        Some(refresh_token) => match self.refresh_token(refresh_token).await {
+                            Ok(token) => token,
+                            Err(cause) => {
+                                debug!("Could not refresh authentication token: {cause}");
+                                self.fetch_token().await?
+                            }
+                        },
+                        None => self.fetch_token().await?,
+                    }
                 }
             }
         };
@@ -117,13 +311,15 @@ mod tests {
     use rstest::{fixture, rstest};
     use url::Url;
     use crate::carl::auth::auth_config::OidcIdentityProviderConfig;
-    use crate::carl::auth::manager::AuthenticationManager;
+    use crate::carl::auth::manager::{AuthError, AuthenticationManager, TokenStorage};
+    use chrono::Utc;
+    use oauth2::AccessToken;
 
     #[fixture]
     fn authentication_manager() -> AuthenticationManager {
         let idp_config: OidcIdentityProviderConfig = OidcIdentityProviderConfig {
             client_id: ClientId::new("opendut-edgar-client".to_string()),
-            client_secret: ClientSecret::new("c7d6ace0-b90f-471a-bb62-a4ecac4150f8".to_string()),
+            client_secret: Some(ClientSecret::new("c7d6ace0-b90f-471a-bb62-a4ecac4150f8".to_string())),
             issuer_url: Url::parse("http://localhost:8081/realms/opendut/").unwrap(),
             scopes: vec![],
         };
@@ -143,5 +339,78 @@ mod tests {
         assert!(token.value.len() > 100);
     }
 
+    #[rstest]
+    #[tokio::test]
+    async fn test_auth_manager_reuses_cached_token(authentication_manager: AuthenticationManager) {
+        let token_cache = std::env::temp_dir().join(format!("opendut-token-cache-{}.json", uuid::Uuid::new_v4()));
+        let authentication_manager = authentication_manager.with_token_cache(token_cache.clone());
+
+        authentication_manager.store_cached_token(&TokenStorage {
+            access_token: AccessToken::new(String::from("cached-token")),
+            expires_in: Utc::now().naive_utc() + chrono::Duration::minutes(5),
+            refresh_token: None,
+        });
+
+        let token = authentication_manager.get_token().await.unwrap();
+        assert_eq!(token.value, "cached-token");
+
+        std::fs::remove_file(token_cache).unwrap();
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_auth_manager_ignores_token_cached_for_another_client(authentication_manager: AuthenticationManager) {
+        let token_cache = std::env::temp_dir().join(format!("opendut-token-cache-{}.json", uuid::Uuid::new_v4()));
+
+        let other_idp_config = OidcIdentityProviderConfig {
+            client_id: ClientId::new("opendut-cleo-login-client".to_string()),
+            client_secret: None,
+            issuer_url: Url::parse("http://localhost:8081/realms/opendut/").unwrap(),
+            scopes: vec![],
+        };
+        let other_authentication_manager = AuthenticationManager::try_from(other_idp_config).unwrap()
+            .with_token_cache(token_cache.clone());
+        other_authentication_manager.store_cached_token(&TokenStorage {
+            access_token: AccessToken::new(String::from("cached-token")),
+            expires_in: Utc::now().naive_utc() + chrono::Duration::minutes(5),
+            refresh_token: None,
+        });
+
+        let authentication_manager = authentication_manager.with_token_cache(token_cache.clone());
+        assert!(authentication_manager.load_cached_token().is_none());
+        assert!(other_authentication_manager.load_cached_token().is_some());
+
+        std::fs::remove_file(token_cache).unwrap();
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_auth_manager_requires_login_with_interactive_login() {
+        let idp_config = OidcIdentityProviderConfig {
+            client_id: ClientId::new("opendut-cleo-login-client".to_string()),
+            client_secret: None,
+            issuer_url: Url::parse("http://localhost:8081/realms/opendut/").unwrap(),
+            scopes: vec![],
+        };
+        let authentication_manager = AuthenticationManager::try_from(idp_config).unwrap()
+            .with_interactive_login();
+
+        let result = authentication_manager.get_token().await;
+        assert!(matches!(result, Err(AuthError::LoginRequired { .. })));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_auth_manager_reports_missing_client_secret_without_interactive_login() {
+        let idp_config = OidcIdentityProviderConfig {
+            client_id: ClientId::new("opendut-edgar-client".to_string()),
+            client_secret: None,
+            issuer_url: Url::parse("http://localhost:8081/realms/opendut/").unwrap(),
+            scopes: vec![],
+        };
+        let authentication_manager = AuthenticationManager::try_from(idp_config).unwrap();
 
+        let result = authentication_manager.get_token().await;
+        assert!(matches!(result, Err(AuthError::ClientSecretMissing { .. })));
+    }
 }
@@ -8,21 +8,25 @@ use tonic::codegen::Service;
 use tonic::transport::{Body, Channel};
 use tracing::error;
 use crate::carl::auth::manager::AuthenticationManager;
+use crate::carl::project::{PROJECT_METADATA_KEY, ProjectSelection};
 
 #[derive(Clone, Debug)]
 pub struct AuthenticationService {
     inner: Channel,
     authentication_manager: Option<Arc<AuthenticationManager>>,
+    project: ProjectSelection,
 }
 
 impl AuthenticationService {
     pub fn new(
         inner: Channel,
         authentication_manager: Option<Arc<AuthenticationManager>>,
+        project: ProjectSelection,
     ) -> Self {
         AuthenticationService {
             inner,
             authentication_manager,
+            project,
         }
     }
 }
@@ -45,6 +49,13 @@ impl Service<Request<BoxBody>> for AuthenticationService {
         let mut inner = std::mem::replace(&mut self.inner, clone);
         let authentication_manager = self.authentication_manager.clone();
 
+        if let Some(project) = self.project.metadata_value() {
+            match HeaderValue::from_str(&project) {
+                Ok(project) => { request.headers_mut().insert(PROJECT_METADATA_KEY, project); }
+                Err(cause) => error!("Failed to set selected project '{project}': {cause}"),
+            }
+        }
+
         Box::pin(async move {
             let token_result = authentication_manager.as_ref()
                 .map(|manager| manager.get_token());
@@ -1,8 +1,10 @@
 #[cfg(any(feature = "client", feature = "wasm-client"))]
 pub use client::*;
 use opendut_types::cluster::{ClusterId, ClusterName};
+use opendut_types::cluster::operation::DeploymentOperationId;
 use opendut_types::cluster::state::ClusterState;
 use opendut_types::ShortName;
+use opendut_types::topology::DeviceId;
 
 #[derive(thiserror::Error, Debug)]
 pub enum CreateClusterConfigurationError {
@@ -13,6 +15,19 @@ pub enum CreateClusterConfigurationError {
         other_id: ClusterId,
         other_name: ClusterName
     },
+    #[error("ClusterConfigration '{cluster_name}' <{cluster_id}> could not be stored, because it was changed in the meantime! Expected revision {expected_revision}, but the configuration is at revision {actual_revision}.")]
+    RevisionConflict {
+        cluster_id: ClusterId,
+        cluster_name: ClusterName,
+        expected_revision: u64,
+        actual_revision: u64,
+    },
+    #[error("ClusterConfigration '{cluster_name}' <{cluster_id}> could not be created, because it exceeds a quota:\n  {cause}")]
+    QuotaExceeded {
+        cluster_id: ClusterId,
+        cluster_name: ClusterName,
+        cause: String,
+    },
     #[error("ClusterConfigration '{cluster_name}' <{cluster_id}> could not be created, due to internal errors:\n  {cause}")]
     Internal {
         cluster_id: ClusterId,
@@ -56,6 +71,10 @@ pub struct ListClusterConfigurationsError {
 
 #[derive(thiserror::Error, Debug)]
 pub enum StoreClusterDeploymentError {
+    #[error("ClusterDeployment for cluster <{cluster_id}> could not be stored, because a ClusterConfiguration with that id does not exist!")]
+    ClusterConfigurationNotFound {
+        cluster_id: ClusterId
+    },
     #[error("ClusterDeployment for cluster '{cluster_name}' <{cluster_id}> cannot be changed when cluster is in state '{}'! A cluster can be updated when: {}", actual_state.short_name(), ClusterState::short_names_joined(required_states))]
     IllegalClusterState {
         cluster_id: ClusterId,
@@ -63,6 +82,12 @@ pub enum StoreClusterDeploymentError {
         actual_state: ClusterState,
         required_states: Vec<ClusterState>,
     },
+    #[error("ClusterDeployment for cluster <{cluster_id}> could not be stored, because the devices {} are reserved by cluster <{other_cluster_id}> at an overlapping time!", devices.iter().map(|device| format!("<{device}>")).collect::<Vec<_>>().join(", "))]
+    ReservationConflict {
+        cluster_id: ClusterId,
+        other_cluster_id: ClusterId,
+        devices: Vec<DeviceId>,
+    },
     #[error("ClusterDeployment for cluster '{cluster_name}' <{cluster_id}> could not be changed, due to internal errors:\n  {cause}")]
     Internal {
         cluster_id: ClusterId,
@@ -98,12 +123,41 @@ pub struct ListClusterDeploymentsError {
     message: String,
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum GetDeploymentOperationError {
+    #[error("DeploymentOperation <{operation_id}> does not exist!")]
+    DeploymentOperationNotFound {
+        operation_id: DeploymentOperationId
+    },
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("{message}")]
+pub struct ListDeploymentOperationsError {
+    message: String,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ValidateClusterDeploymentError {
+    #[error("ClusterDeployment for cluster <{cluster_id}> could not be validated, because a ClusterConfiguration with that id does not exist!")]
+    ClusterConfigurationNotFound {
+        cluster_id: ClusterId
+    },
+    #[error("ClusterDeployment for cluster <{cluster_id}> could not be validated, due to internal errors:\n  {cause}")]
+    Internal {
+        cluster_id: ClusterId,
+        cause: String
+    }
+}
+
 
 #[cfg(any(feature = "client", feature = "wasm-client"))]
 mod client {
     use tonic::codegen::{Body, Bytes, http, InterceptedService, StdError};
 
     use opendut_types::cluster::{ClusterConfiguration, ClusterDeployment, ClusterId};
+    use opendut_types::cluster::operation::DeploymentOperation;
+    use opendut_types::cluster::validation::ClusterDeploymentValidation;
 
     use crate::carl::{ClientError, extract};
     use crate::proto::services::cluster_manager;
@@ -245,7 +299,9 @@ mod client {
             }
         }
 
-        pub async fn store_cluster_deployment(&mut self, deployment: ClusterDeployment) -> Result<ClusterId, ClientError<StoreClusterDeploymentError>> {
+        /// Stores the deployment and starts deploying the cluster in the background.
+        /// The returned operation can be used to follow the progress of the deployment.
+        pub async fn store_cluster_deployment(&mut self, deployment: ClusterDeployment) -> Result<DeploymentOperation, ClientError<StoreClusterDeploymentError>> {
 
             let request = tonic::Request::new(cluster_manager::StoreClusterDeploymentRequest {
                 cluster_deployment: Some(deployment.into()),
@@ -260,8 +316,57 @@ mod client {
                     Err(ClientError::UsageError(error))
                 }
                 cluster_manager::store_cluster_deployment_response::Reply::Success(success) => {
-                    let cluster_id = extract!(success.cluster_id)?;
-                    Ok(cluster_id)
+                    let operation = extract!(success.operation)?;
+                    Ok(operation)
+                }
+            }
+        }
+
+        pub async fn get_deployment_operation(&mut self, operation_id: DeploymentOperationId) -> Result<DeploymentOperation, ClientError<GetDeploymentOperationError>> {
+
+            let request = tonic::Request::new(cluster_manager::GetDeploymentOperationRequest {
+                id: Some(operation_id.into()),
+            });
+
+            let response = self.inner.get_deployment_operation(request).await?
+                .into_inner();
+
+            match extract!(response.reply)? {
+                cluster_manager::get_deployment_operation_response::Reply::Failure(failure) => {
+                    let error = GetDeploymentOperationError::try_from(failure)?;
+                    Err(ClientError::UsageError(error))
+                }
+                cluster_manager::get_deployment_operation_response::Reply::Success(success) => {
+                    let operation = extract!(success.operation)?;
+                    Ok(operation)
+                }
+            }
+        }
+
+        /// Lists the deployment operations, which CARL still knows about, optionally only those of the given cluster.
+        pub async fn list_deployment_operations(&mut self, cluster_id: Option<ClusterId>) -> Result<Vec<DeploymentOperation>, ListDeploymentOperationsError> {
+            let request = tonic::Request::new(cluster_manager::ListDeploymentOperationsRequest {
+                cluster_id: cluster_id.map(Into::into),
+            });
+
+            match self.inner.list_deployment_operations(request).await {
+                Ok(response) => {
+                    let result = response.into_inner().result
+                        .ok_or(ListDeploymentOperationsError { message: String::from("Response contains no result!") })?;
+                    match result {
+                        cluster_manager::list_deployment_operations_response::Result::Failure(_) => {
+                            Err(ListDeploymentOperationsError { message: String::from("Failed to list deployment operations!") })
+                        }
+                        cluster_manager::list_deployment_operations_response::Result::Success(cluster_manager::ListDeploymentOperationsSuccess { operations }) => {
+                            operations.into_iter()
+                                .map(DeploymentOperation::try_from)
+                                .collect::<Result<Vec<DeploymentOperation>, _>>()
+                                .map_err(|_| ListDeploymentOperationsError { message: String::from("Conversion failed for list of deployment operations!") })
+                        }
+                    }
+                },
+                Err(status) => {
+                    Err(ListDeploymentOperationsError { message: format!("gRPC failure: {status}") })
                 }
             }
         }
@@ -287,6 +392,28 @@ mod client {
             }
         }
 
+        /// Checks whether the cluster could be deployed, without deploying it.
+        pub async fn validate_cluster_deployment(&mut self, cluster_id: ClusterId) -> Result<ClusterDeploymentValidation, ClientError<ValidateClusterDeploymentError>> {
+
+            let request = tonic::Request::new(cluster_manager::ValidateClusterDeploymentRequest {
+                cluster_id: Some(cluster_id.into()),
+            });
+
+            let response = self.inner.validate_cluster_deployment(request).await?
+                .into_inner();
+
+            match extract!(response.reply)? {
+                cluster_manager::validate_cluster_deployment_response::Reply::Failure(failure) => {
+                    let error = ValidateClusterDeploymentError::try_from(failure)?;
+                    Err(ClientError::UsageError(error))
+                }
+                cluster_manager::validate_cluster_deployment_response::Reply::Success(success) => {
+                    let validation = extract!(success.validation)?;
+                    Ok(validation)
+                }
+            }
+        }
+
         pub async fn list_cluster_deployments(&mut self) -> Result<Vec<ClusterDeployment>, ListClusterDeploymentsError> {
             let request = tonic::Request::new(cluster_manager::ListClusterDeploymentsRequest {});
 
@@ -1,9 +1,13 @@
 use cfg_if::cfg_if;
 
+pub mod audit;
 pub mod broker;
 pub mod cluster;
 pub mod metadata;
 pub mod peer;
+pub mod project;
+pub mod state;
+pub mod vpn;
 pub mod auth;
 
 cfg_if! {
@@ -103,16 +107,25 @@ cfg_if! {
     if #[cfg(feature = "client")] {
         use tracing::debug;
 
+        use crate::carl::audit::AuditLog;
         use crate::carl::cluster::ClusterManager;
         use crate::carl::metadata::MetadataProvider;
         use crate::carl::peer::PeersRegistrar;
         use crate::carl::broker::PeerMessagingBroker;
+        use crate::carl::project::{ProjectManager, ProjectSelection};
+        use crate::carl::state::StateManager;
+        use crate::carl::vpn::VpnManager;
 
+        use crate::proto::services::audit_log::audit_log_client::AuditLogClient;
         use crate::proto::services::cluster_manager::cluster_manager_client::ClusterManagerClient;
         use crate::proto::services::metadata_provider::metadata_provider_client::MetadataProviderClient;
         use crate::proto::services::peer_manager::peer_manager_client::PeerManagerClient;
         use crate::proto::services::peer_messaging_broker::peer_messaging_broker_client::PeerMessagingBrokerClient;
+        use crate::proto::services::project_manager::project_manager_client::ProjectManagerClient;
+        use crate::proto::services::state_manager::state_manager_client::StateManagerClient;
+        use crate::proto::services::vpn_manager::vpn_manager_client::VpnManagerClient;
 
+        use std::ops::Not;
         use std::sync::Arc;
         use tower::ServiceBuilder;
         use crate::carl::auth::manager::AuthenticationManager;
@@ -121,14 +134,25 @@ cfg_if! {
 
         #[derive(Debug, Clone)]
         pub struct CarlClient {
+            pub audit: AuditLog<AuthenticationService>,
             pub broker: PeerMessagingBroker<AuthenticationService>,
             pub cluster: ClusterManager<AuthenticationService>,
             pub metadata: MetadataProvider<AuthenticationService>,
             pub peers: PeersRegistrar<AuthenticationService>,
+            pub projects: ProjectManager<AuthenticationService>,
+            pub project: ProjectSelection,
+            pub state: StateManager<AuthenticationService>,
+            pub vpn: VpnManager<AuthenticationService>,
+            authentication: Option<Arc<AuthenticationManager>>,
         }
 
         impl CarlClient {
 
+            /// The manager of the authentication tokens, if OIDC is enabled.
+            pub fn authentication(&self) -> Option<&AuthenticationManager> {
+                self.authentication.as_deref()
+            }
+
             pub fn create(host: impl Into<String>, port: u16, ca_cert_path: std::path::PathBuf,
                           domain_name_override: Option<String>, settings: &config::Config) -> Result<CarlClient, InitializationError> {
 
@@ -165,6 +189,15 @@ cfg_if! {
 
                     let auth_manager = AuthenticationManager::try_from(oidc_config)
                         .map_err(|cause| InitializationError::OidcConfiguration { message: String::from("Failed to initialize OIDC authentication manager"), cause: cause.into() })?;
+                    let auth_manager = if settings.get_bool("network.oidc.login.enabled").unwrap_or(false) {
+                        auth_manager.with_interactive_login()
+                    } else {
+                        auth_manager
+                    };
+                    let auth_manager = match settings.get_string("network.oidc.token.cache") {
+                        Ok(token_cache) if token_cache.is_empty().not() => auth_manager.with_token_cache(std::path::PathBuf::from(token_cache)),
+                        _ => auth_manager,
+                    };
 
                     Some(Arc::new(auth_manager))
                 } else {
@@ -175,15 +208,23 @@ cfg_if! {
                 debug!("Set up endpoint for connection to CARL at '{address}'.");
                 let channel = endpoint.connect_lazy();
 
+                let project = ProjectSelection::default();
+
                 let auth_svc = ServiceBuilder::new()
-                    .layer_fn(|channel| AuthenticationService::new(channel, auth_manager.clone()))
+                    .layer_fn(|channel| AuthenticationService::new(channel, auth_manager.clone(), Clone::clone(&project)))
                     .service(channel);
 
                 Ok(CarlClient {
+                    audit: AuditLog::new(AuditLogClient::new(Clone::clone(&auth_svc))),
                     broker: PeerMessagingBroker::new(PeerMessagingBrokerClient::new(Clone::clone(&auth_svc))),
                     cluster: ClusterManager::new(ClusterManagerClient::new(Clone::clone(&auth_svc))),
                     metadata: MetadataProvider::new(MetadataProviderClient::new(Clone::clone(&auth_svc))),
                     peers: PeersRegistrar::new(PeerManagerClient::new(Clone::clone(&auth_svc))),
+                    projects: ProjectManager::new(ProjectManagerClient::new(Clone::clone(&auth_svc))),
+                    project,
+                    state: StateManager::new(StateManagerClient::new(Clone::clone(&auth_svc))),
+                    vpn: VpnManager::new(VpnManagerClient::new(Clone::clone(&auth_svc))),
+                    authentication: auth_manager,
                 })
             }
         }
@@ -198,15 +239,19 @@ pub mod wasm {
     use tonic::service::Interceptor;
     use tonic::Status;
 
+    use crate::carl::audit::AuditLog;
     use crate::carl::broker::PeerMessagingBroker;
     use crate::carl::cluster::ClusterManager;
     use crate::carl::InitializationError;
     use crate::carl::metadata::MetadataProvider;
     use crate::carl::peer::PeersRegistrar;
+    use crate::carl::project::{PROJECT_METADATA_KEY, ProjectManager, ProjectSelection};
+    use crate::carl::vpn::VpnManager;
 
     #[derive(Clone)]
     pub struct AuthInterceptor {
-        pub(crate) auth: Option<Auth>
+        pub(crate) auth: Option<Auth>,
+        pub(crate) project: ProjectSelection,
     }
 
     impl Interceptor for AuthInterceptor {
@@ -230,17 +275,26 @@ pub mod wasm {
                 request.metadata_mut().insert("authorization", token.clone());
             }
 
+            if let Some(project) = self.project.metadata_value() {
+                let project: tonic::metadata::MetadataValue<_> = project.parse()
+                    .map_err(|_err| Status::invalid_argument("could not parse selected project"))?;
+                request.metadata_mut().insert(PROJECT_METADATA_KEY, project);
+            }
+
             Ok(request)
         }
     }
 
     #[derive(Debug, Clone)]
     pub struct CarlClient {
+        pub audit: AuditLog<InterceptedService<tonic_web_wasm_client::Client, AuthInterceptor>>,
         pub broker: PeerMessagingBroker<InterceptedService<tonic_web_wasm_client::Client, AuthInterceptor>>,
         pub cluster: ClusterManager<InterceptedService<tonic_web_wasm_client::Client, AuthInterceptor>>,
         pub metadata: MetadataProvider<InterceptedService<tonic_web_wasm_client::Client, AuthInterceptor>>,
         pub peers: PeersRegistrar<InterceptedService<tonic_web_wasm_client::Client, AuthInterceptor>>,
-
+        pub projects: ProjectManager<InterceptedService<tonic_web_wasm_client::Client, AuthInterceptor>>,
+        pub project: ProjectSelection,
+        pub vpn: VpnManager<InterceptedService<tonic_web_wasm_client::Client, AuthInterceptor>>,
     }
 
     #[derive(Clone, Debug)]
@@ -276,13 +330,18 @@ pub mod wasm {
             let port = url.port().unwrap_or(443_u16);
 
             let client = tonic_web_wasm_client::Client::new(format!("{}://{}:{}", scheme, host, port));
-            let auth_interceptor = AuthInterceptor { auth };
+            let project = ProjectSelection::default();
+            let auth_interceptor = AuthInterceptor { auth, project: Clone::clone(&project) };
 
             Ok(CarlClient {
+                audit: AuditLog::with_interceptor(Clone::clone(&client), Clone::clone(&auth_interceptor)),
                 broker: PeerMessagingBroker::with_interceptor(Clone::clone(&client), Clone::clone(&auth_interceptor)),
                 cluster: ClusterManager::with_interceptor(Clone::clone(&client), Clone::clone(&auth_interceptor)),
                 metadata: MetadataProvider::with_interceptor(Clone::clone(&client), Clone::clone(&auth_interceptor)),
                 peers: PeersRegistrar::with_interceptor(Clone::clone(&client), Clone::clone(&auth_interceptor)),
+                projects: ProjectManager::with_interceptor(Clone::clone(&client), Clone::clone(&auth_interceptor)),
+                project,
+                vpn: VpnManager::with_interceptor(Clone::clone(&client), Clone::clone(&auth_interceptor)),
             })
         }
     }
@@ -1,9 +1,12 @@
 #[cfg(any(feature = "client", feature = "wasm-client"))]
 pub use client::*;
+use opendut_types::cluster::ClusterId;
 use opendut_types::peer::{PeerId, PeerName};
+use opendut_types::peer::executor::ContainerName;
 use opendut_types::peer::state::PeerState;
 use opendut_types::ShortName;
-use opendut_types::topology::DeviceId;
+use opendut_types::topology::{DeviceId, DeviceName};
+use opendut_types::util::net::NetworkInterfaceName;
 
 #[derive(thiserror::Error, Debug)]
 pub enum StorePeerDescriptorError {
@@ -20,6 +23,19 @@ pub enum StorePeerDescriptorError {
         peer_name: PeerName,
         error: IllegalDevicesError
     },
+    #[error("Peer '{peer_name}' <{peer_id}> could not be stored, because it was changed in the meantime! Expected revision {expected_revision}, but the peer is at revision {actual_revision}.")]
+    RevisionConflict {
+        peer_id: PeerId,
+        peer_name: PeerName,
+        expected_revision: u64,
+        actual_revision: u64,
+    },
+    #[error("Peer '{peer_name}' <{peer_id}> could not be stored, because it exceeds a quota:\n  {cause}")]
+    QuotaExceeded {
+        peer_id: PeerId,
+        peer_name: PeerName,
+        cause: String,
+    },
     #[error("Peer '{peer_name}' <{peer_id}> could not be created, due to internal errors:\n  {cause}")]
     Internal {
         peer_id: PeerId,
@@ -28,6 +44,71 @@ pub enum StorePeerDescriptorError {
     }
 }
 
+/// Error of the operations, which add, update or remove a single device, network interface or executor of a peer.
+#[derive(thiserror::Error, Debug)]
+pub enum UpdatePeerError {
+    #[error("Peer <{peer_id}> could not be updated, because a peer with that id does not exist!")]
+    PeerNotFound {
+        peer_id: PeerId,
+    },
+    #[error("Device <{device_id}> of peer '{peer_name}' <{peer_id}> does not exist!")]
+    DeviceNotFound {
+        peer_id: PeerId,
+        peer_name: PeerName,
+        device_id: DeviceId,
+    },
+    #[error("Device <{device_id}> could not be stored on peer '{peer_name}' <{peer_id}>, because it already belongs to another peer!")]
+    DeviceAlreadyExists {
+        peer_id: PeerId,
+        peer_name: PeerName,
+        device_id: DeviceId,
+    },
+    #[error("Device <{device_id}> of peer '{peer_name}' <{peer_id}> cannot be changed, because it is used by the deployed cluster <{cluster_id}>!")]
+    DeviceInDeployedCluster {
+        peer_id: PeerId,
+        peer_name: PeerName,
+        device_id: DeviceId,
+        cluster_id: ClusterId,
+    },
+    #[error("Network interface '{interface_name}' of peer '{peer_name}' <{peer_id}> does not exist!")]
+    NetworkInterfaceNotFound {
+        peer_id: PeerId,
+        peer_name: PeerName,
+        interface_name: NetworkInterfaceName,
+    },
+    #[error("Network interface '{interface_name}' of peer '{peer_name}' <{peer_id}> cannot be deleted, because it is used by the devices: {}", devices.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    NetworkInterfaceInUse {
+        peer_id: PeerId,
+        peer_name: PeerName,
+        interface_name: NetworkInterfaceName,
+        devices: Vec<DeviceName>,
+    },
+    #[error("Peer '{peer_name}' <{peer_id}> has no container executor named '{container_name}'!")]
+    ExecutorNotFound {
+        peer_id: PeerId,
+        peer_name: PeerName,
+        container_name: ContainerName,
+    },
+    #[error("Peer '{peer_name}' <{peer_id}> has {count} container executors named '{container_name}', so it is ambiguous which one to delete!")]
+    ExecutorAmbiguous {
+        peer_id: PeerId,
+        peer_name: PeerName,
+        container_name: ContainerName,
+        count: usize,
+    },
+    #[error("Peer '{peer_name}' <{peer_id}> could not be updated, because it would exceed a quota:\n  {cause}")]
+    QuotaExceeded {
+        peer_id: PeerId,
+        peer_name: PeerName,
+        cause: String,
+    },
+    #[error("Peer <{peer_id}> could not be updated, due to internal errors:\n  {cause}")]
+    Internal {
+        peer_id: PeerId,
+        cause: String,
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum DeletePeerDescriptorError {
     #[error("Peer <{peer_id}> could not be deleted, because a peer with that id does not exist!")]
@@ -78,6 +159,27 @@ pub enum ListDevicesError {
     }
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum ListPeerStatesError {
+    #[error("An internal error occurred computing the states of the peers:\n  {cause}")]
+    Internal {
+        cause: String
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum FindDevicesError {
+    #[error("Invalid device query '{query}':\n  {cause}")]
+    InvalidQuery {
+        query: String,
+        cause: String,
+    },
+    #[error("An internal error occurred searching for devices:\n  {cause}")]
+    Internal {
+        cause: String
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 #[error("{message}")]
 pub struct  CreateSetupError {
@@ -94,14 +196,20 @@ pub enum IllegalDevicesError {
 
 #[cfg(any(feature = "client", feature = "wasm-client"))]
 mod client {
+    use std::collections::HashMap;
+
     use tonic::codegen::{Body, Bytes, http, InterceptedService, StdError};
     use tracing::error;
 
     use opendut_types::peer::{PeerDescriptor, PeerId, PeerSetup};
-    use opendut_types::topology::DeviceDescriptor;
+    use opendut_types::peer::executor::{ContainerName, ExecutorDescriptor};
+    use opendut_types::peer::state::PeerState;
+    use opendut_types::topology::{DeviceDescriptor, DeviceId};
+    use opendut_types::topology::query::DeviceQuery;
+    use opendut_types::util::net::{NetworkInterfaceDescriptor, NetworkInterfaceName};
 
     use crate::carl::{ClientError, extract};
-    use crate::carl::peer::{CreateSetupError, DeletePeerDescriptorError, GetPeerDescriptorError, ListDevicesError, ListPeerDescriptorsError, StorePeerDescriptorError};
+    use crate::carl::peer::{CreateSetupError, DeletePeerDescriptorError, FindDevicesError, GetPeerDescriptorError, ListDevicesError, ListPeerDescriptorsError, ListPeerStatesError, StorePeerDescriptorError, UpdatePeerError};
     use crate::proto::services::peer_manager;
     use crate::proto::services::peer_manager::peer_manager_client::PeerManagerClient;
 
@@ -259,6 +367,88 @@ mod client {
             }
         }
 
+        /// Adds the device to the peer or updates the device of the peer with the same ID.
+        pub async fn store_device(&mut self, peer_id: PeerId, device: DeviceDescriptor) -> Result<PeerDescriptor, ClientError<UpdatePeerError>> {
+
+            let request = tonic::Request::new(peer_manager::StoreDeviceRequest {
+                peer_id: Some(peer_id.into()),
+                device: Some(device.into()),
+            });
+
+            let response = self.inner.store_device(request).await?
+                .into_inner();
+
+            updated_peer(response)
+        }
+
+        pub async fn delete_device(&mut self, peer_id: PeerId, device_id: DeviceId) -> Result<PeerDescriptor, ClientError<UpdatePeerError>> {
+
+            let request = tonic::Request::new(peer_manager::DeleteDeviceRequest {
+                peer_id: Some(peer_id.into()),
+                device_id: Some(device_id.into()),
+            });
+
+            let response = self.inner.delete_device(request).await?
+                .into_inner();
+
+            updated_peer(response)
+        }
+
+        /// Adds the network interface to the peer or updates the network interface of the peer with the same name.
+        pub async fn store_network_interface(&mut self, peer_id: PeerId, network_interface: NetworkInterfaceDescriptor) -> Result<PeerDescriptor, ClientError<UpdatePeerError>> {
+
+            let request = tonic::Request::new(peer_manager::StoreNetworkInterfaceRequest {
+                peer_id: Some(peer_id.into()),
+                network_interface: Some(network_interface.into()),
+            });
+
+            let response = self.inner.store_network_interface(request).await?
+                .into_inner();
+
+            updated_peer(response)
+        }
+
+        pub async fn delete_network_interface(&mut self, peer_id: PeerId, interface_name: NetworkInterfaceName) -> Result<PeerDescriptor, ClientError<UpdatePeerError>> {
+
+            let request = tonic::Request::new(peer_manager::DeleteNetworkInterfaceRequest {
+                peer_id: Some(peer_id.into()),
+                interface_name: Some(interface_name.into()),
+            });
+
+            let response = self.inner.delete_network_interface(request).await?
+                .into_inner();
+
+            updated_peer(response)
+        }
+
+        /// Adds the executor to the peer or updates the container executor of the peer with the same container name.
+        pub async fn store_executor(&mut self, peer_id: PeerId, executor: ExecutorDescriptor) -> Result<PeerDescriptor, ClientError<UpdatePeerError>> {
+
+            let request = tonic::Request::new(peer_manager::StoreExecutorRequest {
+                peer_id: Some(peer_id.into()),
+                executor: Some(executor.into()),
+            });
+
+            let response = self.inner.store_executor(request).await?
+                .into_inner();
+
+            updated_peer(response)
+        }
+
+        /// Removes the container executor of the peer with the given container name.
+        pub async fn delete_executor(&mut self, peer_id: PeerId, container_name: ContainerName) -> Result<PeerDescriptor, ClientError<UpdatePeerError>> {
+
+            let request = tonic::Request::new(peer_manager::DeleteExecutorRequest {
+                peer_id: Some(peer_id.into()),
+                container_name: Some(container_name.into()),
+            });
+
+            let response = self.inner.delete_executor(request).await?
+                .into_inner();
+
+            updated_peer(response)
+        }
+
         pub async fn list_devices(&mut self) -> Result<Vec<DeviceDescriptor>, ListDevicesError> {
             let request = tonic::Request::new(peer_manager::ListDevicesRequest {});
 
@@ -275,5 +465,58 @@ mod client {
                 },
             }
         }
+
+        pub async fn list_peer_states(&mut self) -> Result<HashMap<PeerId, PeerState>, ListPeerStatesError> {
+            let request = tonic::Request::new(peer_manager::ListPeerStatesRequest {});
+
+            match self.inner.list_peer_states(request).await {
+                Ok(response) => {
+                    response.into_inner().peer_states
+                        .into_iter()
+                        .map(<(PeerId, PeerState)>::try_from)
+                        .collect::<Result<_, _>>()
+                        .map_err(|cause| ListPeerStatesError::Internal { cause: cause.to_string() })
+                },
+                Err(status) => {
+                    Err(ListPeerStatesError::Internal { cause: format!("gRPC failure: {status}") })
+                },
+            }
+        }
+
+        pub async fn find_devices(&mut self, query: &DeviceQuery) -> Result<Vec<DeviceDescriptor>, ClientError<FindDevicesError>> {
+
+            let request = tonic::Request::new(peer_manager::FindDevicesRequest {
+                query: query.to_string(),
+            });
+
+            let response = self.inner.find_devices(request).await?
+                .into_inner();
+
+            match extract!(response.reply)? {
+                peer_manager::find_devices_response::Reply::Failure(failure) => {
+                    let error = FindDevicesError::try_from(failure)?;
+                    Err(ClientError::UsageError(error))
+                }
+                peer_manager::find_devices_response::Reply::Success(success) => {
+                    Ok(success.devices.into_iter()
+                        .map(DeviceDescriptor::try_from)
+                        .collect::<Result<Vec<_>, _>>()?
+                    )
+                }
+            }
+        }
+    }
+
+    fn updated_peer(response: peer_manager::UpdatePeerResponse) -> Result<PeerDescriptor, ClientError<UpdatePeerError>> {
+        match extract!(response.reply)? {
+            peer_manager::update_peer_response::Reply::Failure(failure) => {
+                let error = UpdatePeerError::try_from(failure)?;
+                Err(ClientError::UsageError(error))
+            }
+            peer_manager::update_peer_response::Reply::Success(success) => {
+                let peer_descriptor = extract!(success.peer)?;
+                Ok(peer_descriptor)
+            }
+        }
     }
 }
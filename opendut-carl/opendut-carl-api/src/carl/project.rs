@@ -0,0 +1,202 @@
+#[cfg(any(feature = "client", feature = "wasm-client"))]
+pub use client::*;
+use opendut_types::project::{ProjectId, ProjectName};
+
+/// Name of the request metadata, which selects the project a request operates on.
+///
+/// Requests without this metadata operate on the default project.
+pub const PROJECT_METADATA_KEY: &str = "opendut-project";
+
+#[derive(thiserror::Error, Debug)]
+pub enum CreateProjectError {
+    #[error("Project '{project_name}' <{project_id}> could not be created, because a project with the same id or name already exists!")]
+    ProjectAlreadyExists {
+        project_id: ProjectId,
+        project_name: ProjectName,
+    },
+    #[error("Project '{project_name}' <{project_id}> could not be created, due to internal errors:\n  {cause}")]
+    Internal {
+        project_id: ProjectId,
+        project_name: ProjectName,
+        cause: String
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum DeleteProjectError {
+    #[error("Project <{project_id}> could not be deleted, because a project with that id does not exist!")]
+    ProjectNotFound {
+        project_id: ProjectId
+    },
+    #[error("Project '{project_name}' <{project_id}> could not be deleted, because it still contains peers or clusters!")]
+    ProjectNotEmpty {
+        project_id: ProjectId,
+        project_name: ProjectName,
+    },
+    #[error("The default project cannot be deleted!")]
+    DefaultProject,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("{message}")]
+pub struct ListProjectsError {
+    message: String,
+}
+
+#[cfg(any(feature = "client", feature = "wasm-client"))]
+mod client {
+    use std::sync::{Arc, RwLock};
+
+    use tonic::codegen::{Body, Bytes, http, InterceptedService, StdError};
+
+    use opendut_types::project::ProjectDescriptor;
+
+    use crate::carl::{ClientError, extract};
+    use crate::proto::services::project_manager;
+    use crate::proto::services::project_manager::project_manager_client::ProjectManagerClient;
+
+    use super::*;
+
+    /// The project, which requests of a [`CarlClient`](crate::carl::CarlClient) operate on.
+    ///
+    /// The selection is shared between all clones, so that it can be switched while the client is in use.
+    #[derive(Clone, Debug, Default)]
+    pub struct ProjectSelection {
+        inner: Arc<RwLock<Option<ProjectId>>>,
+    }
+
+    impl ProjectSelection {
+        pub fn select(&self, project_id: Option<ProjectId>) {
+            let mut selected = self.inner.write()
+                .expect("Lock of project selection should not be poisoned.");
+            *selected = project_id;
+        }
+
+        pub fn selected(&self) -> Option<ProjectId> {
+            *self.inner.read()
+                .expect("Lock of project selection should not be poisoned.")
+        }
+
+        pub(crate) fn metadata_value(&self) -> Option<String> {
+            self.selected()
+                .map(|project_id| project_id.to_string())
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct ProjectManager<T> {
+        inner: ProjectManagerClient<T>,
+    }
+
+    impl<T> ProjectManager<T>
+    where T: tonic::client::GrpcService<tonic::body::BoxBody>,
+          T::Error: Into<StdError>,
+          T::ResponseBody: Body<Data=Bytes> + Send + 'static,
+          <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: ProjectManagerClient<T>) -> ProjectManager<T> {
+            ProjectManager {
+                inner
+            }
+        }
+
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> ProjectManager<InterceptedService<T, F>>
+            where
+                F: tonic::service::Interceptor,
+                T::ResponseBody: Default,
+                T: tonic::codegen::Service<
+                    http::Request<tonic::body::BoxBody>,
+                    Response = http::Response<
+                        <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                    >,
+                >,
+                <T as tonic::codegen::Service<
+                    http::Request<tonic::body::BoxBody>,
+                >>::Error: Into<StdError> + Send + Sync,
+        {
+            let inner_client = ProjectManagerClient::new(InterceptedService::new(inner, interceptor));
+            ProjectManager {
+                inner: inner_client
+            }
+        }
+
+        pub async fn create_project(&mut self, project: ProjectDescriptor) -> Result<ProjectId, ClientError<CreateProjectError>> {
+
+            let request = tonic::Request::new(project_manager::CreateProjectRequest {
+                project: Some(project.into()),
+            });
+
+            let response = self.inner.create_project(request).await?
+                .into_inner();
+
+            match extract!(response.reply)? {
+                project_manager::create_project_response::Reply::Failure(failure) => {
+                    let error = CreateProjectError::try_from(failure)?;
+                    Err(ClientError::UsageError(error))
+                }
+                project_manager::create_project_response::Reply::Success(success) => {
+                    let project_id = extract!(success.project_id)?;
+                    Ok(project_id)
+                }
+            }
+        }
+
+        pub async fn delete_project(&mut self, project_id: ProjectId) -> Result<ProjectId, ClientError<DeleteProjectError>> {
+
+            let request = tonic::Request::new(project_manager::DeleteProjectRequest {
+                project_id: Some(project_id.into()),
+            });
+
+            let response = self.inner.delete_project(request).await?
+                .into_inner();
+
+            match extract!(response.reply)? {
+                project_manager::delete_project_response::Reply::Failure(failure) => {
+                    let error = DeleteProjectError::try_from(failure)?;
+                    Err(ClientError::UsageError(error))
+                }
+                project_manager::delete_project_response::Reply::Success(success) => {
+                    let project_id = extract!(success.project_id)?;
+                    Ok(project_id)
+                }
+            }
+        }
+
+        pub async fn list_projects(&mut self) -> Result<Vec<ProjectDescriptor>, ListProjectsError> {
+            let request = tonic::Request::new(project_manager::ListProjectsRequest {});
+
+            match self.inner.list_projects(request).await {
+                Ok(response) => {
+                    let reply = response.into_inner().reply
+                        .ok_or(ListProjectsError { message: String::from("Response contains no reply!") })?;
+                    match reply {
+                        project_manager::list_projects_response::Reply::Failure(_) => {
+                            Err(ListProjectsError { message: String::from("Failed to list projects!") })
+                        }
+                        project_manager::list_projects_response::Reply::Success(project_manager::ListProjectsSuccess { projects }) => {
+                            projects.into_iter()
+                                .map(ProjectDescriptor::try_from)
+                                .collect::<Result<Vec<_>, _>>()
+                                .map_err(|_| ListProjectsError { message: String::from("Conversion failed for list of projects!") })
+                        }
+                    }
+                },
+                Err(status) => {
+                    Err(ListProjectsError { message: format!("gRPC failure: {status}") })
+                }
+            }
+        }
+
+        /// Resolves the given name or id of a project to its id.
+        pub async fn resolve_project(&mut self, name_or_id: &str) -> Result<ProjectId, ListProjectsError> {
+            let projects = self.list_projects().await?;
+            projects.into_iter()
+                .find(|project| project.name.to_string() == name_or_id || project.id.to_string() == name_or_id)
+                .map(|project| project.id)
+                .ok_or_else(|| ListProjectsError { message: format!("Project '{name_or_id}' does not exist!") })
+        }
+    }
+}
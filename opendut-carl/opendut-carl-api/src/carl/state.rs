@@ -0,0 +1,297 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[cfg(any(feature = "client", feature = "wasm-client"))]
+pub use client::*;
+use opendut_types::cluster::{ClusterConfiguration, ClusterDeployment};
+use opendut_types::peer::PeerDescriptor;
+
+/// Version of the format of [`StateArchive`]s written by this version of openDuT.
+///
+/// Increment it whenever the format changes incompatibly and register a migration from the previous version in [`MIGRATIONS`].
+pub const STATE_ARCHIVE_VERSION: u32 = 1;
+
+/// Migrates the JSON representation of an archive from the version at its index plus one to the next version.
+type Migration = fn(serde_json::Value) -> Result<serde_json::Value, String>;
+
+const MIGRATIONS: &[Migration] = &[];
+
+/// The peers, clusters and deployments of a project, used to move them between CARL instances or to back them up.
+///
+/// The values of secret environment variables of executors are not part of an archive.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateArchive {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    #[serde(default)]
+    pub peers: Vec<PeerDescriptor>,
+    #[serde(default)]
+    pub clusters: Vec<ClusterConfiguration>,
+    #[serde(default)]
+    pub deployments: Vec<ClusterDeployment>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum StateArchiveError {
+    #[error("The archive does not specify the version of its format.")]
+    MissingVersion,
+    #[error("The archive has version {version}, but only versions up to {supported_version} are supported. Use a newer version of openDuT.")]
+    UnsupportedVersion { version: u32, supported_version: u32 },
+    #[error("Failed to migrate the archive from version {version}:\n  {cause}")]
+    MigrationFailed { version: u32, cause: String },
+    #[error("The archive is invalid:\n  {cause}")]
+    Invalid { cause: String },
+}
+
+impl StateArchive {
+    pub fn to_json(&self) -> Result<String, StateArchiveError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|cause| StateArchiveError::Invalid { cause: cause.to_string() })
+    }
+
+    /// Parses an archive and migrates it from the version it was written with to the current [`STATE_ARCHIVE_VERSION`].
+    pub fn from_json(json: &str) -> Result<Self, StateArchiveError> {
+        let mut archive: serde_json::Value = serde_json::from_str(json)
+            .map_err(|cause| StateArchiveError::Invalid { cause: cause.to_string() })?;
+
+        let version = archive.get("version")
+            .and_then(serde_json::Value::as_u64)
+            .and_then(|version| u32::try_from(version).ok())
+            .filter(|version| *version > 0)
+            .ok_or(StateArchiveError::MissingVersion)?;
+        if version > STATE_ARCHIVE_VERSION {
+            return Err(StateArchiveError::UnsupportedVersion { version, supported_version: STATE_ARCHIVE_VERSION });
+        }
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version as usize - 1) {
+            let version = index as u32 + 1;
+            archive = migration(archive)
+                .map_err(|cause| StateArchiveError::MigrationFailed { version, cause })?;
+            archive["version"] = serde_json::Value::from(version + 1);
+        }
+
+        serde_json::from_value(archive)
+            .map_err(|cause| StateArchiveError::Invalid { cause: cause.to_string() })
+    }
+}
+
+/// How to handle resources of an archive, whose ids already exist in CARL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportStrategy {
+    /// Keeps the existing resources.
+    Skip,
+    /// Replaces the existing resources.
+    Overwrite,
+    /// Imports the resources under new ids, updating all references to them.
+    RenameIds,
+}
+
+impl fmt::Display for ImportStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportStrategy::Skip => write!(f, "skip"),
+            ImportStrategy::Overwrite => write!(f, "overwrite"),
+            ImportStrategy::RenameIds => write!(f, "rename ids"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportedResourceKind {
+    Peer,
+    ClusterConfiguration,
+    ClusterDeployment,
+}
+
+impl fmt::Display for ImportedResourceKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportedResourceKind::Peer => write!(f, "Peer"),
+            ImportedResourceKind::ClusterConfiguration => write!(f, "ClusterConfiguration"),
+            ImportedResourceKind::ClusterDeployment => write!(f, "ClusterDeployment"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportOutcome {
+    Created,
+    Overwritten,
+    Skipped,
+    Renamed { original_id: Uuid },
+    Failed { cause: String },
+}
+
+impl fmt::Display for ImportOutcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportOutcome::Created => write!(f, "Created"),
+            ImportOutcome::Overwritten => write!(f, "Overwritten"),
+            ImportOutcome::Skipped => write!(f, "Skipped"),
+            ImportOutcome::Renamed { original_id } => write!(f, "Renamed from <{original_id}>"),
+            ImportOutcome::Failed { cause } => write!(f, "Failed: {cause}"),
+        }
+    }
+}
+
+/// The outcome of importing a single resource of an archive.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportedResource {
+    pub kind: ImportedResourceKind,
+    /// The id of the resource in CARL, which differs from the id in the archive, if the resource was renamed.
+    pub id: Uuid,
+    pub name: String,
+    pub outcome: ImportOutcome,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ExportStateError {
+    #[error("State could not be exported, due to internal errors:\n  {cause}")]
+    Internal {
+        cause: String
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ImportStateError {
+    #[error("State could not be imported, because the archive has version {version}, but CARL only supports version {supported_version}!")]
+    UnsupportedVersion {
+        version: u32,
+        supported_version: u32,
+    },
+    #[error("State could not be imported, due to internal errors:\n  {cause}")]
+    Internal {
+        cause: String
+    }
+}
+
+#[cfg(any(feature = "client", feature = "wasm-client"))]
+mod client {
+    use tonic::codegen::{Body, Bytes, http, InterceptedService, StdError};
+
+    use crate::carl::{ClientError, extract};
+    use crate::proto::services::state_manager;
+    use crate::proto::services::state_manager::state_manager_client::StateManagerClient;
+
+    use super::*;
+
+    #[derive(Clone, Debug)]
+    pub struct StateManager<T> {
+        inner: StateManagerClient<T>,
+    }
+
+    impl<T> StateManager<T>
+    where T: tonic::client::GrpcService<tonic::body::BoxBody>,
+          T::Error: Into<StdError>,
+          T::ResponseBody: Body<Data=Bytes> + Send + 'static,
+          <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: StateManagerClient<T>) -> StateManager<T> {
+            StateManager {
+                inner
+            }
+        }
+
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> StateManager<InterceptedService<T, F>>
+            where
+                F: tonic::service::Interceptor,
+                T::ResponseBody: Default,
+                T: tonic::codegen::Service<
+                    http::Request<tonic::body::BoxBody>,
+                    Response = http::Response<
+                        <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                    >,
+                >,
+                <T as tonic::codegen::Service<
+                    http::Request<tonic::body::BoxBody>,
+                >>::Error: Into<StdError> + Send + Sync,
+        {
+            let inner_client = StateManagerClient::new(InterceptedService::new(inner, interceptor));
+            StateManager {
+                inner: inner_client
+            }
+        }
+
+        pub async fn export_state(&mut self) -> Result<StateArchive, ClientError<ExportStateError>> {
+
+            let request = tonic::Request::new(state_manager::ExportStateRequest {});
+
+            let response = self.inner.export_state(request).await?
+                .into_inner();
+
+            match extract!(response.reply)? {
+                state_manager::export_state_response::Reply::Failure(failure) => {
+                    let error = ExportStateError::try_from(failure)?;
+                    Err(ClientError::UsageError(error))
+                }
+                state_manager::export_state_response::Reply::Success(success) => {
+                    let archive = extract!(success.archive)?;
+                    Ok(archive)
+                }
+            }
+        }
+
+        pub async fn import_state(&mut self, archive: StateArchive, strategy: ImportStrategy) -> Result<Vec<ImportedResource>, ClientError<ImportStateError>> {
+
+            let request = tonic::Request::new(state_manager::ImportStateRequest {
+                archive: Some(archive.into()),
+                strategy: Some(strategy.into()),
+            });
+
+            let response = self.inner.import_state(request).await?
+                .into_inner();
+
+            match extract!(response.reply)? {
+                state_manager::import_state_response::Reply::Failure(failure) => {
+                    let error = ImportStateError::try_from(failure)?;
+                    Err(ClientError::UsageError(error))
+                }
+                state_manager::import_state_response::Reply::Success(success) => {
+                    let resources = success.resources.into_iter()
+                        .map(ImportedResource::try_from)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok(resources)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn should_read_an_archive_of_the_current_version() -> Result<()> {
+        let archive = StateArchive {
+            version: STATE_ARCHIVE_VERSION,
+            exported_at: Utc::now(),
+            peers: vec![],
+            clusters: vec![],
+            deployments: vec![],
+        };
+
+        let json = archive.to_json()?;
+
+        verify_that!(StateArchive::from_json(&json)?, eq(archive))
+    }
+
+    #[test]
+    fn should_reject_archives_of_unknown_versions() -> Result<()> {
+        let newer = json!({ "version": STATE_ARCHIVE_VERSION + 1, "exported_at": Utc::now() }).to_string();
+        verify_that!(StateArchive::from_json(&newer), err(matches_pattern!(StateArchiveError::UnsupportedVersion { .. })))?;
+
+        let unversioned = json!({ "exported_at": Utc::now(), "peers": [] }).to_string();
+        verify_that!(StateArchive::from_json(&unversioned), err(matches_pattern!(StateArchiveError::MissingVersion)))
+    }
+}
@@ -0,0 +1,89 @@
+#[cfg(any(feature = "client", feature = "wasm-client"))]
+pub use client::*;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ReconcileVpnError {
+    #[error("The VPN could not be reconciled, because it is disabled!")]
+    VpnDisabled,
+    #[error("The VPN could not be reconciled, due to internal errors:\n  {cause}")]
+    Internal {
+        cause: String
+    },
+}
+
+#[cfg(any(feature = "client", feature = "wasm-client"))]
+mod client {
+    use tonic::codegen::{Body, Bytes, http, InterceptedService, StdError};
+
+    use opendut_types::vpn::reconciliation::VpnReconciliationReport;
+
+    use crate::carl::{ClientError, extract};
+    use crate::proto::services::vpn_manager;
+    use crate::proto::services::vpn_manager::vpn_manager_client::VpnManagerClient;
+
+    use super::*;
+
+    #[derive(Clone, Debug)]
+    pub struct VpnManager<T> {
+        inner: VpnManagerClient<T>,
+    }
+
+    impl<T> VpnManager<T>
+    where T: tonic::client::GrpcService<tonic::body::BoxBody>,
+          T::Error: Into<StdError>,
+          T::ResponseBody: Body<Data=Bytes> + Send + 'static,
+          <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: VpnManagerClient<T>) -> VpnManager<T> {
+            VpnManager {
+                inner
+            }
+        }
+
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> VpnManager<InterceptedService<T, F>>
+            where
+                F: tonic::service::Interceptor,
+                T::ResponseBody: Default,
+                T: tonic::codegen::Service<
+                    http::Request<tonic::body::BoxBody>,
+                    Response = http::Response<
+                        <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                    >,
+                >,
+                <T as tonic::codegen::Service<
+                    http::Request<tonic::body::BoxBody>,
+                >>::Error: Into<StdError> + Send + Sync,
+        {
+            let inner_client = VpnManagerClient::new(InterceptedService::new(inner, interceptor));
+            VpnManager {
+                inner: inner_client
+            }
+        }
+
+        /// Compares the VPN with the peers and cluster deployments known to CARL and repairs any drift.
+        /// With `dry_run`, the drift is only reported.
+        pub async fn reconcile(&mut self, dry_run: bool) -> Result<VpnReconciliationReport, ClientError<ReconcileVpnError>> {
+
+            let request = tonic::Request::new(vpn_manager::ReconcileVpnRequest {
+                dry_run,
+            });
+
+            let response = self.inner.reconcile_vpn(request).await?
+                .into_inner();
+
+            match extract!(response.reply)? {
+                vpn_manager::reconcile_vpn_response::Reply::Failure(failure) => {
+                    let error = ReconcileVpnError::try_from(failure)?;
+                    Err(ClientError::UsageError(error))
+                }
+                vpn_manager::reconcile_vpn_response::Reply::Success(success) => {
+                    let report = extract!(success.report)?;
+                    Ok(report)
+                }
+            }
+        }
+    }
+}
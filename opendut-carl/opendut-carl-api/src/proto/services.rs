@@ -1,10 +1,212 @@
+pub mod audit_log {
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    use opendut_types::proto::{ConversionError, ConversionErrorBuilder};
+
+    use crate::carl::audit::{AuditIdentity, AuditRecordFilter, AuditRecordId, ListAuditRecordsError};
+
+    tonic::include_proto!("opendut.carl.services.audit_log");
+
+    impl From<crate::carl::audit::AuditRecord> for AuditRecord {
+        fn from(record: crate::carl::audit::AuditRecord) -> Self {
+            AuditRecord {
+                id: Some(record.id.0.into()),
+                timestamp_ms: record.timestamp.timestamp_millis(),
+                identity: record.identity.0,
+                client_address: record.client_address.map(|address| address.to_string()),
+                action: Some(record.action.into()),
+                resource_id: Some(record.resource_id.into()),
+                project: Some(record.project.into()),
+                outcome: Some(record.outcome.into()),
+                before: record.before.map(|before| before.to_string()),
+                after: record.after.map(|after| after.to_string()),
+            }
+        }
+    }
+
+    impl TryFrom<AuditRecord> for crate::carl::audit::AuditRecord {
+        type Error = ConversionError;
+        fn try_from(record: AuditRecord) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<AuditRecord, crate::carl::audit::AuditRecord>;
+            let id: Uuid = record.id
+                .ok_or_else(|| ErrorBuilder::field_not_set("id"))?
+                .into();
+            let timestamp = Utc.timestamp_millis_opt(record.timestamp_ms)
+                .single()
+                .ok_or_else(|| ErrorBuilder::message(format!("Invalid timestamp: {}", record.timestamp_ms)))?;
+            let action = record.action
+                .ok_or_else(|| ErrorBuilder::field_not_set("action"))?
+                .try_into()?;
+            let resource_id: Uuid = record.resource_id
+                .ok_or_else(|| ErrorBuilder::field_not_set("resource_id"))?
+                .into();
+            let project = record.project
+                .map(TryInto::try_into)
+                .transpose()?
+                .unwrap_or_default();
+            let outcome = record.outcome
+                .ok_or_else(|| ErrorBuilder::field_not_set("outcome"))?
+                .try_into()?;
+            let before = record.before
+                .map(|before| serde_json::from_str(&before))
+                .transpose()
+                .map_err(|cause| ErrorBuilder::message(format!("Invalid value for field 'before': {cause}")))?;
+            let after = record.after
+                .map(|after| serde_json::from_str(&after))
+                .transpose()
+                .map_err(|cause| ErrorBuilder::message(format!("Invalid value for field 'after': {cause}")))?;
+            let client_address = record.client_address
+                .map(|address| address.parse())
+                .transpose()
+                .map_err(|cause| ErrorBuilder::message(format!("Invalid value for field 'client_address': {cause}")))?;
+            Ok(crate::carl::audit::AuditRecord {
+                id: AuditRecordId(id),
+                timestamp,
+                identity: AuditIdentity(record.identity),
+                client_address,
+                action,
+                resource_id,
+                project,
+                outcome,
+                before,
+                after,
+            })
+        }
+    }
+
+    impl From<crate::carl::audit::AuditAction> for AuditAction {
+        fn from(action: crate::carl::audit::AuditAction) -> Self {
+            use crate::carl::audit::AuditAction as Action;
+            let inner = match action {
+                Action::StorePeerDescriptor => audit_action::Inner::StorePeerDescriptor(AuditActionStorePeerDescriptor {}),
+                Action::DeletePeerDescriptor => audit_action::Inner::DeletePeerDescriptor(AuditActionDeletePeerDescriptor {}),
+                Action::GeneratePeerSetup => audit_action::Inner::GeneratePeerSetup(AuditActionGeneratePeerSetup {}),
+                Action::CreateClusterConfiguration => audit_action::Inner::CreateClusterConfiguration(AuditActionCreateClusterConfiguration {}),
+                Action::DeleteClusterConfiguration => audit_action::Inner::DeleteClusterConfiguration(AuditActionDeleteClusterConfiguration {}),
+                Action::StoreClusterDeployment => audit_action::Inner::StoreClusterDeployment(AuditActionStoreClusterDeployment {}),
+                Action::DeleteClusterDeployment => audit_action::Inner::DeleteClusterDeployment(AuditActionDeleteClusterDeployment {}),
+                Action::CreateProject => audit_action::Inner::CreateProject(AuditActionCreateProject {}),
+                Action::DeleteProject => audit_action::Inner::DeleteProject(AuditActionDeleteProject {}),
+                Action::ImportState => audit_action::Inner::ImportState(AuditActionImportState {}),
+                Action::UpdatePeerDescriptor => audit_action::Inner::UpdatePeerDescriptor(AuditActionUpdatePeerDescriptor {}),
+            };
+            AuditAction {
+                inner: Some(inner)
+            }
+        }
+    }
+
+    impl TryFrom<AuditAction> for crate::carl::audit::AuditAction {
+        type Error = ConversionError;
+        fn try_from(action: AuditAction) -> Result<Self, Self::Error> {
+            use crate::carl::audit::AuditAction as Action;
+            type ErrorBuilder = ConversionErrorBuilder<AuditAction, crate::carl::audit::AuditAction>;
+            let action = match action.inner.ok_or_else(|| ErrorBuilder::field_not_set("inner"))? {
+                audit_action::Inner::StorePeerDescriptor(_) => Action::StorePeerDescriptor,
+                audit_action::Inner::DeletePeerDescriptor(_) => Action::DeletePeerDescriptor,
+                audit_action::Inner::GeneratePeerSetup(_) => Action::GeneratePeerSetup,
+                audit_action::Inner::CreateClusterConfiguration(_) => Action::CreateClusterConfiguration,
+                audit_action::Inner::DeleteClusterConfiguration(_) => Action::DeleteClusterConfiguration,
+                audit_action::Inner::StoreClusterDeployment(_) => Action::StoreClusterDeployment,
+                audit_action::Inner::DeleteClusterDeployment(_) => Action::DeleteClusterDeployment,
+                audit_action::Inner::CreateProject(_) => Action::CreateProject,
+                audit_action::Inner::DeleteProject(_) => Action::DeleteProject,
+                audit_action::Inner::ImportState(_) => Action::ImportState,
+                audit_action::Inner::UpdatePeerDescriptor(_) => Action::UpdatePeerDescriptor,
+            };
+            Ok(action)
+        }
+    }
+
+    impl From<crate::carl::audit::AuditOutcome> for AuditOutcome {
+        fn from(outcome: crate::carl::audit::AuditOutcome) -> Self {
+            let inner = match outcome {
+                crate::carl::audit::AuditOutcome::Success => {
+                    audit_outcome::Inner::Success(AuditOutcomeSuccess {})
+                }
+                crate::carl::audit::AuditOutcome::Failure { cause } => {
+                    audit_outcome::Inner::Failure(AuditOutcomeFailure { cause })
+                }
+            };
+            AuditOutcome {
+                inner: Some(inner)
+            }
+        }
+    }
+
+    impl TryFrom<AuditOutcome> for crate::carl::audit::AuditOutcome {
+        type Error = ConversionError;
+        fn try_from(outcome: AuditOutcome) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<AuditOutcome, crate::carl::audit::AuditOutcome>;
+            let outcome = match outcome.inner.ok_or_else(|| ErrorBuilder::field_not_set("inner"))? {
+                audit_outcome::Inner::Success(_) => crate::carl::audit::AuditOutcome::Success,
+                audit_outcome::Inner::Failure(AuditOutcomeFailure { cause }) => crate::carl::audit::AuditOutcome::Failure { cause },
+            };
+            Ok(outcome)
+        }
+    }
+
+    impl From<AuditRecordFilter> for ListAuditRecordsRequest {
+        fn from(filter: AuditRecordFilter) -> Self {
+            ListAuditRecordsRequest {
+                resource_id: filter.resource_id.map(Into::into),
+                identity: filter.identity,
+                limit: filter.limit,
+            }
+        }
+    }
+
+    impl From<ListAuditRecordsRequest> for AuditRecordFilter {
+        fn from(request: ListAuditRecordsRequest) -> Self {
+            AuditRecordFilter {
+                resource_id: request.resource_id.map(Into::into),
+                identity: request.identity,
+                project: None,
+                limit: request.limit,
+            }
+        }
+    }
+
+    impl From<ListAuditRecordsError> for ListAuditRecordsFailure {
+        fn from(error: ListAuditRecordsError) -> Self {
+            let proto_error = match error {
+                ListAuditRecordsError::Internal { cause } => {
+                    list_audit_records_failure::Error::Internal(ListAuditRecordsFailureInternal {
+                        cause
+                    })
+                }
+            };
+            ListAuditRecordsFailure {
+                error: Some(proto_error)
+            }
+        }
+    }
+
+    impl TryFrom<ListAuditRecordsFailure> for ListAuditRecordsError {
+        type Error = ConversionError;
+        fn try_from(failure: ListAuditRecordsFailure) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<ListAuditRecordsFailure, ListAuditRecordsError>;
+            let error = failure.error
+                .ok_or_else(|| ErrorBuilder::field_not_set("error"))?;
+            let error = match error {
+                list_audit_records_failure::Error::Internal(error) => {
+                    ListAuditRecordsError::Internal { cause: error.cause }
+                }
+            };
+            Ok(error)
+        }
+    }
+}
+
 pub mod cluster_manager {
     use opendut_types::cluster::{ClusterId, ClusterName};
+    use opendut_types::cluster::operation::DeploymentOperationId;
     use opendut_types::cluster::state::ClusterState;
     use opendut_types::proto;
     use opendut_types::proto::{ConversionError, ConversionErrorBuilder};
 
-    use crate::carl::cluster::{CreateClusterConfigurationError, DeleteClusterConfigurationError, DeleteClusterDeploymentError, StoreClusterDeploymentError};
+    use crate::carl::cluster::{CreateClusterConfigurationError, DeleteClusterConfigurationError, DeleteClusterDeploymentError, GetDeploymentOperationError, StoreClusterDeploymentError, ValidateClusterDeploymentError};
 
     tonic::include_proto!("opendut.carl.services.cluster_manager");
 
@@ -19,6 +221,21 @@ pub mod cluster_manager {
                         other_name: Some(other_name.into()),
                     })
                 }
+                CreateClusterConfigurationError::RevisionConflict { cluster_id, cluster_name, expected_revision, actual_revision } => {
+                    create_cluster_configuration_failure::Error::RevisionConflict(CreateClusterConfigurationFailureRevisionConflict {
+                        cluster_id: Some(cluster_id.into()),
+                        cluster_name: Some(cluster_name.into()),
+                        expected_revision,
+                        actual_revision,
+                    })
+                }
+                CreateClusterConfigurationError::QuotaExceeded { cluster_id, cluster_name, cause } => {
+                    create_cluster_configuration_failure::Error::QuotaExceeded(CreateClusterConfigurationFailureQuotaExceeded {
+                        cluster_id: Some(cluster_id.into()),
+                        cluster_name: Some(cluster_name.into()),
+                        cause
+                    })
+                }
                 CreateClusterConfigurationError::Internal { cluster_id, cluster_name, cause } => {
                     create_cluster_configuration_failure::Error::Internal(CreateClusterConfigurationFailureInternal {
                         cluster_id: Some(cluster_id.into()),
@@ -43,6 +260,12 @@ pub mod cluster_manager {
                 create_cluster_configuration_failure::Error::ClusterConfigurationAlreadyExists(error) => {
                     error.try_into()?
                 }
+                create_cluster_configuration_failure::Error::RevisionConflict(error) => {
+                    error.try_into()?
+                }
+                create_cluster_configuration_failure::Error::QuotaExceeded(error) => {
+                    error.try_into()?
+                }
                 create_cluster_configuration_failure::Error::Internal(error) => {
                     error.try_into()?
                 }
@@ -71,6 +294,39 @@ pub mod cluster_manager {
         }
     }
 
+    impl TryFrom<CreateClusterConfigurationFailureRevisionConflict> for CreateClusterConfigurationError {
+        type Error = ConversionError;
+        fn try_from(failure: CreateClusterConfigurationFailureRevisionConflict) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<CreateClusterConfigurationFailureRevisionConflict, CreateClusterConfigurationError>;
+            let cluster_id: ClusterId = failure.cluster_id
+                .ok_or_else(|| ErrorBuilder::field_not_set("cluster_id"))?
+                .try_into()?;
+            let cluster_name: ClusterName = failure.cluster_name
+                .ok_or_else(|| ErrorBuilder::field_not_set("cluster_name"))?
+                .try_into()?;
+            Ok(CreateClusterConfigurationError::RevisionConflict {
+                cluster_id,
+                cluster_name,
+                expected_revision: failure.expected_revision,
+                actual_revision: failure.actual_revision,
+            })
+        }
+    }
+
+    impl TryFrom<CreateClusterConfigurationFailureQuotaExceeded> for CreateClusterConfigurationError {
+        type Error = ConversionError;
+        fn try_from(failure: CreateClusterConfigurationFailureQuotaExceeded) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<CreateClusterConfigurationFailureQuotaExceeded, CreateClusterConfigurationError>;
+            let cluster_id: ClusterId = failure.cluster_id
+                .ok_or_else(|| ErrorBuilder::field_not_set("cluster_id"))?
+                .try_into()?;
+            let cluster_name: ClusterName = failure.cluster_name
+                .ok_or_else(|| ErrorBuilder::field_not_set("cluster_name"))?
+                .try_into()?;
+            Ok(CreateClusterConfigurationError::QuotaExceeded { cluster_id, cluster_name, cause: failure.cause })
+        }
+    }
+
     impl TryFrom<CreateClusterConfigurationFailureInternal> for CreateClusterConfigurationError {
         type Error = ConversionError;
         fn try_from(failure: CreateClusterConfigurationFailureInternal) -> Result<Self, Self::Error> {
@@ -184,6 +440,11 @@ pub mod cluster_manager {
     impl From<StoreClusterDeploymentError> for StoreClusterDeploymentFailure {
         fn from(error: StoreClusterDeploymentError) -> Self {
             let proto_error = match error {
+                StoreClusterDeploymentError::ClusterConfigurationNotFound { cluster_id } => {
+                    store_cluster_deployment_failure::Error::ClusterConfigurationNotFound(StoreClusterDeploymentFailureClusterConfigurationNotFound {
+                        cluster_id: Some(cluster_id.into())
+                    })
+                }
                 StoreClusterDeploymentError::IllegalClusterState { cluster_id, cluster_name, actual_state, required_states } => {
                     store_cluster_deployment_failure::Error::IllegalClusterState(StoreClusterDeploymentFailureIllegalClusterState {
                         cluster_id: Some(cluster_id.into()),
@@ -192,6 +453,13 @@ pub mod cluster_manager {
                         required_states: required_states.into_iter().map(Into::into).collect(),
                     })
                 }
+                StoreClusterDeploymentError::ReservationConflict { cluster_id, other_cluster_id, devices } => {
+                    store_cluster_deployment_failure::Error::ReservationConflict(StoreClusterDeploymentFailureReservationConflict {
+                        cluster_id: Some(cluster_id.into()),
+                        other_cluster_id: Some(other_cluster_id.into()),
+                        devices: devices.into_iter().map(Into::into).collect(),
+                    })
+                }
                 StoreClusterDeploymentError::Internal { cluster_id, cluster_name, cause } => {
                     store_cluster_deployment_failure::Error::Internal(StoreClusterDeploymentFailureInternal {
                         cluster_id: Some(cluster_id.into()),
@@ -213,9 +481,27 @@ pub mod cluster_manager {
             let error = failure.error
                 .ok_or_else(|| ErrorBuilder::field_not_set("error"))?;
             let error = match error {
+                store_cluster_deployment_failure::Error::ClusterConfigurationNotFound(error) => {
+                    let cluster_id: ClusterId = error.cluster_id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("cluster_id"))?
+                        .try_into()?;
+                    StoreClusterDeploymentError::ClusterConfigurationNotFound { cluster_id }
+                }
                 store_cluster_deployment_failure::Error::IllegalClusterState(error) => {
                     error.try_into()?
                 }
+                store_cluster_deployment_failure::Error::ReservationConflict(error) => {
+                    let cluster_id: ClusterId = error.cluster_id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("cluster_id"))?
+                        .try_into()?;
+                    let other_cluster_id: ClusterId = error.other_cluster_id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("other_cluster_id"))?
+                        .try_into()?;
+                    let devices = error.devices.into_iter()
+                        .map(TryInto::try_into)
+                        .collect::<Result<_, _>>()?;
+                    StoreClusterDeploymentError::ReservationConflict { cluster_id, other_cluster_id, devices }
+                }
                 store_cluster_deployment_failure::Error::Internal(error) => {
                     error.try_into()?
                 }
@@ -354,6 +640,84 @@ pub mod cluster_manager {
         }
     }
 
+    impl From<GetDeploymentOperationError> for GetDeploymentOperationFailure {
+        fn from(error: GetDeploymentOperationError) -> Self {
+            let proto_error = match error {
+                GetDeploymentOperationError::DeploymentOperationNotFound { operation_id } => {
+                    get_deployment_operation_failure::Error::DeploymentOperationNotFound(GetDeploymentOperationFailureDeploymentOperationNotFound {
+                        id: Some(operation_id.into())
+                    })
+                }
+            };
+            GetDeploymentOperationFailure {
+                error: Some(proto_error)
+            }
+        }
+    }
+
+    impl TryFrom<GetDeploymentOperationFailure> for GetDeploymentOperationError {
+        type Error = ConversionError;
+        fn try_from(failure: GetDeploymentOperationFailure) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<GetDeploymentOperationFailure, GetDeploymentOperationError>;
+            let error = failure.error
+                .ok_or_else(|| ErrorBuilder::field_not_set("error"))?;
+            let error = match error {
+                get_deployment_operation_failure::Error::DeploymentOperationNotFound(error) => {
+                    let operation_id: DeploymentOperationId = error.id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("id"))?
+                        .try_into()?;
+                    GetDeploymentOperationError::DeploymentOperationNotFound { operation_id }
+                }
+            };
+            Ok(error)
+        }
+    }
+
+    impl From<ValidateClusterDeploymentError> for ValidateClusterDeploymentFailure {
+        fn from(error: ValidateClusterDeploymentError) -> Self {
+            let proto_error = match error {
+                ValidateClusterDeploymentError::ClusterConfigurationNotFound { cluster_id } => {
+                    validate_cluster_deployment_failure::Error::ClusterConfigurationNotFound(ValidateClusterDeploymentFailureClusterConfigurationNotFound {
+                        cluster_id: Some(cluster_id.into())
+                    })
+                }
+                ValidateClusterDeploymentError::Internal { cluster_id, cause } => {
+                    validate_cluster_deployment_failure::Error::Internal(ValidateClusterDeploymentFailureInternal {
+                        cluster_id: Some(cluster_id.into()),
+                        cause
+                    })
+                }
+            };
+            ValidateClusterDeploymentFailure {
+                error: Some(proto_error)
+            }
+        }
+    }
+
+    impl TryFrom<ValidateClusterDeploymentFailure> for ValidateClusterDeploymentError {
+        type Error = ConversionError;
+        fn try_from(failure: ValidateClusterDeploymentFailure) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<ValidateClusterDeploymentFailure, ValidateClusterDeploymentError>;
+            let error = failure.error
+                .ok_or_else(|| ErrorBuilder::field_not_set("error"))?;
+            let error = match error {
+                validate_cluster_deployment_failure::Error::ClusterConfigurationNotFound(error) => {
+                    let cluster_id: ClusterId = error.cluster_id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("cluster_id"))?
+                        .try_into()?;
+                    ValidateClusterDeploymentError::ClusterConfigurationNotFound { cluster_id }
+                }
+                validate_cluster_deployment_failure::Error::Internal(error) => {
+                    let cluster_id: ClusterId = error.cluster_id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("cluster_id"))?
+                        .try_into()?;
+                    ValidateClusterDeploymentError::Internal { cluster_id, cause: error.cause }
+                }
+            };
+            Ok(error)
+        }
+    }
+
 }
 
 pub mod metadata_provider {
@@ -368,7 +732,7 @@ pub mod peer_manager {
     use opendut_types::proto::{ConversionError, ConversionErrorBuilder};
     use opendut_types::topology::DeviceId;
 
-    use crate::carl::peer::{StorePeerDescriptorError, DeletePeerDescriptorError, GetPeerDescriptorError, ListPeerDescriptorsError};
+    use crate::carl::peer::{StorePeerDescriptorError, DeletePeerDescriptorError, FindDevicesError, GetPeerDescriptorError, ListPeerDescriptorsError, UpdatePeerError};
 
     tonic::include_proto!("opendut.carl.services.peer_manager");
 
@@ -390,6 +754,21 @@ pub mod peer_manager {
                         error: Some(error.into()),
                     })
                 }
+                StorePeerDescriptorError::RevisionConflict { peer_id, peer_name, expected_revision, actual_revision } => {
+                    store_peer_descriptor_failure::Error::RevisionConflict(StorePeerDescriptorFailureRevisionConflict {
+                        peer_id: Some(peer_id.into()),
+                        peer_name: Some(peer_name.into()),
+                        expected_revision,
+                        actual_revision,
+                    })
+                }
+                StorePeerDescriptorError::QuotaExceeded { peer_id, peer_name, cause } => {
+                    store_peer_descriptor_failure::Error::QuotaExceeded(StorePeerDescriptorFailureQuotaExceeded {
+                        peer_id: Some(peer_id.into()),
+                        peer_name: Some(peer_name.into()),
+                        cause
+                    })
+                }
                 StorePeerDescriptorError::Internal { peer_id, peer_name, cause } => {
                     store_peer_descriptor_failure::Error::Internal(StorePeerDescriptorFailureInternal {
                         peer_id: Some(peer_id.into()),
@@ -417,6 +796,12 @@ pub mod peer_manager {
                 store_peer_descriptor_failure::Error::IllegalDevices(error) => {
                     error.try_into()?
                 }
+                store_peer_descriptor_failure::Error::RevisionConflict(error) => {
+                    error.try_into()?
+                }
+                store_peer_descriptor_failure::Error::QuotaExceeded(error) => {
+                    error.try_into()?
+                }
                 store_peer_descriptor_failure::Error::Internal(error) => {
                     error.try_into()?
                 }
@@ -462,6 +847,39 @@ pub mod peer_manager {
         }
     }
 
+    impl TryFrom<StorePeerDescriptorFailureRevisionConflict> for StorePeerDescriptorError {
+        type Error = ConversionError;
+        fn try_from(failure: StorePeerDescriptorFailureRevisionConflict) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<StorePeerDescriptorFailureRevisionConflict, StorePeerDescriptorError>;
+            let peer_id: PeerId = failure.peer_id
+                .ok_or_else(|| ErrorBuilder::field_not_set("peer_id"))?
+                .try_into()?;
+            let peer_name: PeerName = failure.peer_name
+                .ok_or_else(|| ErrorBuilder::field_not_set("peer_name"))?
+                .try_into()?;
+            Ok(StorePeerDescriptorError::RevisionConflict {
+                peer_id,
+                peer_name,
+                expected_revision: failure.expected_revision,
+                actual_revision: failure.actual_revision,
+            })
+        }
+    }
+
+    impl TryFrom<StorePeerDescriptorFailureQuotaExceeded> for StorePeerDescriptorError {
+        type Error = ConversionError;
+        fn try_from(failure: StorePeerDescriptorFailureQuotaExceeded) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<StorePeerDescriptorFailureQuotaExceeded, StorePeerDescriptorError>;
+            let peer_id: PeerId = failure.peer_id
+                .ok_or_else(|| ErrorBuilder::field_not_set("peer_id"))?
+                .try_into()?;
+            let peer_name: PeerName = failure.peer_name
+                .ok_or_else(|| ErrorBuilder::field_not_set("peer_name"))?
+                .try_into()?;
+            Ok(StorePeerDescriptorError::QuotaExceeded { peer_id, peer_name, cause: failure.cause })
+        }
+    }
+
     impl TryFrom<StorePeerDescriptorFailureInternal> for StorePeerDescriptorError {
         type Error = ConversionError;
         fn try_from(failure: StorePeerDescriptorFailureInternal) -> Result<Self, Self::Error> {
@@ -709,8 +1127,610 @@ pub mod peer_manager {
             Ok(ListPeerDescriptorsError::Internal{ cause: failure.cause})
         }
     }
+
+    impl From<(PeerId, PeerState)> for ListPeerStatesEntry {
+        fn from((peer_id, state): (PeerId, PeerState)) -> Self {
+            ListPeerStatesEntry {
+                peer_id: Some(peer_id.into()),
+                state: Some(state.into()),
+            }
+        }
+    }
+
+    impl TryFrom<ListPeerStatesEntry> for (PeerId, PeerState) {
+        type Error = ConversionError;
+        fn try_from(entry: ListPeerStatesEntry) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<ListPeerStatesEntry, (PeerId, PeerState)>;
+            let peer_id: PeerId = entry.peer_id
+                .ok_or_else(|| ErrorBuilder::field_not_set("peer_id"))?
+                .try_into()?;
+            let state: PeerState = entry.state
+                .ok_or_else(|| ErrorBuilder::field_not_set("state"))?
+                .try_into()?;
+            Ok((peer_id, state))
+        }
+    }
+
+    impl From<FindDevicesError> for FindDevicesFailure {
+        fn from(error: FindDevicesError) -> Self {
+            let proto_error = match error {
+                FindDevicesError::InvalidQuery { query, cause } => {
+                    find_devices_failure::Error::InvalidQuery(FindDevicesFailureInvalidQuery {
+                        query,
+                        cause,
+                    })
+                }
+                FindDevicesError::Internal { cause } => {
+                    find_devices_failure::Error::Internal(FindDevicesFailureInternal {
+                        cause
+                    })
+                }
+            };
+            FindDevicesFailure {
+                error: Some(proto_error)
+            }
+        }
+    }
+
+    impl TryFrom<FindDevicesFailure> for FindDevicesError {
+        type Error = ConversionError;
+        fn try_from(failure: FindDevicesFailure) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<FindDevicesFailure, FindDevicesError>;
+            let error = failure.error
+                .ok_or_else(|| ErrorBuilder::field_not_set("error"))?;
+            let error = match error {
+                find_devices_failure::Error::InvalidQuery(FindDevicesFailureInvalidQuery { query, cause }) => {
+                    FindDevicesError::InvalidQuery { query, cause }
+                }
+                find_devices_failure::Error::Internal(FindDevicesFailureInternal { cause }) => {
+                    FindDevicesError::Internal { cause }
+                }
+            };
+            Ok(error)
+        }
+    }
+
+    impl From<UpdatePeerError> for UpdatePeerFailure {
+        fn from(error: UpdatePeerError) -> Self {
+            let proto_error = match error {
+                UpdatePeerError::PeerNotFound { peer_id } => {
+                    update_peer_failure::Error::PeerNotFound(UpdatePeerFailurePeerNotFound {
+                        peer_id: Some(peer_id.into()),
+                    })
+                }
+                UpdatePeerError::DeviceNotFound { peer_id, peer_name, device_id } => {
+                    update_peer_failure::Error::DeviceNotFound(UpdatePeerFailureDeviceNotFound {
+                        peer_id: Some(peer_id.into()),
+                        peer_name: Some(peer_name.into()),
+                        device_id: Some(device_id.into()),
+                    })
+                }
+                UpdatePeerError::DeviceAlreadyExists { peer_id, peer_name, device_id } => {
+                    update_peer_failure::Error::DeviceAlreadyExists(UpdatePeerFailureDeviceAlreadyExists {
+                        peer_id: Some(peer_id.into()),
+                        peer_name: Some(peer_name.into()),
+                        device_id: Some(device_id.into()),
+                    })
+                }
+                UpdatePeerError::DeviceInDeployedCluster { peer_id, peer_name, device_id, cluster_id } => {
+                    update_peer_failure::Error::DeviceInDeployedCluster(UpdatePeerFailureDeviceInDeployedCluster {
+                        peer_id: Some(peer_id.into()),
+                        peer_name: Some(peer_name.into()),
+                        device_id: Some(device_id.into()),
+                        cluster_id: Some(cluster_id.into()),
+                    })
+                }
+                UpdatePeerError::NetworkInterfaceNotFound { peer_id, peer_name, interface_name } => {
+                    update_peer_failure::Error::NetworkInterfaceNotFound(UpdatePeerFailureNetworkInterfaceNotFound {
+                        peer_id: Some(peer_id.into()),
+                        peer_name: Some(peer_name.into()),
+                        interface_name: Some(interface_name.into()),
+                    })
+                }
+                UpdatePeerError::NetworkInterfaceInUse { peer_id, peer_name, interface_name, devices } => {
+                    update_peer_failure::Error::NetworkInterfaceInUse(UpdatePeerFailureNetworkInterfaceInUse {
+                        peer_id: Some(peer_id.into()),
+                        peer_name: Some(peer_name.into()),
+                        interface_name: Some(interface_name.into()),
+                        devices: devices.into_iter().map(Into::into).collect(),
+                    })
+                }
+                UpdatePeerError::ExecutorNotFound { peer_id, peer_name, container_name } => {
+                    update_peer_failure::Error::ExecutorNotFound(UpdatePeerFailureExecutorNotFound {
+                        peer_id: Some(peer_id.into()),
+                        peer_name: Some(peer_name.into()),
+                        container_name: Some(container_name.into()),
+                    })
+                }
+                UpdatePeerError::ExecutorAmbiguous { peer_id, peer_name, container_name, count } => {
+                    update_peer_failure::Error::ExecutorAmbiguous(UpdatePeerFailureExecutorAmbiguous {
+                        peer_id: Some(peer_id.into()),
+                        peer_name: Some(peer_name.into()),
+                        container_name: Some(container_name.into()),
+                        count: count as u32,
+                    })
+                }
+                UpdatePeerError::QuotaExceeded { peer_id, peer_name, cause } => {
+                    update_peer_failure::Error::QuotaExceeded(UpdatePeerFailureQuotaExceeded {
+                        peer_id: Some(peer_id.into()),
+                        peer_name: Some(peer_name.into()),
+                        cause,
+                    })
+                }
+                UpdatePeerError::Internal { peer_id, cause } => {
+                    update_peer_failure::Error::Internal(UpdatePeerFailureInternal {
+                        peer_id: Some(peer_id.into()),
+                        cause,
+                    })
+                }
+            };
+            UpdatePeerFailure {
+                error: Some(proto_error)
+            }
+        }
+    }
+
+    impl TryFrom<UpdatePeerFailure> for UpdatePeerError {
+        type Error = ConversionError;
+        fn try_from(failure: UpdatePeerFailure) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<UpdatePeerFailure, UpdatePeerError>;
+            let error = failure.error
+                .ok_or_else(|| ErrorBuilder::field_not_set("error"))?;
+            let error = match error {
+                update_peer_failure::Error::PeerNotFound(failure) => {
+                    let peer_id: PeerId = failure.peer_id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("peer_id"))?
+                        .try_into()?;
+                    UpdatePeerError::PeerNotFound { peer_id }
+                }
+                update_peer_failure::Error::DeviceNotFound(failure) => {
+                    let (peer_id, peer_name) = extract_peer(failure.peer_id, failure.peer_name)?;
+                    let device_id: DeviceId = failure.device_id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("device_id"))?
+                        .try_into()?;
+                    UpdatePeerError::DeviceNotFound { peer_id, peer_name, device_id }
+                }
+                update_peer_failure::Error::DeviceAlreadyExists(failure) => {
+                    let (peer_id, peer_name) = extract_peer(failure.peer_id, failure.peer_name)?;
+                    let device_id: DeviceId = failure.device_id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("device_id"))?
+                        .try_into()?;
+                    UpdatePeerError::DeviceAlreadyExists { peer_id, peer_name, device_id }
+                }
+                update_peer_failure::Error::DeviceInDeployedCluster(failure) => {
+                    let (peer_id, peer_name) = extract_peer(failure.peer_id, failure.peer_name)?;
+                    let device_id: DeviceId = failure.device_id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("device_id"))?
+                        .try_into()?;
+                    let cluster_id = failure.cluster_id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("cluster_id"))?
+                        .try_into()?;
+                    UpdatePeerError::DeviceInDeployedCluster { peer_id, peer_name, device_id, cluster_id }
+                }
+                update_peer_failure::Error::NetworkInterfaceNotFound(failure) => {
+                    let (peer_id, peer_name) = extract_peer(failure.peer_id, failure.peer_name)?;
+                    let interface_name = failure.interface_name
+                        .ok_or_else(|| ErrorBuilder::field_not_set("interface_name"))?
+                        .try_into()?;
+                    UpdatePeerError::NetworkInterfaceNotFound { peer_id, peer_name, interface_name }
+                }
+                update_peer_failure::Error::NetworkInterfaceInUse(failure) => {
+                    let (peer_id, peer_name) = extract_peer(failure.peer_id, failure.peer_name)?;
+                    let interface_name = failure.interface_name
+                        .ok_or_else(|| ErrorBuilder::field_not_set("interface_name"))?
+                        .try_into()?;
+                    let devices = failure.devices.into_iter()
+                        .map(TryInto::try_into)
+                        .collect::<Result<_, _>>()?;
+                    UpdatePeerError::NetworkInterfaceInUse { peer_id, peer_name, interface_name, devices }
+                }
+                update_peer_failure::Error::ExecutorNotFound(failure) => {
+                    let (peer_id, peer_name) = extract_peer(failure.peer_id, failure.peer_name)?;
+                    let container_name = failure.container_name
+                        .ok_or_else(|| ErrorBuilder::field_not_set("container_name"))?
+                        .try_into()?;
+                    UpdatePeerError::ExecutorNotFound { peer_id, peer_name, container_name }
+                }
+                update_peer_failure::Error::ExecutorAmbiguous(failure) => {
+                    let (peer_id, peer_name) = extract_peer(failure.peer_id, failure.peer_name)?;
+                    let container_name = failure.container_name
+                        .ok_or_else(|| ErrorBuilder::field_not_set("container_name"))?
+                        .try_into()?;
+                    UpdatePeerError::ExecutorAmbiguous { peer_id, peer_name, container_name, count: failure.count as usize }
+                }
+                update_peer_failure::Error::QuotaExceeded(failure) => {
+                    let (peer_id, peer_name) = extract_peer(failure.peer_id, failure.peer_name)?;
+                    UpdatePeerError::QuotaExceeded { peer_id, peer_name, cause: failure.cause }
+                }
+                update_peer_failure::Error::Internal(failure) => {
+                    let peer_id: PeerId = failure.peer_id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("peer_id"))?
+                        .try_into()?;
+                    UpdatePeerError::Internal { peer_id, cause: failure.cause }
+                }
+            };
+            Ok(error)
+        }
+    }
+
+    fn extract_peer(peer_id: Option<proto::peer::PeerId>, peer_name: Option<proto::peer::PeerName>) -> Result<(PeerId, PeerName), ConversionError> {
+        type ErrorBuilder = ConversionErrorBuilder<UpdatePeerFailure, UpdatePeerError>;
+        let peer_id = peer_id
+            .ok_or_else(|| ErrorBuilder::field_not_set("peer_id"))?
+            .try_into()?;
+        let peer_name = peer_name
+            .ok_or_else(|| ErrorBuilder::field_not_set("peer_name"))?
+            .try_into()?;
+        Ok((peer_id, peer_name))
+    }
 }
 
 pub mod peer_messaging_broker {
     tonic::include_proto!("opendut.carl.services.peer_messaging_broker");
 }
+
+pub mod project_manager {
+    use opendut_types::project::{ProjectId, ProjectName};
+    use opendut_types::proto::{ConversionError, ConversionErrorBuilder};
+
+    use crate::carl::project::{CreateProjectError, DeleteProjectError};
+
+    tonic::include_proto!("opendut.carl.services.project_manager");
+
+    impl From<CreateProjectError> for CreateProjectFailure {
+        fn from(error: CreateProjectError) -> Self {
+            let proto_error = match error {
+                CreateProjectError::ProjectAlreadyExists { project_id, project_name } => {
+                    create_project_failure::Error::ProjectAlreadyExists(CreateProjectFailureProjectAlreadyExists {
+                        project_id: Some(project_id.into()),
+                        project_name: Some(project_name.into()),
+                    })
+                }
+                CreateProjectError::Internal { project_id, project_name, cause } => {
+                    create_project_failure::Error::Internal(CreateProjectFailureInternal {
+                        project_id: Some(project_id.into()),
+                        project_name: Some(project_name.into()),
+                        cause
+                    })
+                }
+            };
+            CreateProjectFailure {
+                error: Some(proto_error)
+            }
+        }
+    }
+
+    impl TryFrom<CreateProjectFailure> for CreateProjectError {
+        type Error = ConversionError;
+        fn try_from(failure: CreateProjectFailure) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<CreateProjectFailure, CreateProjectError>;
+            let error = failure.error
+                .ok_or_else(|| ErrorBuilder::field_not_set("error"))?;
+            let error = match error {
+                create_project_failure::Error::ProjectAlreadyExists(error) => {
+                    let project_id: ProjectId = error.project_id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("project_id"))?
+                        .try_into()?;
+                    let project_name: ProjectName = error.project_name
+                        .ok_or_else(|| ErrorBuilder::field_not_set("project_name"))?
+                        .try_into()?;
+                    CreateProjectError::ProjectAlreadyExists { project_id, project_name }
+                }
+                create_project_failure::Error::Internal(error) => {
+                    let project_id: ProjectId = error.project_id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("project_id"))?
+                        .try_into()?;
+                    let project_name: ProjectName = error.project_name
+                        .ok_or_else(|| ErrorBuilder::field_not_set("project_name"))?
+                        .try_into()?;
+                    CreateProjectError::Internal { project_id, project_name, cause: error.cause }
+                }
+            };
+            Ok(error)
+        }
+    }
+
+    impl From<DeleteProjectError> for DeleteProjectFailure {
+        fn from(error: DeleteProjectError) -> Self {
+            let proto_error = match error {
+                DeleteProjectError::ProjectNotFound { project_id } => {
+                    delete_project_failure::Error::ProjectNotFound(DeleteProjectFailureProjectNotFound {
+                        project_id: Some(project_id.into()),
+                    })
+                }
+                DeleteProjectError::ProjectNotEmpty { project_id, project_name } => {
+                    delete_project_failure::Error::ProjectNotEmpty(DeleteProjectFailureProjectNotEmpty {
+                        project_id: Some(project_id.into()),
+                        project_name: Some(project_name.into()),
+                    })
+                }
+                DeleteProjectError::DefaultProject => {
+                    delete_project_failure::Error::DefaultProject(DeleteProjectFailureDefaultProject {})
+                }
+            };
+            DeleteProjectFailure {
+                error: Some(proto_error)
+            }
+        }
+    }
+
+    impl TryFrom<DeleteProjectFailure> for DeleteProjectError {
+        type Error = ConversionError;
+        fn try_from(failure: DeleteProjectFailure) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<DeleteProjectFailure, DeleteProjectError>;
+            let error = failure.error
+                .ok_or_else(|| ErrorBuilder::field_not_set("error"))?;
+            let error = match error {
+                delete_project_failure::Error::ProjectNotFound(error) => {
+                    let project_id: ProjectId = error.project_id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("project_id"))?
+                        .try_into()?;
+                    DeleteProjectError::ProjectNotFound { project_id }
+                }
+                delete_project_failure::Error::ProjectNotEmpty(error) => {
+                    let project_id: ProjectId = error.project_id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("project_id"))?
+                        .try_into()?;
+                    let project_name: ProjectName = error.project_name
+                        .ok_or_else(|| ErrorBuilder::field_not_set("project_name"))?
+                        .try_into()?;
+                    DeleteProjectError::ProjectNotEmpty { project_id, project_name }
+                }
+                delete_project_failure::Error::DefaultProject(_) => {
+                    DeleteProjectError::DefaultProject
+                }
+            };
+            Ok(error)
+        }
+    }
+}
+
+pub mod state_manager {
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    use opendut_types::proto::{ConversionError, ConversionErrorBuilder};
+
+    use crate::carl::state::{ExportStateError, ImportStateError};
+
+    tonic::include_proto!("opendut.carl.services.state_manager");
+
+    impl From<crate::carl::state::StateArchive> for StateArchive {
+        fn from(archive: crate::carl::state::StateArchive) -> Self {
+            StateArchive {
+                version: archive.version,
+                exported_at_ms: archive.exported_at.timestamp_millis(),
+                peers: archive.peers.into_iter().map(Into::into).collect(),
+                clusters: archive.clusters.into_iter().map(Into::into).collect(),
+                deployments: archive.deployments.into_iter().map(Into::into).collect(),
+            }
+        }
+    }
+
+    impl TryFrom<StateArchive> for crate::carl::state::StateArchive {
+        type Error = ConversionError;
+        fn try_from(archive: StateArchive) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<StateArchive, crate::carl::state::StateArchive>;
+            let exported_at = Utc.timestamp_millis_opt(archive.exported_at_ms)
+                .single()
+                .ok_or_else(|| ErrorBuilder::message(format!("Invalid timestamp: {}", archive.exported_at_ms)))?;
+            let peers = archive.peers.into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<Vec<_>, _>>()?;
+            let clusters = archive.clusters.into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<Vec<_>, _>>()?;
+            let deployments = archive.deployments.into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(crate::carl::state::StateArchive {
+                version: archive.version,
+                exported_at,
+                peers,
+                clusters,
+                deployments,
+            })
+        }
+    }
+
+    impl From<crate::carl::state::ImportStrategy> for ImportStrategy {
+        fn from(strategy: crate::carl::state::ImportStrategy) -> Self {
+            use crate::carl::state::ImportStrategy as Strategy;
+            let inner = match strategy {
+                Strategy::Skip => import_strategy::Inner::Skip(ImportStrategySkip {}),
+                Strategy::Overwrite => import_strategy::Inner::Overwrite(ImportStrategyOverwrite {}),
+                Strategy::RenameIds => import_strategy::Inner::RenameIds(ImportStrategyRenameIds {}),
+            };
+            ImportStrategy {
+                inner: Some(inner)
+            }
+        }
+    }
+
+    impl TryFrom<ImportStrategy> for crate::carl::state::ImportStrategy {
+        type Error = ConversionError;
+        fn try_from(strategy: ImportStrategy) -> Result<Self, Self::Error> {
+            use crate::carl::state::ImportStrategy as Strategy;
+            type ErrorBuilder = ConversionErrorBuilder<ImportStrategy, crate::carl::state::ImportStrategy>;
+            let strategy = match strategy.inner.ok_or_else(|| ErrorBuilder::field_not_set("inner"))? {
+                import_strategy::Inner::Skip(_) => Strategy::Skip,
+                import_strategy::Inner::Overwrite(_) => Strategy::Overwrite,
+                import_strategy::Inner::RenameIds(_) => Strategy::RenameIds,
+            };
+            Ok(strategy)
+        }
+    }
+
+    impl From<crate::carl::state::ImportedResource> for ImportedResource {
+        fn from(resource: crate::carl::state::ImportedResource) -> Self {
+            use crate::carl::state::ImportedResourceKind as Kind;
+            use crate::carl::state::ImportOutcome as Outcome;
+            let kind = match resource.kind {
+                Kind::Peer => imported_resource_kind::Inner::Peer(ImportedResourceKindPeer {}),
+                Kind::ClusterConfiguration => imported_resource_kind::Inner::ClusterConfiguration(ImportedResourceKindClusterConfiguration {}),
+                Kind::ClusterDeployment => imported_resource_kind::Inner::ClusterDeployment(ImportedResourceKindClusterDeployment {}),
+            };
+            let outcome = match resource.outcome {
+                Outcome::Created => import_outcome::Inner::Created(ImportOutcomeCreated {}),
+                Outcome::Overwritten => import_outcome::Inner::Overwritten(ImportOutcomeOverwritten {}),
+                Outcome::Skipped => import_outcome::Inner::Skipped(ImportOutcomeSkipped {}),
+                Outcome::Renamed { original_id } => import_outcome::Inner::Renamed(ImportOutcomeRenamed { original_id: Some(original_id.into()) }),
+                Outcome::Failed { cause } => import_outcome::Inner::Failed(ImportOutcomeFailed { cause }),
+            };
+            ImportedResource {
+                kind: Some(ImportedResourceKind { inner: Some(kind) }),
+                id: Some(resource.id.into()),
+                name: resource.name,
+                outcome: Some(ImportOutcome { inner: Some(outcome) }),
+            }
+        }
+    }
+
+    impl TryFrom<ImportedResource> for crate::carl::state::ImportedResource {
+        type Error = ConversionError;
+        fn try_from(resource: ImportedResource) -> Result<Self, Self::Error> {
+            use crate::carl::state::ImportedResourceKind as Kind;
+            use crate::carl::state::ImportOutcome as Outcome;
+            type ErrorBuilder = ConversionErrorBuilder<ImportedResource, crate::carl::state::ImportedResource>;
+            let kind = match resource.kind.and_then(|kind| kind.inner).ok_or_else(|| ErrorBuilder::field_not_set("kind"))? {
+                imported_resource_kind::Inner::Peer(_) => Kind::Peer,
+                imported_resource_kind::Inner::ClusterConfiguration(_) => Kind::ClusterConfiguration,
+                imported_resource_kind::Inner::ClusterDeployment(_) => Kind::ClusterDeployment,
+            };
+            let id: Uuid = resource.id
+                .ok_or_else(|| ErrorBuilder::field_not_set("id"))?
+                .into();
+            let outcome = match resource.outcome.and_then(|outcome| outcome.inner).ok_or_else(|| ErrorBuilder::field_not_set("outcome"))? {
+                import_outcome::Inner::Created(_) => Outcome::Created,
+                import_outcome::Inner::Overwritten(_) => Outcome::Overwritten,
+                import_outcome::Inner::Skipped(_) => Outcome::Skipped,
+                import_outcome::Inner::Renamed(renamed) => {
+                    let original_id: Uuid = renamed.original_id
+                        .ok_or_else(|| ErrorBuilder::field_not_set("original_id"))?
+                        .into();
+                    Outcome::Renamed { original_id }
+                }
+                import_outcome::Inner::Failed(failed) => Outcome::Failed { cause: failed.cause },
+            };
+            Ok(crate::carl::state::ImportedResource {
+                kind,
+                id,
+                name: resource.name,
+                outcome,
+            })
+        }
+    }
+
+    impl From<ExportStateError> for ExportStateFailure {
+        fn from(error: ExportStateError) -> Self {
+            let proto_error = match error {
+                ExportStateError::Internal { cause } => {
+                    export_state_failure::Error::Internal(ExportStateFailureInternal {
+                        cause
+                    })
+                }
+            };
+            ExportStateFailure {
+                error: Some(proto_error)
+            }
+        }
+    }
+
+    impl TryFrom<ExportStateFailure> for ExportStateError {
+        type Error = ConversionError;
+        fn try_from(failure: ExportStateFailure) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<ExportStateFailure, ExportStateError>;
+            let error = failure.error
+                .ok_or_else(|| ErrorBuilder::field_not_set("error"))?;
+            let error = match error {
+                export_state_failure::Error::Internal(error) => {
+                    ExportStateError::Internal { cause: error.cause }
+                }
+            };
+            Ok(error)
+        }
+    }
+
+    impl From<ImportStateError> for ImportStateFailure {
+        fn from(error: ImportStateError) -> Self {
+            let proto_error = match error {
+                ImportStateError::UnsupportedVersion { version, supported_version } => {
+                    import_state_failure::Error::UnsupportedVersion(ImportStateFailureUnsupportedVersion {
+                        version,
+                        supported_version,
+                    })
+                }
+                ImportStateError::Internal { cause } => {
+                    import_state_failure::Error::Internal(ImportStateFailureInternal {
+                        cause
+                    })
+                }
+            };
+            ImportStateFailure {
+                error: Some(proto_error)
+            }
+        }
+    }
+
+    impl TryFrom<ImportStateFailure> for ImportStateError {
+        type Error = ConversionError;
+        fn try_from(failure: ImportStateFailure) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<ImportStateFailure, ImportStateError>;
+            let error = failure.error
+                .ok_or_else(|| ErrorBuilder::field_not_set("error"))?;
+            let error = match error {
+                import_state_failure::Error::UnsupportedVersion(error) => {
+                    ImportStateError::UnsupportedVersion { version: error.version, supported_version: error.supported_version }
+                }
+                import_state_failure::Error::Internal(error) => {
+                    ImportStateError::Internal { cause: error.cause }
+                }
+            };
+            Ok(error)
+        }
+    }
+}
+
+pub mod vpn_manager {
+    use opendut_types::proto::{ConversionError, ConversionErrorBuilder};
+
+    use crate::carl::vpn::ReconcileVpnError;
+
+    tonic::include_proto!("opendut.carl.services.vpn_manager");
+
+    impl From<ReconcileVpnError> for ReconcileVpnFailure {
+        fn from(error: ReconcileVpnError) -> Self {
+            let proto_error = match error {
+                ReconcileVpnError::VpnDisabled => {
+                    reconcile_vpn_failure::Error::VpnDisabled(ReconcileVpnFailureVpnDisabled {})
+                }
+                ReconcileVpnError::Internal { cause } => {
+                    reconcile_vpn_failure::Error::Internal(ReconcileVpnFailureInternal {
+                        cause
+                    })
+                }
+            };
+            ReconcileVpnFailure {
+                error: Some(proto_error)
+            }
+        }
+    }
+
+    impl TryFrom<ReconcileVpnFailure> for ReconcileVpnError {
+        type Error = ConversionError;
+        fn try_from(failure: ReconcileVpnFailure) -> Result<Self, Self::Error> {
+            type ErrorBuilder = ConversionErrorBuilder<ReconcileVpnFailure, ReconcileVpnError>;
+            let error = failure.error
+                .ok_or_else(|| ErrorBuilder::field_not_set("error"))?;
+            let error = match error {
+                reconcile_vpn_failure::Error::VpnDisabled(_) => {
+                    ReconcileVpnError::VpnDisabled
+                }
+                reconcile_vpn_failure::Error::Internal(error) => {
+                    ReconcileVpnError::Internal { cause: error.cause }
+                }
+            };
+            Ok(error)
+        }
+    }
+}
@@ -1,50 +1,105 @@
+use std::ops::Not;
+
 use tracing::{debug, error, info};
 pub use opendut_carl_api::carl::cluster::{
     CreateClusterConfigurationError,
     DeleteClusterConfigurationError
 };
-use opendut_types::cluster::{ClusterConfiguration, ClusterId};
+use opendut_types::cluster::{ClusterConfiguration, ClusterDeployment, ClusterId};
+use opendut_types::project::ProjectId;
 
+use crate::audit::snapshot::Snapshot;
+use crate::limits::manager::LimitsManagerRef;
+use crate::project;
+use crate::project::OwningProject;
 use crate::resources::manager::ResourcesManagerRef;
 
 pub struct CreateClusterConfigurationParams {
     pub resources_manager: ResourcesManagerRef,
+    pub limits_manager: LimitsManagerRef,
+    pub project: ProjectId,
     pub cluster_configuration: ClusterConfiguration,
 }
 
-#[tracing::instrument(skip(params), level="trace")]
 pub async fn create_cluster_configuration(params: CreateClusterConfigurationParams) -> Result<ClusterId, CreateClusterConfigurationError> {
+    let (result, _) = create_cluster_configuration_with_snapshot(params).await;
+    result
+}
 
-    async fn inner(params: CreateClusterConfigurationParams) -> Result<ClusterId, CreateClusterConfigurationError> {
+/// Stores the cluster configuration and captures the stored cluster configuration, as well as the one it replaced.
+#[tracing::instrument(skip(params), level="trace")]
+pub async fn create_cluster_configuration_with_snapshot(params: CreateClusterConfigurationParams) -> (Result<ClusterId, CreateClusterConfigurationError>, Option<Snapshot<ClusterConfiguration>>) {
+
+    async fn inner(params: CreateClusterConfigurationParams, snapshot: &mut Option<Snapshot<ClusterConfiguration>>) -> Result<ClusterId, CreateClusterConfigurationError> {
 
         let cluster_id = params.cluster_configuration.id;
         let cluster_name = Clone::clone(&params.cluster_configuration.name);
         let resources_manager = params.resources_manager;
+        let project_id = params.project;
 
-        debug!("Creating cluster configuration '{cluster_name}' <{cluster_id}>.");
+        debug!("Creating cluster configuration '{cluster_name}' <{cluster_id}> in project <{project_id}>.");
 
         resources_manager.resources_mut(|resources| {
-            resources.insert(cluster_id, params.cluster_configuration);
-        }).await;
+            let owned_by_other_project = resources.contains::<ClusterConfiguration>(cluster_id)
+                && project::is_owned_by(resources, cluster_id, project_id).not();
+            if owned_by_other_project {
+                return Err(CreateClusterConfigurationError::Internal {
+                    cluster_id,
+                    cluster_name: Clone::clone(&cluster_name),
+                    cause: format!("A cluster with this id already exists in another project than <{project_id}>."),
+                });
+            }
+            params.limits_manager.check_cluster_quota(resources, project_id, cluster_id)
+                .map_err(|cause| CreateClusterConfigurationError::QuotaExceeded {
+                    cluster_id,
+                    cluster_name: Clone::clone(&cluster_name),
+                    cause: cause.to_string(),
+                })?;
+            let before = resources.get::<ClusterConfiguration>(cluster_id);
+            let actual_revision = before.as_ref()
+                .map(|configuration| configuration.revision)
+                .unwrap_or_default();
+            let is_conditional = params.cluster_configuration.revision != 0;
+            if is_conditional && params.cluster_configuration.revision != actual_revision {
+                return Err(CreateClusterConfigurationError::RevisionConflict {
+                    cluster_id,
+                    cluster_name: Clone::clone(&cluster_name),
+                    expected_revision: params.cluster_configuration.revision,
+                    actual_revision,
+                });
+            }
+            let cluster_configuration = ClusterConfiguration {
+                revision: actual_revision + 1,
+                ..params.cluster_configuration
+            };
+            *snapshot = Some(Snapshot { before, after: Some(Clone::clone(&cluster_configuration)) });
+            resources.insert(cluster_id, cluster_configuration);
+            resources.insert(cluster_id, OwningProject(project_id));
+            Ok(())
+        }).await?;
 
         info!("Successfully created cluster configuration '{cluster_name}' <{cluster_id}>.");
 
         Ok(cluster_id)
     }
 
-    inner(params).await
-        .inspect_err(|err| error!("{err}"))
+    let mut snapshot = None;
+    let result = inner(params, &mut snapshot).await
+        .inspect_err(|err| error!("{err}"));
+    (result, snapshot)
 }
 
 pub struct DeleteClusterConfigurationParams {
     pub resources_manager: ResourcesManagerRef,
+    pub project: ProjectId,
     pub cluster_id: ClusterId,
 }
 
+/// Deletes the cluster configuration and captures the deleted cluster configuration.
 #[tracing::instrument(skip(params), level="trace")]
-pub async fn delete_cluster_configuration(params: DeleteClusterConfigurationParams) -> Result<ClusterConfiguration, DeleteClusterConfigurationError> {
+pub async fn delete_cluster_configuration_with_snapshot(params: DeleteClusterConfigurationParams) -> (Result<ClusterConfiguration, DeleteClusterConfigurationError>, Option<Snapshot<ClusterConfiguration>>) {
 
-    async fn inner(params: DeleteClusterConfigurationParams) -> Result<ClusterConfiguration, DeleteClusterConfigurationError> {
+    async fn inner(params: DeleteClusterConfigurationParams, snapshot: &mut Option<Snapshot<ClusterConfiguration>>) -> Result<ClusterConfiguration, DeleteClusterConfigurationError> {
 
         let cluster_id = params.cluster_id;
         let resources_manager = params.resources_manager;
@@ -52,8 +107,16 @@ pub async fn delete_cluster_configuration(params: DeleteClusterConfigurationPara
         debug!("Deleting cluster configuration <{cluster_id}>.");
 
         let cluster_configuration = resources_manager.resources_mut(|resources| {
-            resources.remove::<ClusterConfiguration>(cluster_id)
-                .ok_or_else(|| DeleteClusterConfigurationError::ClusterConfigurationNotFound { cluster_id })
+            if project::is_owned_by(resources, cluster_id, params.project).not() {
+                return Err(DeleteClusterConfigurationError::ClusterConfigurationNotFound { cluster_id });
+            }
+            let cluster_configuration = resources.remove::<ClusterConfiguration>(cluster_id)
+                .ok_or_else(|| DeleteClusterConfigurationError::ClusterConfigurationNotFound { cluster_id })?;
+            if resources.contains::<ClusterDeployment>(cluster_id).not() {
+                resources.remove::<OwningProject>(cluster_id);
+            }
+            *snapshot = Some(Snapshot { before: Some(Clone::clone(&cluster_configuration)), after: None });
+            Ok(cluster_configuration)
         }).await?;
 
         let cluster_name = Clone::clone(&cluster_configuration.name);
@@ -63,6 +126,8 @@ pub async fn delete_cluster_configuration(params: DeleteClusterConfigurationPara
         Ok(cluster_configuration)
     }
 
-    inner(params).await
-        .inspect_err(|err| error!("{err}"))
+    let mut snapshot = None;
+    let result = inner(params, &mut snapshot).await
+        .inspect_err(|err| error!("{err}"));
+    (result, snapshot)
 }
@@ -2,22 +2,31 @@
 
 pub use clusters::{
     create_cluster_configuration,
+    create_cluster_configuration_with_snapshot,
     CreateClusterConfigurationParams,
     CreateClusterConfigurationError,
 };
 
 pub use clusters::{
-    delete_cluster_configuration,
+    delete_cluster_configuration_with_snapshot,
     DeleteClusterConfigurationParams,
     DeleteClusterConfigurationError,
 };
 
 pub use peers::{
     store_peer_descriptor,
+    store_peer_descriptor_with_snapshot,
     StorePeerDescriptorParams,
     StorePeerDescriptorError,
 };
 
+pub use peers::{
+    update_peer_descriptor_with_snapshot,
+    UpdatePeerDescriptorParams,
+    UpdatePeerError,
+    PeerUpdate,
+};
+
 pub use peers::{
     generate_peer_setup,
     GeneratePeerSetupParams,
@@ -25,7 +34,7 @@ pub use peers::{
 };
 
 pub use peers::{
-    delete_peer_descriptor,
+    delete_peer_descriptor_with_snapshot,
     DeletePeerDescriptorParams,
     DeletePeerDescriptorError,
 };
@@ -42,11 +51,56 @@ pub use peers::{
     ListDevicesError,
 };
 
+pub use peers::{
+    list_peer_states,
+    ListPeerStatesParams,
+    ListPeerStatesError,
+};
+
+pub use peers::{
+    find_devices,
+    FindDevicesParams,
+    FindDevicesError,
+};
+
 pub use peers::{
     assign_cluster,
     AssignClusterParams,
     AssignClusterError,
 };
 
+pub use projects::{
+    create_project_with_snapshot,
+    CreateProjectParams,
+    CreateProjectError,
+};
+
+pub use projects::{
+    delete_project_with_snapshot,
+    DeleteProjectParams,
+    DeleteProjectError,
+};
+
+pub use projects::{
+    list_projects,
+    ListProjectsParams,
+    ListProjectsError,
+    project_exists,
+};
+
+pub use state::{
+    export_state,
+    ExportStateParams,
+    ExportStateError,
+};
+
+pub use state::{
+    import_state,
+    ImportStateParams,
+    ImportStateError,
+};
+
 mod peers;
 mod clusters;
+mod projects;
+mod state;
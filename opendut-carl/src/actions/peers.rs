@@ -1,3 +1,4 @@
+use std::collections::{HashMap, HashSet};
 use std::ops::Not;
 use std::sync::Arc;
 
@@ -7,47 +8,117 @@ use url::Url;
 
 pub use opendut_carl_api::carl::peer::{
     DeletePeerDescriptorError,
+    FindDevicesError,
     IllegalDevicesError,
     ListDevicesError,
     ListPeerDescriptorsError,
+    ListPeerStatesError,
     StorePeerDescriptorError,
+    UpdatePeerError,
 };
-use opendut_carl_api::proto::services::peer_messaging_broker::{ApplyPeerConfiguration, downstream};
-use opendut_types::cluster::ClusterAssignment;
+use opendut_types::cluster::{ClusterAssignment, ClusterConfiguration, ClusterDeployment, ClusterId};
 use opendut_types::peer::{PeerDescriptor, PeerId, PeerName, PeerSetup};
 use opendut_types::peer::configuration::PeerConfiguration;
+use opendut_types::peer::executor::{ContainerImage, ContainerName, ExecutorDescriptor};
+use opendut_types::project::ProjectId;
 use opendut_types::proto;
+use opendut_types::peer::state::PeerState;
 use opendut_types::topology::{DeviceDescriptor, DeviceId};
-use opendut_types::util::net::{AuthConfig, Certificate, ClientCredentials};
+use opendut_types::topology::query::{DeviceQuery, DeviceQueryContext};
+use opendut_types::util::net::{AuthConfig, Certificate, ClientCredentials, NetworkInterfaceDescriptor, NetworkInterfaceName};
 use opendut_types::vpn::VpnPeerConfiguration;
 use opendut_util::ErrorOr;
+use crate::audit::snapshot::Snapshot;
+use crate::limits::manager::LimitsManagerRef;
 use crate::peer::broker::{PeerMessagingBroker, PeerMessagingBrokerRef};
 use crate::peer::oidc_client_manager::{OAuthClientCredentials, OpenIdConnectClientManager};
+use crate::project;
+use crate::project::OwningProject;
 
 use crate::resources::manager::ResourcesManagerRef;
+use crate::secrets::manager::SecretsManagerRef;
 use crate::vpn::Vpn;
 
 pub struct StorePeerDescriptorParams {
     pub resources_manager: ResourcesManagerRef,
+    pub secrets_manager: SecretsManagerRef,
+    pub limits_manager: LimitsManagerRef,
     pub vpn: Vpn,
+    pub project: ProjectId,
     pub peer_descriptor: PeerDescriptor,
 }
 
-#[tracing::instrument(skip(params), level="trace")]
 pub async fn store_peer_descriptor(params: StorePeerDescriptorParams) -> Result<PeerId, StorePeerDescriptorError> {
+    let (result, _) = store_peer_descriptor_with_snapshot(params).await;
+    result
+}
+
+/// Stores the peer descriptor and captures the stored peer descriptor, as well as the one it replaced.
+#[tracing::instrument(skip(params), level="trace")]
+pub async fn store_peer_descriptor_with_snapshot(params: StorePeerDescriptorParams) -> (Result<PeerId, StorePeerDescriptorError>, Option<Snapshot<PeerDescriptor>>) {
 
-    async fn inner(params: StorePeerDescriptorParams) -> Result<PeerId, StorePeerDescriptorError> {
+    async fn inner(params: StorePeerDescriptorParams, snapshot: &mut Option<Snapshot<PeerDescriptor>>) -> Result<PeerId, StorePeerDescriptorError> {
 
         let peer_id = params.peer_descriptor.id;
         let peer_name = Clone::clone(&params.peer_descriptor.name);
-        let peer_descriptor = params.peer_descriptor;
         let resources_manager = params.resources_manager;
+        let project_id = params.project;
 
+        let (executors, secrets) = params.secrets_manager.conceal(peer_id, params.peer_descriptor.executors).await
+            .map_err(|cause| StorePeerDescriptorError::Internal {
+                peer_id,
+                peer_name: Clone::clone(&peer_name),
+                cause: cause.to_string()
+            })?;
+        let peer_descriptor = PeerDescriptor {
+            executors,
+            ..params.peer_descriptor
+        };
+
+        let _vpn_change = params.vpn.lock_change().await;
+
+        // The secrets are only stored, once the peer descriptor passed all checks and was stored.
         let is_new_peer = resources_manager.resources_mut(|resources| {
 
+            let owned_by_other_project = resources.contains::<PeerDescriptor>(peer_id)
+                && project::is_owned_by(resources, peer_id, project_id).not();
+            if owned_by_other_project {
+                return Err(StorePeerDescriptorError::Internal {
+                    peer_id,
+                    peer_name: Clone::clone(&peer_name),
+                    cause: format!("A peer with this id already exists in another project than <{project_id}>."),
+                });
+            }
+
+            params.limits_manager.check_peer_quota(resources, project_id, &peer_descriptor)
+                .map_err(|cause| StorePeerDescriptorError::QuotaExceeded {
+                    peer_id,
+                    peer_name: Clone::clone(&peer_name),
+                    cause: cause.to_string(),
+                })?;
+
             let old_peer_descriptor = resources.get::<PeerDescriptor>(peer_id);
             let is_new_peer = old_peer_descriptor.is_none();
 
+            let actual_revision = old_peer_descriptor.as_ref().map(|old| old.revision).unwrap_or_default();
+            let is_conditional = peer_descriptor.revision != 0;
+            if is_conditional && peer_descriptor.revision != actual_revision {
+                return Err(StorePeerDescriptorError::RevisionConflict {
+                    peer_id,
+                    peer_name: Clone::clone(&peer_name),
+                    expected_revision: peer_descriptor.revision,
+                    actual_revision,
+                });
+            }
+            let peer_descriptor = PeerDescriptor {
+                revision: actual_revision + 1,
+                ..peer_descriptor
+            };
+            *snapshot = Some(Snapshot {
+                before: Clone::clone(&old_peer_descriptor),
+                after: Some(Clone::clone(&peer_descriptor)),
+            });
+
             let (devices_to_add, devices_to_remove): (Vec<DeviceDescriptor>, Vec<DeviceDescriptor>) = if let Some(old_peer_descriptor) = old_peer_descriptor {
                 debug!("Updating peer descriptor of '{peer_name}' <{peer_id}>.\n  Old: {old_peer_descriptor:?}\n  New: {peer_descriptor:?}");
                 let devices_to_add = peer_descriptor.topology.devices.iter()
@@ -85,12 +156,15 @@ pub async fn store_peer_descriptor(params: StorePeerDescriptorParams) -> Result<
             resources.insert(peer_id, peer_configuration);
 
             resources.insert(peer_id, peer_descriptor);
+            resources.insert(peer_id, OwningProject(project_id));
 
-            is_new_peer
-        }).await;
+            Ok(is_new_peer)
+        }).await?;
+
+        params.secrets_manager.store(peer_id, secrets).await;
 
         if is_new_peer {
-            if let Vpn::Enabled { vpn_client } = params.vpn {
+            if let Vpn::Enabled { vpn_client, .. } = params.vpn {
                 debug!("Creating VPN peer <{peer_id}>.");
                 vpn_client.create_peer(peer_id)
                     .await
@@ -115,30 +189,251 @@ pub async fn store_peer_descriptor(params: StorePeerDescriptorParams) -> Result<
         Ok(peer_id)
     }
 
-    inner(params).await
-        .inspect_err(|err| error!("{err}"))
+    let mut snapshot = None;
+    let result = inner(params, &mut snapshot).await
+        .inspect_err(|err| error!("{err}"));
+    (result, snapshot)
+}
+
+/// A change of a single device, network interface or executor of a peer.
+#[derive(Clone, Debug)]
+pub enum PeerUpdate {
+    /// Adds the device or replaces the device with the same ID.
+    StoreDevice(DeviceDescriptor),
+    DeleteDevice(DeviceId),
+    /// Adds the network interface or replaces the network interface with the same name, including its use by devices.
+    StoreNetworkInterface(NetworkInterfaceDescriptor),
+    DeleteNetworkInterface(NetworkInterfaceName),
+    /// Adds the executor or replaces the container executor with the same container name.
+    StoreExecutor(ExecutorDescriptor),
+    /// Removes the container executor with the given container name.
+    /// Container executors without a name are only removed, if there is exactly one of them.
+    DeleteExecutor(ContainerName),
+}
+
+/// The state of other resources, which an update of a peer has to be validated against.
+#[derive(Debug, Default)]
+struct PeerUpdateContext {
+    /// Devices, which are used by a deployed cluster, and the respective cluster.
+    deployed_devices: HashMap<DeviceId, ClusterId>,
+    /// Devices, which belong to other peers.
+    foreign_devices: HashSet<DeviceId>,
+}
+
+impl PeerUpdate {
+
+    fn apply(self, mut peer: PeerDescriptor, context: &PeerUpdateContext) -> Result<PeerDescriptor, UpdatePeerError> {
+        let peer_id = peer.id;
+        let peer_name = Clone::clone(&peer.name);
+
+        let check_not_deployed = |device_id: DeviceId| {
+            match context.deployed_devices.get(&device_id) {
+                Some(cluster_id) => Err(UpdatePeerError::DeviceInDeployedCluster { peer_id, peer_name: Clone::clone(&peer_name), device_id, cluster_id: *cluster_id }),
+                None => Ok(()),
+            }
+        };
+
+        match self {
+            PeerUpdate::StoreDevice(mut device) => {
+                if context.foreign_devices.contains(&device.id) {
+                    return Err(UpdatePeerError::DeviceAlreadyExists { peer_id, peer_name, device_id: device.id });
+                }
+                device.interface = peer.network_configuration.interfaces.iter()
+                    .find(|interface| interface.name == device.interface.name)
+                    .cloned()
+                    .ok_or_else(|| UpdatePeerError::NetworkInterfaceNotFound { peer_id, peer_name: Clone::clone(&peer_name), interface_name: Clone::clone(&device.interface.name) })?;
+
+                match peer.topology.devices.iter_mut().find(|existing| existing.id == device.id) {
+                    Some(existing) => {
+                        if *existing != device {
+                            check_not_deployed(device.id)?;
+                        }
+                        *existing = device;
+                    }
+                    None => peer.topology.devices.push(device),
+                }
+            }
+            PeerUpdate::DeleteDevice(device_id) => {
+                if peer.topology.devices.iter().any(|device| device.id == device_id).not() {
+                    return Err(UpdatePeerError::DeviceNotFound { peer_id, peer_name, device_id });
+                }
+                check_not_deployed(device_id)?;
+                peer.topology.devices.retain(|device| device.id != device_id);
+            }
+            PeerUpdate::StoreNetworkInterface(network_interface) => {
+                for device in peer.topology.devices.iter_mut().filter(|device| device.interface.name == network_interface.name) {
+                    if device.interface != network_interface {
+                        check_not_deployed(device.id)?;
+                    }
+                    device.interface = Clone::clone(&network_interface);
+                }
+                match peer.network_configuration.interfaces.iter_mut().find(|existing| existing.name == network_interface.name) {
+                    Some(existing) => *existing = network_interface,
+                    None => peer.network_configuration.interfaces.push(network_interface),
+                }
+            }
+            PeerUpdate::DeleteNetworkInterface(interface_name) => {
+                if peer.network_configuration.interfaces.iter().any(|interface| interface.name == interface_name).not() {
+                    return Err(UpdatePeerError::NetworkInterfaceNotFound { peer_id, peer_name, interface_name });
+                }
+                let devices = peer.topology.devices.iter()
+                    .filter(|device| device.interface.name == interface_name)
+                    .map(|device| Clone::clone(&device.name))
+                    .collect::<Vec<_>>();
+                if devices.is_empty().not() {
+                    return Err(UpdatePeerError::NetworkInterfaceInUse { peer_id, peer_name, interface_name, devices });
+                }
+                peer.network_configuration.interfaces.retain(|interface| interface.name != interface_name);
+            }
+            PeerUpdate::StoreExecutor(executor) => {
+                let existing = peer.executors.executors.iter_mut()
+                    .find(|existing| match (&executor, existing) {
+                        (ExecutorDescriptor::Executable, ExecutorDescriptor::Executable) => true,
+                        (ExecutorDescriptor::Container { name: name @ ContainerName::Value(_), .. }, ExecutorDescriptor::Container { name: existing_name, .. }) => name == existing_name,
+                        _ => false,
+                    });
+                match existing {
+                    Some(existing) => *existing = executor,
+                    None => peer.executors.executors.push(executor),
+                }
+            }
+            PeerUpdate::DeleteExecutor(container_name) => {
+                let is_match = |executor: &ExecutorDescriptor| matches!(executor, ExecutorDescriptor::Container { name, .. } if *name == container_name);
+
+                match peer.executors.executors.iter().filter(|executor| is_match(executor)).count() {
+                    0 => return Err(UpdatePeerError::ExecutorNotFound { peer_id, peer_name, container_name }),
+                    1 => peer.executors.executors.retain(|executor| is_match(executor).not()),
+                    count => return Err(UpdatePeerError::ExecutorAmbiguous { peer_id, peer_name, container_name, count }),
+                }
+            }
+        }
+        Ok(peer)
+    }
+}
+
+/// How often an update is re-applied to the latest revision of a peer, which was changed concurrently.
+const MAX_UPDATE_ATTEMPTS: usize = 3;
+
+pub struct UpdatePeerDescriptorParams {
+    pub resources_manager: ResourcesManagerRef,
+    pub secrets_manager: SecretsManagerRef,
+    pub limits_manager: LimitsManagerRef,
+    pub vpn: Vpn,
+    pub project: ProjectId,
+    pub peer_id: PeerId,
+    pub update: PeerUpdate,
+}
+
+/// Updates the peer descriptor and captures it before and after the update.
+#[tracing::instrument(skip(params), level="trace")]
+pub async fn update_peer_descriptor_with_snapshot(params: UpdatePeerDescriptorParams) -> (Result<PeerDescriptor, UpdatePeerError>, Option<Snapshot<PeerDescriptor>>) {
+
+    async fn inner(params: UpdatePeerDescriptorParams, snapshot: &mut Option<Snapshot<PeerDescriptor>>) -> Result<PeerDescriptor, UpdatePeerError> {
+
+        let peer_id = params.peer_id;
+        let project_id = params.project;
+        let resources_manager = params.resources_manager;
+
+        debug!("Updating peer <{peer_id}>: {:?}", params.update);
+
+        let mut attempt = 1;
+        loop {
+            let (peer_descriptor, context) = resources_manager.resources(|resources| {
+                if project::is_owned_by(resources, peer_id, project_id).not() {
+                    return Err(UpdatePeerError::PeerNotFound { peer_id });
+                }
+                let peer_descriptor = resources.get::<PeerDescriptor>(peer_id)
+                    .ok_or(UpdatePeerError::PeerNotFound { peer_id })?;
+
+                let deployed_devices = resources.iter::<ClusterDeployment>()
+                    .filter_map(|deployment| resources.get::<ClusterConfiguration>(deployment.id))
+                    .flat_map(|configuration| {
+                        let cluster_id = configuration.id;
+                        configuration.devices.into_iter().map(move |device_id| (device_id, cluster_id))
+                    })
+                    .collect();
+                let foreign_devices = resources.iter::<PeerDescriptor>()
+                    .filter(|other| other.id != peer_id)
+                    .flat_map(|other| other.topology.devices.iter().map(|device| device.id))
+                    .collect();
+
+                Ok((peer_descriptor, PeerUpdateContext { deployed_devices, foreign_devices }))
+            }).await?;
+
+            let peer_descriptor = Clone::clone(&params.update).apply(peer_descriptor, &context)?;
+
+            let (result, store_snapshot) = store_peer_descriptor_with_snapshot(StorePeerDescriptorParams {
+                resources_manager: Arc::clone(&resources_manager),
+                secrets_manager: Arc::clone(&params.secrets_manager),
+                limits_manager: Arc::clone(&params.limits_manager),
+                vpn: Clone::clone(&params.vpn),
+                project: project_id,
+                peer_descriptor,
+            }).await;
+            if store_snapshot.is_some() {
+                *snapshot = store_snapshot;
+            }
+
+            match result {
+                Ok(_) => {
+                    return snapshot.as_ref()
+                        .and_then(|snapshot| Clone::clone(&snapshot.after))
+                        .ok_or(UpdatePeerError::PeerNotFound { peer_id });
+                }
+                Err(StorePeerDescriptorError::RevisionConflict { .. }) if attempt < MAX_UPDATE_ATTEMPTS => {
+                    debug!("Peer <{peer_id}> was changed concurrently. Applying update again.");
+                    attempt += 1;
+                }
+                Err(StorePeerDescriptorError::IllegalDevices { peer_id, peer_name, error: IllegalDevicesError::DeviceAlreadyExists { device_id } }) => {
+                    return Err(UpdatePeerError::DeviceAlreadyExists { peer_id, peer_name, device_id });
+                }
+                Err(StorePeerDescriptorError::QuotaExceeded { peer_id, peer_name, cause }) => {
+                    return Err(UpdatePeerError::QuotaExceeded { peer_id, peer_name, cause });
+                }
+                Err(cause) => {
+                    return Err(UpdatePeerError::Internal { peer_id, cause: cause.to_string() });
+                }
+            }
+        }
+    }
+
+    let mut snapshot = None;
+    let result = inner(params, &mut snapshot).await
+        .inspect_err(|err| error!("{err}"));
+    (result, snapshot)
 }
 
 pub struct DeletePeerDescriptorParams {
     pub resources_manager: ResourcesManagerRef,
+    pub secrets_manager: SecretsManagerRef,
     pub vpn: Vpn,
+    pub project: ProjectId,
     pub peer: PeerId,
 }
 
+/// Deletes the peer descriptor and captures the deleted peer descriptor.
 #[tracing::instrument(skip(params), level="trace")]
-pub async fn delete_peer_descriptor(params: DeletePeerDescriptorParams) -> Result<PeerDescriptor, DeletePeerDescriptorError> {
+pub async fn delete_peer_descriptor_with_snapshot(params: DeletePeerDescriptorParams) -> (Result<PeerDescriptor, DeletePeerDescriptorError>, Option<Snapshot<PeerDescriptor>>) {
 
-    async fn inner(params: DeletePeerDescriptorParams) -> Result<PeerDescriptor, DeletePeerDescriptorError> {
+    async fn inner(params: DeletePeerDescriptorParams, snapshot: &mut Option<Snapshot<PeerDescriptor>>) -> Result<PeerDescriptor, DeletePeerDescriptorError> {
 
         let peer_id = params.peer;
         let resources_manager = params.resources_manager;
 
         debug!("Deleting peer descriptor of peer <{peer_id}>.");
 
+        let _vpn_change = params.vpn.lock_change().await;
+
         let peer_descriptor = resources_manager.resources_mut(|resources| {
 
+            if project::is_owned_by(resources, peer_id, params.project).not() {
+                return Err(DeletePeerDescriptorError::PeerNotFound { peer_id });
+            }
+
             let peer_descriptor = resources.remove::<PeerDescriptor>(peer_id)
                 .ok_or_else(|| DeletePeerDescriptorError::PeerNotFound { peer_id })?;
+            resources.remove::<OwningProject>(peer_id);
+            *snapshot = Some(Snapshot { before: Some(Clone::clone(&peer_descriptor)), after: None });
 
             let peer_name = &peer_descriptor.name;
 
@@ -152,9 +447,11 @@ pub async fn delete_peer_descriptor(params: DeletePeerDescriptorParams) -> Resul
             Ok(peer_descriptor)
         }).await?;
 
+        params.secrets_manager.remove(peer_id).await;
+
         let peer_name = &peer_descriptor.name;
 
-        if let Vpn::Enabled { vpn_client } = params.vpn {
+        if let Vpn::Enabled { vpn_client, .. } = params.vpn {
             debug!("Deleting vpn peer <{peer_id}>.");
             vpn_client.delete_peer(peer_id)
                 .await
@@ -173,12 +470,15 @@ pub async fn delete_peer_descriptor(params: DeletePeerDescriptorParams) -> Resul
         Ok(peer_descriptor)
     }
 
-    inner(params).await
-        .inspect_err(|err| error!("{err}"))
+    let mut snapshot = None;
+    let result = inner(params, &mut snapshot).await
+        .inspect_err(|err| error!("{err}"));
+    (result, snapshot)
 }
 
 pub struct ListPeerDescriptorsParams {
     pub resources_manager: ResourcesManagerRef,
+    pub project: ProjectId,
 }
 
 #[tracing::instrument(skip(params), level="trace")]
@@ -187,16 +487,18 @@ pub async fn list_peer_descriptors(params: ListPeerDescriptorsParams) -> Result<
     async fn inner(params: ListPeerDescriptorsParams) -> Result<Vec<PeerDescriptor>, ListPeerDescriptorsError> {
 
         let resources_manager = params.resources_manager;
+        let project_id = params.project;
 
-        debug!("Querying all peer descriptors.");
+        debug!("Querying all peer descriptors of project <{project_id}>.");
 
         let peers = resources_manager.resources(|resources| {
             resources.iter::<PeerDescriptor>()
+                .filter(|peer| project::is_owned_by(resources, peer.id, project_id))
                 .cloned()
                 .collect::<Vec<PeerDescriptor>>()
         }).await;
 
-        info!("Successfully queried all peer descriptors.");
+        info!("Successfully queried all peer descriptors of project <{project_id}>.");
 
         Ok(peers)
     }
@@ -207,6 +509,7 @@ pub async fn list_peer_descriptors(params: ListPeerDescriptorsParams) -> Result<
 
 pub struct ListDevicesParams {
     pub resources_manager: ResourcesManagerRef,
+    pub project: ProjectId,
 }
 
 #[tracing::instrument(skip(params), level="trace")]
@@ -215,11 +518,15 @@ pub async fn list_devices(params: ListDevicesParams) -> Result<Vec<DeviceDescrip
     async fn inner(params: ListDevicesParams) -> Result<Vec<DeviceDescriptor>, ListDevicesError> {
 
         let resources_manager = params.resources_manager;
+        let project_id = params.project;
 
-        debug!("Querying all devices.");
+        debug!("Querying all devices of project <{project_id}>.");
 
-        let devices = resources_manager.resources(|resource| {
-            resource.iter::<DeviceDescriptor>().cloned().collect::<Vec<_>>()
+        let devices = resources_manager.resources(|resources| {
+            resources.iter::<PeerDescriptor>()
+                .filter(|peer| project::is_owned_by(resources, peer.id, project_id))
+                .flat_map(|peer| peer.topology.devices.iter().cloned())
+                .collect::<Vec<_>>()
         }).await;
 
         info!("Successfully queried all peers.");
@@ -231,8 +538,96 @@ pub async fn list_devices(params: ListDevicesParams) -> Result<Vec<DeviceDescrip
         .inspect_err(|err| error!("{err}"))
 }
 
+pub struct ListPeerStatesParams {
+    pub resources_manager: ResourcesManagerRef,
+    pub project: ProjectId,
+}
+
+#[tracing::instrument(skip(params), level="trace")]
+pub async fn list_peer_states(params: ListPeerStatesParams) -> Result<HashMap<PeerId, PeerState>, ListPeerStatesError> {
+
+    async fn inner(params: ListPeerStatesParams) -> Result<HashMap<PeerId, PeerState>, ListPeerStatesError> {
+
+        let resources_manager = params.resources_manager;
+        let project_id = params.project;
+
+        debug!("Querying the states of all peers of project <{project_id}>.");
+
+        let peer_states = resources_manager.resources(|resources| {
+            resources.iter::<PeerDescriptor>()
+                .filter(|peer| project::is_owned_by(resources, peer.id, project_id))
+                .map(|peer| {
+                    let state = resources.get::<PeerState>(peer.id).unwrap_or(PeerState::Down);
+                    (peer.id, state)
+                })
+                .collect::<HashMap<_, _>>()
+        }).await;
+
+        info!("Successfully queried the states of all peers.");
+
+        Ok(peer_states)
+    }
+
+    inner(params).await
+        .inspect_err(|err| error!("{err}"))
+}
+
+pub struct FindDevicesParams {
+    pub resources_manager: ResourcesManagerRef,
+    pub project: ProjectId,
+    pub query: DeviceQuery,
+}
+
+#[tracing::instrument(skip(params), level="trace")]
+pub async fn find_devices(params: FindDevicesParams) -> Result<Vec<DeviceDescriptor>, FindDevicesError> {
+
+    async fn inner(params: FindDevicesParams) -> Result<Vec<DeviceDescriptor>, FindDevicesError> {
+
+        let resources_manager = params.resources_manager;
+        let project_id = params.project;
+        let query = params.query;
+
+        debug!("Searching devices of project <{project_id}> matching query '{query}'.");
+
+        let devices = resources_manager.resources(|resources| {
+            let clusters = resources.iter::<ClusterConfiguration>()
+                .filter(|cluster| project::is_owned_by(resources, cluster.id, project_id))
+                .collect::<Vec<_>>();
+
+            resources.iter::<PeerDescriptor>()
+                .filter(|peer| project::is_owned_by(resources, peer.id, project_id))
+                .flat_map(|peer| {
+                    let peer_state = resources.get::<PeerState>(peer.id).unwrap_or(PeerState::Down);
+                    peer.topology.devices.iter()
+                        .filter(|device| {
+                            query.matches(&DeviceQueryContext {
+                                device,
+                                peer,
+                                peer_state: &peer_state,
+                                clusters: clusters.iter()
+                                    .filter(|cluster| cluster.devices.contains(&device.id))
+                                    .copied()
+                                    .collect(),
+                            })
+                        })
+                        .cloned()
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        }).await;
+
+        info!("Successfully found {} device(s) matching query '{query}'.", devices.len());
+
+        Ok(devices)
+    }
+
+    inner(params).await
+        .inspect_err(|err| error!("{err}"))
+}
+
 pub struct GeneratePeerSetupParams {
     pub resources_manager: ResourcesManagerRef,
+    pub project: ProjectId,
     pub peer: PeerId,
     pub carl_url: Url,
     pub ca: Pem,
@@ -261,12 +656,15 @@ pub async fn generate_peer_setup(params: GeneratePeerSetupParams) -> Result<Peer
 
         debug!("Generating PeerSetup for peer <{peer_id}>");
 
-        let peer_descriptor = params.resources_manager.get::<PeerDescriptor>(peer_id).await
+        let peer_descriptor = params.resources_manager.resources(|resources| {
+            resources.get::<PeerDescriptor>(peer_id)
+                .filter(|_| project::is_owned_by(resources, peer_id, params.project))
+        }).await
             .ok_or(GeneratePeerSetupError::PeerNotFound(peer_id))?;
 
         let peer_name = peer_descriptor.name;
 
-        let vpn_config = if let Vpn::Enabled { vpn_client } = &params.vpn {
+        let vpn_config = if let Vpn::Enabled { vpn_client, .. } = &params.vpn {
             debug!("Retrieving VPN configuration for peer <{peer_id}>.");
             let vpn_config = vpn_client.generate_vpn_peer_configuration(params.peer).await
                 .map_err(|cause| GeneratePeerSetupError::Internal { peer_id, peer_name: Clone::clone(&peer_name), cause: cause.to_string() })?;
@@ -340,12 +738,7 @@ pub async fn assign_cluster(params: AssignClusterParams) -> Result<(), AssignClu
             })
     }).await?;
 
-    params.peer_messaging_broker.send_to_peer(
-        peer_id,
-        downstream::Message::ApplyPeerConfiguration(ApplyPeerConfiguration {
-            configuration: Some(peer_configuration.into()),
-        }),
-    ).await
+    params.peer_messaging_broker.send_peer_configuration(peer_id, peer_configuration).await
     .map_err(|cause| AssignClusterError::SendingToPeerFailed {
         peer_id,
         cause: cause.to_string()
@@ -366,11 +759,14 @@ mod test {
     use opendut_types::topology::{DeviceDescription, DeviceName, Topology};
     use opendut_types::util::net::{NetworkInterfaceConfiguration, NetworkInterfaceDescriptor, NetworkInterfaceName};
 
+    use crate::limits::manager::{LimitsManager, LimitsOptions};
     use crate::resources::manager::ResourcesManager;
+    use crate::secrets::manager::{SecretsManager, SecretsManagerOptions};
 
     use super::*;
 
     mod store_peer_descriptor {
+        use opendut_types::peer::executor::{ContainerCommand, ContainerEnvironmentVariable, ContainerImage, ContainerName, Engine, ExecutorDescriptor};
         use opendut_types::topology::{DeviceDescription, DeviceName};
         use opendut_types::util::net::{NetworkInterfaceConfiguration, NetworkInterfaceDescriptor, NetworkInterfaceName};
 
@@ -384,11 +780,18 @@ mod test {
 
             store_peer_descriptor(StorePeerDescriptorParams {
                 resources_manager: Arc::clone(&resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
                 vpn: Clone::clone(&fixture.vpn),
+                project: ProjectId::DEFAULT,
                 peer_descriptor: Clone::clone(&fixture.peer_a_descriptor),
             }).await?;
 
-            assert_that!(resources_manager.get::<PeerDescriptor>(fixture.peer_a_id).await.as_ref(), some(eq(&fixture.peer_a_descriptor)));
+            let stored_descriptor = PeerDescriptor {
+                revision: 1,
+                ..Clone::clone(&fixture.peer_a_descriptor)
+            };
+            assert_that!(resources_manager.get::<PeerDescriptor>(fixture.peer_a_id).await.as_ref(), some(eq(&stored_descriptor)));
             assert_that!(resources_manager.get::<DeviceDescriptor>(fixture.peer_a_device_1).await.as_ref(), some(eq(&fixture.peer_a_descriptor.topology.devices[0])));
             assert_that!(resources_manager.get::<DeviceDescriptor>(fixture.peer_a_device_2).await.as_ref(), some(eq(&fixture.peer_a_descriptor.topology.devices[1])));
 
@@ -411,15 +814,22 @@ mod test {
                         Clone::clone(&additional_device),
                     ]
                 },
-                ..Clone::clone(&fixture.peer_a_descriptor)
+                ..Clone::clone(&stored_descriptor)
             };
 
             store_peer_descriptor(StorePeerDescriptorParams {
                 resources_manager: Arc::clone(&resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
                 vpn: Clone::clone(&fixture.vpn),
+                project: ProjectId::DEFAULT,
                 peer_descriptor: Clone::clone(&changed_descriptor),
             }).await?;
 
+            let changed_descriptor = PeerDescriptor {
+                revision: 2,
+                ..changed_descriptor
+            };
             assert_that!(resources_manager.get::<PeerDescriptor>(fixture.peer_a_id).await.as_ref(), some(eq(&changed_descriptor)));
             assert_that!(resources_manager.get(fixture.peer_a_device_1).await.as_ref(), some(eq(&fixture.peer_a_descriptor.topology.devices[0])));
             assert_that!(resources_manager.get(additional_device_id).await.as_ref(), some(eq(&additional_device)));
@@ -427,12 +837,218 @@ mod test {
 
             Ok(())
         }
+
+        #[rstest]
+        #[tokio::test]
+        async fn should_scope_peers_by_project(fixture: Fixture) -> anyhow::Result<()> {
+
+            let resources_manager = fixture.resources_manager;
+            let project_a = ProjectId::random();
+            let project_b = ProjectId::random();
+
+            store_peer_descriptor(StorePeerDescriptorParams {
+                resources_manager: Arc::clone(&resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                vpn: Clone::clone(&fixture.vpn),
+                project: project_a,
+                peer_descriptor: Clone::clone(&fixture.peer_a_descriptor),
+            }).await?;
+
+            let result = store_peer_descriptor(StorePeerDescriptorParams {
+                resources_manager: Arc::clone(&resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                vpn: Clone::clone(&fixture.vpn),
+                project: project_b,
+                peer_descriptor: Clone::clone(&fixture.peer_a_descriptor),
+            }).await;
+            assert_that!(result, err(anything()));
+
+            let peers_of_a = list_peer_descriptors(ListPeerDescriptorsParams { resources_manager: Arc::clone(&resources_manager), project: project_a }).await?;
+            assert_that!(peers_of_a, elements_are![eq(PeerDescriptor { revision: 1, ..Clone::clone(&fixture.peer_a_descriptor) })]);
+
+            let peers_of_b = list_peer_descriptors(ListPeerDescriptorsParams { resources_manager: Arc::clone(&resources_manager), project: project_b }).await?;
+            assert_that!(peers_of_b, empty());
+
+            let devices_of_b = list_devices(ListDevicesParams { resources_manager: Arc::clone(&resources_manager), project: project_b }).await?;
+            assert_that!(devices_of_b, empty());
+
+            let (result, _) = delete_peer_descriptor_with_snapshot(DeletePeerDescriptorParams {
+                resources_manager: Arc::clone(&resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                vpn: Clone::clone(&fixture.vpn),
+                project: project_b,
+                peer: fixture.peer_a_id,
+            }).await;
+            assert_that!(result, err(matches_pattern!(DeletePeerDescriptorError::PeerNotFound { .. })));
+
+            Ok(())
+        }
+
+        #[rstest]
+        #[tokio::test]
+        async fn should_reject_descriptor_based_on_outdated_revision(fixture: Fixture) -> anyhow::Result<()> {
+
+            let resources_manager = fixture.resources_manager;
+
+            store_peer_descriptor(StorePeerDescriptorParams {
+                resources_manager: Arc::clone(&resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                vpn: Clone::clone(&fixture.vpn),
+                project: ProjectId::DEFAULT,
+                peer_descriptor: Clone::clone(&fixture.peer_a_descriptor),
+            }).await?;
+
+            let unconditional_descriptor = PeerDescriptor {
+                location: PeerLocation::try_from("Stuttgart").ok(),
+                revision: 0,
+                ..Clone::clone(&fixture.peer_a_descriptor)
+            };
+            store_peer_descriptor(StorePeerDescriptorParams {
+                resources_manager: Arc::clone(&resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                vpn: Clone::clone(&fixture.vpn),
+                project: ProjectId::DEFAULT,
+                peer_descriptor: Clone::clone(&unconditional_descriptor),
+            }).await?;
+
+            let outdated_descriptor = PeerDescriptor {
+                location: PeerLocation::try_from("Munich").ok(),
+                revision: 1,
+                ..Clone::clone(&fixture.peer_a_descriptor)
+            };
+            let result = store_peer_descriptor(StorePeerDescriptorParams {
+                resources_manager: Arc::clone(&resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                vpn: Clone::clone(&fixture.vpn),
+                project: ProjectId::DEFAULT,
+                peer_descriptor: Clone::clone(&outdated_descriptor),
+            }).await;
+            assert_that!(result, err(matches_pattern!(StorePeerDescriptorError::RevisionConflict {
+                expected_revision: eq(1),
+                actual_revision: eq(2),
+            })));
+
+            let stored_descriptor = resources_manager.get::<PeerDescriptor>(fixture.peer_a_id).await;
+            assert_that!(stored_descriptor.map(|descriptor| descriptor.location), some(eq(unconditional_descriptor.location)));
+
+            Ok(())
+        }
+
+        #[rstest]
+        #[tokio::test]
+        async fn should_store_secrets_redacted(fixture: Fixture) -> anyhow::Result<()> {
+
+            let resources_manager = fixture.resources_manager;
+
+            let executors_with_envs = |envs: Vec<ContainerEnvironmentVariable>| ExecutorDescriptors {
+                executors: vec![ExecutorDescriptor::Container {
+                    engine: Engine::Podman,
+                    name: ContainerName::Empty,
+                    image: ContainerImage::try_from("testUrl").unwrap(),
+                    volumes: vec![],
+                    devices: vec![],
+                    envs,
+                    ports: vec![],
+                    command: ContainerCommand::Default,
+                    args: vec![],
+                }]
+            };
+            let plain = ContainerEnvironmentVariable::new("PLAIN", "value")?;
+            let secret = ContainerEnvironmentVariable::new_secret("SECRET", "hunter2")?;
+
+            store_peer_descriptor(StorePeerDescriptorParams {
+                resources_manager: Arc::clone(&resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                vpn: Clone::clone(&fixture.vpn),
+                project: ProjectId::DEFAULT,
+                peer_descriptor: PeerDescriptor {
+                    executors: executors_with_envs(vec![Clone::clone(&plain), Clone::clone(&secret)]),
+                    ..Clone::clone(&fixture.peer_a_descriptor)
+                },
+            }).await?;
+
+            let redacted_executors = executors_with_envs(vec![Clone::clone(&plain), Clone::clone(&secret).redacted()]);
+
+            assert_that!(
+                resources_manager.get::<PeerDescriptor>(fixture.peer_a_id).await.map(|peer| peer.executors),
+                some(eq(Clone::clone(&redacted_executors)))
+            );
+            assert_that!(
+                resources_manager.get::<PeerConfiguration>(fixture.peer_a_id).await.map(|configuration| configuration.executors),
+                some(eq(Clone::clone(&redacted_executors)))
+            );
+            assert_that!(
+                fixture.secrets_manager.reveal(fixture.peer_a_id, Clone::clone(&redacted_executors)).await,
+                ok(eq(executors_with_envs(vec![Clone::clone(&plain), Clone::clone(&secret)])))
+            );
+
+            let rejected = store_peer_descriptor(StorePeerDescriptorParams {
+                resources_manager: Arc::clone(&resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                vpn: Clone::clone(&fixture.vpn),
+                project: ProjectId::DEFAULT,
+                peer_descriptor: PeerDescriptor {
+                    executors: executors_with_envs(vec![Clone::clone(&plain), ContainerEnvironmentVariable::new_secret("SECRET", "changed")?]),
+                    revision: 5,
+                    ..Clone::clone(&fixture.peer_a_descriptor)
+                },
+            }).await;
+            assert_that!(rejected, err(matches_pattern!(StorePeerDescriptorError::RevisionConflict { .. })));
+            assert_that!(
+                fixture.secrets_manager.reveal(fixture.peer_a_id, redacted_executors).await,
+                ok(eq(executors_with_envs(vec![plain, secret])))
+            );
+
+            Ok(())
+        }
+
+        #[rstest]
+        #[tokio::test]
+        async fn should_enforce_the_peer_quota_for_concurrently_stored_peers(fixture: Fixture) -> anyhow::Result<()> {
+
+            let limits_manager = LimitsManager::new(LimitsOptions {
+                max_peers: Some(1),
+                ..LimitsOptions::default()
+            });
+            let store = |peer_descriptor: PeerDescriptor| store_peer_descriptor(StorePeerDescriptorParams {
+                resources_manager: Arc::clone(&fixture.resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: Arc::clone(&limits_manager),
+                vpn: Clone::clone(&fixture.vpn),
+                project: ProjectId::DEFAULT,
+                peer_descriptor,
+            });
+
+            let (first, second) = tokio::join!(
+                store(Clone::clone(&fixture.peer_a_descriptor)),
+                store(PeerDescriptor { id: PeerId::random(), topology: Topology::default(), ..Clone::clone(&fixture.peer_a_descriptor) }),
+            );
+
+            assert_that!(
+                vec![first.is_ok(), second.is_ok()].into_iter().filter(|stored| *stored).count(),
+                eq(1)
+            );
+            assert_that!(
+                first.err().or(second.err()),
+                some(matches_pattern!(StorePeerDescriptorError::QuotaExceeded { .. }))
+            );
+            assert_that!(fixture.resources_manager.resources(|resources| resources.iter::<PeerDescriptor>().count()).await, eq(1));
+
+            Ok(())
+        }
     }
 
     mod assign_cluster {
         use std::net::IpAddr;
         use std::str::FromStr;
-        use opendut_carl_api::proto::services::peer_messaging_broker::Pong;
+        use opendut_carl_api::proto::services::peer_messaging_broker::{ApplyPeerConfiguration, downstream, Pong};
         use opendut_types::cluster::{ClusterAssignment, ClusterId};
         use crate::peer::broker::PeerMessagingBrokerOptions;
         use super::*;
@@ -447,6 +1063,7 @@ mod test {
             let resources_manager = fixture.resources_manager;
             let peer_messaging_broker = PeerMessagingBroker::new(
                 Arc::clone(&resources_manager),
+                Arc::clone(&fixture.secrets_manager),
                 PeerMessagingBrokerOptions::load(&settings.config).unwrap(),
             );
 
@@ -509,8 +1126,273 @@ mod test {
         }
     }
 
+    mod update_peer_descriptor {
+        use opendut_types::cluster::ClusterName;
+        use opendut_types::peer::executor::{ContainerCommand, Engine};
+
+        use super::*;
+
+        #[rstest]
+        #[tokio::test]
+        async fn should_update_devices_which_are_not_used_by_a_deployed_cluster(fixture: Fixture) -> anyhow::Result<()> {
+
+            let resources_manager = fixture.resources_manager;
+
+            store_peer_descriptor(StorePeerDescriptorParams {
+                resources_manager: Arc::clone(&resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                vpn: Clone::clone(&fixture.vpn),
+                project: ProjectId::DEFAULT,
+                peer_descriptor: Clone::clone(&fixture.peer_a_descriptor),
+            }).await?;
+
+            let cluster_id = ClusterId::random();
+            resources_manager.resources_mut(|resources| {
+                resources.insert(cluster_id, ClusterConfiguration {
+                    id: cluster_id,
+                    name: ClusterName::try_from("Cluster").unwrap(),
+                    leader: fixture.peer_a_id,
+                    devices: HashSet::from([fixture.peer_a_device_2]),
+                    revision: 0,
+                });
+                resources.insert(cluster_id, ClusterDeployment::new(cluster_id));
+            }).await;
+
+            let update = |device: DeviceDescriptor| {
+                let params = UpdatePeerDescriptorParams {
+                    resources_manager: Arc::clone(&resources_manager),
+                    secrets_manager: Arc::clone(&fixture.secrets_manager),
+                    limits_manager: LimitsManager::new(LimitsOptions::default()),
+                    vpn: Clone::clone(&fixture.vpn),
+                    project: ProjectId::DEFAULT,
+                    peer_id: fixture.peer_a_id,
+                    update: PeerUpdate::StoreDevice(device),
+                };
+                async move {
+                    let (result, _) = update_peer_descriptor_with_snapshot(params).await;
+                    result
+                }
+            };
+
+            let renamed_device = DeviceDescriptor {
+                name: DeviceName::try_from("Renamed").unwrap(),
+                ..Clone::clone(&fixture.peer_a_descriptor.topology.devices[0])
+            };
+            let updated_peer = update(Clone::clone(&renamed_device)).await?;
+            assert_that!(updated_peer.revision, eq(2));
+            assert_that!(updated_peer.topology.devices, contains(eq(Clone::clone(&renamed_device))));
+            assert_that!(resources_manager.get::<DeviceDescriptor>(fixture.peer_a_device_1).await, some(eq(renamed_device)));
+
+            let deployed_device = DeviceDescriptor {
+                name: DeviceName::try_from("Renamed").unwrap(),
+                interface: Clone::clone(&fixture.peer_a_descriptor.network_configuration.interfaces[0]),
+                ..Clone::clone(&fixture.peer_a_descriptor.topology.devices[1])
+            };
+            let result = update(deployed_device).await;
+            assert_that!(result, err(matches_pattern!(UpdatePeerError::DeviceInDeployedCluster {
+                device_id: eq(fixture.peer_a_device_2),
+                cluster_id: eq(cluster_id),
+            })));
+
+            Ok(())
+        }
+
+        #[rstest]
+        #[tokio::test]
+        async fn should_report_exceeded_quotas_of_updates(fixture: Fixture) -> anyhow::Result<()> {
+
+            store_peer_descriptor(StorePeerDescriptorParams {
+                resources_manager: Arc::clone(&fixture.resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                vpn: Clone::clone(&fixture.vpn),
+                project: ProjectId::DEFAULT,
+                peer_descriptor: Clone::clone(&fixture.peer_a_descriptor),
+            }).await?;
+
+            let (result, snapshot) = update_peer_descriptor_with_snapshot(UpdatePeerDescriptorParams {
+                resources_manager: Arc::clone(&fixture.resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions {
+                    max_executors: Some(fixture.peer_a_descriptor.executors.executors.len()),
+                    ..LimitsOptions::default()
+                }),
+                vpn: Clone::clone(&fixture.vpn),
+                project: ProjectId::DEFAULT,
+                peer_id: fixture.peer_a_id,
+                update: PeerUpdate::StoreExecutor(ExecutorDescriptor::Container {
+                    engine: Engine::Docker,
+                    name: ContainerName::Value(String::from("additional")),
+                    image: ContainerImage::try_from("alpine").unwrap(),
+                    volumes: vec![],
+                    devices: vec![],
+                    envs: vec![],
+                    ports: vec![],
+                    command: ContainerCommand::Default,
+                    args: vec![],
+                }),
+            }).await;
+
+            assert_that!(result, err(matches_pattern!(UpdatePeerError::QuotaExceeded {
+                peer_id: eq(fixture.peer_a_id),
+                peer_name: anything(),
+                cause: anything(),
+            })));
+            assert_that!(snapshot, none());
+
+            Ok(())
+        }
+
+        #[rstest]
+        fn should_not_delete_network_interfaces_which_are_used_by_devices(fixture: Fixture) -> anyhow::Result<()> {
+
+            let interface_name = NetworkInterfaceName::try_from("eth0")?;
+
+            let result = PeerUpdate::DeleteNetworkInterface(Clone::clone(&interface_name))
+                .apply(Clone::clone(&fixture.peer_a_descriptor), &PeerUpdateContext::default());
+            assert_that!(result, err(matches_pattern!(UpdatePeerError::NetworkInterfaceInUse {
+                devices: elements_are![eq(DeviceName::try_from("PeerA_Device_1")?)],
+            })));
+
+            let peer = PeerUpdate::DeleteDevice(fixture.peer_a_device_1)
+                .apply(Clone::clone(&fixture.peer_a_descriptor), &PeerUpdateContext::default())?;
+            let peer = PeerUpdate::DeleteNetworkInterface(interface_name)
+                .apply(peer, &PeerUpdateContext::default())?;
+            assert_that!(peer.network_configuration.interfaces, empty());
+
+            Ok(())
+        }
+
+        #[rstest]
+        fn should_replace_container_executors_with_the_same_name(fixture: Fixture) -> anyhow::Result<()> {
+
+            let container = |name: &str, image: &str| ExecutorDescriptor::Container {
+                engine: Engine::Docker,
+                name: ContainerName::Value(String::from(name)),
+                image: ContainerImage::try_from(image).unwrap(),
+                volumes: vec![],
+                devices: vec![],
+                envs: vec![],
+                ports: vec![],
+                command: ContainerCommand::Default,
+                args: vec![],
+            };
+
+            let peer = PeerUpdate::StoreExecutor(container("test", "alpine"))
+                .apply(Clone::clone(&fixture.peer_a_descriptor), &PeerUpdateContext::default())?;
+            let peer = PeerUpdate::StoreExecutor(container("other", "alpine"))
+                .apply(peer, &PeerUpdateContext::default())?;
+            let peer = PeerUpdate::StoreExecutor(container("test", "debian"))
+                .apply(peer, &PeerUpdateContext::default())?;
+            assert_that!(peer.executors.executors, elements_are![eq(container("test", "debian")), eq(container("other", "alpine"))]);
+
+            let peer = PeerUpdate::DeleteExecutor(ContainerName::Value(String::from("other")))
+                .apply(peer, &PeerUpdateContext::default())?;
+            assert_that!(peer.executors.executors, elements_are![eq(container("test", "debian"))]);
+
+            let result = PeerUpdate::DeleteExecutor(ContainerName::Value(String::from("other")))
+                .apply(peer, &PeerUpdateContext::default());
+            assert_that!(result, err(matches_pattern!(UpdatePeerError::ExecutorNotFound { .. })));
+
+            Ok(())
+        }
+
+        #[rstest]
+        fn should_refuse_to_delete_ambiguous_container_executors(fixture: Fixture) -> anyhow::Result<()> {
+
+            let container = |name: ContainerName| ExecutorDescriptor::Container {
+                engine: Engine::Docker,
+                name,
+                image: ContainerImage::try_from("alpine").unwrap(),
+                volumes: vec![],
+                devices: vec![],
+                envs: vec![],
+                ports: vec![],
+                command: ContainerCommand::Default,
+                args: vec![],
+            };
+
+            let peer = PeerUpdate::StoreExecutor(container(ContainerName::Empty))
+                .apply(Clone::clone(&fixture.peer_a_descriptor), &PeerUpdateContext::default())?;
+            let peer = PeerUpdate::StoreExecutor(container(ContainerName::Empty))
+                .apply(peer, &PeerUpdateContext::default())?;
+            let peer = PeerUpdate::StoreExecutor(container(ContainerName::Value(String::from("test"))))
+                .apply(peer, &PeerUpdateContext::default())?;
+
+            let result = PeerUpdate::DeleteExecutor(ContainerName::Empty)
+                .apply(Clone::clone(&peer), &PeerUpdateContext::default());
+            assert_that!(result, err(matches_pattern!(UpdatePeerError::ExecutorAmbiguous { peer_id: anything(), peer_name: anything(), container_name: eq(ContainerName::Empty), count: eq(2) })));
+
+            let peer = PeerUpdate::DeleteExecutor(ContainerName::Value(String::from("test")))
+                .apply(peer, &PeerUpdateContext::default())?;
+            assert_that!(peer.executors.executors, elements_are![eq(container(ContainerName::Empty)), eq(container(ContainerName::Empty))]);
+
+            Ok(())
+        }
+    }
+
+    mod find_devices {
+        use std::net::{IpAddr, Ipv4Addr};
+        use std::str::FromStr;
+
+        use opendut_types::cluster::ClusterName;
+        use opendut_types::peer::state::PeerUpState;
+
+        use super::*;
+
+        #[rstest]
+        #[tokio::test]
+        async fn should_find_devices_matching_the_query(fixture: Fixture) -> anyhow::Result<()> {
+
+            let resources_manager = fixture.resources_manager;
+
+            store_peer_descriptor(StorePeerDescriptorParams {
+                resources_manager: Arc::clone(&resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                vpn: Clone::clone(&fixture.vpn),
+                project: ProjectId::DEFAULT,
+                peer_descriptor: Clone::clone(&fixture.peer_a_descriptor),
+            }).await?;
+
+            let cluster_id = ClusterId::random();
+            resources_manager.resources_mut(|resources| {
+                resources.insert(cluster_id, ClusterConfiguration {
+                    id: cluster_id,
+                    name: ClusterName::try_from("Cluster").unwrap(),
+                    leader: fixture.peer_a_id,
+                    devices: HashSet::from([fixture.peer_a_device_2]),
+                    revision: 0,
+                });
+                resources.insert(fixture.peer_a_id, PeerState::Up {
+                    inner: PeerUpState::Available,
+                    remote_host: IpAddr::V4(Ipv4Addr::LOCALHOST),
+                });
+            }).await;
+
+            let find = |query: &str| find_devices(FindDevicesParams {
+                resources_manager: Arc::clone(&resources_manager),
+                project: ProjectId::DEFAULT,
+                query: DeviceQuery::from_str(query).unwrap(),
+            });
+
+            let devices = find("peer.name=PeerA and peer.state=available and cluster=none").await?;
+            assert_that!(devices, elements_are![matches_pattern!(DeviceDescriptor { id: eq(fixture.peer_a_device_1) })]);
+
+            let devices = find("interface=eth* and cluster=cluster").await?;
+            assert_that!(devices, elements_are![matches_pattern!(DeviceDescriptor { id: eq(fixture.peer_a_device_2) })]);
+
+            let devices = find("type=can or peer.state=down").await?;
+            assert_that!(devices, empty());
+
+            Ok(())
+        }
+    }
+
     struct Fixture {
         resources_manager: ResourcesManagerRef,
+        secrets_manager: SecretsManagerRef,
         vpn: Vpn,
         peer_a_id: PeerId,
         peer_a_descriptor: PeerDescriptor,
@@ -561,10 +1443,12 @@ mod test {
             },
             executors: ExecutorDescriptors {
                 executors: vec![],
-            }
+            },
+            revision: 0,
         };
         Fixture {
             resources_manager: ResourcesManager::new(),
+            secrets_manager: SecretsManager::new(SecretsManagerOptions { key: None }),
             vpn: Vpn::Disabled,
             peer_a_id,
             peer_a_descriptor,
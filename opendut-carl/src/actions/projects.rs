@@ -0,0 +1,211 @@
+use std::ops::Not;
+
+use tracing::{debug, error, info};
+
+pub use opendut_carl_api::carl::project::{
+    CreateProjectError,
+    DeleteProjectError,
+    ListProjectsError,
+};
+use opendut_types::cluster::ClusterConfiguration;
+use opendut_types::peer::PeerDescriptor;
+use opendut_types::project::{ProjectDescriptor, ProjectId};
+
+use crate::audit::snapshot::Snapshot;
+use crate::project;
+use crate::resources::manager::ResourcesManagerRef;
+
+pub struct CreateProjectParams {
+    pub resources_manager: ResourcesManagerRef,
+    pub project: ProjectDescriptor,
+}
+
+/// Creates the project and captures the created project.
+#[tracing::instrument(skip(params), level="trace")]
+pub async fn create_project_with_snapshot(params: CreateProjectParams) -> (Result<ProjectId, CreateProjectError>, Option<Snapshot<ProjectDescriptor>>) {
+
+    async fn inner(params: CreateProjectParams, snapshot: &mut Option<Snapshot<ProjectDescriptor>>) -> Result<ProjectId, CreateProjectError> {
+
+        let project_id = params.project.id;
+        let project_name = Clone::clone(&params.project.name);
+        let resources_manager = params.resources_manager;
+
+        debug!("Creating project '{project_name}' <{project_id}>.");
+
+        resources_manager.resources_mut(|resources| {
+            let already_exists = project_id.is_default()
+                || project_name == ProjectDescriptor::default_project().name
+                || resources.iter::<ProjectDescriptor>()
+                    .any(|project| project.id == project_id || project.name == project_name);
+
+            if already_exists {
+                Err(CreateProjectError::ProjectAlreadyExists { project_id, project_name: Clone::clone(&project_name) })
+            } else {
+                *snapshot = Some(Snapshot { before: None, after: Some(Clone::clone(&params.project)) });
+                resources.insert(project_id, params.project);
+                Ok(())
+            }
+        }).await?;
+
+        info!("Successfully created project '{project_name}' <{project_id}>.");
+
+        Ok(project_id)
+    }
+
+    let mut snapshot = None;
+    let result = inner(params, &mut snapshot).await
+        .inspect_err(|err| error!("{err}"));
+    (result, snapshot)
+}
+
+pub struct DeleteProjectParams {
+    pub resources_manager: ResourcesManagerRef,
+    pub project_id: ProjectId,
+}
+
+/// Deletes the project and captures the deleted project.
+#[tracing::instrument(skip(params), level="trace")]
+pub async fn delete_project_with_snapshot(params: DeleteProjectParams) -> (Result<ProjectDescriptor, DeleteProjectError>, Option<Snapshot<ProjectDescriptor>>) {
+
+    async fn inner(params: DeleteProjectParams, snapshot: &mut Option<Snapshot<ProjectDescriptor>>) -> Result<ProjectDescriptor, DeleteProjectError> {
+
+        let project_id = params.project_id;
+        let resources_manager = params.resources_manager;
+
+        if project_id.is_default() {
+            return Err(DeleteProjectError::DefaultProject);
+        }
+
+        debug!("Deleting project <{project_id}>.");
+
+        let project = resources_manager.resources_mut(|resources| {
+            let project = resources.get::<ProjectDescriptor>(project_id)
+                .ok_or(DeleteProjectError::ProjectNotFound { project_id })?;
+
+            let is_empty = resources.iter::<PeerDescriptor>()
+                .all(|peer| project::is_owned_by(resources, peer.id, project_id).not())
+                && resources.iter::<ClusterConfiguration>()
+                    .all(|cluster| project::is_owned_by(resources, cluster.id, project_id).not());
+
+            if is_empty {
+                resources.remove::<ProjectDescriptor>(project_id);
+                *snapshot = Some(Snapshot { before: Some(Clone::clone(&project)), after: None });
+                Ok(project)
+            } else {
+                Err(DeleteProjectError::ProjectNotEmpty { project_id, project_name: project.name })
+            }
+        }).await?;
+
+        info!("Successfully deleted project '{}' <{project_id}>.", project.name);
+
+        Ok(project)
+    }
+
+    let mut snapshot = None;
+    let result = inner(params, &mut snapshot).await
+        .inspect_err(|err| error!("{err}"));
+    (result, snapshot)
+}
+
+pub struct ListProjectsParams {
+    pub resources_manager: ResourcesManagerRef,
+}
+
+/// Lists all projects, starting with the default project, which always exists.
+#[tracing::instrument(skip(params), level="trace")]
+pub async fn list_projects(params: ListProjectsParams) -> Result<Vec<ProjectDescriptor>, ListProjectsError> {
+
+    async fn inner(params: ListProjectsParams) -> Result<Vec<ProjectDescriptor>, ListProjectsError> {
+
+        let resources_manager = params.resources_manager;
+
+        debug!("Querying all projects.");
+
+        let projects = resources_manager.resources(|resources| {
+            let mut projects = resources.iter::<ProjectDescriptor>()
+                .cloned()
+                .collect::<Vec<_>>();
+            projects.sort_by_key(|project| project.name.to_string());
+            projects.insert(0, ProjectDescriptor::default_project());
+            projects
+        }).await;
+
+        info!("Successfully queried all projects.");
+
+        Ok(projects)
+    }
+
+    inner(params).await
+        .inspect_err(|err| error!("{err}"))
+}
+
+/// Returns whether the given project exists.
+pub async fn project_exists(resources_manager: &ResourcesManagerRef, project_id: ProjectId) -> bool {
+    project_id.is_default()
+        || resources_manager.contains::<ProjectDescriptor>(project_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use googletest::prelude::*;
+
+    use opendut_types::cluster::{ClusterId, ClusterName};
+    use opendut_types::peer::PeerId;
+    use opendut_types::project::ProjectName;
+
+    use crate::project::OwningProject;
+    use crate::resources::manager::ResourcesManager;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_create_list_and_delete_projects() -> Result<()> {
+        let resources_manager = ResourcesManager::new();
+        let project = ProjectDescriptor {
+            id: ProjectId::random(),
+            name: ProjectName::try_from("team-a")?,
+        };
+
+        let (result, snapshot) = create_project_with_snapshot(CreateProjectParams {
+            resources_manager: Arc::clone(&resources_manager),
+            project: Clone::clone(&project),
+        }).await;
+        result?;
+        verify_that!(snapshot, some(matches_pattern!(Snapshot { before: none(), after: some(eq(Clone::clone(&project))) })))?;
+
+        let (duplicate, snapshot) = create_project_with_snapshot(CreateProjectParams {
+            resources_manager: Arc::clone(&resources_manager),
+            project: ProjectDescriptor { id: ProjectId::random(), name: Clone::clone(&project.name) },
+        }).await;
+        verify_that!(duplicate, err(matches_pattern!(CreateProjectError::ProjectAlreadyExists { .. })))?;
+        verify_that!(snapshot, none())?;
+
+        let projects = list_projects(ListProjectsParams { resources_manager: Arc::clone(&resources_manager) }).await?;
+        verify_that!(projects, elements_are![eq(ProjectDescriptor::default_project()), eq(Clone::clone(&project))])?;
+
+        let cluster_id = ClusterId::random();
+        resources_manager.resources_mut(|resources| {
+            resources.insert(cluster_id, ClusterConfiguration {
+                id: cluster_id,
+                name: ClusterName::try_from("cluster").unwrap(),
+                leader: PeerId::random(),
+                devices: Default::default(),
+                revision: 0,
+            });
+            resources.insert(cluster_id, OwningProject(project.id));
+        }).await;
+
+        let (not_empty, _) = delete_project_with_snapshot(DeleteProjectParams { resources_manager: Arc::clone(&resources_manager), project_id: project.id }).await;
+        verify_that!(not_empty, err(matches_pattern!(DeleteProjectError::ProjectNotEmpty { .. })))?;
+
+        resources_manager.remove::<ClusterConfiguration>(cluster_id).await;
+        let (result, snapshot) = delete_project_with_snapshot(DeleteProjectParams { resources_manager: Arc::clone(&resources_manager), project_id: project.id }).await;
+        result?;
+        verify_that!(snapshot, some(matches_pattern!(Snapshot { before: some(eq(Clone::clone(&project))), after: none() })))?;
+
+        let (default, _) = delete_project_with_snapshot(DeleteProjectParams { resources_manager, project_id: ProjectId::DEFAULT }).await;
+        verify_that!(default, err(matches_pattern!(DeleteProjectError::DefaultProject)))
+    }
+}
@@ -0,0 +1,502 @@
+use std::collections::HashMap;
+use std::ops::Not;
+use std::sync::Arc;
+
+use chrono::Utc;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+pub use opendut_carl_api::carl::state::{
+    ExportStateError,
+    ImportOutcome,
+    ImportStateError,
+    ImportStrategy,
+    ImportedResource,
+    ImportedResourceKind,
+    STATE_ARCHIVE_VERSION,
+    StateArchive,
+};
+use opendut_types::cluster::{ClusterConfiguration, ClusterDeployment, ClusterId};
+use opendut_types::peer::{PeerDescriptor, PeerId};
+use opendut_types::project::ProjectId;
+use opendut_types::topology::{DeviceDescriptor, DeviceId};
+
+use crate::actions;
+use crate::actions::{CreateClusterConfigurationParams, StorePeerDescriptorParams};
+use crate::cluster::manager::ClusterManagerRef;
+use crate::limits::manager::LimitsManagerRef;
+use crate::project;
+use crate::resources::manager::ResourcesManagerRef;
+use crate::resources::Resources;
+use crate::secrets::manager::SecretsManagerRef;
+use crate::vpn::Vpn;
+
+pub struct ExportStateParams {
+    pub resources_manager: ResourcesManagerRef,
+    pub project: ProjectId,
+}
+
+/// Exports the peers, clusters and deployments of the project. Secret values of executors stay redacted.
+#[tracing::instrument(skip(params), level="trace")]
+pub async fn export_state(params: ExportStateParams) -> Result<StateArchive, ExportStateError> {
+
+    async fn inner(params: ExportStateParams) -> Result<StateArchive, ExportStateError> {
+
+        let resources_manager = params.resources_manager;
+        let project_id = params.project;
+
+        debug!("Exporting state of project <{project_id}>.");
+
+        let archive = resources_manager.resources(|resources| {
+            let mut peers = resources.iter::<PeerDescriptor>()
+                .filter(|peer| project::is_owned_by(resources, peer.id, project_id))
+                .cloned()
+                .collect::<Vec<_>>();
+            peers.sort_by_key(|peer| peer.name.to_string());
+
+            let mut clusters = resources.iter::<ClusterConfiguration>()
+                .filter(|cluster| project::is_owned_by(resources, cluster.id, project_id))
+                .cloned()
+                .collect::<Vec<_>>();
+            clusters.sort_by_key(|cluster| cluster.name.to_string());
+
+            let mut deployments = resources.iter::<ClusterDeployment>()
+                .filter(|deployment| project::is_owned_by(resources, deployment.id, project_id))
+                .cloned()
+                .collect::<Vec<_>>();
+            deployments.sort_by_key(|deployment| deployment.id.to_string());
+
+            StateArchive {
+                version: STATE_ARCHIVE_VERSION,
+                exported_at: Utc::now(),
+                peers,
+                clusters,
+                deployments,
+            }
+        }).await;
+
+        info!("Successfully exported {} peer(s), {} cluster(s) and {} deployment(s) of project <{project_id}>.", archive.peers.len(), archive.clusters.len(), archive.deployments.len());
+
+        Ok(archive)
+    }
+
+    inner(params).await
+        .inspect_err(|err| error!("{err}"))
+}
+
+pub struct ImportStateParams {
+    pub resources_manager: ResourcesManagerRef,
+    pub secrets_manager: SecretsManagerRef,
+    pub cluster_manager: ClusterManagerRef,
+    pub limits_manager: LimitsManagerRef,
+    pub vpn: Vpn,
+    pub project: ProjectId,
+    pub archive: StateArchive,
+    pub strategy: ImportStrategy,
+}
+
+/// Imports the peers, clusters and deployments of the archive into the project.
+///
+/// Resources are imported one after another, so that the failure of one resource does not prevent importing the others.
+/// The outcome of each resource is reported.
+///
+/// Archives do not contain the values of secret environment variables. Secrets, whose values are not stored in CARL
+/// for the imported peer, are removed from its executors, e.g. when importing into another instance or under a new ID.
+#[tracing::instrument(skip(params), level="trace")]
+pub async fn import_state(params: ImportStateParams) -> Result<Vec<ImportedResource>, ImportStateError> {
+
+    async fn inner(params: ImportStateParams) -> Result<Vec<ImportedResource>, ImportStateError> {
+
+        let resources_manager = params.resources_manager;
+        let project_id = params.project;
+        let strategy = params.strategy;
+
+        if params.archive.version != STATE_ARCHIVE_VERSION {
+            return Err(ImportStateError::UnsupportedVersion {
+                version: params.archive.version,
+                supported_version: STATE_ARCHIVE_VERSION,
+            });
+        }
+
+        debug!("Importing state into project <{project_id}> with strategy '{strategy}'.");
+
+        let renames = match strategy {
+            ImportStrategy::RenameIds => resources_manager.resources(|resources| Renames::of_conflicts(resources, &params.archive)).await,
+            ImportStrategy::Skip | ImportStrategy::Overwrite => Renames::default(),
+        };
+        let archive = renames.apply(params.archive);
+
+        let mut report = Vec::new();
+
+        for peer in archive.peers {
+            let peer_id = peer.id;
+            let name = peer.name.to_string();
+            let existing = resources_manager.get::<PeerDescriptor>(peer_id).await;
+
+            let outcome = match (existing, strategy) {
+                (Some(_), ImportStrategy::Skip | ImportStrategy::RenameIds) => ImportOutcome::Skipped,
+                (existing, _) => {
+                    let revision = existing.as_ref().map(|existing| existing.revision).unwrap_or_default();
+                    let (executors, removed_secrets) = params.secrets_manager.remove_unknown(peer_id, peer.executors).await;
+                    if removed_secrets.is_empty().not() {
+                        warn!("Removed secret environment variables without known value from imported peer <{peer_id}>: {}", removed_secrets.join(", "));
+                    }
+                    let peer = PeerDescriptor { revision, executors, ..peer };
+
+                    let result = actions::store_peer_descriptor(StorePeerDescriptorParams {
+                        resources_manager: Arc::clone(&resources_manager),
+                        secrets_manager: Arc::clone(&params.secrets_manager),
+                        limits_manager: Arc::clone(&params.limits_manager),
+                        vpn: Clone::clone(&params.vpn),
+                        project: project_id,
+                        peer_descriptor: peer,
+                    }).await.map_err(|error| error.to_string());
+                    renames.outcome(peer_id.0, existing.is_some(), result)
+                }
+            };
+            report.push(ImportedResource { kind: ImportedResourceKind::Peer, id: peer_id.0, name, outcome });
+        }
+
+        let cluster_names = archive.clusters.iter()
+            .map(|cluster| (cluster.id, cluster.name.to_string()))
+            .collect::<HashMap<_, _>>();
+
+        for cluster in archive.clusters {
+            let cluster_id = cluster.id;
+            let name = cluster.name.to_string();
+            let existing = resources_manager.get::<ClusterConfiguration>(cluster_id).await;
+
+            let outcome = match (existing, strategy) {
+                (Some(_), ImportStrategy::Skip | ImportStrategy::RenameIds) => ImportOutcome::Skipped,
+                (existing, _) => {
+                    let revision = existing.as_ref().map(|existing| existing.revision).unwrap_or_default();
+                    let cluster = ClusterConfiguration { revision, ..cluster };
+
+                    let result = actions::create_cluster_configuration(CreateClusterConfigurationParams {
+                        resources_manager: Arc::clone(&resources_manager),
+                        limits_manager: Arc::clone(&params.limits_manager),
+                        project: project_id,
+                        cluster_configuration: cluster,
+                    }).await.map_err(|error| error.to_string());
+                    renames.outcome(cluster_id.0, existing.is_some(), result)
+                }
+            };
+            report.push(ImportedResource { kind: ImportedResourceKind::ClusterConfiguration, id: cluster_id.0, name, outcome });
+        }
+
+        for deployment in archive.deployments {
+            let cluster_id = deployment.id;
+            let name = cluster_names.get(&cluster_id).cloned()
+                .unwrap_or_else(|| cluster_id.to_string());
+            let exists = resources_manager.contains::<ClusterDeployment>(cluster_id).await;
+
+            let outcome = match (exists, strategy) {
+                (true, ImportStrategy::Skip | ImportStrategy::RenameIds) => ImportOutcome::Skipped,
+                (exists, _) => {
+                    let result = if params.cluster_manager.is_accessible_from(project_id, cluster_id).await {
+                        params.cluster_manager.store_cluster_deployment(project_id, deployment).await
+                            .map_err(|error| error.to_string())
+                    } else {
+                        Err(format!("Cluster <{cluster_id}> belongs to another project than <{project_id}>."))
+                    };
+                    renames.outcome(cluster_id.0, exists, result)
+                }
+            };
+            report.push(ImportedResource { kind: ImportedResourceKind::ClusterDeployment, id: cluster_id.0, name, outcome });
+        }
+
+        info!("Successfully imported state into project <{project_id}>.");
+
+        Ok(report)
+    }
+
+    inner(params).await
+        .inspect_err(|err| error!("{err}"))
+}
+
+/// New ids for the resources of an archive, whose ids already exist in CARL, mapped from their original ids.
+#[derive(Default)]
+struct Renames {
+    peers: HashMap<PeerId, PeerId>,
+    devices: HashMap<DeviceId, DeviceId>,
+    clusters: HashMap<ClusterId, ClusterId>,
+}
+
+impl Renames {
+    fn of_conflicts(resources: &Resources, archive: &StateArchive) -> Self {
+        let peers = archive.peers.iter()
+            .filter(|peer| resources.contains::<PeerDescriptor>(peer.id))
+            .map(|peer| (peer.id, PeerId::random()))
+            .collect();
+        let devices = archive.peers.iter()
+            .flat_map(|peer| &peer.topology.devices)
+            .filter(|device| resources.contains::<DeviceDescriptor>(device.id))
+            .map(|device| (device.id, DeviceId::random()))
+            .collect();
+        let clusters = archive.clusters.iter().map(|cluster| cluster.id)
+            .chain(archive.deployments.iter().map(|deployment| deployment.id))
+            .filter(|cluster_id| resources.contains::<ClusterConfiguration>(*cluster_id) || resources.contains::<ClusterDeployment>(*cluster_id))
+            .map(|cluster_id| (cluster_id, ClusterId::random()))
+            .collect();
+        Self { peers, devices, clusters }
+    }
+
+    /// Replaces the ids of the archive's resources and all references to them.
+    fn apply(&self, archive: StateArchive) -> StateArchive {
+        let peer = |id: PeerId| self.peers.get(&id).copied().unwrap_or(id);
+        let device = |id: DeviceId| self.devices.get(&id).copied().unwrap_or(id);
+        let cluster = |id: ClusterId| self.clusters.get(&id).copied().unwrap_or(id);
+
+        let peers = archive.peers.into_iter()
+            .map(|mut descriptor| {
+                descriptor.id = peer(descriptor.id);
+                for device_descriptor in &mut descriptor.topology.devices {
+                    device_descriptor.id = device(device_descriptor.id);
+                }
+                descriptor
+            })
+            .collect();
+        let clusters = archive.clusters.into_iter()
+            .map(|configuration| ClusterConfiguration {
+                id: cluster(configuration.id),
+                leader: peer(configuration.leader),
+                devices: configuration.devices.into_iter().map(device).collect(),
+                ..configuration
+            })
+            .collect();
+        let deployments = archive.deployments.into_iter()
+            .map(|deployment| ClusterDeployment {
+                id: cluster(deployment.id),
+                ..deployment
+            })
+            .collect();
+
+        StateArchive { peers, clusters, deployments, ..archive }
+    }
+
+    fn original_id(&self, id: Uuid) -> Option<Uuid> {
+        self.peers.iter().find(|(_, renamed)| renamed.0 == id).map(|(original, _)| original.0)
+            .or_else(|| self.clusters.iter().find(|(_, renamed)| renamed.0 == id).map(|(original, _)| original.0))
+    }
+
+    fn outcome<T>(&self, id: Uuid, existed: bool, result: Result<T, String>) -> ImportOutcome {
+        match result {
+            Err(cause) => ImportOutcome::Failed { cause },
+            Ok(_) if existed => ImportOutcome::Overwritten,
+            Ok(_) => match self.original_id(id) {
+                Some(original_id) => ImportOutcome::Renamed { original_id },
+                None => ImportOutcome::Created,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+
+    use opendut_types::cluster::ClusterName;
+    use opendut_types::peer::{PeerName, PeerNetworkConfiguration};
+    use opendut_types::peer::executor::{ContainerCommand, ContainerEnvironmentVariable, ContainerImage, ContainerName, Engine, ExecutorDescriptor, ExecutorDescriptors};
+    use opendut_types::topology::{DeviceName, Topology};
+    use opendut_types::util::net::{NetworkInterfaceConfiguration, NetworkInterfaceDescriptor, NetworkInterfaceName};
+
+    use crate::cluster::manager::{ClusterManager, ClusterManagerOptions};
+    use crate::limits::manager::{LimitsManager, LimitsOptions};
+    use crate::peer::broker::{PeerMessagingBroker, PeerMessagingBrokerOptions};
+    use crate::resources::manager::ResourcesManager;
+    use crate::secrets::manager::{SecretsManager, SecretsManagerOptions};
+    use crate::settings;
+
+    use super::*;
+
+    fn peer(name: &str) -> PeerDescriptor {
+        let interface = NetworkInterfaceDescriptor {
+            name: NetworkInterfaceName::try_from("eth0").unwrap(),
+            configuration: NetworkInterfaceConfiguration::Ethernet,
+        };
+        PeerDescriptor {
+            id: PeerId::random(),
+            name: PeerName::try_from(name).unwrap(),
+            location: None,
+            network_configuration: PeerNetworkConfiguration::new(vec![Clone::clone(&interface)]),
+            topology: Topology {
+                devices: vec![DeviceDescriptor {
+                    id: DeviceId::random(),
+                    name: DeviceName::try_from(format!("{name}-device")).unwrap(),
+                    description: None,
+                    interface,
+                    tags: vec![],
+                }],
+            },
+            executors: ExecutorDescriptors { executors: vec![] },
+            revision: 0,
+        }
+    }
+
+    fn import(resources_manager: &ResourcesManagerRef, archive: &StateArchive, strategy: ImportStrategy) -> ImportStateParams {
+        import_with_secrets(resources_manager, &SecretsManager::new(SecretsManagerOptions { key: None }), archive, strategy)
+    }
+
+    fn import_with_secrets(resources_manager: &ResourcesManagerRef, secrets_manager: &SecretsManagerRef, archive: &StateArchive, strategy: ImportStrategy) -> ImportStateParams {
+        let settings = settings::load_defaults().unwrap();
+        let peer_messaging_broker = PeerMessagingBroker::new(
+            Arc::clone(resources_manager),
+            Arc::clone(secrets_manager),
+            PeerMessagingBrokerOptions::load(&settings.config).unwrap(),
+        );
+        ImportStateParams {
+            resources_manager: Arc::clone(resources_manager),
+            secrets_manager: Arc::clone(secrets_manager),
+            cluster_manager: ClusterManager::new(
+                Arc::clone(resources_manager),
+                peer_messaging_broker,
+                Vpn::Disabled,
+                ClusterManagerOptions::load(&settings.config).unwrap(),
+            ),
+            limits_manager: LimitsManager::new(LimitsOptions::default()),
+            vpn: Vpn::Disabled,
+            project: ProjectId::DEFAULT,
+            archive: Clone::clone(archive),
+            strategy,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_import_an_exported_state_into_another_instance() -> Result<()> {
+        let source = ResourcesManager::new();
+        let peer = peer("bench-a");
+        let cluster = ClusterConfiguration {
+            id: ClusterId::random(),
+            name: ClusterName::try_from("bench").unwrap(),
+            leader: peer.id,
+            devices: [peer.topology.devices[0].id].into(),
+            revision: 0,
+        };
+        let archive = StateArchive {
+            version: STATE_ARCHIVE_VERSION,
+            exported_at: Utc::now(),
+            peers: vec![Clone::clone(&peer)],
+            clusters: vec![Clone::clone(&cluster)],
+            deployments: vec![],
+        };
+        import_state(import(&source, &archive, ImportStrategy::Skip)).await?;
+
+        let exported = export_state(ExportStateParams { resources_manager: Arc::clone(&source), project: ProjectId::DEFAULT }).await?;
+        verify_that!(exported.peers, elements_are![matches_pattern!(PeerDescriptor { id: eq(peer.id), revision: eq(1) })])?;
+        verify_that!(exported.clusters, elements_are![matches_pattern!(ClusterConfiguration { id: eq(cluster.id) })])?;
+
+        let target = ResourcesManager::new();
+        let report = import_state(import(&target, &exported, ImportStrategy::Skip)).await?;
+        verify_that!(report, each(matches_pattern!(ImportedResource { outcome: eq(ImportOutcome::Created) })))?;
+        verify_that!(target.get::<ClusterConfiguration>(cluster.id).await, some(matches_pattern!(ClusterConfiguration { leader: eq(peer.id) })))?;
+
+        let report = import_state(import(&target, &exported, ImportStrategy::Skip)).await?;
+        verify_that!(report, each(matches_pattern!(ImportedResource { outcome: eq(ImportOutcome::Skipped) })))?;
+
+        let report = import_state(import(&target, &exported, ImportStrategy::Overwrite)).await?;
+        verify_that!(report, each(matches_pattern!(ImportedResource { outcome: eq(ImportOutcome::Overwritten) })))?;
+        verify_that!(target.get::<PeerDescriptor>(peer.id).await, some(matches_pattern!(PeerDescriptor { revision: eq(2) })))
+    }
+
+    #[tokio::test]
+    async fn should_rename_conflicting_ids_and_their_references() -> Result<()> {
+        let resources_manager = ResourcesManager::new();
+        let peer = peer("bench-a");
+        let device_id = peer.topology.devices[0].id;
+        let cluster = ClusterConfiguration {
+            id: ClusterId::random(),
+            name: ClusterName::try_from("bench").unwrap(),
+            leader: peer.id,
+            devices: [device_id].into(),
+            revision: 0,
+        };
+        let archive = StateArchive {
+            version: STATE_ARCHIVE_VERSION,
+            exported_at: Utc::now(),
+            peers: vec![Clone::clone(&peer)],
+            clusters: vec![Clone::clone(&cluster)],
+            deployments: vec![],
+        };
+        import_state(import(&resources_manager, &archive, ImportStrategy::Skip)).await?;
+
+        let report = import_state(import(&resources_manager, &archive, ImportStrategy::RenameIds)).await?;
+
+        verify_that!(report, elements_are![
+            matches_pattern!(ImportedResource { kind: eq(ImportedResourceKind::Peer), outcome: eq(ImportOutcome::Renamed { original_id: peer.id.0 }) }),
+            matches_pattern!(ImportedResource { kind: eq(ImportedResourceKind::ClusterConfiguration), outcome: eq(ImportOutcome::Renamed { original_id: cluster.id.0 }) }),
+        ])?;
+        let renamed_peer = resources_manager.get::<PeerDescriptor>(PeerId(report[0].id)).await.unwrap();
+        let renamed_cluster = resources_manager.get::<ClusterConfiguration>(ClusterId(report[1].id)).await.unwrap();
+        verify_that!(renamed_cluster.leader, eq(renamed_peer.id))?;
+        verify_that!(renamed_cluster.devices, elements_are![eq(renamed_peer.topology.devices[0].id)])?;
+        verify_that!(renamed_peer.topology.devices[0].id, not(eq(device_id)))
+    }
+
+    #[tokio::test]
+    async fn should_remove_secrets_without_known_value_when_importing() -> Result<()> {
+        let container = |envs: Vec<ContainerEnvironmentVariable>| ExecutorDescriptors {
+            executors: vec![ExecutorDescriptor::Container {
+                engine: Engine::Podman,
+                name: ContainerName::try_from("app").unwrap(),
+                image: ContainerImage::try_from("app:latest").unwrap(),
+                volumes: vec![],
+                devices: vec![],
+                envs,
+                ports: vec![],
+                command: ContainerCommand::Default,
+                args: vec![],
+            }]
+        };
+        let plain = ContainerEnvironmentVariable::new("PLAIN", "value").unwrap();
+        let secret = ContainerEnvironmentVariable::new_secret("SECRET", "hunter2").unwrap();
+        let peer = PeerDescriptor {
+            executors: container(vec![Clone::clone(&plain), Clone::clone(&secret)]),
+            ..peer("bench-a")
+        };
+
+        let source = ResourcesManager::new();
+        let source_secrets = SecretsManager::new(SecretsManagerOptions { key: None });
+        let archive = StateArchive {
+            version: STATE_ARCHIVE_VERSION,
+            exported_at: Utc::now(),
+            peers: vec![Clone::clone(&peer)],
+            clusters: vec![],
+            deployments: vec![],
+        };
+        import_state(import_with_secrets(&source, &source_secrets, &archive, ImportStrategy::Skip)).await?;
+        let exported = export_state(ExportStateParams { resources_manager: Arc::clone(&source), project: ProjectId::DEFAULT }).await?;
+        let with_redacted_secret = container(vec![Clone::clone(&plain), Clone::clone(&secret).redacted()]);
+        let without_secret = container(vec![Clone::clone(&plain)]);
+
+        let report = import_state(import_with_secrets(&source, &source_secrets, &exported, ImportStrategy::Overwrite)).await?;
+        verify_that!(report, elements_are![matches_pattern!(ImportedResource { outcome: eq(ImportOutcome::Overwritten) })])?;
+        verify_that!(source.get::<PeerDescriptor>(peer.id).await.map(|peer| peer.executors), some(eq(Clone::clone(&with_redacted_secret))))?;
+
+        let report = import_state(import_with_secrets(&source, &source_secrets, &exported, ImportStrategy::RenameIds)).await?;
+        verify_that!(report, elements_are![matches_pattern!(ImportedResource { outcome: eq(ImportOutcome::Renamed { original_id: peer.id.0 }) })])?;
+        verify_that!(source.get::<PeerDescriptor>(PeerId(report[0].id)).await.map(|peer| peer.executors), some(eq(Clone::clone(&without_secret))))?;
+        verify_that!(source.get::<PeerDescriptor>(peer.id).await.map(|peer| peer.executors), some(eq(with_redacted_secret)))?;
+
+        let target = ResourcesManager::new();
+        let report = import_state(import(&target, &exported, ImportStrategy::Skip)).await?;
+        verify_that!(report, elements_are![matches_pattern!(ImportedResource { outcome: eq(ImportOutcome::Created) })])?;
+        verify_that!(target.get::<PeerDescriptor>(peer.id).await.map(|peer| peer.executors), some(eq(without_secret)))
+    }
+
+    #[tokio::test]
+    async fn should_reject_archives_of_other_versions() -> Result<()> {
+        let resources_manager = ResourcesManager::new();
+        let archive = StateArchive {
+            version: STATE_ARCHIVE_VERSION + 1,
+            exported_at: Utc::now(),
+            peers: vec![],
+            clusters: vec![],
+            deployments: vec![],
+        };
+
+        let result = import_state(import(&resources_manager, &archive, ImportStrategy::Skip)).await;
+
+        verify_that!(result, err(matches_pattern!(ImportStateError::UnsupportedVersion { .. })))
+    }
+}
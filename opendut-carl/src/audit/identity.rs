@@ -0,0 +1,125 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::extract::ConnectInfo;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::Deserialize;
+use tonic::Request;
+use uuid::Uuid;
+
+use opendut_carl_api::carl::audit::{AuditAction, AuditIdentity, AuditRecord};
+
+const UNKNOWN_IDENTITY: &str = "unknown";
+
+#[derive(Deserialize)]
+struct Claims {
+    preferred_username: Option<String>,
+    #[serde(rename = "clientId")]
+    client_id: Option<String>,
+    azp: Option<String>,
+    sub: Option<String>,
+}
+
+/// The caller of a request, which audit records are attributed to.
+pub struct AuditCaller {
+    pub identity: AuditIdentity,
+    pub client_address: Option<IpAddr>,
+}
+
+impl AuditCaller {
+    pub fn record(self, action: AuditAction, resource_id: Uuid) -> AuditRecord {
+        AuditRecord::new(self.identity, action, resource_id)
+            .with_client_address(self.client_address)
+    }
+}
+
+/// Determines the caller of a request from its bearer token and the address of the client.
+///
+/// CARL does not verify the signature of the token, so the identity is recorded as unverified, together with the address of the client,
+/// and is only meant to be recorded, not to base authorization decisions on.
+pub fn caller_of<T>(request: &Request<T>) -> AuditCaller {
+    let client_address = request.extensions().get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(address)| address.ip());
+
+    AuditCaller {
+        identity: identity_of(request),
+        client_address,
+    }
+}
+
+fn identity_of<T>(request: &Request<T>) -> AuditIdentity {
+    let Some(authorization) = request.metadata().get("authorization") else {
+        return AuditIdentity::anonymous();
+    };
+
+    claimed_identity(authorization.to_str().ok())
+        .map(AuditIdentity::unverified)
+        .unwrap_or_else(|| AuditIdentity(String::from(UNKNOWN_IDENTITY)))
+}
+
+/// Extracts the identity claimed by the bearer token in the value of an `authorization` header.
+/// Returns `None` without a header or if the token is malformed.
+pub fn claimed_identity(authorization: Option<&str>) -> Option<String> {
+    authorization
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .and_then(identity_from_token)
+}
+
+fn identity_from_token(token: &str) -> Option<String> {
+    let payload = token.split('.').nth(1)?;
+    let payload = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    let claims = serde_json::from_slice::<Claims>(&payload).ok()?;
+
+    claims.preferred_username
+        .or(claims.client_id)
+        .or(claims.azp)
+        .or(claims.sub)
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn token(claims: &str) -> String {
+        format!("Bearer header.{}.signature", URL_SAFE_NO_PAD.encode(claims))
+    }
+
+    #[rstest]
+    #[case::preferred_username(r#"{"preferred_username":"alice","azp":"opendut-cleo-client","sub":"1234"}"#, "unverified:alice")]
+    #[case::client_id(r#"{"clientId":"opendut-edgar-client","azp":"opendut-edgar-client","sub":"1234"}"#, "unverified:opendut-edgar-client")]
+    #[case::subject(r#"{"sub":"1234"}"#, "unverified:1234")]
+    #[case::no_identity(r#"{"exp":1}"#, UNKNOWN_IDENTITY)]
+    fn should_determine_identity_from_bearer_token(#[case] claims: &str, #[case] expected: &str) -> Result<()> {
+        let mut request = Request::new(());
+        request.metadata_mut().insert("authorization", token(claims).parse()?);
+
+        verify_that!(identity_of(&request), eq(AuditIdentity(String::from(expected))))
+    }
+
+    #[test]
+    fn should_use_anonymous_identity_without_authorization() -> Result<()> {
+        verify_that!(identity_of(&Request::new(())), eq(AuditIdentity::anonymous()))
+    }
+
+    #[test]
+    fn should_record_the_address_of_the_client() -> Result<()> {
+        let mut request = Request::new(());
+        verify_that!(caller_of(&request).client_address, none())?;
+
+        request.extensions_mut().insert(ConnectInfo(SocketAddr::from(([192, 168, 0, 1], 4321))));
+        let record = caller_of(&request).record(AuditAction::StorePeerDescriptor, Uuid::new_v4());
+        verify_that!(record.client_address, some(eq(IpAddr::from([192, 168, 0, 1]))))?;
+        verify_that!(record.identity, eq(AuditIdentity::anonymous()))
+    }
+
+    #[test]
+    fn should_use_unknown_identity_for_malformed_token() -> Result<()> {
+        let mut request = Request::new(());
+        request.metadata_mut().insert("authorization", "Bearer no-auth-token".parse()?);
+
+        verify_that!(identity_of(&request), eq(AuditIdentity(String::from(UNKNOWN_IDENTITY))))
+    }
+}
@@ -0,0 +1,296 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use chrono::Utc;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::RwLock;
+use tracing::{debug, error};
+
+use opendut_carl_api::carl::audit::{AuditRecord, AuditRecordFilter};
+use opendut_util::project;
+
+pub type AuditManagerRef = Arc<AuditManager>;
+
+/// Keeps the records of all mutating operations and appends them to the audit file, if one is configured.
+///
+/// Records exceeding the configured retention are discarded. The audit file is compacted,
+/// once it contains twice as many lines as records are retained.
+pub struct AuditManager {
+    state: RwLock<State>,
+    file: Option<PathBuf>,
+    retention: AuditRetention,
+}
+
+#[derive(Default)]
+struct State {
+    records: Vec<AuditRecord>,
+    lines_in_file: usize,
+}
+
+/// Limits how many records are kept and for how long. Unlimited, if not set.
+#[derive(Clone, Debug, Default)]
+pub struct AuditRetention {
+    pub max_records: Option<usize>,
+    pub max_age: Option<Duration>,
+}
+
+impl AuditManager {
+
+    pub fn new() -> AuditManagerRef {
+        Arc::new(Self {
+            state: Default::default(),
+            file: None,
+            retention: AuditRetention::default(),
+        })
+    }
+
+    pub async fn load(options: AuditManagerOptions) -> anyhow::Result<AuditManagerRef> {
+        let retention = options.retention;
+        let Some(file) = options.file else {
+            debug!("No audit file configured. Audit records are kept in memory only.");
+            return Ok(Arc::new(Self {
+                state: Default::default(),
+                file: None,
+                retention,
+            }));
+        };
+        let file = project::make_path_absolute(file)?;
+        debug!("Using audit file: {}", file.display());
+
+        let records = if file.exists() {
+            let content = fs::read_to_string(&file).await
+                .context(format!("Failed to read audit file '{}'.", file.display()))?;
+            content.lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str::<AuditRecord>)
+                .collect::<Result<Vec<_>, _>>()
+                .context(format!("Failed to parse audit file '{}'.", file.display()))?
+        } else {
+            Vec::new()
+        };
+        let lines_in_file = records.len();
+
+        let audit_manager = Self {
+            state: RwLock::new(State { records, lines_in_file }),
+            file: Some(file),
+            retention,
+        };
+        {
+            let mut state = audit_manager.state.write().await;
+            audit_manager.apply_retention(&mut state).await;
+        }
+        Ok(Arc::new(audit_manager))
+    }
+
+    #[tracing::instrument(skip(self), level="trace")]
+    pub async fn record(&self, record: AuditRecord) {
+        let mut state = self.state.write().await;
+
+        if let Some(file) = &self.file {
+            match append(file, &record).await {
+                Ok(()) => state.lines_in_file += 1,
+                Err(cause) => error!("Failed to write audit record <{}> to audit file '{}':\n  {cause}", record.id, file.display()),
+            }
+        }
+        state.records.push(record);
+
+        self.apply_retention(&mut state).await;
+    }
+
+    /// Returns the records matching the given filter, latest first.
+    pub async fn list(&self, filter: &AuditRecordFilter) -> Vec<AuditRecord> {
+        let state = self.state.read().await;
+        let limit = filter.limit.map_or(usize::MAX, |limit| limit as usize);
+
+        state.records.iter()
+            .rev()
+            .filter(|record| filter.matches(record))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    async fn apply_retention(&self, state: &mut State) {
+        let expired = self.retention.max_age
+            .and_then(|max_age| chrono::Duration::from_std(max_age).ok())
+            .map(|max_age| {
+                let oldest_retained = Utc::now() - max_age;
+                state.records.iter()
+                    .take_while(|record| record.timestamp < oldest_retained)
+                    .count()
+            })
+            .unwrap_or_default();
+        let excess = self.retention.max_records
+            .map(|max_records| state.records.len().saturating_sub(max_records))
+            .unwrap_or_default();
+
+        let discarded = usize::max(expired, excess);
+        if discarded > 0 {
+            state.records.drain(..discarded);
+            debug!("Discarded {discarded} audit record(s) exceeding the retention.");
+        }
+
+        if let Some(file) = &self.file {
+            if state.lines_in_file > usize::max(2 * state.records.len(), 1) {
+                match compact(file, &state.records).await {
+                    Ok(()) => state.lines_in_file = state.records.len(),
+                    Err(cause) => error!("Failed to compact audit file '{}':\n  {cause}", file.display()),
+                }
+            }
+        }
+    }
+}
+
+async fn append(file: &PathBuf, record: &AuditRecord) -> anyhow::Result<()> {
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file).await?;
+    file.write_all(line.as_bytes()).await?;
+    file.flush().await?;
+    Ok(())
+}
+
+/// Replaces the audit file with one containing only the given records.
+async fn compact(file: &Path, records: &[AuditRecord]) -> anyhow::Result<()> {
+    let mut content = String::new();
+    for record in records {
+        content.push_str(&serde_json::to_string(record)?);
+        content.push('\n');
+    }
+    let temporary_file = file.with_extension("jsonl.tmp");
+    fs::write(&temporary_file, content).await?;
+    fs::rename(&temporary_file, file).await?;
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+pub struct AuditManagerOptions {
+    pub file: Option<PathBuf>,
+    pub retention: AuditRetention,
+}
+impl AuditManagerOptions {
+    pub fn load(config: &config::Config) -> Result<Self, opendut_util::settings::LoadError> {
+        let file = config.get_string("audit.file")?;
+        let file = if file.trim().is_empty() {
+            None
+        } else {
+            Some(PathBuf::from(file))
+        };
+
+        let max_records = Some(config.get::<usize>("audit.retention.records")?)
+            .filter(|max_records| *max_records > 0);
+        let max_age = Some(config.get::<u64>("audit.retention.days")?)
+            .filter(|days| *days > 0)
+            .map(|days| Duration::from_secs(days * 24 * 60 * 60));
+
+        Ok(AuditManagerOptions {
+            file,
+            retention: AuditRetention { max_records, max_age },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::prelude::*;
+    use googletest::prelude::*;
+    use uuid::Uuid;
+
+    use opendut_carl_api::carl::audit::{AuditAction, AuditIdentity, AuditOutcome};
+    use opendut_types::project::ProjectId;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_list_latest_records_first_and_apply_filter() -> Result<()> {
+        let testee = AuditManager::new();
+        let resource_id = Uuid::new_v4();
+
+        testee.record(AuditRecord::new(AuditIdentity(String::from("alice")), AuditAction::StorePeerDescriptor, resource_id)).await;
+        testee.record(AuditRecord::new(AuditIdentity(String::from("bob")), AuditAction::StorePeerDescriptor, Uuid::new_v4())).await;
+        testee.record(AuditRecord::new(AuditIdentity(String::from("alice")), AuditAction::DeletePeerDescriptor, resource_id)).await;
+
+        let records = testee.list(&AuditRecordFilter::default()).await;
+        verify_that!(records.len(), eq(3))?;
+        verify_that!(records[0].action, eq(AuditAction::DeletePeerDescriptor))?;
+
+        let records = testee.list(&AuditRecordFilter { resource_id: Some(resource_id), ..Default::default() }).await;
+        verify_that!(records.len(), eq(2))?;
+
+        let records = testee.list(&AuditRecordFilter { identity: Some(String::from("bob")), ..Default::default() }).await;
+        verify_that!(records.len(), eq(1))?;
+
+        let records = testee.list(&AuditRecordFilter { limit: Some(1), ..Default::default() }).await;
+        verify_that!(records, elements_are![
+            field!(AuditRecord.action, eq(AuditAction::DeletePeerDescriptor))
+        ])
+    }
+
+    #[tokio::test]
+    async fn should_restore_records_from_audit_file() -> Result<()> {
+        let temp = assert_fs::TempDir::new()?;
+        let file = temp.child("audit.jsonl");
+
+        let options = AuditManagerOptions { file: Some(file.to_path_buf()), retention: AuditRetention::default() };
+
+        let record = AuditRecord::new(AuditIdentity::anonymous(), AuditAction::CreateClusterConfiguration, Uuid::new_v4())
+            .with_after(Some(&String::from("cluster")))
+            .with_outcome::<(), _>(&Err("failed"));
+
+        let testee = AuditManager::load(Clone::clone(&options)).await.unwrap();
+        testee.record(Clone::clone(&record)).await;
+
+        let restored = AuditManager::load(options).await.unwrap();
+        let records = restored.list(&AuditRecordFilter::default()).await;
+
+        verify_that!(records, elements_are![eq(record)])?;
+        verify_that!(records[0].outcome, eq(AuditOutcome::Failure { cause: String::from("failed") }))
+    }
+
+    #[tokio::test]
+    async fn should_discard_records_exceeding_the_retention_and_compact_the_audit_file() -> Result<()> {
+        let temp = assert_fs::TempDir::new()?;
+        let file = temp.child("audit.jsonl");
+
+        let options = AuditManagerOptions {
+            file: Some(file.to_path_buf()),
+            retention: AuditRetention { max_records: Some(2), max_age: None },
+        };
+
+        let testee = AuditManager::load(Clone::clone(&options)).await.unwrap();
+        let resource_ids = (0..5).map(|_| Uuid::new_v4()).collect::<Vec<_>>();
+        for resource_id in &resource_ids {
+            testee.record(AuditRecord::new(AuditIdentity::anonymous(), AuditAction::StorePeerDescriptor, *resource_id)).await;
+        }
+
+        let records = testee.list(&AuditRecordFilter::default()).await;
+        verify_that!(records.iter().map(|record| record.resource_id).collect::<Vec<_>>(), elements_are![eq(resource_ids[4]), eq(resource_ids[3])])?;
+
+        let lines_in_file = std::fs::read_to_string(file.path())?.lines().count();
+        verify_that!(lines_in_file, le(4))?;
+
+        let restored = AuditManager::load(options).await.unwrap();
+        verify_that!(restored.list(&AuditRecordFilter::default()).await, eq(records))
+    }
+
+    #[tokio::test]
+    async fn should_only_list_records_of_the_given_project() -> Result<()> {
+        let testee = AuditManager::new();
+        let project = ProjectId::random();
+
+        testee.record(AuditRecord::new(AuditIdentity::anonymous(), AuditAction::StorePeerDescriptor, Uuid::new_v4())).await;
+        testee.record(AuditRecord::new(AuditIdentity::anonymous(), AuditAction::StorePeerDescriptor, Uuid::new_v4()).with_project(project)).await;
+
+        let records = testee.list(&AuditRecordFilter { project: Some(project), ..Default::default() }).await;
+        verify_that!(records, elements_are![
+            field!(AuditRecord.project, eq(project))
+        ])
+    }
+}
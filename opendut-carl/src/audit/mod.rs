@@ -0,0 +1,3 @@
+pub mod identity;
+pub mod manager;
+pub mod snapshot;
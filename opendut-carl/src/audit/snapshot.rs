@@ -0,0 +1,16 @@
+/// The state of a resource directly before and after a mutating operation.
+///
+/// Captured by the actions within the same transaction, which mutates the resource,
+/// so that concurrent operations cannot interleave with the recorded states.
+/// Operations, which fail before mutating the resource, capture no snapshot.
+#[derive(Clone, Debug)]
+pub struct Snapshot<R> {
+    pub before: Option<R>,
+    pub after: Option<R>,
+}
+
+impl<R> Default for Snapshot<R> {
+    fn default() -> Self {
+        Self { before: None, after: None }
+    }
+}
@@ -1,22 +1,42 @@
 use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::ops::Not;
 use std::sync::Arc;
+use std::time::Duration;
 
+use chrono::{DateTime, Utc};
 use futures::future::join_all;
 use futures::FutureExt;
-use tracing::{debug, error};
+use tokio::sync::Mutex;
+use tracing::{debug, error, info, warn};
 
-use opendut_carl_api::carl::cluster::{DeleteClusterDeploymentError, StoreClusterDeploymentError};
+use opendut_carl_api::carl::cluster::{DeleteClusterDeploymentError, StoreClusterDeploymentError, ValidateClusterDeploymentError};
+use opendut_carl_api::proto::services::peer_messaging_broker::{ClusterLeaseExpiring, downstream};
 use opendut_types::cluster::{ClusterAssignment, ClusterConfiguration, ClusterDeployment, ClusterId, ClusterName, PeerClusterAssignment};
+use opendut_types::cluster::operation::{DeploymentOperation, DeploymentOperationError, DeploymentOperationId, DeploymentOperationState, DeploymentStep};
+use opendut_types::cluster::state::{ClusterState, DeployedClusterState};
+use opendut_types::cluster::validation::{ClusterDeploymentIssue, ClusterDeploymentValidation};
 use opendut_types::peer::{PeerDescriptor, PeerId};
+use opendut_types::peer::configuration::PeerConfiguration;
 use opendut_types::peer::state::PeerState;
+use opendut_types::project::ProjectId;
 use opendut_types::topology::DeviceId;
-use opendut_types::util::net::NetworkInterfaceDescriptor;
+use opendut_types::util::net::{NetworkInterfaceConfiguration, NetworkInterfaceDescriptor};
 use opendut_types::util::Port;
+use opendut_types::vpn::health::VpnHealth;
+use opendut_types::vpn::wireguard::WireGuardPeer;
+use opendut_vpn::{ClusterNetworkPolicy, CreateClusterError, NetworkProtocol};
 
 use crate::actions;
 use crate::actions::{AssignClusterParams, ListPeerDescriptorsParams};
+use crate::audit::snapshot::Snapshot;
+use crate::cluster::ports;
+use crate::cluster::ports::{CanServerPortAllocation, CanServerPortRange, CanServerPortsExhaustedError};
 use crate::peer::broker::PeerMessagingBrokerRef;
+use crate::project;
+use crate::project::OwningProject;
 use crate::resources::manager::ResourcesManagerRef;
+use crate::resources::Resources;
 use crate::vpn::Vpn;
 
 pub type ClusterManagerRef = Arc<ClusterManager>;
@@ -37,6 +57,24 @@ pub enum DeployClusterError {
         cluster_id: ClusterId,
         cluster_name: ClusterName,
     },
+    #[error("Not enough free CAN server ports between port {range_start} and {range_end} for the {required} members of cluster <{cluster_id}> with leader <{leader_id}>.")]
+    CanServerPortsExhausted {
+        cluster_id: ClusterId,
+        leader_id: PeerId,
+        range_start: Port,
+        range_end: Port,
+        required: usize,
+    },
+    #[error("Cluster <{cluster_id}> cannot be deployed, since the VPN cannot restrict the traffic between its members to {protocol} without allowing any other traffic.")]
+    VpnProtocolUnsupported {
+        cluster_id: ClusterId,
+        protocol: NetworkProtocol,
+    },
+    #[error("Failure while creating cluster <{cluster_id}> in VPN service:\n  {cause}")]
+    VpnFailure {
+        cluster_id: ClusterId,
+        cause: String,
+    },
     #[error("An error occurred while deploying cluster <{cluster_id}>:\n  {cause}")]
     Internal {
         cluster_id: ClusterId,
@@ -49,6 +87,8 @@ pub struct ClusterManager {
     peer_messaging_broker: PeerMessagingBrokerRef,
     vpn: Vpn,
     options: ClusterManagerOptions,
+    /// The leases, whose members were already warned about their expiry, identified by cluster and end of the lease.
+    lease_warnings: Mutex<HashSet<(ClusterId, DateTime<Utc>)>>,
 }
 
 impl ClusterManager {
@@ -63,13 +103,69 @@ impl ClusterManager {
             peer_messaging_broker,
             vpn,
             options,
+            lease_warnings: Default::default(),
         })
     }
     #[tracing::instrument(skip(self), level="trace")]
     pub async fn deploy(&self, cluster_id: ClusterId) -> Result<(), DeployClusterError> {
+        self.deploy_tracked(cluster_id, None).await
+    }
+
+    /// Deploys the cluster and records the progress against the given operation, if any.
+    async fn deploy_tracked(&self, cluster_id: ClusterId, operation_id: Option<DeploymentOperationId>) -> Result<(), DeployClusterError> {
+
+        let _vpn_change = self.vpn.lock_change().await;
+
+        self.update_operation(operation_id, DeploymentOperationState::Running { step: DeploymentStep::ResolvingPeers }).await;
+
+        let (project_id, cluster_assignment) = self.determine_cluster_assignment(cluster_id, true).await?;
+
+        let member_ids = cluster_assignment.assignments.iter()
+            .map(|assignment| assignment.peer_id)
+            .collect::<Vec<_>>();
+
+        if let Vpn::Enabled { vpn_client, .. } = &self.vpn {
+            self.update_operation(operation_id, DeploymentOperationState::Running { step: DeploymentStep::CreatingVpnGroup }).await;
+
+            vpn_client.create_cluster(project_id, cluster_id, &member_ids, &ClusterNetworkPolicy::from(&cluster_assignment)).await
+                .map_err(|cause| match cause {
+                    CreateClusterError::UnsupportedProtocol { protocol, .. } => DeployClusterError::VpnProtocolUnsupported { cluster_id, protocol },
+                    cause => DeployClusterError::VpnFailure { cluster_id, cause: cause.to_string() },
+                })?;
+
+            let peers_string = member_ids.iter().map(|peer| peer.to_string()).collect::<Vec<_>>().join(",");
+            debug!("Created group for cluster <{cluster_id}> in VPN service, using peers: {peers_string}");
+        } else {
+            debug!("VPN disabled. Not creating VPN group.")
+        }
+
+        self.update_operation(operation_id, DeploymentOperationState::Running { step: DeploymentStep::AssigningPeers }).await;
+
+        for member_id in member_ids {
+            actions::assign_cluster(AssignClusterParams {
+                resources_manager: Arc::clone(&self.resources_manager),
+                peer_messaging_broker: Arc::clone(&self.peer_messaging_broker),
+                peer_id: member_id,
+                cluster_assignment: cluster_assignment.clone(),
+            }).await
+            .map_err(|cause| {
+                let message = format!("Failure while assigning cluster <{cluster_id}> to peer <{member_id}>.");
+                error!("{}\n  {cause}", message);
+                DeployClusterError::Internal { cluster_id, cause: message }
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Determines the assignment, which would be sent to the members of the cluster, if it was deployed now.
+    /// With `reserve_ports`, the CAN server ports of the members are stored as allocated to the cluster,
+    /// otherwise the allocation is only computed.
+    async fn determine_cluster_assignment(&self, cluster_id: ClusterId, reserve_ports: bool) -> Result<(ProjectId, ClusterAssignment), DeployClusterError> {
 
-        let cluster_config = self.resources_manager.resources(|resources| {
+        let (project_id, cluster_config) = self.resources_manager.resources(|resources| {
             resources.get::<ClusterConfiguration>(cluster_id)
+                .map(|configuration| (project::project_of_resource(resources, cluster_id), configuration))
         }).await
         .ok_or(DeployClusterError::ClusterConfigurationNotFound(cluster_id))?;
 
@@ -77,41 +173,54 @@ impl ClusterManager {
 
         let all_peers = actions::list_peer_descriptors(ListPeerDescriptorsParams {
             resources_manager: Arc::clone(&self.resources_manager),
+            project: project_id,
         }).await.map_err(|cause| DeployClusterError::Internal { cluster_id, cause: cause.to_string() })?;
 
 
+        if all_peers.iter().any(|peer| peer.id == cluster_config.leader).not() {
+            return Err(DeployClusterError::LeaderNotFound { leader_id: cluster_config.leader, cluster_id, cluster_name });
+        }
+
         let member_interface_mapping = determine_member_interface_mapping(cluster_config.devices, all_peers, cluster_config.leader)
             .map_err(|cause| match cause {
                 DetermineMemberInterfaceMappingError::PeerForDeviceNotFound { device_id } => DeployClusterError::PeerForDeviceNotFound { device_id, cluster_id, cluster_name },
             })?;
 
-        let member_ids = member_interface_mapping.keys().cloned().collect::<Vec<_>>();
-
-        if let Vpn::Enabled { vpn_client } = &self.vpn {
-            vpn_client.create_cluster(cluster_id, &member_ids).await
-                .map_err(|cause| {
-                    let message = format!("Failure while creating cluster <{cluster_id}> in VPN service.");
-                    error!("{}\n  {cause}", message);
-                    DeployClusterError::Internal { cluster_id, cause: message }
-                })?;
+        let mut member_interface_mapping = member_interface_mapping.into_iter().collect::<Vec<_>>();
+        member_interface_mapping.sort_by_key(|(peer_id, _)| peer_id.0); //assign the CAN server ports in a stable order
 
-            let peers_string = member_ids.iter().map(|peer| peer.to_string()).collect::<Vec<_>>().join(",");
-            debug!("Created group for cluster <{cluster_id}> in VPN service, using peers: {peers_string}");
-        } else {
-            debug!("VPN disabled. Not creating VPN group.")
-        }
-
-        let port_start = self.options.can_server_port_range_start;
-        let port_end = self.options.can_server_port_range_start + u16::try_from(member_interface_mapping.len())
-            .map_err(|cause| DeployClusterError::Internal { cluster_id, cause: cause.to_string() })?;
-        let can_server_ports = (port_start..port_end)
-            .map(Port)
+        let member_ids = member_interface_mapping.iter()
+            .map(|(peer_id, _)| *peer_id)
             .collect::<Vec<_>>();
+        let leader_id = cluster_config.leader;
+        let port_range = self.options.can_server_port_range();
+
+        let allocate_ports = |resources: &Resources| ports::allocate(resources, cluster_id, leader_id, &member_ids, port_range);
+        let port_allocation = if reserve_ports {
+            self.resources_manager.resources_mut(|resources| {
+                let allocation = allocate_ports(resources)?;
+                resources.insert(cluster_id, Clone::clone(&allocation));
+                Ok(allocation)
+            }).await
+        } else {
+            self.resources_manager.resources(allocate_ports).await
+        };
+        let port_allocation = port_allocation
+            .map_err(|cause: CanServerPortsExhaustedError| DeployClusterError::CanServerPortsExhausted {
+                cluster_id,
+                leader_id,
+                range_start: cause.range.start,
+                range_end: cause.range.end,
+                required: cause.required,
+            })?;
 
         let member_assignments: Vec<Result<PeerClusterAssignment, DeployClusterError>> = {
-            let assignment_futures = std::iter::zip(member_interface_mapping, can_server_ports)
-                .map(|((peer_id, device_interfaces), can_server_port)| {
+            let assignment_futures = member_interface_mapping.into_iter()
+                .map(|(peer_id, device_interfaces)| {
+                    let can_server_port = port_allocation.port_of(peer_id)
+                        .ok_or(DeployClusterError::Internal { cluster_id, cause: format!("No CAN server port was allocated to peer <{peer_id}>.") });
                     self.resources_manager.get::<PeerState>(peer_id).map(move |peer_state: Option<PeerState>| {
+                        let can_server_port = can_server_port?;
                         let vpn_address = match peer_state {
                             Some(PeerState::Up { remote_host, .. }) => {
                                 Ok(remote_host)
@@ -124,7 +233,7 @@ impl ClusterManager {
                             }
                         };
                         vpn_address.map(|vpn_address|
-                            PeerClusterAssignment { peer_id, vpn_address, can_server_port, device_interfaces }
+                            PeerClusterAssignment { peer_id, vpn_address, can_server_port, device_interfaces, wireguard: None }
                         )
                     })
                 })
@@ -133,68 +242,577 @@ impl ClusterManager {
             join_all(assignment_futures).await
         };
         let member_assignments: Vec<PeerClusterAssignment> = member_assignments.into_iter().collect::<Result<_, _>>()?;
+        let member_assignments = self.with_wireguard_tunnels(member_assignments).await;
 
-        for member_id in member_ids {
-            actions::assign_cluster(AssignClusterParams {
-                resources_manager: Arc::clone(&self.resources_manager),
-                peer_messaging_broker: Arc::clone(&self.peer_messaging_broker),
-                peer_id: member_id,
-                cluster_assignment: ClusterAssignment {
-                    id: cluster_id,
-                    leader: cluster_config.leader,
-                    assignments: member_assignments.clone(),
-                },
-            }).await
-            .map_err(|cause| {
-                let message = format!("Failure while assigning cluster <{cluster_id}> to peer <{member_id}>.");
-                error!("{}\n  {cause}", message);
-                DeployClusterError::Internal { cluster_id, cause: message }
-            })?;
+        let cluster_assignment = ClusterAssignment {
+            id: cluster_id,
+            leader: cluster_config.leader,
+            assignments: member_assignments,
+        };
+
+        Ok((project_id, cluster_assignment))
+    }
+
+    /// Redeploys the deployed clusters, whose assignment stored for their members no longer matches the current state of the peers,
+    /// e.g. since a member reconnected with another address or the devices of a member changed.
+    /// Clusters with a member, which is not up, are skipped, since the member receives its configuration when it reconnects.
+    #[tracing::instrument(skip(self), level="trace")]
+    pub async fn redeploy_outdated_clusters(&self) -> Vec<ClusterId> {
+        let now = Utc::now();
+        let deployed_clusters = self.resources_manager.resources(|resources| {
+            resources.iter::<ClusterDeployment>()
+                .filter(|deployment| deployment.is_started(now) && deployment.is_expired(now).not())
+                .map(|deployment| deployment.id)
+                .filter(|cluster_id| resources.iter::<DeploymentOperation>()
+                    .any(|operation| operation.cluster_id == *cluster_id && operation.is_finished().not())
+                    .not()
+                )
+                .collect::<Vec<_>>()
+        }).await;
+
+        let mut redeployed_clusters = Vec::new();
+
+        for cluster_id in deployed_clusters {
+            let expected_assignment = match self.determine_cluster_assignment(cluster_id, false).await {
+                Ok((_, assignment)) => assignment,
+                Err(cause) => {
+                    debug!("Not checking whether cluster <{cluster_id}> is outdated, since it cannot be deployed currently:\n  {cause}");
+                    continue;
+                }
+            };
+
+            let stored_assignments = self.resources_manager.resources(|resources| {
+                expected_assignment.assignments.iter()
+                    .map(|member| resources.get::<PeerConfiguration>(member.peer_id)
+                        .and_then(|configuration| configuration.cluster_assignment)
+                    )
+                    .collect::<Vec<_>>()
+            }).await;
+
+            if stored_assignments.iter().flatten().any(|assignment| assignment.id != cluster_id) {
+                debug!("Not redeploying cluster <{cluster_id}>, since one of its members is assigned to another cluster.");
+                continue;
+            }
+            if stored_assignments.iter().all(|assignment| assignment.as_ref() == Some(&expected_assignment)) {
+                continue;
+            }
+
+            info!("Redeploying cluster <{cluster_id}>, since its members changed since the last deployment.");
+            match self.deploy(cluster_id).await {
+                Ok(()) => redeployed_clusters.push(cluster_id),
+                Err(cause) => error!("Failed to redeploy cluster <{cluster_id}>:\n  {cause}"),
+            }
         }
+        redeployed_clusters
+    }
 
-        Ok(())
+    /// Periodically redeploys outdated clusters in the background, unless disabled in the options.
+    pub fn spawn_redeployment(self: &Arc<Self>) {
+        let Some(interval) = self.options.redeploy_interval else {
+            info!("Automatic redeployment of clusters is disabled.");
+            return;
+        };
+
+        let cluster_manager = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                interval.tick().await;
+                cluster_manager.redeploy_outdated_clusters().await;
+            }
+        });
+    }
+
+    /// Checks whether the cluster could be deployed, without deploying it or changing any state.
+    #[tracing::instrument(skip(self), level="trace")]
+    pub async fn validate_deployment(&self, project_id: ProjectId, cluster_id: ClusterId) -> Result<ClusterDeploymentValidation, ValidateClusterDeploymentError> {
+
+        let cluster_config = self.find_configuration(project_id, cluster_id).await
+            .ok_or(ValidateClusterDeploymentError::ClusterConfigurationNotFound { cluster_id })?;
+
+        let all_peers = actions::list_peer_descriptors(ListPeerDescriptorsParams {
+            resources_manager: Arc::clone(&self.resources_manager),
+            project: project_id,
+        }).await.map_err(|cause| ValidateClusterDeploymentError::Internal { cluster_id, cause: cause.to_string() })?;
+
+        let mut issues = Vec::new();
+
+        let mut members: Vec<PeerId> = Vec::new();
+        if all_peers.iter().any(|peer| peer.id == cluster_config.leader) {
+            members.push(cluster_config.leader);
+        } else {
+            issues.push(ClusterDeploymentIssue::LeaderNotFound { leader_id: cluster_config.leader });
+        }
+
+        let mut fd_devices = Vec::new();
+        let mut non_fd_devices = Vec::new();
+        let mut cluster_devices = cluster_config.devices.into_iter().collect::<Vec<_>>();
+        cluster_devices.sort_by_key(|device_id| device_id.0);
+        for device_id in cluster_devices {
+            let peer_and_device = all_peers.iter().find_map(|peer| {
+                peer.topology.devices.iter()
+                    .find(|device| device.id == device_id)
+                    .map(|device| (peer.id, device))
+            });
+            match peer_and_device {
+                Some((peer_id, device)) => {
+                    if members.contains(&peer_id).not() {
+                        members.push(peer_id);
+                    }
+                    if let NetworkInterfaceConfiguration::Can { fd, .. } = device.interface.configuration {
+                        if fd { fd_devices.push(device_id) } else { non_fd_devices.push(device_id) }
+                    }
+                }
+                None => issues.push(ClusterDeploymentIssue::PeerForDeviceNotFound { device_id }),
+            }
+        }
+        if fd_devices.is_empty().not() && non_fd_devices.is_empty().not() {
+            issues.push(ClusterDeploymentIssue::IncompatibleCanFd { fd_devices, non_fd_devices });
+        }
+
+        let port_range = self.options.can_server_port_range();
+        let leader_id = cluster_config.leader;
+        let port_allocation = self.resources_manager.resources(|resources| {
+            ports::allocate(resources, cluster_id, leader_id, &members, port_range)
+        }).await;
+        match port_allocation {
+            Ok(allocation) => {
+                if let Vpn::Enabled { vpn_client, .. } = &self.vpn {
+                    let policy = ClusterNetworkPolicy::for_members(leader_id, &allocation.ports);
+                    for protocol in vpn_client.unsupported_protocols() {
+                        if policy.rules.iter().any(|rule| rule.protocol == protocol) {
+                            issues.push(ClusterDeploymentIssue::VpnProtocolUnsupported { protocol: protocol.to_string() });
+                        }
+                    }
+                }
+            }
+            Err(cause) => {
+                issues.push(ClusterDeploymentIssue::CanServerPortsExhausted {
+                    range_start: cause.range.start,
+                    range_end: cause.range.end,
+                    required: u32::try_from(cause.required).unwrap_or(u32::MAX),
+                });
+            }
+        }
+
+        let vpn_enabled = matches!(self.vpn, Vpn::Enabled { .. });
+
+        let member_issues = self.resources_manager.resources(|resources| {
+            let mut issues = Vec::new();
+
+            for &peer_id in &members {
+                if matches!(resources.get::<PeerState>(peer_id), Some(PeerState::Up { .. })).not() {
+                    issues.push(ClusterDeploymentIssue::PeerNotUp { peer_id });
+                }
+
+                let other_assignment = resources.get::<PeerConfiguration>(peer_id)
+                    .and_then(|configuration| configuration.cluster_assignment)
+                    .filter(|assignment| assignment.id != cluster_id);
+                if let Some(other_assignment) = other_assignment {
+                    issues.push(ClusterDeploymentIssue::PeerInOtherCluster { peer_id, other_cluster_id: other_assignment.id });
+                }
+            }
+
+            if vpn_enabled {
+                let leader_address = resources.get::<VpnHealth>(cluster_config.leader)
+                    .and_then(|health| health.address);
+
+                for &peer_id in &members {
+                    let Some(health) = resources.get::<VpnHealth>(peer_id) else {
+                        continue; //peer did not report its VPN health, e.g. because its VPN does not support it
+                    };
+                    if health.connected.not() {
+                        issues.push(ClusterDeploymentIssue::VpnUnreachable { peer_id, cause: String::from("The VPN client of the peer is not connected.") });
+                    }
+                    else if let Some(leader_address) = leader_address.filter(|_| peer_id != cluster_config.leader) {
+                        let leader_link_down = health.links.iter()
+                            .any(|link| link.remote_address == leader_address && link.connected.not());
+                        if leader_link_down {
+                            issues.push(ClusterDeploymentIssue::VpnUnreachable { peer_id, cause: format!("No VPN connection to the leader at {leader_address}.") });
+                        }
+                    }
+                }
+            }
+            issues
+        }).await;
+        issues.extend(member_issues);
+
+        Ok(ClusterDeploymentValidation { cluster_id, issues })
+    }
+
+    /// Connects the members of a cluster via their WireGuard tunnels, if the VPN is coordinated by CARL itself.
+    /// The address reported by a peer is then used as the endpoint of its tunnel.
+    async fn with_wireguard_tunnels(&self, assignments: Vec<PeerClusterAssignment>) -> Vec<PeerClusterAssignment> {
+        let Vpn::Enabled { vpn_client, .. } = &self.vpn else {
+            return assignments;
+        };
+
+        let mut result = Vec::with_capacity(assignments.len());
+        for assignment in assignments {
+            let assignment = match vpn_client.wireguard_tunnel(assignment.peer_id).await {
+                Some(tunnel) => PeerClusterAssignment {
+                    vpn_address: tunnel.address,
+                    wireguard: Some(WireGuardPeer {
+                        public_key: tunnel.public_key,
+                        endpoint: SocketAddr::new(assignment.vpn_address, tunnel.listen_port.0),
+                    }),
+                    ..assignment
+                },
+                None => assignment,
+            };
+            result.push(assignment);
+        }
+        result
     }
 
     #[tracing::instrument(skip(self), level="trace")]
-    pub async fn find_configuration(&self, id: ClusterId) -> Option<ClusterConfiguration> {
+    pub async fn find_configuration(&self, project_id: ProjectId, id: ClusterId) -> Option<ClusterConfiguration> {
         self.resources_manager.resources(|resources| {
             resources.get::<ClusterConfiguration>(id)
+                .filter(|_| project::is_owned_by(resources, id, project_id))
+        }).await
+    }
+
+    #[tracing::instrument(skip(self), level="trace")]
+    pub async fn list_configuration(&self, project_id: ProjectId) -> Vec<ClusterConfiguration> {
+        self.resources_manager.resources(|resources| {
+            resources.iter::<ClusterConfiguration>()
+                .filter(|configuration| project::is_owned_by(resources, configuration.id, project_id))
+                .cloned()
+                .collect::<Vec<_>>()
         }).await
     }
 
+    /// Returns whether the cluster with the given id may be used from within the given project,
+    /// i.e. it is either owned by the project or not known at all.
     #[tracing::instrument(skip(self), level="trace")]
-    pub async fn list_configuration(&self) -> Vec<ClusterConfiguration> {
+    pub async fn is_accessible_from(&self, project_id: ProjectId, cluster_id: ClusterId) -> bool {
         self.resources_manager.resources(|resources| {
-            resources.iter::<ClusterConfiguration>().cloned().collect::<Vec<_>>()
+            resources.contains::<OwningProject>(cluster_id).not()
+                || project::is_owned_by(resources, cluster_id, project_id)
         }).await
     }
 
+    /// Stores the deployment and deploys the cluster in the background, once its start time is reached.
+    /// The progress and the outcome of the deployment are recorded in the returned operation.
+    pub async fn store_cluster_deployment(self: &Arc<Self>, project_id: ProjectId, deployment: ClusterDeployment) -> Result<DeploymentOperation, StoreClusterDeploymentError> {
+        let (result, _) = self.store_cluster_deployment_with_snapshot(project_id, deployment).await;
+        result
+    }
+
+    /// Like [`ClusterManager::store_cluster_deployment`], but also captures the stored deployment, as well as the one it replaced.
     #[tracing::instrument(skip(self), level="trace")]
-    pub async fn store_cluster_deployment(&self, deployment: ClusterDeployment) -> Result<ClusterId, StoreClusterDeploymentError> {
+    pub async fn store_cluster_deployment_with_snapshot(self: &Arc<Self>, project_id: ProjectId, deployment: ClusterDeployment) -> (Result<DeploymentOperation, StoreClusterDeploymentError>, Option<Snapshot<ClusterDeployment>>) {
+        let mut snapshot = None;
+        let result = self.store_cluster_deployment_inner(project_id, deployment, &mut snapshot).await;
+        (result, snapshot)
+    }
+
+    async fn store_cluster_deployment_inner(self: &Arc<Self>, project_id: ProjectId, deployment: ClusterDeployment, snapshot: &mut Option<Snapshot<ClusterDeployment>>) -> Result<DeploymentOperation, StoreClusterDeploymentError> {
         let cluster_id = deployment.id;
-        self.resources_manager.resources_mut(|resources| {
-            resources.insert(deployment.id, deployment);
+        let now = Utc::now();
+
+        let deployment = ClusterDeployment {
+            start: deployment.start.or(deployment.lease.map(|_| now)), //the lease counts from storing the deployment, if no start is given
+            ..deployment
+        };
+
+        let operation = self.resources_manager.resources_mut(|resources| {
+            // Checked within the same transaction, so the configuration cannot be replaced by one of another project in the meantime.
+            let configuration = resources.get::<ClusterConfiguration>(cluster_id)
+                .filter(|_| project::is_owned_by(resources, cluster_id, project_id))
+                .ok_or(StoreClusterDeploymentError::ClusterConfigurationNotFound { cluster_id })?;
+
+            let operations_of_cluster = resources.iter::<DeploymentOperation>()
+                .filter(|operation| operation.cluster_id == cluster_id)
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if operations_of_cluster.iter().any(|operation| operation.is_finished().not()) {
+                return Err(StoreClusterDeploymentError::IllegalClusterState {
+                    cluster_id,
+                    cluster_name: configuration.name,
+                    actual_state: ClusterState::Deploying,
+                    required_states: vec![
+                        ClusterState::Undeployed,
+                        ClusterState::Deployed(DeployedClusterState::Unhealthy),
+                        ClusterState::Deployed(DeployedClusterState::Healthy),
+                    ],
+                });
+            }
+
+            if let Some((other_cluster_id, devices)) = find_reservation_conflict(resources, &deployment, &configuration.devices) {
+                return Err(StoreClusterDeploymentError::ReservationConflict { cluster_id, other_cluster_id, devices });
+            }
+
+            for finished_operation in operations_of_cluster {
+                resources.remove::<DeploymentOperation>(finished_operation.id);
+            }
+
+            let operation = DeploymentOperation {
+                id: DeploymentOperationId::random(),
+                cluster_id,
+                state: DeploymentOperationState::Pending,
+            };
+            let before = resources.insert(deployment.id, Clone::clone(&deployment));
+            *snapshot = Some(Snapshot { before, after: Some(Clone::clone(&deployment)) });
+            resources.insert(cluster_id, OwningProject(project_id));
+            resources.insert(operation.id, Clone::clone(&operation));
+            Ok(operation)
+        }).await?;
+
+        match deployment.start {
+            Some(start) if deployment.is_started(now).not() => {
+                info!("Scheduled deployment of cluster <{cluster_id}> for {start}.");
+            }
+            _ => self.run_operation(operation.id, cluster_id).await,
+        }
+
+        Ok(operation)
+    }
+
+    /// Deploys the cluster in the background and records the outcome in the operation, unless the operation was already started.
+    async fn run_operation(self: &Arc<Self>, operation_id: DeploymentOperationId, cluster_id: ClusterId) {
+        let started = self.resources_manager.resources_mut(|resources| {
+            let pending = matches!(
+                resources.get::<DeploymentOperation>(operation_id),
+                Some(DeploymentOperation { state: DeploymentOperationState::Pending, .. })
+            );
+            if pending {
+                resources.update::<DeploymentOperation>(operation_id)
+                    .modify(|operation| operation.state = DeploymentOperationState::Running { step: DeploymentStep::ResolvingPeers });
+            }
+            pending
+        }).await;
+
+        if started.not() {
+            return;
+        }
+
+        let cluster_manager = Arc::clone(self);
+        tokio::spawn(async move {
+            let state = match cluster_manager.deploy_tracked(cluster_id, Some(operation_id)).await {
+                Ok(()) => DeploymentOperationState::Succeeded,
+                Err(error) => {
+                    error!("Failed to deploy cluster <{cluster_id}>, due to:\n  {error}");
+                    let step = cluster_manager.resources_manager.resources(|resources| {
+                        match resources.get::<DeploymentOperation>(operation_id) {
+                            Some(DeploymentOperation { state: DeploymentOperationState::Running { step }, .. }) => step,
+                            _ => DeploymentStep::ResolvingPeers,
+                        }
+                    }).await;
+                    DeploymentOperationState::Failed { error: DeploymentOperationError::StepFailed { step, cause: error.to_string() } }
+                }
+            };
+            cluster_manager.update_operation(Some(operation_id), state).await;
+        });
+    }
+
+    /// Starts the scheduled deployments, whose start time was reached, warns the members of deployments,
+    /// whose lease is about to expire, and undeploys the clusters, whose lease expired.
+    #[tracing::instrument(skip(self), level="trace")]
+    pub async fn process_schedule(self: &Arc<Self>, now: DateTime<Utc>) {
+        let (deployments, pending_operations) = self.resources_manager.resources(|resources| {
+            let deployments = resources.iter::<ClusterDeployment>()
+                .cloned()
+                .collect::<Vec<_>>();
+            let pending_operations = resources.iter::<DeploymentOperation>()
+                .filter(|operation| operation.state == DeploymentOperationState::Pending)
+                .cloned()
+                .collect::<Vec<_>>();
+            (deployments, pending_operations)
         }).await;
-        if let Err(error) = self.deploy(cluster_id).await {
-            error!("Failed to deploy cluster <{cluster_id}>, due to:\n  {error}");
+
+        let lease_warning = chrono::Duration::from_std(self.options.lease_warning)
+            .unwrap_or(chrono::Duration::zero());
+
+        for deployment in deployments {
+            let cluster_id = deployment.id;
+            let pending_operations = pending_operations.iter()
+                .filter(|operation| operation.cluster_id == cluster_id);
+
+            if deployment.is_expired(now) {
+                info!("Lease of the deployment of cluster <{cluster_id}> expired. Undeploying the cluster.");
+                for operation in pending_operations {
+                    self.update_operation(Some(operation.id), DeploymentOperationState::Failed { error: DeploymentOperationError::LeaseExpired }).await;
+                }
+                let project_id = self.resources_manager.resources(|resources| project::project_of_resource(resources, cluster_id)).await;
+                if let Err(cause) = self.delete_cluster_deployment(project_id, cluster_id).await {
+                    error!("Failed to undeploy cluster <{cluster_id}> after its lease expired:\n  {cause}");
+                }
+                continue;
+            }
+
+            if deployment.is_started(now).not() {
+                continue;
+            }
+
+            for operation in pending_operations {
+                info!("Starting scheduled deployment of cluster <{cluster_id}>.");
+                self.run_operation(operation.id, cluster_id).await;
+            }
+
+            if let Some(end) = deployment.end().filter(|end| *end - lease_warning <= now) {
+                let first_warning = self.lease_warnings.lock().await.insert((cluster_id, end));
+                if first_warning {
+                    self.warn_lease_expiring(cluster_id, end).await;
+                }
+            }
         }
-        Ok(cluster_id)
+    }
+
+    async fn warn_lease_expiring(&self, cluster_id: ClusterId, end: DateTime<Utc>) {
+        warn!("Lease of the deployment of cluster <{cluster_id}> expires at {end}.");
+
+        let members = self.resources_manager.resources(|resources| {
+            resources.iter::<PeerConfiguration>()
+                .filter_map(|configuration| configuration.cluster_assignment.as_ref())
+                .filter(|assignment| assignment.id == cluster_id)
+                .flat_map(|assignment| assignment.assignments.iter().map(|member| member.peer_id))
+                .collect::<Vec<_>>()
+        }).await;
+
+        for member in members {
+            let message = downstream::Message::ClusterLeaseExpiring(ClusterLeaseExpiring {
+                cluster_id: Some(cluster_id.into()),
+                end_ms: end.timestamp_millis(),
+            });
+            if let Err(cause) = self.peer_messaging_broker.send_to_peer(member, message).await {
+                debug!("Could not warn peer <{member}> about the expiring lease of cluster <{cluster_id}>: {cause}");
+            }
+        }
+    }
+
+    /// Periodically processes the schedule of the deployments in the background, unless disabled in the options.
+    pub fn spawn_scheduler(self: &Arc<Self>) {
+        let Some(interval) = self.options.schedule_interval else {
+            info!("Scheduling of cluster deployments is disabled.");
+            return;
+        };
+
+        let cluster_manager = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                interval.tick().await;
+                cluster_manager.process_schedule(Utc::now()).await;
+            }
+        });
+    }
+
+    async fn update_operation(&self, operation_id: Option<DeploymentOperationId>, state: DeploymentOperationState) {
+        let Some(operation_id) = operation_id else { return };
+
+        self.resources_manager.resources_mut(|resources| {
+            resources.update::<DeploymentOperation>(operation_id)
+                .modify(|operation| operation.state = state);
+        }).await;
+    }
+
+    pub async fn find_deployment_operation(&self, project_id: ProjectId, id: DeploymentOperationId) -> Option<DeploymentOperation> {
+        self.resources_manager.resources(|resources| {
+            resources.get::<DeploymentOperation>(id)
+                .filter(|operation| project::is_owned_by(resources, operation.cluster_id, project_id))
+        }).await
     }
 
     #[tracing::instrument(skip(self), level="trace")]
-    pub async fn delete_cluster_deployment(&self, cluster_id: ClusterId) -> Result<ClusterDeployment, DeleteClusterDeploymentError> {
+    pub async fn list_deployment_operations(&self, project_id: ProjectId, cluster_id: Option<ClusterId>) -> Vec<DeploymentOperation> {
+        self.resources_manager.resources(|resources| {
+            resources.iter::<DeploymentOperation>()
+                .filter(|operation| cluster_id.is_none() || cluster_id == Some(operation.cluster_id))
+                .filter(|operation| project::is_owned_by(resources, operation.cluster_id, project_id))
+                .cloned()
+                .collect::<Vec<_>>()
+        }).await
+    }
+
+    pub async fn delete_cluster_deployment(&self, project_id: ProjectId, cluster_id: ClusterId) -> Result<ClusterDeployment, DeleteClusterDeploymentError> {
+        let (result, _) = self.delete_cluster_deployment_with_snapshot(project_id, cluster_id).await;
+        result
+    }
+
+    /// Like [`ClusterManager::delete_cluster_deployment`], but also captures the deleted deployment.
+    #[tracing::instrument(skip(self), level="trace")]
+    pub async fn delete_cluster_deployment_with_snapshot(&self, project_id: ProjectId, cluster_id: ClusterId) -> (Result<ClusterDeployment, DeleteClusterDeploymentError>, Option<Snapshot<ClusterDeployment>>) {
+        let mut snapshot = None;
+        let result = self.delete_cluster_deployment_inner(project_id, cluster_id, &mut snapshot).await;
+        (result, snapshot)
+    }
 
-        let (deployment, configuration) = self.resources_manager
+    async fn delete_cluster_deployment_inner(&self, project_id: ProjectId, cluster_id: ClusterId, snapshot: &mut Option<Snapshot<ClusterDeployment>>) -> Result<ClusterDeployment, DeleteClusterDeploymentError> {
+
+        let _vpn_change = self.vpn.lock_change().await;
+
+        let (deployment, configuration, unassigned) = self.resources_manager
             .resources_mut(|resources| {
-                resources.remove::<ClusterDeployment>(cluster_id)
-                    .map(|deployment| (deployment, resources.get::<ClusterConfiguration>(cluster_id)))
+                if project::is_owned_by(resources, cluster_id, project_id).not()
+                    || resources.get::<ClusterDeployment>(cluster_id).is_none() {
+                    return Err(DeleteClusterDeploymentError::ClusterDeploymentNotFound { cluster_id });
+                }
+                let running = resources.iter::<DeploymentOperation>()
+                    .any(|operation| operation.cluster_id == cluster_id && matches!(operation.state, DeploymentOperationState::Running { .. }));
+                if running {
+                    if let Some(configuration) = resources.get::<ClusterConfiguration>(cluster_id) {
+                        return Err(DeleteClusterDeploymentError::IllegalClusterState {
+                            cluster_id,
+                            cluster_name: configuration.name,
+                            actual_state: ClusterState::Deploying,
+                            required_states: vec![
+                                ClusterState::Undeployed,
+                                ClusterState::Deployed(DeployedClusterState::Unhealthy),
+                                ClusterState::Deployed(DeployedClusterState::Healthy),
+                            ],
+                        });
+                    }
+                }
+                let deployment = resources.remove::<ClusterDeployment>(cluster_id)
+                    .ok_or(DeleteClusterDeploymentError::ClusterDeploymentNotFound { cluster_id })?;
+                *snapshot = Some(Snapshot { before: Some(Clone::clone(&deployment)), after: None });
+                resources.remove::<CanServerPortAllocation>(cluster_id);
+                for operation in resources.iter_mut::<DeploymentOperation>() {
+                    if operation.cluster_id == cluster_id && operation.state == DeploymentOperationState::Pending {
+                        operation.state = DeploymentOperationState::Failed { error: DeploymentOperationError::DeploymentDeleted };
+                    }
+                }
+                let members = resources.iter::<PeerConfiguration>()
+                    .filter_map(|configuration| configuration.cluster_assignment.as_ref())
+                    .filter(|assignment| assignment.id == cluster_id)
+                    .flat_map(|assignment| assignment.assignments.iter().map(|member| member.peer_id))
+                    .collect::<HashSet<_>>();
+                let unassigned = members.into_iter()
+                    .filter_map(|member| {
+                        let configuration = resources.get::<PeerConfiguration>(member)
+                            .filter(|configuration| configuration.cluster_assignment.as_ref().is_some_and(|assignment| assignment.id == cluster_id))?;
+                        let configuration = PeerConfiguration { cluster_assignment: None, ..configuration };
+                        resources.insert(member, Clone::clone(&configuration));
+                        Some((member, configuration))
+                    })
+                    .collect::<Vec<_>>();
+                let configuration = resources.get::<ClusterConfiguration>(cluster_id);
+                if configuration.is_none() {
+                    resources.remove::<OwningProject>(cluster_id);
+                }
+                Ok((deployment, configuration, unassigned))
             })
-            .await
-            .ok_or(DeleteClusterDeploymentError::ClusterDeploymentNotFound { cluster_id })?;
+            .await?;
+
+        for (member, peer_configuration) in unassigned {
+            if let Err(cause) = self.peer_messaging_broker.send_peer_configuration(member, peer_configuration).await {
+                debug!("Could not send the configuration without cluster <{cluster_id}> to peer <{member}>: {cause}");
+            }
+        }
+
+        self.lease_warnings.lock().await
+            .retain(|(warned_cluster_id, _)| *warned_cluster_id != cluster_id);
 
         if let Some(configuration) = configuration {
-            if let Vpn::Enabled { vpn_client } = &self.vpn {
-                vpn_client.delete_cluster(cluster_id).await
+            if let Vpn::Enabled { vpn_client, .. } = &self.vpn {
+                vpn_client.delete_cluster(project_id, cluster_id).await
                     .map_err(|error| DeleteClusterDeploymentError::Internal { cluster_id, cluster_name: configuration.name, cause: error.to_string() })?;
             }
         }
@@ -202,21 +820,40 @@ impl ClusterManager {
         Ok(deployment)
     }
 
-    pub async fn find_deployment(&self, id: ClusterId) -> Option<ClusterDeployment> {
+    pub async fn find_deployment(&self, project_id: ProjectId, id: ClusterId) -> Option<ClusterDeployment> {
         self.resources_manager.resources(|resources| {
             resources.get::<ClusterDeployment>(id)
+                .filter(|_| project::is_owned_by(resources, id, project_id))
         }).await
     }
 
     #[tracing::instrument(skip(self), level="trace")]
-    pub async fn list_deployment(&self) -> Vec<ClusterDeployment> {
+    pub async fn list_deployment(&self, project_id: ProjectId) -> Vec<ClusterDeployment> {
         self.resources_manager.resources(|resources| {
-            resources.iter::<ClusterDeployment>().cloned().collect::<Vec<_>>()
+            resources.iter::<ClusterDeployment>()
+                .filter(|deployment| project::is_owned_by(resources, deployment.id, project_id))
+                .cloned()
+                .collect::<Vec<_>>()
         }).await
     }
 }
 
-fn determine_member_interface_mapping(
+/// Finds another deployment, whose time slot overlaps with the deployment and which uses some of the same devices.
+fn find_reservation_conflict(resources: &Resources, deployment: &ClusterDeployment, devices: &HashSet<DeviceId>) -> Option<(ClusterId, Vec<DeviceId>)> {
+    resources.iter::<ClusterDeployment>()
+        .filter(|other| other.id != deployment.id && other.overlaps(deployment))
+        .find_map(|other| {
+            let other_configuration = resources.get::<ClusterConfiguration>(other.id)?;
+            let mut shared_devices = devices.intersection(&other_configuration.devices)
+                .copied()
+                .collect::<Vec<_>>();
+            shared_devices.sort_by_key(|device| device.0);
+            Some((other.id, shared_devices))
+                .filter(|(_, shared_devices)| shared_devices.is_empty().not())
+        })
+}
+
+pub(crate) fn determine_member_interface_mapping(
     cluster_devices: HashSet<DeviceId>,
     all_peers: Vec<PeerDescriptor>,
     leader: PeerId,
@@ -226,6 +863,9 @@ fn determine_member_interface_mapping(
 
     result.insert(leader, Vec::new()); //will later be replaced, if leader has devices
 
+    let mut cluster_devices = cluster_devices.into_iter().collect::<Vec<_>>();
+    cluster_devices.sort_by_key(|device_id| device_id.0); //keep the order of the interfaces stable between deployments
+
     for device_id in cluster_devices {
         let member_interfaces = all_peers.iter().find_map(|peer| {
             let interfaces: Vec<NetworkInterfaceDescriptor> = peer.topology.devices.iter()
@@ -254,18 +894,37 @@ fn determine_member_interface_mapping(
 #[derive(Clone)]
 pub struct ClusterManagerOptions {
     pub can_server_port_range_start: u16,
+    pub can_server_port_range_end: u16,
+    pub redeploy_interval: Option<Duration>,
+    pub schedule_interval: Option<Duration>,
+    pub lease_warning: Duration,
 }
 impl ClusterManagerOptions {
     pub fn load(config: &config::Config) -> Result<Self, opendut_util::settings::LoadError> {
         let can_server_port_range_start = config.get::<u16>("peer.can.server_port_range_start")?;
+        let can_server_port_range_end = config.get::<u16>("peer.can.server_port_range_end")?;
+        let redeploy_interval = config.get::<u64>("cluster.redeploy.interval.ms")?;
+        let schedule_interval = config.get::<u64>("cluster.schedule.interval.ms")?;
+        let lease_warning = config.get::<u64>("cluster.lease.warning.ms")?;
 
         Ok(ClusterManagerOptions {
             can_server_port_range_start,
+            can_server_port_range_end,
+            redeploy_interval: Some(Duration::from_millis(redeploy_interval)).filter(|interval| interval.is_zero().not()),
+            schedule_interval: Some(Duration::from_millis(schedule_interval)).filter(|interval| interval.is_zero().not()),
+            lease_warning: Duration::from_millis(lease_warning),
         })
     }
+
+    pub fn can_server_port_range(&self) -> CanServerPortRange {
+        CanServerPortRange {
+            start: Port(self.can_server_port_range_start),
+            end: Port(self.can_server_port_range_end),
+        }
+    }
 }
 #[derive(Debug, thiserror::Error)]
-enum DetermineMemberInterfaceMappingError {
+pub(crate) enum DetermineMemberInterfaceMappingError {
     #[error("Peer for device <{device_id}> not found.")]
     PeerForDeviceNotFound { device_id: DeviceId },
 }
@@ -289,18 +948,26 @@ mod test {
     use opendut_types::peer::executor::{ContainerCommand, ContainerImage, ContainerName, Engine, ExecutorDescriptor, ExecutorDescriptors};
     use opendut_types::topology::{DeviceDescription, DeviceDescriptor, DeviceId, DeviceName, Topology};
     use opendut_types::util::net::{NetworkInterfaceConfiguration, NetworkInterfaceName};
+    use opendut_types::vpn::reconciliation::VpnReconciliationReport;
+    use opendut_types::vpn::VpnPeerConfiguration;
+    use opendut_vpn::{CreatePeerError, CreateVpnPeerConfigurationError, DeleteClusterError, DeletePeerError, ExpectedVpnState, ReconcileError, VpnManagementClient};
 
     use crate::actions::{CreateClusterConfigurationParams, StorePeerDescriptorParams};
+    use crate::limits::manager::{LimitsManager, LimitsOptions};
     use crate::peer::broker::{PeerMessagingBroker, PeerMessagingBrokerOptions};
     use crate::resources::manager::ResourcesManager;
+    use crate::secrets::manager::{SecretsManager, SecretsManagerOptions, SecretsManagerRef};
     use crate::settings;
 
     use super::*;
 
     mod deploy_cluster {
         use opendut_carl_api::proto::services::peer_messaging_broker::ApplyPeerConfiguration;
+        use crate::cluster::ports::CanServerPortAllocation;
         use opendut_types::peer::configuration::PeerConfiguration;
 
+        use crate::vpn::reconciler::VpnReconciler;
+
         use super::*;
 
         #[rstest]
@@ -318,17 +985,24 @@ mod test {
                 name: ClusterName::try_from("MyAwesomeCluster").unwrap(),
                 leader: leader_id,
                 devices: HashSet::from([peer_a.device, peer_b.device]),
+                revision: 0,
             };
 
             actions::store_peer_descriptor(StorePeerDescriptorParams {
                 resources_manager: Arc::clone(&fixture.resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
                 vpn: Vpn::Disabled,
+                project: ProjectId::DEFAULT,
                 peer_descriptor: Clone::clone(&peer_a.descriptor),
             }).await?;
 
             actions::store_peer_descriptor(StorePeerDescriptorParams {
                 resources_manager: Arc::clone(&fixture.resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
                 vpn: Vpn::Disabled,
+                project: ProjectId::DEFAULT,
                 peer_descriptor: Clone::clone(&peer_b.descriptor),
             }).await?;
 
@@ -339,6 +1013,8 @@ mod test {
 
             actions::create_cluster_configuration(CreateClusterConfigurationParams {
                 resources_manager: Arc::clone(&fixture.resources_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                project: ProjectId::DEFAULT,
                 cluster_configuration,
             }).await?;
 
@@ -356,12 +1032,14 @@ mod test {
                                 vpn_address: peer_a.remote_host,
                                 can_server_port: Port(fixture.cluster_manager_options.can_server_port_range_start + 1),
                                 device_interfaces: peer_a.descriptor.topology.devices.clone().into_iter().map(|device| device.interface).collect(),
+                                wireguard: None,
                             }),
                             eq(PeerClusterAssignment {
                                 peer_id: peer_b.id,
                                 vpn_address: peer_b.remote_host,
                                 can_server_port: Port(fixture.cluster_manager_options.can_server_port_range_start),
                                 device_interfaces: peer_b.descriptor.topology.devices.clone().into_iter().map(|device| device.interface).collect(),
+                                wireguard: None,
                             }),
                         ],
                         unordered_elements_are![
@@ -370,12 +1048,14 @@ mod test {
                                 vpn_address: peer_a.remote_host,
                                 can_server_port: Port(fixture.cluster_manager_options.can_server_port_range_start),
                                 device_interfaces: peer_a.descriptor.topology.devices.into_iter().map(|device| device.interface).collect(),
+                                wireguard: None,
                             }),
                             eq(PeerClusterAssignment {
                                 peer_id: peer_b.id,
                                 vpn_address: peer_b.remote_host,
                                 can_server_port: Port(fixture.cluster_manager_options.can_server_port_range_start + 1),
                                 device_interfaces: peer_b.descriptor.topology.devices.into_iter().map(|device| device.interface).collect(),
+                                wireguard: None,
                             }),
                         ],
                     ]
@@ -391,6 +1071,254 @@ mod test {
             Ok(())
         }
 
+        #[rstest]
+        #[tokio::test]
+        async fn redeploy_cluster_when_a_member_reconnects_with_another_address(
+            fixture: Fixture,
+            peer_a: PeerFixture,
+            peer_b: PeerFixture,
+        ) -> anyhow::Result<()> {
+
+            let cluster_id = ClusterId::random();
+
+            for peer in [&peer_a, &peer_b] {
+                actions::store_peer_descriptor(StorePeerDescriptorParams {
+                    resources_manager: Arc::clone(&fixture.resources_manager),
+                    secrets_manager: Arc::clone(&fixture.secrets_manager),
+                    limits_manager: LimitsManager::new(LimitsOptions::default()),
+                    vpn: Vpn::Disabled,
+                    project: ProjectId::DEFAULT,
+                    peer_descriptor: Clone::clone(&peer.descriptor),
+                }).await?;
+            }
+
+            let mut peer_a_rx = peer_open(peer_a.id, peer_a.remote_host, Arc::clone(&fixture.peer_messaging_broker)).await;
+            let _peer_b_rx = peer_open(peer_b.id, peer_b.remote_host, Arc::clone(&fixture.peer_messaging_broker)).await;
+
+            actions::create_cluster_configuration(CreateClusterConfigurationParams {
+                resources_manager: Arc::clone(&fixture.resources_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                project: ProjectId::DEFAULT,
+                cluster_configuration: ClusterConfiguration {
+                    id: cluster_id,
+                    name: ClusterName::try_from("MyAwesomeCluster").unwrap(),
+                    leader: peer_a.id,
+                    devices: HashSet::from([peer_a.device, peer_b.device]),
+                    revision: 0,
+                },
+            }).await?;
+            fixture.resources_manager.insert(cluster_id, ClusterDeployment::new(cluster_id)).await;
+
+            fixture.testee.deploy(cluster_id).await?;
+            receive_peer_configuration_message(&mut peer_a_rx).await;
+
+            assert_that!(fixture.testee.redeploy_outdated_clusters().await, empty());
+
+            let peer_b_new_remote_host = IpAddr::from_str("2.2.2.2")?;
+            let _peer_b_rx = peer_open(peer_b.id, peer_b_new_remote_host, Arc::clone(&fixture.peer_messaging_broker)).await;
+
+            assert_that!(fixture.testee.redeploy_outdated_clusters().await, elements_are![eq(cluster_id)]);
+
+            let result = receive_peer_configuration_message(&mut peer_a_rx).await;
+            assert_that!(result.cluster_assignment.unwrap().assignments, contains(all!(
+                field!(PeerClusterAssignment.peer_id, eq(peer_b.id)),
+                field!(PeerClusterAssignment.vpn_address, eq(peer_b_new_remote_host)),
+            )));
+
+            assert_that!(fixture.testee.redeploy_outdated_clusters().await, empty());
+
+            Ok(())
+        }
+
+        #[rstest]
+        #[tokio::test]
+        async fn keep_the_vpn_group_of_a_cluster_deployed_while_the_vpn_is_reconciled(
+            fixture: Fixture,
+            peer_a: PeerFixture,
+            peer_b: PeerFixture,
+        ) -> anyhow::Result<()> {
+
+            let cluster_id = ClusterId::random();
+            let vpn_client = Arc::new(InMemoryVpn::default());
+            let vpn = Vpn::Enabled { vpn_client: Clone::clone(&vpn_client) as _, changes: Default::default() };
+            let testee = ClusterManager::new(
+                Arc::clone(&fixture.resources_manager),
+                Arc::clone(&fixture.peer_messaging_broker),
+                Clone::clone(&vpn),
+                fixture.cluster_manager_options.clone(),
+            );
+            let reconciler = VpnReconciler::new(Arc::clone(&fixture.resources_manager), vpn);
+
+            for peer in [&peer_a, &peer_b] {
+                actions::store_peer_descriptor(StorePeerDescriptorParams {
+                    resources_manager: Arc::clone(&fixture.resources_manager),
+                    secrets_manager: Arc::clone(&fixture.secrets_manager),
+                    limits_manager: LimitsManager::new(LimitsOptions::default()),
+                    vpn: Vpn::Disabled,
+                    project: ProjectId::DEFAULT,
+                    peer_descriptor: Clone::clone(&peer.descriptor),
+                }).await?;
+            }
+            let (_peer_a_tx, _peer_a_rx) = fixture.peer_messaging_broker.open(peer_a.id, peer_a.remote_host).await;
+            let (_peer_b_tx, _peer_b_rx) = fixture.peer_messaging_broker.open(peer_b.id, peer_b.remote_host).await;
+
+            actions::create_cluster_configuration(CreateClusterConfigurationParams {
+                resources_manager: Arc::clone(&fixture.resources_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                project: ProjectId::DEFAULT,
+                cluster_configuration: ClusterConfiguration {
+                    id: cluster_id,
+                    name: ClusterName::try_from("MyAwesomeCluster").unwrap(),
+                    leader: peer_a.id,
+                    devices: HashSet::from([peer_a.device, peer_b.device]),
+                    revision: 0,
+                },
+            }).await?;
+
+            // The reconciliation determines the expected state before the cluster is deployed
+            // and only compares it with the VPN after the deployment started.
+            let reconciliation = tokio::spawn({
+                let reconciler = Arc::clone(&reconciler);
+                async move { reconciler.reconcile(false).await }
+            });
+            tokio::time::sleep(Duration::from_millis(20)).await;
+
+            fixture.resources_manager.insert(cluster_id, ClusterDeployment::new(cluster_id)).await;
+            testee.deploy(cluster_id).await?;
+            reconciliation.await??;
+
+            assert_that!(vpn_client.clusters(), elements_are![eq((ProjectId::DEFAULT, cluster_id))]);
+
+            Ok(())
+        }
+
+        #[rstest]
+        #[tokio::test]
+        async fn refuse_clusters_whose_traffic_the_vpn_cannot_restrict(
+            fixture: Fixture,
+            peer_a: PeerFixture,
+            peer_b: PeerFixture,
+        ) -> anyhow::Result<()> {
+
+            let cluster_id = ClusterId::random();
+            let vpn_client = Arc::new(InMemoryVpn { unsupported_protocols: vec![NetworkProtocol::Gre], ..Default::default() });
+            let testee = ClusterManager::new(
+                Arc::clone(&fixture.resources_manager),
+                Arc::clone(&fixture.peer_messaging_broker),
+                Vpn::Enabled { vpn_client: Clone::clone(&vpn_client) as _, changes: Default::default() },
+                fixture.cluster_manager_options.clone(),
+            );
+
+            for peer in [&peer_a, &peer_b] {
+                actions::store_peer_descriptor(StorePeerDescriptorParams {
+                    resources_manager: Arc::clone(&fixture.resources_manager),
+                    secrets_manager: Arc::clone(&fixture.secrets_manager),
+                    limits_manager: LimitsManager::new(LimitsOptions::default()),
+                    vpn: Vpn::Disabled,
+                    project: ProjectId::DEFAULT,
+                    peer_descriptor: Clone::clone(&peer.descriptor),
+                }).await?;
+            }
+            let (_peer_a_tx, _peer_a_rx) = fixture.peer_messaging_broker.open(peer_a.id, peer_a.remote_host).await;
+            let (_peer_b_tx, _peer_b_rx) = fixture.peer_messaging_broker.open(peer_b.id, peer_b.remote_host).await;
+
+            actions::create_cluster_configuration(CreateClusterConfigurationParams {
+                resources_manager: Arc::clone(&fixture.resources_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                project: ProjectId::DEFAULT,
+                cluster_configuration: ClusterConfiguration {
+                    id: cluster_id,
+                    name: ClusterName::try_from("MyAwesomeCluster").unwrap(),
+                    leader: peer_a.id,
+                    devices: HashSet::from([peer_a.device, peer_b.device]),
+                    revision: 0,
+                },
+            }).await?;
+
+            let validation = testee.validate_deployment(ProjectId::DEFAULT, cluster_id).await?;
+            assert_that!(validation.issues, elements_are![
+                eq(ClusterDeploymentIssue::VpnProtocolUnsupported { protocol: String::from("gre") }),
+            ]);
+
+            assert_that!(
+                testee.deploy(cluster_id).await,
+                err(matches_pattern!(DeployClusterError::VpnProtocolUnsupported { cluster_id: eq(cluster_id), protocol: eq(NetworkProtocol::Gre) }))
+            );
+            assert_that!(vpn_client.clusters(), empty());
+            assert_that!(fixture.resources_manager.get::<PeerConfiguration>(peer_b.id).await, some(field!(PeerConfiguration.cluster_assignment, none())));
+
+            Ok(())
+        }
+
+        #[rstest]
+        #[tokio::test]
+        async fn allocate_distinct_can_server_ports_to_clusters_with_the_same_leader(
+            fixture: Fixture,
+            peer_a: PeerFixture,
+            peer_b: PeerFixture,
+        ) -> anyhow::Result<()> {
+
+            let range_start = fixture.cluster_manager_options.can_server_port_range_start;
+            let testee = ClusterManager::new(
+                Arc::clone(&fixture.resources_manager),
+                Arc::clone(&fixture.peer_messaging_broker),
+                Vpn::Disabled,
+                ClusterManagerOptions {
+                    can_server_port_range_end: range_start + 3,
+                    ..fixture.cluster_manager_options.clone()
+                },
+            );
+
+            for peer in [&peer_a, &peer_b] {
+                actions::store_peer_descriptor(StorePeerDescriptorParams {
+                    resources_manager: Arc::clone(&fixture.resources_manager),
+                    secrets_manager: Arc::clone(&fixture.secrets_manager),
+                    limits_manager: LimitsManager::new(LimitsOptions::default()),
+                    vpn: Vpn::Disabled,
+                    project: ProjectId::DEFAULT,
+                    peer_descriptor: Clone::clone(&peer.descriptor),
+                }).await?;
+            }
+            let _peer_a_rx = peer_open(peer_a.id, peer_a.remote_host, Arc::clone(&fixture.peer_messaging_broker)).await;
+            let _peer_b_rx = peer_open(peer_b.id, peer_b.remote_host, Arc::clone(&fixture.peer_messaging_broker)).await;
+
+            let mut cluster_ids = Vec::new();
+            for name in ["FirstCluster", "SecondCluster", "ThirdCluster"] {
+                let cluster_id = ClusterId::random();
+                actions::create_cluster_configuration(CreateClusterConfigurationParams {
+                    resources_manager: Arc::clone(&fixture.resources_manager),
+                    limits_manager: LimitsManager::new(LimitsOptions::default()),
+                    project: ProjectId::DEFAULT,
+                    cluster_configuration: ClusterConfiguration {
+                        id: cluster_id,
+                        name: ClusterName::try_from(name).unwrap(),
+                        leader: peer_a.id,
+                        devices: HashSet::from([peer_a.device, peer_b.device]),
+                        revision: 0,
+                    },
+                }).await?;
+                cluster_ids.push(cluster_id);
+            }
+
+            testee.deploy(cluster_ids[0]).await?;
+            testee.deploy(cluster_ids[1]).await?;
+
+            let first = fixture.resources_manager.get::<CanServerPortAllocation>(cluster_ids[0]).await.unwrap();
+            let second = fixture.resources_manager.get::<CanServerPortAllocation>(cluster_ids[1]).await.unwrap();
+            let mut ports = first.ports.into_iter().chain(second.ports)
+                .map(|(_, port)| port.0)
+                .collect::<Vec<_>>();
+            ports.sort();
+            assert_that!(ports, elements_are![eq(range_start), eq(range_start + 1), eq(range_start + 2), eq(range_start + 3)]);
+
+            assert_that!(
+                testee.deploy(cluster_ids[2]).await,
+                err(matches_pattern!(DeployClusterError::CanServerPortsExhausted { .. }))
+            );
+
+            Ok(())
+        }
+
         async fn peer_open(peer_id: PeerId, peer_remote_host: IpAddr, peer_messaging_broker: PeerMessagingBrokerRef) -> mpsc::Receiver<Downstream> {
             let (_peer_tx, mut peer_rx) = peer_messaging_broker.open(peer_id, peer_remote_host).await;
             receive_peer_configuration_message(&mut peer_rx).await; //initial peer configuration after connect
@@ -422,6 +1350,335 @@ mod test {
         Ok(())
     }
 
+    #[rstest]
+    #[tokio::test]
+    async fn store_cluster_deployment_should_record_the_failure_in_the_operation(
+        fixture: Fixture,
+        peer_a: PeerFixture,
+    ) -> anyhow::Result<()> {
+        let cluster_id = ClusterId::random();
+        let unknown_device = DeviceId::random();
+
+        actions::store_peer_descriptor(StorePeerDescriptorParams {
+            resources_manager: Arc::clone(&fixture.resources_manager),
+            secrets_manager: Arc::clone(&fixture.secrets_manager),
+            limits_manager: LimitsManager::new(LimitsOptions::default()),
+            vpn: Vpn::Disabled,
+            project: ProjectId::DEFAULT,
+            peer_descriptor: Clone::clone(&peer_a.descriptor),
+        }).await?;
+
+        actions::create_cluster_configuration(CreateClusterConfigurationParams {
+            resources_manager: Arc::clone(&fixture.resources_manager),
+            limits_manager: LimitsManager::new(LimitsOptions::default()),
+            project: ProjectId::DEFAULT,
+            cluster_configuration: ClusterConfiguration {
+                id: cluster_id,
+                name: ClusterName::try_from("MyAwesomeCluster").unwrap(),
+                leader: peer_a.id,
+                devices: HashSet::from([peer_a.device, unknown_device]),
+                revision: 0,
+            },
+        }).await?;
+
+        let operation = fixture.testee.store_cluster_deployment(ProjectId::DEFAULT, ClusterDeployment::new(cluster_id)).await?;
+        assert_that!(operation.cluster_id, eq(cluster_id));
+
+        let finished_operation = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let operation = fixture.testee.find_deployment_operation(ProjectId::DEFAULT, operation.id).await
+                    .expect("Deployment operation should be stored.");
+                if operation.is_finished() {
+                    break operation;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await?;
+
+        assert_that!(finished_operation.state, matches_pattern!(DeploymentOperationState::Failed {
+            error: matches_pattern!(DeploymentOperationError::StepFailed {
+                step: eq(DeploymentStep::ResolvingPeers),
+                cause: anything(),
+            }),
+        }));
+        assert_that!(
+            fixture.testee.list_deployment_operations(ProjectId::DEFAULT, Some(cluster_id)).await,
+            elements_are![field!(DeploymentOperation.id, eq(operation.id))]
+        );
+
+        assert_that!(
+            fixture.testee.store_cluster_deployment(ProjectId::DEFAULT, ClusterDeployment::new(ClusterId::random())).await,
+            err(matches_pattern!(StoreClusterDeploymentError::ClusterConfigurationNotFound { .. }))
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn store_cluster_deployment_should_refuse_clusters_of_other_projects(
+        fixture: Fixture,
+        peer_a: PeerFixture,
+    ) -> anyhow::Result<()> {
+        let cluster_id = ClusterId::random();
+        let other_project = ProjectId::random();
+
+        actions::create_cluster_configuration(CreateClusterConfigurationParams {
+            resources_manager: Arc::clone(&fixture.resources_manager),
+            limits_manager: LimitsManager::new(LimitsOptions::default()),
+            project: ProjectId::DEFAULT,
+            cluster_configuration: ClusterConfiguration {
+                id: cluster_id,
+                name: ClusterName::try_from("MyAwesomeCluster").unwrap(),
+                leader: peer_a.id,
+                devices: HashSet::from([peer_a.device]),
+                revision: 0,
+            },
+        }).await?;
+
+        assert_that!(
+            fixture.testee.store_cluster_deployment(other_project, ClusterDeployment::new(cluster_id)).await,
+            err(matches_pattern!(StoreClusterDeploymentError::ClusterConfigurationNotFound { cluster_id: eq(cluster_id) }))
+        );
+        assert_that!(fixture.testee.find_deployment(ProjectId::DEFAULT, cluster_id).await, none());
+        assert_that!(fixture.testee.find_deployment(other_project, cluster_id).await, none());
+        assert_that!(fixture.testee.is_accessible_from(ProjectId::DEFAULT, cluster_id).await, eq(true));
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn delete_cluster_deployment_should_refuse_while_an_operation_is_running(
+        fixture: Fixture,
+        peer_a: PeerFixture,
+    ) -> anyhow::Result<()> {
+        let cluster_id = ClusterId::random();
+
+        actions::create_cluster_configuration(CreateClusterConfigurationParams {
+            resources_manager: Arc::clone(&fixture.resources_manager),
+            limits_manager: LimitsManager::new(LimitsOptions::default()),
+            project: ProjectId::DEFAULT,
+            cluster_configuration: ClusterConfiguration {
+                id: cluster_id,
+                name: ClusterName::try_from("MyAwesomeCluster").unwrap(),
+                leader: peer_a.id,
+                devices: HashSet::from([peer_a.device]),
+                revision: 0,
+            },
+        }).await?;
+        fixture.resources_manager.insert(cluster_id, ClusterDeployment::new(cluster_id)).await;
+        let operation = DeploymentOperation {
+            id: DeploymentOperationId::random(),
+            cluster_id,
+            state: DeploymentOperationState::Running { step: DeploymentStep::AssigningPeers },
+        };
+        fixture.resources_manager.insert(operation.id, Clone::clone(&operation)).await;
+
+        assert_that!(
+            fixture.testee.delete_cluster_deployment(ProjectId::DEFAULT, cluster_id).await,
+            err(matches_pattern!(DeleteClusterDeploymentError::IllegalClusterState {
+                cluster_id: eq(cluster_id),
+                cluster_name: anything(),
+                actual_state: eq(ClusterState::Deploying),
+                required_states: anything(),
+            }))
+        );
+        assert_that!(fixture.testee.find_deployment(ProjectId::DEFAULT, cluster_id).await, some(anything()));
+
+        fixture.resources_manager.insert(operation.id, DeploymentOperation { state: DeploymentOperationState::Succeeded, ..operation }).await;
+        assert_that!(fixture.testee.delete_cluster_deployment(ProjectId::DEFAULT, cluster_id).await, ok(anything()));
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn scheduled_deployment_should_be_deployed_at_its_start_and_undeployed_at_the_end_of_its_lease(
+        fixture: Fixture,
+        peer_a: PeerFixture,
+        peer_b: PeerFixture,
+    ) -> anyhow::Result<()> {
+        let cluster_id = ClusterId::random();
+        let other_cluster_id = ClusterId::random();
+
+        for peer in [&peer_a, &peer_b] {
+            actions::store_peer_descriptor(StorePeerDescriptorParams {
+                resources_manager: Arc::clone(&fixture.resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                vpn: Vpn::Disabled,
+                project: ProjectId::DEFAULT,
+                peer_descriptor: Clone::clone(&peer.descriptor),
+            }).await?;
+        }
+        let (_peer_a_tx, _peer_a_rx) = fixture.peer_messaging_broker.open(peer_a.id, peer_a.remote_host).await;
+        let (_peer_b_tx, _peer_b_rx) = fixture.peer_messaging_broker.open(peer_b.id, peer_b.remote_host).await;
+
+        for (id, name) in [(cluster_id, "MyAwesomeCluster"), (other_cluster_id, "MyOtherCluster")] {
+            actions::create_cluster_configuration(CreateClusterConfigurationParams {
+                resources_manager: Arc::clone(&fixture.resources_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                project: ProjectId::DEFAULT,
+                cluster_configuration: ClusterConfiguration {
+                    id,
+                    name: ClusterName::try_from(name).unwrap(),
+                    leader: peer_a.id,
+                    devices: HashSet::from([peer_a.device, peer_b.device]),
+                    revision: 0,
+                },
+            }).await?;
+        }
+
+        let start = chrono::Utc::now() + chrono::Duration::hours(1);
+        let lease = Duration::from_secs(2 * 60 * 60);
+        let deployment = ClusterDeployment { id: cluster_id, start: Some(start), lease: Some(lease) };
+
+        let operation = fixture.testee.store_cluster_deployment(ProjectId::DEFAULT, deployment).await?;
+        assert_that!(operation.state, eq(DeploymentOperationState::Pending));
+
+        let overlapping = ClusterDeployment { id: other_cluster_id, start: Some(start + chrono::Duration::hours(1)), lease: Some(lease) };
+        assert_that!(
+            fixture.testee.store_cluster_deployment(ProjectId::DEFAULT, overlapping).await,
+            err(matches_pattern!(StoreClusterDeploymentError::ReservationConflict { .. }))
+        );
+
+        fixture.testee.process_schedule(start - chrono::Duration::minutes(1)).await;
+        assert_that!(fixture.testee.find_deployment_operation(ProjectId::DEFAULT, operation.id).await, some(field!(DeploymentOperation.state, eq(DeploymentOperationState::Pending))));
+
+        fixture.testee.process_schedule(start).await;
+        let finished_operation = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let operation = fixture.testee.find_deployment_operation(ProjectId::DEFAULT, operation.id).await
+                    .expect("Deployment operation should be stored.");
+                if operation.is_finished() {
+                    break operation;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await?;
+        assert_that!(finished_operation.state, eq(DeploymentOperationState::Succeeded));
+        for peer in [&peer_a, &peer_b] {
+            assert_that!(fixture.resources_manager.get::<PeerConfiguration>(peer.id).await, some(field!(PeerConfiguration.cluster_assignment, some(anything()))));
+        }
+
+        fixture.testee.process_schedule(start + chrono::Duration::hours(2)).await;
+        assert_that!(fixture.testee.find_deployment(ProjectId::DEFAULT, cluster_id).await, none());
+        for peer in [&peer_a, &peer_b] {
+            assert_that!(fixture.resources_manager.get::<PeerConfiguration>(peer.id).await, some(field!(PeerConfiguration.cluster_assignment, none())));
+        }
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn validate_deployment_should_report_all_issues_without_deploying(
+        fixture: Fixture,
+        peer_a: PeerFixture,
+        peer_b: PeerFixture,
+    ) -> anyhow::Result<()> {
+        let cluster_id = ClusterId::random();
+        let unknown_device = DeviceId::random();
+
+        for peer in [&peer_a, &peer_b] {
+            actions::store_peer_descriptor(StorePeerDescriptorParams {
+                resources_manager: Arc::clone(&fixture.resources_manager),
+                secrets_manager: Arc::clone(&fixture.secrets_manager),
+                limits_manager: LimitsManager::new(LimitsOptions::default()),
+                vpn: Vpn::Disabled,
+                project: ProjectId::DEFAULT,
+                peer_descriptor: Clone::clone(&peer.descriptor),
+            }).await?;
+        }
+        let (_peer_a_tx, _peer_a_rx) = fixture.peer_messaging_broker.open(peer_a.id, peer_a.remote_host).await;
+
+        actions::create_cluster_configuration(CreateClusterConfigurationParams {
+            resources_manager: Arc::clone(&fixture.resources_manager),
+            limits_manager: LimitsManager::new(LimitsOptions::default()),
+            project: ProjectId::DEFAULT,
+            cluster_configuration: ClusterConfiguration {
+                id: cluster_id,
+                name: ClusterName::try_from("MyAwesomeCluster").unwrap(),
+                leader: peer_a.id,
+                devices: HashSet::from([peer_a.device, peer_b.device, unknown_device]),
+                revision: 0,
+            },
+        }).await?;
+
+        let validation = fixture.testee.validate_deployment(ProjectId::DEFAULT, cluster_id).await?;
+
+        assert_that!(validation.is_deployable(), eq(false));
+        assert_that!(validation.issues, unordered_elements_are![
+            eq(ClusterDeploymentIssue::PeerForDeviceNotFound { device_id: unknown_device }),
+            eq(ClusterDeploymentIssue::PeerNotUp { peer_id: peer_b.id }),
+        ]);
+        assert_that!(fixture.resources_manager.get::<PeerConfiguration>(peer_a.id).await, some(field!(PeerConfiguration.cluster_assignment, none())));
+
+        assert_that!(
+            fixture.testee.validate_deployment(ProjectId::DEFAULT, ClusterId::random()).await,
+            err(matches_pattern!(ValidateClusterDeploymentError::ClusterConfigurationNotFound { .. }))
+        );
+
+        Ok(())
+    }
+
+    /// Keeps the clusters in memory and, when reconciling, removes those not expected after a delay,
+    /// during which a real VPN would list its resources.
+    #[derive(Default)]
+    struct InMemoryVpn {
+        clusters: std::sync::Mutex<Vec<(ProjectId, ClusterId)>>,
+        unsupported_protocols: Vec<NetworkProtocol>,
+    }
+
+    impl InMemoryVpn {
+        fn clusters(&self) -> Vec<(ProjectId, ClusterId)> {
+            Clone::clone(&self.clusters.lock().unwrap())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl VpnManagementClient for InMemoryVpn {
+        async fn create_cluster(&self, project_id: ProjectId, cluster_id: ClusterId, _: &[PeerId], policy: &ClusterNetworkPolicy) -> std::result::Result<(), CreateClusterError> {
+            if let Some(rule) = policy.rules.iter().find(|rule| self.unsupported_protocols.contains(&rule.protocol)) {
+                return Err(CreateClusterError::UnsupportedProtocol { cluster_id, protocol: rule.protocol });
+            }
+            let mut clusters = self.clusters.lock().unwrap();
+            clusters.retain(|cluster| *cluster != (project_id, cluster_id));
+            clusters.push((project_id, cluster_id));
+            Ok(())
+        }
+
+        async fn delete_cluster(&self, project_id: ProjectId, cluster_id: ClusterId) -> std::result::Result<(), DeleteClusterError> {
+            self.clusters.lock().unwrap().retain(|cluster| *cluster != (project_id, cluster_id));
+            Ok(())
+        }
+
+        async fn create_peer(&self, _: PeerId) -> std::result::Result<(), CreatePeerError> {
+            Ok(())
+        }
+
+        async fn delete_peer(&self, _: PeerId) -> std::result::Result<(), DeletePeerError> {
+            Ok(())
+        }
+
+        async fn generate_vpn_peer_configuration(&self, _: PeerId) -> std::result::Result<VpnPeerConfiguration, CreateVpnPeerConfigurationError> {
+            Ok(VpnPeerConfiguration::Disabled)
+        }
+
+        fn unsupported_protocols(&self) -> Vec<NetworkProtocol> {
+            Clone::clone(&self.unsupported_protocols)
+        }
+
+        async fn reconcile(&self, expected: &ExpectedVpnState, dry_run: bool) -> std::result::Result<VpnReconciliationReport, ReconcileError> {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            if dry_run.not() {
+                self.clusters.lock().unwrap().retain(|cluster| expected.clusters.contains_key(cluster));
+            }
+            Ok(VpnReconciliationReport { dry_run, drifts: vec![] })
+        }
+    }
+
     #[rstest]
     fn should_determine_member_interface_mapping() -> anyhow::Result<()> {
 
@@ -459,6 +1716,7 @@ mod test {
                     devices,
                 },
                 executors: ExecutorDescriptors { executors: vec![] },
+                revision: 0,
             }
         }
 
@@ -486,6 +1744,7 @@ mod test {
     struct Fixture {
         testee: ClusterManagerRef,
         resources_manager: ResourcesManagerRef,
+        secrets_manager: SecretsManagerRef,
         peer_messaging_broker: PeerMessagingBrokerRef,
         cluster_manager_options: ClusterManagerOptions,
     }
@@ -494,8 +1753,10 @@ mod test {
         let settings = settings::load_defaults().unwrap();
 
         let resources_manager = ResourcesManager::new();
+        let secrets_manager = SecretsManager::new(SecretsManagerOptions { key: None });
         let peer_messaging_broker = PeerMessagingBroker::new(
             Arc::clone(&resources_manager),
+            Arc::clone(&secrets_manager),
             PeerMessagingBrokerOptions::load(&settings.config).unwrap(),
         );
 
@@ -510,6 +1771,7 @@ mod test {
         Fixture {
             testee,
             resources_manager,
+            secrets_manager,
             peer_messaging_broker,
             cluster_manager_options,
         }
@@ -574,6 +1836,7 @@ mod test {
                     args: vec![],
                 }],
             },
+            revision: 0,
         };
         PeerFixture {
             id,
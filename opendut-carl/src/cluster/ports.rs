@@ -0,0 +1,142 @@
+use std::ops::Not;
+
+use opendut_types::cluster::ClusterId;
+use opendut_types::peer::PeerId;
+use opendut_types::util::Port;
+
+use crate::resources::Resources;
+
+/// The CAN server ports allocated to the members of a deployed cluster.
+/// The leader runs one cannelloni instance per member, listening on the port of the member,
+/// so the ports of clusters with the same leader must not overlap.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CanServerPortAllocation {
+    pub cluster_id: ClusterId,
+    pub leader: PeerId,
+    pub ports: Vec<(PeerId, Port)>,
+}
+
+impl CanServerPortAllocation {
+    pub fn port_of(&self, peer_id: PeerId) -> Option<Port> {
+        self.ports.iter()
+            .find(|(member, _)| *member == peer_id)
+            .map(|(_, port)| *port)
+    }
+}
+
+/// The ports, which may be allocated as CAN server ports, including the end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CanServerPortRange {
+    pub start: Port,
+    pub end: Port,
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[error("Not enough free CAN server ports between port {} and {} for {required} members with leader <{leader}>.", range.start, range.end)]
+pub struct CanServerPortsExhaustedError {
+    pub leader: PeerId,
+    pub range: CanServerPortRange,
+    pub required: usize,
+}
+
+/// Allocates a CAN server port to each member, which is not used by another deployed cluster with the same leader.
+/// Members keep the ports previously allocated to them in this cluster, so redeploying a cluster does not change its ports.
+///
+/// The allocation is not stored, which is left to the caller.
+pub fn allocate(
+    resources: &Resources,
+    cluster_id: ClusterId,
+    leader: PeerId,
+    members: &[PeerId],
+    range: CanServerPortRange,
+) -> Result<CanServerPortAllocation, CanServerPortsExhaustedError> {
+
+    let previous_allocation = resources.get::<CanServerPortAllocation>(cluster_id)
+        .filter(|allocation| allocation.leader == leader);
+
+    let used_ports = resources.iter::<CanServerPortAllocation>()
+        .filter(|allocation| allocation.cluster_id != cluster_id && allocation.leader == leader)
+        .flat_map(|allocation| allocation.ports.iter().map(|(_, port)| *port))
+        .collect::<Vec<_>>();
+
+    let mut ports: Vec<(PeerId, Port)> = members.iter()
+        .filter_map(|&member| previous_allocation.as_ref()
+            .and_then(|allocation| allocation.port_of(member))
+            .filter(|port| used_ports.contains(port).not())
+            .map(|port| (member, port))
+        )
+        .collect();
+
+    let kept_ports = ports.iter()
+        .map(|(_, port)| *port)
+        .collect::<Vec<_>>();
+    let mut free_ports = (range.start.0..=range.end.0)
+        .map(Port)
+        .filter(|port| used_ports.contains(port).not() && kept_ports.contains(port).not());
+
+    for &member in members {
+        if ports.iter().any(|(allocated, _)| *allocated == member) {
+            continue;
+        }
+        let port = free_ports.next()
+            .ok_or(CanServerPortsExhaustedError { leader, range, required: members.len() })?;
+        ports.push((member, port));
+    }
+
+    ports.sort_by_key(|(_, port)| port.0);
+
+    Ok(CanServerPortAllocation { cluster_id, leader, ports })
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+
+    use super::*;
+
+    const RANGE: CanServerPortRange = CanServerPortRange { start: Port(10000), end: Port(10003) };
+
+    #[test]
+    fn should_allocate_distinct_ports_to_clusters_with_the_same_leader() -> Result<()> {
+        let mut resources = Resources::default();
+        let leader = PeerId::random();
+        let member = PeerId::random();
+
+        let first_cluster = ClusterId::random();
+        let first = allocate(&resources, first_cluster, leader, &[leader, member], RANGE)?;
+        verify_that!(first.ports.iter().map(|(_, port)| port.0).collect::<Vec<_>>(), elements_are![eq(10000), eq(10001)])?;
+        resources.insert(first_cluster, first.clone());
+
+        let second_cluster = ClusterId::random();
+        let second = allocate(&resources, second_cluster, leader, &[leader, member], RANGE)?;
+        verify_that!(second.ports.iter().map(|(_, port)| port.0).collect::<Vec<_>>(), elements_are![eq(10002), eq(10003)])?;
+        resources.insert(second_cluster, second);
+
+        let third = allocate(&resources, ClusterId::random(), leader, &[leader], RANGE);
+        verify_that!(third, err(eq(CanServerPortsExhaustedError { leader, range: RANGE, required: 1 })))?;
+
+        let other_leader = PeerId::random();
+        verify_that!(allocate(&resources, ClusterId::random(), other_leader, &[other_leader, member], RANGE), ok(anything()))?;
+
+        resources.remove::<CanServerPortAllocation>(first_cluster);
+        verify_that!(allocate(&resources, ClusterId::random(), leader, &[leader], RANGE), ok(field!(CanServerPortAllocation.ports, elements_are![(eq(leader), eq(Port(10000)))])))
+    }
+
+    #[test]
+    fn should_keep_the_ports_of_members_when_reallocating_a_cluster() -> Result<()> {
+        let mut resources = Resources::default();
+        let leader = PeerId::random();
+        let member_a = PeerId::random();
+        let member_b = PeerId::random();
+        let cluster_id = ClusterId::random();
+
+        let previous = allocate(&resources, cluster_id, leader, &[leader, member_a, member_b], RANGE)?;
+        resources.insert(cluster_id, previous.clone());
+
+        let reallocated = allocate(&resources, cluster_id, leader, &[member_b, leader], RANGE)?;
+
+        verify_that!(reallocated.port_of(member_b), eq(previous.port_of(member_b)))?;
+        verify_that!(reallocated.port_of(leader), eq(previous.port_of(leader)))?;
+        verify_that!(reallocated.port_of(member_a), none())
+    }
+}
@@ -0,0 +1,59 @@
+use tonic::{Request, Response, Status};
+use tonic_web::CorsGrpcWeb;
+use tracing::trace;
+
+use opendut_carl_api::carl::audit::AuditRecordFilter;
+use opendut_carl_api::proto::services::audit_log::*;
+use opendut_carl_api::proto::services::audit_log::audit_log_server::{AuditLog as AuditLogService, AuditLogServer};
+
+use crate::audit::manager::AuditManagerRef;
+use crate::project;
+use crate::resources::manager::ResourcesManagerRef;
+
+pub struct AuditLogFacade {
+    audit_manager: AuditManagerRef,
+    resources_manager: ResourcesManagerRef,
+}
+
+impl AuditLogFacade {
+
+    pub fn new(audit_manager: AuditManagerRef, resources_manager: ResourcesManagerRef) -> Self {
+        AuditLogFacade {
+            audit_manager,
+            resources_manager,
+        }
+    }
+
+    pub fn into_grpc_service(self) -> CorsGrpcWeb<AuditLogServer<Self>> {
+        tonic_web::enable(AuditLogServer::new(self))
+    }
+}
+
+#[tonic::async_trait]
+impl AuditLogService for AuditLogFacade {
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn list_audit_records(&self, request: Request<ListAuditRecordsRequest>) -> Result<Response<ListAuditRecordsResponse>, Status> {
+
+        trace!("Received request: {:?}", request);
+
+        let project = project::selected_project(&request, &self.resources_manager).await?;
+        let filter = AuditRecordFilter {
+            project: Some(project),
+            ..AuditRecordFilter::from(request.into_inner())
+        };
+
+        let records = self.audit_manager.list(&filter).await
+            .into_iter()
+            .map(From::from)
+            .collect::<Vec<_>>();
+
+        Ok(Response::new(ListAuditRecordsResponse {
+            reply: Some(list_audit_records_response::Reply::Success(
+                ListAuditRecordsSuccess {
+                    records
+                }
+            ))
+        }))
+    }
+}
@@ -1,30 +1,42 @@
+use std::ops::Not;
 use std::sync::Arc;
 
 use tonic::{Request, Response, Status};
 use tonic_web::CorsGrpcWeb;
 use tracing::trace;
 
+use opendut_carl_api::carl::audit::AuditAction;
+use opendut_carl_api::carl::cluster::GetDeploymentOperationError;
 use opendut_carl_api::proto::services::cluster_manager::*;
 use opendut_carl_api::proto::services::cluster_manager::cluster_manager_server::{ClusterManager as ClusterManagerService, ClusterManagerServer};
 use opendut_types::cluster::{ClusterConfiguration, ClusterDeployment, ClusterId};
+use opendut_types::cluster::operation::DeploymentOperationId;
 
 use crate::actions;
 use crate::actions::{CreateClusterConfigurationParams, DeleteClusterConfigurationParams};
+use crate::audit::identity::caller_of;
+use crate::audit::manager::AuditManagerRef;
 use crate::cluster::manager::ClusterManagerRef;
 use crate::grpc::extract;
+use crate::limits::manager::LimitsManagerRef;
+use crate::project;
 use crate::resources::manager::ResourcesManagerRef;
 
 pub struct ClusterManagerFacade {
     cluster_manager: ClusterManagerRef,
     resources_manager: ResourcesManagerRef,
+    audit_manager: AuditManagerRef,
+    limits_manager: LimitsManagerRef,
 }
 
 impl ClusterManagerFacade {
 
-    pub fn new(cluster_manager: ClusterManagerRef, resources_manager: ResourcesManagerRef) -> Self {
+    pub fn new(cluster_manager: ClusterManagerRef, resources_manager: ResourcesManagerRef, audit_manager: AuditManagerRef, limits_manager: LimitsManagerRef) -> Self {
         Self {
             cluster_manager,
-            resources_manager
+            resources_manager,
+            audit_manager,
+            limits_manager,
         }
     }
 
@@ -40,14 +52,28 @@ impl ClusterManagerService for ClusterManagerFacade {
 
         trace!("Received request: {:?}", request);
 
+        let caller = caller_of(&request);
+        let project = project::selected_project(&request, &self.resources_manager).await?;
         let request = request.into_inner();
         let cluster_configuration: ClusterConfiguration = extract!(request.cluster_configuration)?;
+        let cluster_id = cluster_configuration.id;
 
-        let result = actions::create_cluster_configuration(CreateClusterConfigurationParams {
+        let (result, snapshot) = actions::create_cluster_configuration_with_snapshot(CreateClusterConfigurationParams {
             resources_manager: Arc::clone(&self.resources_manager),
+            limits_manager: Arc::clone(&self.limits_manager),
+            project,
             cluster_configuration,
         }).await;
 
+        let snapshot = snapshot.unwrap_or_default();
+        self.audit_manager.record(
+            caller.record(AuditAction::CreateClusterConfiguration, cluster_id.0)
+                .with_project(project)
+                .with_before(snapshot.before.as_ref())
+                .with_after(snapshot.after.as_ref())
+                .with_outcome(&result)
+        ).await;
+
         match result {
             Err(error) => {
                 Ok(Response::new(CreateClusterConfigurationResponse {
@@ -70,14 +96,25 @@ impl ClusterManagerService for ClusterManagerFacade {
 
         trace!("Received request: {:?}", request);
 
+        let caller = caller_of(&request);
+        let project = project::selected_project(&request, &self.resources_manager).await?;
         let request = request.into_inner();
         let cluster_id: ClusterId = extract!(request.cluster_id)?;
 
-        let result =
-            actions::delete_cluster_configuration(DeleteClusterConfigurationParams {
-                resources_manager: Arc::clone(&self.resources_manager),
-                cluster_id,
-            }).await;
+        let (result, snapshot) = actions::delete_cluster_configuration_with_snapshot(DeleteClusterConfigurationParams {
+            resources_manager: Arc::clone(&self.resources_manager),
+            project,
+            cluster_id,
+        }).await;
+
+        let snapshot = snapshot.unwrap_or_default();
+        self.audit_manager.record(
+            caller.record(AuditAction::DeleteClusterConfiguration, cluster_id.0)
+                .with_project(project)
+                .with_before(snapshot.before.as_ref())
+                .with_after(snapshot.after.as_ref())
+                .with_outcome(&result)
+        ).await;
 
         match result {
             Err(error) => {
@@ -99,6 +136,7 @@ impl ClusterManagerService for ClusterManagerFacade {
     #[tracing::instrument(skip(self, request), level="trace")]
     async fn get_cluster_configuration(&self, request: Request<GetClusterConfigurationRequest>) -> Result<Response<GetClusterConfigurationResponse>, Status> {
         trace!("Received request: {:?}", request);
+        let project = project::selected_project(&request, &self.resources_manager).await?;
         match request.into_inner().id {
             None => {
                 Err(Status::invalid_argument("ClusterId is required."))
@@ -106,7 +144,7 @@ impl ClusterManagerService for ClusterManagerFacade {
             Some(id) => {
                 let id = ClusterId::try_from(id)
                     .map_err(|_| Status::invalid_argument("Invalid ClusterId."))?;
-                let configuration = self.cluster_manager.find_configuration(id).await;
+                let configuration = self.cluster_manager.find_configuration(project, id).await;
                 match configuration {
                     Some(configuration) => {
                         Ok(Response::new(GetClusterConfigurationResponse {
@@ -131,7 +169,8 @@ impl ClusterManagerService for ClusterManagerFacade {
     #[tracing::instrument(skip(self, request), level="trace")]
     async fn list_cluster_configurations(&self, request: Request<ListClusterConfigurationsRequest>) -> Result<Response<ListClusterConfigurationsResponse>, Status> {
         trace!("Received request: {:?}", request);
-        let configurations = self.cluster_manager.list_configuration().await;
+        let project = project::selected_project(&request, &self.resources_manager).await?;
+        let configurations = self.cluster_manager.list_configuration(project).await;
         Ok(Response::new(ListClusterConfigurationsResponse {
             result: Some(list_cluster_configurations_response::Result::Success(
                 ListClusterConfigurationsSuccess {
@@ -145,10 +184,26 @@ impl ClusterManagerService for ClusterManagerFacade {
     async fn store_cluster_deployment(&self, request: Request<StoreClusterDeploymentRequest>) -> Result<Response<StoreClusterDeploymentResponse>, Status> {
         trace!("Received request: {:?}", request);
 
+        let caller = caller_of(&request);
+        let project = project::selected_project(&request, &self.resources_manager).await?;
         let request = request.into_inner();
         let cluster_deployment: ClusterDeployment = extract!(request.cluster_deployment)?;
+        let cluster_id = cluster_deployment.id;
+
+        if self.cluster_manager.is_accessible_from(project, cluster_id).await.not() {
+            return Err(Status::not_found(format!("Cluster <{cluster_id}> does not exist in project <{project}>.")));
+        }
+
+        let (result, snapshot) = self.cluster_manager.store_cluster_deployment_with_snapshot(project, cluster_deployment).await;
 
-        let result = self.cluster_manager.store_cluster_deployment(cluster_deployment).await;
+        let snapshot = snapshot.unwrap_or_default();
+        self.audit_manager.record(
+            caller.record(AuditAction::StoreClusterDeployment, cluster_id.0)
+                .with_project(project)
+                .with_before(snapshot.before.as_ref())
+                .with_after(snapshot.after.as_ref())
+                .with_outcome(&result)
+        ).await;
 
         match result {
             Err(error) => {
@@ -156,11 +211,12 @@ impl ClusterManagerService for ClusterManagerFacade {
                     reply: Some(store_cluster_deployment_response::Reply::Failure(error.into()))
                 }))
             }
-            Ok(cluster_id) => {
+            Ok(operation) => {
                 Ok(Response::new(StoreClusterDeploymentResponse {
                     reply: Some(store_cluster_deployment_response::Reply::Success(
                         StoreClusterDeploymentSuccess {
-                            cluster_id: Some(cluster_id.into())
+                            cluster_id: Some(operation.cluster_id.into()),
+                            operation: Some(operation.into()),
                         }
                     ))
                 }))
@@ -171,10 +227,21 @@ impl ClusterManagerService for ClusterManagerFacade {
     async fn delete_cluster_deployment(&self, request: Request<DeleteClusterDeploymentRequest>) -> Result<Response<DeleteClusterDeploymentResponse>, Status> {
         trace!("Received request: {:?}", request);
 
+        let caller = caller_of(&request);
+        let project = project::selected_project(&request, &self.resources_manager).await?;
         let request = request.into_inner();
         let cluster_id: ClusterId = extract!(request.cluster_id)?;
 
-        let result = self.cluster_manager.delete_cluster_deployment(cluster_id).await; // TODO: Replace with action
+        let (result, snapshot) = self.cluster_manager.delete_cluster_deployment_with_snapshot(project, cluster_id).await; // TODO: Replace with action
+
+        let snapshot = snapshot.unwrap_or_default();
+        self.audit_manager.record(
+            caller.record(AuditAction::DeleteClusterDeployment, cluster_id.0)
+                .with_project(project)
+                .with_before(snapshot.before.as_ref())
+                .with_after(snapshot.after.as_ref())
+                .with_outcome(&result)
+        ).await;
 
         match result {
             Err(error) => {
@@ -197,7 +264,8 @@ impl ClusterManagerService for ClusterManagerFacade {
     #[tracing::instrument(skip(self, request), level="trace")]
     async fn list_cluster_deployments(&self, request: Request<ListClusterDeploymentsRequest>) -> Result<Response<ListClusterDeploymentsResponse>, Status> {
         trace!("Received request: {:?}", request);
-        let deployments = self.cluster_manager.list_deployment().await;
+        let project = project::selected_project(&request, &self.resources_manager).await?;
+        let deployments = self.cluster_manager.list_deployment(project).await;
         Ok(Response::new(ListClusterDeploymentsResponse {
             result: Some(list_cluster_deployments_response::Result::Success(
                 ListClusterDeploymentsSuccess {
@@ -206,4 +274,79 @@ impl ClusterManagerService for ClusterManagerFacade {
             ))
         }))
     }
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn validate_cluster_deployment(&self, request: Request<ValidateClusterDeploymentRequest>) -> Result<Response<ValidateClusterDeploymentResponse>, Status> {
+        trace!("Received request: {:?}", request);
+
+        let project = project::selected_project(&request, &self.resources_manager).await?;
+        let request = request.into_inner();
+        let cluster_id: ClusterId = extract!(request.cluster_id)?;
+
+        match self.cluster_manager.validate_deployment(project, cluster_id).await {
+            Err(error) => {
+                Ok(Response::new(ValidateClusterDeploymentResponse {
+                    reply: Some(validate_cluster_deployment_response::Reply::Failure(error.into()))
+                }))
+            }
+            Ok(validation) => {
+                Ok(Response::new(ValidateClusterDeploymentResponse {
+                    reply: Some(validate_cluster_deployment_response::Reply::Success(
+                        ValidateClusterDeploymentSuccess {
+                            validation: Some(validation.into())
+                        }
+                    ))
+                }))
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn get_deployment_operation(&self, request: Request<GetDeploymentOperationRequest>) -> Result<Response<GetDeploymentOperationResponse>, Status> {
+        trace!("Received request: {:?}", request);
+
+        let project = project::selected_project(&request, &self.resources_manager).await?;
+        let request = request.into_inner();
+        let operation_id: DeploymentOperationId = extract!(request.id)?;
+
+        match self.cluster_manager.find_deployment_operation(project, operation_id).await {
+            None => {
+                Ok(Response::new(GetDeploymentOperationResponse {
+                    reply: Some(get_deployment_operation_response::Reply::Failure(
+                        GetDeploymentOperationError::DeploymentOperationNotFound { operation_id }.into()
+                    ))
+                }))
+            }
+            Some(operation) => {
+                Ok(Response::new(GetDeploymentOperationResponse {
+                    reply: Some(get_deployment_operation_response::Reply::Success(
+                        GetDeploymentOperationSuccess {
+                            operation: Some(operation.into())
+                        }
+                    ))
+                }))
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn list_deployment_operations(&self, request: Request<ListDeploymentOperationsRequest>) -> Result<Response<ListDeploymentOperationsResponse>, Status> {
+        trace!("Received request: {:?}", request);
+
+        let project = project::selected_project(&request, &self.resources_manager).await?;
+        let request = request.into_inner();
+        let cluster_id = request.cluster_id
+            .map(ClusterId::try_from)
+            .transpose()
+            .map_err(|cause| Status::invalid_argument(cause.to_string()))?;
+
+        let operations = self.cluster_manager.list_deployment_operations(project, cluster_id).await;
+        Ok(Response::new(ListDeploymentOperationsResponse {
+            result: Some(list_deployment_operations_response::Result::Success(
+                ListDeploymentOperationsSuccess {
+                    operations: operations.into_iter().map(Into::into).collect::<Vec<_>>()
+                }
+            ))
+        }))
+    }
 }
@@ -1,13 +1,21 @@
 use std::fmt::Display;
 
+pub use audit_log::AuditLogFacade as AuditLogService;
 pub use cluster_manager::ClusterManagerFacade as ClusterManagerService;
 pub use metadata_provider::MetadataProviderFacade as MetadataProviderService;
 pub use peer_manager::PeerManagerFacade as PeerManagerService;
 pub use peer_messaging_broker::PeerMessagingBrokerFacade as PeerMessagingBrokerService;
+pub use project_manager::ProjectManagerFacade as ProjectManagerService;
+pub use state_manager::StateManagerFacade as StateManagerService;
+pub use vpn_manager::VpnManagerFacade as VpnManagerService;
 
+mod audit_log;
 mod cluster_manager;
 mod peer_manager;
 mod peer_messaging_broker;
+mod project_manager;
+mod state_manager;
+mod vpn_manager;
 mod metadata_provider;
 
 pub trait ExtractOrInvalidArgument<A, B>
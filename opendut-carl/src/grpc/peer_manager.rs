@@ -1,37 +1,56 @@
 use std::sync::Arc;
 use pem::Pem;
 
+use tokio::sync::RwLock;
 use tonic::{Request, Response, Status};
 use tonic_web::CorsGrpcWeb;
 use tracing::trace;
 use url::Url;
 
-use opendut_carl_api::carl::peer::GetPeerDescriptorError;
+use opendut_carl_api::carl::audit::AuditAction;
+use opendut_carl_api::carl::peer::{FindDevicesError, GetPeerDescriptorError};
 use opendut_carl_api::proto::services::peer_manager;
 use opendut_carl_api::proto::services::peer_manager::*;
 use opendut_carl_api::proto::services::peer_manager::peer_manager_server::{PeerManager as PeerManagerService, PeerManagerServer};
 use opendut_types::peer::{PeerDescriptor, PeerId};
+use opendut_types::peer::executor::{ContainerName, ExecutorDescriptor};
+use opendut_types::project::ProjectId;
+use opendut_types::topology::{DeviceDescriptor, DeviceId};
+use opendut_types::topology::query::DeviceQuery;
+use opendut_types::util::net::{NetworkInterfaceDescriptor, NetworkInterfaceName};
 
 use crate::actions;
-use crate::actions::{DeletePeerDescriptorParams, GeneratePeerSetupParams, ListDevicesParams, ListPeerDescriptorsParams, StorePeerDescriptorParams};
+use crate::actions::{DeletePeerDescriptorParams, FindDevicesParams, GeneratePeerSetupParams, ListDevicesParams, ListPeerDescriptorsParams, ListPeerStatesParams, PeerUpdate, StorePeerDescriptorParams, UpdatePeerDescriptorParams};
+use crate::audit::identity::{AuditCaller, caller_of};
+use crate::audit::manager::AuditManagerRef;
 use crate::grpc::extract;
+use crate::limits::manager::LimitsManagerRef;
 use crate::peer::oidc_client_manager::OpenIdConnectClientManager;
+use crate::project;
 use crate::resources::manager::ResourcesManagerRef;
+use crate::secrets::manager::SecretsManagerRef;
 use crate::vpn::Vpn;
 
 pub struct PeerManagerFacade {
     resources_manager: ResourcesManagerRef,
+    audit_manager: AuditManagerRef,
+    secrets_manager: SecretsManagerRef,
+    limits_manager: LimitsManagerRef,
     vpn: Vpn,
     carl_url: Url,
-    ca: Pem,
+    ca: Arc<RwLock<Pem>>,
     oidc_client_manager: Option<OpenIdConnectClientManager>,
 }
 
 impl PeerManagerFacade {
 
-    pub fn new(resources_manager: ResourcesManagerRef, vpn: Vpn, carl_url: Url, ca: Pem, oidc_client_manager: Option<OpenIdConnectClientManager>) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(resources_manager: ResourcesManagerRef, audit_manager: AuditManagerRef, secrets_manager: SecretsManagerRef, limits_manager: LimitsManagerRef, vpn: Vpn, carl_url: Url, ca: Arc<RwLock<Pem>>, oidc_client_manager: Option<OpenIdConnectClientManager>) -> Self {
         PeerManagerFacade {
             resources_manager,
+            audit_manager,
+            secrets_manager,
+            limits_manager,
             vpn,
             carl_url,
             ca,
@@ -42,6 +61,45 @@ impl PeerManagerFacade {
     pub fn into_grpc_service(self) -> CorsGrpcWeb<PeerManagerServer<Self>> {
         tonic_web::enable(PeerManagerServer::new(self))
     }
+
+    async fn update_peer(&self, caller: AuditCaller, project: ProjectId, peer_id: PeerId, update: PeerUpdate) -> Result<Response<UpdatePeerResponse>, Status> {
+
+        let (result, snapshot) = actions::update_peer_descriptor_with_snapshot(UpdatePeerDescriptorParams {
+            resources_manager: Arc::clone(&self.resources_manager),
+            secrets_manager: Arc::clone(&self.secrets_manager),
+            limits_manager: Arc::clone(&self.limits_manager),
+            vpn: Clone::clone(&self.vpn),
+            project,
+            peer_id,
+            update,
+        }).await;
+
+        let snapshot = snapshot.unwrap_or_default();
+        self.audit_manager.record(
+            caller.record(AuditAction::UpdatePeerDescriptor, peer_id.0)
+                .with_project(project)
+                .with_before(snapshot.before.as_ref())
+                .with_after(snapshot.after.as_ref())
+                .with_outcome(&result)
+        ).await;
+
+        match result {
+            Err(error) => {
+                Ok(Response::new(UpdatePeerResponse {
+                    reply: Some(update_peer_response::Reply::Failure(error.into()))
+                }))
+            }
+            Ok(peer_descriptor) => {
+                Ok(Response::new(UpdatePeerResponse {
+                    reply: Some(update_peer_response::Reply::Success(
+                        UpdatePeerSuccess {
+                            peer: Some(peer_descriptor.into())
+                        }
+                    ))
+                }))
+            }
+        }
+    }
 }
 
 #[tonic::async_trait]
@@ -52,15 +110,30 @@ impl PeerManagerService for PeerManagerFacade {
 
         trace!("Received request: {:?}", request);
 
+        let caller = caller_of(&request);
+        let project = project::selected_project(&request, &self.resources_manager).await?;
         let request = request.into_inner();
         let peer_descriptor: PeerDescriptor = extract!(request.peer)?;
+        let peer_id = peer_descriptor.id;
 
-        let result = actions::store_peer_descriptor(StorePeerDescriptorParams {
+        let (result, snapshot) = actions::store_peer_descriptor_with_snapshot(StorePeerDescriptorParams {
             resources_manager: Arc::clone(&self.resources_manager),
+            secrets_manager: Arc::clone(&self.secrets_manager),
+            limits_manager: Arc::clone(&self.limits_manager),
             vpn: Clone::clone(&self.vpn),
+            project,
             peer_descriptor: Clone::clone(&peer_descriptor),
         }).await;
 
+        let snapshot = snapshot.unwrap_or_default();
+        self.audit_manager.record(
+            caller.record(AuditAction::StorePeerDescriptor, peer_id.0)
+                .with_project(project)
+                .with_before(snapshot.before.as_ref())
+                .with_after(snapshot.after.as_ref())
+                .with_outcome(&result)
+        ).await;
+
         match result {
             Err(error) => {
                 Ok(Response::new(StorePeerDescriptorResponse {
@@ -84,15 +157,27 @@ impl PeerManagerService for PeerManagerFacade {
 
         trace!("Received request: {:?}", request);
 
+        let caller = caller_of(&request);
+        let project = project::selected_project(&request, &self.resources_manager).await?;
         let request = request.into_inner();
         let peer_id: PeerId = extract!(request.peer_id)?;
 
-        let result =
-            actions::delete_peer_descriptor(DeletePeerDescriptorParams {
-                resources_manager: Arc::clone(&self.resources_manager),
-                vpn: Clone::clone(&self.vpn),
-                peer: peer_id,
-            }).await;
+        let (result, snapshot) = actions::delete_peer_descriptor_with_snapshot(DeletePeerDescriptorParams {
+            resources_manager: Arc::clone(&self.resources_manager),
+            secrets_manager: Arc::clone(&self.secrets_manager),
+            vpn: Clone::clone(&self.vpn),
+            project,
+            peer: peer_id,
+        }).await;
+
+        let snapshot = snapshot.unwrap_or_default();
+        self.audit_manager.record(
+            caller.record(AuditAction::DeletePeerDescriptor, peer_id.0)
+                .with_project(project)
+                .with_before(snapshot.before.as_ref())
+                .with_after(snapshot.after.as_ref())
+                .with_outcome(&result)
+        ).await;
 
         match result {
             Err(error) => {
@@ -117,12 +202,14 @@ impl PeerManagerService for PeerManagerFacade {
 
         trace!("Received request: {:?}", request);
 
+        let project = project::selected_project(&request, &self.resources_manager).await?;
         let request = request.into_inner();
         let peer_id: PeerId = extract!(request.peer_id)?;
 
         let result =
             actions::list_peer_descriptors(ListPeerDescriptorsParams {
                 resources_manager: Arc::clone(&self.resources_manager),
+                project,
             }).await
             .map_err(|error| GetPeerDescriptorError::Internal { peer_id, cause: error.to_string() })
             .and_then(|peers| peers.iter()
@@ -154,9 +241,11 @@ impl PeerManagerService for PeerManagerFacade {
 
         trace!("Received request: {:?}", request);
 
+        let project = project::selected_project(&request, &self.resources_manager).await?;
         let result =
             actions::list_peer_descriptors(ListPeerDescriptorsParams {
                 resources_manager: Arc::clone(&self.resources_manager),
+                project,
             }).await
             .map(|peers| peers.into_iter()
                 .map(From::from)
@@ -186,8 +275,10 @@ impl PeerManagerService for PeerManagerFacade {
 
         trace!("Received request: {:?}", request);
 
+        let project = project::selected_project(&request, &self.resources_manager).await?;
         let devices = actions::list_devices(ListDevicesParams {
             resources_manager: Arc::clone(&self.resources_manager),
+            project,
         }).await.expect("Devices should be listable");
 
         let devices = devices.into_iter()
@@ -197,23 +288,92 @@ impl PeerManagerService for PeerManagerFacade {
         Ok(Response::new(ListDevicesResponse { devices }))
     }
 
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn list_peer_states(&self, request: Request<ListPeerStatesRequest>) -> Result<Response<ListPeerStatesResponse>, Status> {
+
+        trace!("Received request: {:?}", request);
+
+        let project = project::selected_project(&request, &self.resources_manager).await?;
+        let peer_states = actions::list_peer_states(ListPeerStatesParams {
+            resources_manager: Arc::clone(&self.resources_manager),
+            project,
+        }).await.expect("Peer states should be listable");
+
+        let peer_states = peer_states.into_iter()
+            .map(From::from)
+            .collect();
+
+        Ok(Response::new(ListPeerStatesResponse { peer_states }))
+    }
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn find_devices(&self, request: Request<FindDevicesRequest>) -> Result<Response<FindDevicesResponse>, Status> {
+
+        trace!("Received request: {:?}", request);
+
+        let project = project::selected_project(&request, &self.resources_manager).await?;
+        let query = request.into_inner().query;
+
+        let result = match DeviceQuery::try_from(Clone::clone(&query)) {
+            Ok(query) => {
+                actions::find_devices(FindDevicesParams {
+                    resources_manager: Arc::clone(&self.resources_manager),
+                    project,
+                    query,
+                }).await
+            }
+            Err(cause) => Err(FindDevicesError::InvalidQuery { query, cause: cause.to_string() }),
+        };
+
+        match result {
+            Err(error) => {
+                Ok(Response::new(FindDevicesResponse {
+                    reply: Some(find_devices_response::Reply::Failure(error.into()))
+                }))
+            }
+            Ok(devices) => {
+                Ok(Response::new(FindDevicesResponse {
+                    reply: Some(find_devices_response::Reply::Success(
+                        FindDevicesSuccess {
+                            devices: devices.into_iter()
+                                .map(From::from)
+                                .collect(),
+                        }
+                    ))
+                }))
+            }
+        }
+    }
+
     #[tracing::instrument(skip(self, request), level="trace")]
     async fn generate_peer_setup(&self, request: Request<GeneratePeerSetupRequest>) -> Result<Response<GeneratePeerSetupResponse>, Status> { // TODO: Refactor error types.
         trace!("Received request: {:?}", request);
 
+        let caller = caller_of(&request);
+        let project = project::selected_project(&request, &self.resources_manager).await?;
         let message = request.into_inner();
         let response = match message.peer {
             Some(peer_id) => {
                 let peer_id = PeerId::try_from(peer_id)
                     .map_err(|cause| Status::invalid_argument(format!("PeerId could not be converted: {}", cause)))?;
-                let setup = actions::generate_peer_setup(GeneratePeerSetupParams {
+                let result = actions::generate_peer_setup(GeneratePeerSetupParams {
                     resources_manager: Arc::clone(&self.resources_manager),
+                    project,
                     peer: peer_id,
                     carl_url: Clone::clone(&self.carl_url),
-                    ca: Clone::clone(&self.ca),
+                    ca: Clone::clone(&*self.ca.read().await),
                     vpn: Clone::clone(&self.vpn),
                     oidc_client_manager: self.oidc_client_manager.clone(),
-                }).await.map_err(|cause| Status::internal(format!("Peer setup could not be created: {}", cause)))?;
+                }).await;
+
+                // The setup contains credentials and is therefore not part of the audit record.
+                self.audit_manager.record(
+                    caller.record(AuditAction::GeneratePeerSetup, peer_id.0)
+                        .with_project(project)
+                        .with_outcome(&result)
+                ).await;
+
+                let setup = result.map_err(|cause| Status::internal(format!("Peer setup could not be created: {}", cause)))?;
 
                 peer_manager::generate_peer_setup_response::Reply::Success(peer_manager::GeneratePeerSetupSuccess { peer: Some(peer_id.into()), setup: Some(setup.into()) })
             }
@@ -224,6 +384,90 @@ impl PeerManagerService for PeerManagerFacade {
 
         Ok(Response::new(GeneratePeerSetupResponse { reply: Some(response) }))
     }
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn store_device(&self, request: Request<StoreDeviceRequest>) -> Result<Response<UpdatePeerResponse>, Status> {
+
+        trace!("Received request: {:?}", request);
+
+        let caller = caller_of(&request);
+        let project = project::selected_project(&request, &self.resources_manager).await?;
+        let request = request.into_inner();
+        let peer_id: PeerId = extract!(request.peer_id)?;
+        let device: DeviceDescriptor = extract!(request.device)?;
+
+        self.update_peer(caller, project, peer_id, PeerUpdate::StoreDevice(device)).await
+    }
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn delete_device(&self, request: Request<DeleteDeviceRequest>) -> Result<Response<UpdatePeerResponse>, Status> {
+
+        trace!("Received request: {:?}", request);
+
+        let caller = caller_of(&request);
+        let project = project::selected_project(&request, &self.resources_manager).await?;
+        let request = request.into_inner();
+        let peer_id: PeerId = extract!(request.peer_id)?;
+        let device_id: DeviceId = extract!(request.device_id)?;
+
+        self.update_peer(caller, project, peer_id, PeerUpdate::DeleteDevice(device_id)).await
+    }
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn store_network_interface(&self, request: Request<StoreNetworkInterfaceRequest>) -> Result<Response<UpdatePeerResponse>, Status> {
+
+        trace!("Received request: {:?}", request);
+
+        let caller = caller_of(&request);
+        let project = project::selected_project(&request, &self.resources_manager).await?;
+        let request = request.into_inner();
+        let peer_id: PeerId = extract!(request.peer_id)?;
+        let network_interface: NetworkInterfaceDescriptor = extract!(request.network_interface)?;
+
+        self.update_peer(caller, project, peer_id, PeerUpdate::StoreNetworkInterface(network_interface)).await
+    }
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn delete_network_interface(&self, request: Request<DeleteNetworkInterfaceRequest>) -> Result<Response<UpdatePeerResponse>, Status> {
+
+        trace!("Received request: {:?}", request);
+
+        let caller = caller_of(&request);
+        let project = project::selected_project(&request, &self.resources_manager).await?;
+        let request = request.into_inner();
+        let peer_id: PeerId = extract!(request.peer_id)?;
+        let interface_name: NetworkInterfaceName = extract!(request.interface_name)?;
+
+        self.update_peer(caller, project, peer_id, PeerUpdate::DeleteNetworkInterface(interface_name)).await
+    }
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn store_executor(&self, request: Request<StoreExecutorRequest>) -> Result<Response<UpdatePeerResponse>, Status> {
+
+        trace!("Received request: {:?}", request);
+
+        let caller = caller_of(&request);
+        let project = project::selected_project(&request, &self.resources_manager).await?;
+        let request = request.into_inner();
+        let peer_id: PeerId = extract!(request.peer_id)?;
+        let executor: ExecutorDescriptor = extract!(request.executor)?;
+
+        self.update_peer(caller, project, peer_id, PeerUpdate::StoreExecutor(executor)).await
+    }
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn delete_executor(&self, request: Request<DeleteExecutorRequest>) -> Result<Response<UpdatePeerResponse>, Status> {
+
+        trace!("Received request: {:?}", request);
+
+        let caller = caller_of(&request);
+        let project = project::selected_project(&request, &self.resources_manager).await?;
+        let request = request.into_inner();
+        let peer_id: PeerId = extract!(request.peer_id)?;
+        let container_name: ContainerName = extract!(request.container_name)?;
+
+        self.update_peer(caller, project, peer_id, PeerUpdate::DeleteExecutor(container_name)).await
+    }
 }
 
 #[cfg(test)]
@@ -234,22 +478,30 @@ mod tests {
     use googletest::prelude::*;
     use rstest::rstest;
     use crate::peer::oidc_client_manager::tests::oidc_client_manager;
+    use tonic::metadata::MetadataValue;
     use url::Url;
 
+    use opendut_carl_api::carl::audit::{AuditOutcome, AuditRecord, AuditRecordFilter};
+    use opendut_carl_api::carl::project::PROJECT_METADATA_KEY;
+    use opendut_types::project::{ProjectDescriptor, ProjectId, ProjectName};
+
     use opendut_types::peer::{PeerLocation, PeerName, PeerNetworkConfiguration};
     use opendut_types::peer::executor::{ContainerCommand, ContainerImage, ContainerName, Engine, ExecutorDescriptor, ExecutorDescriptors};
     use opendut_types::proto;
     use opendut_types::topology::Topology;
     use opendut_types::util::net::{NetworkInterfaceConfiguration, NetworkInterfaceDescriptor, NetworkInterfaceName};
 
+    use crate::audit::manager::AuditManager;
+    use crate::limits::manager::{LimitsManager, LimitsOptions};
     use crate::resources::manager::ResourcesManager;
+    use crate::secrets::manager::{SecretsManager, SecretsManagerOptions};
     use crate::vpn::Vpn;
 
     use super::*;
 
-    pub fn get_cert() -> Pem {
+    pub fn get_cert() -> Arc<RwLock<Pem>> {
         match Pem::from_str(CERTIFICATE_AUTHORITY_STRING) {
-            Ok(cert) => { cert }
+            Ok(cert) => { Arc::new(RwLock::new(cert)) }
             Err(_) => { panic!("Not a valid certificate!") }
         }
     }
@@ -263,8 +515,12 @@ mod tests {
 
 
         let resources_manager = ResourcesManager::new();
+        let audit_manager = AuditManager::new();
         let testee = PeerManagerFacade::new(
             Arc::clone(&resources_manager),
+            Arc::clone(&audit_manager),
+            SecretsManager::new(SecretsManagerOptions { key: None }),
+            LimitsManager::new(LimitsOptions::default()),
             Vpn::Disabled,
             Url::parse("https://example.com:1234").unwrap(),
             get_cert(),
@@ -297,6 +553,7 @@ mod tests {
                     command: ContainerCommand::Default,
                     args: vec![] }],
             },
+            revision: 0,
         };
 
         let create_peer_reply = testee.store_peer_descriptor(Request::new(
@@ -324,7 +581,7 @@ mod tests {
             list_reply.get_ref().reply,
             some(matches_pattern!(list_peer_descriptors_response::Reply::Success(
                 matches_pattern!(ListPeerDescriptorsSuccess {
-                    peers: container_eq([peer_descriptor.into()])
+                    peers: container_eq([PeerDescriptor { revision: 1, ..peer_descriptor }.into()])
                 })
             )))
         )?;
@@ -347,6 +604,93 @@ mod tests {
             )))
         )?;
 
+        let audit_records = audit_manager.list(&Default::default()).await;
+
+        verify_that!(audit_records, elements_are![
+            matches_pattern!(AuditRecord {
+                action: eq(AuditAction::DeletePeerDescriptor),
+                resource_id: eq(peer_id.0),
+                before: some(anything()),
+                after: none(),
+            }),
+            matches_pattern!(AuditRecord {
+                action: eq(AuditAction::StorePeerDescriptor),
+                resource_id: eq(peer_id.0),
+                before: none(),
+                after: some(anything()),
+            }),
+        ])?;
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn should_not_expose_peers_of_other_projects_in_audit_records(oidc_client_manager: OpenIdConnectClientManager) -> Result<()> {
+
+        let resources_manager = ResourcesManager::new();
+        let audit_manager = AuditManager::new();
+        let testee = PeerManagerFacade::new(
+            Arc::clone(&resources_manager),
+            Arc::clone(&audit_manager),
+            SecretsManager::new(SecretsManagerOptions { key: None }),
+            LimitsManager::new(LimitsOptions::default()),
+            Vpn::Disabled,
+            Url::parse("https://example.com:1234").unwrap(),
+            get_cert(),
+            Some(oidc_client_manager),
+        );
+
+        let other_project = ProjectDescriptor {
+            id: ProjectId::random(),
+            name: ProjectName::try_from("other").unwrap(),
+        };
+        resources_manager.insert(other_project.id, Clone::clone(&other_project)).await;
+
+        let peer_id = PeerId::random();
+        let peer_descriptor = PeerDescriptor {
+            id: peer_id,
+            name: PeerName::try_from("TestPeer").unwrap(),
+            location: None,
+            network_configuration: PeerNetworkConfiguration { interfaces: vec![] },
+            topology: Topology::default(),
+            executors: ExecutorDescriptors { executors: vec![] },
+            revision: 0,
+        };
+
+        testee.store_peer_descriptor(Request::new(
+            StorePeerDescriptorRequest {
+                peer: Some(peer_descriptor.into()),
+            }
+        )).await?;
+
+        let mut request = Request::new(peer_manager::DeletePeerDescriptorRequest {
+            peer_id: Some(peer_id.into()),
+        });
+        request.metadata_mut().insert(PROJECT_METADATA_KEY, MetadataValue::try_from(other_project.id.to_string())?);
+        let delete_reply = testee.delete_peer_descriptor(request).await?;
+
+        verify_that!(
+            delete_reply.get_ref().reply,
+            some(matches_pattern!(delete_peer_descriptor_response::Reply::Failure(anything())))
+        )?;
+
+        let audit_records = audit_manager.list(&AuditRecordFilter {
+            project: Some(other_project.id),
+            ..Default::default()
+        }).await;
+
+        verify_that!(audit_records, elements_are![
+            matches_pattern!(AuditRecord {
+                action: eq(AuditAction::DeletePeerDescriptor),
+                resource_id: eq(peer_id.0),
+                project: eq(other_project.id),
+                outcome: matches_pattern!(AuditOutcome::Failure { cause: anything() }),
+                before: none(),
+                after: none(),
+            }),
+        ])?;
+
         Ok(())
     }
 
@@ -357,6 +701,9 @@ mod tests {
         let resources_manager = ResourcesManager::new();
         let testee = PeerManagerFacade::new(
             Arc::clone(&resources_manager),
+            AuditManager::new(),
+            SecretsManager::new(SecretsManagerOptions { key: None }),
+            LimitsManager::new(LimitsOptions::default()),
             Vpn::Disabled,
             Url::parse("https://example.com:1234").unwrap(),
             get_cert(),
@@ -397,6 +744,9 @@ mod tests {
         let resources_manager = ResourcesManager::new();
         let testee = PeerManagerFacade::new(
             Arc::clone(&resources_manager),
+            AuditManager::new(),
+            SecretsManager::new(SecretsManagerOptions { key: None }),
+            LimitsManager::new(LimitsOptions::default()),
             Vpn::Disabled,
             Url::parse("https://example.com:1234").unwrap(),
             get_cert(),
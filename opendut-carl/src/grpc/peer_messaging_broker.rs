@@ -72,8 +72,6 @@ impl opendut_carl_api::proto::services::peer_messaging_broker::peer_messaging_br
 
         let (tx_inbound, rx_outbound) = self.peer_messaging_broker.open(peer_id, remote_host).await;
 
-        let peer_messaging_broker = Clone::clone(&self.peer_messaging_broker);
-
         let mut inbound = request.into_inner();
         tokio::spawn(async move {
             while let Some(result) = inbound.next().await {
@@ -94,12 +92,7 @@ impl opendut_carl_api::proto::services::peer_messaging_broker::peer_messaging_br
                 }
             }
 
-            if let Err(cause) = peer_messaging_broker.remove_peer(peer_id).await {
-                error!("Failed to removed peer <{peer_id}>:\n  {cause}");
-            }
-            else {
-                info!("Removed peer <{peer_id}>.");
-            };
+            info!("Stream of peer <{peer_id}> closed.");
         });
 
         let outbound = ReceiverStream::new(rx_outbound)
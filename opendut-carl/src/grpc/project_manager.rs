@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+use tonic_web::CorsGrpcWeb;
+use tracing::trace;
+
+use opendut_carl_api::carl::audit::AuditAction;
+use opendut_carl_api::proto::services::project_manager::*;
+use opendut_carl_api::proto::services::project_manager::project_manager_server::{ProjectManager as ProjectManagerService, ProjectManagerServer};
+use opendut_types::project::{ProjectDescriptor, ProjectId};
+
+use crate::actions;
+use crate::actions::{CreateProjectParams, DeleteProjectParams, ListProjectsParams};
+use crate::audit::identity::caller_of;
+use crate::audit::manager::AuditManagerRef;
+use crate::grpc::extract;
+use crate::resources::manager::ResourcesManagerRef;
+
+pub struct ProjectManagerFacade {
+    resources_manager: ResourcesManagerRef,
+    audit_manager: AuditManagerRef,
+}
+
+impl ProjectManagerFacade {
+
+    pub fn new(resources_manager: ResourcesManagerRef, audit_manager: AuditManagerRef) -> Self {
+        ProjectManagerFacade {
+            resources_manager,
+            audit_manager,
+        }
+    }
+
+    pub fn into_grpc_service(self) -> CorsGrpcWeb<ProjectManagerServer<Self>> {
+        tonic_web::enable(ProjectManagerServer::new(self))
+    }
+}
+
+#[tonic::async_trait]
+impl ProjectManagerService for ProjectManagerFacade {
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn create_project(&self, request: Request<CreateProjectRequest>) -> Result<Response<CreateProjectResponse>, Status> {
+
+        trace!("Received request: {:?}", request);
+
+        let caller = caller_of(&request);
+        let request = request.into_inner();
+        let project: ProjectDescriptor = extract!(request.project)?;
+        let project_id = project.id;
+
+        let (result, snapshot) = actions::create_project_with_snapshot(CreateProjectParams {
+            resources_manager: Arc::clone(&self.resources_manager),
+            project,
+        }).await;
+
+        let snapshot = snapshot.unwrap_or_default();
+        // Projects are managed independently of the selected project, so their records belong to the default project.
+        self.audit_manager.record(
+            caller.record(AuditAction::CreateProject, project_id.0)
+                .with_before(snapshot.before.as_ref())
+                .with_after(snapshot.after.as_ref())
+                .with_outcome(&result)
+        ).await;
+
+        match result {
+            Err(error) => {
+                Ok(Response::new(CreateProjectResponse {
+                    reply: Some(create_project_response::Reply::Failure(error.into()))
+                }))
+            }
+            Ok(project_id) => {
+                Ok(Response::new(CreateProjectResponse {
+                    reply: Some(create_project_response::Reply::Success(
+                        CreateProjectSuccess {
+                            project_id: Some(project_id.into())
+                        }
+                    ))
+                }))
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn delete_project(&self, request: Request<DeleteProjectRequest>) -> Result<Response<DeleteProjectResponse>, Status> {
+
+        trace!("Received request: {:?}", request);
+
+        let caller = caller_of(&request);
+        let request = request.into_inner();
+        let project_id: ProjectId = extract!(request.project_id)?;
+
+        let (result, snapshot) = actions::delete_project_with_snapshot(DeleteProjectParams {
+            resources_manager: Arc::clone(&self.resources_manager),
+            project_id,
+        }).await;
+
+        let snapshot = snapshot.unwrap_or_default();
+        self.audit_manager.record(
+            caller.record(AuditAction::DeleteProject, project_id.0)
+                .with_before(snapshot.before.as_ref())
+                .with_after(snapshot.after.as_ref())
+                .with_outcome(&result)
+        ).await;
+
+        match result {
+            Err(error) => {
+                Ok(Response::new(DeleteProjectResponse {
+                    reply: Some(delete_project_response::Reply::Failure(error.into()))
+                }))
+            }
+            Ok(project) => {
+                Ok(Response::new(DeleteProjectResponse {
+                    reply: Some(delete_project_response::Reply::Success(
+                        DeleteProjectSuccess {
+                            project_id: Some(project.id.into())
+                        }
+                    ))
+                }))
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn list_projects(&self, request: Request<ListProjectsRequest>) -> Result<Response<ListProjectsResponse>, Status> {
+
+        trace!("Received request: {:?}", request);
+
+        let result = actions::list_projects(ListProjectsParams {
+            resources_manager: Arc::clone(&self.resources_manager),
+        }).await;
+
+        match result {
+            Err(_) => {
+                Ok(Response::new(ListProjectsResponse {
+                    reply: Some(list_projects_response::Reply::Failure(ListProjectsFailure {}))
+                }))
+            }
+            Ok(projects) => {
+                Ok(Response::new(ListProjectsResponse {
+                    reply: Some(list_projects_response::Reply::Success(
+                        ListProjectsSuccess {
+                            projects: projects.into_iter().map(From::from).collect()
+                        }
+                    ))
+                }))
+            }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+use tonic_web::CorsGrpcWeb;
+use tracing::trace;
+
+use opendut_carl_api::carl::audit::AuditAction;
+use opendut_carl_api::carl::state::{ImportStrategy, StateArchive};
+use opendut_carl_api::proto::services::state_manager::*;
+use opendut_carl_api::proto::services::state_manager::state_manager_server::{StateManager as StateManagerService, StateManagerServer};
+
+use crate::actions;
+use crate::actions::{ExportStateParams, ImportStateParams};
+use crate::audit::identity::caller_of;
+use crate::audit::manager::AuditManagerRef;
+use crate::cluster::manager::ClusterManagerRef;
+use crate::grpc::extract;
+use crate::limits::manager::LimitsManagerRef;
+use crate::project;
+use crate::resources::manager::ResourcesManagerRef;
+use crate::secrets::manager::SecretsManagerRef;
+use crate::vpn::Vpn;
+
+pub struct StateManagerFacade {
+    resources_manager: ResourcesManagerRef,
+    audit_manager: AuditManagerRef,
+    secrets_manager: SecretsManagerRef,
+    limits_manager: LimitsManagerRef,
+    cluster_manager: ClusterManagerRef,
+    vpn: Vpn,
+}
+
+impl StateManagerFacade {
+
+    pub fn new(resources_manager: ResourcesManagerRef, audit_manager: AuditManagerRef, secrets_manager: SecretsManagerRef, limits_manager: LimitsManagerRef, cluster_manager: ClusterManagerRef, vpn: Vpn) -> Self {
+        StateManagerFacade {
+            resources_manager,
+            audit_manager,
+            secrets_manager,
+            limits_manager,
+            cluster_manager,
+            vpn,
+        }
+    }
+
+    pub fn into_grpc_service(self) -> CorsGrpcWeb<StateManagerServer<Self>> {
+        tonic_web::enable(StateManagerServer::new(self))
+    }
+}
+
+#[tonic::async_trait]
+impl StateManagerService for StateManagerFacade {
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn export_state(&self, request: Request<ExportStateRequest>) -> Result<Response<ExportStateResponse>, Status> {
+
+        trace!("Received request: {:?}", request);
+
+        let project = project::selected_project(&request, &self.resources_manager).await?;
+
+        let result = actions::export_state(ExportStateParams {
+            resources_manager: Arc::clone(&self.resources_manager),
+            project,
+        }).await;
+
+        match result {
+            Err(error) => {
+                Ok(Response::new(ExportStateResponse {
+                    reply: Some(export_state_response::Reply::Failure(error.into()))
+                }))
+            }
+            Ok(archive) => {
+                Ok(Response::new(ExportStateResponse {
+                    reply: Some(export_state_response::Reply::Success(
+                        ExportStateSuccess {
+                            archive: Some(archive.into())
+                        }
+                    ))
+                }))
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn import_state(&self, request: Request<ImportStateRequest>) -> Result<Response<ImportStateResponse>, Status> {
+
+        trace!("Received request: {:?}", request);
+
+        let caller = caller_of(&request);
+        let project = project::selected_project(&request, &self.resources_manager).await?;
+        let request = request.into_inner();
+        let archive: StateArchive = extract!(request.archive)?;
+        let strategy: ImportStrategy = extract!(request.strategy)?;
+
+        let result = actions::import_state(ImportStateParams {
+            resources_manager: Arc::clone(&self.resources_manager),
+            secrets_manager: Arc::clone(&self.secrets_manager),
+            cluster_manager: Arc::clone(&self.cluster_manager),
+            limits_manager: Arc::clone(&self.limits_manager),
+            vpn: Clone::clone(&self.vpn),
+            project,
+            archive,
+            strategy,
+        }).await;
+
+        self.audit_manager.record(
+            caller.record(AuditAction::ImportState, project.0)
+                .with_project(project)
+                .with_after(result.as_ref().ok())
+                .with_outcome(&result)
+        ).await;
+
+        match result {
+            Err(error) => {
+                Ok(Response::new(ImportStateResponse {
+                    reply: Some(import_state_response::Reply::Failure(error.into()))
+                }))
+            }
+            Ok(resources) => {
+                Ok(Response::new(ImportStateResponse {
+                    reply: Some(import_state_response::Reply::Success(
+                        ImportStateSuccess {
+                            resources: resources.into_iter().map(Into::into).collect(),
+                        }
+                    ))
+                }))
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use tonic::{Request, Response, Status};
+use tonic_web::CorsGrpcWeb;
+use tracing::trace;
+
+use opendut_carl_api::proto::services::vpn_manager::*;
+use opendut_carl_api::proto::services::vpn_manager::vpn_manager_server::{VpnManager as VpnManagerService, VpnManagerServer};
+
+use crate::vpn::reconciler::VpnReconcilerRef;
+
+pub struct VpnManagerFacade {
+    vpn_reconciler: VpnReconcilerRef,
+}
+
+impl VpnManagerFacade {
+
+    pub fn new(vpn_reconciler: VpnReconcilerRef) -> Self {
+        VpnManagerFacade {
+            vpn_reconciler
+        }
+    }
+
+    pub fn into_grpc_service(self) -> CorsGrpcWeb<VpnManagerServer<Self>> {
+        tonic_web::enable(VpnManagerServer::new(self))
+    }
+}
+
+#[tonic::async_trait]
+impl VpnManagerService for VpnManagerFacade {
+
+    #[tracing::instrument(skip(self, request), level="trace")]
+    async fn reconcile_vpn(&self, request: Request<ReconcileVpnRequest>) -> Result<Response<ReconcileVpnResponse>, Status> {
+
+        trace!("Received request: {:?}", request);
+
+        let request = request.into_inner();
+
+        match self.vpn_reconciler.reconcile(request.dry_run).await {
+            Err(error) => {
+                Ok(Response::new(ReconcileVpnResponse {
+                    reply: Some(reconcile_vpn_response::Reply::Failure(error.into()))
+                }))
+            }
+            Ok(report) => {
+                Ok(Response::new(ReconcileVpnResponse {
+                    reply: Some(reconcile_vpn_response::Reply::Success(
+                        ReconcileVpnSuccess {
+                            report: Some(report.into())
+                        }
+                    ))
+                }))
+            }
+        }
+    }
+}
@@ -16,26 +16,32 @@ use futures::TryFutureExt;
 use http::{header::CONTENT_TYPE, Request};
 use pem::Pem;
 use serde::Serialize;
-use tokio::fs;
+use tokio::sync::RwLock;
 use tonic::transport::Server;
-use tower::{BoxError, make::Shared, ServiceExt, steer::Steer};
+use tower::{BoxError, ServiceExt, steer::Steer};
 use tower_http::services::{ServeDir, ServeFile};
-use tracing::{debug, info};
+use tracing::info;
 use url::Url;
 use shadow_rs::formatcp;
 use opendut_carl_api::carl::auth::auth_config::OidcIdentityProviderConfig;
 use itertools::Itertools;
 use uuid::Uuid;
 
-use opendut_util::{logging, project};
+use opendut_util::logging;
 use opendut_util::logging::LoggingConfig;
 use opendut_util::settings::LoadedConfig;
 
+use crate::audit::manager::{AuditManager, AuditManagerOptions, AuditManagerRef};
 use crate::cluster::manager::{ClusterManager, ClusterManagerOptions, ClusterManagerRef};
-use crate::grpc::{ClusterManagerService, MetadataProviderService, PeerManagerService, PeerMessagingBrokerService};
+use crate::grpc::{AuditLogService, ClusterManagerService, MetadataProviderService, PeerManagerService, PeerMessagingBrokerService, ProjectManagerService, StateManagerService, VpnManagerService};
+use crate::limits::layer::RateLimitLayer;
+use crate::limits::manager::{LimitsManager, LimitsManagerRef, LimitsOptions};
 use crate::peer::broker::{PeerMessagingBroker, PeerMessagingBrokerOptions, PeerMessagingBrokerRef};
 use crate::peer::oidc_client_manager::{CarlIdentityProviderConfig, OpenIdConnectClientManager};
+use crate::reload::{ReloadOptions, Reloadables, TlsFiles};
 use crate::resources::manager::{ResourcesManager, ResourcesManagerRef};
+use crate::secrets::manager::{SecretsManager, SecretsManagerOptions, SecretsManagerRef};
+use crate::vpn::reconciler::{VpnReconciler, VpnReconcilerOptions, VpnReconcilerRef};
 use crate::vpn::Vpn;
 
 pub mod grpc;
@@ -43,10 +49,15 @@ pub mod grpc;
 opendut_util::app_info!();
 
 mod actions;
+mod audit;
 mod cluster;
+mod limits;
 mod metrics;
 mod peer;
+mod project;
+mod reload;
 mod resources;
+mod secrets;
 pub mod settings;
 mod vpn;
 
@@ -80,29 +91,13 @@ pub async fn create(settings: LoadedConfig) -> Result<()> { //TODO
         SocketAddr::from_str(&format!("{host}:{port}"))?
     };
 
-    let tls_config = {
-        let cert_path = project::make_path_absolute(settings.config.get_string("network.tls.certificate")?)?;
-        debug!("Using TLS certificate: {}", cert_path.display());
-        assert!(cert_path.exists(), "TLS certificate file at '{}' not found.", cert_path.display());
+    let tls_files = TlsFiles::load(&settings.config)?;
+    let tls_config = tls_files.load_tls_config().await?;
+    let ca_certificate = tls_files.load_ca().await
+        .context("Missing CA certificate file in CARL's configuration TOML.")?;
+    let ca_certificate = Arc::new(RwLock::new(ca_certificate));
 
-        let key_path = project::make_path_absolute(settings.config.get_string("network.tls.key")?)?;
-        debug!("Using TLS key: {}", key_path.display());
-        assert!(key_path.exists(), "TLS key file at '{}' not found.", key_path.display());
-
-        RustlsConfig::from_pem_file(cert_path, key_path).await?
-    };
-
-
-    let ca_string = fs::read_to_string(project::make_path_absolute(settings.config.get_string("network.tls.ca")?)?).await?;
-    let ca_certificate = match Pem::from_str(ca_string.as_str()) {
-        Ok(pem) => { pem }
-        Err(error) => {
-            panic!("Missing CA certificate file in CARL's configuration TOML. {:?}", error)
-        }
-    };
-
-
-    let vpn = vpn::create(&settings.config)
+    let (vpn, reloadable_vpn_client) = vpn::create(&settings.config)
         .context("Error while parsing VPN configuration.")?;
 
     let carl_url = {
@@ -114,10 +109,15 @@ pub async fn create(settings: LoadedConfig) -> Result<()> { //TODO
 
 
     let resources_manager = ResourcesManager::new();
+    let audit_manager = AuditManager::load(AuditManagerOptions::load(&settings.config)?).await
+        .context("Error while loading audit log.")?;
+    let secrets_manager = SecretsManager::new(SecretsManagerOptions::load(&settings.config)?);
+    let limits_manager = LimitsManager::new(LimitsOptions::load(&settings.config)?);
     metrics::initialize_metrics_collection(Arc::clone(&resources_manager));
     
     let peer_messaging_broker = PeerMessagingBroker::new(
         Arc::clone(&resources_manager),
+        Arc::clone(&secrets_manager),
         PeerMessagingBrokerOptions::load(&settings.config)?,
     );
     let cluster_manager = ClusterManager::new(
@@ -126,6 +126,20 @@ pub async fn create(settings: LoadedConfig) -> Result<()> { //TODO
         Clone::clone(&vpn),
         ClusterManagerOptions::load(&settings.config)?,
     );
+    cluster_manager.spawn_redeployment();
+    cluster_manager.spawn_scheduler();
+
+    let vpn_reconciler = VpnReconciler::new(Arc::clone(&resources_manager), Clone::clone(&vpn));
+    vpn_reconciler.spawn(VpnReconcilerOptions::load(&settings.config)?);
+
+    reload::spawn(Reloadables {
+        settings: Clone::clone(&settings),
+        tls_files,
+        tls_config: Clone::clone(&tls_config),
+        ca: Arc::clone(&ca_certificate),
+        peer_messaging_broker: Arc::clone(&peer_messaging_broker),
+        vpn_client: reloadable_vpn_client,
+    }, ReloadOptions::load(&settings.config)?);
 
     /// Isolation in function returning BoxFuture needed due to this: https://github.com/rust-lang/rust/issues/102211#issuecomment-1397600424
     #[allow(clippy::too_many_arguments)]
@@ -134,12 +148,16 @@ pub async fn create(settings: LoadedConfig) -> Result<()> { //TODO
         address: SocketAddr,
         tls_config: RustlsConfig,
         resources_manager: ResourcesManagerRef,
+        audit_manager: AuditManagerRef,
+        secrets_manager: SecretsManagerRef,
+        limits_manager: LimitsManagerRef,
         cluster_manager: ClusterManagerRef,
         peer_messaging_broker: PeerMessagingBrokerRef,
         vpn: Vpn,
+        vpn_reconciler: VpnReconcilerRef,
         carl_url: Url,
         settings: config::Config,
-        ca: Pem,
+        ca: Arc<RwLock<Pem>>,
     ) -> BoxFuture<'static, Result<()>> {
         let oidc_enabled = settings.get_bool("network.oidc.enabled").unwrap_or(false);
 
@@ -155,7 +173,11 @@ pub async fn create(settings: LoadedConfig) -> Result<()> { //TODO
         let grpc = Server::builder()
             .accept_http1(true) //gRPC-web uses HTTP1
             .add_service(
-                ClusterManagerService::new(Arc::clone(&cluster_manager), Arc::clone(&resources_manager))
+                AuditLogService::new(Arc::clone(&audit_manager), Arc::clone(&resources_manager))
+                    .into_grpc_service()
+            )
+            .add_service(
+                ClusterManagerService::new(Arc::clone(&cluster_manager), Arc::clone(&resources_manager), Arc::clone(&audit_manager), Arc::clone(&limits_manager))
                     .into_grpc_service()
             )
             .add_service(
@@ -163,22 +185,38 @@ pub async fn create(settings: LoadedConfig) -> Result<()> { //TODO
                     .into_grpc_service()
             )
             .add_service(
-                PeerManagerService::new(Arc::clone(&resources_manager), vpn, Clone::clone(&carl_url), ca, oidc_client_manager)
+                PeerManagerService::new(Arc::clone(&resources_manager), Arc::clone(&audit_manager), Arc::clone(&secrets_manager), Arc::clone(&limits_manager), Clone::clone(&vpn), Clone::clone(&carl_url), ca, oidc_client_manager)
                     .into_grpc_service()
             )
             .add_service(
                 PeerMessagingBrokerService::new(Arc::clone(&peer_messaging_broker))
                     .into_grpc_service()
             )
-            .into_service()
+            .add_service(
+                ProjectManagerService::new(Arc::clone(&resources_manager), Arc::clone(&audit_manager))
+                    .into_grpc_service()
+            )
+            .add_service(
+                StateManagerService::new(Arc::clone(&resources_manager), Arc::clone(&audit_manager), Arc::clone(&secrets_manager), Arc::clone(&limits_manager), Arc::clone(&cluster_manager), vpn)
+                    .into_grpc_service()
+            )
+            .add_service(
+                VpnManagerService::new(vpn_reconciler)
+                    .into_grpc_service()
+            )
+            .into_service();
+
+        let grpc = tower::ServiceBuilder::new()
+            .layer(RateLimitLayer::new(limits_manager))
+            .service(grpc)
             .map_response(|response| response.map(axum::body::boxed))
             .boxed_clone();
 
-        let lea_dir = project::make_path_absolute(settings.get_string("serve.ui.directory")
+        let lea_dir = opendut_util::project::make_path_absolute(settings.get_string("serve.ui.directory")
             .expect("Failed to find configuration for `serve.ui.directory`."))
             .expect("Failure while making path absolute.");
         let lea_presence_check = settings.get_bool("serve.ui.presence_check").unwrap_or(true);
-        let licenses_dir = project::make_path_absolute("./licenses")
+        let licenses_dir = opendut_util::project::make_path_absolute("./licenses")
             .expect("licenses directory should be absolute");
 
         let lea_idp_config = if oidc_enabled {
@@ -246,7 +284,7 @@ pub async fn create(settings: LoadedConfig) -> Result<()> { //TODO
         Box::pin(
             axum_server_dual_protocol::bind_dual_protocol(address, tls_config)
                 .set_upgrade(true) //http -> https
-                .serve(Shared::new(http_grpc))
+                .serve(axum::ServiceExt::into_make_service_with_connect_info::<SocketAddr>(http_grpc))
                 .map_err(|cause| anyhow!(cause))
         )
     }
@@ -256,9 +294,13 @@ pub async fn create(settings: LoadedConfig) -> Result<()> { //TODO
         address,
         tls_config,
         resources_manager,
+        audit_manager,
+        secrets_manager,
+        limits_manager,
         cluster_manager,
         peer_messaging_broker,
         vpn,
+        vpn_reconciler,
         carl_url,
         settings.config,
         ca_certificate,
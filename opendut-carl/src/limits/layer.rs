@@ -0,0 +1,131 @@
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use axum::extract::ConnectInfo;
+use futures::future::{Either, ready, Ready};
+use tonic::body::BoxBody;
+use tower::{Layer, Service};
+
+use crate::audit::identity;
+use crate::limits::manager::{LimitsManagerRef, RateLimitClient};
+
+/// Applies the rate limits of the [`LimitsManager`](crate::limits::manager::LimitsManager) to all gRPC requests,
+/// before they are dispatched to the individual services.
+///
+/// Requests are attributed to the identity claimed by their bearer token, see [`RateLimitClient`].
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    limits_manager: LimitsManagerRef,
+}
+
+impl RateLimitLayer {
+    pub fn new(limits_manager: LimitsManagerRef) -> Self {
+        Self { limits_manager }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            limits_manager: Arc::clone(&self.limits_manager),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limits_manager: LimitsManagerRef,
+}
+
+impl<S, ReqBody> Service<http::Request<ReqBody>> for RateLimitService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Either<Ready<Result<Self::Response, Self::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        // CORS preflight requests of gRPC-web clients are not counted.
+        if request.method() == http::Method::POST {
+            if let Some(method) = grpc_method_of(request.uri().path()) {
+                let client = client_of(&request);
+                if let Err(error) = self.limits_manager.check_rate(client, method) {
+                    return Either::Left(ready(Ok(tonic::Status::from(error).to_http())));
+                }
+            }
+        }
+        Either::Right(self.inner.call(request))
+    }
+}
+
+/// Determines the client, which sent the request, by the identity claimed in its bearer token.
+/// Requests without a (well-formed) token are attributed to the address of the client, which the server records as [`ConnectInfo`].
+/// Requests without a recorded address share the limits of the unspecified address.
+fn client_of<B>(request: &http::Request<B>) -> RateLimitClient {
+    let authorization = request.headers().get(http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+
+    match identity::claimed_identity(authorization) {
+        Some(identity) => RateLimitClient::Identity(identity),
+        None => {
+            let address = request.extensions().get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(address)| address.ip())
+                .unwrap_or(IpAddr::V6(Ipv6Addr::UNSPECIFIED));
+            RateLimitClient::Address(address)
+        }
+    }
+}
+
+/// Extracts the name of the called method from the path of a gRPC request, e.g. `/opendut.carl.services.peer_manager.PeerManager/GeneratePeerSetup`.
+fn grpc_method_of(path: &str) -> Option<&str> {
+    path.rsplit_once('/')
+        .map(|(_, method)| method)
+        .filter(|method| !method.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine;
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use googletest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn should_extract_the_grpc_method_from_the_path() -> Result<()> {
+        verify_that!(grpc_method_of("/opendut.carl.services.peer_manager.PeerManager/GeneratePeerSetup"), some(eq("GeneratePeerSetup")))?;
+        verify_that!(grpc_method_of("/"), none())
+    }
+
+    #[test]
+    fn should_determine_the_client_from_the_connect_info() -> Result<()> {
+        let mut request = http::Request::new(());
+        verify_that!(client_of(&request), eq(RateLimitClient::Address(IpAddr::V6(Ipv6Addr::UNSPECIFIED))))?;
+
+        request.extensions_mut().insert(ConnectInfo(SocketAddr::from(([192, 168, 0, 1], 4321))));
+        verify_that!(client_of(&request), eq(RateLimitClient::Address(IpAddr::from([192, 168, 0, 1]))))?;
+
+        request.headers_mut().insert(http::header::AUTHORIZATION, "Bearer no-auth-token".parse()?);
+        verify_that!(client_of(&request), eq(RateLimitClient::Address(IpAddr::from([192, 168, 0, 1]))))
+    }
+
+    #[test]
+    fn should_determine_the_client_from_the_identity_of_the_bearer_token() -> Result<()> {
+        let claims = URL_SAFE_NO_PAD.encode(r#"{"preferred_username":"alice"}"#);
+        let mut request = http::Request::new(());
+        request.extensions_mut().insert(ConnectInfo(SocketAddr::from(([192, 168, 0, 1], 4321))));
+        request.headers_mut().insert(http::header::AUTHORIZATION, format!("Bearer header.{claims}.signature").parse()?);
+
+        verify_that!(client_of(&request), eq(RateLimitClient::Identity(String::from("alice"))))
+    }
+}
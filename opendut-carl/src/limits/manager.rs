@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::ops::Not;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tonic::Status;
+use tracing::{debug, warn};
+
+use opendut_types::cluster::{ClusterConfiguration, ClusterId};
+use opendut_types::peer::PeerDescriptor;
+use opendut_types::project::ProjectId;
+
+use crate::metrics::LimitMetrics;
+use crate::project;
+use crate::resources::Resources;
+
+pub type LimitsManagerRef = Arc<LimitsManager>;
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum QuotaExceededError {
+    #[error("Project <{project_id}> already contains the maximum number of {limit} peers.")]
+    Peers {
+        project_id: ProjectId,
+        limit: usize,
+    },
+    #[error("Project <{project_id}> already contains the maximum number of {limit} clusters.")]
+    Clusters {
+        project_id: ProjectId,
+        limit: usize,
+    },
+    #[error("Project <{project_id}> would exceed the maximum number of {limit} container executors.")]
+    Executors {
+        project_id: ProjectId,
+        limit: usize,
+    },
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[error("Rate limit of {limit} requests per {}ms for '{method}' reached. Retry in {}ms.", window.as_millis(), retry_after.as_millis())]
+pub struct RateLimitExceededError {
+    pub method: String,
+    pub limit: u32,
+    pub window: Duration,
+    pub retry_after: Duration,
+}
+
+impl From<RateLimitExceededError> for Status {
+    fn from(error: RateLimitExceededError) -> Self {
+        Status::resource_exhausted(error.to_string())
+    }
+}
+
+impl QuotaExceededError {
+    fn quota(&self) -> &'static str {
+        match self {
+            QuotaExceededError::Peers { .. } => "peers",
+            QuotaExceededError::Clusters { .. } => "clusters",
+            QuotaExceededError::Executors { .. } => "executors",
+        }
+    }
+}
+
+impl From<QuotaExceededError> for Status {
+    fn from(error: QuotaExceededError) -> Self {
+        Status::resource_exhausted(error.to_string())
+    }
+}
+
+/// Enforces the rate limits of CARL's gRPC methods and the quotas of resources per project.
+///
+/// Rate limits are counted per [`RateLimitClient`].
+/// Quotas are counted per project, as projects own the peers and clusters. CARL does not record which user created a resource,
+/// so there is nothing to count per user. To limit the resources of individual users, give them separate projects.
+pub struct LimitsManager {
+    options: LimitsOptions,
+    windows: Mutex<HashMap<(RateLimitClient, String), RateWindow>>,
+    metrics: LimitMetrics,
+}
+
+/// The client, which the requests are counted for in the rate limits.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RateLimitClient {
+    /// The identity claimed by the bearer token of the request.
+    Identity(String),
+    /// The address of the client, for requests without a bearer token.
+    Address(IpAddr),
+}
+
+impl fmt::Display for RateLimitClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateLimitClient::Identity(identity) => write!(f, "{identity}"),
+            RateLimitClient::Address(address) => write!(f, "{address}"),
+        }
+    }
+}
+
+struct RateWindow {
+    started: Instant,
+    requests: u32,
+}
+
+impl LimitsManager {
+
+    pub fn new(options: LimitsOptions) -> LimitsManagerRef {
+        Arc::new(Self {
+            options,
+            windows: Default::default(),
+            metrics: LimitMetrics::new(),
+        })
+    }
+
+    /// Counts a request of the given client to the given gRPC method and
+    /// rejects it, if the rate limit of the method has already been reached within the current window.
+    /// Windows, which have expired, are evicted.
+    pub fn check_rate(&self, client: RateLimitClient, method: &str) -> Result<(), RateLimitExceededError> {
+        let Some(limit) = self.options.rate_limit_of(method) else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        let window_length = self.options.rate_window;
+
+        let mut windows = self.windows.lock().expect("Lock of rate limit windows should not be poisoned.");
+        windows.retain(|_, window| now.duration_since(window.started) < window_length);
+
+        let window = windows.entry((Clone::clone(&client), method.to_owned()))
+            .or_insert(RateWindow { started: now, requests: 0 });
+
+        if window.requests >= limit {
+            warn!("Rejecting request of '{client}' to '{method}', because the rate limit of {limit} requests per {}ms was reached.", window_length.as_millis());
+            self.metrics.record_rate_limited_request(method);
+            return Err(RateLimitExceededError {
+                method: method.to_owned(),
+                limit,
+                window: window_length,
+                retry_after: window_length.saturating_sub(now.duration_since(window.started)),
+            });
+        }
+
+        window.requests += 1;
+        Ok(())
+    }
+
+    /// Checks whether storing the given peer descriptor in the given project stays within the quotas for peers and container executors.
+    pub fn check_peer_quota(&self, resources: &Resources, project_id: ProjectId, peer: &PeerDescriptor) -> Result<(), QuotaExceededError> {
+        let project_peers = resources.iter::<PeerDescriptor>()
+            .filter(|other| other.id != peer.id)
+            .filter(|other| project::is_owned_by(resources, other.id, project_id))
+            .collect::<Vec<_>>();
+
+        let result = if let Some(limit) = self.options.max_peers.filter(|limit| project_peers.len() >= *limit) {
+            Err(QuotaExceededError::Peers { project_id, limit })
+        } else {
+            let executors = project_peers.iter()
+                .map(|other| other.executors.executors.len())
+                .sum::<usize>()
+                + peer.executors.executors.len();
+
+            match self.options.max_executors {
+                Some(limit) if executors > limit => Err(QuotaExceededError::Executors { project_id, limit }),
+                _ => Ok(()),
+            }
+        };
+        self.record_quota_outcome(result)
+    }
+
+    /// Checks whether creating the given cluster in the given project stays within the quota for clusters.
+    pub fn check_cluster_quota(&self, resources: &Resources, project_id: ProjectId, cluster_id: ClusterId) -> Result<(), QuotaExceededError> {
+        let Some(limit) = self.options.max_clusters else {
+            return Ok(());
+        };
+
+        let project_clusters = resources.iter::<ClusterConfiguration>()
+            .filter(|other| other.id != cluster_id)
+            .filter(|other| project::is_owned_by(resources, other.id, project_id))
+            .count();
+
+        let result = if project_clusters >= limit {
+            Err(QuotaExceededError::Clusters { project_id, limit })
+        } else {
+            Ok(())
+        };
+        self.record_quota_outcome(result)
+    }
+
+    fn record_quota_outcome(&self, result: Result<(), QuotaExceededError>) -> Result<(), QuotaExceededError> {
+        result.inspect_err(|error| {
+            warn!("{error}");
+            self.metrics.record_exceeded_quota(error.quota());
+        })
+    }
+}
+
+/// Limits of CARL's gRPC methods and resources. The default options do not limit anything.
+#[derive(Clone, Debug, Default)]
+pub struct LimitsOptions {
+    pub rate_window: Duration,
+    pub rate_default: Option<u32>,
+    /// Rate limits of individual gRPC methods, keyed by the lowercase method name.
+    pub rate_methods: HashMap<String, u32>,
+    pub max_peers: Option<usize>,
+    pub max_clusters: Option<usize>,
+    pub max_executors: Option<usize>,
+}
+
+impl LimitsOptions {
+    pub fn load(config: &config::Config) -> Result<Self, opendut_util::settings::LoadError> {
+        let rate_window = Duration::from_millis(config.get::<u64>("limits.rate.window.ms")?);
+        let rate_default = Some(config.get::<u32>("limits.rate.default")?).filter(|limit| *limit > 0);
+
+        let rate_methods = config.get_table("limits.rate.methods")
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(method, limit)| Ok((method.to_lowercase(), limit.into_uint()? as u32)))
+            .collect::<Result<HashMap<_, _>, config::ConfigError>>()?;
+        debug!("Configured rate limits for gRPC methods: {rate_methods:?}");
+
+        let quota = |key: &str| -> Result<Option<usize>, config::ConfigError> {
+            Ok(Some(config.get::<usize>(key)?).filter(|limit| *limit > 0))
+        };
+
+        Ok(LimitsOptions {
+            rate_window,
+            rate_default,
+            rate_methods,
+            max_peers: quota("limits.quota.peers")?,
+            max_clusters: quota("limits.quota.clusters")?,
+            max_executors: quota("limits.quota.executors")?,
+        })
+    }
+
+    /// Determines the rate limit of a gRPC method, where a limit of `0` disables rate limiting of the method.
+    fn rate_limit_of(&self, method: &str) -> Option<u32> {
+        match self.rate_methods.get(&method.to_lowercase()) {
+            Some(limit) => Some(*limit).filter(|limit| *limit > 0),
+            None => self.rate_default,
+        }
+        .filter(|_| self.rate_window.is_zero().not())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+
+    use opendut_types::peer::{PeerId, PeerLocation, PeerName, PeerNetworkConfiguration};
+    use opendut_types::peer::executor::{ContainerCommand, ContainerImage, ContainerName, Engine, ExecutorDescriptor, ExecutorDescriptors};
+    use opendut_types::topology::Topology;
+
+    use crate::project::OwningProject;
+    use crate::resources::manager::ResourcesManager;
+
+    use super::*;
+
+    fn options() -> LimitsOptions {
+        LimitsOptions {
+            rate_window: Duration::from_secs(60),
+            rate_default: None,
+            rate_methods: HashMap::from([(String::from("generatepeersetup"), 2)]),
+            max_peers: Some(1),
+            max_clusters: None,
+            max_executors: Some(1),
+        }
+    }
+
+    fn peer(executors: usize) -> PeerDescriptor {
+        PeerDescriptor {
+            id: PeerId::random(),
+            name: PeerName::try_from("peer").unwrap(),
+            location: PeerLocation::try_from("Ulm").ok(),
+            network_configuration: PeerNetworkConfiguration { interfaces: vec![] },
+            topology: Topology::default(),
+            executors: ExecutorDescriptors {
+                executors: (0..executors).map(|_| ExecutorDescriptor::Container {
+                    engine: Engine::Docker,
+                    name: ContainerName::Empty,
+                    image: ContainerImage::try_from("testUrl").unwrap(),
+                    volumes: vec![],
+                    devices: vec![],
+                    envs: vec![],
+                    ports: vec![],
+                    command: ContainerCommand::Default,
+                    args: vec![],
+                }).collect(),
+            },
+            revision: 0,
+        }
+    }
+
+    #[test]
+    fn should_limit_the_rate_of_requests_per_client_and_method() -> Result<()> {
+        let testee = LimitsManager::new(options());
+        let alice = || RateLimitClient::Identity(String::from("alice"));
+        let bob = || RateLimitClient::Identity(String::from("bob"));
+        let anonymous = || RateLimitClient::Address(IpAddr::from([192, 168, 0, 1]));
+
+        verify_that!(testee.check_rate(alice(), "GeneratePeerSetup"), ok(eq(())))?;
+        verify_that!(testee.check_rate(alice(), "GeneratePeerSetup"), ok(eq(())))?;
+        verify_that!(testee.check_rate(alice(), "GeneratePeerSetup"), err(field!(RateLimitExceededError.limit, eq(2))))?;
+
+        verify_that!(testee.check_rate(bob(), "GeneratePeerSetup"), ok(eq(())))?;
+        verify_that!(testee.check_rate(anonymous(), "GeneratePeerSetup"), ok(eq(())))?;
+        verify_that!(testee.check_rate(alice(), "ListPeerDescriptors"), ok(eq(())))
+    }
+
+    #[test]
+    fn should_evict_expired_windows() -> Result<()> {
+        let testee = LimitsManager::new(LimitsOptions {
+            rate_window: Duration::from_millis(10),
+            ..options()
+        });
+        let client = |address: [u8; 4]| RateLimitClient::Address(IpAddr::from(address));
+
+        verify_that!(testee.check_rate(client([192, 168, 0, 1]), "GeneratePeerSetup"), ok(eq(())))?;
+        std::thread::sleep(Duration::from_millis(20));
+        verify_that!(testee.check_rate(client([192, 168, 0, 2]), "GeneratePeerSetup"), ok(eq(())))?;
+
+        let windows = testee.windows.lock().unwrap();
+        verify_that!(windows.keys().collect::<Vec<_>>(), elements_are![eq(&(client([192, 168, 0, 2]), String::from("GeneratePeerSetup")))])
+    }
+
+    #[tokio::test]
+    async fn should_enforce_quotas_per_project() -> Result<()> {
+        let testee = LimitsManager::new(options());
+        let resources_manager = ResourcesManager::new();
+        let project_id = ProjectId::random();
+
+        let stored_peer = peer(1);
+        resources_manager.resources_mut(|resources| {
+            resources.insert(stored_peer.id, Clone::clone(&stored_peer));
+            resources.insert(stored_peer.id, OwningProject(project_id));
+        }).await;
+
+        resources_manager.resources(|resources| {
+            verify_that!(testee.check_peer_quota(resources, project_id, &peer(0)), err(matches_pattern!(QuotaExceededError::Peers { .. })))?;
+            verify_that!(testee.check_peer_quota(resources, ProjectId::DEFAULT, &peer(0)), ok(eq(())))?;
+            verify_that!(testee.check_peer_quota(resources, project_id, &stored_peer), ok(eq(())))?;
+            verify_that!(testee.check_peer_quota(resources, project_id, &PeerDescriptor { executors: peer(2).executors, ..Clone::clone(&stored_peer) }), err(matches_pattern!(QuotaExceededError::Executors { .. })))?;
+            verify_that!(testee.check_cluster_quota(resources, project_id, ClusterId::random()), ok(eq(())))
+        }).await
+    }
+}
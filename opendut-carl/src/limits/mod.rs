@@ -0,0 +1,2 @@
+pub mod layer;
+pub mod manager;
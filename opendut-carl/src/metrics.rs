@@ -1,4 +1,5 @@
-use opentelemetry::global;
+use opentelemetry::{global, KeyValue};
+use opentelemetry::metrics::Counter;
 use tracing::trace;
 use opendut_types::cluster::{ClusterConfiguration, ClusterDeployment};
 use opendut_types::peer::PeerDescriptor;
@@ -74,3 +75,34 @@ impl TryFrom<Metrics> for ObservableMetrics {
     }
 }
 
+/// Counts the requests and operations, which were rejected due to rate limits or quotas.
+#[derive(Clone)]
+pub struct LimitMetrics {
+    rate_limited_requests: Counter<u64>,
+    exceeded_quotas: Counter<u64>,
+}
+
+impl LimitMetrics {
+    pub fn new() -> Self {
+        let meter = global::meter("opendut_meter");
+
+        Self {
+            rate_limited_requests: meter.u64_counter("rate_limited_requests").init(),
+            exceeded_quotas: meter.u64_counter("exceeded_quotas").init(),
+        }
+    }
+
+    pub fn record_rate_limited_request(&self, method: &str) {
+        self.rate_limited_requests.add(1, &[KeyValue::new("method", method.to_owned())]);
+    }
+
+    pub fn record_exceeded_quota(&self, quota: &'static str) {
+        self.exceeded_quotas.add(1, &[KeyValue::new("quota", quota)]);
+    }
+}
+
+impl Default for LimitMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::net::IpAddr;
+use std::ops::Not;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -17,30 +18,39 @@ use opendut_carl_api::proto::services::peer_messaging_broker::upstream;
 use opendut_types::peer::PeerId;
 use opendut_types::peer::configuration::PeerConfiguration;
 use opendut_types::peer::state::{PeerState, PeerUpState};
+use opendut_types::vpn::health::VpnHealth;
 
 use crate::resources::manager::ResourcesManagerRef;
+use crate::secrets::manager::SecretsManagerRef;
 
 pub type PeerMessagingBrokerRef = Arc<PeerMessagingBroker>;
 
 
 pub struct PeerMessagingBroker {
     resources_manager: ResourcesManagerRef,
+    secrets_manager: SecretsManagerRef,
     peers: Arc<RwLock<HashMap<PeerId, PeerMessagingRef>>>,
-    options: PeerMessagingBrokerOptions,
+    options: Arc<RwLock<PeerMessagingBrokerOptions>>,
 }
 struct PeerMessagingRef {
     downstream: mpsc::Sender<Downstream>,
 }
 
 impl PeerMessagingBroker {
-    pub fn new(resources_manager: ResourcesManagerRef, options: PeerMessagingBrokerOptions) -> PeerMessagingBrokerRef {
+    pub fn new(resources_manager: ResourcesManagerRef, secrets_manager: SecretsManagerRef, options: PeerMessagingBrokerOptions) -> PeerMessagingBrokerRef {
         Arc::new(Self {
             resources_manager,
+            secrets_manager,
             peers: Default::default(),
-            options,
+            options: Arc::new(RwLock::new(options)),
         })
     }
 
+    /// Replaces the options, which also applies to the streams of already connected peers.
+    pub async fn update_options(&self, options: PeerMessagingBrokerOptions) {
+        *self.options.write().await = options;
+    }
+
     #[tracing::instrument(skip(self), level="trace")]
     pub async fn send_to_peer(&self, peer_id: PeerId, message: downstream::Message) -> Result<(), Error> {
         let downstream = {
@@ -66,6 +76,24 @@ impl PeerMessagingBroker {
         Ok(())
     }
 
+    /// Sends the configuration to the peer, revealing the values of its secrets, which are only ever sent to the peer itself.
+    #[tracing::instrument(skip(self, configuration), level="trace")]
+    pub async fn send_peer_configuration(&self, peer_id: PeerId, configuration: PeerConfiguration) -> Result<(), Error> {
+        let executors = self.secrets_manager.reveal(peer_id, configuration.executors).await
+            .map_err(|cause| Error::Other { message: cause.to_string() })?;
+
+        let configuration = PeerConfiguration {
+            executors,
+            ..configuration
+        };
+
+        self.send_to_peer(peer_id, downstream::Message::ApplyPeerConfiguration(
+            ApplyPeerConfiguration {
+                configuration: Some(configuration.into())
+            }
+        )).await
+    }
+
     pub async fn list_peers(&self) -> Vec<PeerId> {
         let peers = self.peers.read().await;
 
@@ -107,29 +135,25 @@ impl PeerMessagingBroker {
         }).await;
 
         if let Some(configuration) = self.resources_manager.get::<PeerConfiguration>(peer_id).await {
-            if let Err(error) = self.send_to_peer(peer_id, downstream::Message::ApplyPeerConfiguration(
-                ApplyPeerConfiguration {
-                    configuration: Some(configuration.into())
-                }
-            )).await {
+            if let Err(error) = self.send_peer_configuration(peer_id, configuration).await {
                 error!("Failed to send ApplyPeerConfiguration message: {error}")
             };
         } else {
             error!("Failed to send ApplyPeerConfiguration message, because no PeerConfiguration found for peer: {peer_id}")
         }
 
-        let timeout_duration = self.options.peer_disconnect_timeout;
-
         {
             let peers = Arc::clone(&self.peers);
             let resources_manager = Arc::clone(&self.resources_manager);
+            let options = Arc::clone(&self.options);
 
             tokio::spawn(async move {
                 loop {
+                    let timeout_duration = options.read().await.peer_disconnect_timeout;
                     let received = tokio::time::timeout(timeout_duration, rx_inbound.recv()).await;
 
                     match received {
-                        Ok(Some(message)) => handle_stream_message(message, peer_id, &tx_outbound).await,
+                        Ok(Some(message)) => handle_stream_message(message, peer_id, &tx_outbound, &resources_manager).await,
                         Ok(None) => {
                             info!("Peer <{peer_id}> disconnected!");
                             break;
@@ -140,36 +164,31 @@ impl PeerMessagingBroker {
                         }
                     }
                 }
+                let mut peers = peers.write().await;
+
+                let is_current_stream = peers.get(&peer_id)
+                    .is_some_and(|peer| peer.downstream.same_channel(&tx_outbound));
+                if is_current_stream.not() {
+                    debug!("Stream of peer <{peer_id}> was superseded by a newly opened stream. Keeping the peer connected.");
+                    return;
+                }
+
                 down_peer_impl(resources_manager, peer_id).await;
 
                 debug!("Removing peer <{peer_id}> from list of peers connected to message broker.");
-                let mut peers = peers.write().await;
-                let removed = peers.remove(&peer_id);
-                if removed.is_none() {
-                    error!("Failed to remove peer from list of peers connected to message broker.")
-                }
+                peers.remove(&peer_id);
             });
         }
 
         (tx_inbound, rx_outbound)
     }
-
-    pub async fn remove_peer(&self, peer_id: PeerId) -> Result<(), Error> {
-        let mut peers = self.peers.write().await;
-
-        down_peer_impl(Arc::clone(&self.resources_manager), peer_id).await;
-
-        match peers.remove(&peer_id) {
-            Some(_) => Ok(()),
-            None => Err(Error::PeerNotFound(peer_id)),
-        }
-    }
 }
 
 async fn handle_stream_message(
     message: upstream::Message,
     peer_id: PeerId,
     tx_outbound: &Sender<Downstream>,
+    resources_manager: &ResourcesManagerRef,
 ) {
     match message {
         upstream::Message::Ping(_) => {
@@ -179,6 +198,25 @@ async fn handle_stream_message(
                 tx_outbound.send(Downstream{message:Some(message), context}).await
                     .inspect_err(|cause| warn!("Failed to send ping to peer <{peer_id}>: {cause}"));
         },
+        upstream::Message::ReportVpnHealth(report) => {
+            let health = report.health
+                .ok_or_else(|| String::from("Field 'health' not set."))
+                .and_then(|health| VpnHealth::try_from(health).map_err(|cause| cause.to_string()));
+
+            match health {
+                Ok(health) => {
+                    if !health.connected {
+                        warn!("Peer <{peer_id}> reports its VPN connection as down.");
+                    }
+                    for link in health.links.iter().filter(|link| !link.connected || link.relayed) {
+                        let state = if link.connected { "relayed" } else { "disconnected" };
+                        debug!("Peer <{peer_id}> reports its VPN link to <{}> as {state}.", link.remote_address);
+                    }
+                    resources_manager.insert(peer_id, health).await;
+                }
+                Err(cause) => warn!("Received invalid VPN health report from peer <{peer_id}>: {cause}"),
+            }
+        },
     }
 }
 
@@ -190,7 +228,8 @@ async fn down_peer_impl(resources_manager: ResourcesManagerRef, peer_id: PeerId)
             .modify(|peer_state| {
                 *peer_state = PeerState::Down;
             })
-            .or_insert(PeerState::Down)
+            .or_insert(PeerState::Down);
+        resources.remove::<VpnHealth>(peer_id);
     }).await;
 }
 
@@ -229,9 +268,11 @@ mod tests {
     use tokio::sync::mpsc;
     use tokio::sync::mpsc::Receiver;
 
-    use opendut_carl_api::proto::services::peer_messaging_broker::Ping;
+    use opendut_carl_api::proto::services::peer_messaging_broker::{Ping, ReportVpnHealth};
+    use opendut_types::vpn::health::VpnLink;
 
     use crate::resources::manager::ResourcesManager;
+    use crate::secrets::manager::{SecretsManager, SecretsManagerOptions};
 
     use super::*;
 
@@ -242,7 +283,7 @@ mod tests {
         let options = PeerMessagingBrokerOptions {
             peer_disconnect_timeout: Duration::from_millis(200),
         };
-        let testee = PeerMessagingBroker::new(Arc::clone(&resources_manager), options.clone());
+        let testee = PeerMessagingBroker::new(Arc::clone(&resources_manager), SecretsManager::new(SecretsManagerOptions { key: None }), options.clone());
 
         let peer_id = PeerId::random();
         let remote_host = IpAddr::from_str("1.2.3.4")?;
@@ -300,6 +341,64 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn should_store_the_reported_vpn_health_until_the_peer_disconnects() -> Result<()> {
+        let resources_manager = ResourcesManager::new();
+
+        let options = PeerMessagingBrokerOptions {
+            peer_disconnect_timeout: Duration::from_millis(200),
+        };
+        let testee = PeerMessagingBroker::new(Arc::clone(&resources_manager), SecretsManager::new(SecretsManagerOptions { key: None }), options.clone());
+
+        let peer_id = PeerId::random();
+        let (sender, _receiver) = testee.open(peer_id, IpAddr::from_str("1.2.3.4")?).await;
+
+        let health = VpnHealth {
+            connected: true,
+            address: Some(IpAddr::from_str("100.64.0.1")?),
+            links: vec![
+                VpnLink {
+                    remote_address: IpAddr::from_str("100.64.0.2")?,
+                    connected: true,
+                    relayed: true,
+                    latency: None,
+                },
+            ],
+        };
+        sender.send(upstream::Message::ReportVpnHealth(ReportVpnHealth { health: Some(health.clone().into()) })).await?;
+        tokio::time::sleep(options.peer_disconnect_timeout / 2).await;
+
+        verify_that!(resources_manager.get::<VpnHealth>(peer_id).await, some(eq(health)))?;
+
+        tokio::time::sleep(options.peer_disconnect_timeout * 2).await;
+
+        verify_that!(resources_manager.get::<VpnHealth>(peer_id).await, none())
+    }
+
+    #[tokio::test]
+    async fn should_keep_the_peer_up_when_a_superseded_stream_closes() -> Result<()> {
+        let resources_manager = ResourcesManager::new();
+
+        let options = PeerMessagingBrokerOptions {
+            peer_disconnect_timeout: Duration::from_millis(200),
+        };
+        let testee = PeerMessagingBroker::new(Arc::clone(&resources_manager), SecretsManager::new(SecretsManagerOptions { key: None }), options.clone());
+
+        let peer_id = PeerId::random();
+        let (old_sender, _old_receiver) = testee.open(peer_id, IpAddr::from_str("1.2.3.4")?).await;
+        let (sender, mut receiver) = testee.open(peer_id, IpAddr::from_str("5.6.7.8")?).await;
+
+        drop(old_sender);
+        tokio::time::sleep(options.peer_disconnect_timeout / 2).await;
+        do_ping(&sender, &mut receiver).await;
+
+        verify_that!(testee.list_peers().await, elements_are![eq(peer_id)])?;
+        verify_that!(resources_manager.get::<PeerState>(peer_id).await, some(eq(PeerState::Up {
+            inner: PeerUpState::Available,
+            remote_host: IpAddr::from_str("5.6.7.8")?,
+        })))
+    }
+
     async fn do_ping(sender: &mpsc::Sender<upstream::Message>, receiver: &mut Receiver<Downstream>) {
         sender.send(upstream::Message::Ping(Ping {})).await
             .unwrap();
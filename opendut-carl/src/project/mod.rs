@@ -0,0 +1,69 @@
+use tonic::{Request, Status};
+
+use opendut_carl_api::carl::project::PROJECT_METADATA_KEY;
+use opendut_types::project::ProjectId;
+
+use crate::actions;
+use crate::resources::{IntoId, Resources};
+use crate::resources::manager::ResourcesManagerRef;
+
+/// Records the project, which a peer or cluster belongs to.
+///
+/// Peers and clusters without this resource belong to the default project.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OwningProject(pub ProjectId);
+
+/// Determines the project selected by a request.
+///
+/// Requests without a selected project operate on the default project.
+pub fn project_of<T>(request: &Request<T>) -> Result<ProjectId, Status> {
+    let Some(project) = request.metadata().get(PROJECT_METADATA_KEY) else {
+        return Ok(ProjectId::DEFAULT);
+    };
+
+    project.to_str().ok()
+        .and_then(|project| ProjectId::try_from(project).ok())
+        .ok_or_else(|| Status::invalid_argument(format!("Invalid value for metadata '{PROJECT_METADATA_KEY}'. Expected a ProjectId.")))
+}
+
+/// Determines the project selected by a request and ensures that it exists.
+pub async fn selected_project<T>(request: &Request<T>, resources_manager: &ResourcesManagerRef) -> Result<ProjectId, Status> {
+    let project_id = project_of(request)?;
+    if actions::project_exists(resources_manager, project_id).await {
+        Ok(project_id)
+    } else {
+        Err(Status::not_found(format!("Project <{project_id}> does not exist.")))
+    }
+}
+
+pub fn project_of_resource(resources: &Resources, id: impl IntoId<OwningProject>) -> ProjectId {
+    resources.get::<OwningProject>(id)
+        .map(|owner| owner.0)
+        .unwrap_or_default()
+}
+
+pub fn is_owned_by(resources: &Resources, id: impl IntoId<OwningProject>, project_id: ProjectId) -> bool {
+    project_of_resource(resources, id) == project_id
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+    use tonic::metadata::MetadataValue;
+
+    use super::*;
+
+    #[test]
+    fn should_determine_project_of_request() -> Result<()> {
+        let project_id = ProjectId::random();
+
+        let mut request = Request::new(());
+        verify_that!(project_of(&request), ok(eq(ProjectId::DEFAULT)))?;
+
+        request.metadata_mut().insert(PROJECT_METADATA_KEY, MetadataValue::try_from(project_id.to_string())?);
+        verify_that!(project_of(&request), ok(eq(project_id)))?;
+
+        request.metadata_mut().insert(PROJECT_METADATA_KEY, MetadataValue::from_static("not-a-project"));
+        verify_that!(project_of(&request), err(anything()))
+    }
+}
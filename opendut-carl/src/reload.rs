@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use std::ops::Not;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, Context};
+use axum_server::tls_rustls::RustlsConfig;
+use pem::Pem;
+use tokio::sync::RwLock;
+use tracing::{debug, error, info};
+
+use opendut_util::project;
+use opendut_util::settings::LoadedConfig;
+
+use crate::peer::broker::{PeerMessagingBrokerOptions, PeerMessagingBrokerRef};
+use crate::settings;
+use crate::vpn::ReloadableVpnManagementClientRef;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TlsFiles {
+    pub certificate: PathBuf,
+    pub key: PathBuf,
+    pub ca: PathBuf,
+}
+impl TlsFiles {
+    pub fn load(config: &config::Config) -> anyhow::Result<Self> {
+        let certificate = project::make_path_absolute(config.get_string("network.tls.certificate")?)?;
+        let key = project::make_path_absolute(config.get_string("network.tls.key")?)?;
+        let ca = project::make_path_absolute(config.get_string("network.tls.ca")?)?;
+
+        Ok(TlsFiles {
+            certificate,
+            key,
+            ca,
+        })
+    }
+
+    pub async fn load_tls_config(&self) -> anyhow::Result<RustlsConfig> {
+        self.check_exists()?;
+        RustlsConfig::from_pem_file(&self.certificate, &self.key).await
+            .context("Failed to load TLS certificate and key.")
+    }
+
+    pub async fn reload_tls_config(&self, tls_config: &RustlsConfig) -> anyhow::Result<()> {
+        self.check_exists()?;
+        tls_config.reload_from_pem_file(&self.certificate, &self.key).await
+            .context("Failed to reload TLS certificate and key.")
+    }
+
+    pub async fn load_ca(&self) -> anyhow::Result<Pem> {
+        let ca = tokio::fs::read_to_string(&self.ca).await
+            .context(format!("Failed to read CA certificate file at '{}'.", self.ca.display()))?;
+        Pem::from_str(&ca)
+            .context(format!("Failed to parse CA certificate file at '{}'.", self.ca.display()))
+    }
+
+    fn check_exists(&self) -> anyhow::Result<()> {
+        for (kind, path) in [("certificate", &self.certificate), ("key", &self.key)] {
+            debug!("Using TLS {kind}: {}", path.display());
+            if path.exists().not() {
+                return Err(anyhow!("TLS {kind} file at '{}' not found.", path.display()));
+            }
+        }
+        Ok(())
+    }
+
+    fn tls_paths(&self) -> [&Path; 2] {
+        [&self.certificate, &self.key]
+    }
+}
+
+/// Everything, which can be changed while CARL is running.
+pub struct Reloadables {
+    pub settings: LoadedConfig,
+    pub tls_files: TlsFiles,
+    pub tls_config: RustlsConfig,
+    pub ca: Arc<RwLock<Pem>>,
+    pub peer_messaging_broker: PeerMessagingBrokerRef,
+    pub vpn_client: Option<ReloadableVpnManagementClientRef>,
+}
+
+/// Periodically checks the TLS files and the configuration files for changes and applies them.
+///
+/// Of the configuration, only the TLS file locations, `peer.disconnect.timeout.ms` and the VPN settings are reloaded.
+/// Other settings still require a restart.
+pub fn spawn(reloadables: Reloadables, options: ReloadOptions) {
+    let Some(interval) = options.interval else {
+        info!("Reloading of TLS certificates and configuration is disabled.");
+        return;
+    };
+
+    tokio::spawn(async move {
+        let Reloadables { mut settings, mut tls_files, tls_config, ca, peer_messaging_broker, vpn_client } = reloadables;
+
+        let mut watched_tls = WatchedFiles::new(tls_files.tls_paths());
+        let mut watched_ca = WatchedFiles::new([&tls_files.ca]);
+        let mut watched_config = WatchedFiles::new(&settings.config_files_declared);
+
+        let mut interval = tokio::time::interval(interval);
+        interval.tick().await; // the first tick completes immediately
+
+        loop {
+            interval.tick().await;
+
+            let mut tls_changed = watched_tls.changed();
+            let mut ca_changed = watched_ca.changed();
+
+            if watched_config.changed() {
+                info!("Configuration changed. Reloading.");
+                match reload_settings(&settings, &peer_messaging_broker, &vpn_client).await {
+                    Ok(reloaded) => {
+                        match TlsFiles::load(&reloaded.config) {
+                            Ok(reloaded_tls_files) if reloaded_tls_files != tls_files => {
+                                tls_changed |= reloaded_tls_files.tls_paths() != tls_files.tls_paths();
+                                ca_changed |= reloaded_tls_files.ca != tls_files.ca;
+                                tls_files = reloaded_tls_files;
+                                watched_tls = WatchedFiles::new(tls_files.tls_paths());
+                                watched_ca = WatchedFiles::new([&tls_files.ca]);
+                            }
+                            Ok(_) => {}
+                            Err(cause) => error!("Failed to reload locations of TLS files. Keeping the previous ones:\n  {cause:#}"),
+                        }
+                        settings = reloaded;
+                    }
+                    Err(cause) => error!("Failed to reload configuration. Keeping the previous configuration:\n  {cause:#}"),
+                }
+            }
+
+            if tls_changed {
+                match tls_files.reload_tls_config(&tls_config).await {
+                    Ok(()) => info!("Reloaded TLS certificate and key."),
+                    Err(cause) => error!("Keeping the previous TLS certificate and key:\n  {cause:#}"),
+                }
+            }
+
+            if ca_changed {
+                match tls_files.load_ca().await {
+                    Ok(reloaded_ca) => {
+                        *ca.write().await = reloaded_ca;
+                        info!("Reloaded CA certificate.");
+                    }
+                    Err(cause) => error!("Keeping the previous CA certificate:\n  {cause:#}"),
+                }
+            }
+        }
+    });
+}
+
+async fn reload_settings(
+    settings: &LoadedConfig,
+    peer_messaging_broker: &PeerMessagingBrokerRef,
+    vpn_client: &Option<ReloadableVpnManagementClientRef>,
+) -> anyhow::Result<LoadedConfig> {
+    let reloaded = settings::load_with_overrides(Clone::clone(&settings.overrides))?;
+
+    let peer_messaging_broker_options = PeerMessagingBrokerOptions::load(&reloaded.config)?;
+    peer_messaging_broker.update_options(peer_messaging_broker_options).await;
+
+    if let Some(vpn_client) = vpn_client {
+        vpn_client.reload(&reloaded.config)
+            .context("Failed to reload VPN configuration.")?;
+    }
+
+    debug!("Reloaded configuration: {reloaded:?}");
+    Ok(reloaded)
+}
+
+/// Tracks the modification times of files, including whether they exist.
+struct WatchedFiles {
+    modification_times: HashMap<PathBuf, Option<SystemTime>>,
+}
+
+impl WatchedFiles {
+    fn new<P: AsRef<Path>>(paths: impl IntoIterator<Item=P>) -> Self {
+        let modification_times = paths.into_iter()
+            .map(|path| {
+                let path = path.as_ref().to_path_buf();
+                let modification_time = modification_time(&path);
+                (path, modification_time)
+            })
+            .collect();
+        Self { modification_times }
+    }
+
+    /// Returns whether any file was modified, created or removed since the last call.
+    fn changed(&mut self) -> bool {
+        let mut changed = false;
+        for (path, previous_modification_time) in self.modification_times.iter_mut() {
+            let modification_time = modification_time(path);
+            if modification_time != *previous_modification_time {
+                debug!("Detected change of file: {}", path.display());
+                *previous_modification_time = modification_time;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+fn modification_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[derive(Clone, Debug)]
+pub struct ReloadOptions {
+    pub interval: Option<Duration>,
+}
+impl ReloadOptions {
+    pub fn load(config: &config::Config) -> Result<Self, opendut_util::settings::LoadError> {
+        let interval = config.get::<u64>("reload.interval.ms")?;
+        let interval = (interval > 0).then(|| Duration::from_millis(interval));
+
+        Ok(ReloadOptions {
+            interval,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::prelude::*;
+    use googletest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn should_detect_modified_created_and_removed_files() -> Result<()> {
+        let temp = assert_fs::TempDir::new()?;
+        let existing = temp.child("existing.pem");
+        existing.write_str("initial")?;
+        let missing = temp.child("missing.pem");
+
+        let mut testee = WatchedFiles::new([existing.path(), missing.path()]);
+        verify_that!(testee.changed(), eq(false))?;
+
+        let file = std::fs::File::options().write(true).open(existing.path())?;
+        file.set_modified(SystemTime::now() + Duration::from_secs(60))?;
+        verify_that!(testee.changed(), eq(true))?;
+        verify_that!(testee.changed(), eq(false))?;
+
+        missing.write_str("created")?;
+        verify_that!(testee.changed(), eq(true))?;
+
+        std::fs::remove_file(missing.path())?;
+        verify_that!(testee.changed(), eq(true))
+    }
+}
@@ -1,9 +1,14 @@
 use opendut_types::cluster::{ClusterConfiguration, ClusterDeployment, ClusterId};
+use opendut_types::cluster::operation::{DeploymentOperation, DeploymentOperationId};
 use opendut_types::peer::{PeerDescriptor, PeerId};
 use opendut_types::peer::configuration::PeerConfiguration;
 use opendut_types::peer::state::PeerState;
+use opendut_types::project::{ProjectDescriptor, ProjectId};
 use opendut_types::topology::{DeviceDescriptor, DeviceId};
+use opendut_types::vpn::health::VpnHealth;
 
+use crate::cluster::ports::CanServerPortAllocation;
+use crate::project::OwningProject;
 use crate::resources::{Id, IntoId};
 
 impl IntoId<ClusterConfiguration> for ClusterId {
@@ -17,6 +22,18 @@ impl IntoId<ClusterDeployment> for ClusterId {
     }
 }
 
+impl IntoId<CanServerPortAllocation> for ClusterId {
+    fn into_id(self) -> Id {
+        Id::from(self.0)
+    }
+}
+
+impl IntoId<DeploymentOperation> for DeploymentOperationId {
+    fn into_id(self) -> Id {
+        Id::from(self.0)
+    }
+}
+
 impl IntoId<DeviceDescriptor> for DeviceId {
     fn into_id(self) -> Id {
         Id::from(self.0)
@@ -37,4 +54,25 @@ impl IntoId<PeerConfiguration> for PeerId {
     fn into_id(self) -> Id {
         Id::from(self.0)
     }
-}
\ No newline at end of file
+}
+impl IntoId<VpnHealth> for PeerId {
+    fn into_id(self) -> Id {
+        Id::from(self.0)
+    }
+}
+impl IntoId<OwningProject> for PeerId {
+    fn into_id(self) -> Id {
+        Id::from(self.0)
+    }
+}
+impl IntoId<OwningProject> for ClusterId {
+    fn into_id(self) -> Id {
+        Id::from(self.0)
+    }
+}
+
+impl IntoId<ProjectDescriptor> for ProjectId {
+    fn into_id(self) -> Id {
+        Id::from(self.0)
+    }
+}
@@ -117,7 +117,8 @@ mod test {
                     ports: vec![], 
                     command: ContainerCommand::Default, 
                     args: vec![] }],
-            }
+            },
+            revision: 0,
         };
 
         let cluster_resource_id = ClusterId::random();
@@ -126,6 +127,7 @@ mod test {
             name: ClusterName::try_from("ClusterX032").unwrap(),
             leader: peer.id,
             devices: HashSet::new(),
+            revision: 0,
         };
 
         assert_that!(testee.is_empty().await, eq(true));
@@ -0,0 +1,384 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Not;
+use std::sync::Arc;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use ring::aead::{Aad, AES_256_GCM, LessSafeKey, Nonce, NONCE_LEN, UnboundKey};
+use ring::rand::{SecureRandom, SystemRandom};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use opendut_types::peer::executor::{ContainerEnvironmentVariable, ContainerEnvironmentVariableValue, ContainerImage, ContainerName, ExecutorDescriptor, ExecutorDescriptors};
+use opendut_types::peer::PeerId;
+
+pub type SecretsManagerRef = Arc<SecretsManager>;
+
+/// Keeps the values of secret container environment variables encrypted, separately from the peer descriptors.
+///
+/// The peer descriptors stored in CARL only contain redacted secrets. The values are revealed
+/// when sending the configuration to the peer, which runs the respective container.
+pub struct SecretsManager {
+    key: LessSafeKey,
+    random: SystemRandom,
+    secrets: RwLock<HashMap<PeerId, Vec<EncryptedSecret>>>,
+}
+
+/// A secret is identified by the identity of its executor and its name.
+#[derive(Clone)]
+struct EncryptedSecret {
+    executor: ExecutorIdentity,
+    name: String,
+    nonce: [u8; NONCE_LEN],
+    ciphertext: Vec<u8>,
+}
+
+/// Encrypted secrets of a peer, which are not stored yet.
+pub struct ConcealedSecrets(Vec<EncryptedSecret>);
+
+/// Identifies a container executor independently of its position within the peer's executors,
+/// by its container name or, if it has no name, by its image.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ExecutorIdentity(String);
+
+impl ExecutorIdentity {
+    fn of(name: &ContainerName, image: &ContainerImage) -> Self {
+        match name {
+            ContainerName::Value(name) => Self(format!("name:{name}")),
+            ContainerName::Empty => Self(format!("image:{}", image.value())),
+        }
+    }
+}
+
+impl std::fmt::Display for ExecutorIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SecretsError {
+    #[error("No value known for secret environment variable '{name}' of executor '{executor}'. The value has to be specified, when the secret is created.")]
+    ValueNotFound { executor: ExecutorIdentity, name: String },
+    #[error("Executor '{executor}' occurs multiple times and therefore cannot have secret environment variables. Give the containers distinct names.")]
+    AmbiguousExecutor { executor: ExecutorIdentity },
+    #[error("Failed to encrypt secret environment variable '{name}'.")]
+    EncryptionFailed { name: String },
+    #[error("Failed to decrypt secret environment variable '{name}'.")]
+    DecryptionFailed { name: String },
+}
+
+impl SecretsManager {
+
+    pub fn new(options: SecretsManagerOptions) -> SecretsManagerRef {
+        let random = SystemRandom::new();
+
+        let key = match options.key {
+            Some(key) => key,
+            None => {
+                warn!("No key for encrypting secrets configured. Using a random key, which means secrets are lost when CARL restarts.");
+                let mut key = [0u8; KEY_LEN];
+                random.fill(&mut key)
+                    .expect("Random key for encrypting secrets should be generatable.");
+                key
+            }
+        };
+        let key = UnboundKey::new(&AES_256_GCM, &key)
+            .expect("Key for encrypting secrets should have the correct length.");
+
+        Arc::new(Self {
+            key: LessSafeKey::new(key),
+            random,
+            secrets: Default::default(),
+        })
+    }
+
+    /// Encrypts the values of all secrets of the given executors and returns the executors with their secrets redacted,
+    /// together with the encrypted secrets. These only replace the stored secrets of the peer, when passed to [`SecretsManager::store`],
+    /// which allows validating and storing the redacted executors first.
+    ///
+    /// Secrets, which are already redacted, keep their previously stored value.
+    #[tracing::instrument(skip(self, executors), level="trace")]
+    pub async fn conceal(&self, peer_id: PeerId, executors: ExecutorDescriptors) -> Result<(ExecutorDescriptors, ConcealedSecrets), SecretsError> {
+        let secrets = self.secrets.read().await;
+        let previous_secrets = secrets.get(&peer_id).map(Vec::as_slice).unwrap_or_default();
+        let mut concealed_secrets = Vec::new();
+
+        let executors = map_envs(executors, |executor, env| {
+            match env.value() {
+                ContainerEnvironmentVariableValue::Plain(_) => {}
+                ContainerEnvironmentVariableValue::Secret(value) => {
+                    concealed_secrets.push(self.encrypt(peer_id, executor, env.name(), value)?);
+                }
+                ContainerEnvironmentVariableValue::Redacted => {
+                    let previous_secret = previous_secrets.iter()
+                        .find(|secret| secret.executor == *executor && secret.name == env.name())
+                        .ok_or_else(|| SecretsError::ValueNotFound { executor: Clone::clone(executor), name: env.name().to_owned() })?;
+                    concealed_secrets.push(Clone::clone(previous_secret));
+                }
+            }
+            Ok(env.redacted())
+        })?;
+
+        Ok((executors, ConcealedSecrets(concealed_secrets)))
+    }
+
+    /// Replaces the stored secrets of the peer with the given concealed secrets.
+    pub async fn store(&self, peer_id: PeerId, secrets: ConcealedSecrets) {
+        let ConcealedSecrets(secrets) = secrets;
+        debug!("Storing {} secret(s) of peer <{peer_id}>.", secrets.len());
+
+        let mut stored_secrets = self.secrets.write().await;
+        if secrets.is_empty() {
+            stored_secrets.remove(&peer_id);
+        } else {
+            stored_secrets.insert(peer_id, secrets);
+        }
+    }
+
+    /// Returns the given executors with the values of their redacted secrets restored.
+    #[tracing::instrument(skip(self, executors), level="trace")]
+    pub async fn reveal(&self, peer_id: PeerId, executors: ExecutorDescriptors) -> Result<ExecutorDescriptors, SecretsError> {
+        let secrets = self.secrets.read().await;
+        let secrets = secrets.get(&peer_id);
+
+        map_envs(executors, |executor, env| {
+            match env.value() {
+                ContainerEnvironmentVariableValue::Redacted => {
+                    let secret = secrets
+                        .and_then(|secrets| secrets.iter().find(|secret| secret.executor == *executor && secret.name == env.name()))
+                        .ok_or_else(|| SecretsError::ValueNotFound { executor: Clone::clone(executor), name: env.name().to_owned() })?;
+                    let value = self.decrypt(peer_id, secret)?;
+                    ContainerEnvironmentVariable::new_secret(env.name(), value)
+                        .map_err(|_| SecretsError::DecryptionFailed { name: env.name().to_owned() })
+                }
+                _ => Ok(env),
+            }
+        })
+    }
+
+    /// Removes the redacted secrets from the given executors, whose values are not stored for the peer,
+    /// e.g. for peers imported from another instance of CARL. Returns the removed secrets as `<executor>/<name>`.
+    pub async fn remove_unknown(&self, peer_id: PeerId, executors: ExecutorDescriptors) -> (ExecutorDescriptors, Vec<String>) {
+        let secrets = self.secrets.read().await;
+        let secrets = secrets.get(&peer_id).map(Vec::as_slice).unwrap_or_default();
+        let mut removed = Vec::new();
+
+        let executors = executors.executors.into_iter()
+            .map(|executor| match executor {
+                ExecutorDescriptor::Container { engine, name, image, volumes, devices, envs, ports, command, args } => {
+                    let identity = ExecutorIdentity::of(&name, &image);
+                    let envs = envs.into_iter()
+                        .filter(|env| {
+                            let is_unknown = matches!(env.value(), ContainerEnvironmentVariableValue::Redacted)
+                                && secrets.iter().any(|secret| secret.executor == identity && secret.name == env.name()).not();
+                            if is_unknown {
+                                removed.push(format!("{identity}/{}", env.name()));
+                            }
+                            is_unknown.not()
+                        })
+                        .collect();
+                    ExecutorDescriptor::Container { engine, name, image, volumes, devices, envs, ports, command, args }
+                }
+                executor => executor,
+            })
+            .collect();
+
+        (ExecutorDescriptors { executors }, removed)
+    }
+
+    pub async fn remove(&self, peer_id: PeerId) {
+        self.secrets.write().await.remove(&peer_id);
+    }
+
+    fn encrypt(&self, peer_id: PeerId, executor: &ExecutorIdentity, name: &str, value: &str) -> Result<EncryptedSecret, SecretsError> {
+        let error = || SecretsError::EncryptionFailed { name: name.to_owned() };
+
+        let mut nonce = [0u8; NONCE_LEN];
+        self.random.fill(&mut nonce).map_err(|_| error())?;
+
+        let mut ciphertext = value.as_bytes().to_vec();
+        self.key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), associated_data(peer_id, executor, name), &mut ciphertext)
+            .map_err(|_| error())?;
+
+        Ok(EncryptedSecret {
+            executor: Clone::clone(executor),
+            name: name.to_owned(),
+            nonce,
+            ciphertext,
+        })
+    }
+
+    fn decrypt(&self, peer_id: PeerId, secret: &EncryptedSecret) -> Result<String, SecretsError> {
+        let error = || SecretsError::DecryptionFailed { name: Clone::clone(&secret.name) };
+
+        let mut buffer = Clone::clone(&secret.ciphertext);
+        let value = self.key.open_in_place(Nonce::assume_unique_for_key(secret.nonce), associated_data(peer_id, &secret.executor, &secret.name), &mut buffer)
+            .map_err(|_| error())?;
+
+        String::from_utf8(value.to_vec()).map_err(|_| error())
+    }
+}
+
+/// Binds a ciphertext to its peer, executor and name, so that it cannot be moved to another secret.
+/// The executor and name are prefixed with their length, so that their boundary is unambiguous.
+fn associated_data(peer_id: PeerId, executor: &ExecutorIdentity, name: &str) -> Aad<Vec<u8>> {
+    let mut data = peer_id.0.as_bytes().to_vec();
+    for field in [executor.0.as_str(), name] {
+        data.extend_from_slice(&(field.len() as u64).to_be_bytes());
+        data.extend_from_slice(field.as_bytes());
+    }
+    Aad::from(data)
+}
+
+fn map_envs<F>(executors: ExecutorDescriptors, mut f: F) -> Result<ExecutorDescriptors, SecretsError>
+where F: FnMut(&ExecutorIdentity, ContainerEnvironmentVariable) -> Result<ContainerEnvironmentVariable, SecretsError> {
+    let mut identities_with_secrets = HashSet::new();
+    let executors = executors.executors.into_iter()
+        .map(|executor| match executor {
+            ExecutorDescriptor::Container { engine, name, image, volumes, devices, envs, ports, command, args } => {
+                let identity = ExecutorIdentity::of(&name, &image);
+                let has_secrets = envs.iter().any(|env| matches!(env.value(), ContainerEnvironmentVariableValue::Plain(_)).not());
+                if has_secrets && identities_with_secrets.insert(Clone::clone(&identity)).not() {
+                    return Err(SecretsError::AmbiguousExecutor { executor: identity });
+                }
+                let envs = envs.into_iter()
+                    .map(|env| f(&identity, env))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(ExecutorDescriptor::Container { engine, name, image, volumes, devices, envs, ports, command, args })
+            }
+            executor => Ok(executor),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ExecutorDescriptors { executors })
+}
+
+const KEY_LEN: usize = 32;
+
+#[derive(Clone)]
+pub struct SecretsManagerOptions {
+    pub key: Option<[u8; KEY_LEN]>,
+}
+impl SecretsManagerOptions {
+    pub fn load(config: &config::Config) -> Result<Self, opendut_util::settings::LoadError> {
+        let key = config.get_string("secrets.key")?;
+        let key = if key.trim().is_empty() {
+            None
+        } else {
+            let key = STANDARD.decode(key.trim())
+                .ok()
+                .and_then(|key| <[u8; KEY_LEN]>::try_from(key).ok())
+                .ok_or_else(|| config::ConfigError::Message(format!("Configuration value 'secrets.key' must be a base64-encoded key of {KEY_LEN} bytes.")))?;
+            Some(key)
+        };
+
+        Ok(SecretsManagerOptions {
+            key,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+
+    use opendut_types::peer::executor::{ContainerCommand, Engine};
+
+    use super::*;
+
+    fn container(name: ContainerName, envs: Vec<ContainerEnvironmentVariable>) -> ExecutorDescriptor {
+        ExecutorDescriptor::Container {
+            engine: Engine::Docker,
+            name,
+            image: ContainerImage::try_from("testUrl").unwrap(),
+            volumes: vec![],
+            devices: vec![],
+            envs,
+            ports: vec![],
+            command: ContainerCommand::Default,
+            args: vec![],
+        }
+    }
+
+    fn executors(envs: Vec<ContainerEnvironmentVariable>) -> ExecutorDescriptors {
+        ExecutorDescriptors {
+            executors: vec![container(ContainerName::Empty, envs)]
+        }
+    }
+
+    async fn conceal_and_store(testee: &SecretsManager, peer_id: PeerId, executors: ExecutorDescriptors) -> std::result::Result<ExecutorDescriptors, SecretsError> {
+        let (executors, secrets) = testee.conceal(peer_id, executors).await?;
+        testee.store(peer_id, secrets).await;
+        Ok(executors)
+    }
+
+    #[tokio::test]
+    async fn should_conceal_and_reveal_secrets() -> Result<()> {
+        let testee = SecretsManager::new(SecretsManagerOptions { key: None });
+        let peer_id = PeerId::random();
+
+        let plain = ContainerEnvironmentVariable::new("PLAIN", "value")?;
+        let secret = ContainerEnvironmentVariable::new_secret("SECRET", "hunter2")?;
+
+        let concealed = conceal_and_store(&testee, peer_id, executors(vec![Clone::clone(&plain), Clone::clone(&secret)])).await?;
+        verify_that!(concealed, eq(executors(vec![Clone::clone(&plain), Clone::clone(&secret).redacted()])))?;
+
+        let revealed = testee.reveal(peer_id, Clone::clone(&concealed)).await?;
+        verify_that!(revealed, eq(executors(vec![Clone::clone(&plain), Clone::clone(&secret)])))?;
+
+        let concealed_again = conceal_and_store(&testee, peer_id, concealed).await?;
+        let revealed = testee.reveal(peer_id, concealed_again).await?;
+        verify_that!(revealed, eq(executors(vec![plain, secret])))?;
+
+        let revealed_for_other_peer = testee.reveal(PeerId::random(), executors(vec![ContainerEnvironmentVariable::new_secret("SECRET", "")?.redacted()])).await;
+        verify_that!(revealed_for_other_peer, err(anything()))
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_conceal_redacted_secret_without_known_value() -> Result<()> {
+        let testee = SecretsManager::new(SecretsManagerOptions { key: None });
+
+        let redacted = ContainerEnvironmentVariable::new_secret("SECRET", "")?.redacted();
+
+        let result = testee.conceal(PeerId::random(), executors(vec![redacted])).await;
+        verify_that!(result.map(|(executors, _)| executors), err(displays_as(contains_substring("No value known for secret environment variable 'SECRET'"))))
+    }
+
+    #[tokio::test]
+    async fn should_only_replace_stored_secrets_when_storing_them() -> Result<()> {
+        let testee = SecretsManager::new(SecretsManagerOptions { key: None });
+        let peer_id = PeerId::random();
+
+        let secret = ContainerEnvironmentVariable::new_secret("SECRET", "hunter2")?;
+        let concealed = conceal_and_store(&testee, peer_id, executors(vec![Clone::clone(&secret)])).await?;
+
+        let _discarded = testee.conceal(peer_id, executors(vec![ContainerEnvironmentVariable::new_secret("SECRET", "changed")?])).await?;
+
+        verify_that!(testee.reveal(peer_id, concealed).await, ok(eq(executors(vec![secret]))))
+    }
+
+    #[tokio::test]
+    async fn should_keep_secrets_of_executors_when_reordering_them() -> Result<()> {
+        let testee = SecretsManager::new(SecretsManagerOptions { key: None });
+        let peer_id = PeerId::random();
+
+        let first = container(ContainerName::Value(String::from("first")), vec![ContainerEnvironmentVariable::new_secret("SECRET", "one")?]);
+        let second = container(ContainerName::Value(String::from("second")), vec![ContainerEnvironmentVariable::new_secret("SECRET", "two")?]);
+
+        let concealed = conceal_and_store(&testee, peer_id, ExecutorDescriptors { executors: vec![Clone::clone(&first), Clone::clone(&second)] }).await?;
+        let reordered = ExecutorDescriptors { executors: concealed.executors.into_iter().rev().collect() };
+
+        let revealed = testee.reveal(peer_id, reordered).await?;
+        verify_that!(revealed, eq(ExecutorDescriptors { executors: vec![second, first] }))
+    }
+
+    #[tokio::test]
+    async fn should_reject_secrets_of_indistinguishable_executors() -> Result<()> {
+        let testee = SecretsManager::new(SecretsManagerOptions { key: None });
+
+        let unnamed = container(ContainerName::Empty, vec![ContainerEnvironmentVariable::new_secret("SECRET", "hunter2")?]);
+
+        let result = testee.conceal(PeerId::random(), ExecutorDescriptors { executors: vec![Clone::clone(&unnamed), unnamed] }).await;
+        verify_that!(result.map(|(executors, _)| executors), err(matches_pattern!(SecretsError::AmbiguousExecutor { .. })))
+    }
+}
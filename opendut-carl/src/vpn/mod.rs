@@ -1,31 +1,91 @@
+use std::ops::Not;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{anyhow, bail};
+use async_trait::async_trait;
 use config::Config;
+use ipnet::Ipv4Net;
 use serde::{Deserialize, Serialize};
 use serde::de::IntoDeserializer;
+use tokio::sync::{OwnedRwLockReadGuard, OwnedRwLockWriteGuard};
 use tracing::debug;
 use url::Url;
 
-use opendut_vpn::VpnManagementClient;
+use opendut_types::cluster::ClusterId;
+use opendut_types::peer::PeerId;
+use opendut_types::project::ProjectId;
+use opendut_types::util::Port;
+use opendut_types::vpn::reconciliation::VpnReconciliationReport;
+use opendut_types::vpn::VpnPeerConfiguration;
+use opendut_vpn::{ClusterNetworkPolicy, CreateClusterError, CreatePeerError, CreateVpnPeerConfigurationError, DeleteClusterError, DeletePeerError, ExpectedVpnState, NetworkProtocol, ReconcileError, VpnManagementClient, WireGuardTunnel};
+use opendut_vpn_loopback::{LoopbackManagementClient, LoopbackManagementClientConfiguration};
 use opendut_vpn_netbird::{NetbirdManagementClient, NetbirdManagementClientConfiguration, NetbirdToken};
+use opendut_vpn_wireguard::{WireGuardManagementClient, WireGuardManagementClientConfiguration};
+
+pub mod reconciler;
 
 #[derive(Clone)]
 pub enum Vpn {
-    Enabled { vpn_client: Arc<dyn VpnManagementClient + Send + Sync> },
+    Enabled {
+        vpn_client: Arc<dyn VpnManagementClient + Send + Sync>,
+        /// Orders the changes CARL applies to the VPN against the reconciliation of the VPN.
+        changes: Arc<tokio::sync::RwLock<()>>,
+    },
     Disabled,
 }
 
-pub fn create(settings: &Config) -> anyhow::Result<Vpn> {
+impl Vpn {
+    /// Held while peers or cluster deployments are changed together with the VPN, so the reconciliation
+    /// never observes the change in CARL without the change in the VPN or vice versa.
+    /// Changes may run concurrently, but must not acquire this lock again while holding it.
+    pub async fn lock_change(&self) -> Option<OwnedRwLockReadGuard<()>> {
+        match self {
+            Vpn::Enabled { changes, .. } => Some(Arc::clone(changes).read_owned().await),
+            Vpn::Disabled => None,
+        }
+    }
+
+    /// Held while the VPN is reconciled. Waits for running changes to finish and blocks new ones until released.
+    pub async fn lock_reconciliation(&self) -> Option<OwnedRwLockWriteGuard<()>> {
+        match self {
+            Vpn::Enabled { changes, .. } => Some(Arc::clone(changes).write_owned().await),
+            Vpn::Disabled => None,
+        }
+    }
+}
+
+/// Creates the VPN and, if it is enabled, a handle to replace its management client when the configuration changes.
+pub fn create(settings: &Config) -> anyhow::Result<(Vpn, Option<ReloadableVpnManagementClientRef>)> {
+    match create_client(settings)? {
+        Some((kind, vpn_client)) => {
+            let vpn_client = Arc::new(ReloadableVpnManagementClient {
+                kind,
+                inner: std::sync::RwLock::new(vpn_client),
+            });
+            let vpn = Vpn::Enabled {
+                vpn_client: Clone::clone(&vpn_client) as Arc<dyn VpnManagementClient + Send + Sync>,
+                changes: Default::default(),
+            };
+            Ok((vpn, Some(vpn_client)))
+        }
+        None => Ok((Vpn::Disabled, None)),
+    }
+}
+
+fn create_client(settings: &Config) -> anyhow::Result<Option<(VpnKind, Arc<dyn VpnManagementClient + Send + Sync>)>> {
 
     let vpn = settings.get::<VpnConfig>("vpn")?;
 
     if vpn.enabled {
-        match vpn.kind {
-            None => unknown_enum_variant(settings, "vpn.kind"),
-            Some(VpnKind::Netbird) => {
+        let kind = match vpn.kind {
+            None => return unknown_enum_variant(settings, "vpn.kind"),
+            Some(kind) => kind,
+        };
+        let vpn_client: Arc<dyn VpnManagementClient + Send + Sync> = match kind {
+            VpnKind::Netbird => {
                 let netbird_config = settings.get::<VpnNetbirdConfig>("vpn.netbird")?;
                 let base_url = netbird_config.url
                     .ok_or_else(|| anyhow!("No configuration found for: vpn.netbird.base.url"))?;
@@ -48,15 +108,118 @@ pub fn create(settings: &Config) -> anyhow::Result<Vpn> {
                         retries: netbird_config.retries,
                     }
                 )?;
-                Ok(Vpn::Enabled { vpn_client: Arc::new(vpn_client) })
+                Arc::new(vpn_client)
             }
-        }
+            VpnKind::Wireguard => {
+                let wireguard_config = settings.get::<VpnWireGuardConfig>("vpn.wireguard")?;
+                let network = wireguard_config.network
+                    .ok_or_else(|| anyhow!("No configuration found for: vpn.wireguard.network"))?;
+                let network = Ipv4Net::from_str(&network)
+                    .map_err(|cause| anyhow!("Configuration value 'vpn.wireguard.network' must be an IPv4 network in CIDR notation: {cause}"))?;
+                let vpn_client = WireGuardManagementClient::create(
+                    WireGuardManagementClientConfiguration {
+                        network,
+                        listen_port: Port(wireguard_config.listen.port),
+                    }
+                );
+                Arc::new(vpn_client)
+            }
+            VpnKind::Loopback => {
+                let loopback_config = settings.get::<VpnLoopbackConfig>("vpn.loopback")?;
+                let network = loopback_config.network
+                    .ok_or_else(|| anyhow!("No configuration found for: vpn.loopback.network"))?;
+                let network = Ipv4Net::from_str(&network)
+                    .map_err(|cause| anyhow!("Configuration value 'vpn.loopback.network' must be an IPv4 network in CIDR notation: {cause}"))?;
+                let vpn_client = LoopbackManagementClient::create(
+                    LoopbackManagementClientConfiguration {
+                        network,
+                        bridge_name: loopback_config.bridge.name,
+                    }
+                );
+                Arc::new(vpn_client)
+            }
+        };
+        Ok(Some((kind, vpn_client)))
     } else {
-        Ok(Vpn::Disabled)
+        Ok(None)
     }
 }
 
-fn unknown_enum_variant(settings: &Config, key: &str) -> anyhow::Result<Vpn> {
+pub type ReloadableVpnManagementClientRef = Arc<ReloadableVpnManagementClient>;
+
+/// Delegates to a VPN management client, which can be replaced, e.g. when the credentials change.
+pub struct ReloadableVpnManagementClient {
+    kind: VpnKind,
+    inner: std::sync::RwLock<Arc<dyn VpnManagementClient + Send + Sync>>,
+}
+
+impl ReloadableVpnManagementClient {
+
+    /// Replaces the management client with one created from the given settings.
+    /// Disabling the VPN or changing its kind requires a restart.
+    pub fn reload(&self, settings: &Config) -> anyhow::Result<()> {
+        let vpn = settings.get::<VpnConfig>("vpn")?;
+        if vpn.enabled.not() {
+            bail!("Disabling the VPN requires a restart of CARL.");
+        }
+        match vpn.kind {
+            None => return unknown_enum_variant(settings, "vpn.kind"),
+            Some(kind) if kind != self.kind => bail!("Changing the kind of the VPN from {:?} to {kind:?} requires a restart of CARL.", self.kind),
+            Some(_) => {}
+        }
+        if let kind @ (VpnKind::Wireguard | VpnKind::Loopback) = self.kind {
+            // The keys and addresses of the peers only exist in memory and must not be regenerated.
+            debug!("Not reloading the {kind:?} VPN. Changes to its configuration require a restart of CARL.");
+            return Ok(());
+        }
+        let (_, vpn_client) = create_client(settings)?
+            .ok_or_else(|| anyhow!("Disabling the VPN requires a restart of CARL."))?;
+        *self.inner.write().expect("Lock of VPN management client should not be poisoned.") = vpn_client;
+        Ok(())
+    }
+
+    fn current(&self) -> Arc<dyn VpnManagementClient + Send + Sync> {
+        Arc::clone(&self.inner.read().expect("Lock of VPN management client should not be poisoned."))
+    }
+}
+
+#[async_trait]
+impl VpnManagementClient for ReloadableVpnManagementClient {
+
+    async fn create_cluster(&self, project_id: ProjectId, cluster_id: ClusterId, peers: &[PeerId], policy: &ClusterNetworkPolicy) -> Result<(), CreateClusterError> {
+        self.current().create_cluster(project_id, cluster_id, peers, policy).await
+    }
+
+    async fn delete_cluster(&self, project_id: ProjectId, cluster_id: ClusterId) -> Result<(), DeleteClusterError> {
+        self.current().delete_cluster(project_id, cluster_id).await
+    }
+
+    async fn create_peer(&self, peer_id: PeerId) -> Result<(), CreatePeerError> {
+        self.current().create_peer(peer_id).await
+    }
+
+    async fn delete_peer(&self, peer_id: PeerId) -> Result<(), DeletePeerError> {
+        self.current().delete_peer(peer_id).await
+    }
+
+    async fn generate_vpn_peer_configuration(&self, peer_id: PeerId) -> Result<VpnPeerConfiguration, CreateVpnPeerConfigurationError> {
+        self.current().generate_vpn_peer_configuration(peer_id).await
+    }
+
+    fn unsupported_protocols(&self) -> Vec<NetworkProtocol> {
+        self.current().unsupported_protocols()
+    }
+
+    async fn wireguard_tunnel(&self, peer_id: PeerId) -> Option<WireGuardTunnel> {
+        self.current().wireguard_tunnel(peer_id).await
+    }
+
+    async fn reconcile(&self, expected: &ExpectedVpnState, dry_run: bool) -> Result<VpnReconciliationReport, ReconcileError> {
+        self.current().reconcile(expected, dry_run).await
+    }
+}
+
+fn unknown_enum_variant<T>(settings: &Config, key: &str) -> anyhow::Result<T> {
     let value = settings.get_string(key)?;
     if value.trim().is_empty() {
         bail!("No configuration found for: {key}")
@@ -73,10 +236,12 @@ struct VpnConfig {
     kind: Option<VpnKind>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all="kebab-case")]
 enum VpnKind {
     Netbird,
+    Wireguard,
+    Loopback,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -91,6 +256,34 @@ struct VpnNetbirdConfig {
     retries: u32,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all="kebab-case")]
+struct VpnWireGuardConfig {
+    #[serde(deserialize_with = "empty_string_as_none")]
+    network: Option<String>,
+    listen: Listen,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all="kebab-case")]
+struct Listen {
+    port: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all="kebab-case")]
+struct VpnLoopbackConfig {
+    #[serde(deserialize_with = "empty_string_as_none")]
+    network: Option<String>,
+    bridge: Bridge,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all="kebab-case")]
+struct Bridge {
+    name: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all="kebab-case")]
 struct Timeout {
@@ -125,3 +318,35 @@ where
         Some(string) => T::deserialize(string.into_deserializer()).map(Some)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+
+    use crate::settings;
+
+    use super::*;
+
+    fn settings_with(overrides: &[(&str, &str)]) -> Config {
+        let overrides = overrides.iter()
+            .fold(Config::builder().set_override("vpn.enabled", true).unwrap(), |builder, (key, value)| builder.set_override(*key, *value).unwrap())
+            .build().unwrap();
+        settings::load_with_overrides(overrides).unwrap().config
+    }
+
+    #[test]
+    fn should_reject_reloading_a_different_kind_of_vpn() -> Result<()> {
+        let (_, vpn_client) = create(&settings_with(&[("vpn.kind", "loopback")])).unwrap();
+        let vpn_client = vpn_client.unwrap();
+
+        verify_that!(vpn_client.reload(&settings_with(&[("vpn.kind", "loopback")])), ok(anything()))?;
+        verify_that!(
+            vpn_client.reload(&settings_with(&[("vpn.kind", "netbird")])),
+            err(displays_as(eq("Changing the kind of the VPN from Loopback to Netbird requires a restart of CARL.")))
+        )?;
+        verify_that!(
+            vpn_client.reload(&settings_with(&[("vpn.kind", "loopback"), ("vpn.enabled", "false")])),
+            err(displays_as(starts_with("Disabling the VPN")))
+        )
+    }
+}
@@ -0,0 +1,278 @@
+use std::ops::Not;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{debug, error, info, warn};
+
+use opendut_carl_api::carl::vpn::ReconcileVpnError;
+use opendut_types::cluster::{ClusterConfiguration, ClusterDeployment};
+use opendut_types::peer::PeerDescriptor;
+use opendut_types::vpn::reconciliation::{VpnDriftOutcome, VpnReconciliationReport};
+use opendut_vpn::{ClusterNetworkPolicy, ExpectedCluster, ExpectedVpnState};
+
+use crate::cluster::manager::determine_member_interface_mapping;
+use crate::cluster::ports::CanServerPortAllocation;
+use crate::project;
+use crate::resources::manager::ResourcesManagerRef;
+use crate::resources::Resources;
+use crate::vpn::Vpn;
+
+pub type VpnReconcilerRef = Arc<VpnReconciler>;
+
+/// Detects and repairs drift between the VPN and the peers and cluster deployments known to CARL,
+/// e.g. groups left behind in NetBird when CARL crashed while deploying a cluster.
+pub struct VpnReconciler {
+    resources_manager: ResourcesManagerRef,
+    vpn: Vpn,
+}
+
+impl VpnReconciler {
+
+    pub fn new(resources_manager: ResourcesManagerRef, vpn: Vpn) -> VpnReconcilerRef {
+        Arc::new(Self {
+            resources_manager,
+            vpn,
+        })
+    }
+
+    #[tracing::instrument(skip(self), level="trace")]
+    pub async fn reconcile(&self, dry_run: bool) -> Result<VpnReconciliationReport, ReconcileVpnError> {
+        let Vpn::Enabled { vpn_client, .. } = &self.vpn else {
+            return Err(ReconcileVpnError::VpnDisabled);
+        };
+
+        // Peers and cluster deployments are only changed together with the VPN while holding this lock,
+        // so the expected state determined below stays valid until the repairs are done.
+        // This also prevents a periodic and a requested reconciliation from repairing the same drift concurrently.
+        let _reconciliation = self.vpn.lock_reconciliation().await;
+
+        let expected = self.resources_manager.resources(expected_state).await;
+
+        vpn_client.reconcile(&expected, dry_run).await
+            .map_err(|cause| ReconcileVpnError::Internal { cause: cause.to_string() })
+    }
+
+    /// Periodically reconciles the VPN in the background, unless disabled in the options.
+    pub fn spawn(self: &Arc<Self>, options: VpnReconcilerOptions) {
+        let Some(interval) = options.interval else {
+            info!("Periodic reconciliation of the VPN is disabled.");
+            return;
+        };
+        if let Vpn::Disabled = self.vpn {
+            return;
+        }
+
+        let reconciler = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.tick().await; // the first tick completes immediately
+
+            loop {
+                interval.tick().await;
+
+                match reconciler.reconcile(false).await {
+                    Ok(report) if report.is_in_sync() => debug!("VPN is in sync with CARL."),
+                    Ok(report) => {
+                        let failed = report.drifts.iter()
+                            .filter(|drift| matches!(drift.outcome, VpnDriftOutcome::Failed { .. }))
+                            .count();
+                        if failed == 0 {
+                            info!("Repaired {} drifts of the VPN.", report.drifts.len());
+                        } else {
+                            warn!("Found {} drifts of the VPN, of which {failed} could not be repaired.", report.drifts.len());
+                        }
+                    }
+                    Err(cause) => error!("Failed to reconcile the VPN:\n  {cause}"),
+                }
+            }
+        });
+    }
+}
+
+/// Determines the peers and deployed clusters, which are expected to exist in the VPN.
+/// The members and network policy of a cluster are derived from the CAN server ports allocated, when it was deployed.
+/// Deployments, which were not yet deployed, are expected without a policy, so their resources in the VPN are kept, but not checked.
+/// Those, whose members cannot be determined either, are skipped, as they could not have been created in the VPN.
+fn expected_state(resources: &Resources) -> ExpectedVpnState {
+    let mut expected = ExpectedVpnState {
+        peers: resources.iter::<PeerDescriptor>()
+            .map(|peer| peer.id)
+            .collect(),
+        ..Default::default()
+    };
+
+    for deployment in resources.iter::<ClusterDeployment>() {
+        let cluster_id = deployment.id;
+        let Some(configuration) = resources.get::<ClusterConfiguration>(cluster_id) else {
+            continue;
+        };
+        let project_id = project::project_of_resource(resources, cluster_id);
+
+        let expected_cluster = match resources.get::<CanServerPortAllocation>(cluster_id) {
+            Some(allocation) => {
+                let mut peers = allocation.ports.iter()
+                    .map(|(peer_id, _)| *peer_id)
+                    .collect::<Vec<_>>();
+                peers.sort_by_key(|peer_id| peer_id.0);
+                let policy = ClusterNetworkPolicy::for_members(allocation.leader, &allocation.ports);
+                ExpectedCluster { peers, policy: Some(policy) }
+            }
+            None => {
+                let project_peers = resources.iter::<PeerDescriptor>()
+                    .filter(|peer| project::is_owned_by(resources, peer.id, project_id))
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                match determine_member_interface_mapping(configuration.devices, project_peers, configuration.leader) {
+                    Ok(members) => {
+                        let mut peers = members.into_keys().collect::<Vec<_>>();
+                        peers.sort_by_key(|peer_id| peer_id.0);
+                        ExpectedCluster { peers, policy: None }
+                    }
+                    Err(cause) => {
+                        debug!("Not expecting deployed cluster <{cluster_id}> in the VPN, because its members could not be determined: {cause}");
+                        continue;
+                    }
+                }
+            }
+        };
+        expected.clusters.insert((project_id, cluster_id), expected_cluster);
+    }
+    expected
+}
+
+#[derive(Clone, Debug)]
+pub struct VpnReconcilerOptions {
+    pub interval: Option<Duration>,
+}
+
+impl VpnReconcilerOptions {
+    pub fn load(config: &config::Config) -> Result<Self, opendut_util::settings::LoadError> {
+        let interval = config.get::<u64>("vpn.reconcile.interval.ms")?;
+
+        Ok(VpnReconcilerOptions {
+            interval: Some(Duration::from_millis(interval)).filter(|interval| interval.is_zero().not()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use googletest::prelude::*;
+
+    use opendut_types::cluster::{ClusterId, ClusterName};
+    use opendut_types::peer::{PeerId, PeerLocation, PeerName, PeerNetworkConfiguration};
+    use opendut_types::peer::configuration::PeerConfiguration;
+    use opendut_types::peer::executor::ExecutorDescriptors;
+    use opendut_types::project::ProjectId;
+    use opendut_types::topology::Topology;
+    use opendut_types::util::Port;
+    use opendut_vpn_loopback::{LoopbackManagementClient, LoopbackManagementClientConfiguration};
+
+    use crate::project::OwningProject;
+    use crate::resources::manager::ResourcesManager;
+
+    use super::*;
+
+    fn peer(id: PeerId) -> PeerDescriptor {
+        PeerDescriptor {
+            id,
+            name: PeerName::try_from("peer").unwrap(),
+            location: PeerLocation::try_from("Ulm").ok(),
+            network_configuration: PeerNetworkConfiguration { interfaces: vec![] },
+            topology: Topology::default(),
+            executors: ExecutorDescriptors { executors: vec![] },
+            revision: 0,
+        }
+    }
+
+    fn cluster(id: ClusterId, leader: PeerId) -> ClusterConfiguration {
+        ClusterConfiguration {
+            id,
+            name: ClusterName::try_from("cluster").unwrap(),
+            leader,
+            devices: HashSet::new(),
+            revision: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_expect_all_peers_and_the_deployed_clusters_with_their_project() -> Result<()> {
+        let resources_manager = ResourcesManager::new();
+        let project_id = ProjectId::random();
+        let peer_a = PeerId::random();
+        let peer_b = PeerId::random();
+        let deployed_cluster = ClusterId::random();
+        let pending_cluster = ClusterId::random();
+        let undeployed_cluster = ClusterId::random();
+        let can_server_ports = vec![(peer_a, Port(10000)), (peer_b, Port(10001))];
+
+        resources_manager.resources_mut(|resources| {
+            resources.insert(peer_a, peer(peer_a));
+            resources.insert(peer_b, peer(peer_b));
+            resources.insert(peer_b, OwningProject(project_id));
+            resources.insert(deployed_cluster, cluster(deployed_cluster, peer_b));
+            resources.insert(deployed_cluster, OwningProject(project_id));
+            resources.insert(deployed_cluster, ClusterDeployment::new(deployed_cluster));
+            resources.insert(deployed_cluster, CanServerPortAllocation { cluster_id: deployed_cluster, leader: peer_b, ports: Clone::clone(&can_server_ports) });
+            resources.insert(pending_cluster, cluster(pending_cluster, peer_b));
+            resources.insert(pending_cluster, OwningProject(project_id));
+            resources.insert(pending_cluster, ClusterDeployment::new(pending_cluster));
+            resources.insert(undeployed_cluster, cluster(undeployed_cluster, peer_a));
+            // Storing a peer descriptor resets the cluster assignment of the peer, which must not affect the expected policy.
+            resources.insert(peer_a, PeerConfiguration {
+                executors: ExecutorDescriptors { executors: vec![] },
+                cluster_assignment: None,
+            });
+        }).await;
+
+        let expected = resources_manager.resources(expected_state).await;
+
+        let mut deployed_peers = vec![peer_a, peer_b];
+        deployed_peers.sort_by_key(|peer_id| peer_id.0);
+
+        verify_that!(expected.peers, eq(HashSet::from([peer_a, peer_b])))?;
+        verify_that!(expected.clusters.len(), eq(2))?;
+        verify_that!(expected.clusters.get(&(project_id, deployed_cluster)), some(eq(&ExpectedCluster {
+            peers: deployed_peers,
+            policy: Some(ClusterNetworkPolicy::for_members(peer_b, &can_server_ports)),
+        })))?;
+        verify_that!(expected.clusters.get(&(project_id, pending_cluster)), some(eq(&ExpectedCluster {
+            peers: vec![peer_b],
+            policy: None,
+        })))
+    }
+
+    #[tokio::test]
+    async fn should_wait_for_running_changes_of_the_vpn_before_reconciling() -> Result<()> {
+        let vpn = Vpn::Enabled {
+            vpn_client: Arc::new(LoopbackManagementClient::create(LoopbackManagementClientConfiguration {
+                network: "10.0.0.0/24".parse().unwrap(),
+                bridge_name: String::from("br-opendut"),
+            })),
+            changes: Default::default(),
+        };
+        let testee = VpnReconciler::new(ResourcesManager::new(), Clone::clone(&vpn));
+
+        let change = vpn.lock_change().await;
+        let reconciliation = tokio::spawn({
+            let testee = Arc::clone(&testee);
+            async move { testee.reconcile(true).await }
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        verify_that!(reconciliation.is_finished(), eq(false))?;
+
+        drop(change);
+        verify_that!(reconciliation.await.unwrap(), ok(anything()))
+    }
+
+    #[tokio::test]
+    async fn should_refuse_to_reconcile_a_disabled_vpn() -> Result<()> {
+        let testee = VpnReconciler::new(ResourcesManager::new(), Vpn::Disabled);
+
+        verify_that!(testee.reconcile(true).await, err(matches_pattern!(ReconcileVpnError::VpnDisabled)))
+    }
+}
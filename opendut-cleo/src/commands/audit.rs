@@ -0,0 +1,69 @@
+pub mod list {
+    use cli_table::{print_stdout, Table, WithTitle};
+    use uuid::Uuid;
+
+    use opendut_carl_api::carl::audit::AuditRecordFilter;
+    use opendut_carl_api::carl::CarlClient;
+
+    use crate::ListOutputFormat;
+
+    #[derive(Table)]
+    struct AuditRecordTable {
+        #[table(title = "Timestamp")]
+        timestamp: String,
+        #[table(title = "Identity")]
+        identity: String,
+        #[table(title = "Client")]
+        client_address: String,
+        #[table(title = "Action")]
+        action: String,
+        #[table(title = "ResourceID")]
+        resource_id: Uuid,
+        #[table(title = "Outcome")]
+        outcome: String,
+        #[table(title = "Changes")]
+        changes: String,
+    }
+
+    pub async fn execute(carl: &mut CarlClient, resource_id: Option<Uuid>, identity: Option<String>, limit: Option<u32>, output: ListOutputFormat) -> crate::Result<()> {
+
+        let filter = AuditRecordFilter { resource_id, identity, limit, ..Default::default() };
+
+        let records = carl.audit.list_audit_records(filter).await
+            .map_err(|error| format!("Error while listing audit records: {}", error))?;
+
+        match output {
+            ListOutputFormat::Table => {
+                let audit_table = records.into_iter()
+                    .map(|record| {
+                        let changes = record.changes().into_iter()
+                            .map(|change| change.to_string())
+                            .collect::<Vec<_>>()
+                            .join("\n");
+                        AuditRecordTable {
+                            timestamp: record.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+                            identity: record.identity.to_string(),
+                            client_address: record.client_address.map(|address| address.to_string()).unwrap_or_default(),
+                            action: record.action.to_string(),
+                            resource_id: record.resource_id,
+                            outcome: record.outcome.to_string(),
+                            changes,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                print_stdout(audit_table.with_title())
+                    .expect("List of audit records should be printable as table.");
+            }
+            ListOutputFormat::Json => {
+                let json = serde_json::to_string(&records).unwrap();
+                println!("{}", json);
+            }
+            ListOutputFormat::PrettyJson => {
+                let json = serde_json::to_string_pretty(&records).unwrap();
+                println!("{}", json);
+            }
+        }
+
+        Ok(())
+    }
+}
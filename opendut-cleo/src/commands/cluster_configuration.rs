@@ -7,8 +7,12 @@ pub mod create {
     use opendut_types::cluster::{ClusterConfiguration, ClusterId, ClusterName};
     use opendut_types::peer::PeerId;
     use opendut_types::topology::{DeviceDescriptor, DeviceName};
+    use opendut_types::topology::query::DeviceQuery;
     use crate::CreateOutputFormat;
+    use crate::resolve;
+    use crate::resolve::{Candidate, IdPrefix, ResourceKind};
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn execute(
         carl: &mut CarlClient,
         name: String,
@@ -16,6 +20,7 @@ pub mod create {
         leader_id: Uuid,
         device_names: Vec<DeviceName>,
         device_ids: Vec<String>,
+        device_query: Option<DeviceQuery>,
         output: CreateOutputFormat,
     ) -> crate::Result<()> {
 
@@ -29,9 +34,21 @@ pub mod create {
             .map_err(|error| format!("Error while listing devices.\n  {}", error))?;
         let checked_devices = check_devices(&all_devices, &device_names, &device_ids);
         let (devices, errors): (Vec<_>, Vec<_>) = checked_devices.into_iter().partition(Result::is_ok);
-        let devices = devices.into_iter()
+        let mut devices = devices.into_iter()
             .map(Result::unwrap)
             .collect::<Vec<_>>();
+        if let Some(device_query) = device_query {
+            let queried_devices = carl.peers.find_devices(&device_query).await
+                .map_err(|error| format!("Error while searching devices.\n  {}", error))?;
+            if queried_devices.is_empty() {
+                Err(format!("No devices match the query '{}'.", device_query))?
+            }
+            for device in queried_devices {
+                if devices.iter().any(|other| other.id == device.id).not() {
+                    devices.push(device);
+                }
+            }
+        }
         let device_names = devices.clone().into_iter()
             .map(|device| device.name)
             .collect::<Vec<_>>();
@@ -46,7 +63,7 @@ pub mod create {
             Err("Specify at least 2 devices per cluster configuration.".to_string())?
         }
 
-        let configuration = ClusterConfiguration { id: cluster_id, name: Clone::clone(&name), leader, devices: device_ids };
+        let configuration = ClusterConfiguration { id: cluster_id, name: Clone::clone(&name), leader, devices: device_ids, revision: 0 };
         carl.cluster.store_cluster_configuration(configuration.clone()).await
             .map_err(|err| format!("Could not store cluster configuration. Make sure the application is running. Error: {}", err))?;
 
@@ -75,14 +92,15 @@ pub mod create {
     }
 
     fn check_devices(all_devices: &[DeviceDescriptor], device_names: &[DeviceName], device_ids: &[String]) -> Vec<Result<DeviceDescriptor, crate::Error>> {
+        let candidates = all_devices.iter()
+            .map(|device| Candidate { id: device.id.0, name: device.name.value().to_owned() })
+            .collect::<Vec<_>>();
         let mut checked_devices_ids = device_ids.iter().map(|device_id| {
-            let maybe_device = all_devices.iter().find(|device| device.id.to_string().starts_with(device_id));
-            if let Some(device) = maybe_device {
-                Ok(Clone::clone(device))
-            }
-            else {
-                Err(format!("Device '{}' not found", device_id))
-            }
+            let id = resolve::resolve_among(&candidates, ResourceKind::Device, device_id, IdPrefix::Allowed)?;
+            let device = all_devices.iter()
+                .find(|device| device.id.0 == id)
+                .expect("Resolved device should be among all devices.");
+            Ok(Clone::clone(device))
         }).collect::<Vec<_>>();
 
         let already_checked_device_names = checked_devices_ids.clone().iter_mut()
@@ -1,32 +1,103 @@
 
 pub mod create {
+    use std::time::Duration;
+
+    use chrono::{DateTime, Utc};
     use uuid::Uuid;
     use opendut_carl_api::carl::CarlClient;
     use opendut_types::cluster::{ClusterDeployment, ClusterId};
+    use opendut_types::cluster::operation::DeploymentOperationState;
+
+    use crate::commands;
+    use crate::commands::wait::WaitOutcome;
     use crate::CreateOutputFormat;
 
-    pub async fn execute(carl: &mut CarlClient, id: Uuid, output: CreateOutputFormat) -> crate::Result<()> {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn execute(carl: &mut CarlClient, id: Uuid, dry_run: bool, wait: bool, timeout: Option<Duration>, start: Option<DateTime<Utc>>, lease: Option<Duration>, output: CreateOutputFormat) -> crate::Result<WaitOutcome> {
 
         let id = ClusterId::from(id);
 
-        let deployment = ClusterDeployment { id };
-        carl.cluster.store_cluster_deployment(deployment).await
+        if dry_run {
+            return validate(carl, id, output).await
+                .map(|_| WaitOutcome::Reached);
+        }
+
+        let deployment = ClusterDeployment { id, start, lease };
+        let operation = carl.cluster.store_cluster_deployment(deployment).await
             .map_err(|error| format!("Could not create cluster deployment for ClusterID: '{}'.\n  {}", id, error))?;
+
+        let operation = if wait {
+            match commands::deployment_operation::wait_until_finished(carl, operation.id, timeout).await? {
+                Ok(operation) => operation,
+                Err(outcome) => return Ok(outcome),
+            }
+        } else {
+            operation
+        };
+
         match output {
             CreateOutputFormat::Text => {
-                println!("Successfully created cluster deployment for cluster <{}>.", id);
+                match &operation.state {
+                    DeploymentOperationState::Succeeded => {
+                        println!("Successfully deployed cluster <{}>.", id);
+                    }
+                    DeploymentOperationState::Failed { .. } => {}
+                    DeploymentOperationState::Pending if start.is_some_and(|start| start > Utc::now()) => {
+                        println!("Successfully scheduled deployment of cluster <{}>. Deployment is tracked by operation <{}>.", id, operation.id);
+                    }
+                    _ => {
+                        println!("Successfully created cluster deployment for cluster <{}>. Deployment is tracked by operation <{}>.", id, operation.id);
+                    }
+                }
             }
             CreateOutputFormat::Json => {
-                let json = serde_json::to_string(&id).unwrap();
+                let json = serde_json::to_string(&operation).unwrap();
                 println!("{}", json);
             }
             CreateOutputFormat::PrettyJson => {
-                let json = serde_json::to_string_pretty(&id).unwrap();
+                let json = serde_json::to_string_pretty(&operation).unwrap();
                 println!("{}", json);
             }
         }
 
-        Ok(())
+        match operation.state {
+            DeploymentOperationState::Failed { error } => Err(format!("Deployment of cluster <{}> failed.\n  {}", id, error)),
+            _ => Ok(WaitOutcome::Reached),
+        }
+    }
+
+    async fn validate(carl: &mut CarlClient, id: ClusterId, output: CreateOutputFormat) -> crate::Result<()> {
+
+        let validation = carl.cluster.validate_cluster_deployment(id).await
+            .map_err(|error| format!("Could not validate cluster deployment for ClusterID: '{}'.\n  {}", id, error))?;
+        let is_deployable = validation.is_deployable();
+
+        match output {
+            CreateOutputFormat::Text => {
+                if is_deployable {
+                    println!("Cluster <{}> can be deployed.", id);
+                } else {
+                    println!("Cluster <{}> cannot be deployed:", id);
+                    for issue in &validation.issues {
+                        println!("  - {}", issue);
+                    }
+                }
+            }
+            CreateOutputFormat::Json => {
+                let json = serde_json::to_string(&validation).unwrap();
+                println!("{}", json);
+            }
+            CreateOutputFormat::PrettyJson => {
+                let json = serde_json::to_string_pretty(&validation).unwrap();
+                println!("{}", json);
+            }
+        }
+
+        if is_deployable {
+            Ok(())
+        } else {
+            Err(format!("Validation of cluster deployment for ClusterID '{}' found {} issue(s).", id, validation.issues.len()))
+        }
     }
 }
 
@@ -40,6 +111,10 @@ pub mod list {
     struct ClusterTable {
         #[table(title = "ClusterID")]
         id: ClusterId,
+        #[table(title = "Start")]
+        start: String,
+        #[table(title = "End")]
+        end: String,
     }
 
     pub async fn execute(carl: &mut CarlClient, output: ListOutputFormat) -> crate::Result<()> {
@@ -53,6 +128,8 @@ pub mod list {
                     .map(|cluster_deployment| {
                         ClusterTable {
                             id: cluster_deployment.id,
+                            start: cluster_deployment.start.map(|start| start.to_rfc3339()).unwrap_or_default(),
+                            end: cluster_deployment.end().map(|end| end.to_rfc3339()).unwrap_or_default(),
                         }
                     })
                     .collect::<Vec<_>>();
@@ -0,0 +1,44 @@
+pub mod complete {
+    use clap::CommandFactory;
+
+    use opendut_carl_api::carl::CarlClient;
+
+    use crate::completion::Completion;
+    use crate::{completion, resolve, Args};
+
+    /// Prints the candidates for completing the last of the given words, including the names and IDs of resources known to CARL.
+    /// Errors are not reported, since they would garble the command line of the shell.
+    pub async fn execute(carl: &mut CarlClient, project: Option<String>, words: Vec<String>) -> crate::Result<()> {
+
+        match completion::complete(Args::command(), &words) {
+            Completion::Candidates(candidates) => {
+                for candidate in candidates {
+                    println!("{candidate}");
+                }
+            }
+            Completion::Resource(kind) => {
+                if let Some(project) = project {
+                    let Ok(project_id) = carl.projects.resolve_project(&project).await else {
+                        return Ok(());
+                    };
+                    carl.project.select(Some(project_id));
+                }
+                let Ok(candidates) = resolve::candidates(carl, kind).await else {
+                    return Ok(());
+                };
+                let current = words.last().map(String::as_str).unwrap_or_default();
+
+                for candidate in candidates {
+                    let id = candidate.id.to_string();
+                    if candidate.name.starts_with(current) {
+                        println!("{}\t{kind} {id}", candidate.name);
+                    }
+                    if id.starts_with(current) {
+                        println!("{id}\t{}", candidate.name);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
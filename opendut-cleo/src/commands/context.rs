@@ -0,0 +1,205 @@
+use std::ops::Not;
+use std::path::Path;
+
+use toml_edit::Document;
+
+fn read_user_config(path: &Path) -> crate::Result<Document> {
+    if path.exists().not() {
+        return Ok(Document::new());
+    }
+    std::fs::read_to_string(path)
+        .map_err(|cause| format!("Could not read the user configuration '{}'.\n  {}", path.display(), cause))?
+        .parse::<Document>()
+        .map_err(|cause| format!("Could not parse the user configuration '{}'.\n  {}", path.display(), cause))
+}
+
+/// Only the current user may read the user configuration, since contexts may contain client secrets.
+fn write_user_config(path: &Path, document: &Document) -> crate::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|cause| format!("Could not create the directory '{}'.\n  {}", parent.display(), cause))?;
+    }
+    opendut_util::fs::write_private_file(path, &document.to_string())
+        .map_err(|cause| format!("Could not write the user configuration '{}'.\n  {}", path.display(), cause))
+}
+
+pub mod list {
+    use cli_table::{print_stdout, Table, WithTitle};
+    use config::Config;
+    use serde::Serialize;
+
+    use crate::context;
+    use crate::ListOutputFormat;
+
+    #[derive(Table, Serialize)]
+    struct ContextTable {
+        #[table(title = "Name")]
+        name: String,
+        #[table(title = "CARL")]
+        carl: String,
+        #[table(title = "Authentication")]
+        authentication: String,
+        #[table(title = "Project")]
+        project: String,
+        #[table(title = "Selected")]
+        #[serde(skip)]
+        selected: String,
+    }
+
+    pub fn execute(config: &Config, selected: Option<String>, output: ListOutputFormat) -> crate::Result<()> {
+        let contexts = context::names(config).into_iter()
+            .map(|name| {
+                let settings = context::apply(Clone::clone(config), Some(&name))?;
+                Ok(ContextTable {
+                    carl: format!("{}:{}",
+                        settings.get_string("network.carl.host").unwrap_or_default(),
+                        settings.get_int("network.carl.port").unwrap_or_default(),
+                    ),
+                    authentication: if settings.get_bool("network.oidc.enabled").unwrap_or(false) {
+                        settings.get_string("network.oidc.client.issuer.url").unwrap_or_default()
+                    } else {
+                        String::from("disabled")
+                    },
+                    project: settings.get_string("project").unwrap_or_default(),
+                    selected: if selected.as_ref() == Some(&name) { String::from("*") } else { String::new() },
+                    name,
+                })
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        match output {
+            ListOutputFormat::Table => {
+                print_stdout(contexts.with_title())
+                    .expect("List of contexts should be printable as table.");
+            }
+            ListOutputFormat::Json => {
+                let json = serde_json::to_string(&contexts).unwrap();
+                println!("{}", json);
+            }
+            ListOutputFormat::PrettyJson => {
+                let json = serde_json::to_string_pretty(&contexts).unwrap();
+                println!("{}", json);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub mod select {
+    use std::ops::Not;
+    use std::path::Path;
+
+    use config::Config;
+    use toml_edit::value;
+
+    use crate::context;
+
+    pub fn execute(config: &Config, user_config: &Path, name: String) -> crate::Result<()> {
+        if context::names(config).contains(&name).not() {
+            Err(format!("Unknown context '{name}'. Use `opendut-cleo context list` to show the available contexts."))?
+        }
+
+        let mut document = super::read_user_config(user_config)?;
+        document[context::CURRENT_CONTEXT_KEY] = value(Clone::clone(&name));
+        super::write_user_config(user_config, &document)?;
+
+        println!("Switched to context '{name}'.");
+        Ok(())
+    }
+}
+
+pub mod add {
+    use std::path::{Path, PathBuf};
+
+    use toml_edit::{Item, Table, value};
+    use url::Url;
+
+    use crate::context;
+
+    pub struct OidcSettings {
+        pub issuer_url: Url,
+        pub client_id: Option<String>,
+        pub client_secret: Option<String>,
+        pub scopes: Option<String>,
+    }
+
+    pub fn execute(
+        user_config: &Path,
+        name: String,
+        carl_url: Url,
+        ca: PathBuf,
+        domain_name_override: Option<String>,
+        oidc: Option<OidcSettings>,
+        project: Option<String>,
+    ) -> crate::Result<()> {
+        context::validate_name(&name)?;
+
+        if carl_url.scheme() != "https" {
+            Err(format!("CARL has to be reached via 'https', but the URL '{carl_url}' uses '{}'.", carl_url.scheme()))?
+        }
+        let host = carl_url.host_str()
+            .ok_or_else(|| format!("The URL '{carl_url}' does not contain a host."))?;
+        let port = carl_url.port_or_known_default()
+            .ok_or_else(|| format!("The URL '{carl_url}' does not contain a port."))?;
+        let ca = ca.canonicalize()
+            .map_err(|cause| format!("Could not find the CA certificate '{}'.\n  {}", ca.display(), cause))?;
+
+        let mut settings = Table::new();
+        insert(&mut settings, "network.carl.host", value(host));
+        insert(&mut settings, "network.carl.port", value(i64::from(port)));
+        insert(&mut settings, "network.tls.ca", value(ca.display().to_string()));
+        insert(&mut settings, "network.tls.domain.name.override", value(domain_name_override.unwrap_or_default()));
+        match oidc {
+            Some(oidc) => {
+                insert(&mut settings, "network.oidc.enabled", value(true));
+                insert(&mut settings, "network.oidc.client.issuer.url", value(oidc.issuer_url.as_str()));
+                if let Some(client_id) = oidc.client_id {
+                    insert(&mut settings, "network.oidc.client.id", value(client_id));
+                }
+                insert(&mut settings, "network.oidc.login.enabled", value(oidc.client_secret.is_none()));
+                insert(&mut settings, "network.oidc.client.secret", value(oidc.client_secret.unwrap_or_default()));
+                if let Some(scopes) = oidc.scopes {
+                    insert(&mut settings, "network.oidc.client.scopes", value(scopes));
+                }
+            }
+            None => {
+                insert(&mut settings, "network.oidc.enabled", value(false));
+            }
+        }
+        if let Some(project) = project {
+            insert(&mut settings, "project", value(project));
+        }
+
+        let mut document = super::read_user_config(user_config)?;
+        let contexts = document.entry(context::CONTEXTS_KEY)
+            .or_insert_with(|| {
+                let mut contexts = Table::new();
+                contexts.set_implicit(true);
+                Item::Table(contexts)
+            })
+            .as_table_mut()
+            .ok_or_else(|| format!("The setting '{}' in the user configuration '{}' is not a table.", context::CONTEXTS_KEY, user_config.display()))?;
+        contexts.insert(&name, Item::Table(settings));
+        super::write_user_config(user_config, &document)?;
+
+        println!("Added context '{name}' to '{}'. Use `opendut-cleo context use {name}` to switch to it.", user_config.display());
+        Ok(())
+    }
+
+    /// Inserts the value at the dotted path, e.g. `network.carl.host`, creating the intermediate tables as dotted keys.
+    fn insert(table: &mut Table, path: &str, item: Item) {
+        let mut keys = path.split('.').collect::<Vec<_>>();
+        let last = keys.pop().expect("Path should not be empty.");
+        let table = keys.into_iter().fold(table, |table, key| {
+            table.entry(key)
+                .or_insert_with(|| {
+                    let mut nested = Table::new();
+                    nested.set_dotted(true);
+                    Item::Table(nested)
+                })
+                .as_table_mut()
+                .expect("Intermediate keys should be tables.")
+        });
+        table.insert(last, item);
+    }
+}
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use opendut_carl_api::carl::CarlClient;
+use opendut_types::cluster::operation::{DeploymentOperation, DeploymentOperationId};
+
+use crate::commands::wait::WaitOutcome;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Polls the deployment operation until CARL reports it as finished.
+/// Gives up with [`WaitOutcome::TimedOut`], if the operation did not finish within the timeout.
+pub async fn wait_until_finished(carl: &mut CarlClient, id: DeploymentOperationId, timeout: Option<Duration>) -> crate::Result<Result<DeploymentOperation, WaitOutcome>> {
+    let mut last_known = None;
+
+    let polling = async {
+        loop {
+            let operation = carl.cluster.get_deployment_operation(id).await
+                .map_err(|error| format!("Could not get deployment operation <{}>.\n  {}", id, error))?;
+
+            if operation.is_finished() {
+                return Ok(operation);
+            }
+            last_known = Some(operation.state);
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    };
+
+    let result = match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, polling).await,
+        None => Ok(polling.await),
+    };
+
+    match result {
+        Ok(operation) => operation.map(Ok),
+        Err(_) => {
+            let state = last_known.map(|state| state.to_string())
+                .unwrap_or_else(|| String::from("unknown"));
+            Ok(Err(WaitOutcome::TimedOut {
+                cause: format!("Deployment operation <{}> did not finish within {}. It is {}.", id, humantime::format_duration(timeout.unwrap_or_default()), state),
+            }))
+        }
+    }
+}
+
+pub mod list {
+    use cli_table::{print_stdout, Table, WithTitle};
+    use uuid::Uuid;
+
+    use opendut_carl_api::carl::CarlClient;
+    use opendut_types::cluster::ClusterId;
+    use opendut_types::cluster::operation::DeploymentOperationId;
+
+    use crate::ListOutputFormat;
+
+    #[derive(Table)]
+    struct DeploymentOperationTable {
+        #[table(title = "OperationID")]
+        id: DeploymentOperationId,
+        #[table(title = "ClusterID")]
+        cluster_id: ClusterId,
+        #[table(title = "State")]
+        state: String,
+    }
+
+    pub async fn execute(carl: &mut CarlClient, cluster_id: Option<Uuid>, output: ListOutputFormat) -> crate::Result<()> {
+
+        let operations = carl.cluster.list_deployment_operations(cluster_id.map(ClusterId::from)).await
+            .map_err(|error| format!("Error while listing deployment operations: {}", error))?;
+
+        match output {
+            ListOutputFormat::Table => {
+                let operation_table = operations.into_iter()
+                    .map(|operation| DeploymentOperationTable {
+                        id: operation.id,
+                        cluster_id: operation.cluster_id,
+                        state: operation.state.to_string(),
+                    })
+                    .collect::<Vec<_>>();
+                print_stdout(operation_table.with_title())
+                    .expect("List of deployment operations should be printable as table.");
+            }
+            ListOutputFormat::Json => {
+                let json = serde_json::to_string(&operations).unwrap();
+                println!("{}", json);
+            }
+            ListOutputFormat::PrettyJson => {
+                let json = serde_json::to_string_pretty(&operations).unwrap();
+                println!("{}", json);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub mod describe {
+    use indoc::indoc;
+    use uuid::Uuid;
+
+    use opendut_carl_api::carl::CarlClient;
+    use opendut_types::cluster::operation::DeploymentOperationId;
+
+    use crate::DescribeOutputFormat;
+
+    pub async fn execute(carl: &mut CarlClient, id: Uuid, output: DescribeOutputFormat) -> crate::Result<()> {
+        let id = DeploymentOperationId::from(id);
+
+        let operation = carl.cluster.get_deployment_operation(id).await
+            .map_err(|error| format!("Could not get deployment operation <{}>.\n  {}", id, error))?;
+
+        let text = match output {
+            DescribeOutputFormat::Text => {
+                format!(indoc!("
+                    Deployment Operation: {}
+                      Cluster: {}
+                      State: {}
+                "), operation.id, operation.cluster_id, operation.state)
+            }
+            DescribeOutputFormat::Json => {
+                serde_json::to_string(&operation).unwrap()
+            }
+            DescribeOutputFormat::PrettyJson => {
+                serde_json::to_string_pretty(&operation).unwrap()
+            }
+        };
+
+        println!("{text}");
+
+        Ok(())
+    }
+}
@@ -2,7 +2,9 @@ use cli_table::{Table, WithTitle};
 use serde::Serialize;
 
 use opendut_carl_api::carl::CarlClient;
+use opendut_types::peer::PeerDescriptor;
 use opendut_types::topology::{DeviceDescription, DeviceDescriptor, DeviceId, DeviceName};
+use opendut_types::util::net::{NetworkInterfaceDescriptor, NetworkInterfaceName};
 
 use crate::ListOutputFormat;
 
@@ -43,86 +45,104 @@ pub mod create {
         carl: &mut CarlClient,
         peer_id: Uuid,
         device_id: Option<Uuid>,
-        name: Option<String>,
+        name: String,
         description: Option<String>,
-        interface_name: Option<NetworkInterfaceName>,
+        interface_name: NetworkInterfaceName,
         tags: Option<Vec<String>>,
         output: CreateOutputFormat,
     ) -> crate::Result<()> {
         let peer_id = PeerId::from(peer_id);
         let device_id = device_id.map(DeviceId::from).unwrap_or(DeviceId::random());
 
-        let mut peer_descriptor = carl.peers.get_peer_descriptor(peer_id).await
+        let devices = carl.peers.list_devices().await
+            .map_err(|error| format!("Could not list devices.\n  {}", error))?;
+        if devices.iter().any(|device| device.id == device_id) {
+            Err(format!("Device <{}> already exists. Use `opendut-cleo update device` to change it.", device_id))?
+        }
+
+        let peer_descriptor = carl.peers.get_peer_descriptor(peer_id).await
             .map_err(|_| format!("Failed to get peer with ID <{}>.", peer_id))?;
-        let peer_network_interface_names = peer_descriptor.network_configuration.interfaces.iter().map(|peer_interface| {
-            peer_interface.name.clone()
-        }).collect::<Vec<_>>();
-        let maybe_existing_device = peer_descriptor.topology.devices.iter_mut().find(|device| device.id == device_id) ;
-        match maybe_existing_device {
-            None => {
-                //TODO provide separate `update device` command to not need this custom input handling
-                let name = name.ok_or(String::from("Cannot create new device because of missing device name."))?;
-                let interface_name = interface_name.ok_or(String::from("Cannot create new device because of missing interface name."))?;
-
-                let interface = match peer_descriptor.network_configuration.interfaces.iter().find(|descriptor| descriptor.name == interface_name) {
-                    Some(network_interface_descriptor) => network_interface_descriptor.clone(),
-                    None => {
-                        Err(format!("Cannot create new device because interface is not one of the allowed values: {} \nAllowed interfaces are configured on the peer.",
-                                peer_network_interface_names.into_iter().map(|name| name.name()).collect::<Vec<_>>().join(", ")))?
-                    },
-                };
-
-                let new_device = DeviceDescriptor {
-                    id: device_id,
-                    name: DeviceName::try_from(name)
-                        .map_err(|error| error.to_string())?,
-                    description: description
-                        .map(DeviceDescription::try_from)
-                        .transpose()
-                        .map_err(|error| error.to_string())?,
-                    interface,
-                    tags: tags
-                        .unwrap_or_default()
-                        .into_iter()
-                        .map(DeviceTag::try_from)
-                        .collect::<Result<_, _>>()
-                        .map_err(|error| error.to_string())?,
-                };
-                peer_descriptor.topology.devices.push(new_device);
-            }
-            Some(device) => {
-                if let Some(name) = name {
-                    device.name = DeviceName::try_from(name)
-                        .map_err(|error| error.to_string())?;
-                }
-                if let Some(description) = description {
-                    device.description = DeviceDescription::try_from(description)
-                        .map_err(|error| error.to_string())
-                        .ok();
-                }
-                if let Some(interface_name) = interface_name {
-                    device.interface = match peer_descriptor.network_configuration.interfaces.iter().find(|descriptor| descriptor.name == interface_name) {
-                        Some(network_interface_descriptor) => network_interface_descriptor.clone(),
-                        None => {
-                            Err(format!("Cannot create new device because interface is not one of the allowed values: {} \nAllowed interfaces are configured on the peer.",
-                                    peer_network_interface_names.into_iter().map(|name| name.name()).collect::<Vec<_>>().join(", ")))?
-                        },
-                    };
-                }
-                if let Some(tags) = tags {
-                    device.tags = tags
-                        .into_iter()
-                        .map(DeviceTag::try_from)
-                        .collect::<Result<_, _>>()
-                        .map_err(|error| error.to_string())?;
-                }
-            }
+        let interface = super::find_interface(&peer_descriptor, &interface_name)?;
+
+        let device = DeviceDescriptor {
+            id: device_id,
+            name: DeviceName::try_from(name)
+                .map_err(|error| error.to_string())?,
+            description: description
+                .map(DeviceDescription::try_from)
+                .transpose()
+                .map_err(|error| error.to_string())?,
+            interface,
+            tags: tags
+                .unwrap_or_default()
+                .into_iter()
+                .map(DeviceTag::try_from)
+                .collect::<Result<_, _>>()
+                .map_err(|error| error.to_string())?,
+        };
+
+        let peer_descriptor = carl.peers.store_device(peer_id, device).await
+            .map_err(|error| format!("Failed to create device <{}>.\n  {}", device_id, error))?;
+        let output_format = DescribeOutputFormat::from(output);
+        crate::commands::peer::describe::render_peer_descriptor(peer_descriptor, output_format);
+
+        Ok(())
+    }
+}
+
+pub mod update {
+    use uuid::Uuid;
+    use opendut_carl_api::carl::CarlClient;
+    use opendut_types::topology::{DeviceDescription, DeviceId, DeviceName, DeviceTag};
+    use opendut_types::util::net::NetworkInterfaceName;
+    use crate::{CreateOutputFormat, DescribeOutputFormat};
+
+    pub async fn execute(
+        carl: &mut CarlClient,
+        device_id: Uuid,
+        name: Option<String>,
+        description: Option<String>,
+        interface_name: Option<NetworkInterfaceName>,
+        tags: Option<Vec<String>>,
+        output: CreateOutputFormat,
+    ) -> crate::Result<()> {
+        let device_id = DeviceId::from(device_id);
+
+        let peers = carl.peers.list_peer_descriptors().await
+            .map_err(|error| format!("Could not list peers.\n  {}", error))?;
+
+        let peer = peers.into_iter()
+            .find(|peer| peer.topology.devices.iter().any(|device| device.id == device_id))
+            .ok_or(format!("Cannot find a peer with the device <{}>.", device_id))?;
+        let mut device = peer.topology.devices.iter()
+            .find(|device| device.id == device_id)
+            .cloned()
+            .expect("Device should be part of the peer it was found on.");
+
+        if let Some(name) = name {
+            device.name = DeviceName::try_from(name)
+                .map_err(|error| error.to_string())?;
         }
-        carl.peers.store_peer_descriptor(Clone::clone(&peer_descriptor)).await
-            .map_err(|error| format!("Failed to update peer <{}>.\n  {}", peer_id, error))?;
+        if let Some(description) = description {
+            device.description = Some(DeviceDescription::try_from(description)
+                .map_err(|error| error.to_string())?);
+        }
+        if let Some(interface_name) = interface_name {
+            device.interface = super::find_interface(&peer, &interface_name)?;
+        }
+        if let Some(tags) = tags {
+            device.tags = tags
+                .into_iter()
+                .map(DeviceTag::try_from)
+                .collect::<Result<_, _>>()
+                .map_err(|error| error.to_string())?;
+        }
+
+        let peer_descriptor = carl.peers.store_device(peer.id, device).await
+            .map_err(|error| format!("Failed to update device <{}>.\n  {}", device_id, error))?;
         let output_format = DescribeOutputFormat::from(output);
         crate::commands::peer::describe::render_peer_descriptor(peer_descriptor, output_format);
-        
+
         Ok(())
     }
 }
@@ -181,28 +201,38 @@ pub mod describe {
 
 pub mod find {
     use opendut_carl_api::carl::CarlClient;
+    use opendut_types::topology::query::DeviceQuery;
 
     use crate::commands::device::{DeviceTable, render_devices};
     use crate::ListOutputFormat;
 
-    pub async fn execute(carl: &mut CarlClient, criteria: Vec<String>, output: ListOutputFormat) -> crate::Result<()> {
-        let devices = {
-            let devices = carl.peers.list_devices().await
-                .map_err(|_| String::from("Failed to find devices."))?;
-
-            devices.into_iter()
-                .filter(|device| {
-                    criteria.iter().any(|criterion| {
-                        let pattern = glob::Pattern::new(criterion).expect("Failed to read glob pattern");
-                        pattern.matches(&device.name.value().to_lowercase())
-                            || pattern.matches(&device.id.to_string().to_lowercase())
-                            || pattern.matches(&device.description.clone().unwrap().value().to_lowercase())
-                            || pattern.matches(&device.interface.to_string().to_lowercase())
-                            || device.tags.iter().any(|tag| pattern.matches(&tag.value().to_lowercase()))
+    pub async fn execute(carl: &mut CarlClient, criteria: Vec<String>, query: Option<DeviceQuery>, output: ListOutputFormat) -> crate::Result<()> {
+        let devices = match query {
+            Some(query) => {
+                carl.peers.find_devices(&query).await
+                    .map_err(|error| format!("Failed to find devices.\n  {}", error))?
+                    .into_iter()
+                    .map(DeviceTable::from)
+                    .collect::<Vec<_>>()
+            }
+            None => {
+                let devices = carl.peers.list_devices().await
+                    .map_err(|_| String::from("Failed to find devices."))?;
+
+                devices.into_iter()
+                    .filter(|device| {
+                        criteria.iter().any(|criterion| {
+                            let pattern = glob::Pattern::new(criterion).expect("Failed to read glob pattern");
+                            pattern.matches(&device.name.value().to_lowercase())
+                                || pattern.matches(&device.id.to_string().to_lowercase())
+                                || pattern.matches(&device.description.clone().unwrap().value().to_lowercase())
+                                || pattern.matches(&device.interface.to_string().to_lowercase())
+                                || device.tags.iter().any(|tag| pattern.matches(&tag.value().to_lowercase()))
+                        })
                     })
-                })
-                .map(DeviceTable::from)
-                .collect::<Vec<_>>()
+                    .map(DeviceTable::from)
+                    .collect::<Vec<_>>()
+            }
         };
         let text = render_devices(devices, output);
         println!("{text}");
@@ -218,24 +248,32 @@ pub mod delete {
     pub async fn execute(carl: &mut CarlClient, device_id: Uuid) -> crate::Result<()> {
         let device_id = DeviceId::from(device_id);
 
-        let mut peers = carl.peers.list_peer_descriptors().await
+        let peers = carl.peers.list_peer_descriptors().await
             .map_err(|error| format!("Could not list peers.\n  {}", error))?;
 
-        let peer = peers.iter_mut().find(|peer| {
+        let peer_id = peers.iter().find(|peer| {
             peer.topology.devices
                 .iter()
                 .any(|device| device.id == device_id)
-        }).ok_or(format!("Cannot find a peer with the device <{}>.", device_id))?;
+        }).ok_or(format!("Cannot find a peer with the device <{}>.", device_id))?.id;
 
-        peer.topology.devices.retain(|device| device.id != device_id);
-
-        carl.peers.store_peer_descriptor(Clone::clone(peer)).await
-            .map_err(|error| format!("Failed to delete peer.\n  {}", error))?;
+        carl.peers.delete_device(peer_id, device_id).await
+            .map_err(|error| format!("Failed to delete device <{}>.\n  {}", device_id, error))?;
 
         Ok(())
     }
 }
 
+fn find_interface(peer: &PeerDescriptor, interface_name: &NetworkInterfaceName) -> crate::Result<NetworkInterfaceDescriptor> {
+    peer.network_configuration.interfaces.iter()
+        .find(|interface| &interface.name == interface_name)
+        .cloned()
+        .ok_or_else(|| format!("Interface '{}' is not one of the allowed values: {} \nAllowed interfaces are configured on the peer.",
+            interface_name,
+            peer.network_configuration.interfaces.iter().map(|interface| interface.name.name()).collect::<Vec<_>>().join(", "),
+        ))
+}
+
 fn render_devices(devices: Vec<DeviceTable>, output: ListOutputFormat) -> String {
     match output {
         ListOutputFormat::Table => {
@@ -1,3 +1,7 @@
+use opendut_types::peer::executor::{ContainerEnvironmentVariable, ContainerName, Engine, ExecutorDescriptor};
+
+use crate::EngineVariants;
+
 pub mod list {
     use cli_table::{print_stdout, Table, WithTitle};
     use serde::Serialize;
@@ -76,8 +80,7 @@ pub mod list {
                 let volumes = volumes.iter().map(|volume| volume.to_string()).collect::<Vec<_>>();
                 let devices = devices.iter().map(|device| device.to_string()).collect::<Vec<_>>();
                 let ports = ports.iter().map(|port| port.to_string()).collect::<Vec<_>>();
-                let envs = envs.iter().map(|env|
-                    format!("{}={}", env.name(), env.value())).collect::<Vec<_>>();
+                let envs = envs.iter().map(|env| env.to_string()).collect::<Vec<_>>();
                 executor_table.push(ContainerExecutorTable {
                     engine: Clone::clone(engine),
                     name: name.into(),
@@ -95,12 +98,41 @@ pub mod list {
     }
 }
 
+fn environment_variables(envs: Option<Vec<String>>, secret_envs: Option<Vec<String>>) -> crate::Result<Vec<ContainerEnvironmentVariable>> {
+    let mut environment_variables = vec![];
+
+    for env in envs.unwrap_or_default() {
+        if let Some((name, value)) = env.split_once('=') {
+            let env = ContainerEnvironmentVariable::new(name, value)
+                .map_err(|cause| cause.to_string())?;
+            environment_variables.push(env)
+        }
+    };
+
+    for env in secret_envs.unwrap_or_default() {
+        if let Some((name, value)) = env.split_once('=') {
+            let env = ContainerEnvironmentVariable::new_secret(name, value)
+                .map_err(|cause| cause.to_string())?;
+            environment_variables.push(env)
+        }
+    };
+
+    Ok(environment_variables)
+}
+
+fn engine(engine: EngineVariants) -> Engine {
+    match engine {
+        EngineVariants::Docker => { Engine::Docker }
+        EngineVariants::Podman => { Engine::Podman }
+    }
+}
+
 pub mod create {
     use uuid::Uuid;
 
     use opendut_carl_api::carl::CarlClient;
     use opendut_types::peer::{PeerId};
-    use opendut_types::peer::executor::{ContainerCommand, ContainerCommandArgument, ContainerDevice, ContainerEnvironmentVariable, ContainerImage, ContainerName, ContainerPortSpec, ContainerVolume, Engine, ExecutorDescriptor};
+    use opendut_types::peer::executor::{ContainerCommand, ContainerCommandArgument, ContainerDevice, ContainerImage, ContainerName, ContainerPortSpec, ContainerVolume, ExecutorDescriptor};
 
     use crate::{CreateOutputFormat, DescribeOutputFormat, EngineVariants};
     
@@ -114,52 +146,110 @@ pub mod create {
         volumes:  Option<Vec<ContainerVolume>>,
         devices: Option<Vec<ContainerDevice>>,
         envs: Option<Vec<String>>,
+        secret_envs: Option<Vec<String>>,
         ports: Option<Vec<ContainerPortSpec>>,
         command: Option<ContainerCommand>,
         args: Option<Vec<ContainerCommandArgument>>,
         output: CreateOutputFormat,
     ) -> crate::Result<()> {
         let peer_id = PeerId::from(peer_id);
+        let name = name.unwrap_or_default();
 
-        let mut peer_descriptor = carl.peers.get_peer_descriptor(peer_id).await
-            .map_err(|_| format!("Failed to get peer with ID <{}>.", peer_id))?;
-        
-        let engine = match engine {
-            EngineVariants::Docker => { Engine::Docker }
-            EngineVariants::Podman => { Engine::Podman }
-        };
-        
-        let volumes = volumes.unwrap_or_default();
-        let devices = devices.unwrap_or_default();
-        let ports = ports.unwrap_or_default();
-        let args = args.unwrap_or_default();
-        
-        let mut environment_variables = vec![];
-        
-        for env in envs.unwrap_or_default() {
-            if let Some((name, value)) = env.split_once('=') {
-                let env = ContainerEnvironmentVariable::new(name, value)
-                    .map_err(|cause| cause.to_string())?;
-                environment_variables.push(env)
+        if let ContainerName::Value(_) = name {
+            let peer_descriptor = carl.peers.get_peer_descriptor(peer_id).await
+                .map_err(|_| format!("Failed to get peer with ID <{}>.", peer_id))?;
+            if super::find_container(&peer_descriptor.executors.executors, &name).is_some() {
+                Err(format!("A container executor named '{}' already exists. Use `opendut-cleo update container-executor` to change it.", name))?
             }
-        };
-        
+        }
+
         let executor_descriptor = ExecutorDescriptor::Container {
-            engine,
-            name: name.unwrap_or_default(),
+            engine: super::engine(engine),
+            name,
             image,
-            volumes,
-            devices,
-            envs: environment_variables,
-            ports,
+            volumes: volumes.unwrap_or_default(),
+            devices: devices.unwrap_or_default(),
+            envs: super::environment_variables(envs, secret_envs)?,
+            ports: ports.unwrap_or_default(),
             command: command.unwrap_or_default(),
-            args,
+            args: args.unwrap_or_default(),
         };
 
-        peer_descriptor.executors.executors.push(executor_descriptor);
-        
-        carl.peers.store_peer_descriptor(Clone::clone(&peer_descriptor)).await
-            .map_err(|error| format!("Failed to update peer <{}>.\n  {}", peer_id, error))?;
+        let peer_descriptor = carl.peers.store_executor(peer_id, executor_descriptor).await
+            .map_err(|error| format!("Failed to create container executor.\n  {}", error))?;
+        let output_format = DescribeOutputFormat::from(output);
+        crate::commands::peer::describe::render_peer_descriptor(peer_descriptor, output_format);
+
+        Ok(())
+    }
+}
+
+pub mod update {
+    use uuid::Uuid;
+
+    use opendut_carl_api::carl::CarlClient;
+    use opendut_types::peer::PeerId;
+    use opendut_types::peer::executor::{ContainerCommand, ContainerCommandArgument, ContainerDevice, ContainerImage, ContainerName, ContainerPortSpec, ContainerVolume, ExecutorDescriptor};
+
+    use crate::{CreateOutputFormat, DescribeOutputFormat, EngineVariants};
+
+    /// Replaces the given properties of the container executor with the given name. Other properties are kept.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn execute(
+        carl: &mut CarlClient,
+        peer_id: Uuid,
+        name: ContainerName,
+        engine: Option<EngineVariants>,
+        image: Option<ContainerImage>,
+        volumes:  Option<Vec<ContainerVolume>>,
+        devices: Option<Vec<ContainerDevice>>,
+        envs: Option<Vec<String>>,
+        secret_envs: Option<Vec<String>>,
+        ports: Option<Vec<ContainerPortSpec>>,
+        command: Option<ContainerCommand>,
+        args: Option<Vec<ContainerCommandArgument>>,
+        output: CreateOutputFormat,
+    ) -> crate::Result<()> {
+        let peer_id = PeerId::from(peer_id);
+
+        let peer_descriptor = carl.peers.get_peer_descriptor(peer_id).await
+            .map_err(|_| format!("Failed to get peer with ID <{}>.", peer_id))?;
+        let mut executor = super::find_container(&peer_descriptor.executors.executors, &name)
+            .cloned()
+            .ok_or(format!("Peer <{}> has no container executor named '{}'.", peer_id, name))?;
+
+        let replaces_envs = envs.is_some() || secret_envs.is_some();
+        let new_envs = super::environment_variables(envs, secret_envs)?;
+
+        if let ExecutorDescriptor::Container { engine: executor_engine, image: executor_image, volumes: executor_volumes, devices: executor_devices, envs: executor_envs, ports: executor_ports, command: executor_command, args: executor_args, .. } = &mut executor {
+            if let Some(engine) = engine {
+                *executor_engine = super::engine(engine);
+            }
+            if let Some(image) = image {
+                *executor_image = image;
+            }
+            if let Some(volumes) = volumes {
+                *executor_volumes = volumes;
+            }
+            if let Some(devices) = devices {
+                *executor_devices = devices;
+            }
+            if replaces_envs {
+                *executor_envs = new_envs;
+            }
+            if let Some(ports) = ports {
+                *executor_ports = ports;
+            }
+            if let Some(command) = command {
+                *executor_command = command;
+            }
+            if let Some(args) = args {
+                *executor_args = args;
+            }
+        }
+
+        let peer_descriptor = carl.peers.store_executor(peer_id, executor).await
+            .map_err(|error| format!("Failed to update container executor '{}'.\n  {}", name, error))?;
         let output_format = DescribeOutputFormat::from(output);
         crate::commands::peer::describe::render_peer_descriptor(peer_descriptor, output_format);
 
@@ -171,34 +261,21 @@ pub mod delete {
     use uuid::Uuid;
 
     use opendut_carl_api::carl::CarlClient;
-    use opendut_types::peer::executor::{ContainerImage, ExecutorDescriptor};
+    use opendut_types::peer::executor::ContainerName;
     use opendut_types::peer::PeerId;
 
-    pub async fn execute(carl: &mut CarlClient, id: Uuid, images: Vec<ContainerImage>) -> crate::Result<()> {
+    pub async fn execute(carl: &mut CarlClient, id: Uuid, names: Vec<ContainerName>) -> crate::Result<()> {
         let id = PeerId::from(id);
 
-        let mut peer = carl.peers
-            .get_peer_descriptor(id)
-            .await
-            .map_err(|error| format!("Failed to get peer with the id '{}'.\n  {}", id, error))?;
-
-        let container_images = images.into_iter()
-            .map(ContainerImage::try_from)
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|error| error.to_string())?;
-
-        for container_image in container_images {
-            peer.executors.executors.retain(|executor| match executor {
-                ExecutorDescriptor::Executable => true,
-                ExecutorDescriptor::Container { image, .. } => {
-                    image != &container_image
-                }
-            } )
-        };
-
-        carl.peers.store_peer_descriptor(peer).await
-            .map_err(|error| format!("Failed to delete container executor for peer.\n  {}", error))?;
+        for name in names {
+            carl.peers.delete_executor(id, Clone::clone(&name)).await
+                .map_err(|error| format!("Container executor '{}' could not be deleted.\n  {}", name, error))?;
+        }
 
         Ok(())
     }
 }
+
+fn find_container<'a>(executors: &'a [ExecutorDescriptor], name: &ContainerName) -> Option<&'a ExecutorDescriptor> {
+    executors.iter().find(|executor| matches!(executor, ExecutorDescriptor::Container { name: executor_name, .. } if executor_name == name))
+}
@@ -0,0 +1,21 @@
+use opendut_carl_api::carl::auth::manager::DeviceAuthorization;
+use opendut_carl_api::carl::CarlClient;
+
+pub async fn execute(carl: &CarlClient) -> crate::Result<()> {
+    let authentication = carl.authentication()
+        .ok_or("Authentication is disabled in the configuration of CLEO. There is no need to log in.")?;
+
+    authentication.login(prompt).await
+        .map_err(|error| format!("Could not log in.\n  {error}"))?;
+
+    println!("Logged in successfully.");
+    Ok(())
+}
+
+fn prompt(authorization: &DeviceAuthorization) {
+    match &authorization.verification_url_complete {
+        Some(url) => println!("To log in, open the following URL in your browser and confirm the code '{}':\n\n  {url}\n", authorization.user_code),
+        None => println!("To log in, open the following URL in your browser and enter the code '{}':\n\n  {}\n", authorization.user_code, authorization.verification_url),
+    }
+    println!("Waiting for the login to be confirmed within {} minutes...", authorization.expires_in.as_secs().div_ceil(60));
+}
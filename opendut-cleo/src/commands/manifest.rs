@@ -0,0 +1,113 @@
+use std::path::Path;
+
+use opendut_carl_api::carl::CarlClient;
+use opendut_types::cluster::ClusterDeployment;
+
+use crate::manifest::{Change, CurrentState, DesiredState, Manifest};
+
+async fn load(carl: &mut CarlClient, file: &Path) -> crate::Result<(CurrentState, DesiredState)> {
+    let manifest = Manifest::load(file)?;
+
+    let current = CurrentState {
+        peers: carl.peers.list_peer_descriptors().await
+            .map_err(|error| format!("Could not list peers.\n  {error}"))?,
+        clusters: carl.cluster.list_cluster_configurations().await
+            .map_err(|error| format!("Could not list cluster configurations.\n  {error}"))?,
+        deployments: carl.cluster.list_cluster_deployments().await
+            .map_err(|error| format!("Could not list cluster deployments.\n  {error}"))?
+            .into_iter()
+            .map(|deployment| deployment.id)
+            .collect(),
+    };
+    let desired = manifest.resolve(&current)?;
+
+    Ok((current, desired))
+}
+
+async fn perform(carl: &mut CarlClient, changes: Vec<Change>) -> crate::Result<()> {
+    if changes.is_empty() {
+        println!("No changes.");
+    }
+    for change in changes {
+        println!("{change}");
+        match change {
+            Change::CreatePeer(peer) | Change::UpdatePeer { desired: peer, .. } => {
+                let peer_id = peer.id;
+                carl.peers.store_peer_descriptor(peer).await
+                    .map_err(|error| format!("Failed to store peer <{peer_id}>.\n  {error}"))?;
+            }
+            Change::DeletePeer(peer) => {
+                carl.peers.delete_peer_descriptor(peer.id).await
+                    .map_err(|error| format!("Failed to delete peer <{}>.\n  {error}", peer.id))?;
+            }
+            Change::CreateCluster(cluster) | Change::UpdateCluster { desired: cluster, .. } => {
+                let cluster_id = cluster.id;
+                carl.cluster.store_cluster_configuration(cluster).await
+                    .map_err(|error| format!("Failed to store cluster configuration <{cluster_id}>.\n  {error}"))?;
+            }
+            Change::DeleteCluster(cluster) => {
+                carl.cluster.delete_cluster_configuration(cluster.id).await
+                    .map_err(|error| format!("Failed to delete cluster configuration <{}>.\n  {error}", cluster.id))?;
+            }
+            Change::Deploy(cluster) => {
+                let operation = carl.cluster.store_cluster_deployment(ClusterDeployment::new(cluster.id)).await
+                    .map_err(|error| format!("Failed to deploy cluster <{}>.\n  {error}", cluster.id))?;
+                println!("  Deployment operation <{}> started.", operation.id);
+            }
+            Change::Undeploy(cluster) => {
+                carl.cluster.delete_cluster_deployment(cluster.id).await
+                    .map_err(|error| format!("Failed to undeploy cluster <{}>.\n  {error}", cluster.id))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub mod apply {
+    use std::path::PathBuf;
+
+    use opendut_carl_api::carl::CarlClient;
+
+    use crate::manifest;
+
+    pub async fn execute(carl: &mut CarlClient, file: PathBuf) -> crate::Result<()> {
+        let (current, desired) = super::load(carl, &file).await?;
+        let changes = manifest::plan_apply(&current, &desired);
+        super::perform(carl, changes).await
+    }
+}
+
+pub mod diff {
+    use std::path::PathBuf;
+
+    use opendut_carl_api::carl::CarlClient;
+
+    use crate::manifest;
+
+    pub async fn execute(carl: &mut CarlClient, file: PathBuf) -> crate::Result<()> {
+        let (current, desired) = super::load(carl, &file).await?;
+        let changes = manifest::plan_apply(&current, &desired);
+
+        if changes.is_empty() {
+            println!("No changes.");
+        }
+        for change in changes {
+            println!("{change}");
+        }
+        Ok(())
+    }
+}
+
+pub mod delete {
+    use std::path::PathBuf;
+
+    use opendut_carl_api::carl::CarlClient;
+
+    use crate::manifest;
+
+    pub async fn execute(carl: &mut CarlClient, file: PathBuf) -> crate::Result<()> {
+        let (current, desired) = super::load(carl, &file).await?;
+        let changes = manifest::plan_delete(&current, &desired);
+        super::perform(carl, changes).await
+    }
+}
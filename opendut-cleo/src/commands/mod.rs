@@ -1,6 +1,17 @@
+pub mod audit;
 pub mod cluster_configuration;
 pub mod cluster_deployment;
+pub mod completion;
+pub mod context;
+pub mod deployment_operation;
 pub mod device;
+pub mod manifest;
 pub mod peer;
+pub mod project;
 pub mod network_interface;
 pub mod executor;
+pub mod login;
+pub mod state;
+pub mod tui;
+pub mod vpn;
+pub mod wait;
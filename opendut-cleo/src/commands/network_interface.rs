@@ -1,9 +1,27 @@
+use opendut_types::util::net::{CanSamplePoint, NetworkInterfaceConfiguration};
+
+use crate::NetworkInterfaceType;
+
+fn interface_configuration(interface_type: NetworkInterfaceType) -> NetworkInterfaceConfiguration {
+    // TODO: Properly implement CAN parameter configuration
+    match interface_type {
+        NetworkInterfaceType::Ethernet => NetworkInterfaceConfiguration::Ethernet,
+        NetworkInterfaceType::Can => NetworkInterfaceConfiguration::Can {
+            bitrate: 500000,
+            sample_point: CanSamplePoint::try_from(0.7).unwrap(),
+            fd: true,
+            data_bitrate: 2000000,
+            data_sample_point: CanSamplePoint::try_from(0.7).unwrap(),
+        },
+    }
+}
+
 pub mod create {
     use uuid::Uuid;
 
     use opendut_carl_api::carl::CarlClient;
     use opendut_types::peer::PeerId;
-    use opendut_types::util::net::{CanSamplePoint, NetworkInterfaceConfiguration, NetworkInterfaceDescriptor, NetworkInterfaceName};
+    use opendut_types::util::net::{NetworkInterfaceDescriptor, NetworkInterfaceName};
 
     use crate::{CreateOutputFormat, DescribeOutputFormat, NetworkInterfaceType};
 
@@ -16,39 +34,63 @@ pub mod create {
     ) -> crate::Result<()> {
         let peer_id = PeerId::from(peer_id);
 
-        let mut peer_descriptor = carl.peers.get_peer_descriptor(peer_id).await
+        let interface_name = NetworkInterfaceName::try_from(interface_name).map_err(|error| error.to_string())?;
+
+        let peer_descriptor = carl.peers.get_peer_descriptor(peer_id).await
             .map_err(|_| format!("Failed to get peer with ID <{}>.", peer_id))?;
+        if peer_descriptor.network_configuration.interfaces.iter().any(|interface| interface.name == interface_name) {
+            Err(format!("Could not create peer network configuration with name '{}' because it already exists", &interface_name))?
+        }
 
-        let peer_interface_names = peer_descriptor.network_configuration.interfaces
-            .iter().map(|interface| interface.name.clone()).collect::<Vec<_>>();
+        let network_interface = NetworkInterfaceDescriptor {
+            name: Clone::clone(&interface_name),
+            configuration: super::interface_configuration(interface_type),
+        };
 
-        let interface_name = NetworkInterfaceName::try_from(interface_name).map_err(|error| error.to_string())?;
+        let peer_descriptor = carl.peers.store_network_interface(peer_id, network_interface).await
+            .map_err(|error| format!("Failed to create network interface '{}'.\n  {}", interface_name, error))?;
+        let output_format = DescribeOutputFormat::from(output);
+        crate::commands::peer::describe::render_peer_descriptor(peer_descriptor, output_format);
 
-        // TODO: Properly implement CAN parameter configuration
-        let interface_configuration = match interface_type {
-            NetworkInterfaceType::Ethernet => NetworkInterfaceConfiguration::Ethernet,
-            NetworkInterfaceType::Can => NetworkInterfaceConfiguration::Can { 
-                bitrate: 500000, 
-                sample_point: CanSamplePoint::try_from(0.7).unwrap(), 
-                fd: true, 
-                data_bitrate: 2000000, 
-                data_sample_point: CanSamplePoint::try_from(0.7).unwrap(), 
-            },
-        };
+        Ok(())
+    }
+}
+
+pub mod update {
+    use std::ops::Not;
+
+    use uuid::Uuid;
+
+    use opendut_carl_api::carl::CarlClient;
+    use opendut_types::peer::PeerId;
+    use opendut_types::util::net::{NetworkInterfaceDescriptor, NetworkInterfaceName};
+
+    use crate::{CreateOutputFormat, DescribeOutputFormat, NetworkInterfaceType};
 
-        if peer_interface_names.contains(&interface_name) {
-                Err(format!("Could not create peer network configuration with name '{}' because it already exists", &interface_name))?
-        } else {
-            peer_descriptor.network_configuration.interfaces.push(
-                NetworkInterfaceDescriptor {
-                    name: interface_name,
-                    configuration: interface_configuration,
-                }
-            );
+    pub async fn execute(
+        carl: &mut CarlClient,
+        peer_id: Uuid,
+        interface_type: NetworkInterfaceType,
+        interface_name: String,
+        output: CreateOutputFormat,
+    ) -> crate::Result<()> {
+        let peer_id = PeerId::from(peer_id);
+
+        let interface_name = NetworkInterfaceName::try_from(interface_name).map_err(|error| error.to_string())?;
+
+        let peer_descriptor = carl.peers.get_peer_descriptor(peer_id).await
+            .map_err(|_| format!("Failed to get peer with ID <{}>.", peer_id))?;
+        if peer_descriptor.network_configuration.interfaces.iter().any(|interface| interface.name == interface_name).not() {
+            Err(format!("Could not update peer network configuration with name '{}' because it does not exist", &interface_name))?
         }
 
-        carl.peers.store_peer_descriptor(Clone::clone(&peer_descriptor)).await
-            .map_err(|error| format!("Failed to update peer <{}>.\n  {}", peer_id, error))?;
+        let network_interface = NetworkInterfaceDescriptor {
+            name: Clone::clone(&interface_name),
+            configuration: super::interface_configuration(interface_type),
+        };
+
+        let peer_descriptor = carl.peers.store_network_interface(peer_id, network_interface).await
+            .map_err(|error| format!("Failed to update network interface '{}'.\n  {}", interface_name, error))?;
         let output_format = DescribeOutputFormat::from(output);
         crate::commands::peer::describe::render_peer_descriptor(peer_descriptor, output_format);
 
@@ -57,7 +99,6 @@ pub mod create {
 }
 
 pub mod delete {
-    use std::collections::HashMap;
     use uuid::Uuid;
 
     use opendut_carl_api::carl::CarlClient;
@@ -67,31 +108,15 @@ pub mod delete {
     pub async fn execute(carl: &mut CarlClient, id: Uuid, network_configuration: Vec<String>) -> crate::Result<()> {
         let id = PeerId::from(id);
 
-        let mut peer = carl.peers
-            .get_peer_descriptor(id)
-            .await
-            .map_err(|error| format!("Failed to get peer with the id '{}'.\n  {}", id, error))?;
-
         let network_interface_names = network_configuration.into_iter()
             .map(NetworkInterfaceName::try_from)
             .collect::<Result<Vec<_>, _>>()
             .map_err(|error| error.to_string())?;
 
-        let mut device_interfaces_map: HashMap<NetworkInterfaceName,Vec<String>> = HashMap::new();
-        for device in peer.topology.devices.clone() {
-            device_interfaces_map.entry(device.interface.name).or_default().push(device.name.to_string());
-        };
-
         for name in network_interface_names {
-            if device_interfaces_map.contains_key(&name) {
-                Err(format!("Network interface '{}' could not be deleted due to it being used in following devices: {}", name,
-                            device_interfaces_map.get(&name).unwrap().join(", ")))?
-            }
-            peer.network_configuration.interfaces.retain(|interface| interface.name.name() != name.name())
-        };
-
-        carl.peers.store_peer_descriptor(peer).await
-            .map_err(|error| format!("Failed to delete network interfaces for peer.\n  {}", error))?;
+            carl.peers.delete_network_interface(id, Clone::clone(&name)).await
+                .map_err(|error| format!("Network interface '{}' could not be deleted.\n  {}", name, error))?;
+        }
 
         Ok(())
     }
@@ -118,7 +118,8 @@ pub mod list {
                 topology: Default::default(),
                 executors: ExecutorDescriptors {
                     executors: vec![]
-                }
+                },
+                revision: 0,
             }];
             let connected_peers = vec![all_peers[0].id];
             assert_that!(
@@ -270,7 +271,8 @@ pub mod create {
             topology: Default::default(),
             executors: ExecutorDescriptors {
                 executors: vec![],
-            }
+            },
+            revision: 0,
         };
         carl.peers
             .store_peer_descriptor(descriptor.clone())
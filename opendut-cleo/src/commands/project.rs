@@ -0,0 +1,99 @@
+pub mod list {
+    use cli_table::{print_stdout, Table, WithTitle};
+
+    use opendut_carl_api::carl::CarlClient;
+    use opendut_types::project::{ProjectId, ProjectName};
+
+    use crate::ListOutputFormat;
+
+    #[derive(Table)]
+    struct ProjectTable {
+        #[table(title = "Name")]
+        name: ProjectName,
+        #[table(title = "ProjectID")]
+        id: ProjectId,
+        #[table(title = "Selected")]
+        selected: String,
+    }
+
+    pub async fn execute(carl: &mut CarlClient, output: ListOutputFormat) -> crate::Result<()> {
+        let projects = carl.projects.list_projects().await
+            .map_err(|error| format!("Could not list projects.\n  {}", error))?;
+
+        match output {
+            ListOutputFormat::Table => {
+                let selected = carl.project.selected().unwrap_or_default();
+                let project_table = projects.into_iter()
+                    .map(|project| ProjectTable {
+                        selected: if project.id == selected { String::from("*") } else { String::new() },
+                        name: project.name,
+                        id: project.id,
+                    })
+                    .collect::<Vec<_>>();
+                print_stdout(project_table.with_title())
+                    .expect("List of projects should be printable as table.");
+            }
+            ListOutputFormat::Json => {
+                let json = serde_json::to_string(&projects).unwrap();
+                println!("{}", json);
+            }
+            ListOutputFormat::PrettyJson => {
+                let json = serde_json::to_string_pretty(&projects).unwrap();
+                println!("{}", json);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub mod create {
+    use uuid::Uuid;
+
+    use opendut_carl_api::carl::CarlClient;
+    use opendut_types::project::{ProjectDescriptor, ProjectId, ProjectName};
+
+    use crate::CreateOutputFormat;
+
+    pub async fn execute(carl: &mut CarlClient, name: String, id: Option<Uuid>, output: CreateOutputFormat) -> crate::Result<()> {
+        let name = ProjectName::try_from(name)
+            .map_err(|error| format!("Could not create project.\n  {}", error))?;
+        let id = id.map(ProjectId::from).unwrap_or_else(ProjectId::random);
+
+        let project = ProjectDescriptor { id, name: Clone::clone(&name) };
+
+        carl.projects.create_project(Clone::clone(&project)).await
+            .map_err(|error| format!("Failed to create new project.\n  {}", error))?;
+
+        match output {
+            CreateOutputFormat::Text => {
+                println!("Created the project '{}' with the ID: <{}>", name, id);
+            }
+            CreateOutputFormat::Json => {
+                let json = serde_json::to_string(&project).unwrap();
+                println!("{}", json);
+            }
+            CreateOutputFormat::PrettyJson => {
+                let json = serde_json::to_string_pretty(&project).unwrap();
+                println!("{}", json);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub mod delete {
+    use opendut_carl_api::carl::CarlClient;
+
+    pub async fn execute(carl: &mut CarlClient, name_or_id: String) -> crate::Result<()> {
+        let id = carl.projects.resolve_project(&name_or_id).await
+            .map_err(|error| format!("Failed to delete project '{}'.\n  {}", name_or_id, error))?;
+
+        carl.projects.delete_project(id).await
+            .map_err(|error| format!("Failed to delete project with the id '{}'.\n  {}", id, error))?;
+        println!("Deleted project with the ProjectID: {}", id);
+
+        Ok(())
+    }
+}
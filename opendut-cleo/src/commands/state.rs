@@ -0,0 +1,88 @@
+pub mod export {
+    use std::path::PathBuf;
+
+    use opendut_carl_api::carl::CarlClient;
+
+    pub async fn execute(carl: &mut CarlClient, file: PathBuf) -> crate::Result<()> {
+
+        let archive = carl.state.export_state().await
+            .map_err(|error| format!("Could not export the state of CARL.\n  {}", error))?;
+
+        let json = archive.to_json()
+            .map_err(|error| format!("Could not serialize the exported state.\n  {}", error))?;
+        std::fs::write(&file, json)
+            .map_err(|error| format!("Could not write the exported state to '{}'.\n  {}", file.display(), error))?;
+
+        println!(
+            "Exported {} peer(s), {} cluster configuration(s) and {} cluster deployment(s) to '{}'.",
+            archive.peers.len(), archive.clusters.len(), archive.deployments.len(), file.display()
+        );
+        println!("Values of secret environment variables of executors are not exported. When importing into another instance of CARL or under new IDs, these variables are removed and have to be added again via `opendut-cleo update container-executor`.");
+        Ok(())
+    }
+}
+
+pub mod import {
+    use std::path::PathBuf;
+
+    use cli_table::{print_stdout, Table, WithTitle};
+
+    use opendut_carl_api::carl::CarlClient;
+    use opendut_carl_api::carl::state::{ImportOutcome, StateArchive};
+
+    use crate::{ImportStrategy, ListOutputFormat};
+
+    #[derive(Table)]
+    struct ImportedResourceTable {
+        #[table(title = "Kind")]
+        kind: String,
+        #[table(title = "ID")]
+        id: String,
+        #[table(title = "Name")]
+        name: String,
+        #[table(title = "Outcome")]
+        outcome: String,
+    }
+
+    pub async fn execute(carl: &mut CarlClient, file: PathBuf, strategy: ImportStrategy, output: ListOutputFormat) -> crate::Result<()> {
+
+        let json = std::fs::read_to_string(&file)
+            .map_err(|error| format!("Could not read the archive '{}'.\n  {}", file.display(), error))?;
+        let archive = StateArchive::from_json(&json)
+            .map_err(|error| format!("Could not read the archive '{}'.\n  {}", file.display(), error))?;
+
+        let resources = carl.state.import_state(archive, strategy.into()).await
+            .map_err(|error| format!("Could not import the archive '{}'.\n  {}", file.display(), error))?;
+
+        match output {
+            ListOutputFormat::Table => {
+                let imported_resource_table = resources.iter()
+                    .map(|resource| ImportedResourceTable {
+                        kind: resource.kind.to_string(),
+                        id: resource.id.to_string(),
+                        name: Clone::clone(&resource.name),
+                        outcome: resource.outcome.to_string(),
+                    })
+                    .collect::<Vec<_>>();
+                print_stdout(imported_resource_table.with_title())
+                    .expect("List of imported resources should be printable as table.");
+            }
+            ListOutputFormat::Json => {
+                let json = serde_json::to_string(&resources).unwrap();
+                println!("{}", json);
+            }
+            ListOutputFormat::PrettyJson => {
+                let json = serde_json::to_string_pretty(&resources).unwrap();
+                println!("{}", json);
+            }
+        }
+
+        let failed = resources.iter()
+            .filter(|resource| matches!(resource.outcome, ImportOutcome::Failed { .. }))
+            .count();
+        if failed > 0 {
+            Err(format!("{failed} of {} resource(s) could not be imported.", resources.len()))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use crossterm::event::KeyCode;
+
+use opendut_types::cluster::{ClusterConfiguration, ClusterDeployment, ClusterId};
+use opendut_types::cluster::operation::DeploymentOperation;
+use opendut_types::peer::{PeerDescriptor, PeerId};
+use opendut_types::peer::state::PeerState;
+use opendut_types::topology::DeviceDescriptor;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Tab {
+    #[default]
+    Peers,
+    Clusters,
+    Devices,
+}
+
+impl Tab {
+    pub const ALL: [Tab; 3] = [Tab::Peers, Tab::Clusters, Tab::Devices];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Tab::Peers => "Peers",
+            Tab::Clusters => "Clusters",
+            Tab::Devices => "Devices",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        Tab::ALL.iter()
+            .position(|tab| *tab == self)
+            .expect("Every tab should be listed.")
+    }
+
+    fn next(self) -> Tab {
+        Tab::ALL[(self.index() + 1) % Tab::ALL.len()]
+    }
+
+    fn previous(self) -> Tab {
+        Tab::ALL[(self.index() + Tab::ALL.len() - 1) % Tab::ALL.len()]
+    }
+}
+
+/// An operation, which the user triggered and which has to be carried out against CARL.
+// TODO: Add tailing the logs of an executor, once EDGAR reports these logs to CARL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    Refresh,
+    Deploy(ClusterId),
+    Undeploy(ClusterId),
+}
+
+/// The resources retrieved from CARL during the last refresh.
+#[derive(Clone, Debug, Default)]
+pub struct Snapshot {
+    pub peers: Vec<PeerDescriptor>,
+    pub peer_states: HashMap<PeerId, PeerState>,
+    pub clusters: Vec<ClusterConfiguration>,
+    pub deployments: Vec<ClusterDeployment>,
+    pub operations: Vec<DeploymentOperation>,
+}
+
+impl Snapshot {
+    pub fn peer_state(&self, peer_id: PeerId) -> PeerState {
+        self.peer_states.get(&peer_id)
+            .cloned()
+            .unwrap_or(PeerState::Down)
+    }
+
+    pub fn peer_name(&self, peer_id: PeerId) -> String {
+        self.peers.iter()
+            .find(|peer| peer.id == peer_id)
+            .map(|peer| peer.name.to_string())
+            .unwrap_or_else(|| peer_id.to_string())
+    }
+
+    pub fn deployment_state(&self, cluster_id: ClusterId) -> String {
+        let Some(deployment) = self.deployments.iter().find(|deployment| deployment.id == cluster_id) else {
+            return String::from("Not deployed");
+        };
+        if deployment.start.is_some_and(|start| start > Utc::now()) {
+            return String::from("Scheduled");
+        }
+        self.operations.iter()
+            .rev()
+            .find(|operation| operation.cluster_id == cluster_id)
+            .map(|operation| operation.state.to_string())
+            .unwrap_or_else(|| String::from("Deployed"))
+    }
+
+    pub fn devices(&self) -> Vec<(&PeerDescriptor, &DeviceDescriptor)> {
+        self.peers.iter()
+            .flat_map(|peer| peer.topology.devices.iter().map(move |device| (peer, device)))
+            .collect()
+    }
+
+    fn len(&self, tab: Tab) -> usize {
+        match tab {
+            Tab::Peers => self.peers.len(),
+            Tab::Clusters => self.clusters.len(),
+            Tab::Devices => self.devices().len(),
+        }
+    }
+}
+
+pub struct App {
+    pub tab: Tab,
+    pub snapshot: Snapshot,
+    /// The index of the selected row per tab.
+    selection: [usize; 3],
+    /// Details of the selected resource, shown on top of the table.
+    pub details: Option<String>,
+    /// An action, which is only carried out after the user confirmed it.
+    pub confirmation: Option<(Action, String)>,
+    /// The outcome of the last action.
+    pub status: Option<Result<String, String>>,
+    running: bool,
+}
+
+impl App {
+    pub fn new() -> Self {
+        Self {
+            tab: Tab::default(),
+            snapshot: Snapshot::default(),
+            selection: [0; 3],
+            details: None,
+            confirmation: None,
+            status: None,
+            running: true,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selection[self.tab.index()]
+    }
+
+    pub fn update(&mut self, snapshot: Snapshot) {
+        self.snapshot = snapshot;
+        for tab in Tab::ALL {
+            let last = self.snapshot.len(tab).saturating_sub(1);
+            self.selection[tab.index()] = self.selection[tab.index()].min(last);
+        }
+    }
+
+    pub fn set_status(&mut self, status: Result<String, String>) {
+        self.status = Some(status);
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> Option<Action> {
+        if let Some((action, _)) = self.confirmation.take() {
+            return matches!(code, KeyCode::Char('y') | KeyCode::Char('Y'))
+                .then_some(action);
+        }
+        if self.details.is_some() {
+            if matches!(code, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q')) {
+                self.details = None;
+            }
+            return None;
+        }
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.running = false;
+                None
+            }
+            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => {
+                self.tab = self.tab.next();
+                None
+            }
+            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => {
+                self.tab = self.tab.previous();
+                None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                let last = self.snapshot.len(self.tab).saturating_sub(1);
+                let selected = &mut self.selection[self.tab.index()];
+                *selected = (*selected + 1).min(last);
+                None
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                let selected = &mut self.selection[self.tab.index()];
+                *selected = selected.saturating_sub(1);
+                None
+            }
+            KeyCode::Enter => {
+                self.details = self.describe_selected();
+                None
+            }
+            KeyCode::Char('r') => Some(Action::Refresh),
+            KeyCode::Char('d') => self.selected_cluster().map(|cluster| Action::Deploy(cluster.id)),
+            KeyCode::Char('u') => {
+                self.confirmation = self.selected_cluster()
+                    .map(|cluster| (Action::Undeploy(cluster.id), format!("Undeploy cluster '{}' <{}>? (y/n)", cluster.name, cluster.id)));
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn selected_cluster(&self) -> Option<&ClusterConfiguration> {
+        match self.tab {
+            Tab::Clusters => self.snapshot.clusters.get(self.selected()),
+            _ => None,
+        }
+    }
+
+    fn describe_selected(&self) -> Option<String> {
+        let selected = self.selected();
+        match self.tab {
+            Tab::Peers => self.snapshot.peers.get(selected)
+                .map(|peer| serde_json::to_string_pretty(peer).unwrap()),
+            Tab::Clusters => self.snapshot.clusters.get(selected)
+                .map(|cluster| serde_json::to_string_pretty(cluster).unwrap()),
+            Tab::Devices => self.snapshot.devices().get(selected)
+                .map(|(_, device)| serde_json::to_string_pretty(device).unwrap()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use googletest::prelude::*;
+
+    use opendut_types::cluster::ClusterName;
+
+    use super::*;
+
+    fn cluster(name: &str) -> ClusterConfiguration {
+        ClusterConfiguration {
+            id: ClusterId::random(),
+            name: ClusterName::try_from(name).unwrap(),
+            leader: PeerId::random(),
+            devices: HashSet::new(),
+            revision: 0,
+        }
+    }
+
+    #[test]
+    fn should_deploy_and_undeploy_the_selected_cluster() {
+        let mut app = App::new();
+        let clusters = vec![cluster("first"), cluster("second")];
+        app.update(Snapshot { clusters: Clone::clone(&clusters), ..Default::default() });
+
+        assert_that!(app.handle_key(KeyCode::Char('d')), none());
+
+        app.handle_key(KeyCode::Tab);
+        assert_that!(app.tab, eq(Tab::Clusters));
+
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Down);
+        assert_that!(app.selected(), eq(1));
+        assert_that!(app.handle_key(KeyCode::Char('d')), some(eq(Action::Deploy(clusters[1].id))));
+
+        app.handle_key(KeyCode::Up);
+        assert_that!(app.handle_key(KeyCode::Char('u')), none());
+        assert_that!(app.confirmation.as_ref().map(|(_, question)| question.as_str()), some(contains_substring("first")));
+        assert_that!(app.handle_key(KeyCode::Char('y')), some(eq(Action::Undeploy(clusters[0].id))));
+        assert_that!(app.confirmation, none());
+    }
+
+    #[test]
+    fn should_not_undeploy_without_confirmation() {
+        let mut app = App::new();
+        app.update(Snapshot { clusters: vec![cluster("first")], ..Default::default() });
+        app.handle_key(KeyCode::Tab);
+
+        assert_that!(app.handle_key(KeyCode::Char('u')), none());
+        assert_that!(app.handle_key(KeyCode::Char('n')), none());
+        assert_that!(app.confirmation, none());
+        assert_that!(app.is_running(), eq(true));
+    }
+
+    #[test]
+    fn should_close_the_details_before_quitting() {
+        let mut app = App::new();
+        app.update(Snapshot { clusters: vec![cluster("first")], ..Default::default() });
+        app.handle_key(KeyCode::BackTab);
+        app.handle_key(KeyCode::BackTab);
+        assert_that!(app.tab, eq(Tab::Clusters));
+
+        app.handle_key(KeyCode::Enter);
+        assert_that!(app.details, some(contains_substring("first")));
+
+        app.handle_key(KeyCode::Esc);
+        assert_that!(app.details, none());
+        assert_that!(app.is_running(), eq(true));
+
+        app.handle_key(KeyCode::Char('q'));
+        assert_that!(app.is_running(), eq(false));
+    }
+
+    #[test]
+    fn should_keep_the_selection_within_the_refreshed_resources() {
+        let mut app = App::new();
+        app.update(Snapshot { clusters: vec![cluster("first"), cluster("second")], ..Default::default() });
+        app.handle_key(KeyCode::Tab);
+        app.handle_key(KeyCode::Down);
+        assert_that!(app.selected(), eq(1));
+
+        app.update(Snapshot { clusters: vec![cluster("first")], ..Default::default() });
+        assert_that!(app.selected(), eq(0));
+    }
+}
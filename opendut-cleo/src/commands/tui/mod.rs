@@ -0,0 +1,114 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyEventKind};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::CrosstermBackend;
+use ratatui::Terminal;
+
+use opendut_carl_api::carl::CarlClient;
+use opendut_types::cluster::ClusterDeployment;
+
+use crate::commands::tui::app::{Action, App, Snapshot};
+
+mod app;
+mod ui;
+
+/// How often the displayed resources are retrieved from CARL.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+const INPUT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+pub async fn execute(carl: &mut CarlClient) -> crate::Result<()> {
+    enable_raw_mode()
+        .and_then(|_| execute!(io::stdout(), EnterAlternateScreen))
+        .map_err(|cause| format!("Failed to prepare the terminal.\n  {cause}"))?;
+
+    let panic_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        panic_hook(info);
+    }));
+
+    let result = run(carl).await;
+
+    let restored = restore_terminal()
+        .map_err(|cause| format!("Failed to restore the terminal.\n  {cause}"));
+
+    result.and(restored)
+}
+
+/// Leaves the raw mode and the alternate screen. Also called on panics, so that the panic message stays readable.
+fn restore_terminal() -> io::Result<()> {
+    disable_raw_mode()
+        .and_then(|_| execute!(io::stdout(), LeaveAlternateScreen))
+}
+
+async fn run(carl: &mut CarlClient) -> crate::Result<()> {
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))
+        .map_err(|cause| format!("Failed to open the terminal.\n  {cause}"))?;
+
+    let mut app = App::new();
+    let mut last_refresh: Option<Instant> = None;
+
+    while app.is_running() {
+        if last_refresh.map_or(true, |refreshed| refreshed.elapsed() >= REFRESH_INTERVAL) {
+            match fetch_snapshot(carl).await {
+                Ok(snapshot) => app.update(snapshot),
+                Err(error) => app.set_status(Err(error)),
+            }
+            last_refresh = Some(Instant::now());
+        }
+
+        terminal.draw(|frame| ui::render(frame, &app))
+            .map_err(|cause| format!("Failed to draw the terminal.\n  {cause}"))?;
+
+        let has_input = event::poll(INPUT_POLL_INTERVAL)
+            .map_err(|cause| format!("Failed to read from the terminal.\n  {cause}"))?;
+        if has_input {
+            let event = event::read()
+                .map_err(|cause| format!("Failed to read from the terminal.\n  {cause}"))?;
+            if let Event::Key(key) = event {
+                if key.kind == KeyEventKind::Press {
+                    if let Some(action) = app.handle_key(key.code) {
+                        let status = perform(carl, action).await;
+                        app.set_status(status);
+                        last_refresh = None;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn perform(carl: &mut CarlClient, action: Action) -> Result<String, String> {
+    match action {
+        Action::Refresh => Ok(String::from("Refreshed.")),
+        Action::Deploy(cluster_id) => {
+            carl.cluster.store_cluster_deployment(ClusterDeployment::new(cluster_id)).await
+                .map(|operation| format!("Deploying cluster <{cluster_id}>. Deployment is tracked by operation <{}>.", operation.id))
+                .map_err(|error| format!("Could not deploy cluster <{cluster_id}>: {error}"))
+        }
+        Action::Undeploy(cluster_id) => {
+            carl.cluster.delete_cluster_deployment(cluster_id).await
+                .map(|_| format!("Undeploying cluster <{cluster_id}>."))
+                .map_err(|error| format!("Could not undeploy cluster <{cluster_id}>: {error}"))
+        }
+    }
+}
+
+async fn fetch_snapshot(carl: &mut CarlClient) -> crate::Result<Snapshot> {
+    let peers = carl.peers.list_peer_descriptors().await
+        .map_err(|error| format!("Could not list peers: {error}"))?;
+    let peer_states = carl.peers.list_peer_states().await
+        .map_err(|error| format!("Could not list peer states: {error}"))?;
+    let clusters = carl.cluster.list_cluster_configurations().await
+        .map_err(|error| format!("Could not list cluster configurations: {error}"))?;
+    let deployments = carl.cluster.list_cluster_deployments().await
+        .map_err(|error| format!("Could not list cluster deployments: {error}"))?;
+    let operations = carl.cluster.list_deployment_operations(None).await
+        .map_err(|error| format!("Could not list deployment operations: {error}"))?;
+
+    Ok(Snapshot { peers, peer_states, clusters, deployments, operations })
+}
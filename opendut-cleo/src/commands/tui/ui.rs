@@ -0,0 +1,120 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Row, Table, TableState, Tabs, Wrap};
+
+use opendut_types::ShortName;
+
+use crate::commands::tui::app::{App, Tab};
+
+const HELP: &str = "q: quit | tab: switch view | up/down: select | enter: details | d: deploy | u: undeploy | r: refresh";
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let [tabs_area, table_area, status_area, help_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(0),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ]).areas(frame.size());
+
+    let tabs = Tabs::new(Tab::ALL.map(Tab::title))
+        .select(app.tab.index())
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD).fg(Color::Yellow))
+        .block(Block::default().borders(Borders::ALL).title("openDuT"));
+    frame.render_widget(tabs, tabs_area);
+
+    let (header, rows, widths) = match app.tab {
+        Tab::Peers => peer_rows(app),
+        Tab::Clusters => cluster_rows(app),
+        Tab::Devices => device_rows(app),
+    };
+    let table = Table::new(rows, widths)
+        .header(Row::new(header).style(Style::default().add_modifier(Modifier::BOLD)))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(Block::default().borders(Borders::ALL).title(app.tab.title()));
+    let mut table_state = TableState::default().with_selected(Some(app.selected()));
+    frame.render_stateful_widget(table, table_area, &mut table_state);
+
+    let status = match &app.status {
+        Some(Ok(message)) => Paragraph::new(message.as_str()).style(Style::default().fg(Color::Green)),
+        Some(Err(message)) => Paragraph::new(message.as_str()).style(Style::default().fg(Color::Red)),
+        None => Paragraph::default(),
+    };
+    frame.render_widget(status, status_area);
+    frame.render_widget(Paragraph::new(HELP).style(Style::default().fg(Color::DarkGray)), help_area);
+
+    if let Some((_, question)) = &app.confirmation {
+        frame.render_widget(Paragraph::new(question.as_str()).style(Style::default().fg(Color::Yellow)), status_area);
+    }
+
+    if let Some(details) = &app.details {
+        let area = centered(frame.size());
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(details.as_str())
+                .wrap(Wrap { trim: false })
+                .block(Block::default().borders(Borders::ALL).title("Details (esc to close)")),
+            area,
+        );
+    }
+}
+
+type TableContent = (Vec<&'static str>, Vec<Row<'static>>, Vec<Constraint>);
+
+fn peer_rows(app: &App) -> TableContent {
+    let snapshot = &app.snapshot;
+    let rows = snapshot.peers.iter()
+        .map(|peer| Row::new(vec![
+            peer.name.to_string(),
+            peer.id.to_string(),
+            peer.location.clone().unwrap_or_default().to_string(),
+            snapshot.peer_state(peer.id).short_name().to_owned(),
+            peer.topology.devices.len().to_string(),
+        ]))
+        .collect();
+    let widths = vec![Constraint::Percentage(20), Constraint::Length(36), Constraint::Percentage(20), Constraint::Length(12), Constraint::Length(8)];
+    (vec!["Name", "ID", "Location", "State", "Devices"], rows, widths)
+}
+
+fn cluster_rows(app: &App) -> TableContent {
+    let snapshot = &app.snapshot;
+    let rows = snapshot.clusters.iter()
+        .map(|cluster| Row::new(vec![
+            cluster.name.to_string(),
+            cluster.id.to_string(),
+            snapshot.peer_name(cluster.leader),
+            cluster.devices.len().to_string(),
+            snapshot.deployment_state(cluster.id),
+        ]))
+        .collect();
+    let widths = vec![Constraint::Percentage(20), Constraint::Length(36), Constraint::Percentage(20), Constraint::Length(8), Constraint::Min(12)];
+    (vec!["Name", "ID", "Leader", "Devices", "Deployment"], rows, widths)
+}
+
+fn device_rows(app: &App) -> TableContent {
+    let rows = app.snapshot.devices().into_iter()
+        .map(|(peer, device)| Row::new(vec![
+            device.name.to_string(),
+            device.id.to_string(),
+            peer.name.to_string(),
+            device.interface.to_string(),
+            device.tags.iter().map(|tag| tag.value()).collect::<Vec<_>>().join(", "),
+        ]))
+        .collect();
+    let widths = vec![Constraint::Percentage(20), Constraint::Length(36), Constraint::Percentage(15), Constraint::Percentage(20), Constraint::Min(10)];
+    (vec!["Name", "ID", "Peer", "Interface", "Tags"], rows, widths)
+}
+
+fn centered(area: Rect) -> Rect {
+    let [_, vertical, _] = Layout::vertical([
+        Constraint::Percentage(10),
+        Constraint::Percentage(80),
+        Constraint::Percentage(10),
+    ]).areas(area);
+    let [_, centered, _] = Layout::horizontal([
+        Constraint::Percentage(10),
+        Constraint::Percentage(80),
+        Constraint::Percentage(10),
+    ]).areas(vertical);
+    centered
+}
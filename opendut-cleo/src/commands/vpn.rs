@@ -0,0 +1,54 @@
+pub mod reconcile {
+    use cli_table::{print_stdout, Table, WithTitle};
+
+    use opendut_carl_api::carl::CarlClient;
+
+    use crate::ListOutputFormat;
+
+    #[derive(Table)]
+    struct VpnDriftTable {
+        #[table(title = "Drift")]
+        kind: String,
+        #[table(title = "Resource")]
+        resource: String,
+        #[table(title = "Action")]
+        action: String,
+        #[table(title = "Outcome")]
+        outcome: String,
+    }
+
+    pub async fn execute(carl: &mut CarlClient, dry_run: bool, output: ListOutputFormat) -> crate::Result<()> {
+
+        let report = carl.vpn.reconcile(dry_run).await
+            .map_err(|error| format!("Error while reconciling the VPN: {}", error))?;
+
+        match output {
+            ListOutputFormat::Table => {
+                if report.is_in_sync() {
+                    println!("The VPN is in sync with CARL.");
+                    return Ok(());
+                }
+                let drift_table = report.drifts.into_iter()
+                    .map(|drift| VpnDriftTable {
+                        kind: drift.kind.to_string(),
+                        resource: drift.resource,
+                        action: drift.action,
+                        outcome: drift.outcome.to_string(),
+                    })
+                    .collect::<Vec<_>>();
+                print_stdout(drift_table.with_title())
+                    .expect("List of VPN drifts should be printable as table.");
+            }
+            ListOutputFormat::Json => {
+                let json = serde_json::to_string(&report).unwrap();
+                println!("{}", json);
+            }
+            ListOutputFormat::PrettyJson => {
+                let json = serde_json::to_string_pretty(&report).unwrap();
+                println!("{}", json);
+            }
+        }
+
+        Ok(())
+    }
+}
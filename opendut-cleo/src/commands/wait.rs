@@ -0,0 +1,349 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// Exit code of CLEO, if the awaited condition was not reached in time. Matches the exit code of `timeout` from GNU coreutils.
+pub const TIMEOUT_EXIT_CODE: i32 = 124;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Maximum duration of a single check, so that an unresponsive CARL cannot delay waiting beyond the timeout.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of consecutive failed checks, after which CLEO gives up, when waiting without timeout.
+const MAX_CONSECUTIVE_FAILURES: u32 = 10;
+
+pub enum WaitOutcome {
+    Reached,
+    TimedOut { cause: String },
+}
+
+/// The result of checking once, whether the awaited condition is reached.
+#[derive(Debug)]
+enum Check<T> {
+    Reached(T),
+    /// The condition is not reached yet, for the given reason.
+    Pending(String),
+}
+
+enum CheckError {
+    /// The check could not be carried out, e.g. because CARL is restarting. It is repeated.
+    Transient(String),
+    /// The condition cannot be reached anymore.
+    Fatal(String),
+}
+
+/// Repeats checks until the timeout expires, limiting the duration of each check.
+struct Poller {
+    timeout: Option<Duration>,
+    deadline: Option<Instant>,
+    consecutive_failures: u32,
+}
+
+impl Poller {
+    fn new(timeout: Option<Duration>) -> Self {
+        Self {
+            timeout,
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+            consecutive_failures: 0,
+        }
+    }
+
+    /// Runs a single check. Transient errors and checks exceeding their time limit are reported as pending,
+    /// unless they keep failing while waiting without timeout.
+    async fn check<T>(&mut self, check: impl Future<Output=Result<Check<T>, CheckError>>) -> crate::Result<Check<T>> {
+        let limit = self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()).min(CHECK_TIMEOUT))
+            .unwrap_or(CHECK_TIMEOUT);
+
+        let cause = match tokio::time::timeout(limit, check).await {
+            Ok(Ok(check)) => {
+                self.consecutive_failures = 0;
+                return Ok(check);
+            }
+            Ok(Err(CheckError::Fatal(cause))) => return Err(cause),
+            Ok(Err(CheckError::Transient(cause))) => cause,
+            Err(_) => format!("CARL did not respond within {}.", humantime::format_duration(limit)),
+        };
+
+        self.consecutive_failures += 1;
+        if self.timeout.is_none() && self.consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+            Err(format!("Giving up after {} failed attempts.
+  {}", self.consecutive_failures, cause))?
+        }
+        eprintln!("{cause} Retrying...");
+        Ok(Check::Pending(cause))
+    }
+
+    fn is_expired(&self) -> bool {
+        self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    fn format_timeout(&self) -> String {
+        self.timeout.map(|timeout| humantime::format_duration(timeout).to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Limits requests made before polling starts to the duration of a single check.
+async fn within_check_timeout<T>(request: impl Future<Output=crate::Result<T>>) -> crate::Result<T> {
+    tokio::time::timeout(CHECK_TIMEOUT, request).await
+        .map_err(|_| format!("CARL did not respond within {}.", humantime::format_duration(CHECK_TIMEOUT)))?
+}
+
+pub mod peer {
+    use std::time::Duration;
+
+    use uuid::Uuid;
+
+    use opendut_carl_api::carl::CarlClient;
+    use opendut_types::peer::PeerId;
+    use opendut_types::peer::state::{PeerBlockedState, PeerState, PeerUpState};
+    use opendut_types::ShortName;
+
+    use crate::WaitPeerState;
+
+    use super::*;
+
+    pub async fn execute(carl: &mut CarlClient, id: Uuid, state: WaitPeerState, timeout: Option<Duration>) -> crate::Result<WaitOutcome> {
+
+        let id = PeerId::from(id);
+
+        let peer = within_check_timeout(async {
+            carl.peers.get_peer_descriptor(id).await
+                .map_err(|error| format!("Could not wait for peer <{}>.\n  {}", id, error))
+        }).await?;
+        let mut poller = Poller::new(timeout);
+
+        loop {
+            match poller.check(check(carl, id, state)).await? {
+                Check::Reached(actual_state) => {
+                    println!("Peer '{}' <{}> is {}.", peer.name, id, actual_state);
+                    return Ok(WaitOutcome::Reached);
+                }
+                Check::Pending(cause) if poller.is_expired() => {
+                    return Ok(WaitOutcome::TimedOut {
+                        cause: format!("Peer '{}' <{}> did not become {:?} within {}. {}", peer.name, id, state, poller.format_timeout(), cause),
+                    });
+                }
+                Check::Pending(_) => tokio::time::sleep(POLL_INTERVAL).await,
+            }
+        }
+    }
+
+    async fn check(carl: &mut CarlClient, id: PeerId, state: WaitPeerState) -> Result<Check<&'static str>, CheckError> {
+        let actual_state = carl.peers.list_peer_states().await
+            .map_err(|error| CheckError::Transient(format!("Could not get the state of peer <{}>.\n  {}", id, error)))?
+            .remove(&id)
+            .unwrap_or_default();
+
+        if state.matches(&actual_state) {
+            Ok(Check::Reached(actual_state.short_name()))
+        } else {
+            Ok(Check::Pending(format!("It is {}.", actual_state.short_name())))
+        }
+    }
+
+    impl WaitPeerState {
+        fn matches(&self, state: &PeerState) -> bool {
+            matches!((self, state),
+                (WaitPeerState::Down, PeerState::Down)
+                | (WaitPeerState::Up, PeerState::Up { .. })
+                | (WaitPeerState::Available, PeerState::Up { inner: PeerUpState::Available, .. })
+                | (WaitPeerState::Blocked, PeerState::Up { inner: PeerUpState::Blocked(_), .. })
+                | (WaitPeerState::Deploying, PeerState::Up { inner: PeerUpState::Blocked(PeerBlockedState::Deploying), .. })
+                | (WaitPeerState::Member, PeerState::Up { inner: PeerUpState::Blocked(PeerBlockedState::Member), .. })
+                | (WaitPeerState::Undeploying, PeerState::Up { inner: PeerUpState::Blocked(PeerBlockedState::Undeploying), .. })
+            )
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::net::{IpAddr, Ipv4Addr};
+
+        use googletest::prelude::*;
+
+        use super::*;
+
+        #[test]
+        fn should_match_peer_states_like_device_queries() -> Result<()> {
+            let member = PeerState::Up { inner: PeerUpState::Blocked(PeerBlockedState::Member), remote_host: IpAddr::V4(Ipv4Addr::LOCALHOST) };
+
+            verify_that!(WaitPeerState::Up.matches(&member), eq(true))?;
+            verify_that!(WaitPeerState::Blocked.matches(&member), eq(true))?;
+            verify_that!(WaitPeerState::Member.matches(&member), eq(true))?;
+            verify_that!(WaitPeerState::Available.matches(&member), eq(false))?;
+            verify_that!(WaitPeerState::Down.matches(&PeerState::Down), eq(true))
+        }
+    }
+}
+
+pub mod cluster {
+    use std::collections::HashSet;
+    use std::ops::Not;
+    use std::time::Duration;
+
+    use uuid::Uuid;
+
+    use opendut_carl_api::carl::CarlClient;
+    use opendut_types::cluster::{ClusterId, ClusterName};
+    use opendut_types::cluster::operation::{DeploymentOperation, DeploymentOperationError, DeploymentOperationId, DeploymentOperationState};
+
+    use super::*;
+
+    #[derive(Clone, Copy, Debug)]
+    pub enum ClusterCondition {
+        /// The cluster is deployed, its deployment finished and CARL finds no issues with its peers.
+        Healthy,
+        /// The cluster is not deployed and no deployment operation is running anymore.
+        Undeployed,
+    }
+
+    pub async fn execute(carl: &mut CarlClient, id: Uuid, condition: ClusterCondition, timeout: Option<Duration>) -> crate::Result<WaitOutcome> {
+
+        let id = ClusterId::from(id);
+
+        let (configuration, operations) = within_check_timeout(async {
+            let configuration = carl.cluster.get_cluster_configuration(id).await
+                .map_err(|error| format!("Could not wait for cluster <{}>.\n  {}", id, error))?;
+            let operations = list_operations(carl, id).await?;
+            Ok((configuration, operations))
+        }).await?;
+        let name = configuration.name;
+
+        let initially_finished = operations.into_iter()
+            .filter(DeploymentOperation::is_finished)
+            .map(|operation| operation.id)
+            .collect::<HashSet<_>>();
+        let mut poller = Poller::new(timeout);
+
+        loop {
+            match poller.check(check(carl, id, &name, condition, &initially_finished)).await? {
+                Check::Reached(()) => {
+                    match condition {
+                        ClusterCondition::Healthy => println!("Cluster '{}' <{}> is healthy.", name, id),
+                        ClusterCondition::Undeployed => println!("Cluster '{}' <{}> is undeployed.", name, id),
+                    }
+                    return Ok(WaitOutcome::Reached);
+                }
+                Check::Pending(cause) if poller.is_expired() => {
+                    let condition = match condition {
+                        ClusterCondition::Healthy => "healthy",
+                        ClusterCondition::Undeployed => "undeployed",
+                    };
+                    return Ok(WaitOutcome::TimedOut {
+                        cause: format!("Cluster '{}' <{}> did not become {} within {}.\n  {}", name, id, condition, poller.format_timeout(), cause),
+                    });
+                }
+                Check::Pending(_) => tokio::time::sleep(POLL_INTERVAL).await,
+            }
+        }
+    }
+
+    async fn check(carl: &mut CarlClient, id: ClusterId, name: &ClusterName, condition: ClusterCondition, initially_finished: &HashSet<DeploymentOperationId>) -> Result<Check<()>, CheckError> {
+        let operations = list_operations(carl, id).await
+            .map_err(CheckError::Transient)?;
+        if let Some((operation, error)) = failed_operation(&operations, initially_finished) {
+            return Err(CheckError::Fatal(format!("Deployment operation <{}> of cluster '{}' <{}> failed.\n  {}", operation, name, id, error)));
+        }
+
+        let deployed = carl.cluster.list_cluster_deployments().await
+            .map_err(|error| CheckError::Transient(format!("Could not list cluster deployments.\n  {}", error)))?
+            .iter()
+            .any(|deployment| deployment.id == id);
+
+        let pending = if operations.iter().any(|operation| operation.is_finished().not()) {
+            Some(String::from("Its deployment is still running."))
+        } else {
+            match condition {
+                ClusterCondition::Healthy if deployed.not() => Some(String::from("It is not deployed.")),
+                ClusterCondition::Healthy => {
+                    let validation = carl.cluster.validate_cluster_deployment(id).await
+                        .map_err(|error| CheckError::Transient(format!("Could not check the health of cluster <{}>.\n  {}", id, error)))?;
+                    validation.issues.first()
+                        .map(ToString::to_string)
+                }
+                ClusterCondition::Undeployed if deployed => Some(String::from("It is deployed.")),
+                ClusterCondition::Undeployed => None,
+            }
+        };
+
+        Ok(pending.map_or(Check::Reached(()), Check::Pending))
+    }
+
+    async fn list_operations(carl: &mut CarlClient, id: ClusterId) -> crate::Result<Vec<DeploymentOperation>> {
+        carl.cluster.list_deployment_operations(Some(id)).await
+            .map_err(|error| format!("Could not list the deployment operations of cluster <{}>.\n  {}", id, error))
+    }
+
+    /// Finds an operation, which failed after waiting started. Operations, which had already finished before, are ignored.
+    fn failed_operation<'a>(operations: &'a [DeploymentOperation], initially_finished: &HashSet<DeploymentOperationId>) -> Option<(DeploymentOperationId, &'a DeploymentOperationError)> {
+        operations.iter()
+            .filter(|operation| initially_finished.contains(&operation.id).not())
+            .find_map(|operation| match &operation.state {
+                DeploymentOperationState::Failed { error } => Some((operation.id, error)),
+                _ => None,
+            })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use googletest::prelude::*;
+
+        use super::*;
+
+        #[test]
+        fn should_only_report_operations_failing_while_waiting() -> Result<()> {
+            let cluster_id = ClusterId::random();
+            let failed_before = DeploymentOperation { id: DeploymentOperationId::random(), cluster_id, state: DeploymentOperationState::Failed { error: DeploymentOperationError::DeploymentDeleted } };
+            let failed_while_waiting = DeploymentOperation { id: DeploymentOperationId::random(), cluster_id, state: DeploymentOperationState::Failed { error: DeploymentOperationError::LeaseExpired } };
+            let initially_finished = HashSet::from([failed_before.id]);
+
+            verify_that!(failed_operation(&[Clone::clone(&failed_before)], &initially_finished), none())?;
+            verify_that!(
+                failed_operation(&[failed_before, Clone::clone(&failed_while_waiting)], &initially_finished),
+                some(eq((failed_while_waiting.id, &DeploymentOperationError::LeaseExpired)))
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_retry_transient_errors_and_stop_at_fatal_errors() -> Result<()> {
+        let mut poller = Poller::new(Some(Duration::from_secs(60)));
+
+        let transient = poller.check(async { Err::<Check<()>, _>(CheckError::Transient(String::from("CARL is restarting."))) }).await;
+        verify_that!(transient, ok(matches_pattern!(Check::Pending(eq("CARL is restarting.")))))?;
+
+        let fatal = poller.check(async { Err::<Check<()>, _>(CheckError::Fatal(String::from("Deployment failed."))) }).await;
+        verify_that!(fatal, err(eq("Deployment failed.")))
+    }
+
+    #[tokio::test]
+    async fn should_limit_the_duration_of_a_check_to_the_timeout() -> Result<()> {
+        let mut poller = Poller::new(Some(Duration::from_millis(10)));
+
+        let unresponsive = poller.check(std::future::pending::<std::result::Result<Check<()>, CheckError>>()).await;
+
+        verify_that!(unresponsive, ok(matches_pattern!(Check::Pending(starts_with("CARL did not respond")))))?;
+        verify_that!(poller.is_expired(), eq(true))
+    }
+
+    #[tokio::test]
+    async fn should_give_up_after_consecutive_failures_without_timeout() -> Result<()> {
+        let mut poller = Poller::new(None);
+
+        for _ in 1..MAX_CONSECUTIVE_FAILURES {
+            let result = poller.check(async { Err::<Check<()>, _>(CheckError::Transient(String::from("CARL is restarting."))) }).await;
+            verify_that!(result, ok(anything()))?;
+        }
+        let result = poller.check(async { Err::<Check<()>, _>(CheckError::Transient(String::from("CARL is restarting."))) }).await;
+        verify_that!(result, err(starts_with("Giving up after 10 failed attempts.")))
+    }
+}
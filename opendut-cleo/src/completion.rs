@@ -0,0 +1,184 @@
+use std::ops::Not;
+
+use clap::{Arg, Command};
+use indoc::indoc;
+
+use crate::resolve::ResourceKind;
+use crate::Shell;
+
+/// What to offer for the word under the cursor.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Completion {
+    Candidates(Vec<String>),
+    /// The word references a resource, whose names and IDs have to be requested from CARL.
+    Resource(ResourceKind),
+}
+
+/// Determines the completion of the last of the given words, which were typed after the name of the executable.
+pub fn complete(mut command: Command, words: &[String]) -> Completion {
+    command.build();
+
+    let (current, previous) = match words.split_last() {
+        Some((current, previous)) => (current.as_str(), previous),
+        None => ("", words),
+    };
+
+    let mut command = &command;
+    let mut pending: Option<&Arg> = None;
+    let mut positionals = 0;
+
+    for word in previous {
+        if let Some(arg) = pending.take() {
+            if word.starts_with('-').not() {
+                if takes_multiple_values(arg) {
+                    pending = Some(arg);
+                }
+                continue;
+            }
+        }
+        if let Some(long) = word.strip_prefix("--") {
+            pending = command.get_arguments()
+                .find(|arg| arg.get_long() == Some(long))
+                .filter(|arg| arg.get_action().takes_values());
+        }
+        else if let Some(short) = word.strip_prefix('-').filter(|short| short.chars().count() == 1) {
+            pending = command.get_arguments()
+                .find(|arg| arg.get_short().map(String::from).as_deref() == Some(short))
+                .filter(|arg| arg.get_action().takes_values());
+        }
+        else if let Some(subcommand) = command.find_subcommand(word) {
+            command = subcommand;
+            positionals = 0;
+        }
+        else {
+            positionals += 1;
+        }
+    }
+
+    if let Some(arg) = pending.filter(|_| current.starts_with('-').not()) {
+        return complete_value(arg, current);
+    }
+
+    let flags = || command.get_arguments()
+        .filter(|arg| arg.is_positional().not() && arg.is_hide_set().not())
+        .filter_map(|arg| arg.get_long())
+        .map(|long| format!("--{long}"))
+        .filter(|flag| flag.starts_with(current))
+        .collect::<Vec<_>>();
+
+    let positional = command.get_positionals().nth(positionals);
+
+    if current.starts_with('-') {
+        Completion::Candidates(flags())
+    } else if let Some(arg) = positional.filter(|arg| value_name(arg).and_then(ResourceKind::from_value_name).is_some()) {
+        complete_value(arg, current)
+    } else if command.has_subcommands().not() {
+        Completion::Candidates(flags())
+    } else {
+        let subcommands = command.get_subcommands()
+            .filter(|subcommand| subcommand.is_hide_set().not())
+            .map(|subcommand| subcommand.get_name().to_owned())
+            .filter(|name| name.starts_with(current))
+            .collect();
+        Completion::Candidates(subcommands)
+    }
+}
+
+fn complete_value(arg: &Arg, current: &str) -> Completion {
+    let kind = value_name(arg).and_then(ResourceKind::from_value_name);
+
+    match kind {
+        Some(kind) => Completion::Resource(kind),
+        None => {
+            let values = arg.get_possible_values().into_iter()
+                .filter(|value| value.is_hide_set().not())
+                .map(|value| value.get_name().to_owned())
+                .filter(|value| value.starts_with(current))
+                .collect();
+            Completion::Candidates(values)
+        }
+    }
+}
+
+fn value_name(arg: &Arg) -> Option<&str> {
+    arg.get_value_names()
+        .and_then(|value_names| value_names.first())
+        .map(|value_name| value_name.as_str())
+}
+
+fn takes_multiple_values(arg: &Arg) -> bool {
+    arg.get_num_args()
+        .map(|range| range.max_values() > 1)
+        .unwrap_or(false)
+}
+
+/// Returns the value of a global flag like `--context`, if it is among the given words.
+pub fn global_value(words: &[String], flag: &str) -> Option<String> {
+    words.iter()
+        .position(|word| word == flag)
+        .and_then(|index| words.get(index + 1))
+        .cloned()
+}
+
+/// Script, which registers the completion of CLEO in the given shell.
+///
+/// The scripts pass the typed words to the hidden `__complete` command, which prints one candidate per line,
+/// optionally followed by a tab and a description.
+pub fn script(shell: Shell) -> &'static str {
+    match shell {
+        Shell::Bash => indoc!(r#"
+            _opendut_cleo() {
+                local IFS=$'\n'
+                COMPREPLY=( $(opendut-cleo __complete -- "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null | cut -f1) )
+            }
+            complete -o default -F _opendut_cleo opendut-cleo
+        "#),
+        Shell::Zsh => indoc!(r#"
+            #compdef opendut-cleo
+            _opendut_cleo() {
+                local -a candidates
+                candidates=(${(f)"$(opendut-cleo __complete -- "${(@)words[2,CURRENT]}" 2>/dev/null | cut -f1)"})
+                compadd -a candidates
+            }
+            compdef _opendut_cleo opendut-cleo
+        "#),
+        Shell::Fish => indoc!(r#"
+            function __opendut_cleo_complete
+                set -l tokens (commandline -opc)
+                set -l current (commandline -ct)
+                opendut-cleo __complete -- $tokens[2..-1] "$current" 2>/dev/null
+            end
+            complete -c opendut-cleo -f -a '(__opendut_cleo_complete)'
+        "#),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+    use googletest::prelude::*;
+
+    use super::*;
+
+    fn complete(words: &[&str]) -> Completion {
+        let words = words.iter().map(ToString::to_string).collect::<Vec<_>>();
+        super::complete(crate::Args::command(), &words)
+    }
+
+    #[test]
+    fn should_complete_subcommands_and_flags() -> Result<()> {
+        verify_that!(complete(&["cre"]), eq(Completion::Candidates(vec![String::from("create")])))?;
+        verify_that!(complete(&["list", "--out"]), eq(Completion::Candidates(vec![String::from("--output")])))?;
+        verify_that!(complete(&["import", "--strategy", "re"]), eq(Completion::Candidates(vec![String::from("rename-ids")])))
+    }
+
+    #[test]
+    fn should_complete_resources_with_names_and_ids_from_carl() -> Result<()> {
+        verify_that!(complete(&["delete", "peer", "--id", ""]), eq(Completion::Resource(ResourceKind::Peer)))?;
+        verify_that!(complete(&["--project", "bench", "create", "cluster-deployment", "-i", "ben"]), eq(Completion::Resource(ResourceKind::Cluster)))?;
+        verify_that!(complete(&["create", "cluster-configuration", "--device-names", "ecu-a", ""]), eq(Completion::Resource(ResourceKind::Device)))?;
+        verify_that!(complete(&["wait", "peer", ""]), eq(Completion::Resource(ResourceKind::Peer)))?;
+        verify_that!(complete(&["wait", "peer", "--state", "up", ""]), eq(Completion::Resource(ResourceKind::Peer)))?;
+        verify_that!(complete(&["wait", "peer", "bench-a", "--st"]), eq(Completion::Candidates(vec![String::from("--state")])))
+    }
+}
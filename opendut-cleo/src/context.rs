@@ -0,0 +1,185 @@
+use std::ops::Not;
+use std::path::PathBuf;
+
+use config::{Config, Map, Value, ValueKind};
+
+pub const CONTEXTS_KEY: &str = "contexts";
+pub const CURRENT_CONTEXT_KEY: &str = "context";
+const TOKEN_CACHE_KEY: &str = "network.oidc.token.cache";
+const CLIENT_SECRET_KEY: &str = "network.oidc.client.secret";
+const DEFAULT_CONTEXT_NAME: &str = "default";
+
+/// The name of the context selected via the `--context` flag or the configuration, if any.
+pub fn selected(config: &Config, flag: Option<String>) -> Option<String> {
+    flag.or_else(|| config.get_string(CURRENT_CONTEXT_KEY).ok())
+        .filter(|context| context.is_empty().not())
+}
+
+/// Applies the settings of the named context on top of the configuration.
+///
+/// A context lists the same keys as the configuration itself, e.g. `network.carl.host` or `project`,
+/// and overrides them when the context is used.
+pub fn apply(config: Config, context: Option<&str>) -> crate::Result<Config> {
+    let overrides = match context {
+        Some(context) => {
+            let settings = config.get_table(&format!("{CONTEXTS_KEY}.{context}"))
+                .map_err(|_| format!("Unknown context '{context}'. Use `opendut-cleo context list` to show the available contexts."))?;
+            flatten(String::new(), settings)
+        }
+        None => Vec::new(),
+    };
+
+    let token_cache_configured = overrides.iter().any(|(key, _)| key == TOKEN_CACHE_KEY)
+        || config.get_string(TOKEN_CACHE_KEY).is_ok_and(|token_cache| token_cache.is_empty().not());
+
+    let mut builder = Config::builder()
+        .add_source(config);
+    for (key, value) in overrides {
+        builder = builder.set_override(key, value)
+            .map_err(|cause| format!("Invalid setting in context '{}'.\n  {cause}", context.unwrap_or_default()))?;
+    }
+    if token_cache_configured.not() {
+        if let Some(token_cache) = token_cache_path(context.unwrap_or(DEFAULT_CONTEXT_NAME)) {
+            builder = builder.set_override(TOKEN_CACHE_KEY, token_cache.display().to_string())
+                .map_err(|cause| format!("Failed to configure the token cache.\n  {cause}"))?;
+        }
+    }
+
+    builder.build()
+        .map_err(|cause| format!("Failed to load the configuration.\n  {cause}"))
+}
+
+/// Hides the client secrets of the configuration and of all contexts, so that the configuration can be displayed.
+pub fn redact(config: &Config) -> crate::Result<Config> {
+    let mut secret_keys = names(config).into_iter()
+        .map(|name| format!("{CONTEXTS_KEY}.{name}.{CLIENT_SECRET_KEY}"))
+        .chain([String::from(CLIENT_SECRET_KEY)])
+        .filter(|key| config.get_string(key).is_ok());
+
+    secret_keys
+        .try_fold(Config::builder().add_source(Clone::clone(config)), |builder, key| {
+            builder.set_override(key, "redacted")
+        })
+        .and_then(|builder| builder.build())
+        .map_err(|_| String::from("Failed to hide cleo secrets."))
+}
+
+/// The names of all contexts found in the configuration, sorted by name.
+pub fn names(config: &Config) -> Vec<String> {
+    let mut names = config.get_table(CONTEXTS_KEY)
+        .unwrap_or_default()
+        .into_keys()
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// Context names are used as keys in the configuration and as file names for the token cache.
+pub fn validate_name(name: &str) -> crate::Result<()> {
+    let is_valid = name.is_empty().not()
+        && name.chars().all(|character| character.is_ascii_lowercase() || character.is_ascii_digit() || character == '-' || character == '_');
+    if is_valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid context name '{name}'. Use only lowercase letters, digits, '-' and '_'."))
+    }
+}
+
+/// Each context caches its authentication token separately, so that switching between contexts does not require a new login.
+fn token_cache_path(context: &str) -> Option<PathBuf> {
+    opendut_util::settings::user_cache_dir("cleo")
+        .map(|cache_dir| cache_dir.join("tokens").join(format!("{context}.json")))
+}
+
+fn flatten(prefix: String, table: Map<String, Value>) -> Vec<(String, Value)> {
+    table.into_iter()
+        .flat_map(|(key, value)| {
+            let key = if prefix.is_empty() { key } else { format!("{prefix}.{key}") };
+            match value.kind {
+                ValueKind::Table(table) => flatten(key, table),
+                _ => vec![(key, value)],
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use config::{File, FileFormat};
+    use googletest::prelude::*;
+
+    use super::*;
+
+    fn config() -> Config {
+        Config::builder()
+            .add_source(File::from_str(r#"
+                context = "staging"
+                project = ""
+
+                [network]
+                carl.host = "localhost"
+                carl.port = 8080
+                tls.ca = "/etc/opendut/tls/ca.pem"
+
+                [network.oidc]
+                enabled = false
+
+                [contexts.staging]
+                network.carl.host = "carl.staging"
+                network.carl.port = 443
+                network.oidc.enabled = true
+                project = "bench"
+
+                [contexts.dev]
+                network.carl.host = "carl.dev"
+            "#, FileFormat::Toml))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_override_the_configuration_with_the_selected_context() -> Result<()> {
+        let config = config();
+        let context = selected(&config, None);
+        verify_that!(context, some(eq("staging")))?;
+
+        let applied = apply(config, context.as_deref()).unwrap();
+        verify_that!(applied.get_string("network.carl.host"), ok(eq("carl.staging")))?;
+        verify_that!(applied.get_int("network.carl.port"), ok(eq(443)))?;
+        verify_that!(applied.get_bool("network.oidc.enabled"), ok(eq(true)))?;
+        verify_that!(applied.get_string("network.tls.ca"), ok(eq("/etc/opendut/tls/ca.pem")))?;
+        verify_that!(applied.get_string("project"), ok(eq("bench")))?;
+        Ok(())
+    }
+
+    #[test]
+    fn should_prefer_the_context_given_as_flag() -> Result<()> {
+        let config = config();
+        let context = selected(&config, Some(String::from("dev")));
+
+        let applied = apply(config, context.as_deref()).unwrap();
+        verify_that!(applied.get_string("network.carl.host"), ok(eq("carl.dev")))?;
+        verify_that!(applied.get_int("network.carl.port"), ok(eq(8080)))?;
+        verify_that!(applied.get_string(TOKEN_CACHE_KEY), ok(ends_with("dev.json")))?;
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_unknown_contexts() -> Result<()> {
+        verify_that!(apply(config(), Some("production")), err(contains_substring("Unknown context 'production'")))?;
+        verify_that!(names(&config()), elements_are![eq("dev"), eq("staging")])?;
+        verify_that!(validate_name("Prod.1"), err(anything()))
+    }
+
+    #[test]
+    fn should_redact_the_client_secrets_of_all_contexts() -> Result<()> {
+        let config = Config::builder()
+            .add_source(config())
+            .set_override("contexts.dev.network.oidc.client.secret", "dev-secret").unwrap()
+            .build().unwrap();
+
+        let redacted = redact(&config).unwrap();
+        verify_that!(redacted.get_string("contexts.dev.network.oidc.client.secret"), ok(eq("redacted")))?;
+        verify_that!(redacted.get_string("contexts.staging.network.oidc.client.secret"), err(anything()))
+    }
+}
@@ -1,8 +1,10 @@
 use std::ops::Not;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
-use clap::{Parser, Subcommand, ValueEnum};
+use chrono::{DateTime, Utc};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use console::Style;
 use uuid::Uuid;
 
@@ -10,10 +12,17 @@ use opendut_carl_api::carl::CarlClient;
 use opendut_types::peer::PeerSetup;
 use opendut_types::peer::executor::{ContainerCommand, ContainerCommandArgument, ContainerDevice, ContainerImage, ContainerName, ContainerPortSpec, ContainerVolume};
 use opendut_types::topology::DeviceName;
+use opendut_types::topology::query::DeviceQuery;
 use opendut_types::util::net::NetworkInterfaceName;
 use opendut_util::settings::{FileFormat, load_config};
 
+use crate::completion::Completion;
+
 mod commands;
+mod completion;
+mod context;
+mod manifest;
+mod resolve;
 
 type Error = String;
 type Result<T> = std::result::Result<T, Error>;
@@ -27,6 +36,12 @@ opendut_util::app_info!();
 struct Args {
     #[command(subcommand)]
     command: Commands,
+    ///Name or ID of the project to operate in. Overrides the project configured in the configuration file.
+    #[arg(long, global = true)]
+    project: Option<String>,
+    ///Name of the context to use. Overrides the context configured in the configuration file.
+    #[arg(long, global = true)]
+    context: Option<String>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -47,11 +62,19 @@ enum Commands {
         #[arg(value_enum, short, long, default_value_t=CreateOutputFormat::Text)]
         output: CreateOutputFormat,
     },
+    ///Update openDuT resource
+    Update {
+        #[command(subcommand)]
+        resource: UpdateResource,
+        ///Text, JSON or prettified JSON as output format
+        #[arg(value_enum, short, long, default_value_t=CreateOutputFormat::Text)]
+        output: CreateOutputFormat,
+    },
     ///Generate a setup string
     GeneratePeerSetup {
-        ///PeerID
-        #[arg(short, long)]
-        id: Uuid,
+        ///Name or ID of the peer
+        #[arg(short, long, value_name = "PEER")]
+        id: String,
     },
     DecodePeerSetup {
         ///Setup string
@@ -79,35 +102,183 @@ enum Commands {
         #[arg(value_enum, short, long, default_value_t=ListOutputFormat::Table)]
         output: ListOutputFormat,
     },
+    ///Create or update the peers and clusters described in a manifest
+    Apply {
+        ///Manifest file in TOML (.toml), YAML (.yaml, .yml) or JSON (.json) format
+        #[arg(short, long)]
+        file: PathBuf,
+    },
+    ///Show the changes, which applying a manifest would make
+    Diff {
+        ///Manifest file in TOML (.toml), YAML (.yaml, .yml) or JSON (.json) format
+        #[arg(short, long)]
+        file: PathBuf,
+    },
     ///Delete openDuT resource
+    #[command(args_conflicts_with_subcommands = true)]
     Delete {
         ///Name of openDuT resource
         #[command(subcommand)]
-        resource: DeleteResource,
+        resource: Option<DeleteResource>,
+        ///Delete the peers and clusters described in this manifest instead
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+    },
+    ///Display the audit log of mutating operations
+    Audit {
+        ///Only show records for the resource with this ID
+        #[arg(long)]
+        resource_id: Option<Uuid>,
+        ///Only show records of operations triggered by this identity
+        #[arg(long)]
+        identity: Option<String>,
+        ///Maximum number of records to display, latest first
+        #[arg(short, long)]
+        limit: Option<u32>,
+        ///JSON, prettified JSON or table as output format
+        #[arg(value_enum, short, long, default_value_t=ListOutputFormat::Table)]
+        output: ListOutputFormat,
+    },
+    ///Export the peers, clusters and deployments of the selected project into an archive
+    Export {
+        ///Archive file to write in JSON format
+        #[arg(short, long)]
+        file: PathBuf,
+    },
+    ///Import the peers, clusters and deployments of an archive into the selected project
+    Import {
+        ///Archive file in JSON format, written by 'export'
+        #[arg(short, long)]
+        file: PathBuf,
+        ///How to handle resources, whose IDs already exist in CARL
+        #[arg(value_enum, short, long, default_value_t=ImportStrategy::Skip)]
+        strategy: ImportStrategy,
+        ///JSON, prettified JSON or table as output format
+        #[arg(value_enum, short, long, default_value_t=ListOutputFormat::Table)]
+        output: ListOutputFormat,
+    },
+    ///Wait until a peer or cluster reached a state. Exits with code 124, if the timeout elapses before
+    Wait {
+        #[command(subcommand)]
+        resource: WaitResource,
+    },
+    ///Print the script for completing commands, names and IDs in the given shell
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+    ///Print the candidates for completing the last of the given words, used by the completion scripts
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        words: Vec<String>,
+    },
+    ///Manage the contexts for connecting to different CARL instances
+    Context {
+        #[command(subcommand)]
+        command: ContextCommand,
+    },
+    ///Log in as user via the browser, instead of authenticating with a client secret
+    Login,
+    ///Open an interactive dashboard of peers, clusters and devices in the terminal
+    Tui,
+    ///Manage the VPN of openDuT
+    Vpn {
+        #[command(subcommand)]
+        command: VpnCommand,
     },
     Config,
 }
 
+#[derive(Subcommand, Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
+enum ContextCommand {
+    ///List the configured contexts
+    List {
+        ///JSON, prettified JSON or table as output format
+        #[arg(value_enum, short, long, default_value_t=ListOutputFormat::Table)]
+        output: ListOutputFormat,
+    },
+    ///Use the context by default
+    Use {
+        ///Name of the context
+        #[arg()]
+        name: String,
+    },
+    ///Add a context to the user configuration or replace it
+    Add {
+        ///Name of the context
+        #[arg()]
+        name: String,
+        ///URL of CARL, e.g. https://carl.opendut.local:443
+        #[arg(long)]
+        carl_url: url::Url,
+        ///Path to the CA certificate used to verify CARL
+        #[arg(long)]
+        ca: PathBuf,
+        ///Domain name expected in the certificate of CARL, if it differs from the host
+        #[arg(long)]
+        domain_name_override: Option<String>,
+        ///Issuer URL of the OIDC identity provider. Authentication is disabled, if not set
+        #[arg(long)]
+        oidc_issuer_url: Option<url::Url>,
+        ///Client ID to authenticate with
+        #[arg(long, requires = "oidc_issuer_url")]
+        oidc_client_id: Option<String>,
+        ///Client secret to authenticate with. Without a secret, users log in via `opendut-cleo login` instead
+        #[arg(long, requires = "oidc_issuer_url")]
+        oidc_client_secret: Option<String>,
+        ///Comma-separated scopes to request
+        #[arg(long, requires = "oidc_issuer_url")]
+        oidc_scopes: Option<String>,
+        ///Name or ID of the project to operate in
+        #[arg(long)]
+        project: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug)]
+enum VpnCommand {
+    ///Detect and repair drift between the VPN and the peers and cluster deployments known to CARL
+    Reconcile {
+        ///Only report the drift, without repairing it
+        #[arg(long)]
+        dry_run: bool,
+        ///JSON, prettified JSON or table as output format
+        #[arg(value_enum, short, long, default_value_t=ListOutputFormat::Table)]
+        output: ListOutputFormat,
+    },
+}
+
 #[derive(Subcommand, Clone, Debug)]
 enum ListResource {
+    Projects,
     ClusterConfigurations,
     ClusterDeployments,
+    DeploymentOperations {
+        ///Only list the operations of the cluster with this name or ID
+        #[arg(long, value_name = "CLUSTER")]
+        cluster_id: Option<String>,
+    },
     Peers,
     Devices,
     ContainerExecutor {
-        ///PeerID
-        #[arg(short, long)]
-        id: Uuid,
+        ///Name or ID of the peer
+        #[arg(short, long, value_name = "PEER")]
+        id: String,
     },
 }
 
 #[derive(Debug, Clone, clap::Args)]
 #[group(required=true, multiple = true)]
 struct ClusterConfigurationDevices {
-    #[arg(long, num_args = 0..)]
+    #[arg(long, num_args = 0.., value_name = "DEVICE")]
     device_names: Vec<DeviceName>,
-    #[arg(long, num_args = 0..)]
+    #[arg(long, num_args = 0.., value_name = "DEVICE")]
     device_ids: Vec<String>,
+    ///Query selecting the devices, e.g. "tag=powertrain and type=can and peer.state=available"
+    #[arg(long)]
+    device_query: Option<DeviceQuery>,
 }
 
 #[derive(Clone, Debug, ValueEnum)]
@@ -122,8 +293,43 @@ pub enum NetworkInterfaceType {
     Can,
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ImportStrategy {
+    ///Keep the existing resources
+    Skip,
+    ///Replace the existing resources
+    Overwrite,
+    ///Import the resources under new IDs
+    RenameIds,
+}
+
+impl From<ImportStrategy> for opendut_carl_api::carl::state::ImportStrategy {
+    fn from(value: ImportStrategy) -> Self {
+        match value {
+            ImportStrategy::Skip => opendut_carl_api::carl::state::ImportStrategy::Skip,
+            ImportStrategy::Overwrite => opendut_carl_api::carl::state::ImportStrategy::Overwrite,
+            ImportStrategy::RenameIds => opendut_carl_api::carl::state::ImportStrategy::RenameIds,
+        }
+    }
+}
+
 #[derive(Subcommand, Clone, Debug)]
 enum CreateResource {
+    Project {
+        ///Name of the project
+        #[arg(short, long)]
+        name: String,
+        ///ProjectID
+        #[arg(short, long)]
+        id: Option<Uuid>,
+    },
     ClusterConfiguration {
         ///Name of the cluster
         #[arg(short, long)]
@@ -131,17 +337,32 @@ enum CreateResource {
         ///ClusterID
         #[arg(short, long)]
         cluster_id: Option<Uuid>,
-        ///PeerID of the leader
-        #[arg(short, long)]
-        leader_id: Uuid,
+        ///Name or ID of the leader peer
+        #[arg(short, long, value_name = "PEER")]
+        leader_id: String,
         ///List of devices in cluster
         #[clap(flatten)]
         devices: ClusterConfigurationDevices,
     },
     ClusterDeployment {
-        ///ClusterID
-        #[arg(short, long)]
-        id: Uuid,
+        ///Name or ID of the cluster
+        #[arg(short, long, value_name = "CLUSTER")]
+        id: String,
+        ///Only check whether the cluster can be deployed, without deploying it
+        #[arg(long, conflicts_with = "wait")]
+        dry_run: bool,
+        ///Wait until CARL finished deploying the cluster
+        #[arg(long)]
+        wait: bool,
+        ///Maximum time to wait, e.g. 30s or 5m. Waits indefinitely, if not set
+        #[arg(long, requires = "wait", value_parser = humantime::parse_duration)]
+        timeout: Option<Duration>,
+        ///Time at which the cluster is deployed, e.g. 2024-06-01T08:00:00Z. Deployed immediately, if not set
+        #[arg(long, conflicts_with = "dry_run")]
+        start: Option<DateTime<Utc>>,
+        ///Number of minutes, after which the cluster is undeployed again. Stays deployed, if not set
+        #[arg(long, conflicts_with = "dry_run")]
+        lease_minutes: Option<u64>,
     },
     Peer {
         ///Name of peer
@@ -155,9 +376,9 @@ enum CreateResource {
         location: Option<String>,
     },
     ContainerExecutor {
-        ///ID of the peer to add the container executor to
-        #[arg(long)]
-        peer_id: Uuid,
+        ///Name or ID of the peer to add the container executor to
+        #[arg(long, value_name = "PEER")]
+        peer_id: String,
         ///Engine
         #[arg(short, long)]
         engine: EngineVariants,
@@ -176,6 +397,9 @@ enum CreateResource {
         ///Container envs
         #[arg(long, num_args = 1..)]
         envs: Option<Vec<String>>,
+        ///Container envs with secret values, which are not shown after creation
+        #[arg(long, num_args = 1..)]
+        secret_envs: Option<Vec<String>>,
         ///Container ports
         #[arg(short, long, num_args = 1..)]
         ports: Option<Vec<ContainerPortSpec>>,
@@ -187,9 +411,9 @@ enum CreateResource {
         args: Option<Vec<ContainerCommandArgument>>,
     },
     NetworkInterface {
-        ///ID of the peer to add the network interface to
-        #[arg(long)]
-        peer_id: Uuid,
+        ///Name or ID of the peer to add the network interface to
+        #[arg(long, value_name = "PEER")]
+        peer_id: String,
         ///Type of the network interface
         #[arg(long("type"))]
         interface_type: NetworkInterfaceType,
@@ -198,92 +422,213 @@ enum CreateResource {
         interface_name: String,
     },
     Device {
-        ///ID of the peer to add the device to
-        #[arg(long)]
-        peer_id: Uuid,
-        ///ID of the device to be added or updated
+        ///Name or ID of the peer to add the device to
+        #[arg(long, value_name = "PEER")]
+        peer_id: String,
+        ///ID of the device to be added
         #[arg(long)]
         device_id: Option<Uuid>,
         ///Name of the device
         #[arg(long)]
-        name: Option<String>,
+        name: String,
         ///Description of device
         #[arg(long)]
         description: Option<String>,
         ///Interface of device
         #[arg(long)]
-        interface: Option<NetworkInterfaceName>,
+        interface: NetworkInterfaceName,
         /// Tags of device
         #[arg(long("tag"))]
         tags: Option<Vec<String>>,
     }
 }
 
+#[derive(Subcommand, Clone, Debug)]
+enum UpdateResource {
+    ContainerExecutor {
+        ///Name or ID of the peer of the container executor
+        #[arg(long, value_name = "PEER")]
+        peer_id: String,
+        ///Name of the container executor to update
+        #[arg(short, long)]
+        name: ContainerName,
+        ///Engine
+        #[arg(short, long)]
+        engine: Option<EngineVariants>,
+        ///Container image
+        #[arg(short, long)]
+        image: Option<ContainerImage>,
+        ///Container volumes
+        #[arg(short, long, num_args = 1..)]
+        volumes: Option<Vec<ContainerVolume>>,
+        ///Container devices
+        #[arg(long, num_args = 1..)]
+        devices: Option<Vec<ContainerDevice>>,
+        ///Container envs
+        #[arg(long, num_args = 1..)]
+        envs: Option<Vec<String>>,
+        ///Container envs with secret values, which are not shown after creation
+        #[arg(long, num_args = 1..)]
+        secret_envs: Option<Vec<String>>,
+        ///Container ports
+        #[arg(short, long, num_args = 1..)]
+        ports: Option<Vec<ContainerPortSpec>>,
+        ///Container command
+        #[arg(short, long)]
+        command: Option<ContainerCommand>,
+        ///Container arguments
+        #[arg(short, long, num_args = 1..)]
+        args: Option<Vec<ContainerCommandArgument>>,
+    },
+    NetworkInterface {
+        ///Name or ID of the peer of the network interface
+        #[arg(long, value_name = "PEER")]
+        peer_id: String,
+        ///Type of the network interface
+        #[arg(long("type"))]
+        interface_type: NetworkInterfaceType,
+        ///Name of the network interface to update
+        #[arg(long("name"))]
+        interface_name: String,
+    },
+    Device {
+        ///Name or ID of the device to update
+        #[arg(long, value_name = "DEVICE")]
+        id: String,
+        ///Name of the device
+        #[arg(long)]
+        name: Option<String>,
+        ///Description of device
+        #[arg(long)]
+        description: Option<String>,
+        ///Interface of device
+        #[arg(long)]
+        interface: Option<NetworkInterfaceName>,
+        /// Tags of device, replacing the previous tags
+        #[arg(long("tag"))]
+        tags: Option<Vec<String>>,
+    },
+}
+
 #[derive(Subcommand, Clone, Debug)]
 enum DescribeResource {
     ClusterConfiguration {
-        ///ClusterID
+        ///Name or ID of the cluster
+        #[arg(short, long, value_name = "CLUSTER")]
+        id: String,
+    },
+    DeploymentOperation {
+        ///ID of the deployment operation
         #[arg(short, long)]
         id: Uuid,
     },
     Peer {
-        ///PeerID
-        #[arg(short, long)]
-        id: Uuid,
+        ///Name or ID of the peer
+        #[arg(short, long, value_name = "PEER")]
+        id: String,
     },
     Device {
-        ///DeviceID
-        #[arg(short, long)]
-        id: Uuid,
+        ///Name or ID of the device
+        #[arg(short, long, value_name = "DEVICE")]
+        id: String,
     },
 }
 
+#[derive(Subcommand, Clone, Debug)]
+enum WaitResource {
+    Peer {
+        ///Name or ID of the peer
+        #[arg(value_name = "PEER")]
+        id: String,
+        ///State to wait for
+        #[arg(value_enum, short, long)]
+        state: WaitPeerState,
+        ///Maximum time to wait, e.g. 30s or 5m. Waits indefinitely, if not set
+        #[arg(short, long, value_parser = humantime::parse_duration)]
+        timeout: Option<Duration>,
+    },
+    #[command(group = clap::ArgGroup::new("condition").required(true))]
+    Cluster {
+        ///Name or ID of the cluster
+        #[arg(value_name = "CLUSTER")]
+        id: String,
+        ///Wait until the cluster is deployed and CARL finds no issues with its peers
+        #[arg(long, group = "condition")]
+        healthy: bool,
+        ///Wait until the cluster is undeployed
+        #[arg(long, group = "condition")]
+        undeployed: bool,
+        ///Maximum time to wait, e.g. 30s or 5m. Waits indefinitely, if not set
+        #[arg(short, long, value_parser = humantime::parse_duration)]
+        timeout: Option<Duration>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum WaitPeerState {
+    Down,
+    Up,
+    Available,
+    Blocked,
+    Deploying,
+    Member,
+    Undeploying,
+}
+
 #[derive(Subcommand, Clone, Debug)]
 enum FindResource {
     Device {
         ///Criteria for search
-        #[arg(required = true, value_delimiter = ' ', num_args = 1..)]
+        #[arg(required_unless_present = "query", value_delimiter = ' ', num_args = 1..)]
         criteria: Vec<String>,
+        ///Query selecting the devices, e.g. "tag=powertrain and type=can and peer.state=available"
+        #[arg(short, long, conflicts_with = "criteria")]
+        query: Option<DeviceQuery>,
     },
 }
 
 #[derive(Subcommand, Clone, Debug)]
 enum DeleteResource {
+    Project {
+        ///Name or ID of the project
+        #[arg()]
+        name_or_id: String,
+    },
     ClusterConfiguration {
-        ///ClusterID
-        #[arg(short, long)]
-        id: Uuid,
+        ///Name or ID of the cluster
+        #[arg(short, long, value_name = "CLUSTER")]
+        id: String,
     },
     ClusterDeployment {
-        ///ClusterID
-        #[arg(short, long)]
-        id: Uuid,
+        ///Name or ID of the cluster
+        #[arg(short, long, value_name = "CLUSTER")]
+        id: String,
     },
     Peer {
-        ///PeerID
-        #[arg(short, long)]
-        id: Uuid,
+        ///Name or ID of the peer
+        #[arg(short, long, value_name = "PEER")]
+        id: String,
     },
     ContainerExecutor {
-        ///ID of the peer to delete the container executor from
-        #[arg(long)]
-        peer_id: Uuid,
-        ///Container images to delete
+        ///Name or ID of the peer to delete the container executor from
+        #[arg(long, value_name = "PEER")]
+        peer_id: String,
+        ///Names of the container executors to delete
         #[arg(short, long)]
-        images: Vec<ContainerImage>,
+        names: Vec<ContainerName>,
     },
     NetworkInterface {
-        ///ID of the peer to delete the network configuration from
-        #[arg(long)]
-        peer_id: Uuid,
+        ///Name or ID of the peer to delete the network configuration from
+        #[arg(long, value_name = "PEER")]
+        peer_id: String,
         ///NetworkConfiguration Interface
         #[arg(long("interface"))]
         interfaces: Vec<String>,
     },
     Device {
-        ///DeviceID
-        #[arg(short, long)]
-        id: Uuid,
+        ///Name or ID of the device
+        #[arg(short, long, value_name = "DEVICE")]
+        id: String,
     },
 }
 
@@ -345,9 +690,30 @@ async fn execute() -> Result<()> {
         .build()
         .map_err(|_error| "Failed to hide cleo secrets.")?;
 
-    let settings = load_config("cleo", include_str!("../cleo.toml"), FileFormat::Toml, config::Config::default(), cleo_config_hide_secrets_override)
+    let mut settings = load_config("cleo", include_str!("../cleo.toml"), FileFormat::Toml, config::Config::default(), cleo_config_hide_secrets_override)
         .expect("Failed to load config"); // TODO: Point the user to the source of the error.
 
+    let mut args = Args::parse();
+
+    if let Commands::Completions { shell } = args.command {
+        print!("{}", completion::script(shell));
+        return Ok(());
+    }
+    if let Commands::Complete { words } = &args.command {
+        if let Completion::Candidates(candidates) = completion::complete(Args::command(), words) {
+            candidates.iter().for_each(|candidate| println!("{candidate}"));
+            return Ok(());
+        }
+        args.context = args.context.or_else(|| completion::global_value(words, "--context"));
+        args.project = args.project.or_else(|| completion::global_value(words, "--project"));
+    }
+
+    let context = context::selected(&settings.config, args.context);
+    if let Commands::Context { command } = args.command {
+        return execute_context_command(command, &settings.config, context);
+    }
+    settings.config = context::apply(settings.config, context.as_deref())?;
+    settings.redacted_config = context::redact(&settings.config)?;
 
     let mut carl = {
 
@@ -368,21 +734,42 @@ async fn execute() -> Result<()> {
             .expect("Failed to create CARL client")
     };
 
-    let args = Args::parse();
+    let project = args.project
+        .or_else(|| settings.config.get_string("project").ok())
+        .filter(|project| project.is_empty().not());
+    if let Commands::Complete { words } = args.command {
+        return commands::completion::complete::execute(&mut carl, project, words).await;
+    }
+    if let Some(project) = project {
+        let project_id = carl.projects.resolve_project(&project).await
+            .map_err(|error| format!("Could not select project '{project}'.\n  {error}"))?;
+        carl.project.select(Some(project_id));
+    }
 
     match args.command {
         Commands::List { resource, output } => {
             match resource {
+                ListResource::Projects => {
+                    commands::project::list::execute(&mut carl, output).await?;
+                }
                 ListResource::ClusterConfigurations => {
                     commands::cluster_configuration::list::execute(&mut carl, output).await?;
                 }
                 ListResource::ClusterDeployments => {
                     commands::cluster_deployment::list::execute(&mut carl, output).await?;
                 }
+                ListResource::DeploymentOperations { cluster_id } => {
+                    let cluster_id = match cluster_id {
+                        Some(cluster_id) => Some(resolve::cluster(&mut carl, &cluster_id).await?),
+                        None => None,
+                    };
+                    commands::deployment_operation::list::execute(&mut carl, cluster_id, output).await?;
+                }
                 ListResource::Peers => {
                     commands::peer::list::execute(&mut carl, output).await?;
                 }
                 ListResource::ContainerExecutor{ id }  => {
+                    let id = resolve::peer(&mut carl, &id).await?;
                     commands::executor::list::execute(&mut carl, id, output).await?;
                 }
                 ListResource::Devices => {
@@ -392,27 +779,57 @@ async fn execute() -> Result<()> {
         }
         Commands::Create { resource, output } => {
             match resource {
+                CreateResource::Project { name, id } => {
+                    commands::project::create::execute(&mut carl, name, id, output).await?;
+                }
                 CreateResource::ClusterConfiguration { name, cluster_id, leader_id, devices  } => {
-                    commands::cluster_configuration::create::execute(&mut carl, name, cluster_id, leader_id, devices.device_names, devices.device_ids, output).await?;
+                    let leader_id = resolve::peer(&mut carl, &leader_id).await?;
+                    commands::cluster_configuration::create::execute(&mut carl, name, cluster_id, leader_id, devices.device_names, devices.device_ids, devices.device_query, output).await?;
                 }
-                CreateResource::ClusterDeployment { id} => {
-                    commands::cluster_deployment::create::execute(&mut carl, id, output).await?;
+                CreateResource::ClusterDeployment { id, dry_run, wait, timeout, start, lease_minutes } => {
+                    let id = resolve::cluster(&mut carl, &id).await?;
+                    let lease = lease_minutes.map(|minutes| Duration::from_secs(minutes * 60));
+                    let outcome = commands::cluster_deployment::create::execute(&mut carl, id, dry_run, wait, timeout, start, lease, output).await?;
+                    if let commands::wait::WaitOutcome::TimedOut { cause } = outcome {
+                        eprintln!("{}", Style::new().red().apply_to(cause));
+                        std::process::exit(commands::wait::TIMEOUT_EXIT_CODE);
+                    }
                 }
                 CreateResource::Peer { name, id, location} => {
                     commands::peer::create::execute(&mut carl, name, id, location, output).await?;
                 }
-                CreateResource::ContainerExecutor { peer_id, engine, name, image, volumes, devices, envs, ports, command, args} => {
-                    commands::executor::create::execute(&mut carl, peer_id, engine, name, image, volumes, devices, envs, ports, command, args, output).await?;
+                CreateResource::ContainerExecutor { peer_id, engine, name, image, volumes, devices, envs, secret_envs, ports, command, args} => {
+                    let peer_id = resolve::peer(&mut carl, &peer_id).await?;
+                    commands::executor::create::execute(&mut carl, peer_id, engine, name, image, volumes, devices, envs, secret_envs, ports, command, args, output).await?;
                 }
                 CreateResource::NetworkInterface { peer_id, interface_type, interface_name} => {
+                    let peer_id = resolve::peer(&mut carl, &peer_id).await?;
                     commands::network_interface::create::execute(&mut carl, peer_id, interface_type, interface_name, output).await?;
                 }
                 CreateResource::Device { peer_id, device_id, name, description, interface, tags } => {
+                    let peer_id = resolve::peer(&mut carl, &peer_id).await?;
                     commands::device::create::execute(&mut carl, peer_id, device_id, name, description, interface, tags, output).await?;
                 }
             }
         }
+        Commands::Update { resource, output } => {
+            match resource {
+                UpdateResource::ContainerExecutor { peer_id, name, engine, image, volumes, devices, envs, secret_envs, ports, command, args } => {
+                    let peer_id = resolve::peer(&mut carl, &peer_id).await?;
+                    commands::executor::update::execute(&mut carl, peer_id, name, engine, image, volumes, devices, envs, secret_envs, ports, command, args, output).await?;
+                }
+                UpdateResource::NetworkInterface { peer_id, interface_type, interface_name } => {
+                    let peer_id = resolve::peer(&mut carl, &peer_id).await?;
+                    commands::network_interface::update::execute(&mut carl, peer_id, interface_type, interface_name, output).await?;
+                }
+                UpdateResource::Device { id, name, description, interface, tags } => {
+                    let id = resolve::device(&mut carl, &id).await?;
+                    commands::device::update::execute(&mut carl, id, name, description, interface, tags, output).await?;
+                }
+            }
+        }
         Commands::GeneratePeerSetup { id } => {
+            let id = resolve::peer(&mut carl, &id).await?;
             commands::peer::generate_peer_setup::execute(&mut carl, id).await?;
         }
         Commands::DecodePeerSetup { setup_string, output } => {
@@ -421,45 +838,118 @@ async fn execute() -> Result<()> {
         Commands::Describe { resource, output } => {
             match resource {
                 DescribeResource::ClusterConfiguration { id } => {
+                    let id = resolve::cluster(&mut carl, &id).await?;
                     commands::cluster_configuration::describe::execute(&mut carl, id, output).await?
                 }
+                DescribeResource::DeploymentOperation { id } => {
+                    commands::deployment_operation::describe::execute(&mut carl, id, output).await?
+                }
                 DescribeResource::Peer { id } => {
+                    let id = resolve::peer(&mut carl, &id).await?;
                     commands::peer::describe::execute(&mut carl, id, output).await?
                 }
                 DescribeResource::Device { id } => {
+                    let id = resolve::device(&mut carl, &id).await?;
                     commands::device::describe::execute(&mut carl, id, output).await?
                 }
             }
         }
-        Commands::Delete { resource} => {
+        Commands::Apply { file } => {
+            commands::manifest::apply::execute(&mut carl, file).await?;
+        }
+        Commands::Diff { file } => {
+            commands::manifest::diff::execute(&mut carl, file).await?;
+        }
+        Commands::Delete { resource: None, file: Some(file) } => {
+            commands::manifest::delete::execute(&mut carl, file).await?;
+        }
+        Commands::Delete { resource: None, file: None } => {
+            Err(String::from("Specify either the resource to delete or a manifest via '--file'."))?;
+        }
+        Commands::Delete { resource: Some(resource), .. } => {
             match resource {
+                DeleteResource::Project { name_or_id } => {
+                    commands::project::delete::execute(&mut carl, name_or_id).await?;
+                }
                 DeleteResource::ClusterConfiguration { id } => {
+                    let id = resolve::exact::cluster(&mut carl, &id).await?;
                     commands::cluster_configuration::delete::execute(&mut carl, id).await?;
                 }
                 DeleteResource::ClusterDeployment { id } => {
+                    let id = resolve::exact::cluster(&mut carl, &id).await?;
                     commands::cluster_deployment::delete::execute(&mut carl, id).await?;
                 }
                 DeleteResource::Peer { id } => {
+                    let id = resolve::exact::peer(&mut carl, &id).await?;
                     commands::peer::delete::execute(&mut carl, id).await?;
                 }
-                DeleteResource::ContainerExecutor { peer_id, images} => {
-                    commands::executor::delete::execute(&mut carl, peer_id, images).await?;
+                DeleteResource::ContainerExecutor { peer_id, names } => {
+                    let peer_id = resolve::exact::peer(&mut carl, &peer_id).await?;
+                    commands::executor::delete::execute(&mut carl, peer_id, names).await?;
                 }
                 DeleteResource::NetworkInterface { peer_id,  interfaces} => {
+                    let peer_id = resolve::exact::peer(&mut carl, &peer_id).await?;
                     commands::network_interface::delete::execute(&mut carl, peer_id, interfaces).await?;
                 }
                 DeleteResource::Device { id } => {
+                    let id = resolve::exact::device(&mut carl, &id).await?;
                     commands::device::delete::execute(&mut carl, id).await?;
                 }
             }
         }
         Commands::Find { resource, output } => {
             match resource {
-                FindResource::Device { criteria } => {
-                    commands::device::find::execute(&mut carl, criteria, output).await?;
+                FindResource::Device { criteria, query } => {
+                    commands::device::find::execute(&mut carl, criteria, query, output).await?;
+                }
+            }
+        }
+        Commands::Audit { resource_id, identity, limit, output } => {
+            commands::audit::list::execute(&mut carl, resource_id, identity, limit, output).await?;
+        }
+        Commands::Vpn { command } => {
+            match command {
+                VpnCommand::Reconcile { dry_run, output } => {
+                    commands::vpn::reconcile::execute(&mut carl, dry_run, output).await?;
+                }
+            }
+        }
+        Commands::Export { file } => {
+            commands::state::export::execute(&mut carl, file).await?;
+        }
+        Commands::Import { file, strategy, output } => {
+            commands::state::import::execute(&mut carl, file, strategy, output).await?;
+        }
+        Commands::Wait { resource } => {
+            let outcome = match resource {
+                WaitResource::Peer { id, state, timeout } => {
+                    let id = resolve::peer(&mut carl, &id).await?;
+                    commands::wait::peer::execute(&mut carl, id, state, timeout).await?
+                }
+                WaitResource::Cluster { id, healthy: _, undeployed, timeout } => {
+                    let id = resolve::cluster(&mut carl, &id).await?;
+                    let condition = if undeployed {
+                        commands::wait::cluster::ClusterCondition::Undeployed
+                    } else {
+                        commands::wait::cluster::ClusterCondition::Healthy
+                    };
+                    commands::wait::cluster::execute(&mut carl, id, condition, timeout).await?
                 }
+            };
+            if let commands::wait::WaitOutcome::TimedOut { cause } = outcome {
+                eprintln!("{}", Style::new().red().apply_to(cause));
+                std::process::exit(commands::wait::TIMEOUT_EXIT_CODE);
             }
         }
+        Commands::Completions { .. } => unreachable!("Completion scripts should be printed without connecting to CARL."),
+        Commands::Complete { .. } => unreachable!("Completions should be executed before selecting the project."),
+        Commands::Context { .. } => unreachable!("Context commands should be executed without connecting to CARL."),
+        Commands::Login => {
+            commands::login::execute(&carl).await?;
+        }
+        Commands::Tui => {
+            commands::tui::execute(&mut carl).await?;
+        }
         Commands::Config => {
             println!("Show cleo configuration: {:?}", settings);
         }
@@ -468,6 +958,29 @@ async fn execute() -> Result<()> {
 }
 
 
+fn execute_context_command(command: ContextCommand, config: &config::Config, selected: Option<String>) -> Result<()> {
+    let user_config = || opendut_util::settings::user_config_path("cleo")
+        .ok_or_else(|| String::from("Could not determine the path of the user configuration."));
+
+    match command {
+        ContextCommand::List { output } => {
+            commands::context::list::execute(config, selected, output)
+        }
+        ContextCommand::Use { name } => {
+            commands::context::select::execute(config, &user_config()?, name)
+        }
+        ContextCommand::Add { name, carl_url, ca, domain_name_override, oidc_issuer_url, oidc_client_id, oidc_client_secret, oidc_scopes, project } => {
+            let oidc = oidc_issuer_url.map(|issuer_url| commands::context::add::OidcSettings {
+                issuer_url,
+                client_id: oidc_client_id,
+                client_secret: oidc_client_secret,
+                scopes: oidc_scopes,
+            });
+            commands::context::add::execute(&user_config()?, name, carl_url, ca, domain_name_override, oidc, project)
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 struct ParseablePeerSetup(Box<PeerSetup>);
 impl FromStr for ParseablePeerSetup {
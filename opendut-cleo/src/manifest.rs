@@ -0,0 +1,718 @@
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fmt::{Display, Formatter};
+use std::ops::Not;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use opendut_types::cluster::{ClusterConfiguration, ClusterId, ClusterName};
+use opendut_types::peer::{PeerDescriptor, PeerId, PeerLocation, PeerName, PeerNetworkConfiguration};
+use opendut_types::peer::executor::{ContainerCommand, ContainerCommandArgument, ContainerDevice, ContainerEnvironmentVariable, ContainerImage, ContainerName, ContainerPortSpec, ContainerVolume, Engine, ExecutorDescriptor, ExecutorDescriptors};
+use opendut_types::topology::{DeviceDescription, DeviceDescriptor, DeviceId, DeviceName, DeviceTag, Topology};
+use opendut_types::util::net::{CanSamplePoint, NetworkInterfaceConfiguration, NetworkInterfaceDescriptor, NetworkInterfaceName};
+
+/// Declarative description of peers and clusters, which reference each other by name.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Manifest {
+    #[serde(default)]
+    pub peers: Vec<PeerManifest>,
+    #[serde(default)]
+    pub clusters: Vec<ClusterManifest>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PeerManifest {
+    pub id: Option<Uuid>,
+    pub name: String,
+    pub location: Option<String>,
+    #[serde(default)]
+    pub interfaces: Vec<InterfaceManifest>,
+    #[serde(default)]
+    pub devices: Vec<DeviceManifest>,
+    #[serde(default)]
+    pub executors: Vec<ExecutorManifest>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct InterfaceManifest {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub interface_type: InterfaceType,
+    pub bitrate: Option<i32>,
+    pub sample_point: Option<f32>,
+    pub fd: Option<bool>,
+    pub data_bitrate: Option<i32>,
+    pub data_sample_point: Option<f32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InterfaceType {
+    Ethernet,
+    Can,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct DeviceManifest {
+    pub id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub interface: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ExecutorManifest {
+    pub engine: Engine,
+    pub name: Option<String>,
+    pub image: String,
+    #[serde(default)]
+    pub volumes: Vec<String>,
+    #[serde(default)]
+    pub devices: Vec<String>,
+    /// Environment variables in the form `NAME=value`.
+    #[serde(default)]
+    pub envs: Vec<String>,
+    /// Environment variables in the form `NAME=value`, whose values are only revealed to the peer.
+    #[serde(default)]
+    pub secret_envs: Vec<String>,
+    #[serde(default)]
+    pub ports: Vec<String>,
+    pub command: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ClusterManifest {
+    pub id: Option<Uuid>,
+    pub name: String,
+    /// Name of the leading peer.
+    pub leader: String,
+    /// Names of the devices in the cluster.
+    pub devices: Vec<String>,
+    /// Whether the cluster should be deployed. The deployment is left untouched, if not set.
+    pub deployed: Option<bool>,
+}
+
+/// The resources currently stored in CARL.
+#[derive(Clone, Debug, Default)]
+pub struct CurrentState {
+    pub peers: Vec<PeerDescriptor>,
+    pub clusters: Vec<ClusterConfiguration>,
+    pub deployments: Vec<ClusterId>,
+}
+
+/// The resources described by a manifest, with the IDs and revisions of already existing resources.
+#[derive(Clone, Debug, Default)]
+pub struct DesiredState {
+    pub peers: Vec<PeerDescriptor>,
+    pub clusters: Vec<DesiredCluster>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DesiredCluster {
+    pub configuration: ClusterConfiguration,
+    pub deployed: Option<bool>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    CreatePeer(PeerDescriptor),
+    UpdatePeer { current: PeerDescriptor, desired: PeerDescriptor },
+    DeletePeer(PeerDescriptor),
+    CreateCluster(ClusterConfiguration),
+    UpdateCluster { current: ClusterConfiguration, desired: ClusterConfiguration },
+    DeleteCluster(ClusterConfiguration),
+    Deploy(ClusterConfiguration),
+    Undeploy(ClusterConfiguration),
+}
+
+impl Manifest {
+
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|cause| format!("Could not read manifest '{}'.\n  {cause}", path.display()))?;
+
+        match path.extension().and_then(OsStr::to_str) {
+            Some("toml") => toml::from_str(&content)
+                .map_err(|cause| format!("Could not parse manifest '{}'.\n  {cause}", path.display())),
+            Some("yaml" | "yml") => serde_yaml::from_str(&content)
+                .map_err(|cause| format!("Could not parse manifest '{}'.\n  {cause}", path.display())),
+            Some("json") => serde_json::from_str(&content)
+                .map_err(|cause| format!("Could not parse manifest '{}'.\n  {cause}", path.display())),
+            _ => Err(format!("Unsupported format of manifest '{}'. Expected a TOML (.toml), YAML (.yaml, .yml) or JSON (.json) file.", path.display())),
+        }
+    }
+
+    /// Translates the manifest into descriptors. Resources, which already exist in CARL with the same name, keep their ID.
+    pub fn resolve(&self, current: &CurrentState) -> crate::Result<DesiredState> {
+        let mut peers: Vec<PeerDescriptor> = Vec::new();
+        for peer in &self.peers {
+            if peers.iter().any(|other| other.name.to_string() == peer.name) {
+                return Err(format!("Peer '{}' is described more than once in the manifest.", peer.name));
+            }
+            peers.push(peer.resolve(current)?);
+        }
+
+        let available_peers = peers.iter()
+            .chain(current.peers.iter().filter(|current| peers.iter().any(|peer| peer.id == current.id).not()))
+            .collect::<Vec<_>>();
+
+        let mut clusters: Vec<DesiredCluster> = Vec::new();
+        for cluster in &self.clusters {
+            if clusters.iter().any(|other| other.configuration.name.to_string() == cluster.name) {
+                return Err(format!("Cluster '{}' is described more than once in the manifest.", cluster.name));
+            }
+            clusters.push(cluster.resolve(current, &available_peers)?);
+        }
+
+        Ok(DesiredState { peers, clusters })
+    }
+}
+
+impl PeerManifest {
+    fn resolve(&self, current: &CurrentState) -> crate::Result<PeerDescriptor> {
+        let error = |cause: String| format!("Invalid peer '{}' in manifest.\n  {cause}", self.name);
+
+        let existing = current.peers.iter()
+            .find(|peer| match self.id {
+                Some(id) => peer.id == PeerId::from(id),
+                None => peer.name.to_string() == self.name,
+            });
+
+        let id = self.id.map(PeerId::from)
+            .or(existing.map(|peer| peer.id))
+            .unwrap_or_else(PeerId::random);
+
+        let name = PeerName::try_from(Clone::clone(&self.name))
+            .map_err(|cause| error(cause.to_string()))?;
+        let location = self.location.clone()
+            .map(PeerLocation::try_from)
+            .transpose()
+            .map_err(|cause| error(cause.to_string()))?;
+
+        let interfaces = self.interfaces.iter()
+            .map(InterfaceManifest::resolve)
+            .collect::<crate::Result<Vec<_>>>()
+            .map_err(error)?;
+
+        let devices = self.devices.iter()
+            .map(|device| device.resolve(existing, &interfaces))
+            .collect::<crate::Result<Vec<_>>>()
+            .map_err(error)?;
+
+        let executors = self.executors.iter()
+            .map(ExecutorManifest::resolve)
+            .collect::<crate::Result<Vec<_>>>()
+            .map_err(error)?;
+
+        Ok(PeerDescriptor {
+            id,
+            name,
+            location,
+            network_configuration: PeerNetworkConfiguration { interfaces },
+            topology: Topology { devices },
+            executors: ExecutorDescriptors { executors },
+            revision: existing.map(|peer| peer.revision).unwrap_or_default(),
+        })
+    }
+}
+
+impl InterfaceManifest {
+    /// Unspecified CAN parameters default to the values used by `cleo create network-interface`.
+    fn resolve(&self) -> crate::Result<NetworkInterfaceDescriptor> {
+        let name = NetworkInterfaceName::try_from(Clone::clone(&self.name))
+            .map_err(|cause| cause.to_string())?;
+
+        let configuration = match self.interface_type {
+            InterfaceType::Ethernet => NetworkInterfaceConfiguration::Ethernet,
+            InterfaceType::Can => NetworkInterfaceConfiguration::Can {
+                bitrate: self.bitrate.unwrap_or(500000),
+                sample_point: CanSamplePoint::try_from(self.sample_point.unwrap_or(0.7))
+                    .map_err(|cause| cause.to_string())?,
+                fd: self.fd.unwrap_or(true),
+                data_bitrate: self.data_bitrate.unwrap_or(2000000),
+                data_sample_point: CanSamplePoint::try_from(self.data_sample_point.unwrap_or(0.7))
+                    .map_err(|cause| cause.to_string())?,
+            },
+        };
+
+        Ok(NetworkInterfaceDescriptor { name, configuration })
+    }
+}
+
+impl DeviceManifest {
+    fn resolve(&self, existing_peer: Option<&PeerDescriptor>, interfaces: &[NetworkInterfaceDescriptor]) -> crate::Result<DeviceDescriptor> {
+        let existing_id = existing_peer
+            .and_then(|peer| peer.topology.devices.iter().find(|device| device.name.value() == self.name))
+            .map(|device| device.id);
+
+        let interface = interfaces.iter()
+            .find(|interface| interface.name.name() == self.interface)
+            .cloned()
+            .ok_or_else(|| format!("Interface '{}' of device '{}' is not described for the peer.", self.interface, self.name))?;
+
+        Ok(DeviceDescriptor {
+            id: self.id.map(DeviceId::from)
+                .or(existing_id)
+                .unwrap_or_else(DeviceId::random),
+            name: DeviceName::try_from(Clone::clone(&self.name))
+                .map_err(|cause| cause.to_string())?,
+            description: self.description.clone()
+                .map(DeviceDescription::try_from)
+                .transpose()
+                .map_err(|cause| cause.to_string())?,
+            interface,
+            tags: self.tags.iter()
+                .map(|tag| DeviceTag::try_from(tag.as_str()))
+                .collect::<Result<_, _>>()
+                .map_err(|cause| cause.to_string())?,
+        })
+    }
+}
+
+impl ExecutorManifest {
+    fn resolve(&self) -> crate::Result<ExecutorDescriptor> {
+        let mut envs = Vec::new();
+        for (env, secret) in self.envs.iter().map(|env| (env, false)).chain(self.secret_envs.iter().map(|env| (env, true))) {
+            let (name, value) = env.split_once('=')
+                .ok_or_else(|| format!("Environment variable '{env}' is not of the form 'NAME=value'."))?;
+            let env = if secret {
+                ContainerEnvironmentVariable::new_secret(name, value)
+            } else {
+                ContainerEnvironmentVariable::new(name, value)
+            };
+            envs.push(env.map_err(|cause| cause.to_string())?);
+        }
+
+        Ok(ExecutorDescriptor::Container {
+            engine: Clone::clone(&self.engine),
+            name: self.name.clone()
+                .map(ContainerName::try_from)
+                .transpose()
+                .map_err(|cause| cause.to_string())?
+                .unwrap_or_default(),
+            image: ContainerImage::try_from(Clone::clone(&self.image))
+                .map_err(|cause| cause.to_string())?,
+            volumes: self.volumes.iter()
+                .map(|volume| ContainerVolume::try_from(volume.as_str()))
+                .collect::<Result<_, _>>()
+                .map_err(|cause| cause.to_string())?,
+            devices: self.devices.iter()
+                .map(|device| ContainerDevice::try_from(device.as_str()))
+                .collect::<Result<_, _>>()
+                .map_err(|cause| cause.to_string())?,
+            envs,
+            ports: self.ports.iter()
+                .map(|port| ContainerPortSpec::try_from(port.as_str()))
+                .collect::<Result<_, _>>()
+                .map_err(|cause| cause.to_string())?,
+            command: self.command.clone()
+                .map(ContainerCommand::try_from)
+                .transpose()
+                .map_err(|cause| cause.to_string())?
+                .unwrap_or_default(),
+            args: self.args.iter()
+                .map(|arg| ContainerCommandArgument::try_from(arg.as_str()))
+                .collect::<Result<_, _>>()
+                .map_err(|cause| cause.to_string())?,
+        })
+    }
+}
+
+impl ClusterManifest {
+    fn resolve(&self, current: &CurrentState, available_peers: &[&PeerDescriptor]) -> crate::Result<DesiredCluster> {
+        let error = |cause: String| format!("Invalid cluster '{}' in manifest.\n  {cause}", self.name);
+
+        let existing = current.clusters.iter()
+            .find(|cluster| match self.id {
+                Some(id) => cluster.id == ClusterId::from(id),
+                None => cluster.name.to_string() == self.name,
+            });
+
+        let leader = available_peers.iter()
+            .find(|peer| peer.name.to_string() == self.leader)
+            .map(|peer| peer.id)
+            .ok_or_else(|| error(format!("Leader '{}' is neither described in the manifest nor known to CARL.", self.leader)))?;
+
+        let mut devices = HashSet::new();
+        for device_name in &self.devices {
+            let matching = available_peers.iter()
+                .flat_map(|peer| peer.topology.devices.iter())
+                .filter(|device| device.name.value() == device_name)
+                .map(|device| device.id)
+                .collect::<Vec<_>>();
+
+            match matching.as_slice() {
+                [] => return Err(error(format!("Device '{device_name}' is neither described in the manifest nor known to CARL."))),
+                [device_id] => { devices.insert(*device_id); }
+                _ => return Err(error(format!("Multiple devices found for the name '{device_name}'."))),
+            }
+        }
+
+        Ok(DesiredCluster {
+            configuration: ClusterConfiguration {
+                id: self.id.map(ClusterId::from)
+                    .or(existing.map(|cluster| cluster.id))
+                    .unwrap_or_else(ClusterId::random),
+                name: ClusterName::try_from(Clone::clone(&self.name))
+                    .map_err(|cause| error(cause.to_string()))?,
+                leader,
+                devices,
+                revision: existing.map(|cluster| cluster.revision).unwrap_or_default(),
+            },
+            deployed: self.deployed,
+        })
+    }
+}
+
+/// Determines the changes, which converge CARL's state to the desired state.
+/// Resources not described in the manifest are left untouched.
+pub fn plan_apply(current: &CurrentState, desired: &DesiredState) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    for cluster in &desired.clusters {
+        let is_deployed = current.deployments.contains(&cluster.configuration.id);
+        if cluster.deployed == Some(false) && is_deployed {
+            changes.push(Change::Undeploy(Clone::clone(&cluster.configuration)));
+        }
+    }
+
+    for peer in &desired.peers {
+        match current.peers.iter().find(|current| current.id == peer.id) {
+            None => changes.push(Change::CreatePeer(Clone::clone(peer))),
+            Some(current) => {
+                if *current != redacted(peer) {
+                    changes.push(Change::UpdatePeer { current: Clone::clone(current), desired: Clone::clone(peer) });
+                }
+            }
+        }
+    }
+
+    for cluster in &desired.clusters {
+        let configuration = &cluster.configuration;
+        match current.clusters.iter().find(|current| current.id == configuration.id) {
+            None => changes.push(Change::CreateCluster(Clone::clone(configuration))),
+            Some(current) => {
+                if current != configuration {
+                    changes.push(Change::UpdateCluster { current: Clone::clone(current), desired: Clone::clone(configuration) });
+                }
+            }
+        }
+    }
+
+    for cluster in &desired.clusters {
+        let is_deployed = current.deployments.contains(&cluster.configuration.id);
+        if cluster.deployed == Some(true) && is_deployed.not() {
+            changes.push(Change::Deploy(Clone::clone(&cluster.configuration)));
+        }
+    }
+
+    changes
+}
+
+/// Determines the changes, which remove the resources described in the manifest from CARL.
+/// Clusters are undeployed and deleted before their peers.
+pub fn plan_delete(current: &CurrentState, desired: &DesiredState) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    for cluster in &desired.clusters {
+        if let Some(current_cluster) = current.clusters.iter().find(|current| current.id == cluster.configuration.id) {
+            if current.deployments.contains(&current_cluster.id) {
+                changes.push(Change::Undeploy(Clone::clone(current_cluster)));
+            }
+            changes.push(Change::DeleteCluster(Clone::clone(current_cluster)));
+        }
+    }
+
+    for peer in &desired.peers {
+        if let Some(current_peer) = current.peers.iter().find(|current| current.id == peer.id) {
+            changes.push(Change::DeletePeer(Clone::clone(current_peer)));
+        }
+    }
+
+    changes
+}
+
+/// CARL only returns redacted secrets, so changes of secret values cannot be detected.
+fn redacted(peer: &PeerDescriptor) -> PeerDescriptor {
+    let mut peer = Clone::clone(peer);
+    for executor in &mut peer.executors.executors {
+        if let ExecutorDescriptor::Container { envs, .. } = executor {
+            *envs = envs.drain(..).map(ContainerEnvironmentVariable::redacted).collect();
+        }
+    }
+    peer
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::CreatePeer(peer) => write!(f, "+ peer '{}' <{}>", peer.name, peer.id),
+            Change::UpdatePeer { current, desired } => {
+                write!(f, "~ peer '{}' <{}>", desired.name, desired.id)?;
+                if current.name != desired.name {
+                    write!(f, "\n    name: '{}' -> '{}'", current.name, desired.name)?;
+                }
+                if current.location != desired.location {
+                    write!(f, "\n    location: '{}' -> '{}'", current.location.clone().unwrap_or_default(), desired.location.clone().unwrap_or_default())?;
+                }
+                write_differences(f, "interface",
+                    &current.network_configuration.interfaces,
+                    &desired.network_configuration.interfaces,
+                    |interface| interface.name.name(),
+                )?;
+                write_differences(f, "device",
+                    &current.topology.devices,
+                    &desired.topology.devices,
+                    |device| device.name.to_string(),
+                )?;
+                let desired = redacted(desired);
+                write_differences(f, "executor",
+                    &current.executors.executors,
+                    &desired.executors.executors,
+                    |executor| match executor {
+                        ExecutorDescriptor::Executable => String::from("executable"),
+                        ExecutorDescriptor::Container { image, .. } => image.to_string(),
+                    },
+                )
+            }
+            Change::DeletePeer(peer) => write!(f, "- peer '{}' <{}>", peer.name, peer.id),
+            Change::CreateCluster(cluster) => write!(f, "+ cluster '{}' <{}>", cluster.name, cluster.id),
+            Change::UpdateCluster { current, desired } => {
+                write!(f, "~ cluster '{}' <{}>", desired.name, desired.id)?;
+                if current.name != desired.name {
+                    write!(f, "\n    name: '{}' -> '{}'", current.name, desired.name)?;
+                }
+                if current.leader != desired.leader {
+                    write!(f, "\n    leader: <{}> -> <{}>", current.leader, desired.leader)?;
+                }
+                for device in desired.devices.difference(&current.devices) {
+                    write!(f, "\n    + device <{device}>")?;
+                }
+                for device in current.devices.difference(&desired.devices) {
+                    write!(f, "\n    - device <{device}>")?;
+                }
+                Ok(())
+            }
+            Change::DeleteCluster(cluster) => write!(f, "- cluster '{}' <{}>", cluster.name, cluster.id),
+            Change::Deploy(cluster) => write!(f, "+ deployment of cluster '{}' <{}>", cluster.name, cluster.id),
+            Change::Undeploy(cluster) => write!(f, "- deployment of cluster '{}' <{}>", cluster.name, cluster.id),
+        }
+    }
+}
+
+fn write_differences<T: PartialEq>(
+    f: &mut Formatter<'_>,
+    kind: &str,
+    current: &[T],
+    desired: &[T],
+    name: impl Fn(&T) -> String,
+) -> std::fmt::Result {
+    for item in desired {
+        if current.contains(item).not() {
+            let change = if current.iter().any(|other| name(other) == name(item)) { "~" } else { "+" };
+            write!(f, "\n    {change} {kind} '{}'", name(item))?;
+        }
+    }
+    for item in current {
+        if desired.iter().any(|other| name(other) == name(item)).not() {
+            write!(f, "\n    - {kind} '{}'", name(item))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::prelude::*;
+    use googletest::prelude::*;
+    use indoc::indoc;
+
+    use super::*;
+
+    const MANIFEST: &str = indoc!(r#"
+        [[peers]]
+        name = "bench-a"
+        location = "Lab"
+
+        [[peers.interfaces]]
+        name = "eth0"
+        type = "ethernet"
+
+        [[peers.interfaces]]
+        name = "can0"
+        type = "can"
+        bitrate = 250000
+
+        [[peers.devices]]
+        name = "ecu-a"
+        interface = "eth0"
+        tags = ["front"]
+
+        [[peers.devices]]
+        name = "restbus-a"
+        interface = "can0"
+
+        [[peers.executors]]
+        engine = "docker"
+        image = "testenv"
+        secret-envs = ["TOKEN=secret"]
+
+        [[peers]]
+        name = "bench-b"
+
+        [[peers.interfaces]]
+        name = "eth0"
+        type = "ethernet"
+
+        [[peers.devices]]
+        name = "ecu-b"
+        interface = "eth0"
+
+        [[clusters]]
+        name = "bench-cluster"
+        leader = "bench-a"
+        devices = ["ecu-a", "ecu-b"]
+        deployed = true
+    "#);
+
+    fn current_state_after_applying(desired: &DesiredState) -> CurrentState {
+        CurrentState {
+            peers: desired.peers.iter()
+                .map(|peer| PeerDescriptor { revision: peer.revision + 1, ..redacted(peer) })
+                .collect(),
+            clusters: desired.clusters.iter()
+                .map(|cluster| ClusterConfiguration { revision: cluster.configuration.revision + 1, ..Clone::clone(&cluster.configuration) })
+                .collect(),
+            deployments: desired.clusters.iter().map(|cluster| cluster.configuration.id).collect(),
+        }
+    }
+
+    #[test]
+    fn should_create_all_resources_of_a_manifest_and_converge_afterwards() -> anyhow::Result<()> {
+        let manifest: Manifest = toml::from_str(MANIFEST)?;
+        let current = CurrentState::default();
+
+        let desired = manifest.resolve(&current).map_err(anyhow::Error::msg)?;
+        let changes = plan_apply(&current, &desired);
+
+        assert_that!(changes, elements_are![
+            matches_pattern!(Change::CreatePeer(anything())),
+            matches_pattern!(Change::CreatePeer(anything())),
+            matches_pattern!(Change::CreateCluster(anything())),
+            matches_pattern!(Change::Deploy(anything())),
+        ]);
+        let cluster = &desired.clusters[0].configuration;
+        assert_that!(cluster.leader, eq(desired.peers[0].id));
+        assert_that!(cluster.devices, unordered_elements_are![
+            eq(desired.peers[0].topology.devices[0].id),
+            eq(desired.peers[1].topology.devices[0].id),
+        ]);
+
+        let current = current_state_after_applying(&desired);
+        let desired_again = manifest.resolve(&current).map_err(anyhow::Error::msg)?;
+        assert_that!(plan_apply(&current, &desired_again), empty());
+        assert_that!(desired_again.peers[0].topology.devices, eq(Clone::clone(&desired.peers[0].topology.devices)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_update_changed_resources_and_delete_them_in_dependency_order() -> anyhow::Result<()> {
+        let manifest: Manifest = toml::from_str(MANIFEST)?;
+        let desired = manifest.resolve(&CurrentState::default()).map_err(anyhow::Error::msg)?;
+        let current = current_state_after_applying(&desired);
+
+        let mut changed_manifest = Clone::clone(&manifest);
+        changed_manifest.peers[1].devices[0].tags = vec![String::from("rear")];
+        changed_manifest.clusters[0].devices = vec![String::from("ecu-a")];
+        let changed = changed_manifest.resolve(&current).map_err(anyhow::Error::msg)?;
+
+        let changes = plan_apply(&current, &changed);
+        assert_that!(changes, elements_are![
+            matches_pattern!(Change::UpdatePeer { .. }),
+            matches_pattern!(Change::UpdateCluster { .. }),
+        ]);
+        assert_that!(changes[0].to_string(), contains_substring("~ device 'ecu-b'"));
+
+        let changes = plan_delete(&current, &changed);
+        assert_that!(changes, elements_are![
+            matches_pattern!(Change::Undeploy(anything())),
+            matches_pattern!(Change::DeleteCluster(anything())),
+            matches_pattern!(Change::DeletePeer(anything())),
+            matches_pattern!(Change::DeletePeer(anything())),
+        ]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_load_manifests_written_in_yaml() -> anyhow::Result<()> {
+        let temp = assert_fs::TempDir::new()?;
+        let file = temp.child("bench.yaml");
+        file.write_str(indoc!(r#"
+            peers:
+              - name: bench-a
+                location: Lab
+                interfaces:
+                  - name: eth0
+                    type: ethernet
+                  - name: can0
+                    type: can
+                    bitrate: 250000
+                devices:
+                  - name: ecu-a
+                    interface: eth0
+                    tags: [front]
+                  - name: restbus-a
+                    interface: can0
+                executors:
+                  - engine: docker
+                    image: testenv
+                    secret-envs: ["TOKEN=secret"]
+              - name: bench-b
+                interfaces:
+                  - name: eth0
+                    type: ethernet
+                devices:
+                  - name: ecu-b
+                    interface: eth0
+            clusters:
+              - name: bench-cluster
+                leader: bench-a
+                devices: [ecu-a, ecu-b]
+                deployed: true
+        "#))?;
+
+        assert_that!(Manifest::load(file.path()).map_err(anyhow::Error::msg)?, eq(toml::from_str::<Manifest>(MANIFEST)?));
+
+        let unknown = temp.child("bench.xml");
+        unknown.write_str("<peers/>")?;
+        assert_that!(Manifest::load(unknown.path()), err(contains_substring("Unsupported format of manifest")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_fail_for_devices_with_unknown_interfaces_or_ambiguous_names() -> anyhow::Result<()> {
+        let mut manifest: Manifest = toml::from_str(MANIFEST)?;
+        manifest.peers[1].devices[0].name = String::from("ecu-a");
+        assert_that!(manifest.resolve(&CurrentState::default()), err(contains_substring("Multiple devices found for the name 'ecu-a'")));
+
+        manifest.peers[1].devices[0].interface = String::from("eth1");
+        assert_that!(manifest.resolve(&CurrentState::default()), err(contains_substring("Interface 'eth1' of device 'ecu-a'")));
+
+        Ok(())
+    }
+}
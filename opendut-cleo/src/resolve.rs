@@ -0,0 +1,220 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::ops::Not;
+
+use uuid::Uuid;
+
+use opendut_carl_api::carl::CarlClient;
+
+/// Kinds of resources, which can be referenced by name or ID on the command line.
+///
+/// Arguments referencing such a resource carry the kind as their value name, e.g. `#[arg(long, value_name = "PEER")]`,
+/// which is used to complete them with the resources known to CARL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResourceKind {
+    Peer,
+    Device,
+    Cluster,
+}
+
+impl ResourceKind {
+    pub fn from_value_name(value_name: &str) -> Option<Self> {
+        match value_name {
+            "PEER" => Some(ResourceKind::Peer),
+            "DEVICE" => Some(ResourceKind::Device),
+            "CLUSTER" => Some(ResourceKind::Cluster),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ResourceKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceKind::Peer => write!(f, "Peer"),
+            ResourceKind::Device => write!(f, "Device"),
+            ResourceKind::Cluster => write!(f, "Cluster"),
+        }
+    }
+}
+
+/// Whether a resource may be referenced by a prefix of its ID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdPrefix {
+    Allowed,
+    /// Commands deleting a resource require its name or full ID, so that a typo cannot delete another resource.
+    Forbidden,
+}
+
+/// Minimum number of hexadecimal digits of an ID prefix, which makes it unlikely to match another resource by accident.
+pub const MIN_ID_PREFIX_LENGTH: usize = 8;
+
+/// A resource known to CARL, identified by its ID and name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candidate {
+    pub id: Uuid,
+    pub name: String,
+}
+
+/// Lists the resources of the given kind in the selected project.
+pub async fn candidates(carl: &mut CarlClient, kind: ResourceKind) -> crate::Result<Vec<Candidate>> {
+    let candidates = match kind {
+        ResourceKind::Peer => {
+            carl.peers.list_peer_descriptors().await
+                .map_err(|error| format!("Could not list peers.\n  {}", error))?
+                .into_iter()
+                .map(|peer| Candidate { id: peer.id.0, name: peer.name.value() })
+                .collect()
+        }
+        ResourceKind::Device => {
+            carl.peers.list_devices().await
+                .map_err(|error| format!("Could not list devices.\n  {}", error))?
+                .into_iter()
+                .map(|device| Candidate { id: device.id.0, name: device.name.value().to_owned() })
+                .collect()
+        }
+        ResourceKind::Cluster => {
+            carl.cluster.list_cluster_configurations().await
+                .map_err(|error| format!("Could not list cluster configurations.\n  {}", error))?
+                .into_iter()
+                .map(|cluster| Candidate { id: cluster.id.0, name: cluster.name.value() })
+                .collect()
+        }
+    };
+    Ok(candidates)
+}
+
+/// Resolves the name or ID of a resource to its ID, by querying the resources known to CARL.
+pub async fn resolve(carl: &mut CarlClient, kind: ResourceKind, name_or_id: &str, id_prefix: IdPrefix) -> crate::Result<Uuid> {
+    if let Ok(id) = Uuid::parse_str(name_or_id) {
+        return Ok(id);
+    }
+    let candidates = candidates(carl, kind).await?;
+    resolve_among(&candidates, kind, name_or_id, id_prefix)
+}
+
+pub async fn peer(carl: &mut CarlClient, name_or_id: &str) -> crate::Result<Uuid> {
+    resolve(carl, ResourceKind::Peer, name_or_id, IdPrefix::Allowed).await
+}
+
+pub async fn device(carl: &mut CarlClient, name_or_id: &str) -> crate::Result<Uuid> {
+    resolve(carl, ResourceKind::Device, name_or_id, IdPrefix::Allowed).await
+}
+
+pub async fn cluster(carl: &mut CarlClient, name_or_id: &str) -> crate::Result<Uuid> {
+    resolve(carl, ResourceKind::Cluster, name_or_id, IdPrefix::Allowed).await
+}
+
+/// Resolves names and full IDs only, see [`IdPrefix::Forbidden`].
+pub mod exact {
+    use uuid::Uuid;
+
+    use opendut_carl_api::carl::CarlClient;
+
+    use super::{IdPrefix, resolve, ResourceKind};
+
+    pub async fn peer(carl: &mut CarlClient, name_or_id: &str) -> crate::Result<Uuid> {
+        resolve(carl, ResourceKind::Peer, name_or_id, IdPrefix::Forbidden).await
+    }
+
+    pub async fn device(carl: &mut CarlClient, name_or_id: &str) -> crate::Result<Uuid> {
+        resolve(carl, ResourceKind::Device, name_or_id, IdPrefix::Forbidden).await
+    }
+
+    pub async fn cluster(carl: &mut CarlClient, name_or_id: &str) -> crate::Result<Uuid> {
+        resolve(carl, ResourceKind::Cluster, name_or_id, IdPrefix::Forbidden).await
+    }
+}
+
+/// Resolves a name, an ID or, if allowed, the unique prefix of an ID among the given candidates.
+pub fn resolve_among(candidates: &[Candidate], kind: ResourceKind, name_or_id: &str, id_prefix: IdPrefix) -> crate::Result<Uuid> {
+    let by_name = candidates.iter()
+        .filter(|candidate| candidate.name == name_or_id)
+        .collect::<Vec<_>>();
+    let matches = if by_name.is_empty().not() {
+        by_name
+    } else if let Ok(id) = Uuid::parse_str(name_or_id) {
+        candidates.iter()
+            .filter(|candidate| candidate.id == id)
+            .collect::<Vec<_>>()
+    } else if id_prefix == IdPrefix::Allowed && is_id_prefix(name_or_id) {
+        candidates.iter()
+            .filter(|candidate| candidate.id.to_string().starts_with(&name_or_id.to_lowercase()))
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+
+    match matches.as_slice() {
+        [] => match id_prefix {
+            IdPrefix::Allowed => Err(format!("{kind} '{name_or_id}' does not exist. Abbreviated IDs need at least {MIN_ID_PREFIX_LENGTH} characters.")),
+            IdPrefix::Forbidden => Err(format!("{kind} '{name_or_id}' does not exist. Specify its name or full ID.")),
+        },
+        [candidate] => Ok(candidate.id),
+        ambiguous => {
+            let ids = ambiguous.iter()
+                .map(|candidate| format!("{} ({})", candidate.id, candidate.name))
+                .collect::<Vec<_>>();
+            Err(format!("{kind} '{name_or_id}' is ambiguous. Specify one of the following IDs instead:\n  {}", ids.join("\n  ")))
+        }
+    }
+}
+
+fn is_id_prefix(value: &str) -> bool {
+    value.chars().all(|character| character.is_ascii_hexdigit() || character == '-')
+        && value.chars().filter(char::is_ascii_hexdigit).count() >= MIN_ID_PREFIX_LENGTH
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+
+    use super::*;
+
+    fn candidates() -> Vec<Candidate> {
+        vec![
+            Candidate { id: Uuid::parse_str("1d3c0f56-0000-4000-8000-000000000001").unwrap(), name: String::from("bench-a") },
+            Candidate { id: Uuid::parse_str("1d3c0f56-0000-4000-8000-000000000002").unwrap(), name: String::from("bench-b") },
+            Candidate { id: Uuid::parse_str("7f0a2e11-0000-4000-8000-000000000003").unwrap(), name: String::from("bench-b") },
+        ]
+    }
+
+    #[test]
+    fn should_resolve_names_and_unique_id_prefixes() -> Result<()> {
+        let candidates = candidates();
+
+        verify_that!(resolve_among(&candidates, ResourceKind::Peer, "bench-a", IdPrefix::Allowed), ok(eq(candidates[0].id)))?;
+        verify_that!(resolve_among(&candidates, ResourceKind::Peer, "7f0a2e11", IdPrefix::Allowed), ok(eq(candidates[2].id)))?;
+        verify_that!(resolve_among(&candidates, ResourceKind::Peer, "7F0A2E11-0000", IdPrefix::Allowed), ok(eq(candidates[2].id)))
+    }
+
+    #[test]
+    fn should_reject_short_id_prefixes() -> Result<()> {
+        let candidates = candidates();
+
+        verify_that!(resolve_among(&candidates, ResourceKind::Peer, "7f0a2e1", IdPrefix::Allowed), err(starts_with("Peer '7f0a2e1' does not exist.")))?;
+        verify_that!(resolve_among(&candidates, ResourceKind::Peer, "7", IdPrefix::Allowed), err(starts_with("Peer '7' does not exist.")))
+    }
+
+    #[test]
+    fn should_only_resolve_names_and_full_ids_without_id_prefixes() -> Result<()> {
+        let candidates = candidates();
+
+        verify_that!(resolve_among(&candidates, ResourceKind::Peer, "bench-a", IdPrefix::Forbidden), ok(eq(candidates[0].id)))?;
+        verify_that!(resolve_among(&candidates, ResourceKind::Peer, "7f0a2e11-0000-4000-8000-000000000003", IdPrefix::Forbidden), ok(eq(candidates[2].id)))?;
+        verify_that!(resolve_among(&candidates, ResourceKind::Peer, "7f0a2e11", IdPrefix::Forbidden), err(eq("Peer '7f0a2e11' does not exist. Specify its name or full ID.")))?;
+        verify_that!(resolve_among(&candidates, ResourceKind::Peer, "7f0a2e11-0000-4000-8000-000000000004", IdPrefix::Forbidden), err(anything()))
+    }
+
+    #[test]
+    fn should_reject_unknown_and_ambiguous_names() -> Result<()> {
+        let candidates = candidates();
+
+        verify_that!(resolve_among(&candidates, ResourceKind::Peer, "bench-c", IdPrefix::Allowed), err(starts_with("Peer 'bench-c' does not exist.")))?;
+        verify_that!(resolve_among(&candidates, ResourceKind::Peer, "bench-b", IdPrefix::Allowed), err(all!(
+            starts_with("Peer 'bench-b' is ambiguous."),
+            contains_substring("7f0a2e11-0000-4000-8000-000000000003 (bench-b)"),
+        )))?;
+        verify_that!(resolve_among(&candidates, ResourceKind::Peer, "1d3c0f56", IdPrefix::Allowed), err(starts_with("Peer '1d3c0f56' is ambiguous.")))
+    }
+}
@@ -1,4 +1,4 @@
 pub mod client;
 pub mod error;
 pub mod extension;
-mod proto;
+pub mod proto;
@@ -13,3 +13,7 @@ pub fn default_bridge_name() -> NetworkInterfaceName {
 pub fn default_can_bridge_name() -> NetworkInterfaceName {
     NetworkInterfaceName::from_str("br-vcan-opendut").unwrap()
 }
+
+pub fn default_wireguard_interface_name() -> NetworkInterfaceName {
+    NetworkInterfaceName::from_str("wg-opendut").unwrap()
+}
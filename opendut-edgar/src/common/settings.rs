@@ -10,6 +10,22 @@ pub mod key {
     pub mod vpn {
         pub const table: &str = "vpn";
 
+        pub mod netbird {
+            pub mod health {
+                pub mod check {
+                    pub const interval_ms: &str = "vpn.netbird.health.check.interval.ms";
+                }
+            }
+        }
+
+        pub mod wireguard {
+            pub const table: &str = "vpn.wireguard";
+
+            pub mod endpoint {
+                pub const host: &str = "vpn.wireguard.endpoint.host";
+            }
+        }
+
         pub mod disabled {
             pub const table: &str = "vpn.disabled";
 
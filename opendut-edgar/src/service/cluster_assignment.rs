@@ -10,14 +10,16 @@ use crate::service::network_interface;
 use crate::service::network_interface::{bridge, gre};
 use crate::service::network_interface::manager::NetworkInterfaceManagerRef;
 use crate::service::can_manager::CanManagerRef;
+use crate::service::vpn::wireguard;
 
-#[tracing::instrument(skip(cluster_assignment, can_manager, network_interface_manager), level="trace")]
+#[tracing::instrument(skip(cluster_assignment, can_manager, network_interface_manager, wireguard_peers), level="trace")]
 pub async fn network_interfaces_setup(
     cluster_assignment: ClusterAssignment,
     self_id: PeerId,
     bridge_name: &NetworkInterfaceName,
     network_interface_manager: NetworkInterfaceManagerRef,
     can_manager: CanManagerRef,
+    wireguard_peers: &wireguard::ConfiguredPeers,
 ) -> Result<(), Error> {
 
     bridge::recreate(bridge_name, Arc::clone(&network_interface_manager)).await
@@ -27,6 +29,11 @@ pub async fn network_interfaces_setup(
         assignment.peer_id == self_id
     }).ok_or(Error::LocalPeerAssignmentNotFound { self_id })?;
 
+    if cluster_assignment.assignments.iter().any(|assignment| assignment.wireguard.is_some()) {
+        wireguard::configure_peers(&crate::common::default_wireguard_interface_name(), self_id, &cluster_assignment, wireguard_peers).await
+            .map_err(Error::WireGuardPeerSetupFailed)?;
+    }
+
     let local_ip = local_peer_assignment.vpn_address;
 
     let remote_ips = determine_remote_ips(&cluster_assignment, self_id)?;
@@ -190,6 +197,8 @@ pub enum Error {
     LeaderNotDeterminable,
     #[error("IPv6 isn't yet supported for GRE interfaces.")]
     Ipv6NotSupported,
+    #[error("WireGuard peer setup failed: {0}")]
+    WireGuardPeerSetupFailed(wireguard::Error),
     #[error("GRE interface setup failed: {0}")]
     GreInterfaceSetupFailed(gre::Error),
     #[error("Local CAN routing setup failed: {0}")]
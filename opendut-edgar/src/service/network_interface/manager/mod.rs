@@ -1,6 +1,6 @@
 use std::fmt::{Debug, Formatter};
 use std::io;
-use std::net::Ipv4Addr;
+use std::net::{IpAddr, Ipv4Addr};
 use std::sync::Arc;
 
 use anyhow::anyhow;
@@ -95,6 +95,26 @@ impl NetworkInterfaceManager {
         Ok(interface)
     }
 
+    pub async fn create_wireguard_interface(&self, name: &NetworkInterfaceName) -> Result<Interface, Error> {
+        self.handle
+            .link()
+            .add()
+            .wireguard(name.name())
+            .execute().await
+            .map_err(|cause| Error::WireGuardCreation { name: name.clone(), cause })?;
+        let interface = self.try_find_interface(name).await?;
+        Ok(interface)
+    }
+
+    pub async fn add_ip_address(&self, interface: &Interface, address: IpAddr, prefix_length: u8) -> Result<(), Error> {
+        self.handle
+            .address()
+            .add(interface.index, address, prefix_length)
+            .execute().await
+            .map_err(|cause| Error::AddIpAddress { interface: interface.clone(), address, cause })?;
+        Ok(())
+    }
+
     pub async fn set_interface_up(&self, interface: &Interface) -> Result<(), Error> {
         self.handle
             .link()
@@ -184,6 +204,10 @@ pub enum Error {
     DeleteInterface { interface: Interface, cause: rtnetlink::Error },
     #[error("Failure while creating gretap interface '{name}': {cause}")]
     GretapCreation { name: NetworkInterfaceName, cause: rtnetlink::Error },
+    #[error("Failure while creating WireGuard interface '{name}': {cause}")]
+    WireGuardCreation { name: NetworkInterfaceName, cause: rtnetlink::Error },
+    #[error("Failure while adding IP address '{address}' to interface {interface}: {cause}")]
+    AddIpAddress { interface: Interface, address: IpAddr, cause: rtnetlink::Error },
     #[error("Interface with name '{name}' not found.")]
     InterfaceNotFound { name: NetworkInterfaceName },
     #[error("Failure while listing interfaces: {cause}")]
@@ -2,7 +2,7 @@ use std::any::Any;
 use std::fmt::Debug;
 use std::ops::Not;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::Context;
 use config::Config;
@@ -10,18 +10,19 @@ use opentelemetry::propagation::text_map_propagator::TextMapPropagator;
 use opentelemetry_sdk::propagation::TraceContextPropagator;
 use tokio::process::Command;
 use tokio::sync::mpsc::Sender;
-use tokio::time::sleep;
+use tokio::time::{Instant, sleep};
 use tracing::{debug, error, info, Span, trace, warn};
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use opendut_carl_api::proto::services::peer_messaging_broker;
 use opendut_carl_api::proto::services::peer_messaging_broker::{ApplyPeerConfiguration, TracingContext};
 use opendut_carl_api::proto::services::peer_messaging_broker::downstream::Message;
-use opendut_types::cluster::ClusterAssignment;
+use opendut_types::cluster::{ClusterAssignment, ClusterId};
 use opendut_types::peer::configuration::PeerConfiguration;
-use opendut_types::peer::executor::{ContainerCommand, ContainerName, Engine, ExecutorDescriptor, ExecutorDescriptors};
+use opendut_types::peer::executor::{ContainerCommand, ContainerEnvironmentVariableValue, ContainerName, Engine, ExecutorDescriptor, ExecutorDescriptors};
 use opendut_types::peer::PeerId;
 use opendut_types::util::net::NetworkInterfaceName;
+use opendut_types::vpn::health::VpnHealth;
 use opendut_util::logging;
 use opendut_util::logging::LoggingConfig;
 use opendut_util::settings::LoadedConfig;
@@ -89,7 +90,10 @@ pub async fn create(self_id: PeerId, settings: LoadedConfig) -> anyhow::Result<(
 
     let bridge_name = crate::common::default_bridge_name();
 
-    let remote_address = vpn::retrieve_remote_host(&settings).await?;
+    vpn::wireguard::setup_interface(&settings, Arc::clone(&network_interface_manager)).await?;
+
+    let mut remote_address = vpn::retrieve_remote_host(&settings).await?;
+    let mut vpn_health = vpn::monitor_health(&settings)?;
 
     let setup_cluster_info = SetupClusterInfo {
         self_id,
@@ -97,45 +101,92 @@ pub async fn create(self_id: PeerId, settings: LoadedConfig) -> anyhow::Result<(
         network_interface_management_enabled,
         network_interface_manager,
         can_manager,
+        wireguard_peers: Default::default(),
     };
 
     let timeout_duration = Duration::from_millis(settings.config.get::<u64>("carl.disconnect.timeout.ms")?);
 
     let mut carl = carl::connect(&settings.config).await?;
 
-    let (mut rx_inbound, tx_outbound) = carl::open_stream(self_id, &remote_address, &mut carl).await?;
-
     loop {
-        let received = tokio::time::timeout(timeout_duration, rx_inbound.message()).await;
-
-        match received {
-            Ok(received) => match received {
-                Ok(Some(message)) => {
-                    handle_stream_message(
-                        message,
-                        &setup_cluster_info,
-                        &tx_outbound,
-                    ).await?
+        let (mut rx_inbound, tx_outbound) = carl::open_stream(self_id, &remote_address, &mut carl).await?;
+        let mut deadline = Instant::now() + timeout_duration;
+
+        let changed_address = loop {
+            let next_vpn_health = async {
+                match vpn_health.as_mut() {
+                    Some(vpn_health) => vpn_health.recv().await,
+                    None => std::future::pending().await,
                 }
-                Err(status) => {
-                    warn!("CARL sent a gRPC error status: {status}");
-                    //TODO exit?
+            };
+
+            tokio::select! {
+                received = tokio::time::timeout_at(deadline, rx_inbound.message()) => {
+                    match received {
+                        Ok(received) => match received {
+                            Ok(Some(message)) => {
+                                deadline = Instant::now() + timeout_duration;
+                                handle_stream_message(
+                                    message,
+                                    &setup_cluster_info,
+                                    &tx_outbound,
+                                ).await?
+                            }
+                            Err(status) => {
+                                warn!("CARL sent a gRPC error status: {status}");
+                                //TODO exit?
+                            }
+                            Ok(None) => {
+                                info!("CARL disconnected!");
+                                break None;
+                            }
+                        }
+                        Err(_) => {
+                            error!("No message from CARL within {} ms.", timeout_duration.as_millis());
+                            break None;
+                        }
+                    }
                 }
-                Ok(None) => {
-                    info!("CARL disconnected!");
-                    break;
+                Some(health) = next_vpn_health => {
+                    let address = health.address;
+                    report_vpn_health(health, &tx_outbound).await;
+
+                    match address {
+                        Some(address) if address != remote_address => {
+                            info!("VPN address changed from {remote_address} to {address}. Re-opening stream to CARL.");
+                            break Some(address);
+                        }
+                        _ => {}
+                    }
                 }
             }
-            Err(_) => {
-                error!("No message from CARL within {} ms.", timeout_duration.as_millis());
-                break;
-            }
+        };
+
+        match changed_address {
+            Some(address) => remote_address = address,
+            None => break,
         }
     }
 
     Ok(())
 }
 
+async fn report_vpn_health(health: VpnHealth, tx_outbound: &Sender<peer_messaging_broker::Upstream>) {
+    if health.connected.not() {
+        warn!("VPN is not connected.");
+    }
+
+    let message = peer_messaging_broker::Upstream {
+        message: Some(peer_messaging_broker::upstream::Message::ReportVpnHealth(peer_messaging_broker::ReportVpnHealth {
+            health: Some(health.into()),
+        })),
+        context: None
+    };
+    let _ignore_error =
+        tx_outbound.send(message).await
+            .inspect_err(|cause| debug!("Failed to report VPN health to CARL: {cause}"));
+}
+
 
 async fn handle_stream_message(
     message: peer_messaging_broker::Downstream,
@@ -160,6 +211,7 @@ async fn handle_stream_message(
                         .inspect_err(|cause| debug!("Failed to send ping to CARL: {cause}"));
             }
             Message::ApplyPeerConfiguration(message) => { apply_peer_configuration(message, context, setup_cluster_info).await }
+            Message::ClusterLeaseExpiring(message) => { warn_lease_expiring(message) }
         }
     } else {
         ignore(message)
@@ -168,6 +220,19 @@ async fn handle_stream_message(
     Ok(())
 }
 
+fn warn_lease_expiring(message: peer_messaging_broker::ClusterLeaseExpiring) {
+    let Some(cluster_id) = message.cluster_id.clone().and_then(|cluster_id| ClusterId::try_from(cluster_id).ok()) else {
+        return ignore(message);
+    };
+
+    let now_ms = SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|now| i64::try_from(now.as_millis()).unwrap_or(i64::MAX))
+        .unwrap_or_default();
+    let remaining_minutes = (message.end_ms - now_ms).max(0) / 60_000;
+
+    warn!("The lease of cluster <{cluster_id}> expires in {remaining_minutes} minutes. The cluster will be undeployed then.");
+}
+
 #[tracing::instrument(skip(message, context, setup_cluster_info), level="trace")]
 async fn apply_peer_configuration(message: ApplyPeerConfiguration, context: Option<TracingContext>, setup_cluster_info: &SetupClusterInfo) {
     match message.clone() {
@@ -198,62 +263,86 @@ fn setup_executors(executors: ExecutorDescriptors) { //TODO make idempotent
     for executor in executors.executors {
         match executor {
             ExecutorDescriptor::Executable => warn!("Executing Executable not yet implemented."),
-            ExecutorDescriptor::Container {
-                engine,
-                name,
-                image,
-                volumes,
-                devices,
-                envs,
-                ports,
-                command,
-                args
-            } => {
-                let engine = match engine {
-                    Engine::Docker => { "docker" }
-                    Engine::Podman => { "podman" }
-                };
-                let mut cmd = Command::new(engine);
-                cmd.arg("run");
-                cmd.arg("--restart=unless-stopped");
-                if let ContainerName::Value(name) = name {
-                    cmd.args(["--name", name.as_str()]);
-                }
-                for port in ports {
-                    cmd.args(["--publish", port.value()]);
-                }
-                for volume in volumes {
-                    cmd.args(["--volume", volume.value()]);
-                }
-                for device in devices {
-                    cmd.args(["--devices", device.value()]);
-                }
-                for env in envs {
-                    cmd.args(["--env", &format!("{}={}", env.name(), env.value())]);
+            container @ ExecutorDescriptor::Container { .. } => {
+                match start_container(container) {
+                    Ok(()) => info!("Container started."),
+                    Err(error) => error!("Failed to start container: {error:#}"),
                 }
-                cmd.arg(image.value());
-                if let ContainerCommand::Value(command) = command {
-                    cmd.arg(command.as_str());
-                }
-                for arg in args {
-                    cmd.arg(arg.value());
-                }
-                debug!("Command: {:?}", cmd);
-                match cmd.spawn() {
-                    Ok(_) => { info!("Container started.") }
-                    Err(_) => { error!("Failed to start container.") }
-                };
             }
         }
     }
 }
 
+fn start_container(container: ExecutorDescriptor) -> anyhow::Result<()> {
+    let ExecutorDescriptor::Container {
+        engine,
+        name,
+        image,
+        volumes,
+        devices,
+        envs,
+        ports,
+        command,
+        args
+    } = container else {
+        anyhow::bail!("Executor is not a container.");
+    };
+
+    let engine = match engine {
+        Engine::Docker => { "docker" }
+        Engine::Podman => { "podman" }
+    };
+    let mut cmd = Command::new(engine);
+    cmd.arg("run");
+    cmd.arg("--restart=unless-stopped");
+    if let ContainerName::Value(name) = name {
+        cmd.args(["--name", name.as_str()]);
+    }
+    for port in ports {
+        cmd.args(["--publish", port.value()]);
+    }
+    for volume in volumes {
+        cmd.args(["--volume", volume.value()]);
+    }
+    for device in devices {
+        cmd.args(["--devices", device.value()]);
+    }
+    for env in envs {
+        match env.value() {
+            ContainerEnvironmentVariableValue::Plain(value) => {
+                cmd.args(["--env", &format!("{}={}", env.name(), value)]);
+            }
+            ContainerEnvironmentVariableValue::Secret(value) => {
+                // Only pass the name, so the engine takes the value from its own environment and it does not show up in the argument list.
+                cmd.args(["--env", env.name()]);
+                cmd.env(env.name(), value);
+            }
+            ContainerEnvironmentVariableValue::Redacted => {
+                anyhow::bail!("Value of secret environment variable '{}' of image '{}' was not provided by CARL.", env.name(), image.value());
+            }
+        }
+    }
+    cmd.arg(image.value());
+    if let ContainerCommand::Value(command) = command {
+        cmd.arg(command.as_str());
+    }
+    for arg in args {
+        cmd.arg(arg.value());
+    }
+    // not logging the Command itself, since its Debug output includes the environment and thereby the secrets
+    debug!("Command: {:?} {:?}", cmd.as_std().get_program(), cmd.as_std().get_args().collect::<Vec<_>>());
+    cmd.spawn()
+        .with_context(|| format!("Failed to run '{engine}' for image '{}'.", image.value()))?;
+    Ok(())
+}
+
 struct SetupClusterInfo {
     self_id: PeerId,
     bridge_name: NetworkInterfaceName,
     network_interface_management_enabled: bool,
     network_interface_manager: NetworkInterfaceManagerRef,
     can_manager: CanManagerRef,
+    wireguard_peers: vpn::wireguard::ConfiguredPeers,
 }
 #[tracing::instrument(skip(cluster_assignment, info))]
 async fn setup_cluster(
@@ -272,7 +361,8 @@ async fn setup_cluster(
                     info.self_id,
                     &info.bridge_name,
                     Arc::clone(&info.network_interface_manager),
-                    Arc::clone(&info.can_manager)
+                    Arc::clone(&info.can_manager),
+                    &info.wireguard_peers,
                 ).await
                     .inspect_err(|error| {
                         error!("Failed to configure network interfaces: {error}")
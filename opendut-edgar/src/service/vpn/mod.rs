@@ -1,17 +1,31 @@
 use std::net::IpAddr;
+use std::time::Duration;
 
 use anyhow::anyhow;
 use serde::Deserialize;
+use tokio::sync::mpsc;
 
 use opendut_netbird_client_api::extension::LocalPeerStateExtension;
+use opendut_types::vpn::health::VpnHealth;
 use opendut_util::settings::LoadedConfig;
 
 use crate::common::settings;
 
+pub mod netbird;
+pub mod wireguard;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all="kebab-case")]
 pub struct VpnConfig {
     pub enabled: bool,
+    pub kind: VpnKind,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all="kebab-case")]
+pub enum VpnKind {
+    Netbird,
+    Wireguard,
 }
 
 
@@ -19,18 +33,35 @@ pub async fn retrieve_remote_host(settings: &LoadedConfig) -> anyhow::Result<IpA
     let vpn_config = settings.config.get::<VpnConfig>(settings::key::vpn::table)?;
 
     let address = if vpn_config.enabled {
-        let mut client = opendut_netbird_client_api::client::Client::connect().await?;
+        match vpn_config.kind {
+            VpnKind::Netbird => {
+                let mut client = opendut_netbird_client_api::client::Client::connect().await?;
 
-        let status = client.full_status().await?;
+                let status = client.full_status().await?;
 
-        let host = status.local_peer_state
-            .ok_or(anyhow!("NetBird Client did not return a local peer state. May not be logged in. Re-run `edgar setup` to fix this."))?
-            .local_ip()?;
+                let host = status.local_peer_state
+                    .ok_or(anyhow!("NetBird Client did not return a local peer state. May not be logged in. Re-run `edgar setup` to fix this."))?
+                    .local_ip()?;
 
-        IpAddr::from(host)
+                IpAddr::from(host)
+            }
+            VpnKind::Wireguard => wireguard::retrieve_endpoint_host(settings).await?,
+        }
     } else {
         settings.config.get::<IpAddr>(settings::key::vpn::disabled::remote::host)
             .map_err(|cause| anyhow!("Configuration value '{field}' must be a valid IP address: {cause}", field=settings::key::vpn::disabled::remote::host))?
     };
     Ok(address)
 }
+
+/// Starts monitoring the health of the VPN connection, if the configured VPN supports it.
+pub fn monitor_health(settings: &LoadedConfig) -> anyhow::Result<Option<mpsc::Receiver<VpnHealth>>> {
+    let vpn_config = settings.config.get::<VpnConfig>(settings::key::vpn::table)?;
+
+    if vpn_config.enabled && vpn_config.kind == VpnKind::Netbird {
+        let interval = Duration::from_millis(settings.config.get::<u64>(settings::key::vpn::netbird::health::check::interval_ms)?);
+        Ok(Some(netbird::monitor_health(interval)))
+    } else {
+        Ok(None)
+    }
+}
@@ -0,0 +1,103 @@
+use std::net::IpAddr;
+use std::ops::Not;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use opendut_netbird_client_api::client::Client;
+use opendut_netbird_client_api::extension::LocalPeerStateExtension;
+use opendut_netbird_client_api::proto::daemon::{FullStatus, PeerState};
+use opendut_types::vpn::health::{VpnHealth, VpnLink};
+
+/// The connection status, which the NetBird daemon reports for peers with an established tunnel.
+const PEER_STATUS_CONNECTED: &str = "Connected";
+
+/// Periodically queries the status of the NetBird daemon and sends it to the returned receiver.
+/// If the daemon reports the connection to the management server as lost, it is brought up again,
+/// which re-uses the login of `edgar setup`.
+pub fn monitor_health(interval: Duration) -> mpsc::Receiver<VpnHealth> {
+    let (tx_health, rx_health) = mpsc::channel(1);
+
+    tokio::spawn(async move {
+        let mut client: Option<Client> = None;
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let health = match check_health(&mut client).await {
+                Ok(health) => health,
+                Err(cause) => {
+                    warn!("Failed to retrieve status of NetBird client: {cause}");
+                    client = None;
+                    VpnHealth { connected: false, address: None, links: vec![] }
+                }
+            };
+
+            if tx_health.send(health).await.is_err() {
+                debug!("Stopping VPN health monitoring, since the receiver was dropped.");
+                break;
+            }
+        }
+    });
+
+    rx_health
+}
+
+async fn check_health(client: &mut Option<Client>) -> anyhow::Result<VpnHealth> {
+    let client = match client {
+        Some(client) => client,
+        None => client.insert(Client::connect().await?),
+    };
+
+    let status = client.full_status().await?;
+    let health = health_from_status(&status)?;
+
+    if health.connected.not() {
+        info!("NetBird client is not connected to its management server. Bringing it up again.");
+        client.up().await?;
+
+        let status = client.full_status().await?;
+        return health_from_status(&status);
+    }
+
+    Ok(health)
+}
+
+fn health_from_status(status: &FullStatus) -> anyhow::Result<VpnHealth> {
+    let management_connected = status.management_state.as_ref()
+        .map(|state| state.connected)
+        .unwrap_or(false);
+
+    let address = status.local_peer_state.as_ref()
+        .map(|state| state.local_ip())
+        .transpose()?
+        .map(IpAddr::from);
+
+    let links = status.peers.iter()
+        .map(link_from_peer_state)
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(VpnHealth {
+        connected: management_connected && address.is_some(),
+        address,
+        links,
+    })
+}
+
+fn link_from_peer_state(peer: &PeerState) -> anyhow::Result<VpnLink> {
+    let remote_address = peer.ip.split('/').next().unwrap_or(&peer.ip); //strip CIDR mask, if present
+    let remote_address = IpAddr::from_str(remote_address)
+        .map_err(|cause| anyhow!("IP of remote peer '{}' returned by NetBird could not be parsed: {cause}", peer.ip))?;
+
+    Ok(VpnLink {
+        remote_address,
+        connected: peer.conn_status == PEER_STATUS_CONNECTED,
+        relayed: peer.relayed,
+        latency: None, //not reported by the NetBird daemon in the version we use
+    })
+}
@@ -0,0 +1,292 @@
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::ops::Not;
+use std::process::Stdio;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use tokio::io::AsyncWriteExt;
+use tokio::net::UdpSocket;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+use opendut_types::cluster::ClusterAssignment;
+use opendut_types::peer::PeerId;
+use opendut_types::util::net::NetworkInterfaceName;
+use opendut_types::vpn::wireguard::{PrivateKey, PublicKey};
+use opendut_util::settings::LoadedConfig;
+use opendut_vpn::{ClusterNetworkPolicy, NetworkProtocol};
+
+use crate::common::settings;
+use crate::service::network_interface::manager::NetworkInterfaceManagerRef;
+use crate::service::vpn::{VpnConfig, VpnKind};
+
+const PERSISTENT_KEEPALIVE_SECONDS: u16 = 25;
+
+/// The nftables table, in which EDGAR filters the traffic arriving via the WireGuard interface.
+const NFTABLES_TABLE: &str = "opendut_wireguard";
+
+/// The public keys of the WireGuard peers, which EDGAR configured for its cluster assignment.
+/// Only these are removed, when the cluster assignment changes.
+pub type ConfiguredPeers = Mutex<HashSet<PublicKey>>;
+
+/// (Re-)Creates the WireGuard interface with the key and address, which CARL assigned to this peer during setup.
+/// Does nothing, unless the WireGuard VPN is configured.
+pub async fn setup_interface(settings: &LoadedConfig, network_interface_manager: NetworkInterfaceManagerRef) -> anyhow::Result<()> {
+    let vpn_config = settings.config.get::<VpnConfig>(settings::key::vpn::table)?;
+    if vpn_config.enabled.not() || vpn_config.kind != VpnKind::Wireguard {
+        return Ok(());
+    }
+
+    let private_key = PrivateKey::from_str(&settings.config.get_string("vpn.wireguard.private.key")?)
+        .context("Configuration value 'vpn.wireguard.private.key' must be a valid WireGuard key. Re-run `edgar setup` to fix this.")?;
+    let address = settings.config.get::<IpAddr>("vpn.wireguard.address")
+        .context("Configuration value 'vpn.wireguard.address' must be a valid IP address. Re-run `edgar setup` to fix this.")?;
+    let prefix_length = settings.config.get::<u8>("vpn.wireguard.prefix.length")?;
+    let listen_port = settings.config.get::<u16>("vpn.wireguard.listen.port")?;
+
+    let interface_name = crate::common::default_wireguard_interface_name();
+    let name = interface_name.name();
+
+    if let Some(interface) = network_interface_manager.find_interface(&interface_name).await? {
+        network_interface_manager.delete_interface(&interface).await?;
+        debug!("Deleted previous WireGuard interface {interface}.");
+    }
+    let interface = network_interface_manager.create_wireguard_interface(&interface_name).await?;
+
+    // Rules of a previous cluster assignment are removed, as the interface starts without peers.
+    nft(&format!("table inet {NFTABLES_TABLE}\ndelete table inet {NFTABLES_TABLE}\n")).await?;
+
+    // Configuring keys is not supported via rtnetlink, so the `wg` tool is used for this.
+    // The private key is passed via stdin, so it does not show up in the argument list.
+    let mut command = Command::new("wg")
+        .args(["set", name.as_str(), "listen-port", listen_port.to_string().as_str(), "private-key", "/dev/stdin"])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|cause| Error::CommandLineProgramExecution { program: "wg", cause })?;
+    if let Some(mut stdin) = command.stdin.take() {
+        stdin.write_all(private_key.to_base64().as_bytes()).await?;
+    }
+    let output = command.wait_with_output().await?;
+    if output.status.success().not() {
+        return Err(anyhow!("Failed to set private key of WireGuard interface {interface}: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    network_interface_manager.add_ip_address(&interface, address, prefix_length).await?;
+    network_interface_manager.set_interface_up(&interface).await?;
+
+    info!("Created WireGuard interface {interface} with address '{address}/{prefix_length}', listening on port {listen_port}.");
+    Ok(())
+}
+
+/// Determines the address, on which the other peers can reach this peer's WireGuard interface.
+/// Unless configured explicitly, this is the local address used for connecting to CARL.
+pub async fn retrieve_endpoint_host(settings: &LoadedConfig) -> anyhow::Result<IpAddr> {
+    let field = settings::key::vpn::wireguard::endpoint::host;
+    let endpoint_host = settings.config.get_string(field)?;
+
+    if endpoint_host.trim().is_empty().not() {
+        return IpAddr::from_str(endpoint_host.trim())
+            .map_err(|cause| anyhow!("Configuration value '{field}' must be a valid IP address: {cause}"));
+    }
+
+    let carl_host = settings.config.get_string("network.carl.host")?;
+    let carl_port = settings.config.get::<u16>("network.carl.port")?;
+    let carl_address = tokio::net::lookup_host((carl_host.as_str(), carl_port)).await?
+        .next()
+        .ok_or_else(|| anyhow!("Could not resolve address of CARL host '{carl_host}'. Set '{field}' to the address of this peer."))?;
+
+    // Connecting a UDP socket does not send any packets, but determines the route and thereby the local address.
+    let bind_address = if carl_address.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+    let socket = UdpSocket::bind(bind_address).await?;
+    socket.connect(carl_address).await?;
+    let endpoint_host = socket.local_addr()?.ip();
+
+    debug!("Using local address '{endpoint_host}' as endpoint of the WireGuard interface.");
+    Ok(endpoint_host)
+}
+
+/// Configures the members of the cluster, which this peer exchanges traffic with according to the cluster's network policy,
+/// as peers of the WireGuard interface, allowing only the tunnel address of each member.
+/// Traffic arriving via the interface is restricted to the policy. Peers configured for a previous cluster assignment are removed.
+pub async fn configure_peers(interface_name: &NetworkInterfaceName, self_id: PeerId, cluster_assignment: &ClusterAssignment, configured_peers: &ConfiguredPeers) -> Result<(), Error> {
+    let policy = ClusterNetworkPolicy::from(cluster_assignment);
+
+    let connected_peers = policy.rules.iter()
+        .filter_map(|rule| {
+            if rule.source == self_id { Some(rule.destination) }
+            else if rule.destination == self_id { Some(rule.source) }
+            else { None }
+        })
+        .collect::<HashSet<_>>();
+
+    let members = cluster_assignment.assignments.iter()
+        .filter(|assignment| connected_peers.contains(&assignment.peer_id))
+        .filter_map(|assignment| assignment.wireguard.as_ref().map(|wireguard| (assignment, wireguard)))
+        .collect::<Vec<_>>();
+
+    let addresses = cluster_assignment.assignments.iter()
+        .map(|assignment| (assignment.peer_id, assignment.vpn_address))
+        .collect::<HashMap<_, _>>();
+    let ruleset = nftables_ruleset(interface_name, &policy, self_id, &addresses);
+    nft(&ruleset).await?;
+    debug!("Restricted the traffic arriving via WireGuard interface '{interface_name}' to the network policy of cluster <{}>.", cluster_assignment.id);
+
+    let name = interface_name.name();
+
+    let member_keys = members.iter()
+        .map(|(_, wireguard)| wireguard.public_key)
+        .collect::<HashSet<_>>();
+
+    let mut configured_peers = configured_peers.lock().await;
+    for public_key in configured_peers.difference(&member_keys) {
+        wg(&["set", &name, "peer", &public_key.to_string(), "remove"]).await?;
+        debug!("Removed WireGuard peer '{public_key}', which is no member of the cluster.");
+    }
+    configured_peers.retain(|public_key| member_keys.contains(public_key));
+
+    for (assignment, wireguard) in members {
+        let allowed_ip = match assignment.vpn_address {
+            IpAddr::V4(address) => format!("{address}/32"),
+            IpAddr::V6(address) => format!("{address}/128"),
+        };
+        wg(&[
+            "set", &name,
+            "peer", &wireguard.public_key.to_string(),
+            "endpoint", &wireguard.endpoint.to_string(),
+            "allowed-ips", &allowed_ip,
+            "persistent-keepalive", &PERSISTENT_KEEPALIVE_SECONDS.to_string(),
+        ]).await?;
+        configured_peers.insert(wireguard.public_key);
+        debug!("Configured WireGuard peer <{}> with endpoint '{}' and allowed IP '{allowed_ip}'.", assignment.peer_id, wireguard.endpoint);
+    }
+    Ok(())
+}
+
+/// Renders an nftables ruleset, which drops any traffic arriving via the WireGuard interface,
+/// unless it is a reply or allowed by one of the incoming rules of the policy.
+/// Declaring and deleting the table first replaces a previous ruleset within the same transaction.
+fn nftables_ruleset(interface_name: &NetworkInterfaceName, policy: &ClusterNetworkPolicy, self_id: PeerId, addresses: &HashMap<PeerId, IpAddr>) -> String {
+    let mut ruleset = format!("table inet {NFTABLES_TABLE}\ndelete table inet {NFTABLES_TABLE}\ntable inet {NFTABLES_TABLE} {{\n");
+    ruleset.push_str("  chain input {\n    type filter hook input priority filter; policy accept;\n");
+    ruleset.push_str(&format!("    iifname \"{}\" jump cluster\n", interface_name.name()));
+    ruleset.push_str("  }\n  chain cluster {\n    ct state established,related accept\n");
+
+    for rule in policy.incoming_rules(self_id) {
+        let Some(source) = addresses.get(&rule.source) else { continue };
+        let (source, protocol) = match source {
+            IpAddr::V4(address) => (format!("ip saddr {address}"), rule.protocol.to_string()),
+            IpAddr::V6(address) => match rule.protocol {
+                NetworkProtocol::Icmp => (format!("ip6 saddr {address}"), String::from("icmpv6")),
+                protocol => (format!("ip6 saddr {address}"), protocol.to_string()),
+            },
+        };
+        let ports = rule.ports.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let rule = match (rule.protocol, ports.is_empty()) {
+            (NetworkProtocol::Tcp | NetworkProtocol::Udp | NetworkProtocol::Sctp, false) => format!("    {source} {protocol} dport {{ {} }} accept\n", ports.join(", ")),
+            _ => format!("    {source} meta l4proto {protocol} accept\n"),
+        };
+        ruleset.push_str(&rule);
+    }
+    ruleset.push_str("    drop\n  }\n}\n");
+    ruleset
+}
+
+async fn nft(ruleset: &str) -> Result<(), Error> {
+    let mut command = Command::new("nft")
+        .args(["-f", "-"])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|cause| Error::CommandLineProgramExecution { program: "nft", cause })?;
+    if let Some(mut stdin) = command.stdin.take() {
+        stdin.write_all(ruleset.as_bytes()).await
+            .map_err(|cause| Error::CommandLineProgramExecution { program: "nft", cause })?;
+    }
+    let output = command.wait_with_output().await
+        .map_err(|cause| Error::CommandLineProgramExecution { program: "nft", cause })?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(Error::CommandFailed {
+            command: String::from("nft -f -"),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        })
+    }
+}
+
+async fn wg(args: &[&str]) -> Result<(), Error> {
+    let output = Command::new("wg")
+        .args(args)
+        .output().await
+        .map_err(|cause| Error::CommandLineProgramExecution { program: "wg", cause })?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(Error::CommandFailed {
+            command: format!("wg {}", args.join(" ")),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failure while invoking command line program '{program}': {cause}")]
+    CommandLineProgramExecution { program: &'static str, cause: std::io::Error },
+    #[error("Command '{command}' failed: {stderr}")]
+    CommandFailed { command: String, stderr: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use googletest::prelude::*;
+
+    use opendut_types::cluster::{ClusterId, PeerClusterAssignment};
+    use opendut_types::util::Port;
+
+    use super::*;
+
+    #[test]
+    fn should_only_accept_the_incoming_traffic_of_the_policy() -> Result<()> {
+        let leader = PeerId::random();
+        let member = PeerId::random();
+        let assignment = |peer_id: PeerId, address: [u8; 4], can_server_port: u16| PeerClusterAssignment {
+            peer_id,
+            vpn_address: IpAddr::V4(Ipv4Addr::from(address)),
+            can_server_port: Port(can_server_port),
+            device_interfaces: vec![],
+            wireguard: None,
+        };
+        let cluster_assignment = ClusterAssignment {
+            id: ClusterId::random(),
+            leader,
+            assignments: vec![
+                assignment(leader, [10, 10, 0, 1], 10000),
+                assignment(member, [10, 10, 0, 2], 10001),
+            ],
+        };
+        let policy = ClusterNetworkPolicy::from(&cluster_assignment);
+        let addresses = cluster_assignment.assignments.iter()
+            .map(|assignment| (assignment.peer_id, assignment.vpn_address))
+            .collect::<HashMap<_, _>>();
+        let interface_name = NetworkInterfaceName::try_from("wg-opendut")?;
+
+        let leader_ruleset = nftables_ruleset(&interface_name, &policy, leader, &addresses);
+        verify_that!(leader_ruleset, contains_substring("iifname \"wg-opendut\" jump cluster"))?;
+        verify_that!(leader_ruleset, contains_substring("ip saddr 10.10.0.2 meta l4proto gre accept"))?;
+        verify_that!(leader_ruleset, contains_substring("ip saddr 10.10.0.2 sctp dport { 10001 } accept"))?;
+        verify_that!(leader_ruleset, contains_substring("ip saddr 10.10.0.2 meta l4proto icmp accept"))?;
+
+        let member_ruleset = nftables_ruleset(&interface_name, &policy, member, &addresses);
+        verify_that!(member_ruleset, contains_substring("ip saddr 10.10.0.1 meta l4proto gre accept"))?;
+        verify_that!(member_ruleset, not(contains_substring("sctp")))?;
+        verify_that!(member_ruleset, ends_with("    drop\n  }\n}\n"))
+    }
+}
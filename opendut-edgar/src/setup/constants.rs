@@ -66,4 +66,7 @@ pub mod netbird {
     }
 }
 
-pub const REQUIRED_COMMAND_LINE_PROGRAMS: [(&str, &str);3] = [("systemctl", "--version"), ("cannelloni", "-h"), ("cangw", "-s")];
\ No newline at end of file
+pub const REQUIRED_COMMAND_LINE_PROGRAMS: [(&str, &str);3] = [("systemctl", "--version"), ("cannelloni", "-h"), ("cangw", "-s")];
+
+/// Programs used by the service to configure the WireGuard interface and to filter the traffic arriving via it.
+pub const REQUIRED_WIREGUARD_COMMAND_LINE_PROGRAMS: [(&str, &str);2] = [("wg", "--version"), ("nft", "--version")];
\ No newline at end of file
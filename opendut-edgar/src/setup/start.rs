@@ -15,6 +15,7 @@ use opendut_util::logging::OpenTelemetryConfig;
 
 use crate::service::network_interface::manager::NetworkInterfaceManager;
 use crate::setup::{Leader, runner, tasks, User};
+use crate::setup::constants::REQUIRED_WIREGUARD_COMMAND_LINE_PROGRAMS;
 use crate::setup::runner::RunMode;
 use crate::setup::task::Task;
 use crate::setup::tasks::write_configuration;
@@ -34,12 +35,13 @@ pub async fn managed(run_mode: RunMode, no_confirm: bool, setup_string: String,
 
     let mut tasks: Vec<Box<dyn Task>> = vec![
         Box::new(tasks::WriteCaCertificate::with_certificate(peer_setup.ca)),
-        Box::new(tasks::CheckCommandLinePrograms),
+        Box::new(tasks::CheckCommandLinePrograms::default()),
         Box::new(tasks::WriteConfiguration::with_override(
             write_configuration::ConfigOverride {
                 peer_id: peer_setup.id,
                 carl_url: peer_setup.carl,
                 auth_config: peer_setup.auth_config,
+                vpn: Clone::clone(&peer_setup.vpn),
             }),
         ),
         Box::new(tasks::CheckCarlReachable),
@@ -64,6 +66,13 @@ pub async fn managed(run_mode: RunMode, no_confirm: bool, setup_string: String,
                 Box::new(tasks::netbird::Connect { management_url, setup_key, mtu }),
             ]);
         }
+        VpnPeerConfiguration::WireGuard { .. } => {
+            info!("VPN is configured for WireGuard in PeerSetup. The WireGuard interface is created when the service starts.");
+            tasks.push(Box::new(tasks::CheckCommandLinePrograms { programs: REQUIRED_WIREGUARD_COMMAND_LINE_PROGRAMS.to_vec() }));
+        }
+        VpnPeerConfiguration::Loopback { .. } => {
+            info!("VPN is configured for loopback in PeerSetup. The service is expected to run in the network namespace of this peer.");
+        }
     };
 
     if service_user.is_root() {
@@ -100,7 +109,7 @@ pub async fn unmanaged(
     let network_interface_manager = NetworkInterfaceManager::create()?;
 
     let tasks: Vec<Box<dyn Task>> = vec![
-        Box::new(tasks::CheckCommandLinePrograms),
+        Box::new(tasks::CheckCommandLinePrograms::default()),
         Box::new(tasks::netbird::Unpack::default()),
         Box::new(tasks::netbird::InstallService),
         Box::new(tasks::netbird::StartService),
@@ -6,17 +6,29 @@ use crate::setup::constants::REQUIRED_COMMAND_LINE_PROGRAMS;
 use crate::setup::util::EvaluateRequiringSuccess;
 
 
-pub struct CheckCommandLinePrograms;
+pub struct CheckCommandLinePrograms {
+    /// The programs with an argument, which they need to exit successfully with.
+    pub programs: Vec<(&'static str, &'static str)>,
+}
+impl Default for CheckCommandLinePrograms {
+    fn default() -> Self {
+        Self { programs: REQUIRED_COMMAND_LINE_PROGRAMS.to_vec() }
+    }
+}
 impl Task for CheckCommandLinePrograms {
     fn description(&self) -> String {
-        String::from("Check availabilty of needed command line programs")
+        let programs = self.programs.iter()
+            .map(|(command_line_program, _)| *command_line_program)
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("Check availabilty of needed command line programs ({programs})")
     }
     fn check_fulfilled(&self) -> Result<TaskFulfilled> {
         Ok(TaskFulfilled::Unchecked)
     }
     fn execute(&self) -> Result<Success> {
 
-        for (command_line_program, arg) in REQUIRED_COMMAND_LINE_PROGRAMS.iter(){
+        for (command_line_program, arg) in self.programs.iter() {
             Command::new(command_line_program)
             .arg(arg)
             .evaluate_requiring_success()
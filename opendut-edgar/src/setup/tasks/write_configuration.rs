@@ -9,6 +9,7 @@ use url::Url;
 
 use opendut_types::peer::PeerId;
 use opendut_types::util::net::AuthConfig;
+use opendut_types::vpn::VpnPeerConfiguration;
 
 use crate::common::settings;
 use crate::setup::constants;
@@ -18,6 +19,7 @@ pub struct ConfigOverride {
     pub peer_id: PeerId,
     pub carl_url: Url,
     pub auth_config: AuthConfig,
+    pub vpn: VpnPeerConfiguration,
 }
 
 pub struct WriteConfiguration {
@@ -88,6 +90,43 @@ impl Task for WriteConfiguration {
                 }
             };
 
+            if let VpnPeerConfiguration::WireGuard { private_key, address, prefix_length, listen_port } = &self.config_override.vpn {
+                if new_settings.get("vpn").is_none() {
+                    new_settings["vpn"] = toml_edit::table();
+                }
+                new_settings["vpn"]["enabled"] = toml_edit::value(true);
+                new_settings["vpn"]["kind"] = toml_edit::value("wireguard");
+
+                if new_settings.get("vpn").and_then(|vpn| vpn.get("wireguard")).is_none() {
+                    new_settings["vpn"]["wireguard"] = toml_edit::table();
+                }
+                new_settings["vpn"]["wireguard"]["private"] = toml_edit::table();
+                new_settings["vpn"]["wireguard"]["private"].as_table_mut().unwrap().set_dotted(true);
+                new_settings["vpn"]["wireguard"]["private"]["key"] = toml_edit::value(private_key.to_base64());
+                new_settings["vpn"]["wireguard"]["address"] = toml_edit::value(address.to_string());
+                new_settings["vpn"]["wireguard"]["prefix"] = toml_edit::table();
+                new_settings["vpn"]["wireguard"]["prefix"].as_table_mut().unwrap().set_dotted(true);
+                new_settings["vpn"]["wireguard"]["prefix"]["length"] = toml_edit::value(i64::from(*prefix_length));
+                new_settings["vpn"]["wireguard"]["listen"] = toml_edit::table();
+                new_settings["vpn"]["wireguard"]["listen"].as_table_mut().unwrap().set_dotted(true);
+                new_settings["vpn"]["wireguard"]["listen"]["port"] = toml_edit::value(i64::from(listen_port.0));
+            }
+
+            if let VpnPeerConfiguration::Loopback { address } = &self.config_override.vpn {
+                // No VPN software is needed, as the network namespace is already connected to the other peers.
+                if new_settings.get("vpn").is_none() {
+                    new_settings["vpn"] = toml_edit::table();
+                }
+                new_settings["vpn"]["enabled"] = toml_edit::value(false);
+
+                if new_settings.get("vpn").and_then(|vpn| vpn.get("disabled")).is_none() {
+                    new_settings["vpn"]["disabled"] = toml_edit::table();
+                }
+                new_settings["vpn"]["disabled"]["remote"] = toml_edit::table();
+                new_settings["vpn"]["disabled"]["remote"].as_table_mut().unwrap().set_dotted(true);
+                new_settings["vpn"]["disabled"]["remote"]["host"] = toml_edit::value(address.to_string());
+            }
+
             new_settings.to_string()
         };
 
@@ -168,7 +207,11 @@ mod tests {
     use uuid::uuid;
     use googletest::prelude::*;
     use rstest::{fixture, rstest};
+    use std::net::IpAddr;
+
     use opendut_types::util::net::{ClientId, ClientSecret, OAuthScope};
+    use opendut_types::util::Port;
+    use opendut_types::vpn::wireguard::PrivateKey;
 
     use crate::setup::runner;
 
@@ -242,6 +285,38 @@ mod tests {
         Ok(())
     }
 
+    #[rstest]
+    fn should_write_the_wireguard_configuration(
+        write_configuration_auth_disabled: WriteConfiguration,
+    ) -> anyhow::Result<()> {
+
+        let private_key = PrivateKey([1; 32]);
+        let write_configuration = WriteConfiguration {
+            config_override: ConfigOverride {
+                vpn: VpnPeerConfiguration::WireGuard {
+                    private_key: Clone::clone(&private_key),
+                    address: IpAddr::from([10, 10, 0, 1]),
+                    prefix_length: 16,
+                    listen_port: Port(51820),
+                },
+                ..write_configuration_auth_disabled.config_override
+            },
+            ..write_configuration_auth_disabled
+        };
+        let path = write_configuration.config_file_to_write_to.clone();
+
+        runner::test::unchecked(write_configuration)?;
+
+        let file_content = fs::read_to_string(&path)?;
+        assert!(predicate::str::contains(r#"kind = "wireguard""#).eval(&file_content));
+        assert!(predicate::str::contains(format!(r#"key = "{}""#, private_key.to_base64())).eval(&file_content));
+        assert!(predicate::str::contains(r#"address = "10.10.0.1""#).eval(&file_content));
+        assert!(predicate::str::contains("length = 16").eval(&file_content));
+        assert!(predicate::str::contains("port = 51820").eval(&file_content));
+
+        Ok(())
+    }
+
     #[rstest]
     fn should_provide_an_merge_suggestion_for_an_already_existing_configuration_but_should_not_delete_existing_unknown_keys(
         write_configuration_auth_enabled: WriteConfiguration,
@@ -366,6 +441,7 @@ mod tests {
                     client_id: ClientId::from(CLIENT_ID),
                     scopes: vec![OAuthScope("test".to_string())],
                 },
+                vpn: VpnPeerConfiguration::Disabled,
             },
         }
     }
@@ -381,6 +457,7 @@ mod tests {
                 peer_id: fixture.peer_id,
                 carl_url: Url::parse("https://example.com:1234").unwrap(),
                 auth_config: AuthConfig::Disabled,
+                vpn: VpnPeerConfiguration::Disabled,
             },
         }
     }
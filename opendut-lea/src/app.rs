@@ -12,6 +12,7 @@ use opendut_carl_api::carl::wasm::CarlClient;
 
 use crate::components::Toaster;
 use crate::nav::Navbar;
+use crate::projects::load_selected_project;
 use crate::routing::Routes;
 
 #[derive(Clone, Debug)]
@@ -109,6 +110,7 @@ pub fn App() -> impl IntoView {
                 let auth = Auth::init(auth_parameters.clone());
                 let client = CarlClient::create(Clone::clone(&config.carl_url), Some(auth.clone()))
                     .expect("Failed to create CARL client");
+                client.project.select(load_selected_project());
 
                 Ok(AppGlobals {
                     config,
@@ -119,6 +121,7 @@ pub fn App() -> impl IntoView {
             None => {
                 let client = CarlClient::create(Clone::clone(&config.carl_url), None)
                     .expect("Failed to create CARL client");
+                client.project.select(load_selected_project());
                 Ok(AppGlobals {
                     config,
                     client,
@@ -0,0 +1,125 @@
+use leptos::*;
+
+use opendut_carl_api::carl::audit::{AuditOutcome, AuditRecord, AuditRecordFilter};
+
+use crate::app::{ExpectGlobals, use_app_globals};
+use crate::components::{BasePageContainer, Breadcrumb, ButtonColor, ButtonSize, ButtonState, FontAwesomeIcon, IconButton, Initialized};
+
+const AUDIT_RECORDS_LIMIT: u32 = 250;
+
+#[component(transparent)]
+pub fn AuditOverview() -> impl IntoView {
+
+    #[component]
+    fn inner() -> impl IntoView {
+
+        let globals = use_app_globals();
+
+        let audit_records: Resource<(), Vec<AuditRecord>> = create_local_resource(|| {}, move |_| {
+            let mut carl = globals.expect_client();
+            async move {
+                let filter = AuditRecordFilter {
+                    limit: Some(AUDIT_RECORDS_LIMIT),
+                    ..Default::default()
+                };
+                carl.audit.list_audit_records(filter).await
+                    .expect("Failed to request the list of audit records.")
+            }
+        });
+
+        let audit_table_rows = move || {
+            audit_records.get()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|record| view! { <Row record=record /> })
+                .collect::<Vec<_>>()
+        };
+
+        let breadcrumbs = vec![
+            Breadcrumb::new("Dashboard", "/"),
+            Breadcrumb::new("Audit Log", "/audit")
+        ];
+
+        view! {
+            <BasePageContainer
+                title="Audit Log"
+                breadcrumbs=breadcrumbs
+                controls=view! {
+                    <div class="buttons">
+                        <IconButton
+                            icon=FontAwesomeIcon::ArrowsRotate
+                            color=ButtonColor::Light
+                            size=ButtonSize::Normal
+                            state=ButtonState::Enabled
+                            label="Refresh table of audit records"
+                            on_action=move || {
+                                audit_records.refetch();
+                            }
+                        />
+                    </div>
+                }
+            >
+                <div class="mt-4">
+                    <Transition
+                        fallback=move || view! { <p>"Loading..."</p> }
+                    >
+                        <table class="table is-hoverable is-fullwidth">
+                            <thead>
+                                <tr>
+                                    <th class="is-narrow">"Timestamp"</th>
+                                    <th>"Identity"</th>
+                                    <th>"Client"</th>
+                                    <th>"Action"</th>
+                                    <th>"Resource"</th>
+                                    <th>"Outcome"</th>
+                                    <th>"Changes"</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                { audit_table_rows }
+                            </tbody>
+                        </table>
+                    </Transition>
+                </div>
+            </BasePageContainer>
+        }
+    }
+
+    view! {
+        <Initialized>
+            <Inner />
+        </Initialized>
+    }
+}
+
+#[component]
+fn Row(record: AuditRecord) -> impl IntoView {
+
+    let timestamp = record.timestamp
+        .with_timezone(&chrono::Local)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+
+    let (outcome, outcome_class) = match &record.outcome {
+        AuditOutcome::Success => (String::from("Success"), "has-text-success"),
+        AuditOutcome::Failure { cause } => (Clone::clone(cause), "has-text-danger"),
+    };
+
+    let changes = record.changes().into_iter()
+        .map(|change| view! { <li class="is-family-monospace is-size-7">{ change.to_string() }</li> })
+        .collect::<Vec<_>>();
+
+    view! {
+        <tr>
+            <td class="is-vcentered">{ timestamp }</td>
+            <td class="is-vcentered">{ record.identity.to_string() }</td>
+            <td class="is-vcentered is-family-monospace is-size-7">{ record.client_address.map(|address| address.to_string()).unwrap_or_default() }</td>
+            <td class="is-vcentered">{ record.action.to_string() }</td>
+            <td class="is-vcentered is-family-monospace is-size-7">{ record.resource_id.to_string() }</td>
+            <td class=format!("is-vcentered {outcome_class}")>{ outcome }</td>
+            <td class="is-vcentered">
+                <ul>{ changes }</ul>
+            </td>
+        </tr>
+    }
+}
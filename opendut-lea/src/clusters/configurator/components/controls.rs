@@ -3,6 +3,8 @@ use std::rc::Rc;
 use leptos::*;
 use tracing::{debug, error};
 
+use opendut_carl_api::carl::ClientError;
+use opendut_carl_api::carl::cluster::CreateClusterConfigurationError;
 use opendut_types::cluster::{ClusterConfiguration, ClusterId};
 
 use crate::app::{ExpectGlobals, use_app_globals};
@@ -11,21 +13,24 @@ use crate::components::{ButtonColor, ButtonSize, ButtonState, ButtonStateSignalP
 use crate::routing::{navigate_to, WellKnownRoutes};
 
 #[component]
-pub fn Controls(cluster_configuration: ReadSignal<UserClusterConfiguration>) -> impl IntoView {
+pub fn Controls(cluster_configuration: RwSignal<UserClusterConfiguration>) -> impl IntoView {
 
     view! {
         <div class="buttons">
             <SaveClusterButton cluster_configuration=cluster_configuration />
-            <DeleteClusterButton cluster_configuration=cluster_configuration />
+            <DeleteClusterButton cluster_configuration=cluster_configuration.read_only() />
         </div>
     }
 }
 
 #[component]
-fn SaveClusterButton(cluster_configuration: ReadSignal<UserClusterConfiguration>) -> impl IntoView {
+fn SaveClusterButton(cluster_configuration: RwSignal<UserClusterConfiguration>) -> impl IntoView {
 
     let globals = use_app_globals();
     let toaster = use_toaster();
+    let reapply_toaster = Rc::clone(&toaster);
+
+    let conflicting = create_rw_signal(false);
 
     let store_action = create_action(move |_: &()| {
         let toaster = Rc::clone(&toaster);
@@ -38,11 +43,19 @@ fn SaveClusterButton(cluster_configuration: ReadSignal<UserClusterConfiguration>
                     match result {
                         Ok(cluster_id) => {
                             debug!("Successfully stored cluster: {}", cluster_id);
+                            cluster_configuration.update(|configuration| configuration.revision += 1);
                             toaster.toast(Toast::builder()
                                 .simple("Successfully stored cluster configuration.")
                                 .success()
                             );
                         }
+                        Err(ClientError::UsageError(CreateClusterConfigurationError::RevisionConflict { .. })) => {
+                            conflicting.set(true);
+                            toaster.toast(Toast::builder()
+                                .simple("The cluster configuration was changed by someone else in the meantime. Your changes were kept and can be saved over the latest revision.")
+                                .warning()
+                            );
+                        }
                         Err(cause) => {
                             error!("Failed to store cluster <{}>, due to error: {:?}", "id", cause);
                             toaster.toast(Toast::builder()
@@ -75,6 +88,32 @@ fn SaveClusterButton(cluster_configuration: ReadSignal<UserClusterConfiguration>
         }
     });
 
+    let reapply_action = create_action(move |_: &()| {
+        let toaster = Rc::clone(&reapply_toaster);
+        async move {
+            let mut carl = globals.expect_client();
+            let id = cluster_configuration.get_untracked().id;
+            match carl.cluster.get_cluster_configuration(id).await {
+                Ok(latest) => {
+                    cluster_configuration.update(|configuration| configuration.revision = latest.revision);
+                    conflicting.set(false);
+                    store_action.dispatch(());
+                }
+                Err(cause) => {
+                    error!("Failed to reload cluster <{id}>, due to error: {cause:?}");
+                    toaster.toast(Toast::builder()
+                        .simple("Failed to reload cluster configuration!")
+                        .error()
+                    );
+                }
+            }
+        }
+    });
+
+    let reapply_button_state = reapply_action
+        .pending()
+        .derive_loading();
+
     view! {
         <IconButton
             icon=FontAwesomeIcon::Save
@@ -86,6 +125,18 @@ fn SaveClusterButton(cluster_configuration: ReadSignal<UserClusterConfiguration>
                 store_action.dispatch(());
             }
         />
+        <Show when=move || conflicting.get()>
+            <ConfirmationButton
+                icon=FontAwesomeIcon::ArrowsRotate
+                color=ButtonColor::Danger
+                size=ButtonSize::Normal
+                state=reapply_button_state
+                label="Reload and Save Your Changes?"
+                on_conform=move || {
+                    reapply_action.dispatch(());
+                }
+            />
+        </Show>
     }
 }
 
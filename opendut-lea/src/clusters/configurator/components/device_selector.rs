@@ -1,12 +1,15 @@
 use std::collections::HashSet;
 use std::ops::Not;
+use std::str::FromStr;
 
 use leptos::*;
 
 use opendut_types::peer::PeerId;
 use opendut_types::topology::{DeviceDescriptor, DeviceId};
+use opendut_types::topology::query::DeviceQuery;
 
 use crate::clusters::configurator::components::{get_all_peers, get_all_selected_devices};
+use crate::app::{ExpectGlobals, use_app_globals};
 use crate::clusters::configurator::types::UserClusterConfiguration;
 use crate::components::{ButtonColor, ButtonSize, ButtonState, FontAwesomeIcon, IconButton};
 use crate::util::{Ior, NON_BREAKING_SPACE};
@@ -16,8 +19,31 @@ pub type DeviceSelection = Ior<DeviceSelectionError, HashSet<DeviceId>>;
 
 #[component]
 pub fn DeviceSelector(cluster_configuration: RwSignal<UserClusterConfiguration>) -> impl IntoView {
+    let globals = use_app_globals();
     let peer_descriptors = get_all_peers();
 
+    let device_query = create_rw_signal(String::new());
+    let queried_devices = create_local_resource(move || device_query.get(), move |query| {
+        async move {
+            if query.trim().is_empty() {
+                return Ok(None);
+            }
+            let query = DeviceQuery::from_str(&query)
+                .map_err(|cause| cause.to_string())?;
+            let mut carl = globals.expect_client();
+            carl.peers.find_devices(&query).await
+                .map(|devices| Some(devices.into_iter().map(|device| device.id).collect::<HashSet<_>>()))
+                .map_err(|cause| cause.to_string())
+        }
+    });
+
+    let query_help_text = move || {
+        match queried_devices.get() {
+            Some(Err(error)) => error,
+            _ => String::from(NON_BREAKING_SPACE),
+        }
+    };
+
     let (getter, setter) = create_slice(
         cluster_configuration,
         |config| Clone::clone(&config.devices),
@@ -38,11 +64,18 @@ pub fn DeviceSelector(cluster_configuration: RwSignal<UserClusterConfiguration>)
 
     let rows = move || {
         let mut all_devices_by_peer: Vec<_> = Vec::new();
+        let matching_devices = queried_devices.get()
+            .and_then(Result::ok)
+            .flatten();
 
         for peer in peer_descriptors.get().unwrap_or_default() {
             let mut devices = peer.topology.devices;
             let selected_devices = selected_devices();
 
+            if let Some(matching_devices) = &matching_devices {
+                devices.retain(|device| matching_devices.contains(&device.id));
+            }
+
             devices.sort_by(|a, b|
                 a.name.value().to_lowercase().cmp(&b.name.value().to_lowercase()));
 
@@ -105,6 +138,20 @@ pub fn DeviceSelector(cluster_configuration: RwSignal<UserClusterConfiguration>)
 
     view! {
         <p class="help has-text-danger">{ help_text }</p>
+        <div class="field mt-2">
+            <label class="label">Filter</label>
+            <div class="control">
+                <input
+                    class="input"
+                    type="text"
+                    aria-label="Device Query"
+                    placeholder="tag=powertrain and type=can and peer.state=available"
+                    prop:value=move || device_query.get()
+                    on:input=move |ev| device_query.set(event_target_value(&ev))
+                />
+            </div>
+            <p class="help has-text-danger">{ query_help_text }</p>
+        </div>
         <div class="table-container mt-2">
             <table class="table is-fullwidth">
                 <thead>
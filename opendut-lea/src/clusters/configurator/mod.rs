@@ -53,6 +53,7 @@ pub fn ClusterConfigurator() -> impl IntoView {
                 name: UserInputValue::Left(UserInputError::from("Enter a valid cluster name.")),
                 devices: DeviceSelection::Left(String::from("Select at least two devices.")),
                 leader: LeaderSelection::Left(String::from("Select a leader.")),
+                revision: 0,
             });
 
             create_local_resource(|| {}, move |_| { // TODO: maybe a action suits better here
@@ -63,6 +64,7 @@ pub fn ClusterConfigurator() -> impl IntoView {
                             user_configuration.name = UserInputValue::Right(configuration.name.value());
                             user_configuration.devices = DeviceSelection::Right(configuration.devices);
                             user_configuration.leader = LeaderSelection::Right(configuration.leader);
+                            user_configuration.revision = configuration.revision;
                         });
                     }
                 }
@@ -86,7 +88,7 @@ pub fn ClusterConfigurator() -> impl IntoView {
             <BasePageContainer
                 title="Configure Cluster"
                 breadcrumbs=breadcrumbs
-                controls=view! { <Controls cluster_configuration=cluster_configuration.read_only() /> }
+                controls=view! { <Controls cluster_configuration=cluster_configuration /> }
             >
                 <div>
 
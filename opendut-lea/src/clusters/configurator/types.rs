@@ -22,6 +22,7 @@ pub struct UserClusterConfiguration {
     pub name: UserInputValue,
     pub devices: DeviceSelection,
     pub leader: LeaderSelection,
+    pub revision: u64,
 }
 
 impl UserClusterConfiguration {
@@ -49,6 +50,7 @@ impl TryFrom<UserClusterConfiguration> for ClusterConfiguration {
             name,
             leader,
             devices,
+            revision: configuration.revision,
         })
     }
 }
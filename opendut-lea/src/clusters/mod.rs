@@ -1,8 +1,10 @@
 pub use card::ClustersCard;
 pub use configurator::ClusterConfigurator;
 pub use overview::ClustersOverview;
+pub use schedule::ClustersSchedule;
 
 mod card;
 mod configurator;
 mod overview;
+mod schedule;
 mod components;
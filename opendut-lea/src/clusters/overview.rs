@@ -9,6 +9,7 @@ use crate::app::{ExpectGlobals, use_app_globals};
 use crate::clusters::components::CreateClusterButton;
 use crate::components::{BasePageContainer, Breadcrumb, ButtonColor, ButtonSize, ButtonState, FontAwesomeIcon, health, IconButton, Initialized, Toast, use_toaster};
 use crate::components::health::Health;
+use crate::routing::{navigate_to, WellKnownRoutes};
 
 #[component]
 pub fn ClustersOverview() -> impl IntoView {
@@ -39,10 +40,10 @@ pub fn ClustersOverview() -> impl IntoView {
             let mut carl = globals.expect_client();
             let id = Clone::clone(id);
             async move {
-                match carl.cluster.store_cluster_deployment(ClusterDeployment { id }).await {
+                match carl.cluster.store_cluster_deployment(ClusterDeployment::new(id)).await {
                     Ok(_) => {
                         toaster.toast(Toast::builder()
-                            .simple("Successfully stored cluster deployment!")
+                            .simple("Cluster deployment started!")
                             .success()
                         );
                     }
@@ -57,6 +58,38 @@ pub fn ClustersOverview() -> impl IntoView {
             }
         });
 
+        let check_cluster = create_action(move |id: &ClusterId| {
+            let toaster = use_toaster();
+            let mut carl = globals.expect_client();
+            let id = Clone::clone(id);
+            async move {
+                match carl.cluster.validate_cluster_deployment(id).await {
+                    Ok(validation) if validation.is_deployable() => {
+                        toaster.toast(Toast::builder()
+                            .simple("Cluster can be deployed!")
+                            .success()
+                        );
+                    }
+                    Ok(validation) => {
+                        let issues = validation.issues.iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        toaster.toast(Toast::builder()
+                            .simple(format!("Cluster cannot be deployed: {issues}"))
+                            .warning()
+                        );
+                    }
+                    Err(_) => {
+                        toaster.toast(Toast::builder()
+                            .simple("Failed to check cluster deployment!")
+                            .error()
+                        );
+                    }
+                }
+            }
+        });
+
         let undeploy_cluster = create_action(move |id: &ClusterId| {
             let toaster = use_toaster();
             let mut carl = globals.expect_client();
@@ -96,6 +129,7 @@ pub fn ClustersOverview() -> impl IntoView {
                         view! {
                             <Row
                                 cluster_configuration=create_rw_signal(cluster_configuration)
+                                on_check=move || check_cluster.dispatch(cluster_id)
                                 on_deploy=move || deploy_cluster.dispatch(cluster_id)
                                 on_undeploy=move || undeploy_cluster.dispatch(cluster_id)
                                 is_deployed = create_rw_signal(IsDeployed(deployed_clusters().contains(&cluster_id)))
@@ -119,7 +153,19 @@ pub fn ClustersOverview() -> impl IntoView {
                 title="Clusters"
                 breadcrumbs=breadcrumbs
                 controls=view! {
-                    <CreateClusterButton />
+                    <div class="buttons">
+                        <IconButton
+                            icon=FontAwesomeIcon::Calendar
+                            color=ButtonColor::Light
+                            size=ButtonSize::Normal
+                            state=ButtonState::Enabled
+                            label="Show Schedule of Cluster Deployments"
+                            on_action=move || {
+                                navigate_to(WellKnownRoutes::ClustersSchedule);
+                            }
+                        />
+                        <CreateClusterButton />
+                    </div>
                 }
             >
                 <table class="table is-hoverable is-fullwidth">
@@ -148,13 +194,15 @@ pub fn ClustersOverview() -> impl IntoView {
 pub struct IsDeployed(bool);
 
 #[component]
-fn Row<OnDeployFn, OnUndeployFn>(
+fn Row<OnCheckFn, OnDeployFn, OnUndeployFn>(
     cluster_configuration: RwSignal<ClusterConfiguration>,
+    on_check: OnCheckFn,
     on_deploy: OnDeployFn,
     on_undeploy: OnUndeployFn,
     is_deployed: RwSignal<IsDeployed>,
 ) -> impl IntoView
 where
+    OnCheckFn: Fn() + 'static,
     OnDeployFn: Fn() + 'static,
     OnUndeployFn: Fn() + 'static,
 {
@@ -218,6 +266,19 @@ where
                         </div>
                         <div node_ref=dropdown class="dropdown-menu">
                             <div class="dropdown-content">
+                                <button
+                                    class="button is-white is-fullwidth is-justify-content-flex-start"
+                                    aria-label="Check Cluster"
+                                    on:click=move |_| {
+                                        dropdown_active.set(false);
+                                        on_check();
+                                    }
+                                >
+                                    <span class="icon">
+                                        <i class="fa-solid fa-check"></i>
+                                    </span>
+                                    <span>"Check"</span>
+                                </button>
                                 <button
                                     class="button is-white is-fullwidth is-justify-content-flex-start"
                                     aria-label="Deploy Cluster"
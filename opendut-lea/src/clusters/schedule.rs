@@ -0,0 +1,166 @@
+use chrono::{DateTime, Days, Local, NaiveDate, Utc};
+use leptos::*;
+
+use opendut_types::cluster::{ClusterConfiguration, ClusterDeployment};
+
+use crate::app::{ExpectGlobals, use_app_globals};
+use crate::components::{BasePageContainer, Breadcrumb, ButtonColor, ButtonSize, ButtonState, FontAwesomeIcon, IconButton, Initialized};
+
+const SCHEDULE_DAYS: u64 = 7;
+
+#[component(transparent)]
+pub fn ClustersSchedule() -> impl IntoView {
+
+    #[component]
+    fn inner() -> impl IntoView {
+
+        let globals = use_app_globals();
+
+        let clusters = create_local_resource(|| {}, move |_| {
+            let mut carl = globals.expect_client();
+            async move {
+                carl.cluster.list_cluster_configurations().await
+                    .expect("Failed to request the list of clusters")
+            }
+        });
+
+        let cluster_deployments = create_local_resource(|| {}, move |_| {
+            let mut carl = globals.expect_client();
+            async move {
+                carl.cluster.list_cluster_deployments().await
+                    .expect("Failed to request the list of cluster deployments")
+            }
+        });
+
+        let days = store_value({
+            let today = Local::now().date_naive();
+            (0..SCHEDULE_DAYS)
+                .filter_map(|offset| today.checked_add_days(Days::new(offset)))
+                .collect::<Vec<_>>()
+        });
+
+        let header = move || {
+            days.get_value().into_iter()
+                .map(|day| view! { <th class="has-text-centered">{ day.format("%a %d.%m.").to_string() }</th> })
+                .collect::<Vec<_>>()
+        };
+
+        let rows = move || {
+            let configurations = clusters.get().unwrap_or_default();
+            let mut deployments = cluster_deployments.get().unwrap_or_default();
+            deployments.sort_by_key(|deployment| deployment.start);
+
+            deployments.into_iter()
+                .map(|deployment| {
+                    let configuration = configurations.iter()
+                        .find(|configuration| configuration.id == deployment.id)
+                        .cloned();
+                    view! { <Row deployment configuration days=days.get_value() /> }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let breadcrumbs = vec![
+            Breadcrumb::new("Dashboard", "/"),
+            Breadcrumb::new("Clusters", "/clusters"),
+            Breadcrumb::new("Schedule", "/clusters/schedule"),
+        ];
+
+        view! {
+            <BasePageContainer
+                title="Cluster Schedule"
+                breadcrumbs=breadcrumbs
+                controls=view! {
+                    <div class="buttons">
+                        <IconButton
+                            icon=FontAwesomeIcon::ArrowsRotate
+                            color=ButtonColor::Light
+                            size=ButtonSize::Normal
+                            state=ButtonState::Enabled
+                            label="Refresh schedule of cluster deployments"
+                            on_action=move || {
+                                cluster_deployments.refetch();
+                            }
+                        />
+                    </div>
+                }
+            >
+                <div class="mt-4">
+                    <Transition
+                        fallback=move || view! { <p>"Loading..."</p> }
+                    >
+                        <table class="table is-bordered is-fullwidth">
+                            <thead>
+                                <tr>
+                                    <th>"Name"</th>
+                                    <th class="is-narrow">"Start"</th>
+                                    <th class="is-narrow">"End"</th>
+                                    { header }
+                                </tr>
+                            </thead>
+                            <tbody>
+                                { rows }
+                            </tbody>
+                        </table>
+                    </Transition>
+                </div>
+            </BasePageContainer>
+        }
+    }
+
+    view! {
+        <Initialized>
+            <Inner />
+        </Initialized>
+    }
+}
+
+#[component]
+fn Row(
+    deployment: ClusterDeployment,
+    configuration: Option<ClusterConfiguration>,
+    days: Vec<NaiveDate>,
+) -> impl IntoView {
+
+    let name = configuration
+        .map(|configuration| configuration.name.value())
+        .unwrap_or_else(|| deployment.id.to_string());
+    let configurator_href = format!("/clusters/{}/configure/general", deployment.id);
+
+    let now = Utc::now();
+    let state_class = if deployment.is_started(now) { "has-background-success-light" } else { "has-background-info-light" };
+
+    let cells = days.into_iter()
+        .map(|day| {
+            let class = if is_reserved_on(&deployment, day) { state_class } else { "" };
+            view! { <td class=class></td> }
+        })
+        .collect::<Vec<_>>();
+
+    view! {
+        <tr>
+            <td class="is-vcentered">
+                <a href=configurator_href>{ name }</a>
+            </td>
+            <td class="is-vcentered">{ format_timestamp(deployment.start) }</td>
+            <td class="is-vcentered">{ format_timestamp(deployment.end()) }</td>
+            { cells }
+        </tr>
+    }
+}
+
+/// Whether the time slot of the deployment overlaps with the given day in local time.
+fn is_reserved_on(deployment: &ClusterDeployment, day: NaiveDate) -> bool {
+    let starts_before_day_ends = deployment.start
+        .map_or(true, |start| start.with_timezone(&Local).date_naive() <= day);
+    let ends_after_day_starts = deployment.end()
+        .map_or(true, |end| end.with_timezone(&Local).date_naive() >= day);
+
+    starts_before_day_ends && ends_after_day_starts
+}
+
+fn format_timestamp(timestamp: Option<DateTime<Utc>>) -> String {
+    timestamp
+        .map(|timestamp| timestamp.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| String::from("-"))
+}
@@ -33,6 +33,7 @@ mod authenticated;
 pub enum FontAwesomeIcon {
     ArrowsRotate,
     Bars,
+    Calendar,
     Check,
     ChevronDown,
     ChevronUp,
@@ -51,6 +52,7 @@ impl FontAwesomeIcon {
         match self {
             FontAwesomeIcon::ArrowsRotate => "fa-solid fa-arrows-rotate",
             FontAwesomeIcon::Bars => "fa-solid fa-bars",
+            FontAwesomeIcon::Calendar => "fa-solid fa-calendar-days",
             FontAwesomeIcon::Check => "fa-solid fa-check",
             FontAwesomeIcon::ChevronDown => "fa-solid fa-chevron-down",
             FontAwesomeIcon::ChevronUp => "fa-solid fa-chevron-up",
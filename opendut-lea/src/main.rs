@@ -7,6 +7,7 @@ use tracing_subscriber::util::SubscriberInitExt;
 use crate::app::App;
 
 mod app;
+mod audit;
 mod peers;
 mod api;
 mod dashboard;
@@ -17,6 +18,7 @@ mod routing;
 mod util;
 mod licenses;
 mod nav;
+mod projects;
 mod user;
 mod about;
 
@@ -4,6 +4,7 @@ use leptos_oidc::components::{LoginLink, LogoutLink};
 use leptos_use::on_click_outside;
 
 use crate::components::{LeaAuthenticated, ButtonColor, ButtonSize, ButtonState, FontAwesomeIcon, IconButton, Initialized};
+use crate::projects::ProjectSelector;
 use crate::use_context;
 use opendut_carl_api::carl::wasm::OptionalAuthData;
 use crate::{WriteSignal, ReadSignal};
@@ -70,6 +71,10 @@ pub fn Navbar() -> impl IntoView {
                                         <i class="fa-solid fa-microchip fa-lg pr-1" />
                                         <span class="ml-2 is-size-6">"Peers"</span>
                                     </a>
+                                    <a class="dut-nav-flyout-item" href="/audit">
+                                        <i class="fa-solid fa-clipboard-list fa-lg pr-1" />
+                                        <span class="ml-2 is-size-6">"Audit Log"</span>
+                                    </a>
                                 </div>
                                 <div>
                                     <hr class="dut-nav-flyout-divider" />
@@ -87,6 +92,9 @@ pub fn Navbar() -> impl IntoView {
                 <div class="column">
                     <a class="" href="/"><span class="dut-title is-3">"openDuT"</span></a>
                 </div>
+                <div class="column is-narrow">
+                    <ProjectSelector />
+                </div>
                 <div class="column is-narrow">
                     <div class="dut-nav-flyout is-right" class=("is-active", move || profile_visible.get())>
                         <div node_ref=profile_button_area class="dropdown-trigger">
@@ -3,6 +3,8 @@ use std::rc::Rc;
 use leptos::*;
 use tracing::{debug, error, info};
 
+use opendut_carl_api::carl::ClientError;
+use opendut_carl_api::carl::peer::StorePeerDescriptorError;
 use opendut_types::peer::{PeerDescriptor, PeerId};
 
 use crate::app::{use_app_globals, ExpectGlobals};
@@ -33,6 +35,9 @@ fn SavePeerButton(
 ) -> impl IntoView {
     let globals = use_app_globals();
     let toaster = use_toaster();
+    let reapply_toaster = Rc::clone(&toaster);
+
+    let conflicting = create_rw_signal(false);
 
     let setter = create_write_slice(
         configuration,
@@ -59,6 +64,15 @@ fn SavePeerButton(
                                     .success(),
                             );
                             setter.set(false);
+                            configuration.update(|config| config.revision += 1);
+                        }
+                        Err(ClientError::UsageError(StorePeerDescriptorError::RevisionConflict { .. })) => {
+                            conflicting.set(true);
+                            toaster.toast(
+                                Toast::builder()
+                                    .simple("The peer was changed by someone else in the meantime. Your changes were kept and can be saved over the latest revision.")
+                                    .warning(),
+                            );
                         }
                         Err(cause) => {
                             error!("Failed to create peer <{peer_id}>, due to error: {cause:?}");
@@ -83,6 +97,27 @@ fn SavePeerButton(
         }
     });
 
+    let reapply_action = create_action(move |_: &()| {
+        let toaster = Rc::clone(&reapply_toaster);
+        async move {
+            let mut carl = globals.expect_client();
+            let peer_id = configuration.get_untracked().id;
+            match carl.peers.get_peer_descriptor(peer_id).await {
+                Ok(latest) => {
+                    configuration.update(|config| config.revision = latest.revision);
+                    conflicting.set(false);
+                    store_action.dispatch(());
+                }
+                Err(cause) => {
+                    error!("Failed to reload peer <{peer_id}>, due to error: {cause:?}");
+                    toaster.toast(Toast::builder().simple("Failed to reload peer!").error());
+                }
+            }
+        }
+    });
+
+    let reapply_button_state = reapply_action.pending().derive_loading();
+
     view! {
         <IconButton
             icon=FontAwesomeIcon::Save
@@ -94,6 +129,18 @@ fn SavePeerButton(
                 store_action.dispatch(());
             }
         />
+        <Show when=move || conflicting.get()>
+            <ConfirmationButton
+                icon=FontAwesomeIcon::ArrowsRotate
+                color=ButtonColor::Danger
+                size=ButtonSize::Normal
+                state=reapply_button_state
+                label="Reload and Save Your Changes?"
+                on_conform=move || {
+                    reapply_action.dispatch(());
+                }
+            />
+        </Show>
     }
 }
 
@@ -2,6 +2,7 @@ use leptos::*;
 use leptos_router::use_params_map;
 
 use opendut_types::peer::PeerId;
+use opendut_types::peer::executor::ContainerEnvironmentVariableValue;
 
 use crate::app::{ExpectGlobals, use_app_globals};
 use crate::components::{BasePageContainer, Breadcrumb, Initialized, UserInputError, UserInputValue};
@@ -54,6 +55,7 @@ pub fn PeerConfigurator() -> impl IntoView {
                 network_interfaces: Vec::new(),
                 is_new: true,
                 executors: Vec::new(),
+                revision: 0,
             });
 
             let peer_configuration_resource = create_local_resource(|| {}, move |_| {
@@ -63,6 +65,7 @@ pub fn PeerConfigurator() -> impl IntoView {
                         peer_configuration.update(|user_configuration| {
                             user_configuration.name = UserInputValue::Right(configuration.name.value());
                             user_configuration.is_new = false;
+                            user_configuration.revision = configuration.revision;
                             user_configuration.location = UserInputValue::Right(configuration.location.unwrap_or_default().value());
                             user_configuration.devices = configuration.topology.devices.into_iter().map(|device| {
                                 create_rw_signal(UserDeviceConfiguration {
@@ -104,10 +107,17 @@ pub fn PeerConfigurator() -> impl IntoView {
                                         .collect::<Vec<_>>();
                                     let envs = envs.into_iter()
                                         .map(|env| {
+                                            let is_secret = env.is_secret();
                                             let (name, value) = env.into();
+                                            let value = match value {
+                                                ContainerEnvironmentVariableValue::Plain(value)
+                                                | ContainerEnvironmentVariableValue::Secret(value) => value,
+                                                ContainerEnvironmentVariableValue::Redacted => String::new(),
+                                            };
                                             create_rw_signal(UserContainerEnv {
                                                 name: UserInputValue::Right(name),
-                                                value: UserInputValue::Right(value)
+                                                value: UserInputValue::Right(value),
+                                                is_secret,
                                             })
                                         })
                                         .collect::<Vec<_>>();
@@ -1,4 +1,4 @@
-use leptos::{component, create_read_slice, create_rw_signal, create_slice, event_target_value, IntoView, RwSignal,  SignalGet, SignalGetUntracked, SignalUpdate, SignalWith, SignalWithUntracked, view};
+use leptos::{component, create_read_slice, create_rw_signal, create_slice, event_target_checked, event_target_value, IntoView, RwSignal,  SignalGet, SignalGetUntracked, SignalUpdate, SignalWith, SignalWithUntracked, view};
 use opendut_types::peer::executor::{ContainerCommand, ContainerCommandArgument, ContainerDevice, ContainerImage, ContainerName, ContainerPortSpec, ContainerVolume, Engine, IllegalContainerImage};
 use strum::IntoEnumIterator;
 
@@ -559,7 +559,8 @@ fn ExecutorContainerEnvsInput(
             let env = create_rw_signal(
                 UserContainerEnv {
                     name: UserInputValue::Left(String::from("Container environment variable name must not be empty.")),
-                    value: UserInputValue::Right(String::from(""))
+                    value: UserInputValue::Right(String::from("")),
+                    is_secret: false,
                 }
             );
             match executor {
@@ -622,6 +623,15 @@ fn ExecutorContainerEnvsInput(
                           }
                     );
 
+                    let (is_secret_getter, is_secret_setter) = create_slice(env,
+                          |env| {
+                              env.is_secret
+                          },
+                          |env, value| {
+                              env.is_secret = value;
+                          }
+                    );
+
                     let name_text = move || {
                         name_getter.with(|input| match input {
                             UserInputValue::Left(_) => String::new(),
@@ -671,16 +681,28 @@ fn ExecutorContainerEnvsInput(
                                 />
                                 <label class="label has-text-weight-normal mb-0">value</label>
                                 <input
-                                    class="input"
-                                    type="text"
+                                    class="input mr-2"
+                                    type=move || if is_secret_getter.get() { "password" } else { "text" }
                                     aria-label="EnvValue"
-                                    placeholder=""
+                                    placeholder=move || if is_secret_getter.get() { "unchanged" } else { "" }
                                     prop:value={ value_text }
                                     on:input=move |ev| {
                                         let target_value = event_target_value(&ev);
                                         value_setter.set(UserInputValue::Right(target_value));
                                     }
                                 />
+                                <label class="checkbox mr-2">
+                                    <input
+                                        class="mr-1"
+                                        type="checkbox"
+                                        aria-label="EnvSecret"
+                                        prop:checked=move || is_secret_getter.get()
+                                        on:change=move |ev| {
+                                            is_secret_setter.set(event_target_checked(&ev));
+                                        }
+                                    />
+                                    "secret"
+                                </label>
                                 <ConfirmationButton
                                     icon=FontAwesomeIcon::TrashCan
                                     color=ButtonColor::Light
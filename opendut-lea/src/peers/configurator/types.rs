@@ -1,7 +1,7 @@
 use leptos::{RwSignal, SignalGetUntracked};
 
 use opendut_types::peer::{PeerDescriptor, PeerId, PeerLocation, PeerName, PeerNetworkConfiguration};
-use opendut_types::peer::executor::{ContainerCommand, ContainerCommandArgument, ContainerDevice, ContainerEnvironmentVariable, ContainerImage, ContainerName, ContainerPortSpec, ContainerVolume, Engine, ExecutorDescriptor, ExecutorDescriptors};
+use opendut_types::peer::executor::{ContainerCommand, ContainerCommandArgument, ContainerDevice, ContainerEnvironmentVariable, ContainerEnvironmentVariableValue, ContainerImage, ContainerName, ContainerPortSpec, ContainerVolume, Engine, ExecutorDescriptor, ExecutorDescriptors};
 use opendut_types::topology::{DeviceDescription, DeviceDescriptor, DeviceId, DeviceName, Topology};
 use opendut_types::util::net::{NetworkInterfaceConfiguration, NetworkInterfaceDescriptor, NetworkInterfaceName};
 
@@ -46,6 +46,7 @@ pub struct UserPeerConfiguration {
     pub network_interfaces: Vec<RwSignal<UserPeerNetworkInterface>>,
     pub executors: Vec<RwSignal<UserPeerExecutor>>,
     pub is_new: bool,
+    pub revision: u64,
 }
 
 #[derive(Clone, Debug)]
@@ -67,7 +68,9 @@ pub enum UserPeerExecutor {
 #[derive(Clone, Debug, PartialEq)]
 pub struct UserContainerEnv {
     pub name: UserInputValue,
-    pub value: UserInputValue
+    pub value: UserInputValue,
+    /// An empty value of a secret keeps the value previously stored in CARL.
+    pub is_secret: bool,
 }
 
 
@@ -139,6 +142,7 @@ impl TryFrom<UserPeerConfiguration> for PeerDescriptor {
             executors: ExecutorDescriptors {
                 executors
             },
+            revision: configuration.revision,
         })
     }
 }
@@ -265,8 +269,15 @@ impl TryFrom<UserPeerExecutor> for ExecutorDescriptor {
                     .map(|env| {
                         env.name.right_ok_or(PeerMisconfigurationError::InvalidPeerExecutor)
                             .and_then(|name| env.value.right_ok_or(PeerMisconfigurationError::InvalidPeerExecutor)
-                                .and_then(|value| ContainerEnvironmentVariable::new(name, value)
-                                    .map_err(|_| PeerMisconfigurationError::InvalidPeerExecutor)))
+                                .and_then(|value| {
+                                    let value = match (env.is_secret, value.is_empty()) {
+                                        (false, _) => ContainerEnvironmentVariableValue::Plain(value),
+                                        (true, false) => ContainerEnvironmentVariableValue::Secret(value),
+                                        (true, true) => ContainerEnvironmentVariableValue::Redacted,
+                                    };
+                                    ContainerEnvironmentVariable::with_value(name, value)
+                                        .map_err(|_| PeerMisconfigurationError::InvalidPeerExecutor)
+                                }))
                     })
                     .collect::<Result<Vec<_>, _>>()?;
                 Ok(ExecutorDescriptor::Container {
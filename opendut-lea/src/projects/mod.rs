@@ -0,0 +1,77 @@
+use leptos::*;
+use tracing::warn;
+
+use opendut_types::project::{ProjectDescriptor, ProjectId};
+
+use crate::app::{ExpectGlobals, use_app_globals};
+
+const SELECTED_PROJECT_STORAGE_KEY: &str = "opendut-lea-selected-project";
+
+/// Loads the project, which was last selected in this browser, from the local storage.
+pub fn load_selected_project() -> Option<ProjectId> {
+    window().local_storage().ok().flatten()
+        .and_then(|storage| storage.get_item(SELECTED_PROJECT_STORAGE_KEY).ok().flatten())
+        .and_then(|project_id| ProjectId::try_from(project_id.as_str()).ok())
+}
+
+fn store_selected_project(project_id: ProjectId) {
+    let Some(storage) = window().local_storage().ok().flatten() else {
+        warn!("Local storage is not available. The selected project will not be remembered.");
+        return;
+    };
+    let result = if project_id.is_default() {
+        storage.remove_item(SELECTED_PROJECT_STORAGE_KEY)
+    } else {
+        storage.set_item(SELECTED_PROJECT_STORAGE_KEY, &project_id.to_string())
+    };
+    if result.is_err() {
+        warn!("Failed to remember the selected project <{project_id}>.");
+    }
+}
+
+#[component]
+pub fn ProjectSelector() -> impl IntoView {
+
+    let globals = use_app_globals();
+
+    let projects: Resource<(), Vec<ProjectDescriptor>> = create_local_resource(|| {}, move |_| {
+        let mut carl = globals.expect_client();
+        async move {
+            carl.projects.list_projects().await
+                .unwrap_or_else(|_| vec![ProjectDescriptor::default_project()])
+        }
+    });
+
+    let selected = move || globals.expect_client().project.selected().unwrap_or_default();
+
+    let options = move || {
+        let selected = selected();
+        projects.get()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|project| {
+                let is_selected = project.id == selected;
+                view! {
+                    <option value=project.id.to_string() selected=is_selected>{ project.name.to_string() }</option>
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    view! {
+        <div class="select is-small"
+            on:change=move |ev| {
+                let Ok(project_id) = ProjectId::try_from(event_target_value(&ev).as_str()) else {
+                    return;
+                };
+                globals.expect_client().project.select(Some(project_id).filter(|project_id| !project_id.is_default()));
+                store_selected_project(project_id);
+                // Reload, so that all pages fetch the resources of the newly selected project.
+                let _ = window().location().reload();
+            }>
+            <select title="Project">
+                { options }
+            </select>
+        </div>
+    }
+}
@@ -12,6 +12,7 @@ use crate::util::url::UrlEncodable;
 
 pub enum WellKnownRoutes {
     ClustersOverview,
+    ClustersSchedule,
     ClusterConfigurator { id: ClusterId },
     PeerConfigurator { id: PeerId },
     PeersOverview,
@@ -26,6 +27,10 @@ impl WellKnownRoutes {
                 base.join("/clusters")
                     .expect("ClustersOverview route should be valid.")
             },
+            WellKnownRoutes::ClustersSchedule => {
+                base.join("/clusters/schedule")
+                    .expect("ClustersSchedule route should be valid.")
+            },
             WellKnownRoutes::ClusterConfigurator { id } => {
                 base.join(&format!("/clusters/{}/configure/general", id.url_encode()))
                     .expect("ClusterConfigurator route should be valid.")
@@ -58,7 +63,8 @@ mod routes {
     use leptos::*;
     use leptos_router::{Route, Router, Routes};
 
-    use crate::clusters::{ClusterConfigurator, ClustersOverview};
+    use crate::audit::AuditOverview;
+    use crate::clusters::{ClusterConfigurator, ClustersOverview, ClustersSchedule};
     use crate::dashboard::Dashboard;
     use crate::error::ErrorPage;
     use crate::licenses::LicensesOverview;
@@ -75,9 +81,11 @@ mod routes {
                     <Routes>
                         <Route path="/" view=|| view! { <Dashboard /> } />
                         <Route path="/clusters" view=|| view! { <ClustersOverview /> } />
+                        <Route path="/clusters/schedule" view=|| view! { <ClustersSchedule /> } />
                         <Route path="/clusters/:id/configure/:tab" view=|| view! { <ClusterConfigurator /> } />
                         <Route path="/peers" view=|| view! { <PeersOverview /> } />
                         <Route path="/peers/:id/configure/:tab" view=|| view! { <PeerConfigurator /> } />
+                        <Route path="/audit" view=|| view! { <AuditOverview /> } />
                         <Route path="/user" view=|| view! { <UserOverview /> } />
                         <Route path="/licenses" view=|| view! { <LicensesOverview /> } />
                         <Route path="/about" view=|| view! { <AboutOverview /> } />
@@ -8,6 +8,7 @@ fn main() -> Result<()> {
         "proto/opendut/types/peer/peer.proto",
         "proto/opendut/types/peer/configuration.proto",
         "proto/opendut/types/peer/executor.proto",
+        "proto/opendut/types/project/project.proto",
         "proto/opendut/types/topology/device.proto",
         "proto/opendut/types/topology/topology.proto",
         "proto/opendut/types/util/metadata.proto",
@@ -20,5 +21,10 @@ fn main() -> Result<()> {
         "proto/"
     ];
 
-    prost_build::compile_protos(&protos, &includes)
+    prost_build::Config::new()
+        .skip_debug([ // Debug is implemented manually to not log secrets
+            ".opendut.types.peer.executor.ContainerEnvironmentVariable",
+            ".opendut.types.vpn.WireGuardPrivateKey",
+        ])
+        .compile_protos(&protos, &includes)
 }
@@ -3,6 +3,7 @@ use crate::cluster::ClusterId;
 use crate::peer::PeerId;
 use crate::util::net::NetworkInterfaceDescriptor;
 use crate::util::Port;
+use crate::vpn::wireguard::WireGuardPeer;
 
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -18,4 +19,6 @@ pub struct PeerClusterAssignment {
     pub vpn_address: IpAddr,
     pub can_server_port: Port,
     pub device_interfaces: Vec<NetworkInterfaceDescriptor>,
+    /// Set, if the peers are connected via the WireGuard VPN of CARL.
+    pub wireguard: Option<WireGuardPeer>,
 }
@@ -1,7 +1,9 @@
 use std::collections::HashSet;
 use std::fmt;
 use std::ops::Not;
+use std::time::Duration;
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -11,7 +13,9 @@ use crate::peer::PeerId;
 use crate::topology::DeviceId;
 
 mod assignment;
+pub mod operation;
 pub mod state;
+pub mod validation;
 
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
@@ -149,6 +153,10 @@ pub struct ClusterConfiguration {
     pub name: ClusterName,
     pub leader: PeerId,
     pub devices: HashSet<DeviceId>,
+    /// Incremented by CARL whenever the configuration is stored. Storing a configuration based on an outdated revision fails.
+    /// A revision of `0` stores the configuration unconditionally.
+    #[serde(default)]
+    pub revision: u64,
 }
 
 #[derive(thiserror::Error, Clone, Debug)]
@@ -162,6 +170,48 @@ pub enum IllegalClusterConfiguration {
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ClusterDeployment {
     pub id: ClusterId,
+    /// When the cluster is deployed. Deployed immediately, if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<DateTime<Utc>>,
+    /// How long the cluster stays deployed, counted from the start, before it is undeployed again.
+    /// Stays deployed until the deployment is deleted, if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lease: Option<Duration>,
+}
+
+impl ClusterDeployment {
+    /// A deployment, which is deployed immediately and without a lease.
+    pub fn new(id: ClusterId) -> Self {
+        Self { id, start: None, lease: None }
+    }
+
+    /// The time at which the lease ends, if the deployment has a start and a lease.
+    pub fn end(&self) -> Option<DateTime<Utc>> {
+        let start = self.start?;
+        let lease = chrono::Duration::from_std(self.lease?).ok()?;
+        start.checked_add_signed(lease)
+    }
+
+    pub fn is_started(&self, now: DateTime<Utc>) -> bool {
+        self.start.map_or(true, |start| start <= now)
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.end().is_some_and(|end| end <= now)
+    }
+
+    /// Whether the time slots of both deployments overlap. A deployment without start or lease is unbounded on that side.
+    pub fn overlaps(&self, other: &ClusterDeployment) -> bool {
+        let starts_before_other_ends = match (self.start, other.end()) {
+            (Some(start), Some(other_end)) => start < other_end,
+            _ => true,
+        };
+        let ends_after_other_starts = match (self.end(), other.start) {
+            (Some(end), Some(other_start)) => other_start < end,
+            _ => true,
+        };
+        starts_before_other_ends && ends_after_other_starts
+    }
 }
 
 
@@ -184,4 +234,24 @@ mod tests {
         let _peer_name = ClusterName::try_from("_asd123".to_string()).is_err();
         Ok(())
     }
+
+    #[test]
+    fn A_ClusterDeployment_should_overlap_with_deployments_in_the_same_time_slot() -> Result<()> {
+        let start = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        let hour = Duration::from_secs(60 * 60);
+        let deployment = |start_offset_hours: i64, lease: Option<Duration>| ClusterDeployment {
+            id: ClusterId::random(),
+            start: Some(start + chrono::Duration::hours(start_offset_hours)),
+            lease,
+        };
+
+        let reservation = deployment(0, Some(2 * hour));
+
+        verify_that!(reservation.end(), some(eq(start + chrono::Duration::hours(2))))?;
+        verify_that!(reservation.overlaps(&deployment(1, Some(hour))), eq(true))?;
+        verify_that!(reservation.overlaps(&deployment(2, Some(hour))), eq(false))?;
+        verify_that!(reservation.overlaps(&deployment(-1, None)), eq(true))?;
+        verify_that!(reservation.overlaps(&ClusterDeployment::new(ClusterId::random())), eq(true))?;
+        verify_that!(reservation.is_expired(start + chrono::Duration::hours(2)), eq(true))
+    }
 }
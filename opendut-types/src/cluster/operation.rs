@@ -0,0 +1,103 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::cluster::ClusterId;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DeploymentOperationId(pub Uuid);
+
+impl DeploymentOperationId {
+    pub fn random() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl From<Uuid> for DeploymentOperationId {
+    fn from(value: Uuid) -> Self {
+        Self(value)
+    }
+}
+
+impl fmt::Display for DeploymentOperationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Tracks the deployment of a cluster, which CARL performs asynchronously after a `ClusterDeployment` was stored.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DeploymentOperation {
+    pub id: DeploymentOperationId,
+    pub cluster_id: ClusterId,
+    pub state: DeploymentOperationState,
+}
+
+impl DeploymentOperation {
+    pub fn is_finished(&self) -> bool {
+        matches!(self.state, DeploymentOperationState::Succeeded | DeploymentOperationState::Failed { .. })
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DeploymentOperationState {
+    Pending,
+    Running { step: DeploymentStep },
+    Succeeded,
+    Failed { error: DeploymentOperationError },
+}
+
+impl fmt::Display for DeploymentOperationState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeploymentOperationState::Pending => write!(f, "Pending"),
+            DeploymentOperationState::Running { step } => write!(f, "Running ({step})"),
+            DeploymentOperationState::Succeeded => write!(f, "Succeeded"),
+            DeploymentOperationState::Failed { error } => write!(f, "Failed: {error}"),
+        }
+    }
+}
+
+/// The reason, why a [`DeploymentOperation`] failed.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DeploymentOperationError {
+    /// The deployment was deleted, before the operation started.
+    DeploymentDeleted,
+    /// The lease of the deployment expired, before the operation started.
+    LeaseExpired,
+    /// The deployment failed in the given step.
+    StepFailed { step: DeploymentStep, cause: String },
+}
+
+impl fmt::Display for DeploymentOperationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeploymentOperationError::DeploymentDeleted => write!(f, "The deployment was deleted, before it started."),
+            DeploymentOperationError::LeaseExpired => write!(f, "The lease expired, before the deployment started."),
+            DeploymentOperationError::StepFailed { step, cause } => write!(f, "Failed while {step}: {cause}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DeploymentStep {
+    ResolvingPeers,
+    CreatingVpnGroup,
+    AssigningPeers,
+}
+
+impl fmt::Display for DeploymentStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeploymentStep::ResolvingPeers => write!(f, "resolving peers"),
+            DeploymentStep::CreatingVpnGroup => write!(f, "creating VPN group"),
+            DeploymentStep::AssigningPeers => write!(f, "assigning peers"),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cluster::ClusterId;
+use crate::peer::PeerId;
+use crate::topology::DeviceId;
+use crate::util::Port;
+
+/// The result of checking whether a cluster can be deployed, without deploying it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ClusterDeploymentValidation {
+    pub cluster_id: ClusterId,
+    pub issues: Vec<ClusterDeploymentIssue>,
+}
+
+impl ClusterDeploymentValidation {
+    pub fn is_deployable(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClusterDeploymentIssue {
+    PeerForDeviceNotFound {
+        device_id: DeviceId,
+    },
+    LeaderNotFound {
+        leader_id: PeerId,
+    },
+    PeerNotUp {
+        peer_id: PeerId,
+    },
+    PeerInOtherCluster {
+        peer_id: PeerId,
+        other_cluster_id: ClusterId,
+    },
+    CanServerPortsExhausted {
+        range_start: Port,
+        range_end: Port,
+        required: u32,
+    },
+    /// CAN FD frames cannot be forwarded to interfaces without CAN FD, so either all or none of the CAN interfaces have to use it.
+    IncompatibleCanFd {
+        fd_devices: Vec<DeviceId>,
+        non_fd_devices: Vec<DeviceId>,
+    },
+    VpnUnreachable {
+        peer_id: PeerId,
+        cause: String,
+    },
+    /// The VPN cannot accept the traffic of this protocol between the members, without accepting any other traffic,
+    /// so the cluster cannot be deployed.
+    VpnProtocolUnsupported {
+        protocol: String,
+    },
+}
+
+impl fmt::Display for ClusterDeploymentIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClusterDeploymentIssue::PeerForDeviceNotFound { device_id } =>
+                write!(f, "No peer found for device <{device_id}>."),
+            ClusterDeploymentIssue::LeaderNotFound { leader_id } =>
+                write!(f, "Peer designated as leader <{leader_id}> not found."),
+            ClusterDeploymentIssue::PeerNotUp { peer_id } =>
+                write!(f, "Peer <{peer_id}> is not up."),
+            ClusterDeploymentIssue::PeerInOtherCluster { peer_id, other_cluster_id } =>
+                write!(f, "Peer <{peer_id}> is already a member of cluster <{other_cluster_id}>."),
+            ClusterDeploymentIssue::CanServerPortsExhausted { range_start, range_end, required } =>
+                write!(f, "Not enough free CAN server ports for {required} members between port {range_start} and {range_end}."),
+            ClusterDeploymentIssue::IncompatibleCanFd { fd_devices, non_fd_devices } => {
+                let join = |devices: &[DeviceId]| devices.iter().map(|device| format!("<{device}>")).collect::<Vec<_>>().join(", ");
+                write!(f, "CAN FD is enabled for devices {}, but disabled for devices {}.", join(fd_devices), join(non_fd_devices))
+            }
+            ClusterDeploymentIssue::VpnUnreachable { peer_id, cause } =>
+                write!(f, "Peer <{peer_id}> is not reachable via the VPN: {cause}"),
+            ClusterDeploymentIssue::VpnProtocolUnsupported { protocol } =>
+                write!(f, "The VPN cannot restrict the traffic between the members to {protocol} without allowing any other traffic."),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 pub mod cluster;
 pub mod peer;
+pub mod project;
 pub mod proto;
 pub mod topology;
 pub mod vpn;
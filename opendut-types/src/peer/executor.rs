@@ -1,7 +1,7 @@
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 use strum::EnumIter;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -9,6 +9,31 @@ pub struct ExecutorDescriptors {
     pub executors: Vec<ExecutorDescriptor>,
 }
 
+impl ExecutorDescriptors {
+    /// Returns these executors with the values of all secret environment variables withheld.
+    pub fn redacted(self) -> Self {
+        let executors = self.executors.into_iter()
+            .map(|executor| match executor {
+                ExecutorDescriptor::Container { engine, name, image, volumes, devices, envs, ports, command, args } => {
+                    ExecutorDescriptor::Container {
+                        engine,
+                        name,
+                        image,
+                        volumes,
+                        devices,
+                        envs: envs.into_iter().map(ContainerEnvironmentVariable::redacted).collect(),
+                        ports,
+                        command,
+                        args,
+                    }
+                }
+                executor => executor,
+            })
+            .collect();
+        Self { executors }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum ExecutorDescriptor {
@@ -106,7 +131,55 @@ impl fmt::Display for ContainerName {
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ContainerEnvironmentVariable {
     name: String,
-    value: String,
+    value: ContainerEnvironmentVariableValue,
+}
+
+/// The value of a [`ContainerEnvironmentVariable`].
+///
+/// Secret values are only revealed to the peer running the container.
+/// Everywhere else, they are replaced with [`ContainerEnvironmentVariableValue::Redacted`].
+/// Secret values are neither serialized nor included in the `Debug` output.
+#[derive(Clone, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ContainerEnvironmentVariableValue {
+    Plain(String),
+    Secret(String),
+    Redacted,
+}
+
+impl ContainerEnvironmentVariableValue {
+    pub const REDACTED: &'static str = "********";
+}
+
+impl fmt::Debug for ContainerEnvironmentVariableValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ContainerEnvironmentVariableValue::Plain(value) => f.debug_tuple("Plain").field(value).finish(),
+            ContainerEnvironmentVariableValue::Secret(_) => f.debug_tuple("Secret").field(&Self::REDACTED).finish(),
+            ContainerEnvironmentVariableValue::Redacted => write!(f, "Redacted"),
+        }
+    }
+}
+
+impl Serialize for ContainerEnvironmentVariableValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        const NAME: &str = "ContainerEnvironmentVariableValue";
+        match self {
+            ContainerEnvironmentVariableValue::Plain(value) => serializer.serialize_newtype_variant(NAME, 0, "plain", value),
+            ContainerEnvironmentVariableValue::Secret(_)
+            | ContainerEnvironmentVariableValue::Redacted => serializer.serialize_unit_variant(NAME, 2, "redacted"),
+        }
+    }
+}
+
+impl Display for ContainerEnvironmentVariableValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ContainerEnvironmentVariableValue::Plain(value) => write!(f, "{value}"),
+            ContainerEnvironmentVariableValue::Secret(_)
+            | ContainerEnvironmentVariableValue::Redacted => write!(f, "{}", Self::REDACTED),
+        }
+    }
 }
 
 #[derive(thiserror::Error, Clone, Debug)]
@@ -117,11 +190,19 @@ pub enum IllegalContainerEnvironmentVariable{
 
 impl ContainerEnvironmentVariable {
     pub fn new(name: impl Into<String>, value: impl Into<String>) -> Result<Self, IllegalContainerEnvironmentVariable> {
+        Self::with_value(name, ContainerEnvironmentVariableValue::Plain(value.into()))
+    }
+
+    pub fn new_secret(name: impl Into<String>, value: impl Into<String>) -> Result<Self, IllegalContainerEnvironmentVariable> {
+        Self::with_value(name, ContainerEnvironmentVariableValue::Secret(value.into()))
+    }
+
+    pub fn with_value(name: impl Into<String>, value: ContainerEnvironmentVariableValue) -> Result<Self, IllegalContainerEnvironmentVariable> {
         let name= name.into();
         if name.is_empty() {
             Err(IllegalContainerEnvironmentVariable::EmptyName)
         } else {
-            Ok(Self{name, value: value.into()})
+            Ok(Self{name, value})
         }
     }
     
@@ -129,17 +210,36 @@ impl ContainerEnvironmentVariable {
         self.name.as_str()
     }
     
-    pub fn value(&self) -> &str {
-        self.value.as_str()
+    pub fn value(&self) -> &ContainerEnvironmentVariableValue {
+        &self.value
+    }
+
+    pub fn is_secret(&self) -> bool {
+        matches!(self.value, ContainerEnvironmentVariableValue::Secret(_) | ContainerEnvironmentVariableValue::Redacted)
+    }
+
+    /// Returns this variable with its value withheld, if it is a secret.
+    pub fn redacted(self) -> Self {
+        if self.is_secret() {
+            Self { value: ContainerEnvironmentVariableValue::Redacted, ..self }
+        } else {
+            self
+        }
     }
 }
 
-impl From<ContainerEnvironmentVariable> for (String, String) {
+impl From<ContainerEnvironmentVariable> for (String, ContainerEnvironmentVariableValue) {
     fn from(value: ContainerEnvironmentVariable) -> Self {
         (value.name, value.value)
     }
 }
 
+impl Display for ContainerEnvironmentVariable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.value)
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ContainerImage(String);
 
@@ -492,3 +592,23 @@ impl fmt::Display for ContainerCommandArgument {
 
 #[derive(thiserror::Error, Clone, Debug)]
 pub enum IllegalContainerConfiguration {}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn should_not_reveal_secret_values_when_serialized_or_debug_printed() -> Result<()> {
+        let plain = ContainerEnvironmentVariable::new("NAME", "value")?;
+        let secret = ContainerEnvironmentVariable::new_secret("TOKEN", "my-secret")?;
+
+        verify_that!(serde_json::to_string(&plain)?, eq(r#"{"name":"NAME","value":{"plain":"value"}}"#))?;
+        verify_that!(serde_json::to_string(&secret)?, eq(r#"{"name":"TOKEN","value":"redacted"}"#))?;
+        verify_that!(format!("{secret:?}"), not(contains_substring("my-secret")))?;
+
+        let deserialized = serde_json::from_str::<ContainerEnvironmentVariable>(&serde_json::to_string(&secret)?)?;
+        verify_that!(deserialized.value(), eq(&ContainerEnvironmentVariableValue::Redacted))
+    }
+}
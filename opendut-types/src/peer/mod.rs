@@ -238,6 +238,10 @@ pub struct PeerDescriptor {
     pub network_configuration: PeerNetworkConfiguration,
     pub topology: Topology,
     pub executors: ExecutorDescriptors,
+    /// Incremented by CARL whenever the descriptor is stored. Storing a descriptor based on an outdated revision fails.
+    /// A revision of `0` stores the descriptor unconditionally.
+    #[serde(default)]
+    pub revision: u64,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -0,0 +1,179 @@
+use std::fmt;
+use std::ops::Not;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A project groups peers and clusters, which are only visible within that project.
+///
+/// Requests, which do not select a project, operate on the [`ProjectId::DEFAULT`] project.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ProjectId(pub Uuid);
+
+impl ProjectId {
+
+    pub const DEFAULT: Self = Self(Uuid::from_bytes([0; 16]));
+
+    pub fn random() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::DEFAULT
+    }
+}
+
+impl Default for ProjectId {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl From<Uuid> for ProjectId {
+
+    fn from(value: Uuid) -> Self {
+        Self(value)
+    }
+}
+
+#[derive(thiserror::Error, Clone, Debug)]
+#[error("Illegal ProjectId: {value}")]
+pub struct IllegalProjectId {
+    pub value: String,
+}
+
+impl TryFrom<&str> for ProjectId {
+
+    type Error = IllegalProjectId;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Uuid::parse_str(value)
+            .map(Self)
+            .map_err(|_| IllegalProjectId { value: String::from(value) })
+    }
+}
+
+impl fmt::Display for ProjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct ProjectName(pub(crate) String);
+
+impl ProjectName {
+
+    pub const MIN_LENGTH: usize = 4;
+    pub const MAX_LENGTH: usize = 32;
+
+    pub const DEFAULT: &'static str = "default";
+
+    pub fn default_project() -> Self {
+        Self(String::from(Self::DEFAULT))
+    }
+
+    pub fn value(self) -> String {
+        self.0
+    }
+}
+
+#[derive(thiserror::Error, Clone, Debug)]
+pub enum IllegalProjectName {
+    #[error("Project name '{value}' is too short. Expected at least {expected} characters, got {actual}.")]
+    TooShort { value: String, expected: usize, actual: usize },
+    #[error("Project name '{value}' is too long. Expected at most {expected} characters, got {actual}.")]
+    TooLong { value: String, expected: usize, actual: usize },
+    #[error("Project name '{value}' contains invalid characters.")]
+    InvalidCharacter { value: String },
+    #[error("Project name '{value}' contains invalid start or end characters.")]
+    InvalidStartEndCharacter { value: String },
+}
+
+impl From<ProjectName> for String {
+    fn from(value: ProjectName) -> Self {
+        value.0
+    }
+}
+
+impl TryFrom<String> for ProjectName {
+
+    type Error = IllegalProjectName;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let length = value.len();
+        if length < Self::MIN_LENGTH {
+            Err(IllegalProjectName::TooShort {
+                value,
+                expected: Self::MIN_LENGTH,
+                actual: length,
+            })
+        }
+        else if length > Self::MAX_LENGTH {
+            Err(IllegalProjectName::TooLong {
+                value,
+                expected: Self::MAX_LENGTH,
+                actual: length,
+            })
+        }
+        else if crate::util::invalid_start_and_end_of_a_name(&value) {
+            Err(IllegalProjectName::InvalidStartEndCharacter { value })
+        }
+        else if value.chars().any(|c| crate::util::valid_characters_in_name(&c).not()) {
+            Err(IllegalProjectName::InvalidCharacter {
+                value
+            })
+        }
+        else {
+            Ok(Self(value))
+        }
+    }
+}
+
+impl TryFrom<&str> for ProjectName {
+
+    type Error = IllegalProjectName;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        ProjectName::try_from(value.to_owned())
+    }
+}
+
+impl fmt::Display for ProjectName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ProjectDescriptor {
+    pub id: ProjectId,
+    pub name: ProjectName,
+}
+
+impl ProjectDescriptor {
+    pub fn default_project() -> Self {
+        Self {
+            id: ProjectId::DEFAULT,
+            name: ProjectName::default_project(),
+        }
+    }
+}
+
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use googletest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn A_ProjectName_should_reject_invalid_names() -> Result<()> {
+        verify_that!(ProjectName::try_from("team-a"), ok(anything()))?;
+        verify_that!(ProjectName::try_from("abc"), err(anything()))?;
+        verify_that!(ProjectName::try_from("-team"), err(anything()))?;
+        verify_that!(ProjectName::try_from("team a"), err(anything()))
+    }
+}
@@ -1,3 +1,7 @@
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+
 use crate::proto::{ConversionError, ConversionErrorBuilder};
 use crate::proto::topology::DeviceId;
 
@@ -59,6 +63,7 @@ impl From<crate::cluster::ClusterConfiguration> for ClusterConfiguration {
             devices: configuration.devices.into_iter()
                         .map(DeviceId::from)
                         .collect(),
+            revision: configuration.revision,
         }
     }
 }
@@ -88,6 +93,7 @@ impl TryFrom<ClusterConfiguration> for crate::cluster::ClusterConfiguration {
             devices: configuration.devices.into_iter()
                         .map(DeviceId::try_into)
                         .collect::<Result<_, _>>()?,
+            revision: configuration.revision,
         })
     }
 }
@@ -96,6 +102,8 @@ impl From<crate::cluster::ClusterDeployment> for ClusterDeployment {
     fn from(deployment: crate::cluster::ClusterDeployment) -> Self {
         Self {
             id: Some(deployment.id.into()),
+            start_ms: deployment.start.map(|start| start.timestamp_millis()),
+            lease_ms: deployment.lease.map(|lease| u64::try_from(lease.as_millis()).unwrap_or(u64::MAX)),
         }
     }
 }
@@ -110,8 +118,16 @@ impl TryFrom<ClusterDeployment> for crate::cluster::ClusterDeployment {
             .ok_or(ErrorBuilder::field_not_set("id"))?
             .try_into()?;
 
+        let start = deployment.start_ms
+            .map(|start_ms| Utc.timestamp_millis_opt(start_ms).single()
+                .ok_or_else(|| ErrorBuilder::message(format!("Invalid start time: {start_ms}")))
+            )
+            .transpose()?;
+
         Ok(Self {
             id: cluster_id,
+            start,
+            lease: deployment.lease_ms.map(Duration::from_millis),
         })
     }
 }
@@ -227,6 +243,7 @@ impl From<crate::cluster::PeerClusterAssignment> for PeerClusterAssignment {
             vpn_address: Some(value.vpn_address.into()),
             can_server_port: Some(value.can_server_port.into()),
             device_interfaces: value.device_interfaces.into_iter().map(Into::into).collect(),
+            wireguard: value.wireguard.map(Into::into),
         }
     }
 }
@@ -253,15 +270,323 @@ impl TryFrom<PeerClusterAssignment> for crate::cluster::PeerClusterAssignment {
             .map(TryInto::try_into)
             .collect::<Result<_, _>>()?;
 
+        let wireguard = value.wireguard
+            .map(TryInto::try_into)
+            .transpose()?;
+
         Ok(Self {
             peer_id,
             vpn_address,
             can_server_port,
             device_interfaces,
+            wireguard,
+        })
+    }
+}
+
+impl From<crate::cluster::operation::DeploymentOperationId> for DeploymentOperationId {
+    fn from(value: crate::cluster::operation::DeploymentOperationId) -> Self {
+        Self {
+            uuid: Some(value.0.into())
+        }
+    }
+}
+
+impl TryFrom<DeploymentOperationId> for crate::cluster::operation::DeploymentOperationId {
+    type Error = ConversionError;
+
+    fn try_from(value: DeploymentOperationId) -> Result<Self, Self::Error> {
+        type ErrorBuilder = ConversionErrorBuilder<DeploymentOperationId, crate::cluster::operation::DeploymentOperationId>;
+
+        value.uuid
+            .ok_or(ErrorBuilder::field_not_set("uuid"))
+            .map(|uuid| Self(uuid.into()))
+    }
+}
+
+impl From<crate::cluster::operation::DeploymentOperation> for DeploymentOperation {
+    fn from(value: crate::cluster::operation::DeploymentOperation) -> Self {
+        Self {
+            id: Some(value.id.into()),
+            cluster_id: Some(value.cluster_id.into()),
+            state: Some(value.state.into()),
+        }
+    }
+}
+
+impl TryFrom<DeploymentOperation> for crate::cluster::operation::DeploymentOperation {
+    type Error = ConversionError;
+
+    fn try_from(value: DeploymentOperation) -> Result<Self, Self::Error> {
+        type ErrorBuilder = ConversionErrorBuilder<DeploymentOperation, crate::cluster::operation::DeploymentOperation>;
+
+        let id = value.id
+            .ok_or(ErrorBuilder::field_not_set("id"))?
+            .try_into()?;
+        let cluster_id = value.cluster_id
+            .ok_or(ErrorBuilder::field_not_set("cluster_id"))?
+            .try_into()?;
+        let state = value.state
+            .ok_or(ErrorBuilder::field_not_set("state"))?
+            .try_into()?;
+
+        Ok(Self {
+            id,
+            cluster_id,
+            state,
+        })
+    }
+}
+
+impl From<crate::cluster::operation::DeploymentOperationState> for DeploymentOperationState {
+    fn from(value: crate::cluster::operation::DeploymentOperationState) -> Self {
+        use crate::cluster::operation::DeploymentOperationState as State;
+
+        let inner = match value {
+            State::Pending => deployment_operation_state::Inner::Pending(DeploymentOperationStatePending {}),
+            State::Running { step } => deployment_operation_state::Inner::Running(DeploymentOperationStateRunning {
+                step: Some(step.into()),
+            }),
+            State::Succeeded => deployment_operation_state::Inner::Succeeded(DeploymentOperationStateSucceeded {}),
+            State::Failed { error } => deployment_operation_state::Inner::Failed(DeploymentOperationStateFailed {
+                error: Some(error.into()),
+            }),
+        };
+        Self {
+            inner: Some(inner),
+        }
+    }
+}
+
+impl TryFrom<DeploymentOperationState> for crate::cluster::operation::DeploymentOperationState {
+    type Error = ConversionError;
+
+    fn try_from(value: DeploymentOperationState) -> Result<Self, Self::Error> {
+        use crate::cluster::operation::DeploymentOperationState as State;
+        type ErrorBuilder = ConversionErrorBuilder<DeploymentOperationState, crate::cluster::operation::DeploymentOperationState>;
+
+        let inner = value.inner
+            .ok_or(ErrorBuilder::field_not_set("inner"))?;
+
+        let state = match inner {
+            deployment_operation_state::Inner::Pending(_) => State::Pending,
+            deployment_operation_state::Inner::Running(running) => State::Running {
+                step: running.step
+                    .ok_or(ErrorBuilder::field_not_set("step"))?
+                    .try_into()?,
+            },
+            deployment_operation_state::Inner::Succeeded(_) => State::Succeeded,
+            deployment_operation_state::Inner::Failed(failed) => State::Failed {
+                error: failed.error
+                    .ok_or(ErrorBuilder::field_not_set("error"))?
+                    .try_into()?,
+            },
+        };
+        Ok(state)
+    }
+}
+
+impl From<crate::cluster::operation::DeploymentOperationError> for DeploymentOperationError {
+    fn from(value: crate::cluster::operation::DeploymentOperationError) -> Self {
+        use crate::cluster::operation::DeploymentOperationError as Error;
+
+        let inner = match value {
+            Error::DeploymentDeleted => deployment_operation_error::Inner::DeploymentDeleted(DeploymentOperationErrorDeploymentDeleted {}),
+            Error::LeaseExpired => deployment_operation_error::Inner::LeaseExpired(DeploymentOperationErrorLeaseExpired {}),
+            Error::StepFailed { step, cause } => deployment_operation_error::Inner::StepFailed(DeploymentOperationErrorStepFailed {
+                step: Some(step.into()),
+                cause,
+            }),
+        };
+        Self {
+            inner: Some(inner),
+        }
+    }
+}
+
+impl TryFrom<DeploymentOperationError> for crate::cluster::operation::DeploymentOperationError {
+    type Error = ConversionError;
+
+    fn try_from(value: DeploymentOperationError) -> Result<Self, Self::Error> {
+        use crate::cluster::operation::DeploymentOperationError as Error;
+        type ErrorBuilder = ConversionErrorBuilder<DeploymentOperationError, crate::cluster::operation::DeploymentOperationError>;
+
+        let inner = value.inner
+            .ok_or(ErrorBuilder::field_not_set("inner"))?;
+
+        let error = match inner {
+            deployment_operation_error::Inner::DeploymentDeleted(_) => Error::DeploymentDeleted,
+            deployment_operation_error::Inner::LeaseExpired(_) => Error::LeaseExpired,
+            deployment_operation_error::Inner::StepFailed(failed) => Error::StepFailed {
+                step: failed.step
+                    .ok_or(ErrorBuilder::field_not_set("step"))?
+                    .try_into()?,
+                cause: failed.cause,
+            },
+        };
+        Ok(error)
+    }
+}
+
+impl From<crate::cluster::operation::DeploymentStep> for DeploymentStep {
+    fn from(value: crate::cluster::operation::DeploymentStep) -> Self {
+        use crate::cluster::operation::DeploymentStep as Step;
+
+        let inner = match value {
+            Step::ResolvingPeers => deployment_step::Inner::ResolvingPeers(DeploymentStepResolvingPeers {}),
+            Step::CreatingVpnGroup => deployment_step::Inner::CreatingVpnGroup(DeploymentStepCreatingVpnGroup {}),
+            Step::AssigningPeers => deployment_step::Inner::AssigningPeers(DeploymentStepAssigningPeers {}),
+        };
+        Self {
+            inner: Some(inner),
+        }
+    }
+}
+
+impl TryFrom<DeploymentStep> for crate::cluster::operation::DeploymentStep {
+    type Error = ConversionError;
+
+    fn try_from(value: DeploymentStep) -> Result<Self, Self::Error> {
+        use crate::cluster::operation::DeploymentStep as Step;
+        type ErrorBuilder = ConversionErrorBuilder<DeploymentStep, crate::cluster::operation::DeploymentStep>;
+
+        let inner = value.inner
+            .ok_or(ErrorBuilder::field_not_set("inner"))?;
+
+        let step = match inner {
+            deployment_step::Inner::ResolvingPeers(_) => Step::ResolvingPeers,
+            deployment_step::Inner::CreatingVpnGroup(_) => Step::CreatingVpnGroup,
+            deployment_step::Inner::AssigningPeers(_) => Step::AssigningPeers,
+        };
+        Ok(step)
+    }
+}
+
+impl From<crate::cluster::validation::ClusterDeploymentValidation> for ClusterDeploymentValidation {
+    fn from(value: crate::cluster::validation::ClusterDeploymentValidation) -> Self {
+        Self {
+            cluster_id: Some(value.cluster_id.into()),
+            issues: value.issues.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<ClusterDeploymentValidation> for crate::cluster::validation::ClusterDeploymentValidation {
+    type Error = ConversionError;
+
+    fn try_from(value: ClusterDeploymentValidation) -> Result<Self, Self::Error> {
+        type ErrorBuilder = ConversionErrorBuilder<ClusterDeploymentValidation, crate::cluster::validation::ClusterDeploymentValidation>;
+
+        let cluster_id: crate::cluster::ClusterId = value.cluster_id
+            .ok_or(ErrorBuilder::field_not_set("cluster_id"))?
+            .try_into()?;
+
+        let issues = value.issues
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            cluster_id,
+            issues,
         })
     }
 }
 
+impl From<crate::cluster::validation::ClusterDeploymentIssue> for ClusterDeploymentIssue {
+    fn from(value: crate::cluster::validation::ClusterDeploymentIssue) -> Self {
+        use crate::cluster::validation::ClusterDeploymentIssue as Issue;
+
+        let inner = match value {
+            Issue::PeerForDeviceNotFound { device_id } =>
+                cluster_deployment_issue::Inner::PeerForDeviceNotFound(ClusterDeploymentIssuePeerForDeviceNotFound {
+                    device_id: Some(device_id.into()),
+                }),
+            Issue::LeaderNotFound { leader_id } =>
+                cluster_deployment_issue::Inner::LeaderNotFound(ClusterDeploymentIssueLeaderNotFound {
+                    leader_id: Some(leader_id.into()),
+                }),
+            Issue::PeerNotUp { peer_id } =>
+                cluster_deployment_issue::Inner::PeerNotUp(ClusterDeploymentIssuePeerNotUp {
+                    peer_id: Some(peer_id.into()),
+                }),
+            Issue::PeerInOtherCluster { peer_id, other_cluster_id } =>
+                cluster_deployment_issue::Inner::PeerInOtherCluster(ClusterDeploymentIssuePeerInOtherCluster {
+                    peer_id: Some(peer_id.into()),
+                    other_cluster_id: Some(other_cluster_id.into()),
+                }),
+            Issue::CanServerPortsExhausted { range_start, range_end, required } =>
+                cluster_deployment_issue::Inner::CanServerPortsExhausted(ClusterDeploymentIssueCanServerPortsExhausted {
+                    range_start: Some(range_start.into()),
+                    required,
+                    range_end: Some(range_end.into()),
+                }),
+            Issue::IncompatibleCanFd { fd_devices, non_fd_devices } =>
+                cluster_deployment_issue::Inner::IncompatibleCanFd(ClusterDeploymentIssueIncompatibleCanFd {
+                    fd_devices: fd_devices.into_iter().map(Into::into).collect(),
+                    non_fd_devices: non_fd_devices.into_iter().map(Into::into).collect(),
+                }),
+            Issue::VpnUnreachable { peer_id, cause } =>
+                cluster_deployment_issue::Inner::VpnUnreachable(ClusterDeploymentIssueVpnUnreachable {
+                    peer_id: Some(peer_id.into()),
+                    cause,
+                }),
+            Issue::VpnProtocolUnsupported { protocol } =>
+                cluster_deployment_issue::Inner::VpnProtocolUnsupported(ClusterDeploymentIssueVpnProtocolUnsupported {
+                    protocol,
+                }),
+        };
+        Self {
+            inner: Some(inner),
+        }
+    }
+}
+
+impl TryFrom<ClusterDeploymentIssue> for crate::cluster::validation::ClusterDeploymentIssue {
+    type Error = ConversionError;
+
+    fn try_from(value: ClusterDeploymentIssue) -> Result<Self, Self::Error> {
+        use crate::cluster::validation::ClusterDeploymentIssue as Issue;
+        type ErrorBuilder = ConversionErrorBuilder<ClusterDeploymentIssue, crate::cluster::validation::ClusterDeploymentIssue>;
+
+        let inner = value.inner
+            .ok_or(ErrorBuilder::field_not_set("inner"))?;
+
+        let issue = match inner {
+            cluster_deployment_issue::Inner::PeerForDeviceNotFound(issue) => Issue::PeerForDeviceNotFound {
+                device_id: issue.device_id.ok_or(ErrorBuilder::field_not_set("device_id"))?.try_into()?,
+            },
+            cluster_deployment_issue::Inner::LeaderNotFound(issue) => Issue::LeaderNotFound {
+                leader_id: issue.leader_id.ok_or(ErrorBuilder::field_not_set("leader_id"))?.try_into()?,
+            },
+            cluster_deployment_issue::Inner::PeerNotUp(issue) => Issue::PeerNotUp {
+                peer_id: issue.peer_id.ok_or(ErrorBuilder::field_not_set("peer_id"))?.try_into()?,
+            },
+            cluster_deployment_issue::Inner::PeerInOtherCluster(issue) => Issue::PeerInOtherCluster {
+                peer_id: issue.peer_id.ok_or(ErrorBuilder::field_not_set("peer_id"))?.try_into()?,
+                other_cluster_id: issue.other_cluster_id.ok_or(ErrorBuilder::field_not_set("other_cluster_id"))?.try_into()?,
+            },
+            cluster_deployment_issue::Inner::CanServerPortsExhausted(issue) => Issue::CanServerPortsExhausted {
+                range_start: issue.range_start.ok_or(ErrorBuilder::field_not_set("range_start"))?.try_into()?,
+                range_end: issue.range_end.ok_or(ErrorBuilder::field_not_set("range_end"))?.try_into()?,
+                required: issue.required,
+            },
+            cluster_deployment_issue::Inner::IncompatibleCanFd(issue) => Issue::IncompatibleCanFd {
+                fd_devices: issue.fd_devices.into_iter().map(TryInto::try_into).collect::<Result<_, _>>()?,
+                non_fd_devices: issue.non_fd_devices.into_iter().map(TryInto::try_into).collect::<Result<_, _>>()?,
+            },
+            cluster_deployment_issue::Inner::VpnUnreachable(issue) => Issue::VpnUnreachable {
+                peer_id: issue.peer_id.ok_or(ErrorBuilder::field_not_set("peer_id"))?.try_into()?,
+                cause: issue.cause,
+            },
+            cluster_deployment_issue::Inner::VpnProtocolUnsupported(issue) => Issue::VpnProtocolUnsupported {
+                protocol: issue.protocol,
+            },
+        };
+        Ok(issue)
+    }
+}
+
 #[cfg(test)]
 #[allow(non_snake_case)]
 mod test {
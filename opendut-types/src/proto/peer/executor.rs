@@ -266,9 +266,15 @@ impl TryFrom<ContainerDevice> for crate::peer::executor::ContainerDevice {
 impl From<crate::peer::executor::ContainerEnvironmentVariable> for ContainerEnvironmentVariable {
     fn from(value: crate::peer::executor::ContainerEnvironmentVariable) -> Self {
         let (name, value) = value.into();
+        let (value, secret) = match value {
+            crate::peer::executor::ContainerEnvironmentVariableValue::Plain(value) => (Some(value), false),
+            crate::peer::executor::ContainerEnvironmentVariableValue::Secret(value) => (Some(value), true),
+            crate::peer::executor::ContainerEnvironmentVariableValue::Redacted => (None, true),
+        };
         Self {
             name,
-            value
+            value,
+            secret,
         }
     }
 }
@@ -278,12 +284,32 @@ impl TryFrom<ContainerEnvironmentVariable> for crate::peer::executor::ContainerE
 
     fn try_from(value: ContainerEnvironmentVariable) -> Result<Self, Self::Error> {
         type ErrorBuilder = ConversionErrorBuilder<ContainerEnvironmentVariable, crate::peer::executor::ContainerEnvironmentVariable>;
-        
-        crate::peer::executor::ContainerEnvironmentVariable::new(value.name, value.value)
+
+        let env_value = match (value.value, value.secret) {
+            (Some(env_value), true) => crate::peer::executor::ContainerEnvironmentVariableValue::Secret(env_value),
+            (None, true) => crate::peer::executor::ContainerEnvironmentVariableValue::Redacted,
+            (env_value, false) => crate::peer::executor::ContainerEnvironmentVariableValue::Plain(env_value.unwrap_or_default()),
+        };
+
+        crate::peer::executor::ContainerEnvironmentVariable::with_value(value.name, env_value)
             .map_err(|cause| ErrorBuilder::message(cause.to_string()))
     }
 }
 
+impl std::fmt::Debug for ContainerEnvironmentVariable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match (&self.value, self.secret) {
+            (Some(_), true) => Some(crate::peer::executor::ContainerEnvironmentVariableValue::REDACTED),
+            (value, _) => value.as_deref(),
+        };
+        f.debug_struct("ContainerEnvironmentVariable")
+            .field("name", &self.name)
+            .field("value", &value)
+            .field("secret", &self.secret)
+            .finish()
+    }
+}
+
 impl From<crate::peer::executor::ContainerPortSpec> for ContainerPortSpec {
     fn from(value: crate::peer::executor::ContainerPortSpec) -> Self {
         Self {
@@ -118,6 +118,7 @@ impl From<crate::peer::PeerDescriptor> for PeerDescriptor {
             network_configuration: Some(value.network_configuration.into()),
             topology: Some(value.topology.into()),
             executors: Some(value.executors.into()),
+            revision: value.revision,
         }
     }
 }
@@ -159,6 +160,7 @@ impl TryFrom<PeerDescriptor> for crate::peer::PeerDescriptor {
             network_configuration,
             topology,
             executors,
+            revision: value.revision,
         })
     }
 }
@@ -404,6 +406,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn A_ContainerEnvironmentVariable_should_be_convertable_to_its_proto_and_vice_versa() -> Result<()> {
+
+        for native in [
+            crate::peer::executor::ContainerEnvironmentVariable::new("PLAIN", "value")?,
+            crate::peer::executor::ContainerEnvironmentVariable::new_secret("SECRET", "value")?,
+            crate::peer::executor::ContainerEnvironmentVariable::new_secret("REDACTED", "value")?.redacted(),
+        ] {
+            let proto: executor::ContainerEnvironmentVariable = Clone::clone(&native).into();
+
+            assert_that!(
+                crate::peer::executor::ContainerEnvironmentVariable::try_from(Clone::clone(&proto)),
+                ok(eq(native))
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn A_secret_ContainerEnvironmentVariable_should_not_reveal_its_value_when_debug_formatted() -> Result<()> {
+
+        let native = crate::peer::executor::ContainerEnvironmentVariable::new_secret("PASSWORD", "hunter2")?;
+        let proto: executor::ContainerEnvironmentVariable = Clone::clone(&native).into();
+
+        assert_that!(format!("{native:?}"), not(contains_substring("hunter2")));
+        assert_that!(format!("{proto:?}"), not(contains_substring("hunter2")));
+        assert_that!(native.to_string(), eq("PASSWORD=********"));
+
+        Ok(())
+    }
+
     #[test]
     fn A_PeerState_should_be_convertable_to_its_proto_and_vice_versa() -> Result<()> {
 
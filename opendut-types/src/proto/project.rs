@@ -0,0 +1,72 @@
+use crate::proto::{ConversionError, ConversionErrorBuilder};
+
+include!(concat!(env!("OUT_DIR"), "/opendut.types.project.rs"));
+
+impl From<crate::project::ProjectId> for ProjectId {
+    fn from(value: crate::project::ProjectId) -> Self {
+        Self {
+            uuid: Some(value.0.into())
+        }
+    }
+}
+
+impl TryFrom<ProjectId> for crate::project::ProjectId {
+    type Error = ConversionError;
+
+    fn try_from(value: ProjectId) -> Result<Self, Self::Error> {
+        type ErrorBuilder = ConversionErrorBuilder<ProjectId, crate::project::ProjectId>;
+
+        value.uuid
+            .ok_or(ErrorBuilder::field_not_set("uuid"))
+            .map(|uuid| Self(uuid.into()))
+    }
+}
+
+impl From<crate::project::ProjectName> for ProjectName {
+    fn from(value: crate::project::ProjectName) -> Self {
+        Self {
+            value: value.0
+        }
+    }
+}
+
+impl TryFrom<ProjectName> for crate::project::ProjectName {
+    type Error = ConversionError;
+
+    fn try_from(value: ProjectName) -> Result<Self, Self::Error> {
+        type ErrorBuilder = ConversionErrorBuilder<ProjectName, crate::project::ProjectName>;
+
+        crate::project::ProjectName::try_from(value.value)
+            .map_err(|cause| ErrorBuilder::message(cause.to_string()))
+    }
+}
+
+impl From<crate::project::ProjectDescriptor> for ProjectDescriptor {
+    fn from(descriptor: crate::project::ProjectDescriptor) -> Self {
+        Self {
+            id: Some(descriptor.id.into()),
+            name: Some(descriptor.name.into()),
+        }
+    }
+}
+
+impl TryFrom<ProjectDescriptor> for crate::project::ProjectDescriptor {
+    type Error = ConversionError;
+
+    fn try_from(descriptor: ProjectDescriptor) -> Result<Self, Self::Error> {
+        type ErrorBuilder = ConversionErrorBuilder<ProjectDescriptor, crate::project::ProjectDescriptor>;
+
+        let id: crate::project::ProjectId = descriptor.id
+            .ok_or(ErrorBuilder::field_not_set("id"))?
+            .try_into()?;
+
+        let name: crate::project::ProjectName = descriptor.name
+            .ok_or(ErrorBuilder::field_not_set("name"))?
+            .try_into()?;
+
+        Ok(Self {
+            id,
+            name,
+        })
+    }
+}
@@ -22,6 +22,27 @@ impl From<crate::vpn::VpnPeerConfiguration> for VpnPeerConfig {
                     ))
                 }
             }
+            crate::vpn::VpnPeerConfiguration::WireGuard { private_key, address, prefix_length, listen_port } => {
+                VpnPeerConfig {
+                    config: Some(vpn_peer_config::Config::Wireguard(
+                        VpnPeerConfigWireGuard {
+                            private_key: Some(private_key.into()),
+                            address: Some(address.into()),
+                            prefix_length: u32::from(prefix_length),
+                            listen_port: Some(listen_port.into()),
+                        }
+                    ))
+                }
+            }
+            crate::vpn::VpnPeerConfiguration::Loopback { address } => {
+                VpnPeerConfig {
+                    config: Some(vpn_peer_config::Config::Loopback(
+                        VpnPeerConfigLoopback {
+                            address: Some(address.into()),
+                        }
+                    ))
+                }
+            }
         }
     }
 }
@@ -52,6 +73,35 @@ impl TryFrom<VpnPeerConfig> for crate::vpn::VpnPeerConfiguration {
                     setup_key,
                 }
             },
+            vpn_peer_config::Config::Wireguard(config) => {
+                let VpnPeerConfigWireGuard { private_key, address, prefix_length, listen_port } = config;
+                let private_key = private_key
+                    .ok_or(ErrorBuilder::field_not_set("private_key"))?
+                    .try_into()?;
+                let address = address
+                    .ok_or(ErrorBuilder::field_not_set("address"))?
+                    .try_into()?;
+                let prefix_length = u8::try_from(prefix_length)
+                    .map_err(|_| ErrorBuilder::message("Prefix length is out of range"))?;
+                let listen_port = listen_port
+                    .ok_or(ErrorBuilder::field_not_set("listen_port"))?
+                    .try_into()?;
+                crate::vpn::VpnPeerConfiguration::WireGuard {
+                    private_key,
+                    address,
+                    prefix_length,
+                    listen_port,
+                }
+            },
+            vpn_peer_config::Config::Loopback(config) => {
+                let VpnPeerConfigLoopback { address } = config;
+                let address = address
+                    .ok_or(ErrorBuilder::field_not_set("address"))?
+                    .try_into()?;
+                crate::vpn::VpnPeerConfiguration::Loopback {
+                    address,
+                }
+            },
         };
 
         Ok(result)
@@ -77,3 +127,244 @@ impl TryFrom<SetupKey> for crate::vpn::netbird::SetupKey {
         Ok(result)
     }
 }
+
+impl From<crate::vpn::wireguard::PrivateKey> for WireGuardPrivateKey {
+    fn from(value: crate::vpn::wireguard::PrivateKey) -> Self {
+        Self { value: value.0.to_vec() }
+    }
+}
+
+impl TryFrom<WireGuardPrivateKey> for crate::vpn::wireguard::PrivateKey {
+    type Error = ConversionError;
+
+    fn try_from(value: WireGuardPrivateKey) -> Result<Self, Self::Error> {
+        type ErrorBuilder = ConversionErrorBuilder<WireGuardPrivateKey, crate::vpn::wireguard::PrivateKey>;
+
+        let key = <[u8; crate::vpn::wireguard::KEY_LENGTH]>::try_from(value.value)
+            .map_err(|_| ErrorBuilder::message("Private key has an invalid length"))?;
+        Ok(crate::vpn::wireguard::PrivateKey(key))
+    }
+}
+
+impl std::fmt::Debug for WireGuardPrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WireGuardPrivateKey")
+            .field("value", &crate::vpn::wireguard::PrivateKey::REDACTED)
+            .finish()
+    }
+}
+
+impl From<crate::vpn::wireguard::PublicKey> for WireGuardPublicKey {
+    fn from(value: crate::vpn::wireguard::PublicKey) -> Self {
+        Self { value: value.0.to_vec() }
+    }
+}
+
+impl TryFrom<WireGuardPublicKey> for crate::vpn::wireguard::PublicKey {
+    type Error = ConversionError;
+
+    fn try_from(value: WireGuardPublicKey) -> Result<Self, Self::Error> {
+        type ErrorBuilder = ConversionErrorBuilder<WireGuardPublicKey, crate::vpn::wireguard::PublicKey>;
+
+        let key = <[u8; crate::vpn::wireguard::KEY_LENGTH]>::try_from(value.value)
+            .map_err(|_| ErrorBuilder::message("Public key has an invalid length"))?;
+        Ok(crate::vpn::wireguard::PublicKey(key))
+    }
+}
+
+impl From<crate::vpn::wireguard::WireGuardPeer> for WireGuardPeer {
+    fn from(value: crate::vpn::wireguard::WireGuardPeer) -> Self {
+        Self {
+            public_key: Some(value.public_key.into()),
+            endpoint_address: Some(value.endpoint.ip().into()),
+            endpoint_port: Some(crate::util::Port(value.endpoint.port()).into()),
+        }
+    }
+}
+
+impl TryFrom<WireGuardPeer> for crate::vpn::wireguard::WireGuardPeer {
+    type Error = ConversionError;
+
+    fn try_from(value: WireGuardPeer) -> Result<Self, Self::Error> {
+        type ErrorBuilder = ConversionErrorBuilder<WireGuardPeer, crate::vpn::wireguard::WireGuardPeer>;
+
+        let public_key = value.public_key
+            .ok_or(ErrorBuilder::field_not_set("public_key"))?
+            .try_into()?;
+        let endpoint_address: std::net::IpAddr = value.endpoint_address
+            .ok_or(ErrorBuilder::field_not_set("endpoint_address"))?
+            .try_into()?;
+        let endpoint_port: crate::util::Port = value.endpoint_port
+            .ok_or(ErrorBuilder::field_not_set("endpoint_port"))?
+            .try_into()?;
+
+        Ok(crate::vpn::wireguard::WireGuardPeer {
+            public_key,
+            endpoint: std::net::SocketAddr::new(endpoint_address, endpoint_port.0),
+        })
+    }
+}
+
+impl From<crate::vpn::health::VpnHealth> for VpnHealth {
+    fn from(value: crate::vpn::health::VpnHealth) -> Self {
+        Self {
+            connected: value.connected,
+            address: value.address.map(From::from),
+            links: value.links.into_iter().map(From::from).collect(),
+        }
+    }
+}
+
+impl TryFrom<VpnHealth> for crate::vpn::health::VpnHealth {
+    type Error = ConversionError;
+
+    fn try_from(value: VpnHealth) -> Result<Self, Self::Error> {
+        let address = value.address
+            .map(TryFrom::try_from)
+            .transpose()?;
+        let links = value.links.into_iter()
+            .map(TryFrom::try_from)
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            connected: value.connected,
+            address,
+            links,
+        })
+    }
+}
+
+impl From<crate::vpn::health::VpnLink> for VpnLink {
+    fn from(value: crate::vpn::health::VpnLink) -> Self {
+        Self {
+            remote_address: Some(value.remote_address.into()),
+            connected: value.connected,
+            relayed: value.relayed,
+            latency_us: value.latency.map(|latency| u64::try_from(latency.as_micros()).unwrap_or(u64::MAX)),
+        }
+    }
+}
+
+impl TryFrom<VpnLink> for crate::vpn::health::VpnLink {
+    type Error = ConversionError;
+
+    fn try_from(value: VpnLink) -> Result<Self, Self::Error> {
+        type ErrorBuilder = ConversionErrorBuilder<VpnLink, crate::vpn::health::VpnLink>;
+
+        let remote_address = value.remote_address
+            .ok_or(ErrorBuilder::field_not_set("remote_address"))?
+            .try_into()?;
+        Ok(Self {
+            remote_address,
+            connected: value.connected,
+            relayed: value.relayed,
+            latency: value.latency_us.map(std::time::Duration::from_micros),
+        })
+    }
+}
+
+impl From<crate::vpn::reconciliation::VpnReconciliationReport> for VpnReconciliationReport {
+    fn from(value: crate::vpn::reconciliation::VpnReconciliationReport) -> Self {
+        Self {
+            dry_run: value.dry_run,
+            drifts: value.drifts.into_iter().map(From::from).collect(),
+        }
+    }
+}
+
+impl TryFrom<VpnReconciliationReport> for crate::vpn::reconciliation::VpnReconciliationReport {
+    type Error = ConversionError;
+
+    fn try_from(value: VpnReconciliationReport) -> Result<Self, Self::Error> {
+        let drifts = value.drifts.into_iter()
+            .map(TryFrom::try_from)
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            dry_run: value.dry_run,
+            drifts,
+        })
+    }
+}
+
+impl From<crate::vpn::reconciliation::VpnDrift> for VpnDrift {
+    fn from(value: crate::vpn::reconciliation::VpnDrift) -> Self {
+        Self {
+            kind: Some(value.kind.into()),
+            resource: value.resource,
+            action: value.action,
+            outcome: Some(value.outcome.into()),
+        }
+    }
+}
+
+impl TryFrom<VpnDrift> for crate::vpn::reconciliation::VpnDrift {
+    type Error = ConversionError;
+
+    fn try_from(value: VpnDrift) -> Result<Self, Self::Error> {
+        type ErrorBuilder = ConversionErrorBuilder<VpnDrift, crate::vpn::reconciliation::VpnDrift>;
+
+        let kind = value.kind
+            .ok_or(ErrorBuilder::field_not_set("kind"))?
+            .try_into()?;
+        let outcome = value.outcome
+            .ok_or(ErrorBuilder::field_not_set("outcome"))?
+            .try_into()?;
+        Ok(Self {
+            kind,
+            resource: value.resource,
+            action: value.action,
+            outcome,
+        })
+    }
+}
+
+impl From<crate::vpn::reconciliation::VpnDriftKind> for VpnDriftKind {
+    fn from(value: crate::vpn::reconciliation::VpnDriftKind) -> Self {
+        let inner = match value {
+            crate::vpn::reconciliation::VpnDriftKind::Orphaned => vpn_drift_kind::Inner::Orphaned(VpnDriftKindOrphaned {}),
+            crate::vpn::reconciliation::VpnDriftKind::Missing => vpn_drift_kind::Inner::Missing(VpnDriftKindMissing {}),
+            crate::vpn::reconciliation::VpnDriftKind::Diverged => vpn_drift_kind::Inner::Diverged(VpnDriftKindDiverged {}),
+        };
+        Self { inner: Some(inner) }
+    }
+}
+
+impl TryFrom<VpnDriftKind> for crate::vpn::reconciliation::VpnDriftKind {
+    type Error = ConversionError;
+
+    fn try_from(value: VpnDriftKind) -> Result<Self, Self::Error> {
+        type ErrorBuilder = ConversionErrorBuilder<VpnDriftKind, crate::vpn::reconciliation::VpnDriftKind>;
+
+        let result = match value.inner.ok_or(ErrorBuilder::field_not_set("inner"))? {
+            vpn_drift_kind::Inner::Orphaned(_) => crate::vpn::reconciliation::VpnDriftKind::Orphaned,
+            vpn_drift_kind::Inner::Missing(_) => crate::vpn::reconciliation::VpnDriftKind::Missing,
+            vpn_drift_kind::Inner::Diverged(_) => crate::vpn::reconciliation::VpnDriftKind::Diverged,
+        };
+        Ok(result)
+    }
+}
+
+impl From<crate::vpn::reconciliation::VpnDriftOutcome> for VpnDriftOutcome {
+    fn from(value: crate::vpn::reconciliation::VpnDriftOutcome) -> Self {
+        let inner = match value {
+            crate::vpn::reconciliation::VpnDriftOutcome::Planned => vpn_drift_outcome::Inner::Planned(VpnDriftOutcomePlanned {}),
+            crate::vpn::reconciliation::VpnDriftOutcome::Repaired => vpn_drift_outcome::Inner::Repaired(VpnDriftOutcomeRepaired {}),
+            crate::vpn::reconciliation::VpnDriftOutcome::Failed { cause } => vpn_drift_outcome::Inner::Failed(VpnDriftOutcomeFailed { cause }),
+        };
+        Self { inner: Some(inner) }
+    }
+}
+
+impl TryFrom<VpnDriftOutcome> for crate::vpn::reconciliation::VpnDriftOutcome {
+    type Error = ConversionError;
+
+    fn try_from(value: VpnDriftOutcome) -> Result<Self, Self::Error> {
+        type ErrorBuilder = ConversionErrorBuilder<VpnDriftOutcome, crate::vpn::reconciliation::VpnDriftOutcome>;
+
+        let result = match value.inner.ok_or(ErrorBuilder::field_not_set("inner"))? {
+            vpn_drift_outcome::Inner::Planned(_) => crate::vpn::reconciliation::VpnDriftOutcome::Planned,
+            vpn_drift_outcome::Inner::Repaired(_) => crate::vpn::reconciliation::VpnDriftOutcome::Repaired,
+            vpn_drift_outcome::Inner::Failed(failed) => crate::vpn::reconciliation::VpnDriftOutcome::Failed { cause: failed.cause },
+        };
+        Ok(result)
+    }
+}
@@ -5,6 +5,8 @@ use std::str::FromStr;
 use crate::util::net::NetworkInterfaceDescriptor;
 use serde::{Deserialize, Serialize};
 
+pub mod query;
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Topology {
     pub devices: Vec<DeviceDescriptor>,
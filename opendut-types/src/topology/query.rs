@@ -0,0 +1,610 @@
+use std::fmt;
+use std::ops::Not;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cluster::ClusterConfiguration;
+use crate::peer::PeerDescriptor;
+use crate::peer::state::PeerState;
+use crate::ShortName;
+use crate::topology::DeviceDescriptor;
+use crate::util::net::NetworkInterfaceConfiguration;
+
+/// A query selecting devices by their own properties and the properties of their peer,
+/// for example `tag=powertrain and type=can and peer.state=available`.
+///
+/// A query combines predicates of the form `<field>=<value>` or `<field>!=<value>` with `and`, `or`, `not` and parentheses.
+/// Values are compared case-insensitively and may contain `*` as a wildcard. Values containing spaces have to be quoted.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct DeviceQuery {
+    source: String,
+    expression: Expression,
+}
+
+/// Everything known about a device, which a [`DeviceQuery`] can refer to.
+pub struct DeviceQueryContext<'a> {
+    pub device: &'a DeviceDescriptor,
+    pub peer: &'a PeerDescriptor,
+    pub peer_state: &'a PeerState,
+    /// The clusters, which the device is configured for.
+    pub clusters: Vec<&'a ClusterConfiguration>,
+}
+
+impl DeviceQuery {
+    /// Maximum number of characters of a query.
+    pub const MAX_LENGTH: usize = 1024;
+    /// Maximum number of parentheses and `not`s, which may be nested within each other.
+    pub const MAX_NESTING_DEPTH: usize = 32;
+
+    pub fn matches(&self, context: &DeviceQueryContext) -> bool {
+        self.expression.matches(context)
+    }
+
+    pub fn value(&self) -> &str {
+        &self.source
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Expression {
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+    Not(Box<Expression>),
+    Predicate {
+        field: Field,
+        value: String,
+        negated: bool,
+    },
+}
+
+impl Expression {
+    /// Detaches the sub-expressions, so that dropping this expression does not recurse into them.
+    fn take_children(&mut self, children: &mut Vec<Expression>) {
+        let leaf = || Box::new(Expression::Predicate { field: Field::Name, value: String::new(), negated: false });
+        match self {
+            Expression::And(left, right) | Expression::Or(left, right) => {
+                children.push(*std::mem::replace(left, leaf()));
+                children.push(*std::mem::replace(right, leaf()));
+            }
+            Expression::Not(inner) => children.push(*std::mem::replace(inner, leaf())),
+            Expression::Predicate { .. } => {}
+        }
+    }
+
+    fn matches(&self, context: &DeviceQueryContext) -> bool {
+        match self {
+            Expression::And(left, right) => left.matches(context) && right.matches(context),
+            Expression::Or(left, right) => left.matches(context) || right.matches(context),
+            Expression::Not(inner) => inner.matches(context).not(),
+            Expression::Predicate { field, value, negated } => field.matches(value, context) != *negated,
+        }
+    }
+}
+
+impl Drop for Expression {
+    fn drop(&mut self) {
+        let mut children = Vec::new();
+        self.take_children(&mut children);
+        while let Some(mut child) = children.pop() {
+            child.take_children(&mut children);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Field {
+    Name,
+    Tag,
+    Type,
+    Fd,
+    Interface,
+    PeerName,
+    PeerLocation,
+    PeerState,
+    Cluster,
+}
+
+impl Field {
+    const ALL: [(&'static str, Field); 9] = [
+        ("name", Field::Name),
+        ("tag", Field::Tag),
+        ("type", Field::Type),
+        ("fd", Field::Fd),
+        ("interface", Field::Interface),
+        ("peer.name", Field::PeerName),
+        ("peer.location", Field::PeerLocation),
+        ("peer.state", Field::PeerState),
+        ("cluster", Field::Cluster),
+    ];
+
+    const TYPES: [&'static str; 2] = ["ethernet", "can"];
+    const BOOLEANS: [&'static str; 2] = ["true", "false"];
+    const PEER_STATES: [&'static str; 7] = ["down", "up", "available", "blocked", "deploying", "member", "undeploying"];
+
+    fn parse(name: &str) -> Option<Field> {
+        Field::ALL.iter()
+            .find(|(field_name, _)| field_name.eq_ignore_ascii_case(name))
+            .map(|(_, field)| *field)
+    }
+
+    fn name(&self) -> &'static str {
+        Field::ALL.iter()
+            .find(|(_, field)| field == self)
+            .map(|(name, _)| *name)
+            .expect("Every field should have a name.")
+    }
+
+    /// The values allowed for this field, if it does not accept arbitrary patterns.
+    fn allowed_values(&self) -> Option<&'static [&'static str]> {
+        match self {
+            Field::Type => Some(&Field::TYPES),
+            Field::Fd => Some(&Field::BOOLEANS),
+            Field::PeerState => Some(&Field::PEER_STATES),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, value: &str, context: &DeviceQueryContext) -> bool {
+        let device = context.device;
+        match self {
+            Field::Name => matches_pattern(value, device.name.value()),
+            Field::Tag => device.tags.iter().any(|tag| matches_pattern(value, tag.value())),
+            Field::Type => match device.interface.configuration {
+                NetworkInterfaceConfiguration::Ethernet => value == "ethernet",
+                NetworkInterfaceConfiguration::Can { .. } => value == "can",
+            },
+            Field::Fd => {
+                let fd = matches!(device.interface.configuration, NetworkInterfaceConfiguration::Can { fd: true, .. });
+                value == fd.to_string()
+            }
+            Field::Interface => matches_pattern(value, &device.interface.name.name()),
+            Field::PeerName => matches_pattern(value, &context.peer.name.to_string()),
+            Field::PeerLocation => matches_pattern(value, &context.peer.location.clone().unwrap_or_default().to_string()),
+            Field::PeerState => match (value, context.peer_state) {
+                ("up", PeerState::Up { .. }) => true,
+                ("blocked", PeerState::Up { inner: crate::peer::state::PeerUpState::Blocked(_), .. }) => true,
+                (value, state) => value.eq_ignore_ascii_case(state.short_name()),
+            },
+            Field::Cluster => {
+                if value == "none" {
+                    context.clusters.is_empty()
+                } else {
+                    context.clusters.iter().any(|cluster| {
+                        matches_pattern(value, &cluster.name.to_string())
+                            || matches_pattern(value, &cluster.id.to_string())
+                    })
+                }
+            }
+        }
+    }
+}
+
+/// Matches the text case-insensitively against the pattern, in which `*` matches any sequence of characters.
+fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.to_lowercase().chars().collect::<Vec<_>>();
+    let text = text.to_lowercase().chars().collect::<Vec<_>>();
+
+    let (mut pattern_index, mut text_index) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while text_index < text.len() {
+        if pattern_index < pattern.len() && pattern[pattern_index] == '*' {
+            backtrack = Some((pattern_index, text_index));
+            pattern_index += 1;
+        }
+        else if pattern_index < pattern.len() && pattern[pattern_index] == text[text_index] {
+            pattern_index += 1;
+            text_index += 1;
+        }
+        else if let Some((star_index, star_text_index)) = backtrack {
+            pattern_index = star_index + 1;
+            text_index = star_text_index + 1;
+            backtrack = Some((star_index, star_text_index + 1));
+        }
+        else {
+            return false;
+        }
+    }
+    pattern[pattern_index..].iter().all(|character| *character == '*')
+}
+
+#[derive(thiserror::Error, Clone, Debug, Eq, PartialEq)]
+pub enum IllegalDeviceQuery {
+    #[error("Device query is empty.")]
+    Empty,
+    #[error("Device query '{query}' ends unexpectedly. Expected {expected}.")]
+    UnexpectedEnd {
+        query: String,
+        expected: String,
+    },
+    #[error("Device query '{query}' contains unexpected '{token}'. Expected {expected}.")]
+    UnexpectedToken {
+        query: String,
+        token: String,
+        expected: String,
+    },
+    #[error("Device query '{query}' contains the unclosed quote at position {position}.")]
+    UnclosedQuote {
+        query: String,
+        position: usize,
+    },
+    #[error("Device query '{query}' refers to the unknown field '{field}'. Known fields are: {}", Field::ALL.map(|(name, _)| name).join(", "))]
+    UnknownField {
+        query: String,
+        field: String,
+    },
+    #[error("Device query '{query}' contains the illegal value '{value}' for field '{field}'. Allowed values are: {allowed}")]
+    IllegalValue {
+        query: String,
+        field: String,
+        value: String,
+        allowed: String,
+    },
+    #[error("Device query is {length} characters long, but at most {limit} characters are allowed.")]
+    TooLong {
+        length: usize,
+        limit: usize,
+    },
+    #[error("Device query '{query}' nests parentheses and 'not' deeper than {limit} levels.")]
+    TooDeeplyNested {
+        query: String,
+        limit: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Equals,
+    NotEquals,
+    LeftParenthesis,
+    RightParenthesis,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "{word}"),
+            Token::Quoted(value) => write!(f, "\"{value}\""),
+            Token::Equals => write!(f, "="),
+            Token::NotEquals => write!(f, "!="),
+            Token::LeftParenthesis => write!(f, "("),
+            Token::RightParenthesis => write!(f, ")"),
+        }
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, IllegalDeviceQuery> {
+    let mut tokens = Vec::new();
+    let mut characters = query.char_indices().peekable();
+
+    while let Some((position, character)) = characters.next() {
+        match character {
+            character if character.is_whitespace() => {}
+            '(' => tokens.push(Token::LeftParenthesis),
+            ')' => tokens.push(Token::RightParenthesis),
+            '=' => tokens.push(Token::Equals),
+            '!' if matches!(characters.peek(), Some((_, '='))) => {
+                characters.next();
+                tokens.push(Token::NotEquals);
+            }
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match characters.next() {
+                        Some((_, '"')) => break,
+                        Some((_, character)) => value.push(character),
+                        None => return Err(IllegalDeviceQuery::UnclosedQuote { query: query.to_owned(), position }),
+                    }
+                }
+                tokens.push(Token::Quoted(value));
+            }
+            character => {
+                let mut word = String::from(character);
+                while let Some((_, next)) = characters.peek() {
+                    if next.is_whitespace() || matches!(next, '(' | ')' | '=' | '!' | '"') {
+                        break;
+                    }
+                    word.push(*next);
+                    characters.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    query: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+
+    fn parse(mut self) -> Result<Expression, IllegalDeviceQuery> {
+        if self.tokens.is_empty() {
+            return Err(IllegalDeviceQuery::Empty);
+        }
+        let expression = self.parse_or()?;
+        match self.tokens.get(self.position) {
+            None => Ok(expression),
+            Some(token) => Err(self.unexpected_token(token, "'and', 'or' or the end of the query")),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expression, IllegalDeviceQuery> {
+        let mut expression = self.parse_and()?;
+        while self.next_is_keyword("or") {
+            self.position += 1;
+            expression = Expression::Or(Box::new(expression), Box::new(self.parse_and()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_and(&mut self) -> Result<Expression, IllegalDeviceQuery> {
+        let mut expression = self.parse_unary()?;
+        while self.next_is_keyword("and") {
+            self.position += 1;
+            expression = Expression::And(Box::new(expression), Box::new(self.parse_unary()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, IllegalDeviceQuery> {
+        if self.next_is_keyword("not") {
+            self.position += 1;
+            let inner = self.nested(Self::parse_unary)?;
+            return Ok(Expression::Not(Box::new(inner)));
+        }
+        match self.next("a field, 'not' or '('")? {
+            Token::LeftParenthesis => {
+                let expression = self.nested(Self::parse_or)?;
+                match self.next("')'")? {
+                    Token::RightParenthesis => Ok(expression),
+                    token => Err(self.unexpected_token(&token, "')'")),
+                }
+            }
+            Token::Word(field) => self.parse_predicate(field),
+            token => Err(self.unexpected_token(&token, "a field, 'not' or '('")),
+        }
+    }
+
+    fn parse_predicate(&mut self, field_name: String) -> Result<Expression, IllegalDeviceQuery> {
+        let field = Field::parse(&field_name)
+            .ok_or_else(|| IllegalDeviceQuery::UnknownField { query: self.query.to_owned(), field: field_name })?;
+
+        let negated = match self.next("'=' or '!='")? {
+            Token::Equals => false,
+            Token::NotEquals => true,
+            token => return Err(self.unexpected_token(&token, "'=' or '!='")),
+        };
+
+        let value = match self.next("a value")? {
+            Token::Word(value) | Token::Quoted(value) => value.to_lowercase(),
+            token => return Err(self.unexpected_token(&token, "a value")),
+        };
+
+        if let Some(allowed) = field.allowed_values() {
+            if allowed.contains(&value.as_str()).not() {
+                return Err(IllegalDeviceQuery::IllegalValue {
+                    query: self.query.to_owned(),
+                    field: field.name().to_owned(),
+                    value,
+                    allowed: allowed.join(", "),
+                });
+            }
+        }
+
+        Ok(Expression::Predicate { field, value, negated })
+    }
+
+    /// Parses a nested expression, unless this exceeds the maximum nesting depth.
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Expression, IllegalDeviceQuery>) -> Result<Expression, IllegalDeviceQuery> {
+        if self.depth >= DeviceQuery::MAX_NESTING_DEPTH {
+            return Err(IllegalDeviceQuery::TooDeeplyNested { query: self.query.to_owned(), limit: DeviceQuery::MAX_NESTING_DEPTH });
+        }
+        self.depth += 1;
+        let expression = parse(self);
+        self.depth -= 1;
+        expression
+    }
+
+    fn next_is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.tokens.get(self.position), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn next(&mut self, expected: &str) -> Result<Token, IllegalDeviceQuery> {
+        let token = self.tokens.get(self.position).cloned()
+            .ok_or_else(|| IllegalDeviceQuery::UnexpectedEnd { query: self.query.to_owned(), expected: expected.to_owned() })?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn unexpected_token(&self, token: &Token, expected: &str) -> IllegalDeviceQuery {
+        IllegalDeviceQuery::UnexpectedToken {
+            query: self.query.to_owned(),
+            token: token.to_string(),
+            expected: expected.to_owned(),
+        }
+    }
+}
+
+impl FromStr for DeviceQuery {
+    type Err = IllegalDeviceQuery;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        let query = query.trim();
+        let length = query.chars().count();
+        if length > DeviceQuery::MAX_LENGTH {
+            return Err(IllegalDeviceQuery::TooLong { length, limit: DeviceQuery::MAX_LENGTH });
+        }
+        let parser = Parser {
+            query,
+            tokens: tokenize(query)?,
+            position: 0,
+            depth: 0,
+        };
+        let expression = parser.parse()?;
+        Ok(DeviceQuery { source: query.to_owned(), expression })
+    }
+}
+
+impl TryFrom<String> for DeviceQuery {
+    type Error = IllegalDeviceQuery;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        DeviceQuery::from_str(&value)
+    }
+}
+
+impl TryFrom<&str> for DeviceQuery {
+    type Error = IllegalDeviceQuery;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        DeviceQuery::from_str(value)
+    }
+}
+
+impl From<DeviceQuery> for String {
+    fn from(value: DeviceQuery) -> Self {
+        value.source
+    }
+}
+
+impl fmt::Display for DeviceQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use googletest::prelude::*;
+
+    use crate::cluster::{ClusterId, ClusterName};
+    use crate::peer::{PeerId, PeerLocation, PeerName, PeerNetworkConfiguration};
+    use crate::peer::executor::ExecutorDescriptors;
+    use crate::peer::state::PeerUpState;
+    use crate::topology::{DeviceId, DeviceName, DeviceTag, Topology};
+    use crate::util::net::{CanSamplePoint, NetworkInterfaceDescriptor, NetworkInterfaceName};
+
+    use super::*;
+
+    fn device(name: &str, tags: &[&str], configuration: NetworkInterfaceConfiguration) -> DeviceDescriptor {
+        DeviceDescriptor {
+            id: DeviceId::random(),
+            name: DeviceName::try_from(name).unwrap(),
+            description: None,
+            interface: NetworkInterfaceDescriptor {
+                name: NetworkInterfaceName::try_from("vcan0").unwrap(),
+                configuration,
+            },
+            tags: tags.iter().map(|tag| DeviceTag::try_from(*tag).unwrap()).collect(),
+        }
+    }
+
+    fn peer() -> PeerDescriptor {
+        PeerDescriptor {
+            id: PeerId::random(),
+            name: PeerName::try_from("TestBench").unwrap(),
+            location: PeerLocation::try_from("Ulm").ok(),
+            network_configuration: PeerNetworkConfiguration { interfaces: vec![] },
+            topology: Topology::default(),
+            executors: ExecutorDescriptors { executors: vec![] },
+            revision: 0,
+        }
+    }
+
+    fn can(fd: bool) -> NetworkInterfaceConfiguration {
+        NetworkInterfaceConfiguration::Can {
+            bitrate: 500000,
+            sample_point: CanSamplePoint::try_from(0.7).unwrap(),
+            fd,
+            data_bitrate: 2000000,
+            data_sample_point: CanSamplePoint::try_from(0.7).unwrap(),
+        }
+    }
+
+    #[test]
+    fn should_match_devices_by_their_properties_and_their_peer() -> Result<()> {
+        let peer = peer();
+        let available = PeerState::Up { inner: PeerUpState::Available, remote_host: IpAddr::V4(Ipv4Addr::LOCALHOST) };
+        let cluster = ClusterConfiguration {
+            id: ClusterId::random(),
+            name: ClusterName::try_from("HIL-1").unwrap(),
+            leader: peer.id,
+            devices: HashSet::new(),
+            revision: 0,
+        };
+
+        let engine_control = device("EngineControl", &["powertrain"], can(true));
+        let infotainment = device("Infotainment", &["multimedia"], NetworkInterfaceConfiguration::Ethernet);
+
+        let engine_control = DeviceQueryContext { device: &engine_control, peer: &peer, peer_state: &available, clusters: vec![&cluster] };
+        let infotainment = DeviceQueryContext { device: &infotainment, peer: &peer, peer_state: &PeerState::Down, clusters: vec![] };
+
+        let query = DeviceQuery::from_str("tag=powertrain and type=can and peer.state=available")?;
+        verify_that!(query.matches(&engine_control), eq(true))?;
+        verify_that!(query.matches(&infotainment), eq(false))?;
+
+        let query = DeviceQuery::from_str("(name=info* or fd=true) and not cluster=none")?;
+        verify_that!(query.matches(&engine_control), eq(true))?;
+        verify_that!(query.matches(&infotainment), eq(false))?;
+
+        let query = DeviceQuery::from_str(r#"peer.location="ulm" and cluster!=hil-1 and peer.state=down"#)?;
+        verify_that!(query.matches(&engine_control), eq(false))?;
+        verify_that!(query.matches(&infotainment), eq(true))?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_illegal_queries() -> Result<()> {
+        verify_that!(DeviceQuery::from_str("  "), err(eq(IllegalDeviceQuery::Empty)))?;
+        verify_that!(DeviceQuery::from_str("color=red"), err(matches_pattern!(IllegalDeviceQuery::UnknownField { field: eq("color") })))?;
+        verify_that!(DeviceQuery::from_str("type=flexray"), err(matches_pattern!(IllegalDeviceQuery::IllegalValue { value: eq("flexray") })))?;
+        verify_that!(DeviceQuery::from_str("(tag=a or tag=b"), err(matches_pattern!(IllegalDeviceQuery::UnexpectedEnd { expected: eq("')'") })))?;
+        verify_that!(DeviceQuery::from_str("tag=a tag=b"), err(matches_pattern!(IllegalDeviceQuery::UnexpectedToken { token: eq("tag") })))?;
+        verify_that!(DeviceQuery::from_str("name=\"open"), err(matches_pattern!(IllegalDeviceQuery::UnclosedQuote { position: eq(5) })))?;
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_too_long_and_too_deeply_nested_queries() -> Result<()> {
+        let deep = |depth: usize| format!("{}name=a{}", "(".repeat(depth), ")".repeat(depth));
+
+        verify_that!(DeviceQuery::from_str(&deep(DeviceQuery::MAX_NESTING_DEPTH)), ok(anything()))?;
+        verify_that!(DeviceQuery::from_str(&deep(DeviceQuery::MAX_NESTING_DEPTH + 1)), err(matches_pattern!(IllegalDeviceQuery::TooDeeplyNested { limit: eq(DeviceQuery::MAX_NESTING_DEPTH) })))?;
+        verify_that!(DeviceQuery::from_str(&"not ".repeat(DeviceQuery::MAX_NESTING_DEPTH + 1)), err(matches_pattern!(IllegalDeviceQuery::TooDeeplyNested { limit: eq(DeviceQuery::MAX_NESTING_DEPTH) })))?;
+        verify_that!(DeviceQuery::from_str(&deep(100_000)), err(matches_pattern!(IllegalDeviceQuery::TooLong { limit: eq(DeviceQuery::MAX_LENGTH) })))?;
+        Ok(())
+    }
+
+    #[test]
+    fn should_drop_deeply_nested_expressions_without_recursion() {
+        let mut expression = Expression::Predicate { field: Field::Name, value: String::from("a"), negated: false };
+        for _ in 0..100_000 {
+            expression = Expression::Not(Box::new(expression));
+        }
+        drop(expression);
+    }
+
+    #[test]
+    fn should_match_wildcard_patterns() -> Result<()> {
+        verify_that!(matches_pattern("*", ""), eq(true))?;
+        verify_that!(matches_pattern("engine*", "EngineControl"), eq(true))?;
+        verify_that!(matches_pattern("*con*ol", "EngineControl"), eq(true))?;
+        verify_that!(matches_pattern("e*x", "EngineControl"), eq(false))?;
+        verify_that!(matches_pattern("enginecontrol", "EngineControl"), eq(true))?;
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
+use std::net::IpAddr;
+
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use crate::util::Port;
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum VpnPeerConfiguration {
@@ -9,7 +13,19 @@ pub enum VpnPeerConfiguration {
     Netbird {
         management_url: Url,
         setup_key: netbird::SetupKey,
-    }
+    },
+    #[serde(rename = "wireguard", rename_all = "kebab-case")]
+    WireGuard {
+        private_key: wireguard::PrivateKey,
+        address: IpAddr,
+        prefix_length: u8,
+        listen_port: Port,
+    },
+    /// Peer running in a network namespace on the same host as CARL, connected to the other peers via a bridge. Intended for testing.
+    #[serde(rename_all = "kebab-case")]
+    Loopback {
+        address: IpAddr,
+    },
 }
 
 pub mod netbird {
@@ -31,3 +47,230 @@ pub mod netbird {
         }
     }
 }
+
+pub mod wireguard {
+    use std::fmt;
+    use std::fmt::Formatter;
+    use std::net::SocketAddr;
+    use std::str::FromStr;
+
+    use base64::Engine;
+    use base64::prelude::BASE64_STANDARD;
+    use serde::{Deserialize, Serialize};
+
+    pub const KEY_LENGTH: usize = 32;
+
+    /// The private key of a WireGuard interface. Its debug representation is redacted to not log the key.
+    #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(try_from = "String", into = "String")]
+    pub struct PrivateKey(pub [u8; KEY_LENGTH]);
+
+    impl PrivateKey {
+        pub const REDACTED: &'static str = "<redacted>";
+
+        pub fn to_base64(&self) -> String {
+            BASE64_STANDARD.encode(self.0)
+        }
+    }
+
+    impl fmt::Debug for PrivateKey {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.debug_tuple("PrivateKey").field(&Self::REDACTED).finish()
+        }
+    }
+
+    impl FromStr for PrivateKey {
+        type Err = InvalidKeyError;
+
+        fn from_str(value: &str) -> Result<Self, Self::Err> {
+            decode_key(value).map(Self)
+        }
+    }
+
+    impl TryFrom<String> for PrivateKey {
+        type Error = InvalidKeyError;
+
+        fn try_from(value: String) -> Result<Self, Self::Error> {
+            Self::from_str(&value)
+        }
+    }
+
+    impl From<PrivateKey> for String {
+        fn from(value: PrivateKey) -> Self {
+            value.to_base64()
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(try_from = "String", into = "String")]
+    pub struct PublicKey(pub [u8; KEY_LENGTH]);
+
+    impl fmt::Display for PublicKey {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", BASE64_STANDARD.encode(self.0))
+        }
+    }
+
+    impl FromStr for PublicKey {
+        type Err = InvalidKeyError;
+
+        fn from_str(value: &str) -> Result<Self, Self::Err> {
+            decode_key(value).map(Self)
+        }
+    }
+
+    impl TryFrom<String> for PublicKey {
+        type Error = InvalidKeyError;
+
+        fn try_from(value: String) -> Result<Self, Self::Error> {
+            Self::from_str(&value)
+        }
+    }
+
+    impl From<PublicKey> for String {
+        fn from(value: PublicKey) -> Self {
+            value.to_string()
+        }
+    }
+
+    #[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+    #[error("A WireGuard key must be the base64 encoding of {KEY_LENGTH} bytes.")]
+    pub struct InvalidKeyError;
+
+    fn decode_key(value: &str) -> Result<[u8; KEY_LENGTH], InvalidKeyError> {
+        BASE64_STANDARD.decode(value.trim())
+            .map_err(|_| InvalidKeyError)?
+            .try_into()
+            .map_err(|_| InvalidKeyError)
+    }
+
+    /// Describes how a member of a cluster can be reached via WireGuard.
+    /// The tunnel address of the member is the `vpn_address` of its [`PeerClusterAssignment`](crate::cluster::PeerClusterAssignment).
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct WireGuardPeer {
+        pub public_key: PublicKey,
+        pub endpoint: SocketAddr,
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use googletest::prelude::*;
+
+        use super::*;
+
+        #[test]
+        fn should_encode_and_decode_keys_as_base64() -> Result<()> {
+            let key = PublicKey([7; KEY_LENGTH]);
+
+            verify_that!(PublicKey::from_str(&key.to_string()), ok(eq(key)))?;
+            verify_that!(PublicKey::from_str("AAAA"), err(eq(InvalidKeyError)))?;
+            verify_that!(format!("{:?}", PrivateKey([7; KEY_LENGTH])), not(contains_substring(key.to_string())))
+        }
+    }
+}
+
+pub mod health {
+    use std::net::IpAddr;
+    use std::time::Duration;
+
+    use serde::{Deserialize, Serialize};
+
+    /// The state of the VPN connection of a peer, as last reported by its EDGAR.
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct VpnHealth {
+        /// Whether the peer is connected to the management server of the VPN.
+        pub connected: bool,
+        /// The address of the peer within the VPN, if it has been assigned one.
+        pub address: Option<IpAddr>,
+        pub links: Vec<VpnLink>,
+    }
+
+    /// The connection of a peer to another peer of the VPN.
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct VpnLink {
+        /// The address of the other peer within the VPN.
+        pub remote_address: IpAddr,
+        pub connected: bool,
+        /// Whether the traffic is forwarded via a relay server, rather than exchanged directly.
+        pub relayed: bool,
+        /// The round-trip time to the other peer, if the VPN reports it.
+        pub latency: Option<Duration>,
+    }
+}
+
+pub mod reconciliation {
+    use std::fmt;
+    use std::fmt::Formatter;
+
+    use serde::{Deserialize, Serialize};
+
+    /// Result of comparing the resources of the VPN with the peers and cluster deployments known to CARL.
+    #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct VpnReconciliationReport {
+        /// Whether the drift was only reported, without repairing it.
+        pub dry_run: bool,
+        pub drifts: Vec<VpnDrift>,
+    }
+
+    impl VpnReconciliationReport {
+        pub fn is_in_sync(&self) -> bool {
+            self.drifts.is_empty()
+        }
+    }
+
+    /// A single resource of the VPN, which differs from the state expected by CARL.
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct VpnDrift {
+        pub kind: VpnDriftKind,
+        /// Human-readable description of the affected resource, e.g. the name of a NetBird group.
+        pub resource: String,
+        /// Human-readable description of what is done to repair the drift.
+        pub action: String,
+        pub outcome: VpnDriftOutcome,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub enum VpnDriftKind {
+        /// The resource exists in the VPN, but belongs to no peer or cluster deployment known to CARL.
+        Orphaned,
+        /// The resource is expected by CARL, but does not exist in the VPN.
+        Missing,
+        /// The resource exists in the VPN, but its content differs from what CARL expects.
+        Diverged,
+    }
+
+    impl fmt::Display for VpnDriftKind {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            match self {
+                VpnDriftKind::Orphaned => write!(f, "Orphaned"),
+                VpnDriftKind::Missing => write!(f, "Missing"),
+                VpnDriftKind::Diverged => write!(f, "Diverged"),
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub enum VpnDriftOutcome {
+        /// The drift was found during a dry-run and has not been touched.
+        Planned,
+        Repaired,
+        Failed { cause: String },
+    }
+
+    impl fmt::Display for VpnDriftOutcome {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            match self {
+                VpnDriftOutcome::Planned => write!(f, "Planned"),
+                VpnDriftOutcome::Repaired => write!(f, "Repaired"),
+                VpnDriftOutcome::Failed { cause } => write!(f, "Failed: {cause}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::io::Write;
+use std::path::Path;
+
+/// Writes the file, so that only the current user can read it, because it contains credentials.
+/// Missing parent directories are created.
+///
+/// The content is written to a temporary file next to it, which replaces the file afterwards.
+/// This way, an existing file keeps no permissions granted to other users and is never left partially written.
+pub fn write_private_file(path: &Path, content: &str) -> std::io::Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if parent.as_os_str().is_empty() => Path::new("."),
+        Some(parent) => parent,
+        None => Path::new("."),
+    };
+    std::fs::create_dir_all(parent)?;
+
+    let file_name = path.file_name()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Path '{}' does not name a file.", path.display())))?;
+    let temporary_path = parent.join(format!(".{}.{}.tmp", file_name.to_string_lossy(), std::process::id()));
+
+    let result = write_restricted(&temporary_path, content)
+        .and_then(|()| std::fs::rename(&temporary_path, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temporary_path);
+    }
+    result
+}
+
+fn write_restricted(path: &Path, content: &str) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    {
+        // The mode only applies to newly created files, so restrict a leftover file as well.
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(content.as_bytes())?;
+    file.sync_all()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn temporary_directory() -> PathBuf {
+        std::env::temp_dir().join(format!("opendut-util-fs-{}-{:?}", std::process::id(), std::thread::current().id()))
+    }
+
+    #[test]
+    fn should_replace_an_existing_file_readable_by_others() -> std::io::Result<()> {
+        let directory = temporary_directory();
+        let path = directory.join("credentials.json");
+        std::fs::create_dir_all(&directory)?;
+        std::fs::write(&path, "old")?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644))?;
+        }
+
+        write_private_file(&path, "new")?;
+
+        assert_eq!(std::fs::read_to_string(&path)?, "new");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path)?.permissions().mode() & 0o777, 0o600);
+        }
+        assert_eq!(std::fs::read_dir(&directory)?.count(), 1, "Temporary file should have been renamed.");
+
+        std::fs::remove_dir_all(directory)
+    }
+}
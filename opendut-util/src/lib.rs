@@ -1,5 +1,8 @@
 pub mod app_info;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod fs;
+
 #[cfg(not(target_arch = "wasm32"))]
 pub mod logging;
 
@@ -18,6 +18,8 @@ pub struct LoadedConfig {
     pub redacted_config: Config,
     pub config_files_used: Vec<PathBuf>,
     pub config_files_declared: Vec<PathBuf>,
+    /// The overrides passed when loading, so that the configuration can be reloaded with them.
+    pub overrides: Config,
 }
 
 impl Debug for LoadedConfig {
@@ -44,7 +46,6 @@ pub fn load_config(name: &str, defaults: &str, defaults_format: FileFormat, over
 
     let development_config = format!("opendut-{name}/{name}-development.toml");
     let system_config = format!("/etc/opendut/{name}.toml");
-    let user_config = format!("opendut/{name}/config.toml");
 
     let builder = Config::builder()
         .add_source(config::File::from_str(defaults, defaults_format));
@@ -56,15 +57,7 @@ pub fn load_config(name: &str, defaults: &str, defaults_format: FileFormat, over
     }
 
     config_files.push(Some(PathBuf::from(system_config)));
-
-    match std::env::var("XDG_CONFIG_HOME") {
-        Ok(xdg_config_home) => {
-            config_files.push(Some(PathBuf::from(xdg_config_home).join(user_config)));
-        }
-        Err(_) => {
-            config_files.push(home_dir().map(|path| path.join(".config").join(user_config)));
-        }
-    }
+    config_files.push(user_config_path(name));
 
     let (sources_used, sources_declared): (Vec<PathBuf>, Vec<PathBuf>) = config_files.into_iter()
         .fold((Vec::new(), Vec::new()), |(mut used, mut declared), path| {
@@ -89,7 +82,7 @@ pub fn load_config(name: &str, defaults: &str, defaults_format: FileFormat, over
             .try_parsing(true)
     );
 
-    let settings = builder.add_source(overrides);
+    let settings = builder.add_source(Clone::clone(&overrides));
     let secret_redacted_settings = settings.clone()
         .add_source(secret_redacted_overrides);
 
@@ -98,5 +91,23 @@ pub fn load_config(name: &str, defaults: &str, defaults_format: FileFormat, over
         redacted_config: secret_redacted_settings.build()?,
         config_files_used: sources_used,
         config_files_declared: sources_declared,
+        overrides,
     })
-}
\ No newline at end of file
+}
+/// The path of the user configuration, `[XDG_CONFIG_HOME|~/.config]/opendut/{name}/config.toml`.
+pub fn user_config_path(name: &str) -> Option<PathBuf> {
+    let user_config = format!("opendut/{name}/config.toml");
+    match std::env::var("XDG_CONFIG_HOME") {
+        Ok(xdg_config_home) => Some(PathBuf::from(xdg_config_home).join(user_config)),
+        Err(_) => home_dir().map(|path| path.join(".config").join(user_config)),
+    }
+}
+
+/// The directory for cached data of the user, `[XDG_CACHE_HOME|~/.cache]/opendut/{name}`.
+pub fn user_cache_dir(name: &str) -> Option<PathBuf> {
+    let user_cache = format!("opendut/{name}");
+    match std::env::var("XDG_CACHE_HOME") {
+        Ok(xdg_cache_home) => Some(PathBuf::from(xdg_cache_home).join(user_cache)),
+        Err(_) => home_dir().map(|path| path.join(".cache").join(user_cache)),
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+use std::ops::Not;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use ipnet::Ipv4Net;
+use tokio::process::Command;
+use tracing::{debug, info, warn};
+
+use opendut_types::cluster::ClusterId;
+use opendut_types::peer::PeerId;
+use opendut_types::project::ProjectId;
+use opendut_types::vpn::VpnPeerConfiguration;
+use opendut_vpn::{ClusterNetworkPolicy, CreateClusterError, CreatePeerError, CreateVpnPeerConfigurationError, DeleteClusterError, DeletePeerError, VpnManagementClient};
+
+/// Name of the interface within the network namespace of a peer.
+pub const PEER_INTERFACE_NAME: &str = "veth-opendut";
+
+pub struct LoopbackManagementClientConfiguration {
+    /// The network of the bridge, which connects the network namespaces of the peers.
+    /// Its first address is assigned to the bridge itself and serves as the default gateway of the peers.
+    pub network: Ipv4Net,
+    pub bridge_name: String,
+}
+
+/// Connects peers running on the same Linux host as CARL, e.g. for testing cluster deployments in CI.
+///
+/// For each peer, a network namespace named after the peer is created and connected to a bridge via a veth pair.
+/// EDGAR is then expected to run within this namespace, e.g. via `ip netns exec opendut-<peer-id> opendut-edgar service`.
+/// Managing namespaces requires CARL to run with the `CAP_NET_ADMIN` and `CAP_SYS_ADMIN` capabilities.
+pub struct LoopbackManagementClient {
+    configuration: LoopbackManagementClientConfiguration,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    peers: HashMap<PeerId, Ipv4Addr>,
+    clusters: HashMap<(ProjectId, ClusterId), Vec<PeerId>>,
+}
+
+impl LoopbackManagementClient {
+
+    pub fn create(configuration: LoopbackManagementClientConfiguration) -> Self {
+        info!("Using loopback VPN with bridge '{}' and network '{}'.", configuration.bridge_name, configuration.network);
+        Self {
+            configuration,
+            state: Default::default(),
+        }
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("Lock of loopback VPN state should not be poisoned.")
+    }
+
+    fn gateway(&self) -> Option<Ipv4Addr> {
+        self.configuration.network.hosts().next()
+    }
+
+    fn allocate_address(&self, peer_id: PeerId) -> Result<Ipv4Addr, LoopbackError> {
+        let mut state = self.lock_state();
+        let address = self.configuration.network.hosts()
+            .skip(1)
+            .find(|address| state.peers.values().all(|other| other != address))
+            .ok_or(LoopbackError::NetworkExhausted { network: self.configuration.network })?;
+        state.peers.insert(peer_id, address);
+        Ok(address)
+    }
+
+    async fn ensure_bridge(&self) -> Result<(), LoopbackError> {
+        let bridge_exists = ip(&["link", "show", &self.configuration.bridge_name]).await.is_ok();
+        if bridge_exists.not() {
+            for command in self.bridge_commands()? {
+                ip(&command).await?;
+            }
+            debug!("Created bridge '{}' for the loopback VPN.", self.configuration.bridge_name);
+        }
+        Ok(())
+    }
+
+    fn bridge_commands(&self) -> Result<Vec<Vec<String>>, LoopbackError> {
+        let network = self.configuration.network;
+        let gateway = self.gateway().ok_or(LoopbackError::NetworkExhausted { network })?;
+        let bridge = &self.configuration.bridge_name;
+
+        Ok(vec![
+            args(["link", "add", bridge, "type", "bridge"]),
+            args(["addr", "add", &format!("{gateway}/{}", network.prefix_len()), "dev", bridge]),
+            args(["link", "set", bridge, "up"]),
+        ])
+    }
+
+    fn peer_commands(&self, peer_id: PeerId, address: Ipv4Addr) -> Result<Vec<Vec<String>>, LoopbackError> {
+        let network = self.configuration.network;
+        let gateway = self.gateway().ok_or(LoopbackError::NetworkExhausted { network })?;
+        let bridge = &self.configuration.bridge_name;
+        let namespace = namespace_name(peer_id);
+        let host_interface = host_interface_name(peer_id);
+
+        Ok(vec![
+            args(["netns", "add", &namespace]),
+            args(["link", "add", &host_interface, "type", "veth", "peer", "name", PEER_INTERFACE_NAME, "netns", &namespace]),
+            args(["link", "set", &host_interface, "master", bridge]),
+            args(["link", "set", &host_interface, "up"]),
+            args(["-n", &namespace, "addr", "add", &format!("{address}/{}", network.prefix_len()), "dev", PEER_INTERFACE_NAME]),
+            args(["-n", &namespace, "link", "set", "lo", "up"]),
+            args(["-n", &namespace, "link", "set", PEER_INTERFACE_NAME, "up"]),
+            args(["-n", &namespace, "route", "add", "default", "via", &gateway.to_string()]),
+        ])
+    }
+
+    async fn create_namespace(&self, peer_id: PeerId, address: Ipv4Addr) -> Result<(), LoopbackError> {
+        self.ensure_bridge().await?;
+
+        // Remove leftovers, e.g. from before a restart of CARL.
+        let _ = delete_namespace(peer_id).await;
+
+        for command in self.peer_commands(peer_id, address)? {
+            ip(&command).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl VpnManagementClient for LoopbackManagementClient {
+
+    #[tracing::instrument(skip(self), level="trace")]
+    async fn create_cluster(&self, project_id: ProjectId, cluster_id: ClusterId, peers: &[PeerId], _policy: &ClusterNetworkPolicy) -> Result<(), CreateClusterError> {
+        // The policy is not enforced, as the namespaces are only connected locally for testing.
+        let mut state = self.lock_state();
+
+        if let Some(peer_id) = peers.iter().find(|peer_id| state.peers.contains_key(peer_id).not()) {
+            return Err(CreateClusterError::PeerResolutionFailure {
+                peer_id: *peer_id,
+                cluster_id,
+                error: Box::new(LoopbackError::PeerNotFound { peer_id: *peer_id }),
+            });
+        }
+
+        state.clusters.insert((project_id, cluster_id), peers.to_vec());
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), level="trace")]
+    async fn delete_cluster(&self, project_id: ProjectId, cluster_id: ClusterId) -> Result<(), DeleteClusterError> {
+        self.lock_state().clusters.remove(&(project_id, cluster_id))
+            .map(|_| ())
+            .ok_or_else(|| DeleteClusterError::NotFound { cluster_id, message: String::from("The loopback VPN does not know the cluster.") })
+    }
+
+    #[tracing::instrument(skip(self), level="trace")]
+    async fn create_peer(&self, peer_id: PeerId) -> Result<(), CreatePeerError> {
+        if self.lock_state().peers.contains_key(&peer_id) {
+            debug!("Loopback peer <{peer_id}> already exists. Keeping its namespace.");
+            return Ok(());
+        }
+
+        let address = self.allocate_address(peer_id)
+            .map_err(|error| CreatePeerError::CreationFailure { peer_id, error: Box::new(error) })?;
+
+        if let Err(error) = self.create_namespace(peer_id, address).await {
+            self.lock_state().peers.remove(&peer_id);
+            let _ = delete_namespace(peer_id).await;
+            return Err(CreatePeerError::CreationFailure { peer_id, error: Box::new(error) });
+        }
+
+        info!("Created network namespace '{}' for peer <{peer_id}> with address '{address}'.", namespace_name(peer_id));
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), level="trace")]
+    async fn delete_peer(&self, peer_id: PeerId) -> Result<(), DeletePeerError> {
+        {
+            let mut state = self.lock_state();
+            state.peers.remove(&peer_id)
+                .ok_or_else(|| DeletePeerError::ResolutionFailure {
+                    peer_id,
+                    error: Box::new(LoopbackError::PeerNotFound { peer_id }),
+                })?;
+            for members in state.clusters.values_mut() {
+                members.retain(|member| *member != peer_id);
+            }
+        }
+
+        delete_namespace(peer_id).await
+            .map_err(|error| DeletePeerError::DeletionFailure { peer_id, error: Box::new(error) })
+    }
+
+    #[tracing::instrument(skip(self), level="trace")]
+    async fn generate_vpn_peer_configuration(&self, peer_id: PeerId) -> Result<VpnPeerConfiguration, CreateVpnPeerConfigurationError> {
+        let address = self.lock_state().peers.get(&peer_id).copied()
+            .ok_or_else(|| CreateVpnPeerConfigurationError::CreationFailure {
+                peer_id,
+                error: Box::new(LoopbackError::PeerNotFound { peer_id }),
+            })?;
+
+        Ok(VpnPeerConfiguration::Loopback {
+            address: IpAddr::V4(address),
+        })
+    }
+}
+
+/// Name of the network namespace, in which EDGAR of the given peer is expected to run.
+pub fn namespace_name(peer_id: PeerId) -> String {
+    format!("opendut-{peer_id}")
+}
+
+/// Name of the host-side veth interface of a peer, which is limited to 15 characters by the kernel.
+fn host_interface_name(peer_id: PeerId) -> String {
+    let uuid = peer_id.0.simple().to_string();
+    format!("veth-{}", &uuid[..8])
+}
+
+async fn delete_namespace(peer_id: PeerId) -> Result<(), LoopbackError> {
+    // Deleting the namespace also deletes the veth pair.
+    ip(&["netns", "delete", &namespace_name(peer_id)]).await
+        .inspect_err(|error| warn!("{error}"))
+}
+
+fn args<const N: usize>(args: [&str; N]) -> Vec<String> {
+    args.into_iter().map(ToOwned::to_owned).collect()
+}
+
+async fn ip<S: AsRef<str>>(args: &[S]) -> Result<(), LoopbackError> {
+    let args = args.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+
+    let output = Command::new("ip")
+        .args(&args)
+        .output().await
+        .map_err(|cause| LoopbackError::CommandLineProgramExecution { cause })?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(LoopbackError::CommandFailed {
+            args: args.join(" "),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum LoopbackError {
+    #[error("Peer <{peer_id}> is not known to the loopback VPN.")]
+    PeerNotFound { peer_id: PeerId },
+    #[error("No more addresses available in the loopback network '{network}'.")]
+    NetworkExhausted { network: Ipv4Net },
+    #[error("Failure while invoking command line program 'ip': {cause}")]
+    CommandLineProgramExecution { cause: std::io::Error },
+    #[error("Command 'ip {args}' failed: {stderr}")]
+    CommandFailed { args: String, stderr: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use googletest::prelude::*;
+
+    use super::*;
+
+    fn testee(network: &str) -> LoopbackManagementClient {
+        LoopbackManagementClient::create(LoopbackManagementClientConfiguration {
+            network: Ipv4Net::from_str(network).unwrap(),
+            bridge_name: String::from("br-opendut-lo"),
+        })
+    }
+
+    #[test]
+    fn should_allocate_addresses_after_the_gateway() -> Result<()> {
+        let testee = testee("10.20.0.0/29");
+
+        let addresses = (0..5)
+            .map(|_| testee.allocate_address(PeerId::random()).unwrap())
+            .collect::<Vec<_>>();
+
+        verify_that!(testee.gateway(), some(eq(Ipv4Addr::new(10, 20, 0, 1))))?;
+        verify_that!(addresses, elements_are![
+            eq(Ipv4Addr::new(10, 20, 0, 2)),
+            eq(Ipv4Addr::new(10, 20, 0, 3)),
+            eq(Ipv4Addr::new(10, 20, 0, 4)),
+            eq(Ipv4Addr::new(10, 20, 0, 5)),
+            eq(Ipv4Addr::new(10, 20, 0, 6)),
+        ])?;
+        verify_that!(testee.allocate_address(PeerId::random()), err(anything()))
+    }
+
+    #[test]
+    fn should_connect_the_namespace_of_a_peer_to_the_bridge() -> Result<()> {
+        let testee = testee("10.20.0.0/24");
+        let peer_id = PeerId::try_from("dc72f6d9-d700-455f-8c31-9f15438e7503")?;
+        let namespace = "opendut-dc72f6d9-d700-455f-8c31-9f15438e7503";
+
+        let commands = testee.peer_commands(peer_id, Ipv4Addr::new(10, 20, 0, 2))?
+            .into_iter()
+            .map(|command| command.join(" "))
+            .collect::<Vec<_>>();
+
+        verify_that!(commands, contains_each![
+            eq(format!("netns add {namespace}")),
+            eq(format!("link add veth-dc72f6d9 type veth peer name veth-opendut netns {namespace}")),
+            eq("link set veth-dc72f6d9 master br-opendut-lo"),
+            eq(format!("-n {namespace} addr add 10.20.0.2/24 dev veth-opendut")),
+            eq(format!("-n {namespace} route add default via 10.20.0.1")),
+        ])
+    }
+
+    #[tokio::test]
+    async fn should_only_generate_configurations_for_known_peers() -> Result<()> {
+        let testee = testee("10.20.0.0/24");
+        let peer_id = PeerId::random();
+
+        verify_that!(testee.generate_vpn_peer_configuration(peer_id).await, err(anything()))?;
+
+        testee.allocate_address(peer_id)?;
+        verify_that!(testee.generate_vpn_peer_configuration(peer_id).await, ok(eq(VpnPeerConfiguration::Loopback { address: IpAddr::from([10, 20, 0, 2]) })))
+    }
+}
@@ -11,7 +11,7 @@ use opendut_types::peer::PeerId;
 use crate::{netbird, routes};
 use crate::client::request_handler::{DefaultRequestHandler, RequestHandler, RequestHandlerConfig};
 use crate::netbird::error;
-use crate::netbird::error::{CreateClientError, CreateSetupKeyError, GetGroupError, GetRulesError, RequestError};
+use crate::netbird::error::{CreateClientError, CreateSetupKeyError, GetGroupError, GetPolicyError, RequestError};
 
 mod request_handler;
 
@@ -21,13 +21,17 @@ mod tests;
 pub trait Client {
     async fn create_netbird_group(&self, name: netbird::GroupName, peers: Vec<netbird::PeerId>) -> Result<netbird::Group, RequestError>;
     async fn get_netbird_group(&self, group_name: &netbird::GroupName) -> Result<netbird::Group, GetGroupError>;
+    async fn list_netbird_groups(&self) -> Result<Vec<netbird::Group>, RequestError>;
     async fn delete_netbird_group(&self, group_id: &netbird::GroupId) -> Result<(), RequestError>;
     async fn get_netbird_peer(&self, peer_id: &netbird::PeerId) -> Result<netbird::Peer, RequestError>;
     async fn delete_netbird_peer(&self, peer_id: &netbird::PeerId) -> Result<(), RequestError>;
-    async fn create_netbird_self_access_control_rule(&self, group: netbird::Group, rule_name: netbird::RuleName) -> Result<(), RequestError>;
-    async fn get_netbird_rule(&self, rule_name: &netbird::RuleName) -> Result<netbird::Rule, GetRulesError>;
-    async fn delete_netbird_rule(&self, rule_id: &netbird::RuleId) -> Result<(), RequestError>;
+    async fn create_netbird_policy(&self, policy_name: netbird::PolicyName, rules: Vec<netbird::PolicyRuleSpec>) -> Result<(), RequestError>;
+    async fn get_netbird_policy(&self, policy_name: &netbird::PolicyName) -> Result<netbird::Policy, GetPolicyError>;
+    async fn list_netbird_policies(&self) -> Result<Vec<netbird::Policy>, RequestError>;
+    async fn delete_netbird_policy(&self, policy_id: &netbird::PolicyId) -> Result<(), RequestError>;
     async fn generate_netbird_setup_key(&self, peer_id: PeerId) -> Result<netbird::SetupKey, CreateSetupKeyError>;
+    async fn list_netbird_setup_keys(&self) -> Result<Vec<netbird::SetupKeyInfo>, RequestError>;
+    async fn revoke_netbird_setup_key(&self, setup_key: &netbird::SetupKeyInfo) -> Result<(), RequestError>;
 }
 
 pub struct DefaultClient {
@@ -144,6 +148,20 @@ impl Client for DefaultClient {
         }
     }
 
+    #[tracing::instrument(skip(self), level="trace")]
+    async fn list_netbird_groups(&self) -> Result<Vec<netbird::Group>, RequestError> {
+        let url = routes::groups(self.netbird_url.clone());
+        let request = Request::new(Method::GET, url);
+
+        let response = self.requester.handle(request).await?
+            .error_for_status().map_err(RequestError::IllegalStatus)?;
+
+        let result = response.json().await
+            .map_err(RequestError::JsonDeserialization)?;
+
+        Ok(result)
+    }
+
     #[tracing::instrument(skip(self), level="trace")]
     async fn delete_netbird_group(&self, group_id: &netbird::GroupId) -> Result<(), RequestError> {
         let url = routes::group(Clone::clone(&self.netbird_url), group_id);
@@ -152,7 +170,7 @@ impl Client for DefaultClient {
 
         let response = self.requester.handle(request).await?;
 
-        parse_response_status(response, format!("NetBird group with ID <{:?}>", group_id)).await
+        parse_response_status(response, format!("deleting NetBird group with ID <{:?}>", group_id)).await
     }
 
     #[tracing::instrument(skip(self), level="trace")]
@@ -185,28 +203,52 @@ impl Client for DefaultClient {
     }
 
     #[tracing::instrument(skip(self), level="trace")]
-    async fn create_netbird_self_access_control_rule(&self, group: netbird::Group, rule_name: netbird::RuleName) -> Result<(), RequestError> {
-        let url = routes::rules(self.netbird_url.clone());
+    async fn create_netbird_policy(&self, policy_name: netbird::PolicyName, rules: Vec<netbird::PolicyRuleSpec>) -> Result<(), RequestError> {
+        let url = routes::policies(self.netbird_url.clone());
 
         let body = {
             #[derive(Serialize)]
-            struct CreateAccessControlRule {
-                name: netbird::RuleName,
+            struct CreatePolicy {
+                name: netbird::PolicyName,
                 description: String,
-                disabled: bool,
-                flow: netbird::RuleFlow,
+                enabled: bool,
+                rules: Vec<CreatePolicyRule>,
+            }
+
+            #[derive(Serialize)]
+            struct CreatePolicyRule {
+                name: String,
+                description: String,
+                enabled: bool,
+                action: netbird::PolicyAction,
+                bidirectional: bool,
+                protocol: netbird::PolicyProtocol,
+                #[serde(skip_serializing_if = "Vec::is_empty")]
+                ports: Vec<String>,
                 sources: Vec<netbird::GroupId>,
                 destinations: Vec<netbird::GroupId>,
             }
 
-            let description = rule_name.description();
-            CreateAccessControlRule {
-                name: rule_name,
+            let description = policy_name.description();
+            let rules = rules.into_iter().enumerate()
+                .map(|(index, rule)| CreatePolicyRule {
+                    name: format!("{policy_name}-{index}"),
+                    description: description.clone(),
+                    enabled: true,
+                    action: netbird::PolicyAction::Accept,
+                    bidirectional: rule.bidirectional,
+                    protocol: rule.protocol,
+                    ports: rule.ports,
+                    sources: rule.sources,
+                    destinations: rule.destinations,
+                })
+                .collect();
+
+            CreatePolicy {
+                name: policy_name,
                 description,
-                disabled: false,
-                flow: netbird::RuleFlow::Bidirect,
-                sources: vec![group.id.clone()],
-                destinations: vec![group.id],
+                enabled: true,
+                rules,
             }
         };
 
@@ -220,34 +262,48 @@ impl Client for DefaultClient {
     }
 
     #[tracing::instrument(skip(self), level="trace")]
-    async fn get_netbird_rule(&self, rule_name: &netbird::RuleName) -> Result<netbird::Rule, GetRulesError> {
-        let url = routes::rules(self.netbird_url.clone());
+    async fn get_netbird_policy(&self, policy_name: &netbird::PolicyName) -> Result<netbird::Policy, GetPolicyError> {
+        let url = routes::policies(self.netbird_url.clone());
         let request = Request::new(Method::GET, url);
         let response = self.requester.handle(request).await
-            .map_err(|cause| GetRulesError::RequestFailure { rule_name: rule_name.to_owned(), cause })?;
-        let result = response.json::<Vec<netbird::Rule>>().await
-            .map_err(|cause| GetRulesError::RequestFailure { rule_name: rule_name.to_owned(), cause: RequestError::JsonDeserialization(cause) })?;
+            .map_err(|cause| GetPolicyError::RequestFailure { policy_name: policy_name.to_owned(), cause })?;
+        let result = response.json::<Vec<netbird::Policy>>().await
+            .map_err(|cause| GetPolicyError::RequestFailure { policy_name: policy_name.to_owned(), cause: RequestError::JsonDeserialization(cause) })?;
 
-        let rules = result.into_iter()
-            .filter(|rule| rule.name == *rule_name)
+        let policies = result.into_iter()
+            .filter(|policy| policy.name == *policy_name)
             .collect::<Vec<_>>();
 
-        if rules.len() > 1 {
-            Err(GetRulesError::MultipleRulesFound { rule_name: rule_name.to_owned() })
+        if policies.len() > 1 {
+            Err(GetPolicyError::MultiplePoliciesFound { policy_name: policy_name.to_owned() })
         } else {
-            rules.into_iter().next().ok_or(GetRulesError::RuleNotFound { rule_name: rule_name.to_owned() })
+            policies.into_iter().next().ok_or(GetPolicyError::PolicyNotFound { policy_name: policy_name.to_owned() })
         }
     }
 
     #[tracing::instrument(skip(self), level="trace")]
-    async fn delete_netbird_rule(&self, rule_id: &netbird::RuleId) -> Result<(), RequestError> {
-        let url = routes::rule(Clone::clone(&self.netbird_url), rule_id);
+    async fn list_netbird_policies(&self) -> Result<Vec<netbird::Policy>, RequestError> {
+        let url = routes::policies(self.netbird_url.clone());
+        let request = Request::new(Method::GET, url);
+
+        let response = self.requester.handle(request).await?
+            .error_for_status().map_err(RequestError::IllegalStatus)?;
+
+        let result = response.json().await
+            .map_err(RequestError::JsonDeserialization)?;
+
+        Ok(result)
+    }
+
+    #[tracing::instrument(skip(self), level="trace")]
+    async fn delete_netbird_policy(&self, policy_id: &netbird::PolicyId) -> Result<(), RequestError> {
+        let url = routes::policy(Clone::clone(&self.netbird_url), policy_id);
 
         let request = Request::new(Method::DELETE, url);
 
         let response = self.requester.handle(request).await?;
 
-        parse_response_status(response, format!("NetBird rule with ID <{:?}>", rule_id)).await
+        parse_response_status(response, format!("deleting NetBird policy with ID <{:?}>", policy_id)).await
     }
 
     #[tracing::instrument(skip(self), level="trace")]
@@ -293,10 +349,61 @@ impl Client for DefaultClient {
 
         Ok(result)
     }
+
+    #[tracing::instrument(skip(self), level="trace")]
+    async fn list_netbird_setup_keys(&self) -> Result<Vec<netbird::SetupKeyInfo>, RequestError> {
+        let url = routes::setup_keys(self.netbird_url.clone());
+        let request = Request::new(Method::GET, url);
+
+        let response = self.requester.handle(request).await?
+            .error_for_status().map_err(RequestError::IllegalStatus)?;
+
+        let result = response.json().await
+            .map_err(RequestError::JsonDeserialization)?;
+
+        Ok(result)
+    }
+
+    #[tracing::instrument(skip(self), level="trace")]
+    async fn revoke_netbird_setup_key(&self, setup_key: &netbird::SetupKeyInfo) -> Result<(), RequestError> {
+        let url = routes::setup_key(self.netbird_url.clone(), &setup_key.id);
+
+        let body = {
+            // NetBird's API requires all fields when updating a setup key, even though only `revoked` can be changed.
+            #[derive(Serialize)]
+            struct UpdateSetupKey {
+                name: String,
+                r#type: netbird::SetupKeyType,
+                expires_in: u64, //seconds
+                revoked: bool,
+                auto_groups: Vec<String>,
+                usage_limit: u64,
+            }
+
+            UpdateSetupKey {
+                name: Clone::clone(&setup_key.name),
+                r#type: Clone::clone(&setup_key.r#type),
+                expires_in: DefaultClient::SETUP_KEY_EXPIRY_DURATION.as_secs(),
+                revoked: true,
+                auto_groups: Clone::clone(&setup_key.auto_groups),
+                usage_limit: setup_key.usage_limit,
+            }
+        };
+
+        let request = json_request(Method::PUT, url, body)?;
+
+        let response = self.requester.handle(request).await?;
+
+        parse_response_status(response, format!("revoking NetBird setup key with ID <{:?}>", setup_key.id)).await
+    }
 }
 
 fn post_json_request(url: Url, body: impl Serialize) -> Result<Request, RequestError> {
-    let mut request = Request::new(Method::POST, url);
+    json_request(Method::POST, url, body)
+}
+
+fn json_request(method: Method, url: Url, body: impl Serialize) -> Result<Request, RequestError> {
+    let mut request = Request::new(method, url);
 
     request.headers_mut()
         .insert(header::CONTENT_TYPE, DefaultClient::APPLICATION_JSON.parse().unwrap());
@@ -315,7 +422,7 @@ async fn parse_response_status(response: Response, error_text: String) -> Result
         Err(status) => {
             let body = response.text().await.unwrap_or(String::from("<no body>"));
             let status_code = status.status().expect("Error should be generated from a response");
-            error!("Received status code '{code}' when deleting {error_text}:\n  {body}", code=status_code, error_text=error_text, body=body);
+            error!("Received status code '{code}' when {error_text}:\n  {body}", code=status_code, error_text=error_text, body=body);
             Err(RequestError::IllegalRequest(status_code, body))
         }
     }
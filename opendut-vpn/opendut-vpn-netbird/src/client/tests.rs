@@ -12,6 +12,7 @@ use serde_json::json;
 use uuid::uuid;
 
 use opendut_types::cluster::ClusterId;
+use opendut_types::project::ProjectId;
 
 use super::*;
 const RETRIES: u32 = 5;
@@ -77,7 +78,7 @@ async fn create_group(fixture: Fixture) -> anyhow::Result<()> {
     let client = DefaultClient::create(fixture.base_url(), None, None, Some(Box::new(requester)), TIMEOUT, RETRIES)?;
 
     let result = client.create_netbird_group(
-        fixture.cluster_id().into(),
+        (ProjectId::DEFAULT, fixture.cluster_id()).into(),
         vec![fixture.netbird_peer_id()],
     ).await?;
 
@@ -268,27 +269,128 @@ async fn create_a_setup_key(fixture: Fixture) -> anyhow::Result<()> {
 
 #[rstest]
 #[tokio::test]
-async fn create_access_control_rule(fixture: Fixture) -> anyhow::Result<()> {
+async fn list_and_revoke_setup_keys(fixture: Fixture) -> anyhow::Result<()> {
 
     let requester = fixture.requester(|fixture, request| {
+        match (request.method(), request.url().path()) {
+            (&Method::GET, "/api/setup-keys") => {
+                let response = http::Response::builder()
+                    .body(
+                        json!([
+                            {
+                                "id": "2531583362",
+                                "key": "A6160****",
+                                "name": fixture.peer_setup_key_name(),
+                                "expires": "2023-06-01T14:47:22.291057Z",
+                                "type": "one-off",
+                                "valid": true,
+                                "revoked": false,
+                                "used_times": 0,
+                                "last_used": "0001-01-01T00:00:00Z",
+                                "state": "valid",
+                                "auto_groups": [
+                                    fixture.netbird_group_id()
+                                ],
+                                "updated_at": "2023-05-05T09:00:35.477782Z",
+                                "usage_limit": 0
+                            }
+                        ]).to_string()
+                    ).unwrap();
+
+                Ok(Response::from(response))
+            }
+            (&Method::PUT, "/api/setup-keys/2531583362") => {
+                let request = request.body().unwrap().as_bytes().unwrap();
+                let request: serde_json::Value = serde_json::from_slice(request).unwrap();
+
+                let expectation = json!({
+                    "name": fixture.peer_setup_key_name(),
+                    "type": "one-off",
+                    "expires_in": 86400,
+                    "revoked": true,
+                    "auto_groups": [
+                        fixture.netbird_group_id()
+                    ],
+                    "usage_limit": 0,
+                });
+
+                assert_that!(request, eq(expectation));
+
+                Ok(Response::from(http::Response::builder()
+                    .body("{}")
+                    .unwrap()))
+            }
+            (method, path) => {
+                panic!("Unexpected request {method} {path}")
+            }
+        }
+    });
+
+    let client = DefaultClient::create(fixture.base_url(), None, None, Some(Box::new(requester)), TIMEOUT, RETRIES)?;
+
+    let setup_keys = client.list_netbird_setup_keys().await?;
+
+    assert_that!(setup_keys, elements_are![
+        matches_pattern!(netbird::SetupKeyInfo {
+            id: eq("2531583362"),
+            name: eq(fixture.peer_setup_key_name()),
+            state: eq(netbird::SetupKeyState::Valid),
+        })
+    ]);
+    assert_that!(netbird::setup_key_peer_id(&setup_keys[0].name), some(eq(fixture.peer_id())));
+
+    let result = client.revoke_netbird_setup_key(&setup_keys[0]).await;
+
+    assert_that!(result, ok(anything()));
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn create_policy(fixture: Fixture) -> anyhow::Result<()> {
+
+    let requester = fixture.requester(|fixture, request| {
+        assert_that!(request.url().path(), eq("/api/policies"));
         let request = request.body().unwrap().as_bytes().unwrap();
         let request: serde_json::Value = serde_json::from_slice(request).unwrap();
 
+        let policy_name = String::from(fixture.netbird_cluster_policy_name());
         let expectation = json!({
-            "name": String::from(fixture.netbird_cluster_rule_name()),
-            "description": fixture.netbird_cluster_rule_name().description(),
-            "disabled": false,
-            "flow": "bidirect",
-            "sources": [fixture.netbird_group_id()],
-            "destinations": [fixture.netbird_group_id()],
+            "name": policy_name,
+            "description": fixture.netbird_cluster_policy_name().description(),
+            "enabled": true,
+            "rules": [
+                {
+                    "name": format!("{policy_name}-0"),
+                    "description": fixture.netbird_cluster_policy_name().description(),
+                    "enabled": true,
+                    "action": "accept",
+                    "bidirectional": false,
+                    "protocol": "udp",
+                    "ports": ["10001"],
+                    "sources": [fixture.netbird_group_id()],
+                    "destinations": ["ch8i4ug6lnn4g9hqv7m1"],
+                },
+                {
+                    "name": format!("{policy_name}-1"),
+                    "description": fixture.netbird_cluster_policy_name().description(),
+                    "enabled": true,
+                    "action": "accept",
+                    "bidirectional": true,
+                    "protocol": "icmp",
+                    "sources": [fixture.netbird_group_id()],
+                    "destinations": ["ch8i4ug6lnn4g9hqv7m1"],
+                },
+            ],
         });
 
         assert_that!(request, eq(expectation));
 
         let group = json!({
             "id": fixture.netbird_group_id(),
-            "name": String::from(fixture.netbird_cluster_rule_name()),
-            "peers_count": 0,
+            "name": String::from(fixture.peer_netbird_group_name()),
+            "peers_count": 1,
             "issued": "api"
         });
 
@@ -296,12 +398,23 @@ async fn create_access_control_rule(fixture: Fixture) -> anyhow::Result<()> {
             .body(
                 json!({
                     "id": "ch8i4ug6lnn4g9hqv7mg",
-                    "name": String::from(fixture.netbird_cluster_rule_name()),
-                    "description": fixture.netbird_cluster_rule_name().description(),
-                    "disabled": false,
-                    "flow": "bidirect",
-                    "sources": [group],
-                    "destinations": [group]
+                    "name": policy_name,
+                    "description": fixture.netbird_cluster_policy_name().description(),
+                    "enabled": true,
+                    "rules": [
+                        {
+                            "id": "ch8i4ug6lnn4g9hqv7mh",
+                            "name": format!("{policy_name}-0"),
+                            "description": fixture.netbird_cluster_policy_name().description(),
+                            "enabled": true,
+                            "action": "accept",
+                            "bidirectional": false,
+                            "protocol": "udp",
+                            "ports": ["10001"],
+                            "sources": [group],
+                            "destinations": [group]
+                        }
+                    ]
                 }).to_string()
             ).unwrap();
 
@@ -310,32 +423,91 @@ async fn create_access_control_rule(fixture: Fixture) -> anyhow::Result<()> {
 
     let client = DefaultClient::create(fixture.base_url(), None, None, Some(Box::new(requester)), TIMEOUT, RETRIES)?;
 
-    let group = netbird::Group {
-        id: fixture.netbird_group_id(),
-        name: fixture.cluster_id().into(),
-        peers_count: 0,
-        peers: vec![],
-    };
+    let sources = vec![fixture.netbird_group_id()];
+    let destinations = vec![netbird::GroupId::from("ch8i4ug6lnn4g9hqv7m1")];
 
-    client.create_netbird_self_access_control_rule(
-        group,
-        fixture.cluster_id().into(),
+    client.create_netbird_policy(
+        (ProjectId::DEFAULT, fixture.cluster_id()).into(),
+        vec![
+            netbird::PolicyRuleSpec { protocol: netbird::PolicyProtocol::Udp, ports: vec![String::from("10001")], bidirectional: false, sources: sources.clone(), destinations: destinations.clone() },
+            netbird::PolicyRuleSpec { protocol: netbird::PolicyProtocol::Icmp, ports: vec![], bidirectional: true, sources, destinations },
+        ],
     ).await?;
 
     Ok(())
 }
 
+#[rstest]
+#[tokio::test]
+async fn get_policy(fixture: Fixture) -> anyhow::Result<()> {
+
+    let requester = fixture.requester(|fixture, request| {
+        assert_that!(request.method(), eq(&Method::GET));
+        assert_that!(request.url().path(), eq("/api/policies"));
+
+        let group = json!({
+            "id": fixture.netbird_group_id(),
+            "name": String::from(fixture.cluster_netbird_group_name()),
+            "peers_count": 2,
+            "issued": "api"
+        });
+
+        let response = http::Response::builder()
+            .body(
+                json!([
+                    {
+                        "id": "ch8i4ug6lnn4g9hqv7mg",
+                        "name": String::from(fixture.netbird_cluster_policy_name()),
+                        "description": "This is a default rule that allows connections between all the resources",
+                        "enabled": true,
+                        "rules": [
+                            {
+                                "id": "ch8i4ug6lnn4g9hqv7mh",
+                                "name": "Default",
+                                "description": "This is a default rule that allows connections between all the resources",
+                                "enabled": true,
+                                "action": "accept",
+                                "bidirectional": true,
+                                "protocol": "all",
+                                "ports": null,
+                                "sources": [group],
+                                "destinations": [group]
+                            }
+                        ]
+                    }
+                ]).to_string()
+            ).unwrap();
+
+        Ok(Response::from(response))
+    });
+
+    let client = DefaultClient::create(fixture.base_url(), None, None, Some(Box::new(requester)), TIMEOUT, RETRIES)?;
+
+    let policy = client.get_netbird_policy(&fixture.netbird_cluster_policy_name()).await?;
+
+    assert_that!(policy.id, eq(netbird::PolicyId::from("ch8i4ug6lnn4g9hqv7mg")));
+    assert_that!(policy.rules[0].spec(), eq(netbird::PolicyRuleSpec {
+        protocol: netbird::PolicyProtocol::All,
+        ports: vec![],
+        bidirectional: true,
+        sources: vec![fixture.netbird_group_id()],
+        destinations: vec![fixture.netbird_group_id()],
+    }));
+
+    Ok(())
+}
+
 #[fixture]
 fn fixture() -> Fixture {
     let base_url = Url::parse("https://localhost/api/").unwrap();
     let peer_id = PeerId::from(uuid!("b7dd1960-9ab5-4f3a-851d-6b68a90099eb"));
     let cluster_id = ClusterId::from(uuid!("999f8513-d7ab-43fe-9bf0-091abaff2a97"));
     let netbird_group_id = netbird::GroupId::from("ch8i4ug6lnn4g9hqv7m0");
-    let cluster_netbird_group_name = netbird::GroupName::Cluster(cluster_id);
+    let cluster_netbird_group_name = netbird::GroupName::Cluster(ProjectId::DEFAULT, cluster_id);
     let peer_netbird_group_name = netbird::GroupName::Peer(peer_id);
     let netbird_peer_id = netbird::PeerId(String::from("chacbco6lnnbn6cg5s90"));
     let netbird_peer_setup_key_name = netbird::setup_key_name_format(peer_id);
-    let netbird_cluster_rule_name = netbird::RuleName::Cluster(cluster_id).into();
+    let netbird_cluster_policy_name = netbird::PolicyName::Cluster(ProjectId::DEFAULT, cluster_id);
     Fixture {
         base_url,
         peer_id,
@@ -345,7 +517,7 @@ fn fixture() -> Fixture {
         peer_netbird_group_name,
         netbird_peer_id,
         netbird_peer_setup_key_name,
-        netbird_cluster_rule_name
+        netbird_cluster_policy_name
     }
 }
 
@@ -359,7 +531,7 @@ struct Fixture {
     peer_netbird_group_name: netbird::GroupName,
     netbird_peer_id: netbird::PeerId,
     netbird_peer_setup_key_name: String,
-    netbird_cluster_rule_name: netbird::RuleName,
+    netbird_cluster_policy_name: netbird::PolicyName,
 }
 
 impl Fixture {
@@ -395,8 +567,8 @@ impl Fixture {
         Clone::clone(&self.netbird_peer_setup_key_name)
     }
 
-    pub fn netbird_cluster_rule_name(&self) -> netbird::RuleName {
-        Clone::clone(&self.netbird_cluster_rule_name)
+    pub fn netbird_cluster_policy_name(&self) -> netbird::PolicyName {
+        Clone::clone(&self.netbird_cluster_policy_name)
     }
 
     pub fn requester<F>(&self, handler: F) -> MockRequester<F>
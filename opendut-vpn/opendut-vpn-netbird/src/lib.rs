@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
 use std::path::PathBuf;
@@ -11,16 +12,19 @@ use tracing::{debug, error, info, trace, warn};
 pub use netbird::Token as NetbirdToken;
 use opendut_types::cluster::ClusterId;
 use opendut_types::peer::PeerId;
+use opendut_types::project::ProjectId;
+use opendut_types::vpn::reconciliation::VpnReconciliationReport;
 use opendut_types::vpn::VpnPeerConfiguration;
-use opendut_vpn::{CreateClusterError, CreatePeerError, CreateVpnPeerConfigurationError, DeleteClusterError, DeletePeerError, VpnManagementClient};
+use opendut_vpn::{ClusterNetworkPolicy, CreateClusterError, CreatePeerError, CreateVpnPeerConfigurationError, DeleteClusterError, DeletePeerError, ExpectedVpnState, NetworkProtocol, ReconcileError, VpnManagementClient};
 
 use crate::client::{Client, DefaultClient};
-use crate::netbird::error::{CreateClientError, CreateSetupKeyError, GetGroupError, GetRulesError, RequestError};
+use crate::netbird::error::{CreateClientError, CreateSetupKeyError, GetGroupError, GetPolicyError, RequestError};
 use crate::netbird::GroupName;
 
 mod client;
 mod routes;
 mod netbird;
+mod reconciler;
 
 type Inner = Box<dyn Client + Send + Sync>;
 
@@ -70,9 +74,16 @@ impl NetbirdManagementClient {
 impl VpnManagementClient for NetbirdManagementClient {
 
     #[tracing::instrument(skip(self), level="trace")]
-    async fn create_cluster(&self, cluster_id: ClusterId, peers: &[PeerId]) -> Result<(), CreateClusterError> {
+    async fn create_cluster(&self, project_id: ProjectId, cluster_id: ClusterId, peers: &[PeerId], policy: &ClusterNetworkPolicy) -> Result<(), CreateClusterError> {
 
-        match self.delete_cluster(cluster_id).await {
+        let unsupported_protocol = policy.rules.iter()
+            .map(|rule| rule.protocol)
+            .find(|protocol| netbird::UNSUPPORTED_PROTOCOLS.contains(protocol));
+        if let Some(protocol) = unsupported_protocol {
+            return Err(CreateClusterError::UnsupportedProtocol { cluster_id, protocol });
+        }
+
+        match self.delete_cluster(project_id, cluster_id).await {
             Ok(_) => debug!("Deleted a previous cluster with ID <{cluster_id}> before creating the new cluster."),
             Err(cause) => match cause {
                 DeleteClusterError::NotFound { cluster_id, message } => trace!("Did not need to delete a previous cluster with ID <{cluster_id}> before creating the new cluster. ({message})"),
@@ -82,11 +93,13 @@ impl VpnManagementClient for NetbirdManagementClient {
             }
         };
 
+        let mut self_groups = HashMap::new();
         let netbird_peers: Vec<netbird::PeerId> = {
             let mut netbird_peers = vec![];
             for peer_id in peers {
                 let group = self.inner.get_netbird_group(&(*peer_id).into()).await
                     .map_err(|error| CreateClusterError::PeerResolutionFailure { peer_id: *peer_id, cluster_id, error: error.into() })?;
+                self_groups.insert(*peer_id, group.id);
                 let peer = group.peers.into_iter().next()
                     .ok_or(CreateClusterError::PeerResolutionFailure { peer_id: *peer_id, cluster_id, error: anyhow!("Self-Group does not contain expected peer!").into() })?;
                 netbird_peers.push(peer.id);
@@ -94,26 +107,36 @@ impl VpnManagementClient for NetbirdManagementClient {
             netbird_peers
         };
 
-        let group = self.inner.create_netbird_group(cluster_id.into(), netbird_peers).await
+        let policy_rules = netbird::PolicyRuleSpec::translate(policy, &self_groups)
+            .map_err(|cause| match cause {
+                netbird::TranslatePolicyError::SelfGroupNotFound { peer_id } => CreateClusterError::PeerResolutionFailure { peer_id, cluster_id, error: cause.into() },
+                netbird::TranslatePolicyError::UnsupportedProtocol { protocol } => CreateClusterError::UnsupportedProtocol { cluster_id, protocol },
+            })?;
+
+        self.inner.create_netbird_group((project_id, cluster_id).into(), netbird_peers).await
             .map_err(|error| CreateClusterError::CreationFailure { cluster_id, error: error.into() })?;
 
-        self.inner.create_netbird_self_access_control_rule(group, cluster_id.into()).await
-            .map_err(|error| CreateClusterError::AccessControlRuleCreationFailure { cluster_id, error: error.into() })?;
+        if policy_rules.is_empty() {
+            debug!("Not creating a NetBird policy for cluster <{cluster_id}>, since its members do not exchange any traffic.");
+        } else {
+            self.inner.create_netbird_policy((project_id, cluster_id).into(), policy_rules).await
+                .map_err(|error| CreateClusterError::AccessControlRuleCreationFailure { cluster_id, error: error.into() })?;
+        }
 
         Ok(())
     }
 
     #[tracing::instrument(skip(self), level="trace")]
-    async fn delete_cluster(&self, cluster_id: ClusterId) -> Result<(), DeleteClusterError> {
-        let rule_name = netbird::RuleName::Cluster(cluster_id);
-        match self.inner.get_netbird_rule(&rule_name).await {
-            Ok(rule) => {
-                match self.inner.delete_netbird_rule(&rule.id).await {
-                    Ok(_) => debug!("Deleted NetBird rule with name '{}' and NetBird Rule ID '{}'.", rule.name, rule.id.0),
+    async fn delete_cluster(&self, project_id: ProjectId, cluster_id: ClusterId) -> Result<(), DeleteClusterError> {
+        let policy_name = netbird::PolicyName::Cluster(project_id, cluster_id);
+        match self.inner.get_netbird_policy(&policy_name).await {
+            Ok(policy) => {
+                match self.inner.delete_netbird_policy(&policy.id).await {
+                    Ok(_) => debug!("Deleted NetBird policy with name '{}' and NetBird Policy ID '{}'.", policy.name, policy.id.0),
                     Err(cause) => return match cause {
                         RequestError::IllegalStatus(error) => {
                             if let Some(http::StatusCode::NOT_FOUND) = error.status() {
-                                Err(DeleteClusterError::NotFound { cluster_id, message: format!("Received '404 Not Found' when deleting rule for cluster <{cluster_id}> with NetBird rule ID <{netbird_rule}>.", netbird_rule = rule.id.0) })
+                                Err(DeleteClusterError::NotFound { cluster_id, message: format!("Received '404 Not Found' when deleting policy for cluster <{cluster_id}> with NetBird policy ID <{netbird_policy}>.", netbird_policy = policy.id.0) })
                             } else {
                                 Err(DeleteClusterError::DeletionFailure { cluster_id, error: error.into() })
                             }
@@ -121,22 +144,22 @@ impl VpnManagementClient for NetbirdManagementClient {
                         RequestError::IllegalRequest(error, code) => {
                             Err(DeleteClusterError::DeletionFailure {
                                 cluster_id,
-                                error: anyhow!("Received status code '{code}' when deleting cluster <{cluster_id}> with NetBird rule ID <{netbird_rule}>:\n  {error}", code=code, cluster_id=cluster_id, netbird_rule=rule.id.0, error=error).into(),
+                                error: anyhow!("Received status code '{code}' when deleting cluster <{cluster_id}> with NetBird policy ID <{netbird_policy}>:\n  {error}", code=code, cluster_id=cluster_id, netbird_policy=policy.id.0, error=error).into(),
                             })
                         }
                         other => Err(DeleteClusterError::DeletionFailure { cluster_id, error: other.into() }),
                     }
                 }
             }
-            Err(GetRulesError::RuleNotFound { .. }) => {
-                // No rule found, so no need to delete it.
+            Err(GetPolicyError::PolicyNotFound { .. }) => {
+                // No policy found, so no need to delete it.
             }
             Err(cause) => {
-                return Err(DeleteClusterError::DeletionFailure { cluster_id, error: anyhow!("Failed to get cluster rule '{rule_name}' to be deleted.\n {cause}").into() });
+                return Err(DeleteClusterError::DeletionFailure { cluster_id, error: anyhow!("Failed to get cluster policy '{policy_name}' to be deleted.\n {cause}").into() });
             }
         };
 
-        let group_name = netbird::GroupName::from(cluster_id);
+        let group_name = netbird::GroupName::from((project_id, cluster_id));
         match self.inner.get_netbird_group(&group_name).await {
             Ok(group) => {
                 match self.inner.delete_netbird_group(&group.id).await {
@@ -251,6 +274,15 @@ impl VpnManagementClient for NetbirdManagementClient {
             setup_key: opendut_types::vpn::netbird::SetupKey::from(setup_key.key),
         })
     }
+
+    fn unsupported_protocols(&self) -> Vec<NetworkProtocol> {
+        netbird::UNSUPPORTED_PROTOCOLS.to_vec()
+    }
+
+    #[tracing::instrument(skip(self, expected), level="trace")]
+    async fn reconcile(&self, expected: &ExpectedVpnState, dry_run: bool) -> Result<VpnReconciliationReport, ReconcileError> {
+        reconciler::reconcile(self, expected, dry_run).await
+    }
 }
 
 #[cfg(test)]
@@ -264,24 +296,28 @@ mod test {
 
     use opendut_types::cluster::ClusterId;
     use opendut_types::peer::PeerId;
+    use opendut_types::project::ProjectId;
     use opendut_types::vpn;
     use opendut_types::vpn::VpnPeerConfiguration;
-    use opendut_vpn::VpnManagementClient;
+    use opendut_types::util::Port;
+    use opendut_types::vpn::reconciliation::VpnDriftOutcome;
+    use opendut_vpn::{ClusterNetworkPolicy, ClusterNetworkRule, ExpectedVpnState, NetworkProtocol, VpnManagementClient};
 
     use crate::{netbird, NetbirdManagementClient};
     use crate::client::Client;
-    use crate::netbird::error::{CreateSetupKeyError, GetGroupError, GetRulesError, RequestError};
+    use crate::netbird::error::{CreateSetupKeyError, GetGroupError, GetPolicyError, RequestError};
     use crate::netbird::GroupPeerInfo;
 
     #[tokio::test]
-    async fn A_NetbirdManagementClient_should_create_a_cluster_by_creating_a_netbird_group_containing_all_peers_of_the_cluster() -> Result<()> {
+    async fn A_NetbirdManagementClient_should_create_a_cluster_by_creating_a_netbird_group_containing_all_peers_of_the_cluster_prefixed_with_its_project() -> Result<()> {
 
         let cluster_id = ClusterId::from(uuid!("6a6510a9-031b-4834-a4f7-454cc401fe13"));
         let peer_a_id = PeerId::from(uuid!("d61bed7b-2fec-4a5b-a937-d6a791cb5ff9"));
         let peer_b_id = PeerId::from(uuid!("267ca2c0-942e-4780-857e-c153ddcf2328"));
         let peer_a_group_name = netbird::GroupName::from(peer_a_id);
         let peer_b_group_name = netbird::GroupName::from(peer_b_id);
-        let cluster_group_name = netbird::GroupName::from(cluster_id);
+        let project_id = ProjectId::random();
+        let cluster_group_name = netbird::GroupName::from((project_id, cluster_id));
         let cluster_group_id = netbird::GroupId::from("cluster-group");
         let peer_a_group = netbird::Group {
             id: netbird::GroupId::from("peer-a-group"),
@@ -303,8 +339,8 @@ mod test {
         };
 
         let fixture = Fixture::setup(|mock_client| {
-            mock_client.expect_get_netbird_rule()
-                .returning(|rule_name| Err(GetRulesError::RuleNotFound { rule_name: rule_name.to_owned() }));
+            mock_client.expect_get_netbird_policy()
+                .returning(|policy_name| Err(GetPolicyError::PolicyNotFound { policy_name: policy_name.to_owned() }));
             mock_client.expect_get_netbird_group()
                 .returning({
                     let cluster_group_name = Clone::clone(&cluster_group_name);
@@ -337,13 +373,26 @@ mod test {
                     let cluster_group = Clone::clone(&cluster_group);
                     move |_, _| Ok(Clone::clone(&cluster_group))
                 });
-            mock_client.expect_create_netbird_self_access_control_rule()
+            mock_client.expect_create_netbird_policy()
                 .times(1)
-                .withf(move |actual_group, _rule_name| actual_group == &cluster_group)
+                .withf(move |actual_policy_name, actual_rules| {
+                    actual_policy_name == &netbird::PolicyName::Cluster(project_id, cluster_id)
+                    && actual_rules == &vec![netbird::PolicyRuleSpec {
+                        protocol: netbird::PolicyProtocol::Udp,
+                        ports: vec![String::from("10001")],
+                        bidirectional: false,
+                        sources: vec![netbird::GroupId::from("peer-b-group")],
+                        destinations: vec![netbird::GroupId::from("peer-a-group")],
+                    }]
+                })
                 .returning(|_, _| Ok(()));
         });
 
-        assert_that!(fixture.testee.create_cluster(cluster_id, &vec![peer_a_id, peer_b_id]).await, ok(anything()));
+        let policy = ClusterNetworkPolicy {
+            rules: vec![ClusterNetworkRule { protocol: NetworkProtocol::Udp, ports: vec![Port(10001)], source: peer_b_id, destination: peer_a_id, bidirectional: false }],
+        };
+
+        assert_that!(fixture.testee.create_cluster(project_id, cluster_id, &[peer_a_id, peer_b_id], &policy).await, ok(anything()));
 
         Ok(())
     }
@@ -423,6 +472,69 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn A_NetbirdManagementClient_should_not_delete_an_orphaned_self_group_whose_members_changed_since_planning() -> Result<()> {
+
+        let peer_id = PeerId::from(uuid!("d61bed7b-2fec-4a5b-a937-d6a791cb5ff9"));
+        let orphaned_group = netbird::Group {
+            id: netbird::GroupId::from("peer-self-group"),
+            name: netbird::GroupName::from(peer_id),
+            peers_count: 0,
+            peers: vec![],
+        };
+        let joined_group = netbird::Group {
+            peers_count: 1,
+            peers: vec![GroupPeerInfo { id: netbird::PeerId::from("netbird-peer"), name: String::from("netbird-peer") }],
+            ..Clone::clone(&orphaned_group)
+        };
+
+        let fixture = Fixture::setup(|mock_client| {
+            let listed = std::sync::atomic::AtomicBool::new(false);
+            mock_client.expect_list_netbird_groups()
+                .times(2)
+                .returning(move || {
+                    if listed.swap(true, std::sync::atomic::Ordering::SeqCst) {
+                        Ok(vec![Clone::clone(&joined_group)])
+                    } else {
+                        Ok(vec![Clone::clone(&orphaned_group)])
+                    }
+                });
+            mock_client.expect_list_netbird_policies()
+                .returning(|| Ok(vec![]));
+            mock_client.expect_list_netbird_setup_keys()
+                .returning(|| Ok(vec![]));
+            mock_client.expect_delete_netbird_peer()
+                .never();
+            mock_client.expect_delete_netbird_group()
+                .never();
+        });
+
+        let report = fixture.testee.reconcile(&ExpectedVpnState::default(), false).await?;
+
+        verify_that!(report.drifts, elements_are![
+            field!(vpn::reconciliation::VpnDrift.outcome, matches_pattern!(VpnDriftOutcome::Failed { .. })),
+        ])
+    }
+
+    #[tokio::test]
+    async fn A_NetbirdManagementClient_should_refuse_to_create_a_cluster_exchanging_traffic_of_protocols_unknown_to_netbird() -> Result<()> {
+
+        let cluster_id = ClusterId::from(uuid!("6a6510a9-031b-4834-a4f7-454cc401fe13"));
+        let leader = PeerId::from(uuid!("d61bed7b-2fec-4a5b-a937-d6a791cb5ff9"));
+        let member = PeerId::from(uuid!("a6d6b0a4-5a4e-4bba-b4b7-25e9a8d1c0a1"));
+
+        let fixture = Fixture::setup(|_| {});
+
+        let policy = ClusterNetworkPolicy {
+            rules: vec![ClusterNetworkRule { protocol: NetworkProtocol::Gre, ports: vec![], source: member, destination: leader, bidirectional: true }],
+        };
+
+        verify_that!(
+            fixture.testee.create_cluster(ProjectId::DEFAULT, cluster_id, &[leader, member], &policy).await,
+            err(matches_pattern!(opendut_vpn::CreateClusterError::UnsupportedProtocol { cluster_id: eq(cluster_id), protocol: eq(NetworkProtocol::Gre) }))
+        )
+    }
+
     struct Fixture {
         testee: NetbirdManagementClient,
     }
@@ -451,13 +563,17 @@ mod test {
         impl Client for MockClient {
             async fn create_netbird_group(&self, name: netbird::GroupName, peers: Vec<netbird::PeerId>) -> std::result::Result<netbird::Group, RequestError>;
             async fn get_netbird_group(&self, group_name: &netbird::GroupName) -> std::result::Result<netbird::Group, GetGroupError>;
+            async fn list_netbird_groups(&self) -> std::result::Result<Vec<netbird::Group>, RequestError>;
             async fn delete_netbird_group(&self, group_id: &netbird::GroupId) -> std::result::Result<(), RequestError>;
             async fn get_netbird_peer(&self, peer_id: &netbird::PeerId) -> std::result::Result<netbird::Peer, RequestError>;
             async fn delete_netbird_peer(&self, peer_id: &netbird::PeerId) -> std::result::Result<(), RequestError>;
-            async fn create_netbird_self_access_control_rule(&self, group: netbird::Group, rule_name: netbird::RuleName) -> std::result::Result<(), RequestError>;
-            async fn get_netbird_rule(&self, rule_name: &netbird::RuleName) -> std::result::Result<netbird::Rule, GetRulesError>;
-            async fn delete_netbird_rule(&self, rule_id: &netbird::RuleId) -> std::result::Result<(), RequestError>;
+            async fn create_netbird_policy(&self, policy_name: netbird::PolicyName, rules: Vec<netbird::PolicyRuleSpec>) -> std::result::Result<(), RequestError>;
+            async fn get_netbird_policy(&self, policy_name: &netbird::PolicyName) -> std::result::Result<netbird::Policy, GetPolicyError>;
+            async fn list_netbird_policies(&self) -> std::result::Result<Vec<netbird::Policy>, RequestError>;
+            async fn delete_netbird_policy(&self, policy_id: &netbird::PolicyId) -> std::result::Result<(), RequestError>;
             async fn generate_netbird_setup_key(&self, peer_id: PeerId) -> std::result::Result<netbird::SetupKey, CreateSetupKeyError>;
+            async fn list_netbird_setup_keys(&self) -> std::result::Result<Vec<netbird::SetupKeyInfo>, RequestError>;
+            async fn revoke_netbird_setup_key(&self, setup_key: &netbird::SetupKeyInfo) -> std::result::Result<(), RequestError>;
         }
     }
 }
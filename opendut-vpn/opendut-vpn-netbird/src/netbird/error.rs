@@ -6,7 +6,7 @@ use reqwest::header::InvalidHeaderValue;
 use opendut_types::peer::PeerId;
 
 use crate::netbird::group::GroupName;
-use crate::netbird::rules::RuleName;
+use crate::netbird::policy::PolicyName;
 
 #[derive(thiserror::Error, Debug)]
 pub enum GetGroupError {
@@ -22,14 +22,14 @@ pub enum GetGroupError {
 }
 
 #[derive(thiserror::Error, Debug)]
-pub enum GetRulesError {
-    #[error("A rule with name '{rule_name}' does not exist!")]
-    RuleNotFound { rule_name: RuleName },
-    #[error("Multiple rules with name '{rule_name}' exist!")]
-    MultipleRulesFound { rule_name: RuleName },
-    #[error("Could not request rule '{rule_name}:\n  {cause}")]
+pub enum GetPolicyError {
+    #[error("A policy with name '{policy_name}' does not exist!")]
+    PolicyNotFound { policy_name: PolicyName },
+    #[error("Multiple policies with name '{policy_name}' exist!")]
+    MultiplePoliciesFound { policy_name: PolicyName },
+    #[error("Could not request policy '{policy_name}':\n  {cause}")]
     RequestFailure {
-        rule_name: RuleName,
+        policy_name: PolicyName,
         cause: RequestError
     }
 }
@@ -5,6 +5,9 @@ use serde::{Deserialize, Serialize};
 
 use opendut_types::cluster::ClusterId;
 use opendut_types::peer::PeerId;
+use opendut_types::project::ProjectId;
+
+use crate::netbird::project;
 
 #[derive(thiserror::Error, Debug)]
 #[error("Cannot create GroupName from '{value}':\n  {cause}")]
@@ -17,7 +20,7 @@ pub struct InvalidGroupNameError {
 #[serde(try_from = "String", into = "String")]
 pub enum GroupName {
     Peer(PeerId),
-    Cluster(ClusterId),
+    Cluster(ProjectId, ClusterId),
     Other(String),
 }
 
@@ -32,9 +35,9 @@ impl From<PeerId> for GroupName {
     }
 }
 
-impl From<ClusterId> for GroupName {
-    fn from(value: ClusterId) -> Self {
-        Self::Cluster(value)
+impl From<(ProjectId, ClusterId)> for GroupName {
+    fn from((project_id, cluster_id): (ProjectId, ClusterId)) -> Self {
+        Self::Cluster(project_id, cluster_id)
     }
 }
 
@@ -43,14 +46,18 @@ impl TryFrom<&str> for GroupName {
     type Error = InvalidGroupNameError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let Ok((project_id, unprefixed)) = project::unprefixed(value) else {
+            return Ok(Self::Other(value.to_owned()));
+        };
+
         if let Some(uuid) = value.strip_prefix(GroupName::PEER_GROUP_PREFIX) {
             PeerId::try_from(uuid)
                 .map(Self::Peer)
                 .map_err(|cause| InvalidGroupNameError { value: value.to_owned(), cause: cause.into() })
         }
-        else if let Some(uuid) = value.strip_prefix(GroupName::CLUSTER_GROUP_PREFIX) {
+        else if let Some(uuid) = unprefixed.strip_prefix(GroupName::CLUSTER_GROUP_PREFIX) {
             ClusterId::try_from(uuid)
-                .map(Self::Cluster)
+                .map(|cluster_id| Self::Cluster(project_id, cluster_id))
                 .map_err(|cause| InvalidGroupNameError { value: value.to_owned(), cause: cause.into() })
         }
         else {
@@ -72,7 +79,7 @@ impl From<&GroupName> for String {
     fn from(value: &GroupName) -> Self {
         match value {
             GroupName::Peer(id) => format!("{}{}", GroupName::PEER_GROUP_PREFIX, id.0),
-            GroupName::Cluster(id) => format!("{}{}", GroupName::CLUSTER_GROUP_PREFIX, id.0),
+            GroupName::Cluster(project_id, id) => project::prefixed(*project_id, format!("{}{}", GroupName::CLUSTER_GROUP_PREFIX, id.0)),
             GroupName::Other(name) => name.to_owned(),
         }
     }
@@ -131,16 +138,32 @@ mod test {
         let group_name = "opendut-cluster-group-5c806e1c-448e-4dda-854a-20a33cfe1cfe";
 
         let from_string = GroupName::try_from(String::from(group_name));
-        let from_id = GroupName::from(cluster_id);
+        let from_id = GroupName::from((ProjectId::DEFAULT, cluster_id));
 
-        assert_that!(from_string.as_ref(), ok(eq(&GroupName::Cluster(cluster_id))));
-        assert_that!(&from_id, eq(&GroupName::Cluster(cluster_id)));
+        assert_that!(from_string.as_ref(), ok(eq(&GroupName::Cluster(ProjectId::DEFAULT, cluster_id))));
+        assert_that!(&from_id, eq(&GroupName::Cluster(ProjectId::DEFAULT, cluster_id)));
         assert_that!(String::from(from_string.unwrap()), eq(group_name));
         assert_that!(String::from(from_id), eq(group_name));
 
         Ok(())
     }
 
+    #[test]
+    fn A_GroupName_for_a_cluster_of_a_project_should_be_prefixed_with_the_project() -> anyhow::Result<()> {
+
+        let project_id = ProjectId::try_from("0bd4a4ba-4b5f-4e4e-9b43-5a3cbd0b4b7e")?;
+        let cluster_id = ClusterId::try_from("5c806e1c-448e-4dda-854a-20a33cfe1cfe")?;
+        let group_name = "opendut-project-0bd4a4ba-4b5f-4e4e-9b43-5a3cbd0b4b7e-opendut-cluster-group-5c806e1c-448e-4dda-854a-20a33cfe1cfe";
+
+        let from_string = GroupName::try_from(String::from(group_name));
+        let from_id = GroupName::from((project_id, cluster_id));
+
+        assert_that!(from_string.as_ref(), ok(eq(&GroupName::Cluster(project_id, cluster_id))));
+        assert_that!(String::from(from_id), eq(group_name));
+
+        Ok(())
+    }
+
     #[test]
     fn A_GroupName_for_a_cluster_should_not_be_convertable_from_string_containing_an_invalid_uuid() {
 
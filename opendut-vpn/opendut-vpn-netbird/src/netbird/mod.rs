@@ -2,8 +2,8 @@
 
 pub(crate) use group::{Group, GroupId, GroupName, GroupPeerInfo};
 pub(crate) use peer::{Peer, PeerId};
-pub(crate) use rules::{Rule, RuleFlow, RuleId, RuleName};
-pub(crate) use setup_key::{name_format as setup_key_name_format, SetupKey, State as SetupKeyState, Timestamp as SetupKetTimeStamp, Type as SetupKeyType};
+pub(crate) use policy::{GroupInfo, Policy, PolicyAction, PolicyId, PolicyName, PolicyProtocol, PolicyRule, PolicyRuleSpec, TranslatePolicyError, UNSUPPORTED_PROTOCOLS};
+pub(crate) use setup_key::{name_format as setup_key_name_format, peer_id_of as setup_key_peer_id, SetupKey, SetupKeyInfo, State as SetupKeyState, Timestamp as SetupKetTimeStamp, Type as SetupKeyType};
 pub use token::Token;
 
 pub mod error;
@@ -11,5 +11,6 @@ pub mod error;
 mod token;
 mod group;
 mod setup_key;
-mod rules;
+mod policy;
 mod peer;
+mod project;
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::ops::Not;
+
+use serde::{Deserialize, Serialize};
+
+use opendut_types::cluster::ClusterId;
+use opendut_types::peer::PeerId;
+use opendut_types::project::ProjectId;
+use opendut_vpn::{ClusterNetworkPolicy, NetworkProtocol};
+
+use crate::netbird::group::GroupId;
+use crate::netbird::project;
+
+#[derive(thiserror::Error, Debug)]
+#[error("Cannot create PolicyName from '{value}':\n  {cause}")]
+pub struct InvalidPolicyNameError {
+    value: String,
+    cause: Box<dyn Error>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum PolicyName {
+    Cluster(ProjectId, ClusterId),
+    Other(String),
+}
+
+impl PolicyName {
+    /// Matches the name of the access control rules, which were created for clusters before switching to policies.
+    /// NetBird lists these rules as policies, so they are recognized as policies of the respective cluster.
+    const CLUSTER_POLICY_PREFIX: &'static str = "opendut-cluster-rule-";
+
+    pub fn description(&self) -> String {
+        match self {
+            PolicyName::Cluster(project_id, cluster_id) => format!("Policy for the openDuT cluster <{cluster_id}> of project <{project_id}>."),
+            PolicyName::Other(name) => name.to_owned(),
+        }
+    }
+}
+
+impl From<(ProjectId, ClusterId)> for PolicyName {
+    fn from((project_id, cluster_id): (ProjectId, ClusterId)) -> Self {
+        PolicyName::Cluster(project_id, cluster_id)
+    }
+}
+
+impl TryFrom<&str> for PolicyName {
+    type Error = InvalidPolicyNameError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let Ok((project_id, unprefixed)) = project::unprefixed(value) else {
+            return Ok(Self::Other(value.to_owned()));
+        };
+
+        if let Some(uuid) = unprefixed.strip_prefix(PolicyName::CLUSTER_POLICY_PREFIX) {
+            ClusterId::try_from(uuid)
+                .map(|cluster_id| Self::Cluster(project_id, cluster_id))
+                .map_err(|cause| InvalidPolicyNameError { value: value.to_owned(), cause: cause.into() })
+        }
+        else {
+            Ok(Self::Other(value.to_owned()))
+        }
+    }
+}
+
+impl TryFrom<String> for PolicyName {
+
+    type Error = InvalidPolicyNameError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        PolicyName::try_from(value.as_str())
+    }
+}
+
+impl From<&PolicyName> for String {
+    fn from(value: &PolicyName) -> Self {
+        match value {
+            PolicyName::Cluster(project_id, id) => project::prefixed(*project_id, format!("{}{}", PolicyName::CLUSTER_POLICY_PREFIX, id)),
+            PolicyName::Other(name) => name.to_owned(),
+        }
+    }
+}
+
+impl From<PolicyName> for String {
+    fn from(value: PolicyName) -> Self {
+        String::from(&value)
+    }
+}
+
+impl Display for PolicyName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", String::from(self))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PolicyId(pub String);
+
+impl From<&str> for PolicyId {
+    fn from(value: &str) -> Self {
+        PolicyId(value.to_owned())
+    }
+}
+
+impl From<String> for PolicyId {
+    fn from(value: String) -> Self {
+        PolicyId(value)
+    }
+}
+
+
+#[derive(Debug, Deserialize)]
+pub struct Policy {
+    pub id: PolicyId,
+    pub name: PolicyName,
+    pub enabled: bool,
+    pub rules: Vec<PolicyRule>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PolicyRule {
+    pub enabled: bool,
+    pub action: PolicyAction,
+    pub bidirectional: bool,
+    pub protocol: PolicyProtocol,
+    #[serde(deserialize_with = "opendut_util::serde::deserialize_null_default", default)]
+    pub ports: Vec<String>,
+    pub sources: Vec<GroupInfo>,
+    pub destinations: Vec<GroupInfo>,
+}
+
+impl PolicyRule {
+    pub fn spec(&self) -> PolicyRuleSpec {
+        PolicyRuleSpec {
+            protocol: self.protocol,
+            ports: self.ports.clone(),
+            bidirectional: self.bidirectional,
+            sources: self.sources.iter().map(|group| group.id.clone()).collect(),
+            destinations: self.destinations.iter().map(|group| group.id.clone()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GroupInfo {
+    pub id: GroupId,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all="kebab-case")]
+pub enum PolicyAction {
+    Accept,
+    Drop,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all="kebab-case")]
+pub enum PolicyProtocol {
+    All,
+    Tcp,
+    Udp,
+    Icmp,
+}
+
+/// The protocols, which NetBird policies cannot accept without accepting all other traffic between the same peers.
+/// Clusters, whose members exchange traffic of these protocols, cannot be created.
+pub const UNSUPPORTED_PROTOCOLS: [NetworkProtocol; 2] = [NetworkProtocol::Gre, NetworkProtocol::Sctp];
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum TranslatePolicyError {
+    #[error("Peer <{peer_id}> is referenced by the network policy, but is not a member of the cluster.")]
+    SelfGroupNotFound { peer_id: PeerId },
+    #[error("NetBird policies cannot accept {protocol} traffic without accepting any other traffic between the same peers.")]
+    UnsupportedProtocol { protocol: NetworkProtocol },
+}
+
+/// The traffic accepted by a rule of a policy, as sent to NetBird.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolicyRuleSpec {
+    pub protocol: PolicyProtocol,
+    pub ports: Vec<String>,
+    pub bidirectional: bool,
+    pub sources: Vec<GroupId>,
+    pub destinations: Vec<GroupId>,
+}
+
+impl PolicyRuleSpec {
+    /// Translates the rules of a cluster policy, using the self groups of the peers as sources and destinations.
+    ///
+    /// NetBird policies only distinguish TCP, UDP and ICMP. Rules for other protocols, like GRE or SCTP, cannot be translated
+    /// (see [`UNSUPPORTED_PROTOCOLS`]), since accepting them with protocol `all` would allow any traffic between the peers.
+    pub fn translate(policy: &ClusterNetworkPolicy, self_groups: &HashMap<PeerId, GroupId>) -> Result<Vec<PolicyRuleSpec>, TranslatePolicyError> {
+        let mut specs: Vec<PolicyRuleSpec> = Vec::new();
+
+        for rule in &policy.rules {
+            let source = self_groups.get(&rule.source).ok_or(TranslatePolicyError::SelfGroupNotFound { peer_id: rule.source })?;
+            let destination = self_groups.get(&rule.destination).ok_or(TranslatePolicyError::SelfGroupNotFound { peer_id: rule.destination })?;
+
+            let (protocol, ports) = match rule.protocol {
+                NetworkProtocol::Tcp => (PolicyProtocol::Tcp, rule.ports.iter().map(ToString::to_string).collect()),
+                NetworkProtocol::Udp => (PolicyProtocol::Udp, rule.ports.iter().map(ToString::to_string).collect()),
+                NetworkProtocol::Icmp => (PolicyProtocol::Icmp, vec![]),
+                protocol @ (NetworkProtocol::Gre | NetworkProtocol::Sctp) => return Err(TranslatePolicyError::UnsupportedProtocol { protocol }),
+            };
+
+            let spec = PolicyRuleSpec {
+                protocol,
+                ports,
+                bidirectional: rule.bidirectional,
+                sources: vec![source.clone()],
+                destinations: vec![destination.clone()],
+            };
+
+            if specs.iter().any(|existing| existing.covers(&spec)).not() {
+                specs.retain(|existing| spec.covers(existing).not());
+                specs.push(spec);
+            }
+        }
+        Ok(specs)
+    }
+
+    /// Whether this rule accepts all traffic accepted by the other rule.
+    fn covers(&self, other: &PolicyRuleSpec) -> bool {
+        let same_peers = self.sources == other.sources && self.destinations == other.destinations;
+        let reversed_peers = self.sources == other.destinations && self.destinations == other.sources;
+
+        let peers_covered = (same_peers && (self.bidirectional || other.bidirectional.not()))
+            || (self.bidirectional && reversed_peers);
+        let traffic_covered = self.protocol == PolicyProtocol::All
+            || (self.protocol == other.protocol && (self.ports.is_empty() || self.ports == other.ports));
+
+        peers_covered && traffic_covered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+
+    use opendut_vpn::ClusterNetworkRule;
+    use opendut_types::util::Port;
+
+    use super::*;
+
+    #[test]
+    fn should_refuse_protocols_unknown_to_netbird_instead_of_accepting_all_traffic() -> Result<()> {
+        let leader = PeerId::random();
+        let member = PeerId::random();
+        let self_groups = HashMap::from([
+            (leader, GroupId::from("leader-group")),
+            (member, GroupId::from("member-group")),
+        ]);
+        let policy = ClusterNetworkPolicy {
+            rules: vec![
+                ClusterNetworkRule { protocol: NetworkProtocol::Gre, ports: vec![], source: member, destination: leader, bidirectional: true },
+                ClusterNetworkRule { protocol: NetworkProtocol::Sctp, ports: vec![Port(10001)], source: member, destination: leader, bidirectional: false },
+                ClusterNetworkRule { protocol: NetworkProtocol::Udp, ports: vec![Port(10002)], source: leader, destination: member, bidirectional: false },
+            ],
+        };
+
+        let specs = PolicyRuleSpec::translate(&policy, &self_groups);
+
+        verify_that!(specs, err(eq(TranslatePolicyError::UnsupportedProtocol { protocol: NetworkProtocol::Gre })))
+    }
+
+    #[test]
+    fn should_translate_ports_and_fail_for_peers_without_self_group() -> Result<()> {
+        let leader = PeerId::random();
+        let member = PeerId::random();
+        let unknown = PeerId::random();
+        let self_groups = HashMap::from([
+            (leader, GroupId::from("leader-group")),
+            (member, GroupId::from("member-group")),
+        ]);
+        let rule = ClusterNetworkRule { protocol: NetworkProtocol::Udp, ports: vec![Port(10001)], source: member, destination: leader, bidirectional: false };
+
+        let specs = PolicyRuleSpec::translate(&ClusterNetworkPolicy { rules: vec![rule.clone()] }, &self_groups);
+        verify_that!(specs, ok(elements_are![
+            all!(
+                field!(PolicyRuleSpec.protocol, eq(PolicyProtocol::Udp)),
+                field!(PolicyRuleSpec.ports, eq(vec![String::from("10001")])),
+            ),
+        ]))?;
+
+        let specs = PolicyRuleSpec::translate(&ClusterNetworkPolicy { rules: vec![ClusterNetworkRule { source: unknown, ..rule }] }, &self_groups);
+        verify_that!(specs, err(eq(TranslatePolicyError::SelfGroupNotFound { peer_id: unknown })))
+    }
+}
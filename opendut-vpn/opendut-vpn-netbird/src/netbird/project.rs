@@ -0,0 +1,30 @@
+use opendut_types::project::{IllegalProjectId, ProjectId};
+
+const PROJECT_PREFIX: &str = "opendut-project-";
+const PROJECT_ID_LENGTH: usize = 36;
+
+/// Prefixes the name of a NetBird resource with the id of its project, to keep the resources of different projects apart.
+///
+/// Resources of the default project are not prefixed, so that their names stay compatible with earlier versions.
+pub(crate) fn prefixed(project_id: ProjectId, name: String) -> String {
+    if project_id.is_default() {
+        name
+    } else {
+        format!("{PROJECT_PREFIX}{project_id}-{name}")
+    }
+}
+
+/// Splits the name of a NetBird resource into its project and the remaining name.
+pub(crate) fn unprefixed(value: &str) -> Result<(ProjectId, &str), IllegalProjectId> {
+    match value.strip_prefix(PROJECT_PREFIX) {
+        Some(rest) if rest.len() > PROJECT_ID_LENGTH => {
+            let (project_id, name) = rest.split_at(PROJECT_ID_LENGTH);
+            let project_id = ProjectId::try_from(project_id)?;
+            let name = name.strip_prefix('-')
+                .ok_or_else(|| IllegalProjectId { value: rest.to_owned() })?;
+            Ok((project_id, name))
+        }
+        Some(rest) => Err(IllegalProjectId { value: rest.to_owned() }),
+        None => Ok((ProjectId::DEFAULT, value)),
+    }
+}
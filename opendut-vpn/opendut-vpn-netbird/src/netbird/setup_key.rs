@@ -7,7 +7,15 @@ use opendut_types::peer::PeerId;
 
 pub fn name_format(peer_id: PeerId) -> String {
     // Fixed format, do not change. Allows resolving EDGAR's NetBird peer without a mapping table.
-    format!("opendut-peer-{peer_id}")
+    format!("{NAME_PREFIX}{peer_id}")
+}
+
+const NAME_PREFIX: &str = "opendut-peer-";
+
+/// Resolves the peer, for which a setup key was created, from the name of the setup key.
+pub fn peer_id_of(name: &str) -> Option<PeerId> {
+    name.strip_prefix(NAME_PREFIX)
+        .and_then(|uuid| PeerId::try_from(uuid).ok())
 }
 
 #[allow(unused)]
@@ -28,6 +36,20 @@ pub struct SetupKey {
     pub usage_limit: u64,
 }
 
+/// A setup key as returned when listing setup keys.
+/// The key itself is omitted, since NetBird may only return it masked.
+#[allow(unused)]
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct SetupKeyInfo {
+    pub id: String,
+    pub name: String,
+    pub r#type: Type,
+    pub revoked: bool,
+    pub state: State,
+    pub auto_groups: Vec<String>,
+    pub usage_limit: u64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum Type {
@@ -0,0 +1,477 @@
+use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::Entry;
+use std::ops::Not;
+
+use tracing::{debug, info, warn};
+
+use opendut_types::cluster::ClusterId;
+use opendut_types::peer::PeerId;
+use opendut_types::project::ProjectId;
+use opendut_types::vpn::reconciliation::{VpnDrift, VpnDriftKind, VpnDriftOutcome, VpnReconciliationReport};
+use opendut_vpn::{ClusterNetworkPolicy, ExpectedVpnState, ReconcileError, VpnManagementClient};
+
+use crate::netbird;
+use crate::NetbirdManagementClient;
+
+/// Lists the opendut-owned groups, policies and setup keys of NetBird and compares them with the expected state.
+///
+/// NetBird peers are only considered via the self group of their openDuT peer,
+/// since other NetBird peers cannot be told apart from peers not managed by openDuT.
+pub(crate) async fn reconcile(client: &NetbirdManagementClient, expected: &ExpectedVpnState, dry_run: bool) -> Result<VpnReconciliationReport, ReconcileError> {
+    let groups = client.inner.list_netbird_groups().await
+        .map_err(|cause| ReconcileError::InspectionFailure { error: format!("Failed to list NetBird groups:\n  {cause}").into() })?;
+    let policies = client.inner.list_netbird_policies().await
+        .map_err(|cause| ReconcileError::InspectionFailure { error: format!("Failed to list NetBird policies:\n  {cause}").into() })?;
+    let setup_keys = client.inner.list_netbird_setup_keys().await
+        .map_err(|cause| ReconcileError::InspectionFailure { error: format!("Failed to list NetBird setup keys:\n  {cause}").into() })?;
+
+    let plan = plan(expected, groups, policies, setup_keys);
+
+    let mut drifts = Vec::with_capacity(plan.len());
+    for PlannedRepair { kind, resource, repair } in plan {
+        let outcome = if dry_run {
+            VpnDriftOutcome::Planned
+        } else {
+            match repair.execute(client).await {
+                Ok(()) => {
+                    info!("Repaired drift of NetBird: {kind} {resource}. {}", repair.description());
+                    VpnDriftOutcome::Repaired
+                }
+                Err(cause) => {
+                    warn!("Failed to repair drift of NetBird: {kind} {resource}. {}\n  {cause}", repair.description());
+                    VpnDriftOutcome::Failed { cause }
+                }
+            }
+        };
+        drifts.push(VpnDrift {
+            kind,
+            resource,
+            action: repair.description(),
+            outcome,
+        });
+    }
+
+    debug!("Reconciliation of NetBird found {} drifts.", drifts.len());
+    Ok(VpnReconciliationReport { dry_run, drifts })
+}
+
+struct PlannedRepair {
+    kind: VpnDriftKind,
+    resource: String,
+    repair: Repair,
+}
+
+enum Repair {
+    DeletePolicy(netbird::Policy),
+    DeleteGroup(netbird::Group),
+    /// Deletes the self group of a peer together with the NetBird peers in it.
+    DeletePeer(netbird::Group),
+    RevokeSetupKey(netbird::SetupKeyInfo),
+    CreatePeer(PeerId),
+    RecreateCluster { project_id: ProjectId, cluster_id: ClusterId, peers: Vec<PeerId>, policy: ClusterNetworkPolicy },
+}
+
+impl Repair {
+    fn description(&self) -> String {
+        match self {
+            Repair::DeletePolicy(_) => String::from("Delete the policy."),
+            Repair::DeleteGroup(_) => String::from("Delete the group."),
+            Repair::DeletePeer(group) => format!("Delete the group and the {} NetBird peers in it.", group.peers.len()),
+            Repair::RevokeSetupKey(_) => String::from("Revoke the setup key."),
+            Repair::CreatePeer(_) => String::from("Create the self group of the peer."),
+            Repair::RecreateCluster { .. } => String::from("Recreate the group and policy of the cluster."),
+        }
+    }
+
+    async fn execute(&self, client: &NetbirdManagementClient) -> Result<(), String> {
+        match self {
+            Repair::DeletePolicy(policy) => {
+                client.inner.delete_netbird_policy(&policy.id).await
+                    .map_err(|cause| cause.to_string())
+            }
+            Repair::DeleteGroup(group) => {
+                if recheck_group(client, group).await?.not() {
+                    return Ok(());
+                }
+                client.inner.delete_netbird_group(&group.id).await
+                    .map_err(|cause| cause.to_string())
+            }
+            Repair::DeletePeer(group) => {
+                if recheck_group(client, group).await?.not() {
+                    return Ok(());
+                }
+                for peer in &group.peers {
+                    client.inner.delete_netbird_peer(&peer.id).await
+                        .map_err(|cause| cause.to_string())?;
+                }
+                client.inner.delete_netbird_group(&group.id).await
+                    .map_err(|cause| cause.to_string())
+            }
+            Repair::RevokeSetupKey(setup_key) => {
+                client.inner.revoke_netbird_setup_key(setup_key).await
+                    .map_err(|cause| cause.to_string())
+            }
+            Repair::CreatePeer(peer_id) => {
+                client.create_peer(*peer_id).await
+                    .map_err(|cause| cause.to_string())
+            }
+            Repair::RecreateCluster { project_id, cluster_id, peers, policy } => {
+                client.create_cluster(*project_id, *cluster_id, peers, policy).await
+                    .map_err(|cause| cause.to_string())
+            }
+        }
+    }
+}
+
+/// Fetches the group planned for deletion again, since NetBird peers may have been added to it after it was listed,
+/// e.g. when EDGAR registered with the setup key of its peer in the meantime.
+/// Returns whether the group still exists and fails, if its members changed, which leaves the group to the next reconciliation.
+async fn recheck_group(client: &NetbirdManagementClient, group: &netbird::Group) -> Result<bool, String> {
+    let current = client.inner.list_netbird_groups().await
+        .map_err(|cause| format!("Failed to check the group again before deleting it:\n  {cause}"))?
+        .into_iter()
+        .find(|current| current.id == group.id);
+
+    match current {
+        Some(current) if group_members(&current) != group_members(group) => {
+            Err(String::from("The members of the group changed since the reconciliation was planned. It is checked again by the next reconciliation."))
+        }
+        current => Ok(current.is_some()),
+    }
+}
+
+fn group_members(group: &netbird::Group) -> HashSet<&String> {
+    group.peers.iter()
+        .map(|peer| &peer.id.0)
+        .collect()
+}
+
+/// Determines the repairs in the order they have to be executed:
+/// policies before the groups they reference, deletions before (re-)creations.
+fn plan(expected: &ExpectedVpnState, groups: Vec<netbird::Group>, policies: Vec<netbird::Policy>, setup_keys: Vec<netbird::SetupKeyInfo>) -> Vec<PlannedRepair> {
+    let mut plan = Vec::new();
+
+    let mut cluster_policies: HashMap<(ProjectId, ClusterId), netbird::Policy> = HashMap::new();
+    for policy in policies {
+        let netbird::PolicyName::Cluster(project_id, cluster_id) = policy.name else { continue };
+        let resource = format!("policy '{}'", policy.name);
+
+        if expected.clusters.contains_key(&(project_id, cluster_id)).not() {
+            plan.push(PlannedRepair { kind: VpnDriftKind::Orphaned, resource, repair: Repair::DeletePolicy(policy) });
+        }
+        else if let Entry::Vacant(entry) = cluster_policies.entry((project_id, cluster_id)) {
+            entry.insert(policy);
+        }
+        else {
+            plan.push(PlannedRepair { kind: VpnDriftKind::Orphaned, resource: format!("duplicate {resource}"), repair: Repair::DeletePolicy(policy) });
+        }
+    }
+
+    // Keep the self group, which contains a NetBird peer, if a peer has multiple self groups.
+    let mut groups = groups;
+    groups.sort_by_key(|group| group.peers.is_empty());
+
+    let mut cluster_groups: HashMap<(ProjectId, ClusterId), netbird::Group> = HashMap::new();
+    let mut peer_groups: HashMap<PeerId, netbird::Group> = HashMap::new();
+    let mut orphaned_peer_groups = Vec::new();
+    for group in groups {
+        let resource = format!("group '{}'", group.name);
+        match group.name {
+            netbird::GroupName::Cluster(project_id, cluster_id) => {
+                if expected.clusters.contains_key(&(project_id, cluster_id)).not() {
+                    plan.push(PlannedRepair { kind: VpnDriftKind::Orphaned, resource, repair: Repair::DeleteGroup(group) });
+                }
+                else if let Entry::Vacant(entry) = cluster_groups.entry((project_id, cluster_id)) {
+                    entry.insert(group);
+                }
+                else {
+                    plan.push(PlannedRepair { kind: VpnDriftKind::Orphaned, resource: format!("duplicate {resource}"), repair: Repair::DeleteGroup(group) });
+                }
+            }
+            netbird::GroupName::Peer(peer_id) => {
+                if expected.peers.contains(&peer_id).not() {
+                    orphaned_peer_groups.push(PlannedRepair { kind: VpnDriftKind::Orphaned, resource, repair: Repair::DeletePeer(group) });
+                }
+                else if let Entry::Vacant(entry) = peer_groups.entry(peer_id) {
+                    entry.insert(group);
+                }
+                else {
+                    plan.push(PlannedRepair { kind: VpnDriftKind::Orphaned, resource: format!("duplicate {resource}"), repair: Repair::DeleteGroup(group) });
+                }
+            }
+            netbird::GroupName::Other(_) => {}
+        }
+    }
+    plan.append(&mut orphaned_peer_groups);
+
+    for setup_key in setup_keys {
+        let Some(peer_id) = netbird::setup_key_peer_id(&setup_key.name) else { continue };
+        if expected.peers.contains(&peer_id).not() && setup_key.state == netbird::SetupKeyState::Valid {
+            plan.push(PlannedRepair { kind: VpnDriftKind::Orphaned, resource: format!("setup key '{}'", setup_key.name), repair: Repair::RevokeSetupKey(setup_key) });
+        }
+    }
+
+    let mut expected_peers = expected.peers.iter().collect::<Vec<_>>();
+    expected_peers.sort_by_key(|peer_id| peer_id.0);
+    for peer_id in expected_peers {
+        if peer_groups.contains_key(peer_id).not() {
+            let resource = format!("group '{}'", netbird::GroupName::Peer(*peer_id));
+            plan.push(PlannedRepair { kind: VpnDriftKind::Missing, resource, repair: Repair::CreatePeer(*peer_id) });
+        }
+    }
+
+    let self_groups = peer_groups.iter()
+        .map(|(peer_id, group)| (*peer_id, group.id.clone()))
+        .collect::<HashMap<_, _>>();
+
+    let mut expected_clusters = expected.clusters.iter().collect::<Vec<_>>();
+    expected_clusters.sort_by_key(|((project_id, cluster_id), _)| (project_id.0, cluster_id.0));
+    for (&(project_id, cluster_id), cluster) in expected_clusters {
+        let Some(policy) = &cluster.policy else {
+            debug!("Not checking cluster <{cluster_id}>, since it has not yet been deployed.");
+            continue;
+        };
+        let group_name = netbird::GroupName::Cluster(project_id, cluster_id);
+        let policy_name = netbird::PolicyName::Cluster(project_id, cluster_id);
+        let repair = Repair::RecreateCluster { project_id, cluster_id, peers: Clone::clone(&cluster.peers), policy: Clone::clone(policy) };
+
+        let Some(group) = cluster_groups.get(&(project_id, cluster_id)) else {
+            plan.push(PlannedRepair { kind: VpnDriftKind::Missing, resource: format!("group '{group_name}'"), repair });
+            continue;
+        };
+
+        let expected_members = cluster.peers.iter()
+            .filter_map(|peer_id| peer_groups.get(peer_id))
+            .flat_map(|peer_group| peer_group.peers.iter().map(|peer| &peer.id.0))
+            .collect::<HashSet<_>>();
+        let actual_members = group.peers.iter()
+            .map(|peer| &peer.id.0)
+            .collect::<HashSet<_>>();
+        if expected_members != actual_members {
+            plan.push(PlannedRepair { kind: VpnDriftKind::Diverged, resource: format!("members of group '{group_name}'"), repair });
+            continue;
+        }
+
+        // Missing self groups are recreated above, after which the cluster is checked again by the next reconciliation.
+        let Ok(expected_rules) = netbird::PolicyRuleSpec::translate(policy, &self_groups) else { continue };
+        match cluster_policies.get(&(project_id, cluster_id)) {
+            None if expected_rules.is_empty() => {}
+            None => {
+                plan.push(PlannedRepair { kind: VpnDriftKind::Missing, resource: format!("policy '{policy_name}'"), repair });
+            }
+            Some(actual_policy) => {
+                let actual_rules = actual_policy.rules.iter()
+                    .filter(|rule| rule.enabled && rule.action == netbird::PolicyAction::Accept)
+                    .map(netbird::PolicyRule::spec)
+                    .collect::<Vec<_>>();
+                let in_sync = actual_policy.enabled
+                    && expected_rules.iter().all(|rule| actual_rules.contains(rule))
+                    && actual_rules.iter().all(|rule| expected_rules.contains(rule));
+                if in_sync.not() {
+                    plan.push(PlannedRepair { kind: VpnDriftKind::Diverged, resource: format!("rules of policy '{policy_name}'"), repair });
+                }
+            }
+        }
+    }
+
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+
+    use opendut_vpn::{ClusterNetworkRule, ExpectedCluster, NetworkProtocol};
+
+    use crate::netbird::{GroupInfo, GroupPeerInfo};
+
+    use super::*;
+
+    fn group(name: netbird::GroupName, netbird_peers: &[&str]) -> netbird::Group {
+        netbird::Group {
+            id: netbird::GroupId::from(format!("group-{name}")),
+            name,
+            peers_count: netbird_peers.len(),
+            peers: netbird_peers.iter()
+                .map(|peer| GroupPeerInfo { id: netbird::PeerId::from(*peer), name: peer.to_string() })
+                .collect(),
+        }
+    }
+
+    fn policy(project_id: ProjectId, cluster_id: ClusterId, rules: Vec<netbird::PolicyRuleSpec>) -> netbird::Policy {
+        let group_info = |id: netbird::GroupId| GroupInfo { id };
+        netbird::Policy {
+            id: netbird::PolicyId::from(format!("policy-{cluster_id}")),
+            name: netbird::PolicyName::Cluster(project_id, cluster_id),
+            enabled: true,
+            rules: rules.into_iter()
+                .map(|rule| netbird::PolicyRule {
+                    enabled: true,
+                    action: netbird::PolicyAction::Accept,
+                    bidirectional: rule.bidirectional,
+                    protocol: rule.protocol,
+                    ports: rule.ports,
+                    sources: rule.sources.into_iter().map(group_info).collect(),
+                    destinations: rule.destinations.into_iter().map(group_info).collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn icmp_rule(source: PeerId, destination: PeerId) -> ClusterNetworkRule {
+        ClusterNetworkRule { protocol: NetworkProtocol::Icmp, ports: vec![], source, destination, bidirectional: true }
+    }
+
+    fn icmp_spec(source: PeerId, destination: PeerId) -> netbird::PolicyRuleSpec {
+        netbird::PolicyRuleSpec {
+            protocol: netbird::PolicyProtocol::Icmp,
+            ports: vec![],
+            bidirectional: true,
+            sources: vec![group(netbird::GroupName::Peer(source), &[]).id],
+            destinations: vec![group(netbird::GroupName::Peer(destination), &[]).id],
+        }
+    }
+
+    fn cluster(peers: Vec<PeerId>, rules: Vec<ClusterNetworkRule>) -> ExpectedCluster {
+        ExpectedCluster { peers, policy: Some(ClusterNetworkPolicy { rules }) }
+    }
+
+    fn setup_key(peer_id: PeerId, state: netbird::SetupKeyState) -> netbird::SetupKeyInfo {
+        netbird::SetupKeyInfo {
+            id: format!("setup-key-{peer_id}"),
+            name: netbird::setup_key_name_format(peer_id),
+            r#type: netbird::SetupKeyType::OneOff,
+            revoked: state == netbird::SetupKeyState::Revoked,
+            state,
+            auto_groups: vec![],
+            usage_limit: 0,
+        }
+    }
+
+    fn summary(plan: &[PlannedRepair]) -> Vec<(VpnDriftKind, String)> {
+        plan.iter()
+            .map(|planned| (planned.kind, Clone::clone(&planned.resource)))
+            .collect()
+    }
+
+    #[test]
+    fn should_plan_to_delete_resources_of_unknown_peers_and_clusters() -> Result<()> {
+        let known_peer = PeerId::random();
+        let deleted_peer = PeerId::random();
+        let deleted_cluster = ClusterId::random();
+
+        let expected = ExpectedVpnState {
+            peers: HashSet::from([known_peer]),
+            clusters: HashMap::new(),
+        };
+
+        let plan = plan(
+            &expected,
+            vec![
+                group(netbird::GroupName::Peer(known_peer), &["netbird-a"]),
+                group(netbird::GroupName::Peer(deleted_peer), &["netbird-b"]),
+                group(netbird::GroupName::Cluster(ProjectId::DEFAULT, deleted_cluster), &["netbird-a", "netbird-b"]),
+                group(netbird::GroupName::Other(String::from("All")), &["netbird-a", "netbird-b"]),
+            ],
+            vec![policy(ProjectId::DEFAULT, deleted_cluster, vec![])],
+            vec![
+                setup_key(known_peer, netbird::SetupKeyState::Valid),
+                setup_key(deleted_peer, netbird::SetupKeyState::Valid),
+                setup_key(deleted_peer, netbird::SetupKeyState::Revoked),
+            ],
+        );
+
+        verify_that!(summary(&plan), elements_are![
+            eq((VpnDriftKind::Orphaned, format!("policy '{}'", netbird::PolicyName::Cluster(ProjectId::DEFAULT, deleted_cluster)))),
+            eq((VpnDriftKind::Orphaned, format!("group '{}'", netbird::GroupName::Cluster(ProjectId::DEFAULT, deleted_cluster)))),
+            eq((VpnDriftKind::Orphaned, format!("group '{}'", netbird::GroupName::Peer(deleted_peer)))),
+            eq((VpnDriftKind::Orphaned, format!("setup key '{}'", netbird::setup_key_name_format(deleted_peer)))),
+        ])
+    }
+
+    #[test]
+    fn should_plan_to_recreate_missing_and_diverged_resources_of_deployed_clusters() -> Result<()> {
+        let peer_a = PeerId::random();
+        let peer_b = PeerId::random();
+        let peer_c = PeerId::random();
+        let complete_cluster = ClusterId::random();
+        let diverged_cluster = ClusterId::random();
+        let missing_cluster = ClusterId::random();
+        let unassigned_cluster = ClusterId::random();
+
+        let expected = ExpectedVpnState {
+            peers: HashSet::from([peer_a, peer_b, peer_c]),
+            clusters: HashMap::from([
+                ((ProjectId::DEFAULT, complete_cluster), cluster(vec![peer_a, peer_b], vec![icmp_rule(peer_b, peer_a)])),
+                ((ProjectId::DEFAULT, diverged_cluster), cluster(vec![peer_a, peer_b], vec![icmp_rule(peer_b, peer_a)])),
+                ((ProjectId::DEFAULT, missing_cluster), cluster(vec![peer_a], vec![])),
+                ((ProjectId::DEFAULT, unassigned_cluster), ExpectedCluster { peers: vec![peer_a], policy: None }),
+            ]),
+        };
+
+        let plan = plan(
+            &expected,
+            vec![
+                group(netbird::GroupName::Peer(peer_a), &["netbird-a"]),
+                group(netbird::GroupName::Peer(peer_b), &["netbird-b"]),
+                group(netbird::GroupName::Cluster(ProjectId::DEFAULT, complete_cluster), &["netbird-b", "netbird-a"]),
+                group(netbird::GroupName::Cluster(ProjectId::DEFAULT, diverged_cluster), &["netbird-a"]),
+            ],
+            vec![
+                policy(ProjectId::DEFAULT, complete_cluster, vec![icmp_spec(peer_b, peer_a)]),
+                policy(ProjectId::DEFAULT, diverged_cluster, vec![icmp_spec(peer_b, peer_a)]),
+            ],
+            vec![],
+        );
+
+        let summary = summary(&plan);
+        verify_that!(summary.len(), eq(3))?;
+        verify_that!(summary, contains(eq((VpnDriftKind::Missing, format!("group '{}'", netbird::GroupName::Peer(peer_c))))))?;
+        verify_that!(summary, contains(eq((VpnDriftKind::Missing, format!("group '{}'", netbird::GroupName::Cluster(ProjectId::DEFAULT, missing_cluster))))))?;
+        verify_that!(summary, contains(eq((VpnDriftKind::Diverged, format!("members of group '{}'", netbird::GroupName::Cluster(ProjectId::DEFAULT, diverged_cluster))))))
+    }
+
+    #[test]
+    fn should_plan_to_recreate_clusters_whose_policy_diverged_from_their_network_policy() -> Result<()> {
+        let peer_a = PeerId::random();
+        let peer_b = PeerId::random();
+        let legacy_cluster = ClusterId::random();
+        let unrestricted_cluster = ClusterId::random();
+
+        let expected = ExpectedVpnState {
+            peers: HashSet::from([peer_a, peer_b]),
+            clusters: HashMap::from([
+                ((ProjectId::DEFAULT, legacy_cluster), cluster(vec![peer_a, peer_b], vec![icmp_rule(peer_b, peer_a)])),
+                ((ProjectId::DEFAULT, unrestricted_cluster), cluster(vec![peer_a], vec![])),
+            ]),
+        };
+        let legacy_group = group(netbird::GroupName::Cluster(ProjectId::DEFAULT, legacy_cluster), &["netbird-a", "netbird-b"]);
+        let any_to_any = netbird::PolicyRuleSpec {
+            protocol: netbird::PolicyProtocol::All,
+            ports: vec![],
+            bidirectional: true,
+            sources: vec![legacy_group.id.clone()],
+            destinations: vec![legacy_group.id.clone()],
+        };
+
+        let plan = plan(
+            &expected,
+            vec![
+                group(netbird::GroupName::Peer(peer_a), &["netbird-a"]),
+                group(netbird::GroupName::Peer(peer_b), &["netbird-b"]),
+                legacy_group,
+                group(netbird::GroupName::Cluster(ProjectId::DEFAULT, unrestricted_cluster), &["netbird-a"]),
+            ],
+            vec![
+                policy(ProjectId::DEFAULT, legacy_cluster, vec![any_to_any.clone()]),
+                policy(ProjectId::DEFAULT, unrestricted_cluster, vec![any_to_any]),
+            ],
+            vec![],
+        );
+
+        verify_that!(summary(&plan), unordered_elements_are![
+            eq((VpnDriftKind::Diverged, format!("rules of policy '{}'", netbird::PolicyName::Cluster(ProjectId::DEFAULT, legacy_cluster)))),
+            eq((VpnDriftKind::Diverged, format!("rules of policy '{}'", netbird::PolicyName::Cluster(ProjectId::DEFAULT, unrestricted_cluster)))),
+        ])
+    }
+}
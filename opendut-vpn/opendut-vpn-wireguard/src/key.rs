@@ -0,0 +1,48 @@
+use curve25519_dalek::MontgomeryPoint;
+use rand::RngCore;
+use rand::rngs::OsRng;
+
+use opendut_types::vpn::wireguard::{KEY_LENGTH, PrivateKey, PublicKey};
+
+/// Generates a new private key, clamped as described for Curve25519, like `wg genkey` does.
+pub fn generate_private_key() -> PrivateKey {
+    let mut key = [0u8; KEY_LENGTH];
+    OsRng.fill_bytes(&mut key);
+
+    key[0] &= 248;
+    key[31] &= 127;
+    key[31] |= 64;
+
+    PrivateKey(key)
+}
+
+/// Derives the public key of a private key, like `wg pubkey` does.
+pub fn derive_public_key(private_key: &PrivateKey) -> PublicKey {
+    PublicKey(MontgomeryPoint::mul_base_clamped(private_key.0).to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use googletest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn should_derive_the_public_key_of_a_private_key() -> Result<()> {
+        // Key pair of Alice from the test vectors of RFC 7748, section 6.1.
+        let private_key = PrivateKey::from_str("dwdtCnMYpX08FsFyUbJmRd9ML4frwJkqsXf7pR25LCo=")?;
+
+        verify_that!(derive_public_key(&private_key).to_string(), eq("hSDwCYkwp1R0i33ctD73Wg2/Og0mOBr066SpjqqbTmo="))
+    }
+
+    #[test]
+    fn should_generate_clamped_private_keys() -> Result<()> {
+        let PrivateKey(key) = generate_private_key();
+
+        verify_that!(key[0] & 7, eq(0))?;
+        verify_that!(key[31] & 128, eq(0))?;
+        verify_that!(key[31] & 64, eq(64))
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+use std::ops::Not;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use ipnet::Ipv4Net;
+use tracing::{debug, info};
+
+use opendut_types::cluster::ClusterId;
+use opendut_types::peer::PeerId;
+use opendut_types::project::ProjectId;
+use opendut_types::util::Port;
+use opendut_types::vpn::VpnPeerConfiguration;
+use opendut_types::vpn::wireguard::{PrivateKey, PublicKey};
+use opendut_vpn::{ClusterNetworkPolicy, CreateClusterError, CreatePeerError, CreateVpnPeerConfigurationError, DeleteClusterError, DeletePeerError, VpnManagementClient, WireGuardTunnel};
+
+mod key;
+
+pub struct WireGuardManagementClientConfiguration {
+    /// The network from which the tunnel addresses of the peers are allocated.
+    pub network: Ipv4Net,
+    /// The UDP port on which the WireGuard interface of each peer listens.
+    pub listen_port: Port,
+}
+
+/// Coordinates a WireGuard VPN without a separate management server.
+///
+/// Key pairs and tunnel addresses of the peers are generated and kept in memory.
+/// They are distributed to EDGAR in the [`VpnPeerConfiguration`] when setting up a peer
+/// and in the cluster assignments, so that each peer only knows the members of its clusters.
+/// As nothing is persisted, a restart of CARL invalidates the WireGuard configuration of every peer.
+pub struct WireGuardManagementClient {
+    configuration: WireGuardManagementClientConfiguration,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    peers: HashMap<PeerId, WireGuardPeerEntry>,
+    clusters: HashMap<(ProjectId, ClusterId), Vec<PeerId>>,
+}
+
+struct WireGuardPeerEntry {
+    private_key: PrivateKey,
+    public_key: PublicKey,
+    address: Ipv4Addr,
+}
+
+impl WireGuardManagementClient {
+
+    pub fn create(configuration: WireGuardManagementClientConfiguration) -> Self {
+        info!("Using WireGuard VPN with network '{}'.", configuration.network);
+        Self {
+            configuration,
+            state: Default::default(),
+        }
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("Lock of WireGuard state should not be poisoned.")
+    }
+}
+
+#[async_trait]
+impl VpnManagementClient for WireGuardManagementClient {
+
+    #[tracing::instrument(skip(self), level="trace")]
+    async fn create_cluster(&self, project_id: ProjectId, cluster_id: ClusterId, peers: &[PeerId], _policy: &ClusterNetworkPolicy) -> Result<(), CreateClusterError> {
+        // The policy is enforced by EDGAR, which derives it from the cluster assignment. It only configures the members,
+        // which it may exchange traffic with, as WireGuard peers and filters the traffic arriving via its WireGuard interface.
+        let mut state = self.lock_state();
+
+        if let Some(peer_id) = peers.iter().find(|peer_id| state.peers.contains_key(peer_id).not()) {
+            return Err(CreateClusterError::PeerResolutionFailure {
+                peer_id: *peer_id,
+                cluster_id,
+                error: Box::new(WireGuardError::PeerNotFound { peer_id: *peer_id }),
+            });
+        }
+
+        state.clusters.insert((project_id, cluster_id), peers.to_vec());
+        debug!("Created WireGuard cluster <{cluster_id}> with {} peers.", peers.len());
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), level="trace")]
+    async fn delete_cluster(&self, project_id: ProjectId, cluster_id: ClusterId) -> Result<(), DeleteClusterError> {
+        // The clusters are only held in memory, so clusters deployed before a restart of CARL are unknown,
+        // but still need to be undeployable.
+        if self.lock_state().clusters.remove(&(project_id, cluster_id)).is_none() {
+            debug!("WireGuard cluster <{cluster_id}> is not known. Nothing to delete.");
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), level="trace")]
+    async fn create_peer(&self, peer_id: PeerId) -> Result<(), CreatePeerError> {
+        let mut state = self.lock_state();
+
+        if state.peers.contains_key(&peer_id) {
+            debug!("WireGuard peer <{peer_id}> already exists. Keeping its key and address.");
+            return Ok(());
+        }
+
+        let address = self.configuration.network.hosts()
+            .find(|address| state.peers.values().all(|peer| peer.address != *address))
+            .ok_or_else(|| CreatePeerError::CreationFailure {
+                peer_id,
+                error: Box::new(WireGuardError::NetworkExhausted { network: self.configuration.network }),
+            })?;
+
+        let private_key = key::generate_private_key();
+        let public_key = key::derive_public_key(&private_key);
+
+        state.peers.insert(peer_id, WireGuardPeerEntry { private_key, public_key, address });
+        debug!("Created WireGuard peer <{peer_id}> with address '{address}' and public key '{public_key}'.");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), level="trace")]
+    async fn delete_peer(&self, peer_id: PeerId) -> Result<(), DeletePeerError> {
+        let mut state = self.lock_state();
+
+        state.peers.remove(&peer_id)
+            .ok_or_else(|| DeletePeerError::ResolutionFailure {
+                peer_id,
+                error: Box::new(WireGuardError::PeerNotFound { peer_id }),
+            })?;
+
+        for members in state.clusters.values_mut() {
+            members.retain(|member| *member != peer_id);
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), level="trace")]
+    async fn generate_vpn_peer_configuration(&self, peer_id: PeerId) -> Result<VpnPeerConfiguration, CreateVpnPeerConfigurationError> {
+        let state = self.lock_state();
+
+        let peer = state.peers.get(&peer_id)
+            .ok_or_else(|| CreateVpnPeerConfigurationError::CreationFailure {
+                peer_id,
+                error: Box::new(WireGuardError::PeerNotFound { peer_id }),
+            })?;
+
+        Ok(VpnPeerConfiguration::WireGuard {
+            private_key: Clone::clone(&peer.private_key),
+            address: IpAddr::V4(peer.address),
+            prefix_length: self.configuration.network.prefix_len(),
+            listen_port: self.configuration.listen_port,
+        })
+    }
+
+    async fn wireguard_tunnel(&self, peer_id: PeerId) -> Option<WireGuardTunnel> {
+        self.lock_state().peers.get(&peer_id)
+            .map(|peer| WireGuardTunnel {
+                address: IpAddr::V4(peer.address),
+                public_key: peer.public_key,
+                listen_port: self.configuration.listen_port,
+            })
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum WireGuardError {
+    #[error("Peer <{peer_id}> is not known to the WireGuard VPN.")]
+    PeerNotFound { peer_id: PeerId },
+    #[error("No more addresses available in the WireGuard network '{network}'.")]
+    NetworkExhausted { network: Ipv4Net },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use googletest::prelude::*;
+
+    use super::*;
+
+    fn testee(network: &str) -> WireGuardManagementClient {
+        WireGuardManagementClient::create(WireGuardManagementClientConfiguration {
+            network: Ipv4Net::from_str(network).unwrap(),
+            listen_port: Port(51820),
+        })
+    }
+
+    #[tokio::test]
+    async fn should_allocate_a_free_address_for_each_peer() -> Result<()> {
+        let testee = testee("10.10.0.0/30");
+        let peer_a = PeerId::random();
+        let peer_b = PeerId::random();
+        let peer_c = PeerId::random();
+
+        testee.create_peer(peer_a).await?;
+        testee.create_peer(peer_b).await?;
+        verify_that!(testee.create_peer(peer_c).await, err(anything()))?;
+
+        verify_that!(testee.wireguard_tunnel(peer_a).await.map(|tunnel| tunnel.address), some(eq(IpAddr::from([10, 10, 0, 1]))))?;
+        verify_that!(testee.wireguard_tunnel(peer_b).await.map(|tunnel| tunnel.address), some(eq(IpAddr::from([10, 10, 0, 2]))))?;
+
+        testee.delete_peer(peer_a).await?;
+        testee.create_peer(peer_c).await?;
+        verify_that!(testee.wireguard_tunnel(peer_c).await.map(|tunnel| tunnel.address), some(eq(IpAddr::from([10, 10, 0, 1]))))
+    }
+
+    #[tokio::test]
+    async fn should_generate_a_configuration_matching_the_tunnel_of_the_peer() -> Result<()> {
+        let testee = testee("10.10.0.0/16");
+        let peer_id = PeerId::random();
+
+        verify_that!(testee.generate_vpn_peer_configuration(peer_id).await, err(anything()))?;
+
+        testee.create_peer(peer_id).await?;
+        let tunnel = testee.wireguard_tunnel(peer_id).await.unwrap();
+
+        let VpnPeerConfiguration::WireGuard { private_key, address, prefix_length, listen_port } = testee.generate_vpn_peer_configuration(peer_id).await? else {
+            return fail!("Expected a WireGuard configuration.");
+        };
+        verify_that!(key::derive_public_key(&private_key), eq(tunnel.public_key))?;
+        verify_that!(address, eq(tunnel.address))?;
+        verify_that!(prefix_length, eq(16))?;
+        verify_that!(listen_port, eq(Port(51820)))
+    }
+
+    #[tokio::test]
+    async fn should_only_create_clusters_of_known_peers() -> Result<()> {
+        let testee = testee("10.10.0.0/16");
+        let peer_id = PeerId::random();
+        let cluster_id = ClusterId::random();
+
+        verify_that!(testee.create_cluster(ProjectId::DEFAULT, cluster_id, &[peer_id], &ClusterNetworkPolicy::default()).await, err(anything()))?;
+
+        testee.create_peer(peer_id).await?;
+        verify_that!(testee.create_cluster(ProjectId::DEFAULT, cluster_id, &[peer_id], &ClusterNetworkPolicy::default()).await, ok(eq(())))?;
+        verify_that!(testee.delete_cluster(ProjectId::DEFAULT, cluster_id).await, ok(eq(())))
+    }
+
+    #[tokio::test]
+    async fn should_delete_unknown_clusters_without_error() -> Result<()> {
+        let testee = testee("10.10.0.0/16");
+        let peer_id = PeerId::random();
+        let cluster_id = ClusterId::random();
+
+        verify_that!(testee.delete_cluster(ProjectId::DEFAULT, cluster_id).await, ok(eq(())))?;
+
+        testee.create_peer(peer_id).await?;
+        testee.create_cluster(ProjectId::DEFAULT, cluster_id, &[peer_id], &ClusterNetworkPolicy::default()).await?;
+        testee.delete_cluster(ProjectId::DEFAULT, cluster_id).await?;
+        verify_that!(testee.delete_cluster(ProjectId::DEFAULT, cluster_id).await, ok(eq(())))
+    }
+}
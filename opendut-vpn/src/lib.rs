@@ -1,21 +1,73 @@
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+
 use async_trait::async_trait;
 
 use opendut_types::cluster::ClusterId;
 use opendut_types::peer::PeerId;
+use opendut_types::project::ProjectId;
+use opendut_types::util::Port;
+use opendut_types::vpn::reconciliation::VpnReconciliationReport;
 use opendut_types::vpn::VpnPeerConfiguration;
+use opendut_types::vpn::wireguard::PublicKey;
+
+pub use policy::{ClusterNetworkPolicy, ClusterNetworkRule, NetworkProtocol};
+
+mod policy;
 
 #[async_trait]
 pub trait VpnManagementClient {
 
-    async fn create_cluster(&self, cluster_id: ClusterId, peers: &[PeerId]) -> Result<(), CreateClusterError>;
+    /// Creates the cluster with the given peers, which may only exchange the traffic allowed by the policy.
+    async fn create_cluster(&self, project_id: ProjectId, cluster_id: ClusterId, peers: &[PeerId], policy: &ClusterNetworkPolicy) -> Result<(), CreateClusterError>;
 
-    async fn delete_cluster(&self, cluster_id: ClusterId) -> Result<(), DeleteClusterError>;
+    async fn delete_cluster(&self, project_id: ProjectId, cluster_id: ClusterId) -> Result<(), DeleteClusterError>;
 
     async fn create_peer(&self, peer_id: PeerId) -> Result<(), CreatePeerError>;
 
     async fn delete_peer(&self, peer_id: PeerId) -> Result<(), DeletePeerError>;
 
     async fn generate_vpn_peer_configuration(&self, peer_id: PeerId) -> Result<VpnPeerConfiguration, CreateVpnPeerConfigurationError>;
+
+    /// The protocols, whose traffic the VPN cannot accept without also accepting any other traffic between the members of a cluster.
+    /// Clusters, whose network policy contains rules for these protocols, cannot be created.
+    fn unsupported_protocols(&self) -> Vec<NetworkProtocol> {
+        Vec::new()
+    }
+
+    /// Returns the WireGuard tunnel of the given peer, if the VPN is coordinated by CARL itself.
+    /// VPNs with their own coordination server do not need to distribute any tunnel information with the cluster assignments.
+    async fn wireguard_tunnel(&self, _peer_id: PeerId) -> Option<WireGuardTunnel> {
+        None
+    }
+
+    /// Compares the resources of the VPN with the expected state and deletes or repairs any drift.
+    /// With `dry_run`, the drift is only reported.
+    /// VPNs, whose state is kept in memory by CARL, cannot drift and report nothing.
+    async fn reconcile(&self, _expected: &ExpectedVpnState, dry_run: bool) -> Result<VpnReconciliationReport, ReconcileError> {
+        Ok(VpnReconciliationReport { dry_run, drifts: Vec::new() })
+    }
+}
+
+/// The peers and deployed clusters, which CARL expects to exist in the VPN.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExpectedVpnState {
+    pub peers: HashSet<PeerId>,
+    pub clusters: HashMap<(ProjectId, ClusterId), ExpectedCluster>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExpectedCluster {
+    pub peers: Vec<PeerId>,
+    /// Unknown, if the cluster has not yet been deployed.
+    pub policy: Option<ClusterNetworkPolicy>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WireGuardTunnel {
+    pub address: IpAddr,
+    pub public_key: PublicKey,
+    pub listen_port: Port,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -35,7 +87,12 @@ pub enum CreateClusterError {
     AccessControlRuleCreationFailure {
         cluster_id: ClusterId,
         error: Box<dyn std::error::Error>
-    }
+    },
+    #[error("Cluster <{cluster_id}> cannot be created, since the VPN cannot restrict the traffic between its members to {protocol} without allowing any other traffic.")]
+    UnsupportedProtocol {
+        cluster_id: ClusterId,
+        protocol: NetworkProtocol,
+    },
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -83,3 +140,11 @@ pub enum CreateVpnPeerConfigurationError {
         error: Box<dyn std::error::Error>
     },
 }
+
+#[derive(thiserror::Error, Debug)]
+pub enum ReconcileError {
+    #[error("An error occurred while listing the resources of the VPN:\n  {error}")]
+    InspectionFailure {
+        error: Box<dyn std::error::Error + Send + Sync>
+    },
+}
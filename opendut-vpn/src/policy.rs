@@ -0,0 +1,184 @@
+use std::fmt::{Display, Formatter};
+
+use opendut_types::cluster::ClusterAssignment;
+use opendut_types::peer::PeerId;
+use opendut_types::util::Port;
+
+/// The traffic, which the members of a cluster are allowed to exchange via the VPN.
+/// Any traffic not matching one of the rules should be dropped by the VPN.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClusterNetworkPolicy {
+    pub rules: Vec<ClusterNetworkRule>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClusterNetworkRule {
+    pub protocol: NetworkProtocol,
+    /// The destination ports. Empty, if the protocol has no ports or all ports are allowed.
+    pub ports: Vec<Port>,
+    pub source: PeerId,
+    pub destination: PeerId,
+    /// Whether the destination may also initiate traffic to the source.
+    pub bidirectional: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NetworkProtocol {
+    Gre,
+    Icmp,
+    Sctp,
+    Tcp,
+    Udp,
+}
+
+impl Display for NetworkProtocol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let protocol = match self {
+            NetworkProtocol::Gre => "gre",
+            NetworkProtocol::Icmp => "icmp",
+            NetworkProtocol::Sctp => "sctp",
+            NetworkProtocol::Tcp => "tcp",
+            NetworkProtocol::Udp => "udp",
+        };
+        write!(f, "{protocol}")
+    }
+}
+
+/// Derives the policy from the traffic EDGAR sets up for a cluster assignment.
+/// The members are connected in a star topology around the leader:
+/// Ethernet is bridged via GRE and CAN is routed via cannelloni, which connects to the leader
+/// on the CAN server port of the member, using SCTP. ICMP is allowed for diagnostics.
+impl From<&ClusterAssignment> for ClusterNetworkPolicy {
+    fn from(assignment: &ClusterAssignment) -> Self {
+        let can_server_ports = assignment.assignments.iter()
+            .map(|member| (member.peer_id, member.can_server_port))
+            .collect::<Vec<_>>();
+
+        ClusterNetworkPolicy::for_members(assignment.leader, &can_server_ports)
+    }
+}
+
+impl ClusterNetworkPolicy {
+    /// The policy of a cluster with the given leader and the CAN server ports of its members,
+    /// as they would be sent to the members in their cluster assignment.
+    pub fn for_members(leader: PeerId, can_server_ports: &[(PeerId, Port)]) -> Self {
+        let mut members = can_server_ports.iter()
+            .filter(|(member, _)| *member != leader)
+            .collect::<Vec<_>>();
+        members.sort_by_key(|(member, _)| member.0);
+
+        let rules = members.into_iter()
+            .flat_map(|&(member, can_server_port)| [
+                ClusterNetworkRule { protocol: NetworkProtocol::Gre, ports: vec![], source: member, destination: leader, bidirectional: true },
+                ClusterNetworkRule { protocol: NetworkProtocol::Sctp, ports: vec![can_server_port], source: member, destination: leader, bidirectional: false },
+                ClusterNetworkRule { protocol: NetworkProtocol::Icmp, ports: vec![], source: member, destination: leader, bidirectional: true },
+            ])
+            .collect();
+
+        ClusterNetworkPolicy { rules }
+    }
+
+    /// The traffic, which the given peer has to accept from the other members.
+    /// These are the rules with the peer as destination and the reversed bidirectional rules with the peer as source.
+    pub fn incoming_rules(&self, peer_id: PeerId) -> Vec<ClusterNetworkRule> {
+        self.rules.iter()
+            .filter_map(|rule| {
+                if rule.destination == peer_id {
+                    Some(ClusterNetworkRule { bidirectional: false, ..Clone::clone(rule) })
+                } else if rule.bidirectional && rule.source == peer_id {
+                    Some(ClusterNetworkRule { source: rule.destination, destination: rule.source, bidirectional: false, ..Clone::clone(rule) })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use googletest::prelude::*;
+
+    use opendut_types::cluster::{ClusterId, PeerClusterAssignment};
+
+    use super::*;
+
+    fn member(peer_id: PeerId, can_server_port: u16) -> PeerClusterAssignment {
+        PeerClusterAssignment {
+            peer_id,
+            vpn_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            can_server_port: Port(can_server_port),
+            device_interfaces: vec![],
+            wireguard: None,
+        }
+    }
+
+    #[test]
+    fn should_only_allow_gre_cannelloni_and_icmp_between_the_leader_and_each_member() -> Result<()> {
+        let leader = PeerId::random();
+        let member_a = PeerId::random();
+        let member_b = PeerId::random();
+
+        let policy = ClusterNetworkPolicy::from(&ClusterAssignment {
+            id: ClusterId::random(),
+            leader,
+            assignments: vec![
+                member(leader, 10000),
+                member(member_a, 10001),
+                member(member_b, 10002),
+            ],
+        });
+
+        verify_that!(policy.rules.len(), eq(6))?;
+        verify_that!(policy.rules, each(field!(ClusterNetworkRule.destination, eq(leader))))?;
+        verify_that!(policy.rules, contains(all!(
+            field!(ClusterNetworkRule.protocol, eq(NetworkProtocol::Sctp)),
+            field!(ClusterNetworkRule.source, eq(member_b)),
+            field!(ClusterNetworkRule.ports, eq(vec![Port(10002)])),
+            field!(ClusterNetworkRule.bidirectional, eq(false)),
+        )))?;
+        verify_that!(policy.rules, contains(all!(
+            field!(ClusterNetworkRule.protocol, eq(NetworkProtocol::Gre)),
+            field!(ClusterNetworkRule.source, eq(member_a)),
+            field!(ClusterNetworkRule.bidirectional, eq(true)),
+        )))
+    }
+
+    #[test]
+    fn should_determine_the_incoming_rules_of_a_peer() -> Result<()> {
+        let leader = PeerId::random();
+        let member_a = PeerId::random();
+        let member_b = PeerId::random();
+
+        let policy = ClusterNetworkPolicy::from(&ClusterAssignment {
+            id: ClusterId::random(),
+            leader,
+            assignments: vec![
+                member(leader, 10000),
+                member(member_a, 10001),
+                member(member_b, 10002),
+            ],
+        });
+
+        verify_that!(policy.incoming_rules(leader).len(), eq(6))?;
+        verify_that!(policy.incoming_rules(member_a), unordered_elements_are![
+            eq(ClusterNetworkRule { protocol: NetworkProtocol::Gre, ports: vec![], source: leader, destination: member_a, bidirectional: false }),
+            eq(ClusterNetworkRule { protocol: NetworkProtocol::Icmp, ports: vec![], source: leader, destination: member_a, bidirectional: false }),
+        ])
+    }
+
+    #[test]
+    fn should_allow_no_traffic_in_a_cluster_of_only_the_leader() -> Result<()> {
+        let leader = PeerId::random();
+
+        let policy = ClusterNetworkPolicy::from(&ClusterAssignment {
+            id: ClusterId::random(),
+            leader,
+            assignments: vec![member(leader, 10000)],
+        });
+
+        verify_that!(policy, eq(ClusterNetworkPolicy::default()))
+    }
+}